@@ -13,11 +13,12 @@ extern crate rustc_middle;
 extern crate rustc_session;
 
 use analysis::{
-    abstract_interpretation::FixpointEngine,
+    abstract_interpretation::{AbstractState, FixpointEngine},
     domains::{
         DefinitelyAccessibleAnalysis, DefinitelyInitializedAnalysis, FramingAnalysis,
-        MaybeBorrowedAnalysis, ReachingDefsAnalysis,
+        IntervalAnalysis, IntervalsState, MaybeBorrowedAnalysis, ReachingDefsAnalysis,
     },
+    PointwiseState,
 };
 use polonius_engine::{Algorithm, Output};
 use rustc_ast::ast;
@@ -26,11 +27,11 @@ use rustc_driver::Compilation;
 use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_interface::{interface, Config, Queries};
 use rustc_middle::{
-    ty,
+    mir, ty,
     ty::query::{query_values::mir_borrowck, ExternProviders, Providers},
 };
 use rustc_session::{Attribute, Session};
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{cell::RefCell, cmp::Ordering, collections::HashMap, rc::Rc};
 
 struct OurCompilerCalls {
     args: Vec<String>,
@@ -64,6 +65,88 @@ fn get_attribute<'tcx>(
     })
 }
 
+/// Print the facts inferred by the interval analysis at the head of each loop,
+/// as `line <n>: <fact>` lines that use the names of the source variables.
+fn print_loop_invariants<'tcx>(
+    tcx: ty::TyCtxt<'tcx>,
+    body: &mir::Body<'tcx>,
+    result: &PointwiseState<'_, 'tcx, IntervalsState>,
+) {
+    let names: HashMap<mir::Local, String> = body
+        .var_debug_info
+        .iter()
+        .filter_map(|info| match info.value {
+            mir::VarDebugInfoContents::Place(place) => {
+                Some((place.as_local()?, info.name.to_string()))
+            }
+            _ => None,
+        })
+        .collect();
+    // The target of an edge is a loop head if it dominates the source.
+    let dominators = body.dominators();
+    let mut loop_heads: Vec<_> = body
+        .basic_blocks()
+        .iter_enumerated()
+        .flat_map(|(bb, data)| {
+            data.terminator()
+                .successors()
+                .map(move |&successor| (bb, successor))
+        })
+        .filter(|&(bb, successor)| dominators.is_dominated_by(bb, successor))
+        .map(|(_, loop_head)| loop_head)
+        .collect();
+    loop_heads.sort();
+    loop_heads.dedup();
+
+    for loop_head in loop_heads {
+        let location = mir::Location {
+            block: loop_head,
+            statement_index: 0,
+        };
+        let state = match result.lookup_before(location) {
+            Some(state) if !state.is_bottom() => state,
+            _ => continue,
+        };
+        let name = |local: mir::Local| match state.get_sequence(local) {
+            Some(sequence) => names.get(&sequence).map(|name| format!("len({})", name)),
+            None => names.get(&local).cloned(),
+        };
+        let mut facts = vec![];
+        for (local, interval) in state.get_intervals() {
+            if let Some(name) = name(local) {
+                let type_interval = state.get_type_interval(local);
+                if let Some(lower) = interval
+                    .lower
+                    .filter(|&lower| Some(lower) != type_interval.lower)
+                {
+                    facts.push(format!("{} <= {}", lower, name));
+                }
+                if let Some(upper) = interval
+                    .upper
+                    .filter(|&upper| Some(upper) != type_interval.upper)
+                {
+                    facts.push(format!("{} <= {}", name, upper));
+                }
+            }
+        }
+        for (x, y, c) in state.get_upper_bounds() {
+            if let (Some(x), Some(y)) = (name(x), name(y)) {
+                facts.push(match c.cmp(&0) {
+                    Ordering::Less => format!("{} <= {} - {}", x, y, c.unsigned_abs()),
+                    Ordering::Equal => format!("{} <= {}", x, y),
+                    Ordering::Greater => format!("{} <= {} + {}", x, y, c),
+                });
+            }
+        }
+        facts.sort();
+        let span = body.source_info(location).span;
+        let line = tcx.sess.source_map().lookup_char_pos(span.lo()).line;
+        for fact in facts {
+            println!("line {}: {}", line, fact);
+        }
+    }
+}
+
 mod mir_storage {
     use super::*;
 
@@ -225,6 +308,24 @@ impl rustc_driver::Callbacks for OurCompilerCalls {
                             Err(e) => eprintln!("{}", e.to_pretty_str(body)),
                         }
                     }
+                    "IntervalAnalysis" => {
+                        let result = IntervalAnalysis::new(tcx, local_def_id.to_def_id(), body)
+                            .run_fwd_analysis();
+                        match result {
+                            Ok(state) => {
+                                println!("{}", serde_json::to_string_pretty(&state).unwrap())
+                            }
+                            Err(e) => eprintln!("{}", e.to_pretty_str(body)),
+                        }
+                    }
+                    "IntervalLoopInvariants" => {
+                        let result = IntervalAnalysis::new(tcx, local_def_id.to_def_id(), body)
+                            .run_fwd_analysis();
+                        match result {
+                            Ok(state) => print_loop_invariants(tcx, body, &state),
+                            Err(e) => eprintln!("{}", e.to_pretty_str(body)),
+                        }
+                    }
                     "MaybeBorrowedAnalysis" => {
                         let analyzer = MaybeBorrowedAnalysis::new(tcx, &body_with_facts);
                         match analyzer.run_analysis() {
@@ -0,0 +1,685 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{
+    abstract_interpretation::{AbstractState, AnalysisResult, FixpointEngine},
+    domains::{CheckedResult, Comparison, Interval, IntervalsState, Term},
+    AnalysisError,
+};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_middle::{
+    mir,
+    ty::{self, TyCtxt},
+};
+use rustc_span::def_id::DefId;
+use std::rc::Rc;
+
+/// An interval analysis of the integer locals, extended with difference
+/// bounds `x <= y + c` between them. Locals whose address is taken are not
+/// tracked, because they can be modified through a reference.
+///
+/// The lengths of the arrays, slices and vectors held by a local, or behind a
+/// reference held by a local, are tracked like integer locals, so that the
+/// bounds `i < v.len()` of a loop are kept after the temporary holding the
+/// result of `len` goes out of scope.
+pub struct IntervalAnalysis<'mir, 'tcx: 'mir> {
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    mir: &'mir mir::Body<'tcx>,
+    type_intervals: Rc<FxHashMap<mir::Local, Interval>>,
+    lengths: Rc<FxHashMap<mir::Local, mir::Local>>,
+}
+
+impl<'mir, 'tcx: 'mir> IntervalAnalysis<'mir, 'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>, def_id: DefId, mir: &'mir mir::Body<'tcx>) -> Self {
+        let mut borrowed_locals = FxHashSet::default();
+        // The locals that can be overwritten through a mutable borrow.
+        let mut mutably_borrowed_locals = FxHashSet::default();
+        for block in mir.basic_blocks() {
+            for stmt in &block.statements {
+                let (place, is_mutable) = match &stmt.kind {
+                    mir::StatementKind::Assign(box (_, mir::Rvalue::Ref(_, kind, place))) => {
+                        (place, matches!(kind, mir::BorrowKind::Mut { .. }))
+                    }
+                    mir::StatementKind::Assign(box (
+                        _,
+                        mir::Rvalue::AddressOf(mutability, place),
+                    )) => (place, *mutability == mir::Mutability::Mut),
+                    _ => continue,
+                };
+                borrowed_locals.insert(place.local);
+                if is_mutable && !place.is_indirect() {
+                    mutably_borrowed_locals.insert(place.local);
+                }
+            }
+        }
+        let mut analysis = IntervalAnalysis {
+            tcx,
+            def_id,
+            mir,
+            type_intervals: Rc::new(FxHashMap::default()),
+            lengths: Rc::new(FxHashMap::default()),
+        };
+        let mut type_intervals: FxHashMap<_, _> = mir
+            .local_decls
+            .iter_enumerated()
+            .filter(|(local, _)| !borrowed_locals.contains(local))
+            .filter_map(|(local, decl)| Some((local, analysis.type_interval(decl.ty)?)))
+            .collect();
+        // The length of each sequence is tracked by a local that is not part
+        // of the body.
+        let mut lengths = FxHashMap::default();
+        for (local, decl) in mir.local_decls.iter_enumerated() {
+            let is_mutably_borrowed = mutably_borrowed_locals.contains(&local);
+            if let Some(interval) = analysis.length_interval(decl.ty, is_mutably_borrowed) {
+                let length = mir::Local::new(mir.local_decls.len() + lengths.len());
+                type_intervals.insert(length, interval);
+                lengths.insert(local, length);
+            }
+        }
+        analysis.type_intervals = Rc::new(type_intervals);
+        analysis.lengths = Rc::new(lengths);
+        analysis
+    }
+
+    /// The range of an integer type.
+    fn type_interval(&self, ty: ty::Ty<'tcx>) -> Option<Interval> {
+        let bits = self.bit_width(ty)?;
+        Some(match ty.kind() {
+            ty::TyKind::Int(_) => Interval::new(
+                Some(-(1i128 << (bits - 1))),
+                Some((1i128 << (bits - 1)) - 1),
+            ),
+            ty::TyKind::Uint(_) if bits < 127 => Interval::new(Some(0), Some((1i128 << bits) - 1)),
+            ty::TyKind::Uint(_) => Interval::new(Some(0), None),
+            _ => unreachable!(),
+        })
+    }
+
+    /// The range of the length of the sequence held by a local of type `ty`,
+    /// if the length can only change when the local is assigned.
+    fn length_interval(&self, ty: ty::Ty<'tcx>, is_mutably_borrowed: bool) -> Option<Interval> {
+        let usize_interval = self.type_interval(self.tcx.types.usize);
+        match ty.kind() {
+            ty::TyKind::Array(_, length) => {
+                let length = length.try_eval_usize(self.tcx, ty::ParamEnv::reveal_all())?;
+                Some(Interval::constant(length.into()))
+            }
+            _ if is_mutably_borrowed => None,
+            ty::TyKind::Slice(_) => usize_interval,
+            _ if self.is_vec(ty) => usize_interval,
+            // A vector can be resized through a mutable reference.
+            ty::TyKind::Ref(_, target_ty, mutability)
+                if *mutability == mir::Mutability::Not || !self.is_vec(*target_ty) =>
+            {
+                self.length_interval(*target_ty, false)
+            }
+            _ => None,
+        }
+    }
+
+    fn is_vec(&self, ty: ty::Ty<'tcx>) -> bool {
+        match ty.kind() {
+            ty::TyKind::Adt(adt_def, _) => self.tcx.def_path_str(adt_def.did()) == "std::vec::Vec",
+            _ => false,
+        }
+    }
+
+    fn bit_width(&self, ty: ty::Ty<'tcx>) -> Option<u64> {
+        let pointer_width = self.tcx.data_layout.pointer_size.bits();
+        match ty.kind() {
+            ty::TyKind::Int(int_ty) => Some(int_ty.bit_width().unwrap_or(pointer_width)),
+            ty::TyKind::Uint(uint_ty) => Some(uint_ty.bit_width().unwrap_or(pointer_width)),
+            _ => None,
+        }
+    }
+
+    fn is_tracked(&self, local: mir::Local) -> bool {
+        self.type_intervals.contains_key(&local)
+    }
+
+    fn eval_constant(&self, constant: &mir::Constant<'tcx>) -> Option<i128> {
+        let ty = constant.ty();
+        let bits = self.bit_width(ty)?;
+        let value = constant
+            .literal
+            .try_eval_bits(self.tcx, ty::ParamEnv::reveal_all(), ty)?;
+        match ty.kind() {
+            ty::TyKind::Int(_) => {
+                let shift = 128 - bits;
+                Some(((value << shift) as i128) >> shift)
+            }
+            _ => i128::try_from(value).ok(),
+        }
+    }
+
+    /// The term of an operand, looking through copies of tracked locals.
+    fn eval_term(&self, state: &IntervalsState, operand: &mir::Operand<'tcx>) -> Option<Term> {
+        match operand {
+            mir::Operand::Copy(place) | mir::Operand::Move(place) => {
+                let local = place.as_local().filter(|&local| self.is_tracked(local))?;
+                Some(Term::Local(
+                    state.copies.get(&local).copied().unwrap_or(local),
+                ))
+            }
+            mir::Operand::Constant(constant) => self.eval_constant(constant).map(Term::Constant),
+        }
+    }
+
+    fn term_interval(&self, state: &IntervalsState, term: Term) -> Interval {
+        match term {
+            Term::Local(local) => state.get_interval(local),
+            Term::Constant(value) => Interval::constant(value),
+        }
+    }
+
+    /// The result of a checked operation, if `operand` reads its first field.
+    fn eval_checked_result(
+        &self,
+        state: &IntervalsState,
+        operand: &mir::Operand<'tcx>,
+    ) -> Option<CheckedResult> {
+        let place = operand.place()?;
+        match place.projection[..] {
+            [mir::ProjectionElem::Field(field, _)] if field.index() == 0 => {
+                state.checked_results.get(&place.local).copied()
+            }
+            _ => None,
+        }
+    }
+
+    fn eval_operand(&self, state: &IntervalsState, operand: &mir::Operand<'tcx>) -> Interval {
+        if let Some(term) = self.eval_term(state, operand) {
+            self.term_interval(state, term)
+        } else if let Some(result) = self.eval_checked_result(state, operand) {
+            result.interval
+        } else {
+            Interval::top()
+        }
+    }
+
+    fn eval_binary_op(
+        &self,
+        state: &IntervalsState,
+        op: mir::BinOp,
+        left: &mir::Operand<'tcx>,
+        right: &mir::Operand<'tcx>,
+    ) -> Interval {
+        let left = self.eval_operand(state, left);
+        let right = self.eval_operand(state, right);
+        match op {
+            mir::BinOp::Add => left.add(&right),
+            mir::BinOp::Sub => left.sub(&right),
+            mir::BinOp::Mul => left.mul(&right),
+            _ => Interval::top(),
+        }
+    }
+
+    /// The local and the offset of `local + constant` or `local - constant`.
+    fn eval_offset(
+        &self,
+        state: &IntervalsState,
+        op: mir::BinOp,
+        left: &mir::Operand<'tcx>,
+        right: &mir::Operand<'tcx>,
+    ) -> Option<(mir::Local, i128)> {
+        match (
+            op,
+            self.eval_term(state, left)?,
+            self.eval_term(state, right)?,
+        ) {
+            (mir::BinOp::Add, Term::Local(local), Term::Constant(offset))
+            | (mir::BinOp::Add, Term::Constant(offset), Term::Local(local)) => {
+                Some((local, offset))
+            }
+            (mir::BinOp::Sub, Term::Local(local), Term::Constant(offset)) => {
+                Some((local, offset.checked_neg()?))
+            }
+            _ => None,
+        }
+    }
+
+    fn apply_assignment(
+        &self,
+        state: &mut IntervalsState,
+        target: mir::Place<'tcx>,
+        rvalue: &mir::Rvalue<'tcx>,
+    ) {
+        let target = match target.as_local() {
+            Some(local) => local,
+            // Writing through a reference changes neither the reference nor
+            // the length of the sequence it points to.
+            None if target.is_indirect() => return,
+            None => {
+                state.kill(target.local);
+                return;
+            }
+        };
+        // Evaluate the right-hand side before forgetting the old value of the
+        // target, which the right-hand side can mention.
+        let mut offset_of = None;
+        let mut interval = Interval::top();
+        let mut copy_of = None;
+        let reference_to = self.eval_reference(state, rvalue);
+        match rvalue {
+            mir::Rvalue::Use(operand) => {
+                interval = self.eval_operand(state, operand);
+                match self.eval_term(state, operand) {
+                    Some(Term::Local(source)) => {
+                        if source != target {
+                            copy_of = Some(source);
+                        }
+                        offset_of = Some((source, 0));
+                    }
+                    Some(Term::Constant(_)) => {}
+                    None => {
+                        offset_of = self
+                            .eval_checked_result(state, operand)
+                            .and_then(|result| result.offset_of);
+                    }
+                }
+            }
+            mir::Rvalue::BinaryOp(op, box (left, right)) => {
+                interval = self.eval_binary_op(state, *op, left, right);
+                offset_of = self.eval_offset(state, *op, left, right);
+                if self.is_tracked(target) {
+                    // Without overflow checks the operation can wrap around.
+                    if !interval.is_included_in(&state.get_type_interval(target)) {
+                        interval = Interval::top();
+                        offset_of = None;
+                    }
+                } else if let (
+                    mir::BinOp::Lt
+                    | mir::BinOp::Le
+                    | mir::BinOp::Gt
+                    | mir::BinOp::Ge
+                    | mir::BinOp::Eq
+                    | mir::BinOp::Ne,
+                    Some(left),
+                    Some(right),
+                ) = (
+                    op,
+                    self.eval_term(state, left),
+                    self.eval_term(state, right),
+                ) {
+                    let comparison = Comparison {
+                        op: *op,
+                        left,
+                        right,
+                    };
+                    state.kill(target);
+                    state.comparisons.insert(target, comparison);
+                    return;
+                }
+            }
+            mir::Rvalue::CheckedBinaryOp(op, box (left, right)) => {
+                let result = CheckedResult {
+                    interval: self.eval_binary_op(state, *op, left, right),
+                    offset_of: self.eval_offset(state, *op, left, right),
+                };
+                state.kill(target);
+                state.checked_results.insert(target, result);
+                return;
+            }
+            mir::Rvalue::Cast(mir::CastKind::Misc, operand, _) => {
+                interval = self.eval_operand(state, operand);
+                if self.is_tracked(target)
+                    && !interval.is_included_in(&state.get_type_interval(target))
+                {
+                    interval = Interval::top();
+                }
+            }
+            mir::Rvalue::Len(place) => {
+                if let Some(&length) = self
+                    .eval_sequence(*place)
+                    .and_then(|sequence| self.lengths.get(&sequence))
+                {
+                    interval = state.get_interval(length);
+                    offset_of = Some((length, 0));
+                }
+            }
+            _ => {}
+        }
+
+        self.assign(state, target, interval, offset_of, copy_of);
+        if let Some(sequence) = reference_to {
+            state.references.insert(target, sequence);
+        }
+    }
+
+    /// The sequence local whose length is tracked, if `place` is the local or
+    /// the target of the reference held by the local.
+    fn eval_sequence(&self, place: mir::Place<'tcx>) -> Option<mir::Local> {
+        match place.projection[..] {
+            [] | [mir::ProjectionElem::Deref] if self.lengths.contains_key(&place.local) => {
+                Some(place.local)
+            }
+            _ => None,
+        }
+    }
+
+    /// The sequence local that `rvalue` references, if `rvalue` borrows a
+    /// sequence, copies a reference to a sequence or unsizes it.
+    fn eval_reference(
+        &self,
+        state: &IntervalsState,
+        rvalue: &mir::Rvalue<'tcx>,
+    ) -> Option<mir::Local> {
+        match rvalue {
+            mir::Rvalue::Ref(_, _, place) => self.eval_sequence(*place),
+            mir::Rvalue::Use(operand)
+            | mir::Rvalue::Cast(mir::CastKind::Pointer(_), operand, _) => {
+                let local = operand.place()?.as_local()?;
+                if let Some(&sequence) = state.references.get(&local) {
+                    Some(sequence)
+                } else if self.mir.local_decls[local].ty.is_ref()
+                    && self.lengths.contains_key(&local)
+                {
+                    Some(local)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// The local that holds the length of the sequence on which `len` is
+    /// called, if `func` is the `len` method of slices or vectors.
+    fn eval_length_call(
+        &self,
+        state: &IntervalsState,
+        func: &mir::Operand<'tcx>,
+        args: &[mir::Operand<'tcx>],
+    ) -> Option<mir::Local> {
+        let (def_id, _) = func.const_fn_def()?;
+        match self.tcx.def_path_str(def_id).as_str() {
+            "core::slice::<impl [T]>::len" | "std::vec::Vec::<T, A>::len" => {}
+            _ => return None,
+        }
+        let reference = args.first()?.place()?.as_local()?;
+        let sequence = state
+            .references
+            .get(&reference)
+            .copied()
+            .or_else(|| Some(reference).filter(|local| self.lengths.contains_key(local)))?;
+        self.lengths.get(&sequence).copied()
+    }
+
+    /// Assign to the local `target` a value in `interval`, which is equal to
+    /// `base + offset` if `offset_of` is `Some((base, offset))`.
+    fn assign(
+        &self,
+        state: &mut IntervalsState,
+        target: mir::Local,
+        interval: Interval,
+        offset_of: Option<(mir::Local, i128)>,
+        copy_of: Option<mir::Local>,
+    ) {
+        // Compute the difference bounds of the new value `target == base +
+        // offset` from those of `base`, which can be the old value of `target`.
+        let mut upper_bounds = vec![];
+        if let Some((base, offset)) = offset_of.filter(|_| self.is_tracked(target)) {
+            if base != target {
+                upper_bounds.push((target, base, offset));
+                if let Some(c) = offset.checked_neg() {
+                    upper_bounds.push((base, target, c));
+                }
+            }
+            for (x, y, c) in state.get_upper_bounds() {
+                // base <= y + c  ==>  target <= y + c + offset
+                if x == base && y != target {
+                    if let Some(c) = c.checked_add(offset) {
+                        upper_bounds.push((target, y, c));
+                    }
+                }
+                // x <= base + c  ==>  x <= target + c - offset
+                if y == base && x != target {
+                    if let Some(c) = c.checked_sub(offset) {
+                        upper_bounds.push((x, target, c));
+                    }
+                }
+            }
+        }
+
+        state.kill(target);
+        if self.is_tracked(target) {
+            state.restrict(target, interval);
+            for (x, y, c) in upper_bounds {
+                state.add_upper_bound(x, y, c);
+            }
+            if let Some(source) = copy_of {
+                state.copies.insert(target, source);
+            }
+        }
+    }
+
+    /// Restrict `state` to the executions where `comparison` evaluates to
+    /// `value`.
+    fn apply_comparison(&self, state: &mut IntervalsState, comparison: Comparison, value: bool) {
+        let op = if value {
+            comparison.op
+        } else {
+            match comparison.op {
+                mir::BinOp::Lt => mir::BinOp::Ge,
+                mir::BinOp::Le => mir::BinOp::Gt,
+                mir::BinOp::Gt => mir::BinOp::Le,
+                mir::BinOp::Ge => mir::BinOp::Lt,
+                mir::BinOp::Eq => mir::BinOp::Ne,
+                mir::BinOp::Ne => mir::BinOp::Eq,
+                _ => unreachable!(),
+            }
+        };
+        // Normalize to `left <= right + c` or equalities.
+        let (left, right, c) = match op {
+            mir::BinOp::Lt => (comparison.left, comparison.right, -1),
+            mir::BinOp::Le => (comparison.left, comparison.right, 0),
+            mir::BinOp::Gt => (comparison.right, comparison.left, -1),
+            mir::BinOp::Ge => (comparison.right, comparison.left, 0),
+            mir::BinOp::Eq => {
+                self.apply_upper_bound(state, comparison.left, comparison.right, 0);
+                self.apply_upper_bound(state, comparison.right, comparison.left, 0);
+                return;
+            }
+            _ => return,
+        };
+        self.apply_upper_bound(state, left, right, c);
+    }
+
+    /// Restrict `state` to the executions where `left <= right + c`.
+    fn apply_upper_bound(&self, state: &mut IntervalsState, left: Term, right: Term, c: i128) {
+        let left_interval = self.term_interval(state, left);
+        let right_interval = self.term_interval(state, right);
+        if let Term::Local(local) = left {
+            let upper = right_interval.upper.and_then(|upper| upper.checked_add(c));
+            state.restrict(local, Interval::new(None, upper));
+        }
+        if let Term::Local(local) = right {
+            let lower = left_interval.lower.and_then(|lower| lower.checked_sub(c));
+            state.restrict(local, Interval::new(lower, None));
+        }
+        if let (Term::Local(x), Term::Local(y)) = (left, right) {
+            state.add_upper_bound(x, y, c);
+            // Combine the new bound with the known bounds of `y` and on `x`,
+            // e.g. with the length of a sequence that `y` is a copy of.
+            let upper_bounds: Vec<_> = state.get_upper_bounds().collect();
+            for (u, v, d) in upper_bounds {
+                // y <= v + d  ==>  x <= v + c + d
+                if u == y {
+                    if let Some(e) = c.checked_add(d) {
+                        state.add_upper_bound(x, v, e);
+                    }
+                }
+                // u <= x + d  ==>  u <= y + c + d
+                if v == x {
+                    if let Some(e) = c.checked_add(d) {
+                        state.add_upper_bound(u, y, e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Restrict the state on the edges of a `switchInt` or `assert` on the
+    /// boolean `operand`.
+    fn apply_condition(
+        &self,
+        state: &mut IntervalsState,
+        operand: &mir::Operand<'tcx>,
+        value: bool,
+    ) {
+        if let Some(comparison) = operand
+            .place()
+            .and_then(|place| place.as_local())
+            .and_then(|local| state.comparisons.get(&local).copied())
+        {
+            self.apply_comparison(state, comparison, value);
+        }
+    }
+}
+
+impl<'mir, 'tcx: 'mir> FixpointEngine<'mir, 'tcx> for IntervalAnalysis<'mir, 'tcx> {
+    type State = IntervalsState;
+
+    fn def_id(&self) -> DefId {
+        self.def_id
+    }
+
+    fn body(&self) -> &'mir mir::Body<'tcx> {
+        self.mir
+    }
+
+    /// The bottom element of the lattice is the state of unreachable code.
+    fn new_bottom(&self) -> Self::State {
+        IntervalsState::new_bottom(self.type_intervals.clone(), self.lengths.clone())
+    }
+
+    /// The arguments can have any value of their type.
+    fn new_initial(&self) -> Self::State {
+        IntervalsState::new_top(self.type_intervals.clone(), self.lengths.clone())
+    }
+
+    fn need_to_widen(counter: u32) -> bool {
+        counter > 3
+    }
+
+    fn apply_statement_effect(
+        &self,
+        state: &mut Self::State,
+        location: mir::Location,
+    ) -> AnalysisResult<()> {
+        if state.is_bottom() {
+            return Ok(());
+        }
+        let stmt = &self.mir[location.block].statements[location.statement_index];
+        match &stmt.kind {
+            mir::StatementKind::Assign(box (target, rvalue)) => {
+                self.apply_assignment(state, *target, rvalue);
+            }
+            mir::StatementKind::StorageLive(local) | mir::StatementKind::StorageDead(local) => {
+                state.kill(*local);
+            }
+            mir::StatementKind::SetDiscriminant { place, .. } => {
+                state.kill(place.local);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn apply_terminator_effect(
+        &self,
+        state: &Self::State,
+        location: mir::Location,
+    ) -> AnalysisResult<Vec<(mir::BasicBlock, Self::State)>> {
+        let terminator = self.mir[location.block].terminator();
+        if state.is_bottom() {
+            return Ok(terminator
+                .successors()
+                .map(|&bb| (bb, state.clone()))
+                .collect());
+        }
+        let mut res_vec = Vec::new();
+        match &terminator.kind {
+            mir::TerminatorKind::SwitchInt { discr, targets, .. } => {
+                let is_bool = discr.ty(self.mir, self.tcx).is_bool();
+                for (value, target) in targets.iter() {
+                    let mut target_state = state.clone();
+                    if is_bool && value <= 1 {
+                        self.apply_condition(&mut target_state, discr, value == 1);
+                    }
+                    res_vec.push((target, target_state));
+                }
+                let mut otherwise_state = state.clone();
+                if is_bool {
+                    if let [(value, _)] = targets.iter().collect::<Vec<_>>()[..] {
+                        self.apply_condition(&mut otherwise_state, discr, value != 1);
+                    }
+                }
+                res_vec.push((targets.otherwise(), otherwise_state));
+            }
+            mir::TerminatorKind::Assert {
+                cond,
+                expected,
+                target,
+                cleanup,
+                ..
+            } => {
+                let mut target_state = state.clone();
+                self.apply_condition(&mut target_state, cond, *expected);
+                res_vec.push((*target, target_state));
+                if let Some(cleanup) = cleanup {
+                    res_vec.push((*cleanup, state.clone()));
+                }
+            }
+            mir::TerminatorKind::Call {
+                func,
+                args,
+                destination,
+                cleanup,
+                ..
+            } => {
+                let mut dest_state = state.clone();
+                if let Some((place, bb)) = destination {
+                    match (place.as_local(), self.eval_length_call(state, func, args)) {
+                        (Some(target), Some(length)) if self.is_tracked(target) => {
+                            let interval = state.get_interval(length);
+                            self.assign(&mut dest_state, target, interval, Some((length, 0)), None);
+                        }
+                        _ => dest_state.kill(place.local),
+                    }
+                    res_vec.push((*bb, dest_state.clone()));
+                }
+                if let Some(bb) = cleanup {
+                    res_vec.push((*bb, dest_state));
+                }
+            }
+            mir::TerminatorKind::DropAndReplace { place, .. } => {
+                let mut dest_state = state.clone();
+                dest_state.kill(place.local);
+                for &bb in terminator.successors() {
+                    res_vec.push((bb, dest_state.clone()));
+                }
+            }
+            mir::TerminatorKind::Yield { resume_arg, .. } => {
+                let mut dest_state = state.clone();
+                dest_state.kill(resume_arg.local);
+                for &bb in terminator.successors() {
+                    res_vec.push((bb, dest_state.clone()));
+                }
+            }
+            mir::TerminatorKind::InlineAsm { .. } => {
+                return Err(AnalysisError::UnsupportedStatement(location));
+            }
+            _ => {
+                for &bb in terminator.successors() {
+                    res_vec.push((bb, state.clone()));
+                }
+            }
+        }
+        Ok(res_vec)
+    }
+}
@@ -0,0 +1,458 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::abstract_interpretation::AbstractState;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_middle::mir;
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use std::{cmp, collections::BTreeMap, fmt, rc::Rc};
+
+/// A set of integers `lower..=upper`, where `None` means unbounded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Interval {
+    pub lower: Option<i128>,
+    pub upper: Option<i128>,
+}
+
+impl Interval {
+    pub fn new(lower: Option<i128>, upper: Option<i128>) -> Self {
+        Interval { lower, upper }
+    }
+
+    pub fn top() -> Self {
+        Interval::new(None, None)
+    }
+
+    pub fn constant(value: i128) -> Self {
+        Interval::new(Some(value), Some(value))
+    }
+
+    /// Whether `self` is a subset of `other`.
+    pub fn is_included_in(&self, other: &Self) -> bool {
+        let lower_ok = match (self.lower, other.lower) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(a), Some(b)) => a >= b,
+        };
+        let upper_ok = match (self.upper, other.upper) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(a), Some(b)) => a <= b,
+        };
+        lower_ok && upper_ok
+    }
+
+    pub fn join(&self, other: &Self) -> Self {
+        Interval::new(
+            self.lower.zip(other.lower).map(|(a, b)| cmp::min(a, b)),
+            self.upper.zip(other.upper).map(|(a, b)| cmp::max(a, b)),
+        )
+    }
+
+    /// Returns `None` if the intersection is empty.
+    pub fn meet(&self, other: &Self) -> Option<Self> {
+        let lower = match (self.lower, other.lower) {
+            (Some(a), Some(b)) => Some(cmp::max(a, b)),
+            (a, b) => a.or(b),
+        };
+        let upper = match (self.upper, other.upper) {
+            (Some(a), Some(b)) => Some(cmp::min(a, b)),
+            (a, b) => a.or(b),
+        };
+        match (lower, upper) {
+            (Some(lower), Some(upper)) if lower > upper => None,
+            _ => Some(Interval::new(lower, upper)),
+        }
+    }
+
+    /// Drop the bounds that got weaker since `previous`.
+    pub fn widen(&self, previous: &Self) -> Self {
+        Interval::new(
+            self.lower
+                .filter(|&a| previous.lower.map_or(false, |b| a >= b)),
+            self.upper
+                .filter(|&a| previous.upper.map_or(false, |b| a <= b)),
+        )
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        Interval::new(
+            self.lower
+                .zip(other.lower)
+                .and_then(|(a, b)| a.checked_add(b)),
+            self.upper
+                .zip(other.upper)
+                .and_then(|(a, b)| a.checked_add(b)),
+        )
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        Interval::new(
+            self.lower
+                .zip(other.upper)
+                .and_then(|(a, b)| a.checked_sub(b)),
+            self.upper
+                .zip(other.lower)
+                .and_then(|(a, b)| a.checked_sub(b)),
+        )
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        match (self.lower, self.upper, other.lower, other.upper) {
+            (Some(a), Some(b), Some(c), Some(d)) => {
+                let products = [
+                    a.checked_mul(c),
+                    a.checked_mul(d),
+                    b.checked_mul(c),
+                    b.checked_mul(d),
+                ];
+                if products.iter().all(Option::is_some) {
+                    let products = products.iter().map(|p| p.unwrap());
+                    Interval::new(products.clone().min(), products.max())
+                } else {
+                    Interval::top()
+                }
+            }
+            _ => Interval::top(),
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.lower {
+            Some(lower) => write!(f, "[{}, ", lower)?,
+            None => write!(f, "[-inf, ")?,
+        }
+        match self.upper {
+            Some(upper) => write!(f, "{}]", upper),
+            None => write!(f, "+inf]"),
+        }
+    }
+}
+
+/// An integer local or constant compared by a boolean local.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Term {
+    Local(mir::Local),
+    Constant(i128),
+}
+
+/// The comparison `left op right` that was assigned to a boolean local.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Comparison {
+    pub op: mir::BinOp,
+    pub left: Term,
+    pub right: Term,
+}
+
+impl Comparison {
+    fn mentions(&self, local: mir::Local) -> bool {
+        self.left == Term::Local(local) || self.right == Term::Local(local)
+    }
+}
+
+/// The result of a checked addition or subtraction `local + offset`, whose
+/// first field is the value of the operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CheckedResult {
+    pub interval: Interval,
+    pub offset_of: Option<(mir::Local, i128)>,
+}
+
+/// The abstract state of the interval analysis. The tracked locals are the
+/// integer locals whose address is never taken, and the locals that hold the
+/// length of a sequence; a tracked local that has no interval can have any
+/// value of its type.
+#[derive(Clone)]
+pub struct IntervalsState {
+    pub(super) reachable: bool,
+    /// The range of the type of each tracked local.
+    pub(super) type_intervals: Rc<FxHashMap<mir::Local, Interval>>,
+    /// The local that holds the length of each sequence local. These locals
+    /// are numbered after the locals of the body.
+    pub(super) lengths: Rc<FxHashMap<mir::Local, mir::Local>>,
+    pub(super) intervals: FxHashMap<mir::Local, Interval>,
+    /// The difference bounds: `(x, y) -> c` means `x <= y + c`.
+    pub(super) upper_bounds: FxHashMap<(mir::Local, mir::Local), i128>,
+    /// Locals that are a copy of another local.
+    pub(super) copies: FxHashMap<mir::Local, mir::Local>,
+    pub(super) comparisons: FxHashMap<mir::Local, Comparison>,
+    pub(super) checked_results: FxHashMap<mir::Local, CheckedResult>,
+    /// Locals that hold a reference to a sequence local.
+    pub(super) references: FxHashMap<mir::Local, mir::Local>,
+}
+
+impl fmt::Debug for IntervalsState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // ignore type_intervals and lengths
+        f.debug_struct("IntervalsState")
+            .field("reachable", &self.reachable)
+            .field("intervals", &self.intervals)
+            .field("upper_bounds", &self.upper_bounds)
+            .finish()
+    }
+}
+
+impl PartialEq for IntervalsState {
+    fn eq(&self, other: &Self) -> bool {
+        debug_assert!(Rc::ptr_eq(&self.type_intervals, &other.type_intervals));
+        debug_assert!(Rc::ptr_eq(&self.lengths, &other.lengths));
+        // Ignore the `type_intervals` and `lengths` fields.
+        self.reachable == other.reachable
+            && self.intervals == other.intervals
+            && self.upper_bounds == other.upper_bounds
+            && self.copies == other.copies
+            && self.comparisons == other.comparisons
+            && self.checked_results == other.checked_results
+            && self.references == other.references
+    }
+}
+impl Eq for IntervalsState {}
+
+impl IntervalsState {
+    pub(super) fn new_bottom(
+        type_intervals: Rc<FxHashMap<mir::Local, Interval>>,
+        lengths: Rc<FxHashMap<mir::Local, mir::Local>>,
+    ) -> Self {
+        IntervalsState {
+            reachable: false,
+            type_intervals,
+            lengths,
+            intervals: FxHashMap::default(),
+            upper_bounds: FxHashMap::default(),
+            copies: FxHashMap::default(),
+            comparisons: FxHashMap::default(),
+            checked_results: FxHashMap::default(),
+            references: FxHashMap::default(),
+        }
+    }
+
+    pub(super) fn new_top(
+        type_intervals: Rc<FxHashMap<mir::Local, Interval>>,
+        lengths: Rc<FxHashMap<mir::Local, mir::Local>>,
+    ) -> Self {
+        IntervalsState {
+            reachable: true,
+            ..Self::new_bottom(type_intervals, lengths)
+        }
+    }
+
+    pub fn is_tracked(&self, local: mir::Local) -> bool {
+        self.type_intervals.contains_key(&local)
+    }
+
+    /// The interval of a tracked `local`.
+    pub fn get_interval(&self, local: mir::Local) -> Interval {
+        debug_assert!(self.is_tracked(local));
+        self.intervals
+            .get(&local)
+            .copied()
+            .unwrap_or_else(|| self.type_intervals[&local])
+    }
+
+    /// The intervals that are more precise than the type of their local.
+    pub fn get_intervals(&self) -> impl Iterator<Item = (mir::Local, Interval)> + '_ {
+        self.intervals
+            .iter()
+            .map(|(&local, &interval)| (local, interval))
+    }
+
+    /// The sequence local whose length is held by `local`, if `local` is not
+    /// a local of the body but the length of a sequence.
+    pub fn get_sequence(&self, local: mir::Local) -> Option<mir::Local> {
+        self.lengths
+            .iter()
+            .find(|(_, &length)| length == local)
+            .map(|(&sequence, _)| sequence)
+    }
+
+    /// The name of a tracked local, e.g. `_1` or `len(_1)`.
+    pub fn get_name(&self, local: mir::Local) -> String {
+        match self.get_sequence(local) {
+            Some(sequence) => format!("len({:?})", sequence),
+            None => format!("{:?}", local),
+        }
+    }
+
+    /// The range of the type of a tracked `local`.
+    pub fn get_type_interval(&self, local: mir::Local) -> Interval {
+        self.type_intervals[&local]
+    }
+
+    /// Restrict the interval of a tracked `local`. Makes the state
+    /// unreachable if the interval becomes empty.
+    pub(super) fn restrict(&mut self, local: mir::Local, interval: Interval) {
+        match self.get_interval(local).meet(&interval) {
+            Some(interval) => {
+                if interval == self.type_intervals[&local] {
+                    self.intervals.remove(&local);
+                } else {
+                    self.intervals.insert(local, interval);
+                }
+            }
+            None => self.reachable = false,
+        }
+    }
+
+    /// The difference bounds `x <= y + c`, as `(x, y, c)` triples.
+    pub fn get_upper_bounds(&self) -> impl Iterator<Item = (mir::Local, mir::Local, i128)> + '_ {
+        self.upper_bounds.iter().map(|(&(x, y), &c)| (x, y, c))
+    }
+
+    /// Forget everything about the value of `local`.
+    pub(super) fn kill(&mut self, local: mir::Local) {
+        self.intervals.remove(&local);
+        self.upper_bounds
+            .retain(|&(x, y), _| x != local && y != local);
+        self.copies.remove(&local);
+        self.copies.retain(|_, source| *source != local);
+        self.comparisons.remove(&local);
+        self.comparisons
+            .retain(|_, comparison| !comparison.mentions(local));
+        self.checked_results.remove(&local);
+        self.checked_results
+            .retain(|_, result| result.offset_of.map_or(true, |(base, _)| base != local));
+        self.references.remove(&local);
+        self.references.retain(|_, sequence| *sequence != local);
+        if let Some(&length) = self.lengths.get(&local) {
+            self.kill(length);
+        }
+    }
+
+    pub(super) fn add_upper_bound(&mut self, x: mir::Local, y: mir::Local, c: i128) {
+        if x == y {
+            return;
+        }
+        let bound = self.upper_bounds.entry((x, y)).or_insert(c);
+        *bound = cmp::min(*bound, c);
+    }
+
+    /// The difference bound `x <= y + c` that follows from the intervals.
+    fn implied_upper_bound(&self, x: mir::Local, y: mir::Local) -> Option<i128> {
+        let x_upper = self.get_interval(x).upper?;
+        let y_lower = self.get_interval(y).lower?;
+        x_upper.checked_sub(y_lower)
+    }
+}
+
+impl Serialize for IntervalsState {
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        if !self.reachable {
+            return serializer.serialize_str("unreachable");
+        }
+        let mut facts: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (&local, interval) in self.intervals.iter() {
+            facts
+                .entry(self.get_name(local))
+                .or_default()
+                .push(format!("{}", interval));
+        }
+        for (&(x, y), c) in self.upper_bounds.iter() {
+            facts.entry(self.get_name(x)).or_default().push(format!(
+                "<= {} + {}",
+                self.get_name(y),
+                c
+            ));
+        }
+        let mut map = serializer.serialize_map(Some(facts.len()))?;
+        for (local, mut local_facts) in facts {
+            local_facts.sort();
+            map.serialize_entry(&local, &local_facts)?;
+        }
+        map.end()
+    }
+}
+
+impl AbstractState for IntervalsState {
+    fn is_bottom(&self) -> bool {
+        !self.reachable
+    }
+
+    fn join(&mut self, other: &Self) {
+        if !other.reachable {
+            return;
+        }
+        if !self.reachable {
+            *self = other.clone();
+            return;
+        }
+        let mut upper_bounds = FxHashMap::default();
+        for (&(x, y), &c) in self.upper_bounds.iter() {
+            if let Some(other_c) = other
+                .upper_bounds
+                .get(&(x, y))
+                .copied()
+                .or_else(|| other.implied_upper_bound(x, y))
+            {
+                upper_bounds.insert((x, y), cmp::max(c, other_c));
+            }
+        }
+        for (&(x, y), &other_c) in other.upper_bounds.iter() {
+            if self.upper_bounds.contains_key(&(x, y)) {
+                continue;
+            }
+            if let Some(c) = self.implied_upper_bound(x, y) {
+                upper_bounds.insert((x, y), cmp::max(c, other_c));
+            }
+        }
+        self.upper_bounds = upper_bounds;
+
+        let mut intervals = FxHashMap::default();
+        for (&local, a) in self.intervals.iter() {
+            if let Some(b) = other.intervals.get(&local) {
+                intervals.insert(local, a.join(b));
+            }
+        }
+        self.intervals = intervals;
+        self.copies
+            .retain(|local, source| other.copies.get(local) == Some(source));
+        self.comparisons
+            .retain(|local, comparison| other.comparisons.get(local) == Some(comparison));
+        self.references
+            .retain(|local, sequence| other.references.get(local) == Some(sequence));
+        let other_checked_results = &other.checked_results;
+        self.checked_results.retain(|local, result| {
+            if let Some(other_result) = other_checked_results.get(local) {
+                result.interval = result.interval.join(&other_result.interval);
+                if result.offset_of != other_result.offset_of {
+                    result.offset_of = None;
+                }
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    fn widen(&mut self, previous: &Self) {
+        if !previous.reachable {
+            return;
+        }
+        let previous_intervals = &previous.intervals;
+        let type_intervals = &self.type_intervals;
+        self.intervals.retain(|local, interval| {
+            if let Some(previous_interval) = previous_intervals.get(local) {
+                // The unstable bounds fall back to the bounds of the type.
+                let type_interval = type_intervals[local];
+                let widened = interval.widen(previous_interval);
+                *interval = Interval::new(
+                    widened.lower.or(type_interval.lower),
+                    widened.upper.or(type_interval.upper),
+                );
+                *interval != type_interval
+            } else {
+                false
+            }
+        });
+        let previous_upper_bounds = &previous.upper_bounds;
+        self.upper_bounds.retain(|key, c| {
+            previous_upper_bounds
+                .get(key)
+                .map_or(false, |previous_c| *c <= *previous_c)
+        });
+    }
+}
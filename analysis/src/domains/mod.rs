@@ -7,11 +7,13 @@
 mod definitely_accessible;
 mod definitely_initialized;
 mod framing;
+mod intervals;
 mod maybe_borrowed;
 mod reaching_definitions;
 
 pub use definitely_accessible::*;
 pub use definitely_initialized::*;
 pub use framing::*;
+pub use intervals::*;
 pub use maybe_borrowed::*;
 pub use reaching_definitions::*;
@@ -10,32 +10,6 @@ use std::{
 };
 use utils::*;
 
-/// Prepend paths to an environment variable
-fn env_prepend_path(name: &str, value: Vec<PathBuf>, cmd: &mut Command) {
-    let old_value = env::var_os(name);
-    let mut parts = value;
-    if let Some(ref v) = old_value {
-        parts.extend(env::split_paths(v).collect::<Vec<_>>());
-    };
-    match env::join_paths(parts) {
-        Ok(new_value) => {
-            cmd.env(name, new_value);
-        }
-        Err(err) => panic!("Error: {:?}", err),
-    }
-}
-
-/// Append paths to the loader environment variable
-fn add_to_loader_path(paths: Vec<PathBuf>, cmd: &mut Command) {
-    #[cfg(target_os = "windows")]
-    const LOADER_PATH: &str = "PATH";
-    #[cfg(target_os = "linux")]
-    const LOADER_PATH: &str = "LD_LIBRARY_PATH";
-    #[cfg(target_os = "macos")]
-    const LOADER_PATH: &str = "DYLD_FALLBACK_LIBRARY_PATH";
-    env_prepend_path(LOADER_PATH, paths, cmd);
-}
-
 fn generate_program_testing_accessible_paths(
     program_path: impl AsRef<OsStr> + fmt::Debug,
 ) -> Vec<String> {
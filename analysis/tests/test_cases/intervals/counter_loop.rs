@@ -0,0 +1,21 @@
+fn main() {
+    let v = vec![1, 2, 3];
+    println!("{}", sum(&v));
+}
+
+#[analyzer::run]
+fn sum(v: &Vec<i32>) -> i32 {
+    let n = v.len();
+    let mut i = 0;
+    let mut total = 0;
+    // INVARIANT: i <= n
+    // INVARIANT: i <= len(v)
+    // INVARIANT: n <= len(v)
+    // INVARIANT: len(v) <= n
+    // NOT INVARIANT: i <= n - 1
+    while i < n {
+        total += v[i];
+        i += 1;
+    }
+    total
+}
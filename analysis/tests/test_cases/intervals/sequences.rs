@@ -0,0 +1,57 @@
+fn main() {
+    let mut a = [1, 2, 3, 4, 5, 6, 7, 8];
+    fill(&mut a);
+    println!("{:?} {:?} {}", find(&a, 0), count_to(10), sum(a));
+}
+
+#[analyzer::run]
+fn find(s: &[u32], x: u32) -> Option<usize> {
+    let mut i = 0;
+    // INVARIANT: i <= len(s)
+    // NOT INVARIANT: i <= len(s) - 1
+    while i < s.len() {
+        if s[i] == x {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+#[analyzer::run]
+fn fill(s: &mut [u32]) {
+    let mut i = 0;
+    // Writing the elements does not change the length.
+    // INVARIANT: i <= len(s)
+    while i < s.len() {
+        s[i] = 0;
+        i += 1;
+    }
+}
+
+#[analyzer::run]
+fn sum(a: [u32; 8]) -> u32 {
+    let mut i = 0;
+    let mut total = 0;
+    // INVARIANT: i <= 8
+    // INVARIANT: i <= len(a)
+    while i < a.len() {
+        total += a[i] % 10;
+        i += 1;
+    }
+    total
+}
+
+#[analyzer::run]
+fn count_to(n: u32) -> u32 {
+    let mut i = 0;
+    let mut j = 10;
+    // INVARIANT: i <= n
+    // INVARIANT: 10 <= j
+    // NOT INVARIANT: i <= n - 1
+    while i < n {
+        i += 1;
+        j += 1;
+    }
+    j
+}
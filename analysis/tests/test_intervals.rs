@@ -0,0 +1,102 @@
+mod utils;
+
+use glob::glob;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+use utils::*;
+
+/// The facts that the interval analysis should infer at the head of a loop,
+/// written as `// INVARIANT: <fact>` on the lines before the loop, and the
+/// facts that it should not infer, written as `// NOT INVARIANT: <fact>`.
+/// Returns the line of the loop, whether the fact is expected, and the fact.
+fn collect_annotations(source: &str) -> Vec<(usize, bool, String)> {
+    let mut annotations = vec![];
+    let mut pending = vec![];
+    for (index, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if let Some(fact) = line.strip_prefix("// INVARIANT:") {
+            pending.push((true, fact.trim().to_string()));
+        } else if let Some(fact) = line.strip_prefix("// NOT INVARIANT:") {
+            pending.push((false, fact.trim().to_string()));
+        } else {
+            annotations.extend(
+                pending
+                    .drain(..)
+                    .map(|(is_expected, fact)| (index + 1, is_expected, fact)),
+            );
+        }
+    }
+    annotations
+}
+
+/// Run the interval analysis on `program`, returning the facts that it infers
+/// at the head of each loop as `(line, fact)` pairs.
+fn infer_loop_invariants(program: &Path) -> HashSet<(usize, String)> {
+    let compiler_sysroot = PathBuf::from(find_sysroot());
+    let mut cmd = Command::new(find_compiled_executable("analysis-driver"));
+    cmd.arg("--edition=2018")
+        .arg("--sysroot")
+        .arg(&compiler_sysroot)
+        .arg("--analysis=IntervalLoopInvariants")
+        .arg(program);
+    add_to_loader_path(
+        vec![compiler_sysroot.join("lib"), compiler_sysroot.join("bin")],
+        &mut cmd,
+    );
+    println!("Running {:?}", cmd);
+    let output = cmd
+        .env("RUST_BACKTRACE", "1")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .unwrap_or_else(|err| panic!("Failed to execute process: {:?}", err));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        println!("Test case {:?} unexpectedly failed.", program);
+        println!("Exit status: {:?}", output.status);
+        println!("┌─── Begin of stdout ───┐");
+        println!("{}", stdout);
+        println!("└──── End of stdout ────┘");
+        println!("┌─── Begin of stderr ───┐");
+        println!("{}", String::from_utf8_lossy(&output.stderr));
+        println!("└──── End of stderr ────┘");
+        panic!("Test case unexpectedly failed. See the output for details.");
+    }
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (line_number, fact) = line.strip_prefix("line ")?.split_once(": ")?;
+            Some((line_number.parse().ok()?, fact.to_string()))
+        })
+        .collect()
+}
+
+#[test]
+fn test_interval_loop_invariants() {
+    let programs: Vec<_> = glob("tests/test_cases/intervals/*.rs")
+        .unwrap()
+        .map(|result| result.unwrap())
+        .collect();
+    assert!(!programs.is_empty());
+    for program in programs {
+        let annotations = collect_annotations(&fs::read_to_string(&program).unwrap());
+        assert!(!annotations.is_empty(), "{:?} has no annotations", program);
+        let facts = infer_loop_invariants(&program);
+        for (line, is_expected, fact) in annotations {
+            assert_eq!(
+                facts.contains(&(line, fact.clone())),
+                is_expected,
+                "{:?}, line {}: the fact {:?} was {}inferred. Inferred facts: {:?}",
+                program,
+                line,
+                fact,
+                if is_expected { "not " } else { "" },
+                facts
+            );
+        }
+    }
+}
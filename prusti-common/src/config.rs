@@ -10,19 +10,24 @@ pub mod commandline;
 use self::commandline::CommandLine;
 use config_crate::{Config, Environment, File};
 use serde::Deserialize;
-use std::{collections::HashSet, env, path::PathBuf, sync::RwLock};
+use std::{collections::{BTreeMap, HashSet}, env, path::PathBuf, sync::RwLock};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Optimizations {
     pub inline_constant_functions: bool,
     pub delete_unused_predicates: bool,
+    pub delete_unused_functions: bool,
     pub optimize_folding: bool,
+    pub remove_redundant_fold_unfold: bool,
     pub remove_empty_if: bool,
     pub purify_vars: bool,
     pub fix_quantifiers: bool,
+    pub simplify_expressions: bool,
     pub fix_unfoldings: bool,
+    pub remove_dead_stores: bool,
     pub remove_unused_vars: bool,
     pub remove_trivial_assertions: bool,
+    pub remove_empty_blocks: bool,
     pub clean_cfg: bool,
 }
 
@@ -31,13 +36,18 @@ impl Optimizations {
         Optimizations {
             inline_constant_functions: false,
             delete_unused_predicates: false,
+            delete_unused_functions: false,
             optimize_folding: false,
+            remove_redundant_fold_unfold: false,
             remove_empty_if: false,
             purify_vars: false,
             fix_quantifiers: false,
+            simplify_expressions: false,
             fix_unfoldings: false,
+            remove_dead_stores: false,
             remove_unused_vars: false,
             remove_trivial_assertions: false,
+            remove_empty_blocks: false,
             clean_cfg: false,
         }
     }
@@ -46,14 +56,19 @@ impl Optimizations {
         Optimizations {
             inline_constant_functions: true,
             delete_unused_predicates: true,
+            delete_unused_functions: true,
             optimize_folding: true,
+            remove_redundant_fold_unfold: true,
             remove_empty_if: true,
             purify_vars: true,
             fix_quantifiers: true,
+            simplify_expressions: true,
             // Disabled because https://github.com/viperproject/prusti-dev/issues/892 has been fixed
             fix_unfoldings: false,
+            remove_dead_stores: true,
             remove_unused_vars: true,
             remove_trivial_assertions: true,
+            remove_empty_blocks: true,
             clean_cfg: true,
         }
     }
@@ -72,26 +87,50 @@ lazy_static! {
         settings.set_default("check_panics", true).unwrap();
         settings.set_default("encode_unsigned_num_constraint", false).unwrap();
         settings.set_default("encode_bitvectors", false).unwrap();
+        settings.set_default("infer_loop_invariants", false).unwrap();
+        settings.set_default("infer_purity", false).unwrap();
+        settings.set_default("infer_procedure_summaries", false).unwrap();
+        settings.set_default("auto_frame_conditions", false).unwrap();
+        settings.set_default("pure_function_fuel", 1).unwrap();
         settings.set_default("simplify_encoding", true).unwrap();
         settings.set_default("log_dir", "log").unwrap();
         settings.set_default("cache_path", "").unwrap();
         settings.set_default("dump_debug_info", false).unwrap();
         settings.set_default("dump_debug_info_during_fold", false).unwrap();
+        settings.set_default("dump_foldunfold_state", false).unwrap();
         settings.set_default("ignore_regions", false).unwrap();
         settings.set_default("max_log_file_name_length", 60).unwrap();
         settings.set_default("dump_path_ctxt_in_debug_info", false).unwrap();
         settings.set_default("dump_reborrowing_dag_in_debug_info", false).unwrap();
         settings.set_default("dump_borrowck_info", false).unwrap();
         settings.set_default("dump_viper_program", false).unwrap();
+        settings.set_default("performance_report", false).unwrap();
+        settings.set_default("coverage_report", false).unwrap();
+        settings.set_default("report_dead_specs", false).unwrap();
+        settings.set_default("report_dead_code", false).unwrap();
+        settings.set_default("rank_verification_errors", false).unwrap();
+        settings.set_default("law_report", false).unwrap();
+        settings.set_default::<Vec<String>>("assumed_verified_modules", vec![]).unwrap();
+        settings.set_default("verify_entry_points", true).unwrap();
+        settings.set_default("enable_generators", false).unwrap();
+        settings.set_default("functional_correctness", true).unwrap();
         settings.set_default("foldunfold_state_filter", "").unwrap();
+        settings.set_default("foldunfold_fold_at_join", false).unwrap();
+        settings.set_default("foldunfold_infer_join_permissions", false).unwrap();
+        settings.set_default("foldunfold_read_unfolded_discriminants", false).unwrap();
         settings.set_default("contracts_lib", "").unwrap();
         settings.set_default::<Vec<String>>("extra_jvm_args", vec![]).unwrap();
         settings.set_default::<Vec<String>>("extra_verifier_args", vec![]).unwrap();
         settings.set_default("quiet", false).unwrap();
         settings.set_default("assert_timeout", 10_000).unwrap();
         settings.set_default("use_more_complete_exhale", true).unwrap();
+        settings.set_default("nonlinear_arithmetic", false).unwrap();
         settings.set_default("skip_unsupported_features", false).unwrap();
+        settings.set_default("warn_trusted_without_postcondition", false).unwrap();
+        settings.set_default("warn_missing_triggers", false).unwrap();
+        settings.set_default("unsupported_features_summary", false).unwrap();
         settings.set_default("internal_errors_as_warnings", false).unwrap();
+        settings.set_default("catch_encoder_panics", false).unwrap();
         settings.set_default("allow_unreachable_unsupported_code", false).unwrap();
         settings.set_default("no_verify", false).unwrap();
         settings.set_default("no_verify_deps", false).unwrap();
@@ -100,6 +139,7 @@ lazy_static! {
         settings.set_default("optimizations","all").unwrap();
         settings.set_default("intern_names", true).unwrap();
         settings.set_default("enable_purification_optimization", false).unwrap();
+        settings.set_default("enable_struct_purification", false).unwrap();
         settings.set_default("enable_manual_axiomatization", false).unwrap();
         settings.set_default::<Option<i64>>("verification_deadline", None).unwrap();
         settings.set_default("unsafe_core_proof", false).unwrap();
@@ -107,11 +147,13 @@ lazy_static! {
 
         settings.set_default("print_desugared_specs", false).unwrap();
         settings.set_default("print_typeckd_specs", false).unwrap();
+        settings.set_default("print_viper_program", false).unwrap();
         settings.set_default("print_collected_verification_items", false).unwrap();
         settings.set_default("hide_uuids", false).unwrap();
         settings.set_default("counterexample", false).unwrap();
         settings.set_default("print_hash", false).unwrap();
         settings.set_default("enable_cache", true).unwrap();
+        settings.set_default("batch_viper_ast_construction", false).unwrap();
 
         // Flags for debugging Prusti that can change verification results.
         settings.set_default("disable_name_mangling", false).unwrap();
@@ -125,6 +167,11 @@ lazy_static! {
         allowed_keys.insert("server_max_stored_verifiers".to_string());
         allowed_keys.insert("server_max_concurrency".to_string());
         allowed_keys.insert("server_address".to_string());
+        allowed_keys.insert("viper_server_url".to_string());
+        allowed_keys.insert("verification_manifest".to_string());
+        allowed_keys.insert("verified_by".to_string());
+        allowed_keys.insert("verification_artifact".to_string());
+        allowed_keys.insert("artifact_signing_key".to_string());
         allowed_keys.insert("config".to_string());
         allowed_keys.insert("log".to_string());
         allowed_keys.insert("log_style".to_string());
@@ -194,6 +241,20 @@ pub fn dump() -> String {
     format!("{:#?}", SETTINGS.read().unwrap())
 }
 
+/// The values of all the settings, sorted by name.
+pub fn all_settings() -> BTreeMap<String, String> {
+    SETTINGS
+        .read()
+        .unwrap()
+        .cache
+        .clone()
+        .into_table()
+        .unwrap()
+        .into_iter()
+        .map(|(key, value)| (key, value.to_string()))
+        .collect()
+}
+
 fn read_optional_setting<T>(name: &'static str) -> Option<T>
 where
     T: Deserialize<'static>,
@@ -226,9 +287,43 @@ pub fn viper_backend() -> String {
         .to_string()
 }
 
+/// The value of `check_panics`, which is `true`, `false` or `strict`, or an
+/// error message if it is none of them.
+fn read_check_panics_mode() -> Result<String, String> {
+    let mode = read_setting::<String>("check_panics").to_lowercase();
+    match mode.trim() {
+        "true" | "false" | "strict" => Ok(mode.trim().to_string()),
+        _ => Err(format!(
+            "invalid value {:?} of the configuration flag \"check_panics\", expected true, \
+            false or strict",
+            mode
+        )),
+    }
+}
+
+/// The value of `check_panics`. An invalid value is reported by
+/// `check_settings` and behaves like the default `true`.
+fn check_panics_mode() -> String {
+    read_check_panics_mode().unwrap_or_else(|_| "true".to_string())
+}
+
+/// Check the values of the configuration flags that cannot be checked by
+/// their type, returning an error message for the first invalid one.
+pub fn check_settings() -> Result<(), String> {
+    read_check_panics_mode()?;
+    Ok(())
+}
+
 /// Should we check absence of panics?
 pub fn check_panics() -> bool {
-    read_setting("check_panics")
+    check_panics_mode() != "false"
+}
+
+/// Should we check that every potential panic site is unreachable, regardless
+/// of the other flags, and report which functions are panic-free? Enabled by
+/// `check_panics=strict`.
+pub fn strict_panic_freedom() -> bool {
+    check_panics_mode() == "strict"
 }
 
 /// Should we simplify the encoding before passing it to Viper?
@@ -246,6 +341,12 @@ pub fn dump_debug_info_during_fold() -> bool {
     read_setting("dump_debug_info_during_fold")
 }
 
+/// Should we dump, for each statement, the fold-unfold state, the required
+/// permissions and the chosen fold/unfold actions?
+pub fn dump_foldunfold_state() -> bool {
+    read_setting("dump_foldunfold_state")
+}
+
 /// Should the dumped debug files not contain lifetime regions?
 pub fn ignore_regions() -> bool {
     read_setting("ignore_regions")
@@ -277,11 +378,123 @@ pub fn dump_viper_program() -> bool {
     read_setting("dump_viper_program")
 }
 
+/// When enabled, Prusti writes `prusti-report.json` and `prusti-report.html` to the
+/// log directory, with the time spent encoding and verifying each function.
+pub fn performance_report() -> bool {
+    read_setting("performance_report")
+}
+
+/// When enabled, Prusti prints which functions of each module were verified,
+/// trusted, skipped as unsupported or have no specification, and writes the
+/// same information to `prusti-coverage.json` in the log directory.
+pub fn coverage_report() -> bool {
+    read_setting("coverage_report")
+}
+
+/// When enabled, Prusti warns about the contracts of trusted functions that
+/// are never assumed at a call site of the verified code.
+pub fn report_dead_specs() -> bool {
+    read_setting("report_dead_specs")
+}
+
+/// When enabled, Prusti checks whether each branch of an `if` or `match` of
+/// the verified functions is reachable, and warns about the branches that are
+/// proven unreachable, e.g. because of the precondition.
+pub fn report_dead_code() -> bool {
+    read_setting("report_dead_code")
+}
+
+/// When enabled, Prusti classifies each verification error as a definite
+/// violation (the verifier found a counterexample), an unknown result (the
+/// verifier is incomplete) or a resource limit (e.g. a timeout), and reports
+/// the errors in this order.
+pub fn rank_verification_errors() -> bool {
+    read_setting("rank_verification_errors")
+}
+
+/// When enabled, Prusti prints for each implementation of a trait with
+/// `#[law]` methods whether each law holds for the implementation.
+pub fn law_report() -> bool {
+    read_setting("law_report")
+}
+
+/// The modules, relative to the crate root, whose functions are assumed to be
+/// verified and are therefore not verified, e.g. `["parser", "net::tcp"]`.
+/// Their nested modules are assumed to be verified too.
+pub fn assumed_verified_modules() -> Vec<String> {
+    read_setting("assumed_verified_modules")
+}
+
+/// When disabled, the postconditions and pledges of the verified functions
+/// are neither checked nor assumed at their calls, so only the absence of
+/// panics and the preconditions of the called functions are verified.
+pub fn functional_correctness() -> bool {
+    read_setting("functional_correctness")
+}
+
+/// When enabled, the bodies of generators are verified as state machines,
+/// whose yield invariants are checked at every `yield`. This is an
+/// experimental feature for the unstable generators of Rust.
+pub fn enable_generators() -> bool {
+    read_setting("enable_generators")
+}
+
+/// When enabled, the entry point of the crate, i.e. the `main` function of a
+/// binary, is verified like any other function.
+pub fn verify_entry_points() -> bool {
+    read_setting("verify_entry_points")
+}
+
+/// When set, Prusti records in this JSON file the modules whose functions all
+/// verified successfully, with who verified them and when, and warns about
+/// the assumed verified modules that are not recorded in it.
+pub fn verification_manifest() -> Option<String> {
+    read_optional_setting("verification_manifest")
+}
+
+/// The name recorded in the verification manifest as the person who verified
+/// the modules. Defaults to the value of the `USER` environment variable.
+pub fn verified_by() -> Option<String> {
+    read_optional_setting("verified_by")
+}
+
+/// When set, Prusti writes to this file a verification artifact signed with
+/// the `ARTIFACT_SIGNING_KEY` after a successful verification, which can be
+/// checked with `prusti-verify-artifact`.
+pub fn verification_artifact() -> Option<String> {
+    read_optional_setting("verification_artifact")
+}
+
+/// The path of the Ed25519 private key, in PKCS#8 format, that signs the
+/// verification artifact.
+pub fn artifact_signing_key() -> Option<String> {
+    read_optional_setting("artifact_signing_key")
+}
+
 /// The Viper backend that should be used for the verification
 pub fn foldunfold_state_filter() -> String {
     read_setting("foldunfold_state_filter")
 }
 
+/// When joining two branches in which a place is folded in one and unfolded in
+/// the other, fold the place in the second branch instead of unfolding it in
+/// the first one.
+pub fn foldunfold_fold_at_join() -> bool {
+    read_setting("foldunfold_fold_at_join")
+}
+
+/// Compute with a backward analysis the predicates that are required after
+/// each join point, and obtain them at the end of the joined branches.
+pub fn foldunfold_infer_join_permissions() -> bool {
+    read_setting("foldunfold_infer_join_permissions")
+}
+
+/// Read the discriminant of an enum whose predicate is unfolded from its
+/// discriminant field, instead of folding the predicate back.
+pub fn foldunfold_read_unfolded_discriminants() -> bool {
+    read_setting("foldunfold_read_unfolded_discriminants")
+}
+
 /// In which folder should we store log/dumps?
 pub fn log_dir() -> PathBuf {
     PathBuf::from(read_setting::<String>("log_dir"))
@@ -294,7 +507,7 @@ pub fn cache_path() -> PathBuf {
 
 /// Check binary operations for overflows
 pub fn check_overflows() -> bool {
-    read_setting("check_overflows")
+    read_setting("check_overflows") || strict_panic_freedom()
 }
 
 /// Encode (and check) that unsigned integers are non-negative.
@@ -302,6 +515,41 @@ pub fn encode_unsigned_num_constraint() -> bool {
     read_setting("encode_unsigned_num_constraint")
 }
 
+/// When enabled, simple numeric loop invariants (bounds of integer variables and
+/// differences between them) are inferred by an interval analysis of the MIR and
+/// added to the user-provided loop invariants.
+pub fn infer_loop_invariants() -> bool {
+    read_setting("infer_loop_invariants")
+}
+
+/// When enabled, functions without a Prusti specification whose body has no side
+/// effects and whose signature is supported by pure functions are treated as
+/// `#[pure]`.
+pub fn infer_purity() -> bool {
+    read_setting("infer_purity")
+}
+
+/// When enabled, the postcondition of private functions without a contract is
+/// extended with a summary inferred from their MIR.
+pub fn infer_procedure_summaries() -> bool {
+    read_setting("infer_procedure_summaries")
+}
+
+/// When enabled, the postcondition of methods taking `&mut self` states that
+/// the fields of primitive type that their MIR never modifies keep their
+/// value, unless the method is annotated with `#[no_auto_frame]`.
+pub fn auto_frame_conditions() -> bool {
+    read_setting("auto_frame_conditions")
+}
+
+/// How many times the definition of a recursive pure function is unfolded
+/// when one of its calls is encountered, unless overridden with
+/// `#[pure(fuel = ...)]`. With `0`, only the postconditions of the function
+/// are known at its calls.
+pub fn pure_function_fuel() -> u64 {
+    read_setting("pure_function_fuel")
+}
+
 /// Enable (highly hacky) support for bitvectors.
 pub fn encode_bitvectors() -> bool {
     read_setting("encode_bitvectors")
@@ -337,6 +585,11 @@ pub fn use_more_complete_exhale() -> bool {
     read_setting("use_more_complete_exhale")
 }
 
+/// Enable the nonlinear arithmetic reasoning of Z3 in Silicon.
+pub fn nonlinear_arithmetic() -> bool {
+    read_setting("nonlinear_arithmetic")
+}
+
 /// Should Prusti print the items collected for verification.
 pub fn print_collected_verification_items() -> bool {
     read_setting("print_collected_verification_items")
@@ -352,6 +605,11 @@ pub fn print_typeckd_specs() -> bool {
     read_setting("print_typeckd_specs")
 }
 
+/// Should Prusti print the Viper programs sent to the verifier.
+pub fn print_viper_program() -> bool {
+    read_setting("print_viper_program")
+}
+
 /// Should Prusti hide the UUIDs of expressions and specifications.
 pub fn hide_uuids() -> bool {
     read_setting("hide_uuids")
@@ -372,6 +630,12 @@ pub fn enable_cache() -> bool {
     read_setting("enable_cache")
 }
 
+/// When enabled, Prusti sends the Viper program to the JVM as source text and
+/// builds the AST with the Viper parser, instead of creating it node by node.
+pub fn batch_viper_ast_construction() -> bool {
+    read_setting("batch_viper_ast_construction")
+}
+
 /**
 The maximum amount of instantiated viper verifiers the server will keep around for reuse.
 If not set, this defaults to `SERVER_MAX_CONCURRENT_VERIFICATION_OPERATIONS`.
@@ -403,6 +667,13 @@ pub fn json_communication() -> bool {
     read_setting("json_communication")
 }
 
+/// When set, Prusti will emit the Viper programs as text and verify them with
+/// the ViperServer at this URL, without starting a JVM.
+/// e.g. "http://127.0.0.1:4000"
+pub fn viper_server_url() -> Option<String> {
+    read_optional_setting("viper_server_url")
+}
+
 /// Disable mangling of generated Viper names.
 ///
 /// **Note:** This is very likely to result in invalid programs being
@@ -447,13 +718,18 @@ pub fn optimizations() -> Optimizations {
             "all" => opt = Optimizations::all_enabled(),
             "inline_constant_functions" => opt.inline_constant_functions = true,
             "delete_unused_predicates" => opt.delete_unused_predicates = true,
+            "delete_unused_functions" => opt.delete_unused_functions = true,
             "optimize_folding" => opt.optimize_folding = true,
+            "remove_redundant_fold_unfold" => opt.remove_redundant_fold_unfold = true,
             "remove_empty_if" => opt.remove_empty_if = true,
             "purify_vars" => opt.purify_vars = true,
             "fix_quantifiers" => opt.fix_quantifiers = true,
+            "simplify_expressions" => opt.simplify_expressions = true,
             "fix_unfoldings" => opt.fix_unfoldings = true,
+            "remove_dead_stores" => opt.remove_dead_stores = true,
             "remove_unused_vars" => opt.remove_unused_vars = true,
             "remove_trivial_assertions" => opt.remove_trivial_assertions = true,
+            "remove_empty_blocks" => opt.remove_empty_blocks = true,
             "clean_cfg" => opt.clean_cfg = true,
             _ => warn!("Ignoring Unkown optimization '{}'", trimmed),
         }
@@ -467,6 +743,13 @@ pub fn enable_purification_optimization() -> bool {
     read_setting("enable_purification_optimization")
 }
 
+/// Enable the purification of local structs whose fields are integers or
+/// booleans. This only has an effect if the purification optimization is
+/// enabled.
+pub fn enable_struct_purification() -> bool {
+    read_setting("enable_struct_purification")
+}
+
 /// Enable manual axiomatization of pure functions.
 ///
 /// **Note:** this is currently very incomplete and may introduce unsoudnesses.
@@ -509,11 +792,38 @@ pub fn skip_unsupported_features() -> bool {
     read_setting("skip_unsupported_features")
 }
 
+/// Print a summary of the unsupported features used in the crate, grouped by
+/// category, at the end of the verification
+pub fn unsupported_features_summary() -> bool {
+    read_setting("unsupported_features_summary")
+}
+
+/// Warn about trusted functions that return a value or take a mutable
+/// reference, but have no postcondition
+pub fn warn_trusted_without_postcondition() -> bool {
+    read_setting("warn_trusted_without_postcondition")
+}
+
+/// Warn about quantifiers without explicit triggers for which the verifier
+/// cannot infer triggers, or only triggers with arithmetic terms
+pub fn warn_missing_triggers() -> bool {
+    read_setting("warn_missing_triggers")
+}
+
 /// Report internal errors as warnings instead of errors. Used for testing.
 pub fn internal_errors_as_warnings() -> bool {
     read_setting("internal_errors_as_warnings")
 }
 
+/// Report a panic while encoding a function as an internal error of that
+/// function and continue with the verification of the other functions.
+///
+/// **Note:** the panic may leave the caches of the encoder in an inconsistent
+/// state, so this is only intended for triaging crashes.
+pub fn catch_encoder_panics() -> bool {
+    read_setting("catch_encoder_panics")
+}
+
 /// Encode unsupported code as `assert false`, so that we report error messages
 /// only for unsupported code that is actually reachable.
 pub fn allow_unreachable_unsupported_code() -> bool {
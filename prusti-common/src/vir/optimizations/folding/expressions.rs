@@ -442,7 +442,8 @@ impl ast::FallibleExprFolder for ExprOptimizer {
     }
 
     fn fallible_fold_let_expr(&mut self, _let_expr: ast::LetExpr) -> Result<ast::Expr, ()> {
-        unreachable!();
+        // Unfoldings cannot be pulled out of the scope of the variable.
+        Err(())
     }
 
     fn fallible_fold_bin_op(
@@ -0,0 +1,205 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::vir::polymorphic_vir::{
+    ast::*, cfg::CfgMethod, compute_identifier, utils::walk_methods, WithIdentifier,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Delete the functions that are not reachable from the methods, the
+/// predicates, or the domain axioms. The definition collector includes every
+/// function mentioned in the encoding, but the optimizations (e.g. inlining,
+/// purification, removing dead stores) may remove the last calls to some of
+/// them.
+pub fn delete_unused_functions(
+    methods: &[CfgMethod],
+    predicates: &[Predicate],
+    domains: &[Domain],
+    mut functions: Vec<Function>,
+) -> Vec<Function> {
+    let mut collector = UsedFunctionCollector::default();
+    walk_methods(methods, &mut collector);
+    for predicate in predicates {
+        if let Some(body) = predicate.body() {
+            ExprWalker::walk(&mut collector, &body);
+        }
+    }
+    for axiom in domains.iter().flat_map(|domain| &domain.axioms) {
+        ExprWalker::walk(&mut collector, &axiom.expr);
+    }
+
+    let functions_map: HashMap<String, &Function> = functions
+        .iter()
+        .map(|function| (function.get_identifier(), function))
+        .collect();
+    let mut reachable_functions = HashSet::new();
+    let mut to_visit: Vec<String> = collector.used_functions.drain().collect();
+    while let Some(identifier) = to_visit.pop() {
+        if !reachable_functions.insert(identifier.clone()) {
+            continue;
+        }
+        if let Some(function) = functions_map.get(&identifier) {
+            for expr in function
+                .pres
+                .iter()
+                .chain(&function.posts)
+                .chain(&function.body)
+            {
+                ExprWalker::walk(&mut collector, expr);
+            }
+            to_visit.extend(collector.used_functions.drain());
+        }
+    }
+
+    functions.retain(|function| {
+        let identifier = function.get_identifier();
+        if reachable_functions.contains(&identifier) {
+            true
+        } else {
+            debug!("Removed unused function {}", identifier);
+            false
+        }
+    });
+    functions
+}
+
+/// Delete the builtin methods that are never called. This has to be done after
+/// the optimizations, because the purification introduces calls to the havoc
+/// methods.
+pub fn delete_unused_builtin_methods(
+    methods: &[CfgMethod],
+    mut builtin_methods: Vec<BodylessMethod>,
+) -> Vec<BodylessMethod> {
+    let mut called_methods = HashSet::new();
+    for method in methods {
+        method.walk_statements(|stmt| {
+            if let Stmt::MethodCall(MethodCall { method_name, .. }) = stmt {
+                called_methods.insert(method_name.clone());
+            }
+        });
+    }
+    builtin_methods.retain(|method| called_methods.contains(&method.name));
+    builtin_methods
+}
+
+#[derive(Default)]
+struct UsedFunctionCollector {
+    used_functions: HashSet<String>,
+}
+
+impl ExprWalker for UsedFunctionCollector {
+    fn walk_func_app(
+        &mut self,
+        FuncApp {
+            function_name,
+            type_arguments,
+            arguments,
+            formal_arguments,
+            return_type,
+            ..
+        }: &FuncApp,
+    ) {
+        self.used_functions.insert(compute_identifier(
+            function_name,
+            type_arguments,
+            formal_arguments,
+            return_type,
+        ));
+        for arg in arguments {
+            ExprWalker::walk(self, arg);
+        }
+    }
+}
+
+impl StmtWalker for UsedFunctionCollector {
+    fn walk_expr(&mut self, expr: &Expr) {
+        ExprWalker::walk(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vir::polymorphic_vir::cfg::Successor;
+
+    fn call(name: &str) -> Expr {
+        Expr::func_app(
+            name.to_string(),
+            vec![],
+            vec![],
+            vec![],
+            Type::Bool,
+            Position::default(),
+        )
+    }
+
+    fn function(name: &str, body: Option<Expr>) -> Function {
+        Function {
+            name: name.to_string(),
+            type_arguments: vec![],
+            formal_args: vec![],
+            return_type: Type::Bool,
+            pres: vec![],
+            posts: vec![],
+            body,
+        }
+    }
+
+    fn assert_call(name: &str) -> Stmt {
+        Stmt::Assert(Assert {
+            expr: call(name),
+            position: Position::default(),
+        })
+    }
+
+    /// The functions called from a method, directly or through other
+    /// functions, are kept, as well as the functions called from a predicate.
+    #[test]
+    fn keeps_reachable_functions() {
+        let mut method = CfgMethod::new("m".to_string(), 0, vec![], vec![], vec![]);
+        let start = method.add_block("start", vec![assert_call("caller")]);
+        method.set_successor(start, Successor::Return);
+        let typ = Type::typed_ref("P");
+        let predicate = Predicate::Struct(StructPredicate {
+            this: Predicate::construct_this(typ.clone()),
+            typ,
+            extra_params: vec![],
+            body: Some(call("invariant")),
+        });
+        let functions = vec![
+            function("caller", Some(call("callee"))),
+            function("callee", None),
+            function("invariant", None),
+            function("unused", Some(call("callee"))),
+        ];
+        let functions = delete_unused_functions(&[method], &[predicate], &[], functions);
+        let names: Vec<_> = functions.iter().map(|function| &function.name).collect();
+        assert_eq!(names, vec!["caller", "callee", "invariant"]);
+    }
+
+    #[test]
+    fn deletes_uncalled_builtin_methods() {
+        let mut method = CfgMethod::new("m".to_string(), 0, vec![], vec![], vec![]);
+        let start = method.add_block(
+            "start",
+            vec![Stmt::MethodCall(MethodCall {
+                method_name: "havoc_int".to_string(),
+                arguments: vec![],
+                targets: vec![],
+            })],
+        );
+        method.set_successor(start, Successor::Return);
+        let builtin_method = |name: &str| BodylessMethod {
+            name: name.to_string(),
+            formal_args: vec![],
+            formal_returns: vec![],
+        };
+        let builtin_methods = vec![builtin_method("havoc_bool"), builtin_method("havoc_int")];
+        let builtin_methods = delete_unused_builtin_methods(&[method], builtin_methods);
+        assert_eq!(builtin_methods.len(), 1);
+        assert_eq!(builtin_methods[0].name, "havoc_int");
+    }
+}
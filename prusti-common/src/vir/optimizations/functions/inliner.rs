@@ -28,12 +28,14 @@ use std::{collections::HashMap, mem};
 /// }
 /// ```
 ///
-/// And then inline them on call sites.
+/// And then inline them on call sites. The functions selected by
+/// `is_never_inlined` (i.e. marked with `#[never_inline_spec]`) are kept.
 ///
 /// The optimization is performed until a fix-point.
 pub fn inline_constant_functions(
     mut methods: Vec<cfg::CfgMethod>,
     mut functions: Vec<ast::Function>,
+    is_never_inlined: impl Fn(&str) -> bool,
 ) -> (Vec<cfg::CfgMethod>, Vec<ast::Function>) {
     trace!("[enter] purify_constant_functions");
     let mut non_pure_functions = Vec::new();
@@ -42,7 +44,9 @@ pub fn inline_constant_functions(
     while changed {
         changed = false;
         for mut function in functions.into_iter() {
-            if let Some(body) = try_purify(&mut function) {
+            if is_never_inlined(&function.name) {
+                non_pure_functions.push(function);
+            } else if let Some(body) = try_purify(&mut function) {
                 pure_function_map.insert(function.name.clone(), body);
                 changed = true;
             } else {
@@ -6,7 +6,16 @@
 
 //! A module that contains optimizations for functions.
 
+mod delete_unused_functions;
 mod inliner;
 mod simplifier;
+mod spec_inliner;
+mod unroller;
 
-pub use self::{inliner::inline_constant_functions, simplifier::Simplifier};
+pub use self::{
+    delete_unused_functions::{delete_unused_builtin_methods, delete_unused_functions},
+    inliner::inline_constant_functions,
+    simplifier::Simplifier,
+    spec_inliner::inline_spec_functions,
+    unroller::unroll_recursive_calls,
+};
@@ -39,6 +39,7 @@ struct ExprSimplifier {}
 impl ExprSimplifier {
     fn apply_rules(&self, e: ast::Expr) -> ast::Expr {
         trace!("[enter] apply_rules={}", e);
+        let e = fold_constants(&e).unwrap_or(e);
         let result = match e {
             ast::Expr::UnaryOp(ast::UnaryOp {
                 op_kind: ast::UnaryOpKind::Not,
@@ -52,6 +53,16 @@ impl ExprSimplifier {
                 value: ast::Const::Bool(!b),
                 position: pos,
             }),
+            ast::Expr::UnaryOp(ast::UnaryOp {
+                op_kind: ast::UnaryOpKind::Not,
+                argument:
+                    box ast::Expr::UnaryOp(ast::UnaryOp {
+                        op_kind: ast::UnaryOpKind::Not,
+                        box argument,
+                        ..
+                    }),
+                ..
+            }) => argument,
             ast::Expr::UnaryOp(ast::UnaryOp {
                 op_kind: ast::UnaryOpKind::Not,
                 argument:
@@ -173,6 +184,29 @@ impl ExprSimplifier {
                     true.into()
                 }
             }
+            // Flatten nested conjunctions: `(a && b) && c` becomes `a && (b && c)`
+            ast::Expr::BinOp(ast::BinOp {
+                op_kind: ast::BinaryOpKind::And,
+                left:
+                    box ast::Expr::BinOp(ast::BinOp {
+                        op_kind: ast::BinaryOpKind::And,
+                        left: box first,
+                        right: box second,
+                        position: inner_pos,
+                    }),
+                right: box third,
+                position: pos,
+            }) => self.apply_rules(ast::Expr::BinOp(ast::BinOp {
+                op_kind: ast::BinaryOpKind::And,
+                left: box first,
+                right: box ast::Expr::BinOp(ast::BinOp {
+                    op_kind: ast::BinaryOpKind::And,
+                    left: box second,
+                    right: box third,
+                    position: inner_pos,
+                }),
+                position: pos,
+            })),
             ast::Expr::BinOp(ast::BinOp {
                 op_kind: ast::BinaryOpKind::And,
                 left: box op1,
@@ -191,6 +225,63 @@ impl ExprSimplifier {
     }
 }
 
+/// Evaluate unary and binary operations whose operands are constants.
+fn fold_constants(e: &ast::Expr) -> Option<ast::Expr> {
+    let (value, position) = match e {
+        ast::Expr::UnaryOp(ast::UnaryOp {
+            op_kind: ast::UnaryOpKind::Minus,
+            argument:
+                box ast::Expr::Const(ast::ConstExpr {
+                    value: ast::Const::Int(n),
+                    ..
+                }),
+            position,
+        }) => (ast::Const::Int(n.checked_neg()?), *position),
+        ast::Expr::BinOp(ast::BinOp {
+            op_kind,
+            left: box ast::Expr::Const(ast::ConstExpr { value: left, .. }),
+            right: box ast::Expr::Const(ast::ConstExpr { value: right, .. }),
+            position,
+        }) => (fold_binary_constants(*op_kind, left, right)?, *position),
+        _ => return None,
+    };
+    Some(ast::Expr::Const(ast::ConstExpr { value, position }))
+}
+
+fn fold_binary_constants(
+    op_kind: ast::BinaryOpKind,
+    left: &ast::Const,
+    right: &ast::Const,
+) -> Option<ast::Const> {
+    use ast::{BinaryOpKind::*, Const};
+    let value = match (left, right) {
+        (&Const::Int(l), &Const::Int(r)) => match op_kind {
+            // Do not fold operations that overflow, to not lose precision.
+            Add => Const::Int(l.checked_add(r)?),
+            Sub => Const::Int(l.checked_sub(r)?),
+            Mul => Const::Int(l.checked_mul(r)?),
+            // Viper and Rust round differently on negative operands.
+            Div if l >= 0 && r > 0 => Const::Int(l / r),
+            Mod if l >= 0 && r > 0 => Const::Int(l % r),
+            EqCmp => Const::Bool(l == r),
+            NeCmp => Const::Bool(l != r),
+            GtCmp => Const::Bool(l > r),
+            GeCmp => Const::Bool(l >= r),
+            LtCmp => Const::Bool(l < r),
+            LeCmp => Const::Bool(l <= r),
+            _ => return None,
+        },
+        // Conjunctions, disjunctions and implications are handled by `apply_rules`.
+        (&Const::Bool(l), &Const::Bool(r)) => match op_kind {
+            EqCmp => Const::Bool(l == r),
+            NeCmp => Const::Bool(l != r),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(value)
+}
+
 impl ExprFolder for ExprSimplifier {
     fn fold(&mut self, e: ast::Expr) -> ast::Expr {
         let folded_expr = ast::default_fold_expr(self, e);
@@ -240,3 +331,91 @@ impl ExprFolder for ExprSimplifier {
         self.apply_rules(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vir::polymorphic_vir::{
+        generator::Generator,
+        interpreter::{Interpreter, Value},
+    };
+
+    fn inputs(path: &str, typ: &ast::Type) -> Value {
+        match typ {
+            ast::Type::Bool => Value::Bool(path.len() % 2 == 0),
+            _ => Value::Int(path.len() as i128 - 2),
+        }
+    }
+
+    /// Constants are folded, double negations removed and nested conjunctions
+    /// flattened, but divisions of negative constants are kept.
+    #[test]
+    fn simplification_rules() {
+        let b = ast::Expr::local(ast::LocalVar::new("b", ast::Type::Bool));
+        let c = ast::Expr::local(ast::LocalVar::new("c", ast::Type::Bool));
+        let d = ast::Expr::local(ast::LocalVar::new("d", ast::Type::Bool));
+        let i = ast::Expr::local(ast::LocalVar::new("i", ast::Type::Int));
+        let cases = vec![
+            (
+                ast::Expr::add(ast::Expr::mul(2.into(), 3.into()), 1.into()),
+                7.into(),
+            ),
+            (
+                ast::Expr::eq_cmp(ast::Expr::add(2.into(), 3.into()), 6.into()),
+                false.into(),
+            ),
+            (ast::Expr::not(ast::Expr::not(b.clone())), b.clone()),
+            (
+                ast::Expr::and(ast::Expr::and(b.clone(), c.clone()), d.clone()),
+                ast::Expr::and(b.clone(), ast::Expr::and(c.clone(), d.clone())),
+            ),
+            (
+                ast::Expr::and(true.into(), ast::Expr::gt_cmp(i.clone(), 1.into())),
+                ast::Expr::gt_cmp(i.clone(), 1.into()),
+            ),
+            (
+                ast::Expr::div(ast::Expr::minus(7.into()), 2.into()),
+                ast::Expr::div((-7).into(), 2.into()),
+            ),
+            (
+                ast::Expr::mul(i64::MAX.into(), 2.into()),
+                ast::Expr::mul(i64::MAX.into(), 2.into()),
+            ),
+        ];
+        for (expr, expected) in cases {
+            assert_eq!(expr.clone().simplify(), expected, "{}", expr);
+        }
+    }
+
+    /// The simplification preserves the type and the purity of random
+    /// expressions, and their value whenever they can be evaluated.
+    #[test]
+    fn simplification_preserves_values() {
+        let locals = vec![
+            ast::LocalVar::new("i", ast::Type::Int),
+            ast::LocalVar::new("long_name", ast::Type::Int),
+            ast::LocalVar::new("b", ast::Type::Bool),
+        ];
+        for seed in 0..2000 {
+            let mut generator = Generator::new(seed, locals.clone());
+            let typ = if seed % 2 == 0 {
+                ast::Type::Int
+            } else {
+                ast::Type::Bool
+            };
+            let expr = generator.expr(&typ, 5);
+            let simplified = expr.clone().simplify();
+            assert_eq!(simplified.get_type(), &typ, "{} ~> {}", expr, simplified);
+            assert_eq!(simplified.is_pure(), expr.is_pure());
+            if let Ok(value) = Interpreter::new(inputs).eval(&expr) {
+                assert_eq!(
+                    Interpreter::new(inputs).eval(&simplified),
+                    Ok(value),
+                    "{} ~> {}",
+                    expr,
+                    simplified
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,299 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Inliner of pure functions marked with `#[inline_spec]`.
+
+use crate::vir::polymorphic_vir::{
+    ast::{self, ExprFolder, ExprIterator},
+    Program,
+};
+use std::{collections::HashMap, mem};
+
+/// Expand the calls of the functions selected by `is_inlined` with their
+/// body, so that the verifier does not need to instantiate the definitional
+/// axiom of the function. For example, the call `double(x + 1)` of
+///
+/// ```viper
+/// function double(x: Int): Int
+///     requires x <= 1000
+/// {
+///     2 * x
+/// }
+/// ```
+///
+/// becomes:
+///
+/// ```viper
+/// x + 1 <= 1000 ? 2 * (x + 1) : double(x + 1)
+/// ```
+///
+/// As in `unroll_recursive_calls`, a call is only replaced by the body under
+/// its precondition, so that the precondition is still checked where it does
+/// not hold. Functions without body or whose precondition contains
+/// permissions are not inlined. A function is not expanded in its own body,
+/// so recursive functions are expanded only once. Calls in the triggers of
+/// quantifiers are kept, since a trigger must consist of function
+/// applications.
+#[must_use]
+pub fn inline_spec_functions(mut program: Program, is_inlined: impl Fn(&str) -> bool) -> Program {
+    let inlined_functions: HashMap<_, _> = program
+        .functions
+        .iter()
+        .filter(|function| {
+            is_inlined(&function.name)
+                && function.body.is_some()
+                && function.pres.iter().all(|pre| pre.is_pure())
+        })
+        .map(|function| (function.name.clone(), function.clone()))
+        .collect();
+    if inlined_functions.is_empty() {
+        return program;
+    }
+    let mut inliner = SpecFunctionInliner {
+        inlined_functions: &inlined_functions,
+        expanding: Vec::new(),
+        inlined_bodies: 0,
+    };
+    for function in &mut program.functions {
+        inliner.expanding.push(function.name.clone());
+        function.pres = mem::take(&mut function.pres)
+            .into_iter()
+            .map(|pre| inliner.fold(pre))
+            .collect();
+        function.posts = mem::take(&mut function.posts)
+            .into_iter()
+            .map(|post| inliner.fold(post))
+            .collect();
+        function.body = function.body.take().map(|body| inliner.fold(body));
+        inliner.expanding.pop();
+    }
+    for method in &mut program.methods {
+        let mut sentinel_stmt = ast::Stmt::comment("moved out stmt");
+        for block in &mut method.basic_blocks {
+            for stmt in &mut block.stmts {
+                mem::swap(&mut sentinel_stmt, stmt);
+                sentinel_stmt = ast::StmtFolder::fold(&mut inliner, sentinel_stmt);
+                mem::swap(&mut sentinel_stmt, stmt);
+            }
+        }
+    }
+    program
+}
+
+/// Replace the calls of the inlined functions with their body.
+struct SpecFunctionInliner<'a> {
+    inlined_functions: &'a HashMap<String, ast::Function>,
+    /// The functions whose body is currently being expanded.
+    expanding: Vec<String>,
+    /// The number of expanded bodies, used to rename their `let` variables.
+    inlined_bodies: usize,
+}
+
+impl<'a> SpecFunctionInliner<'a> {
+    /// Substitute the formal arguments of `function` with `arguments`.
+    fn instantiate(
+        &self,
+        function: &ast::Function,
+        expr: ast::Expr,
+        arguments: &[ast::Expr],
+    ) -> ast::Expr {
+        let replacements: Vec<_> = function
+            .formal_args
+            .iter()
+            .map(|formal_arg| ast::Expr::local(formal_arg.clone()))
+            .zip(arguments.iter().cloned())
+            .collect();
+        expr.replace_multiple_places(&replacements)
+    }
+}
+
+impl<'a> ast::StmtFolder for SpecFunctionInliner<'a> {
+    fn fold_expr(&mut self, expr: ast::Expr) -> ast::Expr {
+        ast::ExprFolder::fold(self, expr)
+    }
+}
+
+impl<'a> ast::ExprFolder for SpecFunctionInliner<'a> {
+    fn fold_forall(&mut self, forall: ast::ForAll) -> ast::Expr {
+        ast::Expr::ForAll(ast::ForAll {
+            body: self.fold_boxed(forall.body),
+            ..forall
+        })
+    }
+
+    fn fold_exists(&mut self, exists: ast::Exists) -> ast::Expr {
+        ast::Expr::Exists(ast::Exists {
+            body: self.fold_boxed(exists.body),
+            ..exists
+        })
+    }
+
+    fn fold_func_app(&mut self, func_app: ast::FuncApp) -> ast::Expr {
+        let ast::FuncApp {
+            function_name,
+            type_arguments,
+            arguments,
+            formal_arguments,
+            return_type,
+            position,
+        } = func_app;
+        let arguments: Vec<_> = arguments.into_iter().map(|e| self.fold(e)).collect();
+        let call = ast::FuncApp {
+            function_name,
+            type_arguments,
+            arguments,
+            formal_arguments,
+            return_type,
+            position,
+        };
+        let function = match self.inlined_functions.get(&call.function_name) {
+            Some(function)
+                if function.type_arguments == call.type_arguments
+                    && !self.expanding.contains(&call.function_name) =>
+            {
+                function
+            }
+            _ => return ast::Expr::FuncApp(call),
+        };
+        let precondition = function
+            .pres
+            .iter()
+            .cloned()
+            .map(|pre| self.instantiate(function, pre, &call.arguments))
+            .conjoin();
+        // The `let` variables of the body are renamed before the arguments
+        // are substituted, so that they neither shadow the variables of an
+        // enclosing expansion nor capture the ones of the arguments.
+        let body = LetVariableRenamer {
+            suffix: self.inlined_bodies,
+        }
+        .fold(function.body.clone().unwrap());
+        self.inlined_bodies += 1;
+        let body = self.instantiate(function, body, &call.arguments);
+        self.expanding.push(call.function_name.clone());
+        let body = self.fold(body);
+        self.expanding.pop();
+        let position = call.position;
+        if function.pres.is_empty() {
+            body.set_pos(position)
+        } else {
+            ast::Expr::ite(precondition, body, ast::Expr::FuncApp(call)).set_pos(position)
+        }
+    }
+}
+
+/// Rename the variables bound by `let` expressions by appending a suffix.
+struct LetVariableRenamer {
+    suffix: usize,
+}
+
+impl ast::ExprFolder for LetVariableRenamer {
+    fn fold_let_expr(&mut self, let_expr: ast::LetExpr) -> ast::Expr {
+        let ast::LetExpr {
+            variable,
+            def,
+            body,
+            position,
+        } = let_expr;
+        let renamed = ast::LocalVar::new(
+            format!("{}${}", variable.name, self.suffix),
+            variable.typ.clone(),
+        );
+        let body = (*body).replace_place(
+            &ast::Expr::local(variable),
+            &ast::Expr::local(renamed.clone()),
+        );
+        ast::Expr::LetExpr(ast::LetExpr {
+            variable: renamed,
+            def: self.fold_boxed(def),
+            body: Box::new(self.fold(body)),
+            position,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn double(arg: ast::Expr) -> ast::Expr {
+        ast::Expr::func_app(
+            "double".to_string(),
+            vec![],
+            vec![arg],
+            vec![ast::LocalVar::new("x", ast::Type::Int)],
+            ast::Type::Int,
+            ast::Position::default(),
+        )
+    }
+
+    /// Collects the names of the applied functions.
+    struct FunctionNames(Vec<String>);
+
+    impl ast::ExprWalker for FunctionNames {
+        fn walk_func_app(&mut self, func_app: &ast::FuncApp) {
+            self.0.push(func_app.function_name.clone());
+            for arg in &func_app.arguments {
+                ast::ExprWalker::walk(self, arg);
+            }
+        }
+    }
+
+    /// The calls in the body of a quantifier are inlined, but not the ones
+    /// in its triggers.
+    #[test]
+    fn triggers_are_not_inlined() {
+        let x = ast::LocalVar::new("x", ast::Type::Int);
+        let function = ast::Function {
+            name: "double".to_string(),
+            type_arguments: vec![],
+            formal_args: vec![x.clone()],
+            return_type: ast::Type::Int,
+            pres: vec![],
+            posts: vec![],
+            body: Some(ast::Expr::mul(2.into(), ast::Expr::local(x.clone()))),
+        };
+        let quantifier = ast::Expr::forall(
+            vec![x.clone()],
+            vec![ast::Trigger::new(vec![double(ast::Expr::local(x.clone()))])],
+            ast::Expr::gt_cmp(double(ast::Expr::local(x)), 0.into()),
+        );
+        let caller = ast::Function {
+            name: "caller".to_string(),
+            type_arguments: vec![],
+            formal_args: vec![],
+            return_type: ast::Type::Bool,
+            pres: vec![],
+            posts: vec![],
+            body: Some(quantifier),
+        };
+        let program = Program {
+            name: "program".to_string(),
+            domains: vec![],
+            fields: vec![],
+            builtin_methods: vec![],
+            methods: vec![],
+            functions: vec![function, caller],
+            viper_predicates: vec![],
+        };
+        let program = inline_spec_functions(program, |name| name == "double");
+        let inlined = program.functions[1].body.as_ref().unwrap();
+        let forall = match inlined {
+            ast::Expr::ForAll(forall) => forall,
+            _ => panic!("expected a quantifier: {}", inlined),
+        };
+        let mut body_calls = FunctionNames(vec![]);
+        ast::ExprWalker::walk(&mut body_calls, &forall.body);
+        assert!(body_calls.0.is_empty(), "{}", inlined);
+        let mut trigger_calls = FunctionNames(vec![]);
+        for trigger in &forall.triggers {
+            for expr in trigger.elements() {
+                ast::ExprWalker::walk(&mut trigger_calls, expr);
+            }
+        }
+        assert_eq!(trigger_calls.0, vec!["double".to_string()], "{}", inlined);
+    }
+}
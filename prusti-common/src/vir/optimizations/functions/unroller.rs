@@ -0,0 +1,356 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Unroller of recursive pure functions.
+
+use crate::vir::polymorphic_vir::ast::{self, ExprFolder, ExprIterator, ExprWalker};
+use std::collections::{HashMap, HashSet};
+
+/// Unroll the recursive calls in the body of `function`, so that the verifier
+/// unfolds its definition `fuel` times when it encounters a call. For example,
+/// with `fuel` 2
+///
+/// ```viper
+/// function sum(n: Int): Int
+///     requires 0 <= n
+/// {
+///     n == 0 ? 0 : n + sum(n - 1)
+/// }
+/// ```
+///
+/// becomes:
+///
+/// ```viper
+/// function sum(n: Int): Int
+///     requires 0 <= n
+/// {
+///     let unrolled$0 == (0 <= n - 1 ? (n - 1 == 0 ? 0 : n - 1 + sum(n - 1 - 1)) : sum(n)) in
+///     n == 0 ? 0 : n + (0 <= n - 1 ? unrolled$0 : sum(n - 1))
+/// }
+/// ```
+///
+/// Each distinct recursive call is unfolded only once and bound to a
+/// variable that is shared by all its occurrences. Constant offsets of the
+/// arguments are merged, so that `fib(n - 1 - 1)` and `fib(n - 2)` share their
+/// unfolding and the size of the body of `fib` grows linearly with the fuel
+/// instead of exponentially.
+///
+/// A variable is only used under the precondition of its call, so that the
+/// precondition is still checked where it does not hold; otherwise its value
+/// is the irrelevant call of the function with its own arguments. Functions
+/// whose precondition contains permissions are not unrolled.
+#[must_use]
+pub fn unroll_recursive_calls(mut function: ast::Function, fuel: u64) -> ast::Function {
+    if fuel <= 1 || !function.pres.iter().all(|pre| pre.is_pure()) {
+        return function;
+    }
+    if let Some(body) = function.body.take() {
+        let mut unroller = RecursiveCallUnroller {
+            function: &function,
+            body: &body,
+            unfolded_calls: HashSet::new(),
+            variables: HashMap::new(),
+            in_progress: HashSet::new(),
+            bindings: Vec::new(),
+        };
+        unroller.collect_unfolded_calls(fuel);
+        let mut unrolled_body = unroller.fold(body.clone());
+        // The unfoldings are bound in the order in which they were completed,
+        // so that every unfolding is in the scope of the ones that it uses.
+        for (variable, definition) in unroller.bindings.into_iter().rev() {
+            unrolled_body = ast::Expr::LetExpr(ast::LetExpr {
+                variable,
+                def: Box::new(definition),
+                body: Box::new(unrolled_body),
+                position: ast::Position::default(),
+            });
+        }
+        function.body = Some(unrolled_body);
+    }
+    function
+}
+
+/// Replace the recursive calls with variables bound to the body of the
+/// function.
+struct RecursiveCallUnroller<'a> {
+    function: &'a ast::Function,
+    body: &'a ast::Expr,
+    /// The arguments of the calls that are unfolded.
+    unfolded_calls: HashSet<Vec<ast::Expr>>,
+    /// The variables of the unfoldings that are complete.
+    variables: HashMap<Vec<ast::Expr>, ast::LocalVar>,
+    /// The arguments of the calls whose unfolding is being built; using them
+    /// would make the bindings cyclic.
+    in_progress: HashSet<Vec<ast::Expr>>,
+    bindings: Vec<(ast::LocalVar, ast::Expr)>,
+}
+
+impl<'a> RecursiveCallUnroller<'a> {
+    fn is_recursive_call(&self, func_app: &ast::FuncApp) -> bool {
+        func_app.function_name == self.function.name
+            && func_app.type_arguments == self.function.type_arguments
+    }
+
+    /// Substitute the formal arguments of the function with `arguments`.
+    fn instantiate(&self, expr: ast::Expr, arguments: &[ast::Expr]) -> ast::Expr {
+        let replacements: Vec<_> = self
+            .function
+            .formal_args
+            .iter()
+            .map(|formal_arg| ast::Expr::local(formal_arg.clone()))
+            .zip(arguments.iter().cloned())
+            .collect();
+        expr.replace_multiple_places(&replacements)
+    }
+
+    /// The arguments of the recursive calls in `expr`, with merged offsets.
+    fn recursive_calls(&self, expr: &ast::Expr) -> Vec<Vec<ast::Expr>> {
+        let mut collector = RecursiveCallCollector {
+            unroller: self,
+            calls: Vec::new(),
+        };
+        collector.walk(expr);
+        collector.calls
+    }
+
+    /// Find the calls that are unfolded: the ones that are reached by
+    /// unfolding the definition fewer than `fuel` times.
+    fn collect_unfolded_calls(&mut self, fuel: u64) {
+        let mut level = self.recursive_calls(self.body);
+        for _ in 1..fuel {
+            let mut next_level = Vec::new();
+            for arguments in level {
+                if self.unfolded_calls.insert(arguments.clone()) {
+                    let body = self.instantiate(self.body.clone(), &arguments);
+                    next_level.extend(self.recursive_calls(&body));
+                }
+            }
+            level = next_level;
+        }
+    }
+
+    /// Bind the unfolding of the call with `arguments` to a variable.
+    fn unfold(&mut self, arguments: Vec<ast::Expr>) -> ast::LocalVar {
+        self.in_progress.insert(arguments.clone());
+        let precondition = self
+            .function
+            .pres
+            .iter()
+            .cloned()
+            .map(|pre| self.instantiate(pre, &arguments))
+            .conjoin();
+        let body = self.fold(self.instantiate(self.body.clone(), &arguments));
+        let own_call = ast::Expr::func_app(
+            self.function.name.clone(),
+            self.function.type_arguments.clone(),
+            self.function
+                .formal_args
+                .iter()
+                .cloned()
+                .map(ast::Expr::local)
+                .collect(),
+            self.function.formal_args.clone(),
+            self.function.return_type.clone(),
+            ast::Position::default(),
+        );
+        let variable = ast::LocalVar::new(
+            format!("unrolled${}", self.bindings.len()),
+            self.function.return_type.clone(),
+        );
+        self.bindings.push((
+            variable.clone(),
+            ast::Expr::ite(precondition, body, own_call),
+        ));
+        self.in_progress.remove(&arguments);
+        self.variables.insert(arguments, variable.clone());
+        variable
+    }
+}
+
+impl<'a> ast::ExprFolder for RecursiveCallUnroller<'a> {
+    fn fold_func_app(&mut self, func_app: ast::FuncApp) -> ast::Expr {
+        let key = if self.is_recursive_call(&func_app) {
+            Some(merge_offsets(&func_app.arguments))
+        } else {
+            None
+        };
+        let ast::FuncApp {
+            function_name,
+            type_arguments,
+            arguments,
+            formal_arguments,
+            return_type,
+            position,
+        } = func_app;
+        let arguments: Vec<_> = arguments.into_iter().map(|e| self.fold(e)).collect();
+        let call = ast::FuncApp {
+            function_name,
+            type_arguments,
+            arguments,
+            formal_arguments,
+            return_type,
+            position,
+        };
+        let key = match key {
+            Some(key) if self.unfolded_calls.contains(&key) && !self.in_progress.contains(&key) => {
+                key
+            }
+            _ => return ast::Expr::FuncApp(call),
+        };
+        let variable = match self.variables.get(&key) {
+            Some(variable) => variable.clone(),
+            None => self.unfold(key),
+        };
+        let precondition = self
+            .function
+            .pres
+            .iter()
+            .cloned()
+            .map(|pre| self.instantiate(pre, &call.arguments))
+            .conjoin();
+        ast::Expr::ite(
+            precondition,
+            ast::Expr::local(variable),
+            ast::Expr::FuncApp(call),
+        )
+        .set_pos(position)
+    }
+}
+
+/// Collect the arguments of the recursive calls in an expression.
+struct RecursiveCallCollector<'a, 'b> {
+    unroller: &'b RecursiveCallUnroller<'a>,
+    calls: Vec<Vec<ast::Expr>>,
+}
+
+impl<'a, 'b> ast::ExprWalker for RecursiveCallCollector<'a, 'b> {
+    fn walk_func_app(&mut self, func_app: &ast::FuncApp) {
+        if self.unroller.is_recursive_call(func_app) {
+            self.calls.push(merge_offsets(&func_app.arguments));
+        }
+        for argument in &func_app.arguments {
+            self.walk(argument);
+        }
+    }
+}
+
+/// Merge the constant offsets of each argument, turning `n - 1 - 1` into
+/// `n - 2`.
+fn merge_offsets(arguments: &[ast::Expr]) -> Vec<ast::Expr> {
+    arguments
+        .iter()
+        .map(|argument| {
+            let (base, offset) = split_offset(argument);
+            match offset {
+                0 => base.clone(),
+                offset if offset > 0 => ast::Expr::add(base.clone(), offset.into()),
+                offset => ast::Expr::sub(base.clone(), (-offset).into()),
+            }
+        })
+        .collect()
+}
+
+/// Split `expr` into a base and the sum of the integer constants that are
+/// added to it.
+fn split_offset(expr: &ast::Expr) -> (&ast::Expr, i64) {
+    if let ast::Expr::BinOp(ast::BinOp {
+        op_kind,
+        left,
+        right,
+        ..
+    }) = expr
+    {
+        if let ast::Expr::Const(ast::ConstExpr {
+            value: ast::Const::Int(constant),
+            ..
+        }) = **right
+        {
+            let (base, offset) = split_offset(left);
+            let offset = match op_kind {
+                ast::BinaryOpKind::Add => offset.checked_add(constant),
+                ast::BinaryOpKind::Sub => offset.checked_sub(constant),
+                _ => None,
+            };
+            if let Some(offset) = offset.filter(|offset| *offset != i64::MIN) {
+                return (base, offset);
+            }
+        }
+    }
+    (expr, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fib_call(n: &ast::LocalVar, arg: ast::Expr) -> ast::Expr {
+        ast::Expr::func_app(
+            "fib".to_string(),
+            vec![],
+            vec![arg],
+            vec![n.clone()],
+            ast::Type::Int,
+            ast::Position::default(),
+        )
+    }
+
+    /// Counts the `let` expressions and the calls.
+    #[derive(Default)]
+    struct Size {
+        lets: usize,
+        calls: usize,
+    }
+
+    impl ast::ExprWalker for Size {
+        fn walk_let_expr(&mut self, let_expr: &ast::LetExpr) {
+            self.lets += 1;
+            self.walk(&let_expr.def);
+            self.walk(&let_expr.body);
+        }
+
+        fn walk_func_app(&mut self, func_app: &ast::FuncApp) {
+            self.calls += 1;
+            for arg in &func_app.arguments {
+                self.walk(arg);
+            }
+        }
+    }
+
+    /// The calls of `fib` that are reached along several paths share their
+    /// unfolding, so that the body grows linearly with the fuel.
+    #[test]
+    fn unfoldings_are_shared() {
+        let n = ast::LocalVar::new("n", ast::Type::Int);
+        let function = ast::Function {
+            name: "fib".to_string(),
+            type_arguments: vec![],
+            formal_args: vec![n.clone()],
+            return_type: ast::Type::Int,
+            pres: vec![ast::Expr::le_cmp(0.into(), ast::Expr::local(n.clone()))],
+            posts: vec![],
+            body: Some(ast::Expr::ite(
+                ast::Expr::le_cmp(ast::Expr::local(n.clone()), 1.into()),
+                ast::Expr::local(n.clone()),
+                ast::Expr::add(
+                    fib_call(&n, ast::Expr::sub(ast::Expr::local(n.clone()), 1.into())),
+                    fib_call(&n, ast::Expr::sub(ast::Expr::local(n.clone()), 2.into())),
+                ),
+            )),
+        };
+        for fuel in 2..20 {
+            let unrolled = unroll_recursive_calls(function.clone(), fuel);
+            let mut size = Size::default();
+            ast::ExprWalker::walk(&mut size, unrolled.body.as_ref().unwrap());
+            // One unfolding for each of `fib(n - 1)` to `fib(n - 2 * (fuel - 1))`.
+            let unfoldings = 2 * (fuel as usize - 1);
+            assert_eq!(size.lets, unfoldings);
+            // The body and each unfolding keep their two recursive calls for
+            // when the precondition does not hold, and each unfolding also
+            // has its own fallback call.
+            assert_eq!(size.calls, 3 * unfoldings + 2);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::vir::polymorphic_vir::cfg;
 
@@ -52,3 +52,140 @@ pub fn clean_cfg(mut method: cfg::CfgMethod) -> cfg::CfgMethod {
     method.basic_blocks = new_basic_blocks;
     method
 }
+
+/// Remove the basic blocks that contain only comments and jump unconditionally
+/// to another block, by redirecting their incoming edges to their successor.
+/// The blocks that become unreachable are dropped.
+pub fn remove_empty_blocks(mut method: cfg::CfgMethod) -> cfg::CfgMethod {
+    let mut forward = HashMap::new();
+    for (block_index, basic_block) in method.basic_blocks.iter().enumerate().skip(1) {
+        if let cfg::Successor::Goto(target) = &basic_block.successor {
+            let is_empty = basic_block.stmts.iter().all(|stmt| stmt.is_comment());
+            if is_empty && target.block_index != block_index {
+                forward.insert(block_index, target.block_index);
+            }
+        }
+    }
+    let resolve = |mut block_index: usize| {
+        let mut visited = HashSet::new();
+        while let Some(&target) = forward.get(&block_index) {
+            if !visited.insert(block_index) {
+                // A cycle of empty blocks; keep it.
+                break;
+            }
+            block_index = target;
+        }
+        block_index
+    };
+    for basic_block in &mut method.basic_blocks {
+        for target in successor_targets(&mut basic_block.successor) {
+            target.block_index = resolve(target.block_index);
+        }
+    }
+
+    // Drop the unreachable blocks.
+    let mut reachable = vec![false; method.basic_blocks.len()];
+    let mut stack = vec![0];
+    while let Some(block_index) = stack.pop() {
+        if !reachable[block_index] {
+            reachable[block_index] = true;
+            for target in method.basic_blocks[block_index].successor.get_following() {
+                stack.push(target.block_index);
+            }
+        }
+    }
+    let mut new_indices = HashMap::new();
+    let mut new_basic_blocks = Vec::new();
+    for (block_index, basic_block) in method.basic_blocks.into_iter().enumerate() {
+        if reachable[block_index] {
+            new_indices.insert(block_index, new_basic_blocks.len());
+            new_basic_blocks.push(basic_block);
+        }
+    }
+    for basic_block in &mut new_basic_blocks {
+        for target in successor_targets(&mut basic_block.successor) {
+            target.block_index = new_indices[&target.block_index];
+        }
+    }
+    method.basic_blocks = new_basic_blocks;
+    method
+}
+
+fn successor_targets(successor: &mut cfg::Successor) -> Vec<&mut cfg::CfgBlockIndex> {
+    match successor {
+        cfg::Successor::Undefined | cfg::Successor::Return => vec![],
+        cfg::Successor::Goto(target) => vec![target],
+        cfg::Successor::GotoSwitch(conditional_targets, default_target) => conditional_targets
+            .iter_mut()
+            .map(|(_, target)| target)
+            .chain(std::iter::once(default_target))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vir::polymorphic_vir::ast;
+
+    fn assert_stmt(name: &str) -> ast::Stmt {
+        ast::Stmt::Assert(ast::Assert {
+            expr: ast::Expr::local(ast::LocalVar::new(name, ast::Type::Bool)),
+            position: ast::Position::default(),
+        })
+    }
+
+    fn targets(basic_block: &mut cfg::CfgBlock) -> Vec<usize> {
+        successor_targets(&mut basic_block.successor)
+            .into_iter()
+            .map(|target| target.block_index)
+            .collect()
+    }
+
+    /// The edges into a chain of empty blocks are redirected to its end and
+    /// the chain is dropped, while the other blocks keep their order.
+    #[test]
+    fn removes_chains_of_empty_blocks() {
+        let mut method = cfg::CfgMethod::new("m".to_string(), 0, vec![], vec![], vec![]);
+        let start = method.add_block("start", vec![assert_stmt("a")]);
+        let first_empty = method.add_block("first_empty", vec![ast::Stmt::comment("empty")]);
+        let second_empty = method.add_block("second_empty", vec![]);
+        let other = method.add_block("other", vec![assert_stmt("b")]);
+        let end = method.add_block("end", vec![assert_stmt("c")]);
+        method.set_successor(
+            start,
+            cfg::Successor::GotoSwitch(vec![(true.into(), first_empty)], other),
+        );
+        method.set_successor(first_empty, cfg::Successor::Goto(second_empty));
+        method.set_successor(second_empty, cfg::Successor::Goto(end));
+        method.set_successor(other, cfg::Successor::Goto(end));
+        method.set_successor(end, cfg::Successor::Return);
+
+        let mut method = remove_empty_blocks(method);
+        assert_eq!(method.basic_blocks.len(), 3);
+        assert_eq!(targets(&mut method.basic_blocks[0]), vec![2, 1]);
+        assert_eq!(targets(&mut method.basic_blocks[1]), vec![2]);
+        assert_eq!(method.basic_blocks[2].successor, cfg::Successor::Return);
+    }
+
+    /// A cycle of empty blocks is kept instead of being resolved forever.
+    #[test]
+    fn keeps_cycles_of_empty_blocks() {
+        let mut method = cfg::CfgMethod::new("m".to_string(), 0, vec![], vec![], vec![]);
+        let start = method.add_block("start", vec![assert_stmt("a")]);
+        let first_empty = method.add_block("first_empty", vec![]);
+        let second_empty = method.add_block("second_empty", vec![]);
+        method.set_successor(start, cfg::Successor::Goto(first_empty));
+        method.set_successor(first_empty, cfg::Successor::Goto(second_empty));
+        method.set_successor(second_empty, cfg::Successor::Goto(first_empty));
+
+        let mut method = remove_empty_blocks(method);
+        assert_eq!(method.basic_blocks.len(), 3);
+        let cycle_entry = targets(&mut method.basic_blocks[0])[0];
+        let cycle_exit = targets(&mut method.basic_blocks[cycle_entry])[0];
+        assert_eq!(
+            targets(&mut method.basic_blocks[cycle_exit]),
+            vec![cycle_entry]
+        );
+    }
+}
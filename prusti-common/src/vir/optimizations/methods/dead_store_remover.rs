@@ -0,0 +1,267 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Optimization that removes assignments to local variables that are never
+//! read afterwards.
+
+use crate::vir::polymorphic_vir::{ast, cfg};
+use std::collections::HashSet;
+
+/// Remove dead stores, i.e. `x := e` where the local variable `x` is not live
+/// after the assignment. Only assignments whose right-hand side cannot fail
+/// are removed, because removing the others would also remove a check.
+/// Variables used in old expressions are never considered dead, because
+/// their value at a label can be read after the label.
+///
+/// The variables that become unused are then removed by `remove_unused_vars`.
+pub fn remove_dead_stores(mut method: cfg::CfgMethod) -> cfg::CfgMethod {
+    let mut old_vars = OldVarCollector {
+        vars: HashSet::new(),
+    };
+    method.walk_expressions(|expr| ast::ExprWalker::walk(&mut old_vars, expr));
+    let return_vars: HashSet<String> = method
+        .get_formal_returns()
+        .iter()
+        .map(|var| var.name.clone())
+        .collect();
+
+    // Compute the live variables at the entry of each block.
+    let block_count = method.basic_blocks.len();
+    let mut live_in: Vec<HashSet<String>> = vec![HashSet::new(); block_count];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for block_index in (0..block_count).rev() {
+            let block = &method.basic_blocks[block_index];
+            let mut live = live_out(block, &live_in, &return_vars);
+            for stmt in block.stmts.iter().rev() {
+                transfer(stmt, &mut live);
+            }
+            if live != live_in[block_index] {
+                live_in[block_index] = live;
+                changed = true;
+            }
+        }
+    }
+
+    // Remove the dead stores.
+    for block_index in 0..block_count {
+        let mut live = live_out(&method.basic_blocks[block_index], &live_in, &return_vars);
+        let block = &mut method.basic_blocks[block_index];
+        let mut stmts = Vec::with_capacity(block.stmts.len());
+        for stmt in block.stmts.drain(..).rev() {
+            if is_dead_store(&stmt, &live, &old_vars.vars) {
+                debug!("Removing dead store: {}", stmt);
+                continue;
+            }
+            transfer(&stmt, &mut live);
+            stmts.push(stmt);
+        }
+        stmts.reverse();
+        block.stmts = stmts;
+    }
+    method
+}
+
+fn live_out(
+    block: &cfg::CfgBlock,
+    live_in: &[HashSet<String>],
+    return_vars: &HashSet<String>,
+) -> HashSet<String> {
+    let mut live: HashSet<String> = block
+        .successor
+        .get_following()
+        .iter()
+        .flat_map(|target| live_in[target.block_index].iter().cloned())
+        .collect();
+    match &block.successor {
+        cfg::Successor::Return => live.extend(return_vars.iter().cloned()),
+        cfg::Successor::GotoSwitch(guarded_targets, _) => {
+            let mut collector = VarCollector { vars: &mut live };
+            for (guard, _) in guarded_targets {
+                ast::ExprWalker::walk(&mut collector, guard);
+            }
+        }
+        cfg::Successor::Undefined | cfg::Successor::Goto(_) => {}
+    }
+    live
+}
+
+/// Compute the live variables before `stmt` from those after it.
+fn transfer(stmt: &ast::Stmt, live: &mut HashSet<String>) {
+    if let Some((target, source)) = as_local_assignment(stmt) {
+        live.remove(&target.name);
+        ast::ExprWalker::walk(&mut VarCollector { vars: live }, source);
+    } else {
+        ast::StmtWalker::walk(&mut VarCollector { vars: live }, stmt);
+    }
+}
+
+fn is_dead_store(stmt: &ast::Stmt, live: &HashSet<String>, old_vars: &HashSet<String>) -> bool {
+    if let Some((target, source)) = as_local_assignment(stmt) {
+        !live.contains(&target.name) && !old_vars.contains(&target.name) && cannot_fail(source)
+    } else {
+        false
+    }
+}
+
+fn as_local_assignment(stmt: &ast::Stmt) -> Option<(&ast::LocalVar, &ast::Expr)> {
+    match stmt {
+        ast::Stmt::Assign(ast::Assign {
+            target: ast::Expr::Local(ast::Local { variable, .. }),
+            source,
+            kind: ast::AssignKind::Copy | ast::AssignKind::Ghost,
+        }) => Some((variable, source)),
+        _ => None,
+    }
+}
+
+/// Whether the evaluation of `expr` has no well-definedness conditions. Only
+/// the expressions built from variables, constants and total operators are
+/// known to always succeed; field accesses, container operations, function
+/// applications and the other expressions may fail.
+fn cannot_fail(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::Local(_) | ast::Expr::Const(_) => true,
+        ast::Expr::LabelledOld(ast::LabelledOld { base, .. }) => cannot_fail(base),
+        ast::Expr::UnaryOp(ast::UnaryOp { argument, .. }) => cannot_fail(argument),
+        ast::Expr::BinOp(ast::BinOp {
+            op_kind,
+            left,
+            right,
+            ..
+        }) => {
+            !matches!(op_kind, ast::BinaryOpKind::Div | ast::BinaryOpKind::Mod)
+                && cannot_fail(left)
+                && cannot_fail(right)
+        }
+        ast::Expr::Cond(ast::Cond {
+            guard,
+            then_expr,
+            else_expr,
+            ..
+        }) => cannot_fail(guard) && cannot_fail(then_expr) && cannot_fail(else_expr),
+        ast::Expr::Variant(_)
+        | ast::Expr::Field(_)
+        | ast::Expr::AddrOf(_)
+        | ast::Expr::MagicWand(_)
+        | ast::Expr::PredicateAccessPredicate(_)
+        | ast::Expr::FieldAccessPredicate(_)
+        | ast::Expr::ContainerOp(_)
+        | ast::Expr::Seq(_)
+        | ast::Expr::Unfolding(_)
+        | ast::Expr::ForAll(_)
+        | ast::Expr::Exists(_)
+        | ast::Expr::LetExpr(_)
+        | ast::Expr::FuncApp(_)
+        | ast::Expr::DomainFuncApp(_)
+        | ast::Expr::InhaleExhale(_)
+        | ast::Expr::Downcast(_)
+        | ast::Expr::SnapApp(_)
+        | ast::Expr::Cast(_) => false,
+    }
+}
+
+/// Collects all the mentioned variables.
+struct VarCollector<'a> {
+    vars: &'a mut HashSet<String>,
+}
+
+impl<'a> ast::ExprWalker for VarCollector<'a> {
+    fn walk_local_var(&mut self, local_var: &ast::LocalVar) {
+        self.vars.insert(local_var.name.clone());
+    }
+}
+
+impl<'a> ast::StmtWalker for VarCollector<'a> {
+    fn walk_expr(&mut self, expr: &ast::Expr) {
+        ast::ExprWalker::walk(self, expr);
+    }
+    fn walk_local_var(&mut self, local_var: &ast::LocalVar) {
+        self.vars.insert(local_var.name.clone());
+    }
+}
+
+/// Collects the variables used in old expressions.
+struct OldVarCollector {
+    vars: HashSet<String>,
+}
+
+impl ast::ExprWalker for OldVarCollector {
+    fn walk_labelled_old(&mut self, labelled_old: &ast::LabelledOld) {
+        let mut collector = VarCollector {
+            vars: &mut self.vars,
+        };
+        ast::ExprWalker::walk(&mut collector, &labelled_old.base);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(name: &str) -> ast::Expr {
+        ast::Expr::local(ast::LocalVar::new(name, ast::Type::Int))
+    }
+
+    fn assign(target: &str, source: ast::Expr) -> ast::Stmt {
+        ast::Stmt::Assign(ast::Assign {
+            target: local(target),
+            source,
+            kind: ast::AssignKind::Copy,
+        })
+    }
+
+    fn optimize(stmts: Vec<ast::Stmt>) -> Vec<ast::Stmt> {
+        let locals = vec![
+            ast::LocalVar::new("x", ast::Type::Int),
+            ast::LocalVar::new("y", ast::Type::Int),
+        ];
+        let mut method = cfg::CfgMethod::new("m".to_string(), 0, vec![], locals, vec![]);
+        let start = method.add_block("start", stmts);
+        method.set_successor(start, cfg::Successor::Return);
+        remove_dead_stores(method).basic_blocks.remove(0).stmts
+    }
+
+    #[test]
+    fn removes_infallible_dead_stores() {
+        let stmts = optimize(vec![
+            assign("x", ast::Expr::add(local("y"), 1.into())),
+            assign("x", local("y")),
+            ast::Stmt::Assert(ast::Assert {
+                expr: ast::Expr::eq_cmp(local("x"), 0.into()),
+                position: ast::Position::default(),
+            }),
+        ]);
+        assert_eq!(stmts.len(), 2);
+    }
+
+    /// A dead store whose value may be out of the bounds of a sequence is
+    /// kept, since removing it would remove the bounds check.
+    #[test]
+    fn keeps_fallible_dead_stores() {
+        let seq = ast::Expr::Seq(ast::Seq {
+            typ: ast::Type::Seq(ast::SeqType {
+                typ: box ast::Type::Int,
+            }),
+            elements: vec![],
+            position: ast::Position::default(),
+        });
+        let stmts = optimize(vec![
+            assign(
+                "x",
+                ast::Expr::ContainerOp(ast::ContainerOp {
+                    op_kind: ast::ContainerOpKind::SeqIndex,
+                    left: box seq,
+                    right: box local("y"),
+                    position: ast::Position::default(),
+                }),
+            ),
+            assign("x", ast::Expr::div(local("y"), local("y"))),
+        ]);
+        assert_eq!(stmts.len(), 2);
+    }
+}
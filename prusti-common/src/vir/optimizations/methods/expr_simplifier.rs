@@ -0,0 +1,41 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Optimization that simplifies the expressions of a method.
+
+use crate::vir::{
+    optimizations::functions::Simplifier,
+    polymorphic_vir::{ast, cfg},
+};
+
+/// Simplify the expressions in the statements and in the successors by
+/// folding constants, simplifying boolean tautologies and flattening nested
+/// conjunctions. Assertions that become trivially true are removed later by
+/// `remove_trivial_assertions`.
+pub fn simplify_expressions(mut method: cfg::CfgMethod) -> cfg::CfgMethod {
+    let mut simplifier = StmtSimplifier;
+    for block in &mut method.basic_blocks {
+        block.stmts = block
+            .stmts
+            .drain(..)
+            .map(|stmt| ast::StmtFolder::fold(&mut simplifier, stmt))
+            .collect();
+        if let cfg::Successor::GotoSwitch(guarded_targets, _) = &mut block.successor {
+            for (guard, _) in guarded_targets {
+                *guard = guard.clone().simplify();
+            }
+        }
+    }
+    method
+}
+
+struct StmtSimplifier;
+
+impl ast::StmtFolder for StmtSimplifier {
+    fn fold_expr(&mut self, expr: ast::Expr) -> ast::Expr {
+        expr.simplify()
+    }
+}
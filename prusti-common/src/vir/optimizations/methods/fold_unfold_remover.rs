@@ -0,0 +1,249 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Optimization that removes redundant `unfold P(x); fold P(x)` pairs
+//! introduced by the fold-unfold algorithm.
+
+use crate::vir::polymorphic_vir::{ast, cfg, Stmt};
+
+/// Remove `unfold P(x); fold P(x)` sequences. The two statements do not need
+/// to be adjacent: they may be separated by straight-line statements that do
+/// not change the permissions and that do not mention the base variable of
+/// `x`, because those statements cannot depend on the unfolded permissions.
+pub fn remove_redundant_fold_unfold(mut method: cfg::CfgMethod) -> cfg::CfgMethod {
+    for block in &mut method.basic_blocks {
+        while remove_first_pair(&mut block.stmts) {}
+    }
+    method
+}
+
+/// Remove the first redundant pair in `stmts`. Returns `true` if a pair was
+/// removed.
+fn remove_first_pair(stmts: &mut Vec<Stmt>) -> bool {
+    let pair = stmts
+        .iter()
+        .enumerate()
+        .find_map(|(unfold_index, stmt)| match stmt {
+            Stmt::Unfold(unfold) => find_matching_fold(unfold, &stmts[unfold_index + 1..])
+                .map(|offset| (unfold_index, unfold_index + 1 + offset)),
+            _ => None,
+        });
+    if let Some((unfold_index, fold_index)) = pair {
+        stmts.remove(fold_index);
+        stmts.remove(unfold_index);
+        true
+    } else {
+        false
+    }
+}
+
+/// Find the index of the `fold` statement that undoes `unfold`, skipping
+/// over permission-neutral statements.
+fn find_matching_fold(unfold: &ast::Unfold, stmts: &[Stmt]) -> Option<usize> {
+    let bases: Vec<_> = unfold.arguments.iter().map(get_base_name).collect();
+    for (index, stmt) in stmts.iter().enumerate() {
+        match stmt {
+            Stmt::Fold(fold)
+                if fold.predicate == unfold.predicate
+                    && fold.arguments == unfold.arguments
+                    && fold.permission == unfold.permission
+                    && fold.enum_variant == unfold.enum_variant =>
+            {
+                return Some(index);
+            }
+            _ if is_permission_neutral(stmt, &bases) => {}
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn get_base_name(expr: &ast::Expr) -> Option<String> {
+    if expr.is_place() {
+        Some(expr.get_base().name)
+    } else {
+        None
+    }
+}
+
+/// Whether `stmt` neither changes the permissions nor mentions any of the
+/// given base variables. A `None` base means that we could not determine the
+/// base of an argument, which makes every statement non-neutral.
+fn is_permission_neutral(stmt: &Stmt, bases: &[Option<String>]) -> bool {
+    let exprs: Vec<&ast::Expr> = match stmt {
+        Stmt::Comment(_) => return true,
+        Stmt::Assert(ast::Assert { expr, .. }) | Stmt::Inhale(ast::Inhale { expr })
+            if expr.is_pure() =>
+        {
+            vec![expr]
+        }
+        Stmt::Assign(ast::Assign {
+            target,
+            source,
+            kind: ast::AssignKind::Copy,
+        }) => vec![target, source],
+        _ => return false,
+    };
+    bases.iter().all(|base| match base {
+        Some(base) => exprs.iter().all(|expr| !mentions_var(expr, base)),
+        None => false,
+    })
+}
+
+fn mentions_var(expr: &ast::Expr, name: &str) -> bool {
+    struct VarFinder<'a> {
+        name: &'a str,
+        found: bool,
+    }
+    impl<'a> ast::ExprWalker for VarFinder<'a> {
+        fn walk_local_var(&mut self, var: &ast::LocalVar) {
+            if var.name == self.name {
+                self.found = true;
+            }
+        }
+    }
+    let mut finder = VarFinder { name, found: false };
+    ast::ExprWalker::walk(&mut finder, expr);
+    finder.found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vir::polymorphic_vir::{
+        generator::Generator,
+        interpreter::{Interpreter, Value},
+    };
+
+    fn locals() -> Vec<ast::LocalVar> {
+        vec![
+            ast::LocalVar::new("i", ast::Type::Int),
+            ast::LocalVar::new("b", ast::Type::Bool),
+            ast::LocalVar::new("x", ast::Type::typed_ref("T")),
+            ast::LocalVar::new("y", ast::Type::typed_ref("T")),
+        ]
+    }
+
+    fn inputs(path: &str, typ: &ast::Type) -> Value {
+        match typ {
+            ast::Type::Bool => Value::Bool(path.len() % 2 == 0),
+            _ => Value::Int(path.len() as i128),
+        }
+    }
+
+    /// Collects the bases of the accessed fields.
+    struct FieldBases(Vec<String>);
+
+    impl ast::ExprWalker for FieldBases {
+        fn walk_field(&mut self, ast::FieldExpr { base, .. }: &ast::FieldExpr) {
+            self.0.push(base.get_base().name);
+            ast::ExprWalker::walk(self, base);
+        }
+    }
+
+    impl ast::StmtWalker for FieldBases {
+        fn walk_expr(&mut self, expr: &ast::Expr) {
+            ast::ExprWalker::walk(self, expr);
+        }
+    }
+
+    /// Check that the statements access the fields of a reference only while
+    /// its predicate is unfolded, fold only unfolded predicates and unfold
+    /// only folded ones. `unfolded` holds the references whose predicate is
+    /// unfolded, and is updated to the state after the statements.
+    fn check_permissions(stmts: &[Stmt], unfolded: &mut Vec<String>) -> Result<(), String> {
+        for stmt in stmts {
+            match stmt {
+                Stmt::Fold(ast::Fold { arguments, .. }) => {
+                    let base = arguments[0].get_base().name;
+                    let index = unfolded
+                        .iter()
+                        .position(|name| name == &base)
+                        .ok_or_else(|| format!("{} without permission", stmt))?;
+                    unfolded.remove(index);
+                }
+                Stmt::Unfold(ast::Unfold { arguments, .. }) => {
+                    let base = arguments[0].get_base().name;
+                    if unfolded.contains(&base) {
+                        return Err(format!("{} without permission", stmt));
+                    }
+                    unfolded.push(base);
+                }
+                Stmt::If(ast::If {
+                    guard,
+                    then_stmts,
+                    else_stmts,
+                }) => {
+                    check_permissions(
+                        &[Stmt::Assert(ast::Assert {
+                            expr: guard.clone(),
+                            position: ast::Position::default(),
+                        })],
+                        unfolded,
+                    )?;
+                    let mut then_unfolded = unfolded.clone();
+                    check_permissions(then_stmts, &mut then_unfolded)?;
+                    let mut else_unfolded = unfolded.clone();
+                    check_permissions(else_stmts, &mut else_unfolded)?;
+                    then_unfolded.sort();
+                    else_unfolded.sort();
+                    if then_unfolded != else_unfolded {
+                        return Err(format!(
+                            "the branches of {} unfold different predicates",
+                            stmt
+                        ));
+                    }
+                    *unfolded = then_unfolded;
+                }
+                _ => {
+                    let mut bases = FieldBases(vec![]);
+                    ast::StmtWalker::walk(&mut bases, stmt);
+                    if let Some(base) = bases.0.iter().find(|base| !unfolded.contains(base)) {
+                        return Err(format!("{} accesses {} without permission", stmt, base));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Removing the redundant pairs from random statements that are
+    /// well-formed with respect to the permissions keeps them well-formed,
+    /// with the same permissions at the end, and computing the same values.
+    #[test]
+    fn removal_preserves_permissions_and_values() {
+        for seed in 0..1000 {
+            let stmts = Generator::new(seed, locals()).stmts(10, 2);
+            let mut unfolded = vec![];
+            check_permissions(&stmts, &mut unfolded).unwrap();
+            assert!(unfolded.is_empty());
+
+            let mut method = cfg::CfgMethod::new("m".to_string(), 0, vec![], locals(), vec![]);
+            let start = method.add_block("start", stmts.clone());
+            method.set_successor(start, cfg::Successor::Return);
+            let optimized = &remove_redundant_fold_unfold(method).basic_blocks[0].stmts;
+            let mut unfolded = vec![];
+            if let Err(error) = check_permissions(optimized, &mut unfolded) {
+                panic!("seed {}: {}", seed, error);
+            }
+            assert!(unfolded.is_empty(), "seed {}", seed);
+
+            let mut original_interpreter = Interpreter::new(inputs);
+            let original_result = original_interpreter.execute(&stmts);
+            let mut optimized_interpreter = Interpreter::new(inputs);
+            let optimized_result = optimized_interpreter.execute(optimized);
+            assert_eq!(original_result, optimized_result, "seed {}", seed);
+            if original_result.is_ok() {
+                assert_eq!(
+                    original_interpreter.heap(),
+                    optimized_interpreter.heap(),
+                    "seed {}",
+                    seed
+                );
+            }
+        }
+    }
+}
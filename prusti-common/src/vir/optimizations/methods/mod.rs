@@ -8,7 +8,10 @@
 
 mod assert_remover;
 mod cfg_cleaner;
+mod dead_store_remover;
 mod empty_if_remover;
+mod expr_simplifier;
+mod fold_unfold_remover;
 mod purifier;
 mod quantifier_fixer;
 mod unfolding_fixer;
@@ -18,9 +21,16 @@ use super::log_method;
 use crate::{config::Optimizations, vir::polymorphic_vir::cfg::CfgMethod};
 
 use self::{
-    assert_remover::remove_trivial_assertions, cfg_cleaner::clean_cfg,
-    empty_if_remover::remove_empty_if, purifier::purify_vars, quantifier_fixer::fix_quantifiers,
-    unfolding_fixer::fix_unfoldings, var_remover::remove_unused_vars,
+    assert_remover::remove_trivial_assertions,
+    cfg_cleaner::{clean_cfg, remove_empty_blocks},
+    dead_store_remover::remove_dead_stores,
+    empty_if_remover::remove_empty_if,
+    expr_simplifier::simplify_expressions,
+    fold_unfold_remover::remove_redundant_fold_unfold,
+    purifier::purify_vars,
+    quantifier_fixer::fix_quantifiers,
+    unfolding_fixer::fix_unfoldings,
+    var_remover::remove_unused_vars,
 };
 
 #[allow(clippy::let_and_return)]
@@ -49,9 +59,13 @@ pub fn optimize_method_encoding(
     let cfg = apply!(purify_vars, cfg);
     let cfg = apply!(fix_unfoldings, cfg);
     let cfg = apply!(fix_quantifiers, cfg);
+    let cfg = apply!(simplify_expressions, cfg);
+    let cfg = apply!(remove_redundant_fold_unfold, cfg);
     let cfg = apply!(remove_empty_if, cfg);
+    let cfg = apply!(remove_dead_stores, cfg);
     let cfg = apply!(remove_unused_vars, cfg);
     let cfg = apply!(remove_trivial_assertions, cfg);
+    let cfg = apply!(remove_empty_blocks, cfg);
     let cfg = apply!(clean_cfg, cfg);
 
     cfg
@@ -99,6 +99,17 @@ impl VarCollector {
             self.impure_vars.insert(local_var.clone());
         }
     }
+    /// Replace `var`, whose predicate is purifiable, with a variable of the
+    /// type of the value of the predicate.
+    fn add_predicate_replacement(&mut self, var: &ast::LocalVar, predicate_type: &ast::Type) {
+        let mut new_var = var.clone();
+        new_var.typ = match &predicate_type.name()[..] {
+            "usize" => ast::Type::Int,
+            "isize" => ast::Type::Int,
+            x => unreachable!("{}", x),
+        };
+        self.replacements.insert(var.clone(), new_var);
+    }
 }
 
 impl ast::ExprWalker for VarCollector {
@@ -116,15 +127,7 @@ impl ast::ExprWalker for VarCollector {
         let old_pure_context = self.is_pure_context;
         if is_purifiable_predicate(predicate_type) {
             if let ast::Expr::Local(ast::Local { variable: var, .. }) = argument {
-                let mut new_var = var.clone();
-                let original = var.clone();
-                let name = &predicate_type.name()[..];
-                new_var.typ = match name {
-                    "usize" => ast::Type::Int,
-                    "isize" => ast::Type::Int,
-                    x => unreachable!("{}", x),
-                };
-                self.replacements.insert(original, new_var);
+                self.add_predicate_replacement(var, predicate_type);
                 self.is_pure_context = true;
             }
         }
@@ -234,7 +237,8 @@ impl ast::StmtWalker for VarCollector {
     ) {
         let old_pure_context = self.is_pure_context;
         if is_purifiable_predicate(predicate) {
-            if let ast::Expr::Local(_) = arguments[0] {
+            if let ast::Expr::Local(ast::Local { variable: var, .. }) = &arguments[0] {
+                self.add_predicate_replacement(var, predicate);
                 self.is_pure_context = true;
             }
         }
@@ -253,7 +257,8 @@ impl ast::StmtWalker for VarCollector {
     ) {
         let old_pure_context = self.is_pure_context;
         if is_purifiable_predicate(predicate) {
-            if let ast::Expr::Local(_) = arguments[0] {
+            if let ast::Expr::Local(ast::Local { variable: var, .. }) = &arguments[0] {
+                self.add_predicate_replacement(var, predicate);
                 self.is_pure_context = true;
             }
         }
@@ -497,3 +502,113 @@ impl ast::StmtFolder for VarPurifier {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vir::polymorphic_vir::{
+        generator::Generator,
+        interpreter::{EvalError, Interpreter, Value},
+    };
+
+    fn locals() -> Vec<ast::LocalVar> {
+        vec![
+            ast::LocalVar::new("i", ast::Type::Int),
+            ast::LocalVar::new("b", ast::Type::Bool),
+            ast::LocalVar::new("n", ast::Type::typed_ref("usize")),
+            ast::LocalVar::new("m", ast::Type::typed_ref("usize")),
+            // Folding the predicate of `r` makes it impure.
+            ast::LocalVar::new("r", ast::Type::typed_ref("i32")),
+        ]
+    }
+
+    /// Inputs that do not depend on whether a variable was purified: the
+    /// input `n.val_int` of a reference `n` equals the input `n` of the
+    /// purified variable.
+    fn inputs(path: &str, typ: &ast::Type) -> Value {
+        let path = path.strip_suffix(".val_int").unwrap_or(path);
+        match typ {
+            ast::Type::Bool => Value::Bool(path.len() % 2 == 0),
+            _ => Value::Int(path.len() as i128),
+        }
+    }
+
+    /// The place that holds the value of a local variable.
+    fn observed(var: &ast::LocalVar) -> ast::Expr {
+        if var.typ.is_typed_ref_or_type_var() {
+            ast::Expr::local(var.clone()).field(Generator::ref_field())
+        } else {
+            ast::Expr::local(var.clone())
+        }
+    }
+
+    /// Checks that the variables used by the statements are declared with
+    /// their type and that the assignments are well-typed.
+    struct WellFormednessChecker<'a> {
+        declared: &'a [ast::LocalVar],
+    }
+
+    impl<'a> ast::ExprWalker for WellFormednessChecker<'a> {
+        fn walk_local_var(&mut self, var: &ast::LocalVar) {
+            assert!(self.declared.contains(var), "undeclared variable {:?}", var);
+        }
+    }
+
+    impl<'a> ast::StmtWalker for WellFormednessChecker<'a> {
+        fn walk_expr(&mut self, expr: &ast::Expr) {
+            ast::ExprWalker::walk(self, expr);
+        }
+        fn walk_assign(&mut self, ast::Assign { target, source, .. }: &ast::Assign) {
+            assert_eq!(
+                target.get_type(),
+                source.get_type(),
+                "{} := {}",
+                target,
+                source
+            );
+            self.walk_expr(target);
+            self.walk_expr(source);
+        }
+    }
+
+    /// The purification of random statements gives well-formed statements
+    /// that compute the same values, unless the purified code fails because
+    /// it checks the bounds of the purified `usize` values.
+    #[test]
+    fn purification_preserves_values() {
+        for seed in 0..1000 {
+            let stmts = Generator::new(seed, locals()).stmts(10, 3);
+            let mut method = cfg::CfgMethod::new("m".to_string(), 0, vec![], locals(), vec![]);
+            let start = method.add_block("start", stmts.clone());
+            method.set_successor(start, cfg::Successor::Return);
+            let purified = purify_vars(method);
+            let purified_stmts = &purified.basic_blocks[0].stmts;
+            let mut checker = WellFormednessChecker {
+                declared: &purified.local_vars,
+            };
+            for stmt in purified_stmts {
+                ast::StmtWalker::walk(&mut checker, stmt);
+            }
+
+            let mut original_interpreter = Interpreter::new(inputs);
+            if original_interpreter.execute(&stmts).is_err() {
+                continue;
+            }
+            let mut purified_interpreter = Interpreter::new(inputs);
+            match purified_interpreter.execute(purified_stmts) {
+                Ok(()) => {}
+                Err(EvalError::AssertionFailed(_)) | Err(EvalError::Infeasible(_)) => continue,
+                Err(error) => panic!("seed {}: {:?}", seed, error),
+            }
+            for (original_var, purified_var) in locals().iter().zip(&purified.local_vars) {
+                assert_eq!(
+                    original_interpreter.eval(&observed(original_var)),
+                    purified_interpreter.eval(&observed(purified_var)),
+                    "seed {}: value of {}",
+                    seed,
+                    original_var
+                );
+            }
+        }
+    }
+}
@@ -56,7 +56,13 @@ fn log_methods(
     }
 }
 
-pub fn optimize_program(p: Program, source_file_name: &str) -> Program {
+/// Optimize `p`. The functions selected by `is_never_inlined` are not inlined
+/// at their call sites.
+pub fn optimize_program(
+    p: Program,
+    source_file_name: &str,
+    is_never_inlined: impl Fn(&str) -> bool,
+) -> Program {
     let mut program = p;
     let optimizations = config::optimizations();
     debug!("Enabled optimisations: {:?}", optimizations);
@@ -87,8 +93,11 @@ pub fn optimize_program(p: Program, source_file_name: &str) -> Program {
             "inline_constant_functions",
             false,
         );
-        let (new_methods, new_functions) =
-            functions::inline_constant_functions(program.methods, program.functions);
+        let (new_methods, new_functions) = functions::inline_constant_functions(
+            program.methods,
+            program.functions,
+            is_never_inlined,
+        );
         program.methods = new_methods;
         program.functions = new_functions;
         log_methods(
@@ -129,5 +138,16 @@ pub fn optimize_program(p: Program, source_file_name: &str) -> Program {
         program.methods = purification::purify_methods(program.methods, &program.viper_predicates);
     }
 
+    if optimizations.delete_unused_functions {
+        program.functions = functions::delete_unused_functions(
+            &program.methods,
+            &program.viper_predicates,
+            &program.domains,
+            program.functions,
+        );
+        program.builtin_methods =
+            functions::delete_unused_builtin_methods(&program.methods, program.builtin_methods);
+    }
+
     program
 }
@@ -1,9 +1,10 @@
-use crate::vir::polymorphic_vir::{
-    ast, cfg, utils::walk_method, Expr, Field, LocalVar, Stmt, Type,
+use crate::{
+    config,
+    vir::polymorphic_vir::{ast, cfg, utils::walk_method, Expr, Field, LocalVar, Stmt, Type},
 };
 use log::debug;
 use prusti_utils::force_matches;
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 /// This purifies local variables in a method body
 pub fn purify_methods(
@@ -31,17 +32,81 @@ fn translate_type(typ: &Type) -> Type {
 
 static SUPPORTED_TYPES: &[&str] = &["bool", "i32", "usize", "u32"];
 
+/// If the body of the struct predicate of `typ` consists only of the fields of
+/// supported types, returns these fields. Such structs (e.g. small tuples of
+/// integers) are purified by replacing each of their fields with a pure local
+/// variable.
+fn get_purifiable_struct_fields(typ: &Type, predicates: &[ast::Predicate]) -> Option<Vec<Field>> {
+    let body = predicates.iter().find_map(|predicate| match predicate {
+        ast::Predicate::Struct(predicate) if &predicate.typ == typ => predicate.body.as_ref(),
+        _ => None,
+    })?;
+    let mut conjuncts = Vec::new();
+    collect_conjuncts(body, &mut conjuncts);
+    let mut fields = Vec::new();
+    for conjunct in conjuncts {
+        match conjunct {
+            Expr::FieldAccessPredicate(ast::FieldAccessPredicate {
+                base: box Expr::Field(ast::FieldExpr { field, .. }),
+                ..
+            }) if SUPPORTED_TYPES.contains(&field.typ.name().as_str()) => {
+                fields.push(field.clone());
+            }
+            Expr::PredicateAccessPredicate(ast::PredicateAccessPredicate {
+                predicate_type,
+                ..
+            }) if SUPPORTED_TYPES.contains(&predicate_type.name().as_str()) => {}
+            _ => return None,
+        }
+    }
+    if fields.is_empty() {
+        None
+    } else {
+        Some(fields)
+    }
+}
+
+fn collect_conjuncts<'a>(expr: &'a Expr, conjuncts: &mut Vec<&'a Expr>) {
+    if let Expr::BinOp(ast::BinOp {
+        op_kind: ast::BinaryOpKind::And,
+        box left,
+        box right,
+        ..
+    }) = expr
+    {
+        collect_conjuncts(left, conjuncts);
+        collect_conjuncts(right, conjuncts);
+    } else {
+        conjuncts.push(expr);
+    }
+}
+
+/// The pure local variable that replaces the field `field` of the purified
+/// struct variable `var`.
+fn get_field_var(var: &str, field: &Field) -> LocalVar {
+    LocalVar::new(
+        format!("{}${}", var, field.name),
+        translate_type(&field.typ),
+    )
+}
+
 fn purify_method(method: &mut cfg::CfgMethod, predicates: &[ast::Predicate]) {
     let mut candidates = HashSet::new();
+    let mut struct_candidates = HashMap::new();
     for var in &method.local_vars {
         match &var.typ {
             Type::TypedRef(..) if SUPPORTED_TYPES.contains(&var.typ.name().as_str()) => {
                 candidates.insert(var.name.clone());
             }
+            Type::TypedRef(..) if config::enable_struct_purification() => {
+                if let Some(fields) = get_purifiable_struct_fields(&var.typ, predicates) {
+                    struct_candidates.insert(var.name.clone(), fields);
+                }
+            }
             _ => {}
         };
     }
-    let mut collector = PurifiableVariableCollector::new(candidates);
+    let mut collector = PurifiableVariableCollector::new(candidates, struct_candidates);
 
     debug!(
         "PurifiableVariableCollector for method {} before filtering {:?}",
@@ -55,60 +120,175 @@ fn purify_method(method: &mut cfg::CfgMethod, predicates: &[ast::Predicate]) {
         collector
     );
 
-    for var in &mut method.local_vars {
-        if collector.vars.contains(&var.name) {
-            var.typ = translate_type(&var.typ);
+    let mut local_vars = Vec::new();
+    for mut var in method.local_vars.drain(..) {
+        if let Some(fields) = collector.struct_vars.get(&var.name) {
+            local_vars.extend(fields.iter().map(|field| get_field_var(&var.name, field)));
+        } else {
+            if collector.vars.contains(&var.name) {
+                var.typ = translate_type(&var.typ);
+            }
+            local_vars.push(var);
         }
     }
+    method.local_vars = local_vars;
     let mut p = Purifier::new(collector, predicates);
 
     //for stmt in method
     for block in &mut method.basic_blocks {
-        block.stmts = block
-            .stmts
-            .clone()
-            .into_iter()
-            .map(|stmt| ast::StmtFolder::fold(&mut p, stmt))
-            .collect();
+        let mut stmts = Vec::new();
+        for stmt in block.stmts.drain(..) {
+            if let Some(havocs) = p.havoc_struct_fields(&stmt) {
+                stmts.extend(havocs);
+            } else {
+                stmts.push(ast::StmtFolder::fold(&mut p, stmt));
+            }
+        }
+        block.stmts = stmts;
     }
 }
 
 /// This is a ExprWalkerand StmtWalker used to collect information about which
 /// local variables can be purified.
 ///
-/// The current implementation is only for ints/bools. So to check if a
-/// reference is borrowed we simple check if a variable is ever mentioned
-/// without a field access.
+/// The current implementation is only for ints/bools and for structs whose
+/// fields are ints/bools. So to check if a reference is borrowed we simple
+/// check if a variable is ever mentioned without a field access (for structs,
+/// without an access to the value of one of its fields).
 #[derive(Debug)]
 struct PurifiableVariableCollector {
     vars: HashSet<String>,
+    /// The struct variables that can be purified, with their fields.
+    struct_vars: HashMap<String, Vec<Field>>,
+    /// Whether only the uses of the struct variables are checked. This is
+    /// the case for the expressions of statements other than assignments.
+    check_only_structs: bool,
 }
 
 impl PurifiableVariableCollector {
-    fn new(initial_vars: HashSet<String>) -> Self {
-        PurifiableVariableCollector { vars: initial_vars }
+    fn new(initial_vars: HashSet<String>, struct_vars: HashMap<String, Vec<Field>>) -> Self {
+        PurifiableVariableCollector {
+            vars: initial_vars,
+            struct_vars,
+            check_only_structs: false,
+        }
+    }
+
+    /// Whether `expr` is a purifiable struct variable or one of its fields.
+    fn is_struct_place(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Local(ast::Local { variable, .. }) => {
+                self.struct_vars.contains_key(&variable.name)
+            }
+            Expr::Field(ast::FieldExpr {
+                base: box Expr::Local(ast::Local { variable, .. }),
+                ..
+            }) => self.struct_vars.contains_key(&variable.name),
+            _ => false,
+        }
     }
 }
 
 impl ast::ExprWalker for PurifiableVariableCollector {
     fn walk_local(&mut self, ast::Local { variable, .. }: &ast::Local) {
-        if self.vars.remove(&variable.name) {
+        let removed = (!self.check_only_structs && self.vars.remove(&variable.name))
+            || self.struct_vars.remove(&variable.name).is_some();
+        if removed {
             debug!("Will not purify the variable {:?} ", variable)
         }
     }
 
     fn walk_field(&mut self, ast::FieldExpr { box base, .. }: &ast::FieldExpr) {
         match base {
+            // A field of a struct is a reference that may be borrowed.
+            Expr::Local(_) if self.is_struct_place(base) => ast::ExprWalker::walk(self, base),
             Expr::Local(_) => {}
+            Expr::Field(_) if self.is_struct_place(base) => {}
             _ => ast::ExprWalker::walk(self, base),
         }
     }
+
+    fn walk_predicate_access_predicate(
+        &mut self,
+        ast::PredicateAccessPredicate { box argument, .. }: &ast::PredicateAccessPredicate,
+    ) {
+        if !self.is_struct_place(argument) {
+            ast::ExprWalker::walk(self, argument);
+        }
+    }
+
+    fn walk_unfolding(
+        &mut self,
+        ast::Unfolding {
+            arguments,
+            box base,
+            ..
+        }: &ast::Unfolding,
+    ) {
+        for argument in arguments {
+            if !self.is_struct_place(argument) {
+                ast::ExprWalker::walk(self, argument);
+            }
+        }
+        ast::ExprWalker::walk(self, base);
+    }
 }
 
 impl ast::StmtWalker for PurifiableVariableCollector {
-    fn walk_assign(&mut self, ast::Assign { source, .. }: &ast::Assign) {
+    fn walk_expr(&mut self, expr: &Expr) {
+        self.check_only_structs = true;
+        ast::ExprWalker::walk(self, expr);
+        self.check_only_structs = false;
+    }
+
+    fn walk_assign(&mut self, ast::Assign { target, source, .. }: &ast::Assign) {
+        if self.is_struct_place(target) {
+            // The struct or the reference to one of its fields is assigned.
+            let variable = target.get_base();
+            if self.struct_vars.remove(&variable.name).is_some() {
+                debug!("Will not purify the variable {:?} ", variable)
+            }
+        }
         ast::ExprWalker::walk(self, source);
     }
+
+    fn walk_method_call(
+        &mut self,
+        ast::MethodCall {
+            method_name,
+            arguments,
+            targets,
+        }: &ast::MethodCall,
+    ) {
+        for argument in arguments {
+            ast::StmtWalker::walk_expr(self, argument);
+        }
+        // Only the havocs of a struct variable are replaced with havocs of the
+        // variables of its fields. The other methods assign the struct itself.
+        if !method_name.starts_with("builtin$havoc") || targets.len() != 1 {
+            for target in targets {
+                if self.struct_vars.remove(&target.name).is_some() {
+                    debug!("Will not purify the variable {:?} ", target)
+                }
+            }
+        }
+    }
+
+    fn walk_fold(&mut self, ast::Fold { arguments, .. }: &ast::Fold) {
+        for argument in arguments {
+            if !self.is_struct_place(argument) {
+                ast::StmtWalker::walk_expr(self, argument);
+            }
+        }
+    }
+
+    fn walk_unfold(&mut self, ast::Unfold { arguments, .. }: &ast::Unfold) {
+        for argument in arguments {
+            if !self.is_struct_place(argument) {
+                ast::StmtWalker::walk_expr(self, argument);
+            }
+        }
+    }
 }
 
 /// StmtFolder and ExprFolder used to purify local variables
@@ -116,6 +296,8 @@ impl ast::StmtWalker for PurifiableVariableCollector {
 struct Purifier<'a> {
     /// names of local variables that can be purified
     targets: BTreeSet<String>,
+    /// names of struct local variables that can be purified, with their fields
+    struct_targets: HashMap<String, Vec<Field>>,
     /// Viper predicates.
     predicates: &'a [ast::Predicate],
 }
@@ -129,9 +311,57 @@ impl<'a> Purifier<'a> {
 
         Purifier {
             targets,
+            struct_targets: c.struct_vars,
             predicates,
         }
     }
+    /// Whether `expr` is a purified variable or a field of a purified struct
+    /// variable, whose predicates and field permissions are removed.
+    fn is_purified_place(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Local(ast::Local { variable, .. }) => {
+                self.targets.contains(&variable.name)
+                    || self.struct_targets.contains_key(&variable.name)
+            }
+            Expr::Field(ast::FieldExpr {
+                base: box Expr::Local(ast::Local { variable, .. }),
+                ..
+            }) => self.struct_targets.contains_key(&variable.name),
+            _ => false,
+        }
+    }
+    /// Replace a havoc of a purified struct variable with havocs of the
+    /// variables of its fields.
+    fn havoc_struct_fields(&self, stmt: &Stmt) -> Option<Vec<Stmt>> {
+        if let Stmt::MethodCall(ast::MethodCall {
+            method_name,
+            targets,
+            ..
+        }) = stmt
+        {
+            if method_name.starts_with("builtin$havoc") && targets.len() == 1 {
+                let fields = self.struct_targets.get(&targets[0].name)?;
+                let havocs = fields
+                    .iter()
+                    .map(|field| {
+                        let field_var = get_field_var(&targets[0].name, field);
+                        let method_name = match field_var.typ {
+                            Type::Bool => "builtin$havoc_bool",
+                            Type::Int => "builtin$havoc_int",
+                            ref typ => unreachable!("{:?}", typ),
+                        };
+                        Stmt::MethodCall(ast::MethodCall {
+                            method_name: method_name.to_string(),
+                            arguments: vec![],
+                            targets: vec![field_var],
+                        })
+                    })
+                    .collect();
+                return Some(havocs);
+            }
+        }
+        None
+    }
     /// Get the body of the struct predicate. If the predicate does not exist,
     /// or is a non-struct predicate, returns `None`.
     fn find_predicate(&self, predicate_type: &Type) -> Option<&Expr> {
@@ -223,15 +453,12 @@ impl<'a> ast::StmtFolder for Purifier<'a> {
             position,
         }: ast::Fold,
     ) -> Stmt {
-        if let [Expr::Local(ast::Local { variable: l, .. })] = arguments.as_slice() {
-            if self.targets.contains(&l.name) {
+        if let [place] = arguments.as_slice() {
+            if self.is_purified_place(place) {
                 if let Some(predicate) = self.find_predicate(&predicate_type) {
                     let purified_predicate = predicate
                         .clone()
-                        .replace_place(
-                            &LocalVar::new("self", predicate_type).into(),
-                            &l.clone().into(),
-                        )
+                        .replace_place(&LocalVar::new("self", predicate_type).into(), place)
                         .purify();
                     return Stmt::Assert(ast::Assert {
                         expr: self.fold_expr(purified_predicate),
@@ -261,15 +488,12 @@ impl<'a> ast::StmtFolder for Purifier<'a> {
             enum_variant,
         }: ast::Unfold,
     ) -> Stmt {
-        if let [Expr::Local(ast::Local { variable: l, .. })] = arguments.as_slice() {
-            if self.targets.contains(&l.name) {
+        if let [place] = arguments.as_slice() {
+            if self.is_purified_place(place) {
                 if let Some(predicate) = self.find_predicate(&predicate_type) {
                     let purified_predicate = predicate
                         .clone()
-                        .replace_place(
-                            &LocalVar::new("self", predicate_type).into(),
-                            &l.clone().into(),
-                        )
+                        .replace_place(&LocalVar::new("self", predicate_type).into(), place)
                         .purify();
                     return Stmt::Inhale(ast::Inhale {
                         expr: self.fold_expr(purified_predicate),
@@ -309,6 +533,20 @@ impl<'a> ast::ExprFolder for Purifier<'a> {
             position,
         }: ast::FieldExpr,
     ) -> Expr {
+        if let Expr::Field(ast::FieldExpr {
+            base: box Expr::Local(ast::Local { variable: l, .. }),
+            field: struct_field,
+            position: struct_field_position,
+        }) = &*base
+        {
+            if self.struct_targets.contains_key(&l.name) {
+                // `x.f.val_int` becomes `x$f`.
+                return Expr::local_with_pos(
+                    get_field_var(&l.name, struct_field),
+                    *struct_field_position,
+                );
+            }
+        }
         let rec = self.fold_boxed(base);
 
         if let Expr::Local(ast::Local {
@@ -337,13 +575,8 @@ impl<'a> ast::ExprFolder for Purifier<'a> {
             position,
         }: ast::PredicateAccessPredicate,
     ) -> Expr {
-        if let Expr::Local(ast::Local {
-            variable: local, ..
-        }) = *argument.clone()
-        {
-            if self.targets.contains(&local.name) {
-                return true.into();
-            }
+        if self.is_purified_place(&argument) {
+            return true.into();
         }
 
         Expr::PredicateAccessPredicate(ast::PredicateAccessPredicate {
@@ -362,11 +595,9 @@ impl<'a> ast::ExprFolder for Purifier<'a> {
             position,
         }: ast::FieldAccessPredicate,
     ) -> Expr {
-        if let Expr::Field(ast::FieldExpr { base, .. }) = *receiver.clone() {
-            if let Expr::Local(ast::Local { variable: l, .. }) = *base {
-                if self.targets.contains(&l.name) {
-                    return true.into();
-                }
+        if let Expr::Field(ast::FieldExpr { base, .. }) = &*receiver {
+            if self.is_purified_place(base) {
+                return true.into();
             }
         }
 
@@ -388,11 +619,8 @@ impl<'a> ast::ExprFolder for Purifier<'a> {
             position,
         }: ast::Unfolding,
     ) -> Expr {
-        if let [Expr::Local(ast::Local {
-            variable: local, ..
-        })] = arguments.as_slice()
-        {
-            if self.targets.contains(&local.name) {
+        if let [place] = arguments.as_slice() {
+            if self.is_purified_place(place) {
                 return ast::ExprFolder::fold(self, *base);
             }
         }
@@ -311,6 +311,10 @@ impl<'v> ToViper<'v, viper::Expr<'v>> for PermAmount {
                 PermAmount::Write.to_viper(ast),
                 PermAmount::Read.to_viper(ast),
             ),
+            PermAmount::Fraction(fraction) => ast.fractional_perm(
+                ast.int_lit(fraction.numerator() as i64),
+                ast.int_lit(fraction.denominator() as i64),
+            ),
         }
     }
 }
@@ -725,9 +729,11 @@ impl<'v> ToViper<'v, viper::Predicate<'v>> for Predicate {
 
 impl<'v> ToViper<'v, viper::Predicate<'v>> for StructPredicate {
     fn to_viper(&self, ast: &AstFactory<'v>) -> viper::Predicate<'v> {
+        let mut params = vec![self.this.clone()];
+        params.extend(self.extra_params.iter().cloned());
         ast.predicate(
             &self.name,
-            &[self.this.to_viper_decl(ast)],
+            &params.to_viper_decl(ast),
             self.body.as_ref().map(|b| b.to_viper(ast)),
         )
     }
@@ -735,9 +741,11 @@ impl<'v> ToViper<'v, viper::Predicate<'v>> for StructPredicate {
 
 impl<'v> ToViper<'v, viper::Predicate<'v>> for EnumPredicate {
     fn to_viper(&self, ast: &AstFactory<'v>) -> viper::Predicate<'v> {
+        let mut params = vec![self.this.clone()];
+        params.extend(self.extra_params.iter().cloned());
         ast.predicate(
             &self.name,
-            &[self.this.to_viper_decl(ast)],
+            &params.to_viper_decl(ast),
             Some(self.body().to_viper(ast)),
         )
     }
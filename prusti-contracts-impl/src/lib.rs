@@ -33,11 +33,109 @@ pub fn trusted(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
     tokens
 }
 
+#[proc_macro_attribute]
+pub fn lemma(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    // Lemmas are only called through `apply_lemma!`, which is erased, and
+    // their body is usually empty.
+    let mut result: TokenStream = "#[allow(dead_code, unused_variables)]".parse().unwrap();
+    result.extend(tokens);
+    result
+}
+
+#[proc_macro_attribute]
+pub fn law(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    // Like lemmas, the default bodies and implementations of laws are usually
+    // empty.
+    let mut result: TokenStream = "#[allow(unused_variables)]".parse().unwrap();
+    result.extend(tokens);
+    result
+}
+
+#[proc_macro_attribute]
+pub fn bounded(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn nonlinear_arithmetic(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn allow_overflow(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    prusti_specs::allow_overflow_runtime(tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn bitwise_arithmetic(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn transition(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn opaque(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn inline_spec(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn never_inline_spec(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn no_auto_frame(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn explicit_expiry(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn insert_runtime_check(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    prusti_specs::insert_runtime_check(attr.into(), tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn apply_lemma(_tokens: TokenStream) -> TokenStream {
+    TokenStream::new()
+}
+
+#[proc_macro]
+pub fn wrapping(tokens: TokenStream) -> TokenStream {
+    prusti_specs::wrapping_runtime(tokens.into()).into()
+}
+
 #[proc_macro]
 pub fn body_invariant(_tokens: TokenStream) -> TokenStream {
     TokenStream::new()
 }
 
+#[proc_macro]
+pub fn yield_invariant(_tokens: TokenStream) -> TokenStream {
+    TokenStream::new()
+}
+
+#[proc_macro]
+pub fn resume_requires(_tokens: TokenStream) -> TokenStream {
+    TokenStream::new()
+}
+
+#[proc_macro]
+pub fn prusti_calc(_tokens: TokenStream) -> TokenStream {
+    TokenStream::new()
+}
+
 #[proc_macro_attribute]
 pub fn refine_trait_spec(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
     tokens
@@ -52,3 +150,43 @@ pub fn extern_spec(_attr: TokenStream, _tokens: TokenStream) -> TokenStream {
 pub fn predicate(_tokens: TokenStream) -> TokenStream {
     TokenStream::new()
 }
+
+#[proc_macro_attribute]
+pub fn lock_invariant(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn message_invariant(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro]
+pub fn atomic_invariant(tokens: TokenStream) -> TokenStream {
+    prusti_specs::atomic_invariant(tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn typestate(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn refined(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn invariant(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn symbolic(_attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    tokens
+}
+
+#[proc_macro_attribute]
+pub fn domain(_attr: TokenStream, _tokens: TokenStream) -> TokenStream {
+    TokenStream::new()
+}
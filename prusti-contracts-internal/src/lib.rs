@@ -38,11 +38,111 @@ pub fn trusted(attr: TokenStream, tokens: TokenStream) -> TokenStream {
     rewrite_prusti_attributes(SpecAttributeKind::Trusted, attr.into(), tokens.into()).into()
 }
 
+#[proc_macro_attribute]
+pub fn lemma(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    rewrite_prusti_attributes(SpecAttributeKind::Lemma, attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn law(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    rewrite_prusti_attributes(SpecAttributeKind::Law, attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn bounded(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    rewrite_prusti_attributes(SpecAttributeKind::Bounded, attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn nonlinear_arithmetic(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    rewrite_prusti_attributes(
+        SpecAttributeKind::NonlinearArithmetic,
+        attr.into(),
+        tokens.into(),
+    )
+    .into()
+}
+
+#[proc_macro_attribute]
+pub fn allow_overflow(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    rewrite_prusti_attributes(SpecAttributeKind::AllowOverflow, attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn bitwise_arithmetic(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    rewrite_prusti_attributes(SpecAttributeKind::BitwiseArithmetic, attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn transition(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    rewrite_prusti_attributes(SpecAttributeKind::Transition, attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn opaque(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    rewrite_prusti_attributes(SpecAttributeKind::Opaque, attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn inline_spec(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    rewrite_prusti_attributes(SpecAttributeKind::InlineSpec, attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn never_inline_spec(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    rewrite_prusti_attributes(SpecAttributeKind::NeverInlineSpec, attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn no_auto_frame(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    rewrite_prusti_attributes(SpecAttributeKind::NoAutoFrame, attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn explicit_expiry(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    rewrite_prusti_attributes(SpecAttributeKind::ExplicitExpiry, attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn insert_runtime_check(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    rewrite_prusti_attributes(
+        SpecAttributeKind::InsertRuntimeCheck,
+        attr.into(),
+        tokens.into(),
+    )
+    .into()
+}
+
+#[proc_macro]
+pub fn apply_lemma(tokens: TokenStream) -> TokenStream {
+    prusti_specs::apply_lemma(tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn wrapping(tokens: TokenStream) -> TokenStream {
+    prusti_specs::wrapping(tokens.into()).into()
+}
+
 #[proc_macro]
 pub fn body_invariant(tokens: TokenStream) -> TokenStream {
     prusti_specs::body_invariant(tokens.into()).into()
 }
 
+#[proc_macro]
+pub fn yield_invariant(tokens: TokenStream) -> TokenStream {
+    prusti_specs::yield_invariant(tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn resume_requires(tokens: TokenStream) -> TokenStream {
+    prusti_specs::resume_requires(tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn prusti_calc(tokens: TokenStream) -> TokenStream {
+    prusti_specs::prusti_calc(tokens.into()).into()
+}
+
 #[proc_macro]
 pub fn closure(tokens: TokenStream) -> TokenStream {
     prusti_specs::closure(tokens.into(), false).into()
@@ -62,3 +162,43 @@ pub fn extern_spec(attr: TokenStream, tokens: TokenStream) -> TokenStream {
 pub fn predicate(tokens: TokenStream) -> TokenStream {
     prusti_specs::predicate(tokens.into()).into()
 }
+
+#[proc_macro_attribute]
+pub fn lock_invariant(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    prusti_specs::lock_invariant(attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn message_invariant(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    prusti_specs::message_invariant(attr.into(), tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn atomic_invariant(tokens: TokenStream) -> TokenStream {
+    prusti_specs::atomic_invariant(tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn typestate(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    prusti_specs::typestate(attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn refined(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    prusti_specs::refined(attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn invariant(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    prusti_specs::invariant(attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn symbolic(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    prusti_specs::symbolic(attr.into(), tokens.into()).into()
+}
+
+#[proc_macro_attribute]
+pub fn domain(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    prusti_specs::domain(attr.into(), tokens.into()).into()
+}
@@ -19,3 +19,28 @@ pub fn test4() {
         body_invariant!(true)
     }
 }
+
+#[allow_overflow]
+pub fn test5(a: u8, b: u8) -> u8 {
+    let mut c = a * b;
+    c += a;
+    assert!(c - b == 251 || a != 255 || b != 2);
+    c - b
+}
+
+pub fn test6(a: i8) -> i8 {
+    let b = wrapping!(a - 1);
+    wrapping! {
+        let c = b * 2;
+        c + a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn wrapping_arithmetic() {
+        assert_eq!(super::test5(255, 2), 251);
+        assert_eq!(super::test6(-128), 126);
+    }
+}
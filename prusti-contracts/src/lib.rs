@@ -20,9 +20,81 @@ mod private {
     /// A macro for marking a function as trusted.
     pub use prusti_contracts_impl::trusted;
 
+    /// A macro for marking a function as a lemma, whose body is a proof of
+    /// its postcondition.
+    pub use prusti_contracts_impl::lemma;
+
+    /// A macro for declaring a law of a trait, a lemma that every
+    /// implementation of the trait is verified against.
+    pub use prusti_contracts_impl::law;
+
+    /// A macro for verifying a function by unrolling each of its loops a
+    /// bounded number of times instead of using loop invariants.
+    pub use prusti_contracts_impl::bounded;
+
+    /// A macro for enabling nonlinear arithmetic reasoning in the verification
+    /// of a function.
+    pub use prusti_contracts_impl::nonlinear_arithmetic;
+
+    /// A macro for allowing arithmetic operations in a function to overflow,
+    /// wrapping around instead.
+    pub use prusti_contracts_impl::allow_overflow;
+
+    /// A macro for encoding masks and shifts by constant powers of two in a
+    /// function as remainders, divisions and multiplications.
+    pub use prusti_contracts_impl::bitwise_arithmetic;
+
+    /// A macro for declaring the states that a method changes between,
+    /// written as `#[transition(A | B -> C)]`.
+    pub use prusti_contracts_impl::transition;
+
+    /// A macro for hiding the definition of a pure function, which is then
+    /// only known where it is exposed with `reveal!`.
+    pub use prusti_contracts_impl::opaque;
+
+    /// A macro for expanding the body of a pure function wherever it is
+    /// used, instead of relying on its definitional axiom.
+    pub use prusti_contracts_impl::inline_spec;
+
+    /// A macro for preventing the body of a pure function from being
+    /// expanded at its use sites.
+    pub use prusti_contracts_impl::never_inline_spec;
+
+    /// A macro for preventing Prusti from adding the fields of `self` that a
+    /// method does not modify to its postcondition.
+    pub use prusti_contracts_impl::no_auto_frame;
+
+    /// A macro for handling the references returned by a function with
+    /// explicit expiry obligations at the call sites instead of magic wands.
+    pub use prusti_contracts_impl::explicit_expiry;
+
+    /// A macro for checking the following precondition or postcondition at
+    /// runtime instead of verifying it.
+    pub use prusti_contracts_impl::insert_runtime_check;
+
+    /// A macro for applying a lemma, i.e. asserting its precondition and
+    /// assuming its postcondition.
+    pub use prusti_contracts_impl::apply_lemma;
+
+    /// A macro for allowing arithmetic operations in a block to overflow,
+    /// wrapping around instead.
+    pub use prusti_contracts_impl::wrapping;
+
     /// A macro for writing a loop body invariant.
     pub use prusti_contracts_impl::body_invariant;
 
+    /// A macro for writing an invariant of a generator, which must hold
+    /// whenever it yields.
+    pub use prusti_contracts_impl::yield_invariant;
+
+    /// A macro for writing a precondition of resuming a generator, which
+    /// may only mention its resume argument.
+    pub use prusti_contracts_impl::resume_requires;
+
+    /// A macro for writing a calculational proof, in which each line is
+    /// checked with the previous lines and the hints between them available.
+    pub use prusti_contracts_impl::prusti_calc;
+
     /// A macro for defining a closure with a specification.
     /// Note: this is a declarative macro defined in this crate
     /// because declarative macros can't be exported from
@@ -47,6 +119,39 @@ mod private {
     /// A macro for defining a predicate using prusti expression syntax instead
     /// of just Rust expressions.
     pub use prusti_contracts_impl::predicate;
+
+    /// A macro for attaching an invariant to a struct, which holds whenever
+    /// a value of the struct protected by a `Mutex` or a `RwLock` is not
+    /// borrowed from a guard.
+    pub use prusti_contracts_impl::lock_invariant;
+
+    /// A macro for attaching an invariant to a struct, which holds for every
+    /// value of the struct sent through a channel of `std::sync::mpsc`.
+    pub use prusti_contracts_impl::message_invariant;
+
+    /// A macro for declaring the states of a struct, which are changed by
+    /// the methods annotated with `#[transition(...)]`.
+    pub use prusti_contracts_impl::typestate;
+
+    /// A macro for defining a wrapper around an atomic integer or boolean
+    /// whose value always satisfies an invariant.
+    pub use prusti_contracts_impl::atomic_invariant;
+
+    /// A macro for refining a struct with a predicate, which is assumed for
+    /// the arguments of every function and checked for its result.
+    pub use prusti_contracts_impl::refined;
+
+    /// A macro for attaching an invariant to a struct on one of its `impl`
+    /// blocks, which is added to its refinements.
+    pub use prusti_contracts_impl::invariant;
+
+    /// A macro for declaring a constant whose value is unknown to the
+    /// verifier, except for the given constraint.
+    pub use prusti_contracts_impl::symbolic;
+
+    /// A macro for declaring a Viper domain, whose functions and axioms are
+    /// written as a module of function stubs and `axiom!` items.
+    pub use prusti_contracts_impl::domain;
 }
 
 #[cfg(feature = "prusti")]
@@ -69,9 +174,81 @@ mod private {
     /// A macro for marking a function as trusted.
     pub use prusti_contracts_internal::trusted;
 
+    /// A macro for marking a function as a lemma, whose body is a proof of
+    /// its postcondition.
+    pub use prusti_contracts_internal::lemma;
+
+    /// A macro for declaring a law of a trait, a lemma that every
+    /// implementation of the trait is verified against.
+    pub use prusti_contracts_internal::law;
+
+    /// A macro for verifying a function by unrolling each of its loops a
+    /// bounded number of times instead of using loop invariants.
+    pub use prusti_contracts_internal::bounded;
+
+    /// A macro for enabling nonlinear arithmetic reasoning in the verification
+    /// of a function.
+    pub use prusti_contracts_internal::nonlinear_arithmetic;
+
+    /// A macro for allowing arithmetic operations in a function to overflow,
+    /// wrapping around instead.
+    pub use prusti_contracts_internal::allow_overflow;
+
+    /// A macro for encoding masks and shifts by constant powers of two in a
+    /// function as remainders, divisions and multiplications.
+    pub use prusti_contracts_internal::bitwise_arithmetic;
+
+    /// A macro for declaring the states that a method changes between,
+    /// written as `#[transition(A | B -> C)]`.
+    pub use prusti_contracts_internal::transition;
+
+    /// A macro for hiding the definition of a pure function, which is then
+    /// only known where it is exposed with `reveal!`.
+    pub use prusti_contracts_internal::opaque;
+
+    /// A macro for expanding the body of a pure function wherever it is
+    /// used, instead of relying on its definitional axiom.
+    pub use prusti_contracts_internal::inline_spec;
+
+    /// A macro for preventing the body of a pure function from being
+    /// expanded at its use sites.
+    pub use prusti_contracts_internal::never_inline_spec;
+
+    /// A macro for preventing Prusti from adding the fields of `self` that a
+    /// method does not modify to its postcondition.
+    pub use prusti_contracts_internal::no_auto_frame;
+
+    /// A macro for handling the references returned by a function with
+    /// explicit expiry obligations at the call sites instead of magic wands.
+    pub use prusti_contracts_internal::explicit_expiry;
+
+    /// A macro for checking the following precondition or postcondition at
+    /// runtime instead of verifying it.
+    pub use prusti_contracts_internal::insert_runtime_check;
+
+    /// A macro for applying a lemma, i.e. asserting its precondition and
+    /// assuming its postcondition.
+    pub use prusti_contracts_internal::apply_lemma;
+
+    /// A macro for allowing arithmetic operations in a block to overflow,
+    /// wrapping around instead.
+    pub use prusti_contracts_internal::wrapping;
+
     /// A macro for writing a loop body invariant.
     pub use prusti_contracts_internal::body_invariant;
 
+    /// A macro for writing an invariant of a generator, which must hold
+    /// whenever it yields.
+    pub use prusti_contracts_internal::yield_invariant;
+
+    /// A macro for writing a precondition of resuming a generator, which
+    /// may only mention its resume argument.
+    pub use prusti_contracts_internal::resume_requires;
+
+    /// A macro for writing a calculational proof, in which each line is
+    /// checked with the previous lines and the hints between them available.
+    pub use prusti_contracts_internal::prusti_calc;
+
     /// A macro for defining a closure with a specification.
     pub use prusti_contracts_internal::closure;
 
@@ -84,6 +261,39 @@ mod private {
     /// A macro for defining a predicate using prusti expression syntax instead
     /// of just Rust expressions.
     pub use prusti_contracts_internal::predicate;
+
+    /// A macro for attaching an invariant to a struct, which holds whenever
+    /// a value of the struct protected by a `Mutex` or a `RwLock` is not
+    /// borrowed from a guard.
+    pub use prusti_contracts_internal::lock_invariant;
+
+    /// A macro for attaching an invariant to a struct, which holds for every
+    /// value of the struct sent through a channel of `std::sync::mpsc`.
+    pub use prusti_contracts_internal::message_invariant;
+
+    /// A macro for declaring the states of a struct, which are changed by
+    /// the methods annotated with `#[transition(...)]`.
+    pub use prusti_contracts_internal::typestate;
+
+    /// A macro for defining a wrapper around an atomic integer or boolean
+    /// whose value always satisfies an invariant.
+    pub use prusti_contracts_internal::atomic_invariant;
+
+    /// A macro for refining a struct with a predicate, which is assumed for
+    /// the arguments of every function and checked for its result.
+    pub use prusti_contracts_internal::refined;
+
+    /// A macro for attaching an invariant to a struct on one of its `impl`
+    /// blocks, which is added to its refinements.
+    pub use prusti_contracts_internal::invariant;
+
+    /// A macro for declaring a constant whose value is unknown to the
+    /// verifier, except for the given constraint.
+    pub use prusti_contracts_internal::symbolic;
+
+    /// A macro for declaring a Viper domain, whose functions and axioms are
+    /// written as a module of function stubs and `axiom!` items.
+    pub use prusti_contracts_internal::domain;
 }
 
 
@@ -99,6 +309,12 @@ pub fn old<T>(arg: T) -> T {
     arg
 }
 
+/// This function is used in loop body invariants to evaluate an expression
+/// in the context just before the first iteration of the loop.
+pub fn on_entry<T>(arg: T) -> T {
+    arg
+}
+
 pub fn forall<T, F>(_trigger_set: T, _closure: F) -> bool {
     true
 }
@@ -107,4 +323,1103 @@ pub fn exists<T, F>(_trigger_set: T, _closure: F) -> bool {
     true
 }
 
+/// This function is called when a contract marked with
+/// `#[insert_runtime_check]` does not hold at runtime. Prusti does not verify
+/// the code after its calls, so the checked contract is assumed instead.
+pub fn runtime_check_failed(message: &str) -> ! {
+    panic!("{}", message)
+}
+
+/// This function is used by `reveal!` to expose the definition of an opaque
+/// pure function.
+pub fn reveal<F>(_function: F) {}
+
+/// A macro for exposing the definition of the `#[opaque]` pure function
+/// `$function` in the rest of the enclosing method, written as `reveal!(f)`.
+#[macro_export]
+macro_rules! reveal {
+    ($function:path) => {
+        $crate::reveal($function)
+    };
+}
+
+/// The sum of `term(i)` for all `i` in `range`, for use in specifications.
+pub fn sum<T, F: Fn(T) -> R, R>(_range: core::ops::Range<T>, _term: F) -> R {
+    unreachable!()
+}
+
+/// The number of `i` in `range` for which `predicate(i)` holds, for use in
+/// specifications.
+pub fn count<T, F: Fn(T) -> bool>(_range: core::ops::Range<T>, _predicate: F) -> usize {
+    unreachable!()
+}
+
+/// The maximum of `term(i)` for all `i` in the non-empty `range`, for use in
+/// specifications.
+pub fn max<T, F: Fn(T) -> R, R>(_range: core::ops::Range<T>, _term: F) -> R {
+    unreachable!()
+}
+
+/// The index of the variant of the enum `value`, in the order in which the
+/// variants are declared, for use in specifications. It does not depend on
+/// the values of the discriminants, so `discriminant_of(x) ==
+/// old(discriminant_of(x))` states that the variant of `x` did not change.
+pub fn discriminant_of<T>(_value: &T) -> usize {
+    unreachable!()
+}
+
+/// This function is used by `unchanged!` to state that the fields of the
+/// value behind a reference, except the fields borrowed in the tuple
+/// `except`, did not change.
+pub fn unchanged<T, E>(_value: &T, _except: E) -> bool {
+    unreachable!()
+}
+
+/// A macro for stating in a postcondition that the fields of the struct
+/// behind the reference `$value` have the same value as before the call,
+/// written as `unchanged!(self)`. The fields that may change are listed after
+/// `except`, as in `unchanged!(self except self.len, self.capacity)`.
+#[macro_export]
+macro_rules! unchanged {
+    ($value:ident) => {
+        $crate::unchanged(&*$value, ())
+    };
+    ($value:ident except $($field:expr),+ $(,)?) => {
+        $crate::unchanged(&*$value, ($(&$field,)+))
+    };
+}
+
+/// A machine integer type, which can be converted to and from the ghost
+/// integers `Int` and `Nat`.
+pub trait MachineInt: Copy {
+    #[doc(hidden)]
+    fn to_i128(self) -> i128;
+    #[doc(hidden)]
+    fn from_i128(value: i128) -> Self;
+}
+
+macro_rules! machine_int {
+    ($($t:ty),*) => {
+        $(
+            impl MachineInt for $t {
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+                fn from_i128(value: i128) -> Self {
+                    <$t>::try_from(value).unwrap()
+                }
+            }
+        )*
+    };
+}
+
+machine_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+/// A ghost integer, which Prusti encodes as an unbounded mathematical integer
+/// and whose arithmetic operations therefore never overflow. At runtime, it is
+/// backed by an `i128`. Converting it to a machine integer with `to` requires
+/// that the value fits into the target type.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Int(i128);
+
+impl Int {
+    /// The ghost integer with the value of the machine integer `value`.
+    pub fn new<T: MachineInt>(value: T) -> Self {
+        Int(value.to_i128())
+    }
+
+    /// The value as the machine integer type `T`, which must contain it.
+    pub fn to<T: MachineInt>(self) -> T {
+        T::from_i128(self.0)
+    }
+
+    /// The value as a natural number, which requires it to be non-negative.
+    pub fn to_nat(self) -> Nat {
+        assert!(self.0 >= 0);
+        Nat(self.0)
+    }
+}
+
+/// A ghost natural number, which Prusti encodes as a non-negative unbounded
+/// mathematical integer. Its arithmetic operations never overflow, and a
+/// subtraction requires that the result is not negative. At runtime, it is
+/// backed by an `i128`.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Nat(i128);
+
+impl Nat {
+    /// The ghost natural number with the value of the machine integer
+    /// `value`, which must not be negative.
+    pub fn new<T: MachineInt>(value: T) -> Self {
+        Int::new(value).to_nat()
+    }
+
+    /// The value as the machine integer type `T`, which must contain it.
+    pub fn to<T: MachineInt>(self) -> T {
+        T::from_i128(self.0)
+    }
+
+    /// The value as a ghost integer.
+    pub fn to_int(self) -> Int {
+        Int(self.0)
+    }
+}
+
+macro_rules! ghost_int_ops {
+    ($t:ident) => {
+        impl core::ops::Add for $t {
+            type Output = $t;
+            fn add(self, other: $t) -> $t {
+                $t(self.0 + other.0)
+            }
+        }
+
+        impl core::ops::Mul for $t {
+            type Output = $t;
+            fn mul(self, other: $t) -> $t {
+                $t(self.0 * other.0)
+            }
+        }
+    };
+}
+
+ghost_int_ops!(Int);
+ghost_int_ops!(Nat);
+
+impl core::ops::Sub for Int {
+    type Output = Int;
+    fn sub(self, other: Int) -> Int {
+        Int(self.0 - other.0)
+    }
+}
+
+impl core::ops::Sub for Nat {
+    type Output = Nat;
+    fn sub(self, other: Nat) -> Nat {
+        Int(self.0 - other.0).to_nat()
+    }
+}
+
+impl core::ops::Neg for Int {
+    type Output = Int;
+    fn neg(self) -> Int {
+        Int(-self.0)
+    }
+}
+
+/// A macro for defining a module `$name` of lemmas about the multiplication,
+/// division, remainder and powers of values of the integer type `$t`. The
+/// lemmas can be applied with `apply_lemma!($name::mul_le_mul_left(a, b, c))`,
+/// or several at once with `apply_lemmas!`, to prove nonlinear properties
+/// without enabling nonlinear arithmetic. The lemmas themselves are proven
+/// with nonlinear arithmetic, and the lemmas about powers by unfolding `pow`
+/// or by induction.
+#[macro_export]
+macro_rules! nonlinear_lemmas {
+    ($name:ident, $t:ty) => {
+        #[allow(dead_code, unused_comparisons)]
+        mod $name {
+            use $crate::*;
+
+            #[nonlinear_arithmetic]
+            #[lemma]
+            #[requires(0 <= a && 0 <= b)]
+            #[ensures(0 <= a * b)]
+            pub fn mul_nonneg(a: $t, b: $t) {}
+
+            #[nonlinear_arithmetic]
+            #[lemma]
+            #[requires(a <= b && 0 <= c)]
+            #[ensures(c * a <= c * b)]
+            pub fn mul_le_mul_left(a: $t, b: $t, c: $t) {}
+
+            #[nonlinear_arithmetic]
+            #[lemma]
+            #[requires(a <= b && 0 <= c)]
+            #[ensures(a * c <= b * c)]
+            pub fn mul_le_mul_right(a: $t, b: $t, c: $t) {}
+
+            #[nonlinear_arithmetic]
+            #[lemma]
+            #[requires(0 <= a && 0 < b)]
+            #[ensures(0 <= a / b && a / b <= a)]
+            pub fn div_le(a: $t, b: $t) {}
+
+            #[nonlinear_arithmetic]
+            #[lemma]
+            #[requires(0 <= a && a <= b && 0 < c)]
+            #[ensures(a / c <= b / c)]
+            pub fn div_le_div(a: $t, b: $t, c: $t) {}
+
+            #[nonlinear_arithmetic]
+            #[lemma]
+            #[requires(0 <= a && 0 < b)]
+            #[ensures(a == b * (a / b) + a % b)]
+            pub fn div_mod(a: $t, b: $t) {}
+
+            #[nonlinear_arithmetic]
+            #[lemma]
+            #[requires(0 <= a && 0 < b)]
+            #[ensures(0 <= a % b && a % b < b)]
+            pub fn mod_bounds(a: $t, b: $t) {}
+
+            #[nonlinear_arithmetic]
+            #[lemma]
+            #[requires(a < b && 0 < c)]
+            #[ensures(c * a < c * b)]
+            pub fn mul_lt_mul_left(a: $t, b: $t, c: $t) {}
+
+            #[nonlinear_arithmetic]
+            #[lemma]
+            #[requires(0 <= a && 0 < b)]
+            #[ensures(b * (a / b) <= a && a - b < b * (a / b))]
+            pub fn div_mul_bounds(a: $t, b: $t) {}
+
+            #[nonlinear_arithmetic]
+            #[lemma]
+            #[requires(0 <= a && 0 < b)]
+            #[ensures((a * b) / b == a && (a * b) % b == 0)]
+            pub fn mul_div_cancel(a: $t, b: $t) {}
+
+            /// The power `base` to the `exp`, wrapping around on overflow like
+            /// `wrapping_pow`, for use in specifications.
+            #[pure]
+            #[allow_overflow]
+            pub fn pow(base: $t, exp: u32) -> $t {
+                if exp == 0 {
+                    1
+                } else {
+                    base * pow(base, exp - 1)
+                }
+            }
+
+            #[lemma]
+            #[ensures(pow(base, 0) == 1 && pow(base, 1) == base)]
+            pub fn pow_base(base: $t) {}
+
+            #[lemma]
+            #[requires(exp < u32::MAX)]
+            #[requires(<$t>::MIN <= base * pow(base, exp) && base * pow(base, exp) <= <$t>::MAX)]
+            #[ensures(pow(base, exp + 1) == base * pow(base, exp))]
+            pub fn pow_succ(base: $t, exp: u32) {}
+
+            #[lemma(induction = exp)]
+            #[ensures(pow(1, exp) == 1)]
+            pub fn pow_one(exp: u32) {}
+        }
+    };
+}
+
+/// A macro for applying several lemmas of the module `$module`, written as
+/// `apply_lemmas!(u32_lemmas::{div_mod(a, b), mod_bounds(a, b)})`.
+#[macro_export]
+macro_rules! apply_lemmas {
+    ($module:ident :: { $($lemma:ident ( $($arg:expr),* $(,)? )),* $(,)? }) => {
+        $( $crate::apply_lemma!($module::$lemma($($arg),*)); )*
+    };
+}
+
+/// A macro for defining a wrapper `$name` around a `BinaryHeap` of integers,
+/// specified against the ghost multiset of its elements: `count(value)` is the
+/// number of occurrences of `value` in the heap and `top()` is the greatest
+/// element, or the least element of a heap of `Reverse<...>`. The wrapper
+/// provides `new`, `len`, `is_empty`, `push`, `pop` and `peek`, which take and
+/// return the integers themselves.
+///
+/// ```ignore
+/// binary_heap!(pub struct MaxHeap(u32));
+/// binary_heap!(pub struct MinHeap(Reverse<u32>));
+/// ```
+#[macro_export]
+macro_rules! binary_heap {
+    ($(#[$attr:meta])* $vis:vis struct $name:ident(Reverse<$t:ty>)) => {
+        $crate::binary_heap!(@common [$(#[$attr])* $vis $name] $t, ::core::cmp::Reverse<$t>, <=);
+
+        impl $name {
+            fn prusti_wrap(value: $t) -> ::core::cmp::Reverse<$t> {
+                ::core::cmp::Reverse(value)
+            }
+
+            fn prusti_unwrap(value: ::core::cmp::Reverse<$t>) -> $t {
+                value.0
+            }
+        }
+    };
+    ($(#[$attr:meta])* $vis:vis struct $name:ident($t:ty)) => {
+        $crate::binary_heap!(@common [$(#[$attr])* $vis $name] $t, $t, >=);
+
+        impl $name {
+            fn prusti_wrap(value: $t) -> $t {
+                value
+            }
+
+            fn prusti_unwrap(value: $t) -> $t {
+                value
+            }
+        }
+    };
+    (@common [$(#[$attr:meta])* $vis:vis $name:ident] $t:ty, $element:ty, $order:tt) => {
+        $(#[$attr])*
+        $vis struct $name(::std::collections::BinaryHeap<$element>);
+
+        const _: () = {
+            use $crate::*;
+
+            #[allow(clippy::new_without_default)]
+            impl $name {
+                #[pure]
+                #[trusted]
+                pub fn len(&self) -> usize {
+                    self.0.len()
+                }
+
+                #[pure]
+                #[trusted]
+                #[ensures(result <= self.len())]
+                pub fn count(&self, value: $t) -> usize {
+                    self.0
+                        .iter()
+                        .filter(|element| Self::prusti_unwrap(**element) == value)
+                        .count()
+                }
+
+                #[pure]
+                #[trusted]
+                #[requires(self.len() > 0)]
+                #[ensures(self.count(result) > 0)]
+                #[ensures(forall(|value: $t| self.count(value) > 0 ==> result $order value))]
+                pub fn top(&self) -> $t {
+                    Self::prusti_unwrap(*self.0.peek().unwrap())
+                }
+
+                #[pure]
+                #[ensures(result == (self.len() == 0))]
+                pub fn is_empty(&self) -> bool {
+                    self.len() == 0
+                }
+
+                #[pure]
+                #[trusted]
+                #[ensures(match result {
+                    Some(value) => self.len() > 0 && value == self.top(),
+                    None => self.len() == 0,
+                })]
+                pub fn peek(&self) -> Option<$t> {
+                    self.0.peek().map(|element| Self::prusti_unwrap(*element))
+                }
+
+                #[trusted]
+                #[ensures(result.len() == 0)]
+                #[ensures(forall(|value: $t| result.count(value) == 0))]
+                pub fn new() -> Self {
+                    Self(::std::collections::BinaryHeap::new())
+                }
+
+                #[trusted]
+                #[requires(self.len() < usize::MAX)]
+                #[ensures(self.len() == old(self.len()) + 1)]
+                #[ensures(self.count(value) == old(self.count(value)) + 1)]
+                #[ensures(forall(|other: $t| other != value ==>
+                    self.count(other) == old(self.count(other))))]
+                pub fn push(&mut self, value: $t) {
+                    self.0.push(Self::prusti_wrap(value))
+                }
+
+                #[trusted]
+                #[ensures(match result {
+                    Some(value) => old(self.len()) > 0 && value == old(self.top()) &&
+                        self.len() == old(self.len()) - 1 &&
+                        self.count(value) == old(self.count(value)) - 1 &&
+                        forall(|other: $t| other != value ==>
+                            self.count(other) == old(self.count(other))),
+                    None => old(self.len()) == 0 && self.len() == 0,
+                })]
+                pub fn pop(&mut self) -> Option<$t> {
+                    self.0.pop().map(Self::prusti_unwrap)
+                }
+            }
+        };
+    };
+}
+
+/// A macro for specifying `new`, `len`, `is_empty`, `contains_key`, `get`,
+/// `insert` and `remove` of `HashMap` and `BTreeMap` against the ghost set of
+/// their keys: `contains_key(&k)` holds if the map has an entry for `k`, and
+/// `len` is the number of its keys. The specifications are generic in the key
+/// and value types, whose values are compared by their snapshots, so the
+/// macro should be invoked once in a crate. The specification of `remove`
+/// requires a sized borrowed key type, for example, `&String` instead of
+/// `&str`.
+///
+/// ```ignore
+/// map_specs!();
+/// ```
+#[macro_export]
+macro_rules! map_specs {
+    () => {
+        const _: () = {
+            use $crate::*;
+            use ::std::{
+                borrow::Borrow,
+                collections::{BTreeMap, HashMap},
+                hash::{BuildHasher, Hash},
+            };
+
+            #[extern_spec]
+            impl<K, V> HashMap<K, V> {
+                #[ensures(result.len() == 0)]
+                #[ensures(forall(|k: K| !result.contains_key(&k)))]
+                pub fn new() -> HashMap<K, V>;
+            }
+
+            #[extern_spec]
+            impl<K, V, S> HashMap<K, V, S>
+            where
+                K: Eq + Hash,
+                S: BuildHasher,
+            {
+                #[pure]
+                pub fn len(&self) -> usize;
+
+                #[pure]
+                #[ensures(result == (self.len() == 0))]
+                pub fn is_empty(&self) -> bool;
+
+                #[pure]
+                #[ensures(result ==> self.len() > 0)]
+                pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+                where
+                    K: Borrow<Q>,
+                    Q: Hash + Eq;
+
+                #[ensures(matches!(result, Some(_)) == self.contains_key(k))]
+                pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+                where
+                    K: Borrow<Q>,
+                    Q: Hash + Eq;
+
+                #[requires(self.len() < usize::MAX)]
+                #[ensures(self.contains_key(&k))]
+                #[ensures(matches!(result, Some(_)) == old(self.contains_key(&k)))]
+                #[ensures(old(self.contains_key(&k)) ==> self.len() == old(self.len()))]
+                #[ensures(!old(self.contains_key(&k)) ==> self.len() == old(self.len()) + 1)]
+                #[ensures(forall(|other: K| other != k ==>
+                    self.contains_key(&other) == old(self.contains_key(&other))))]
+                pub fn insert(&mut self, k: K, v: V) -> Option<V>;
+
+                #[ensures(!self.contains_key(k))]
+                #[ensures(matches!(result, Some(_)) == old(self.contains_key(k)))]
+                #[ensures(old(self.contains_key(k)) ==> self.len() == old(self.len()) - 1)]
+                #[ensures(!old(self.contains_key(k)) ==> self.len() == old(self.len()))]
+                #[ensures(forall(|other: Q| other != *k ==>
+                    self.contains_key(&other) == old(self.contains_key(&other))))]
+                pub fn remove<Q>(&mut self, k: &Q) -> Option<V>
+                where
+                    K: Borrow<Q>,
+                    Q: Hash + Eq;
+            }
+
+            #[extern_spec]
+            impl<K, V> BTreeMap<K, V>
+            where
+                K: Ord,
+            {
+                #[ensures(result.len() == 0)]
+                #[ensures(forall(|k: K| !result.contains_key(&k)))]
+                pub fn new() -> BTreeMap<K, V>;
+
+                #[pure]
+                pub fn len(&self) -> usize;
+
+                #[pure]
+                #[ensures(result == (self.len() == 0))]
+                pub fn is_empty(&self) -> bool;
+
+                #[pure]
+                #[ensures(result ==> self.len() > 0)]
+                pub fn contains_key<Q: ?Sized>(&self, k: &Q) -> bool
+                where
+                    K: Borrow<Q>,
+                    Q: Ord;
+
+                #[ensures(matches!(result, Some(_)) == self.contains_key(k))]
+                pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+                where
+                    K: Borrow<Q>,
+                    Q: Ord;
+
+                #[requires(self.len() < usize::MAX)]
+                #[ensures(self.contains_key(&k))]
+                #[ensures(matches!(result, Some(_)) == old(self.contains_key(&k)))]
+                #[ensures(old(self.contains_key(&k)) ==> self.len() == old(self.len()))]
+                #[ensures(!old(self.contains_key(&k)) ==> self.len() == old(self.len()) + 1)]
+                #[ensures(forall(|other: K| other != k ==>
+                    self.contains_key(&other) == old(self.contains_key(&other))))]
+                pub fn insert(&mut self, k: K, v: V) -> Option<V>;
+
+                #[ensures(!self.contains_key(k))]
+                #[ensures(matches!(result, Some(_)) == old(self.contains_key(k)))]
+                #[ensures(old(self.contains_key(k)) ==> self.len() == old(self.len()) - 1)]
+                #[ensures(!old(self.contains_key(k)) ==> self.len() == old(self.len()))]
+                #[ensures(forall(|other: Q| other != *k ==>
+                    self.contains_key(&other) == old(self.contains_key(&other))))]
+                pub fn remove<Q>(&mut self, k: &Q) -> Option<V>
+                where
+                    K: Borrow<Q>,
+                    Q: Ord;
+            }
+        };
+    };
+}
+
+/// A macro for defining a wrapper `$name` around a `VecDeque` of integers,
+/// specified against the ghost sequence of its elements: `lookup(index)` is
+/// the element at `index`, counted from the front. The wrapper provides `new`,
+/// `len`, `is_empty`, `get`, `push_front`, `push_back`, `pop_front` and
+/// `pop_back`, which take and return the integers themselves.
+///
+/// ```ignore
+/// vec_deque!(pub struct Queue(usize));
+/// ```
+#[macro_export]
+macro_rules! vec_deque {
+    ($(#[$attr:meta])* $vis:vis struct $name:ident($t:ty)) => {
+        $(#[$attr])*
+        $vis struct $name(::std::collections::VecDeque<$t>);
+
+        const _: () = {
+            use $crate::*;
+
+            #[allow(clippy::new_without_default)]
+            impl $name {
+                #[pure]
+                #[trusted]
+                pub fn len(&self) -> usize {
+                    self.0.len()
+                }
+
+                #[pure]
+                #[trusted]
+                #[requires(index < self.len())]
+                pub fn lookup(&self, index: usize) -> $t {
+                    self.0[index]
+                }
+
+                #[pure]
+                #[ensures(result == (self.len() == 0))]
+                pub fn is_empty(&self) -> bool {
+                    self.len() == 0
+                }
+
+                #[pure]
+                #[trusted]
+                #[ensures(match result {
+                    Some(value) => index < self.len() && value == self.lookup(index),
+                    None => index >= self.len(),
+                })]
+                pub fn get(&self, index: usize) -> Option<$t> {
+                    self.0.get(index).copied()
+                }
+
+                #[trusted]
+                #[ensures(result.len() == 0)]
+                pub fn new() -> Self {
+                    Self(::std::collections::VecDeque::new())
+                }
+
+                #[trusted]
+                #[requires(self.len() < usize::MAX)]
+                #[ensures(self.len() == old(self.len()) + 1)]
+                #[ensures(self.lookup(0) == value)]
+                #[ensures(forall(|i: usize| i < old(self.len()) ==>
+                    self.lookup(i + 1) == old(self.lookup(i))))]
+                pub fn push_front(&mut self, value: $t) {
+                    self.0.push_front(value)
+                }
+
+                #[trusted]
+                #[requires(self.len() < usize::MAX)]
+                #[ensures(self.len() == old(self.len()) + 1)]
+                #[ensures(self.lookup(old(self.len())) == value)]
+                #[ensures(forall(|i: usize| i < old(self.len()) ==>
+                    self.lookup(i) == old(self.lookup(i))))]
+                pub fn push_back(&mut self, value: $t) {
+                    self.0.push_back(value)
+                }
+
+                #[trusted]
+                #[ensures(match result {
+                    Some(value) => old(self.len()) > 0 && value == old(self.lookup(0)) &&
+                        self.len() == old(self.len()) - 1 &&
+                        forall(|i: usize| i < self.len() ==>
+                            self.lookup(i) == old(self.lookup(i + 1))),
+                    None => old(self.len()) == 0 && self.len() == 0,
+                })]
+                pub fn pop_front(&mut self) -> Option<$t> {
+                    self.0.pop_front()
+                }
+
+                #[trusted]
+                #[ensures(match result {
+                    Some(value) => old(self.len()) > 0 &&
+                        value == old(self.lookup(self.len() - 1)) &&
+                        self.len() == old(self.len()) - 1 &&
+                        forall(|i: usize| i < self.len() ==>
+                            self.lookup(i) == old(self.lookup(i))),
+                    None => old(self.len()) == 0 && self.len() == 0,
+                })]
+                pub fn pop_back(&mut self) -> Option<$t> {
+                    self.0.pop_back()
+                }
+            }
+        };
+    };
+}
+
+/// A macro for specifying `new`, `len`, `is_empty`, `clear`, `push`, `pop`,
+/// `swap_remove`, `truncate`, `retain`, `append` and `extend_from_slice` of
+/// `Vec` against the ghost sequence of its elements. The macro defines the
+/// trusted pure function `vec_lookup(&vec, index)`, which requires
+/// `index < vec.len()` and returns the element at `index`. The element-level
+/// specifications need elements that implement `Copy` and `PartialEq`. The
+/// crate that invokes the macro, once, must enable the `allocator_api`
+/// feature, because the methods are specified for every allocator.
+///
+/// ```ignore
+/// #![feature(allocator_api)]
+///
+/// vec_specs!();
+/// ```
+#[macro_export]
+macro_rules! vec_specs {
+    () => {
+        /// The element at `index` of `vec`.
+        #[trusted]
+        #[pure]
+        #[requires(index < vec.len())]
+        fn vec_lookup<T: Copy, A: ::std::alloc::Allocator>(
+            vec: &::std::vec::Vec<T, A>,
+            index: usize,
+        ) -> T {
+            vec[index]
+        }
+
+        const _: () = {
+            use $crate::*;
+            use ::std::alloc::Allocator;
+
+            #[extern_spec]
+            impl<T> Vec<T> {
+                #[ensures(result.len() == 0)]
+                pub fn new() -> Vec<T>;
+            }
+
+            #[extern_spec]
+            impl<T: Copy + PartialEq, A: Allocator> Vec<T, A> {
+                #[pure]
+                pub fn len(&self) -> usize;
+
+                #[pure]
+                #[ensures(result == (self.len() == 0))]
+                pub fn is_empty(&self) -> bool;
+
+                #[ensures(self.len() == 0)]
+                pub fn clear(&mut self);
+
+                #[requires(self.len() < usize::MAX)]
+                #[ensures(self.len() == old(self.len()) + 1)]
+                #[ensures(vec_lookup(self, old(self.len())) == value)]
+                #[ensures(forall(|i: usize| i < old(self.len()) ==>
+                    vec_lookup(self, i) == old(vec_lookup(self, i))))]
+                pub fn push(&mut self, value: T);
+
+                #[ensures(old(self.len()) == 0 ==> self.len() == 0 && matches!(result, None))]
+                #[ensures(old(self.len()) > 0 ==> self.len() == old(self.len()) - 1 &&
+                    result == Some(old(vec_lookup(self, self.len() - 1))))]
+                #[ensures(forall(|i: usize| i < self.len() ==>
+                    vec_lookup(self, i) == old(vec_lookup(self, i))))]
+                pub fn pop(&mut self) -> Option<T>;
+
+                /// Remove and return the element at `index`, which is replaced
+                /// by the last element.
+                #[requires(index < self.len())]
+                #[ensures(result == old(vec_lookup(self, index)))]
+                #[ensures(self.len() == old(self.len()) - 1)]
+                #[ensures(index < self.len() ==>
+                    vec_lookup(self, index) == old(vec_lookup(self, self.len() - 1)))]
+                #[ensures(forall(|i: usize| i < self.len() && i != index ==>
+                    vec_lookup(self, i) == old(vec_lookup(self, i))))]
+                pub fn swap_remove(&mut self, index: usize) -> T;
+
+                #[ensures(old(self.len()) <= len ==> self.len() == old(self.len()))]
+                #[ensures(len < old(self.len()) ==> self.len() == len)]
+                #[ensures(forall(|i: usize| i < self.len() ==>
+                    vec_lookup(self, i) == old(vec_lookup(self, i))))]
+                pub fn truncate(&mut self, len: usize);
+
+                /// Keep the elements for which `f` returns `true`, in their
+                /// order. Every kept element occurred in the old vector at an
+                /// index that is at least its new index, and every value
+                /// occurs at most as often as before.
+                #[ensures(self.len() <= old(self.len()))]
+                #[ensures(forall(|i: usize| i < self.len() ==>
+                    exists(|j: usize| i <= j && j < old(self.len()) &&
+                        vec_lookup(self, i) == old(vec_lookup(self, j)))))]
+                #[ensures(forall(|value: T|
+                    count(0..self.len(), |i: usize| vec_lookup(self, i) == value) <=
+                    old(count(0..self.len(), |i: usize| vec_lookup(self, i) == value))))]
+                pub fn retain<F>(&mut self, f: F)
+                where
+                    F: FnMut(&T) -> bool;
+
+                #[requires(self.len() <= usize::MAX - other.len())]
+                #[ensures(self.len() == old(self.len() + other.len()))]
+                #[ensures(other.len() == 0)]
+                #[ensures(forall(|i: usize| i < old(self.len()) ==>
+                    vec_lookup(self, i) == old(vec_lookup(self, i))))]
+                #[ensures(forall(|i: usize| i < old(other.len()) ==>
+                    vec_lookup(self, old(self.len()) + i) == old(vec_lookup(other, i))))]
+                pub fn append(&mut self, other: &mut Vec<T, A>);
+
+                #[requires(self.len() <= usize::MAX - other.len())]
+                #[ensures(self.len() == old(self.len()) + other.len())]
+                #[ensures(forall(|i: usize| i < old(self.len()) ==>
+                    vec_lookup(self, i) == old(vec_lookup(self, i))))]
+                #[ensures(forall(|i: usize| i < other.len() ==>
+                    vec_lookup(self, old(self.len()) + i) == other[i]))]
+                pub fn extend_from_slice(&mut self, other: &[T]);
+            }
+        };
+    };
+}
+
+/// A macro for defining a wrapper `$name` around an iterator of integers,
+/// specified against the ghost sequence of the elements that it still
+/// produces: `lookup(index)` is the element produced after `index` other
+/// elements. The wrapper provides the constructors `empty`, `once` and
+/// `repeat`, the adapters `take`, `skip`, `chain` and `zip`, and `next`.
+/// `zip` returns the wrapper `$pairs` around an iterator of pairs, whose
+/// elements are `first(index)` and `second(index)`.
+///
+/// ```ignore
+/// iterator!(pub struct Numbers(u32), pub struct NumberPairs);
+/// ```
+#[macro_export]
+macro_rules! iterator {
+    (
+        $(#[$attr:meta])* $vis:vis struct $name:ident($t:ty),
+        $(#[$pairs_attr:meta])* $pairs_vis:vis struct $pairs:ident $(,)?
+    ) => {
+        $(#[$attr])*
+        $vis struct $name(::std::boxed::Box<dyn ::std::iter::Iterator<Item = $t>>);
+
+        $(#[$pairs_attr])*
+        $pairs_vis struct $pairs(::std::boxed::Box<dyn ::std::iter::Iterator<Item = ($t, $t)>>);
+
+        const _: () = {
+            use $crate::*;
+
+            #[allow(unused_variables)]
+            impl $name {
+                #[pure]
+                #[trusted]
+                pub fn len(&self) -> usize {
+                    self.0.size_hint().0
+                }
+
+                #[pure]
+                #[trusted]
+                #[requires(index < self.len())]
+                pub fn lookup(&self, index: usize) -> $t {
+                    unreachable!()
+                }
+
+                #[trusted]
+                #[ensures(result.len() == 0)]
+                pub fn empty() -> Self {
+                    Self(::std::boxed::Box::new(::std::iter::empty()))
+                }
+
+                #[trusted]
+                #[ensures(result.len() == 1 && result.lookup(0) == value)]
+                pub fn once(value: $t) -> Self {
+                    Self(::std::boxed::Box::new(::std::iter::once(value)))
+                }
+
+                /// The iterator `std::iter::repeat(value).take(count)`.
+                #[trusted]
+                #[ensures(result.len() == count)]
+                #[ensures(forall(|i: usize| i < count ==> result.lookup(i) == value))]
+                pub fn repeat(value: $t, count: usize) -> Self {
+                    Self(::std::boxed::Box::new(::std::iter::repeat(value).take(count)))
+                }
+
+                #[trusted]
+                #[ensures(result.len() == if self.len() < count { self.len() } else { count })]
+                #[ensures(forall(|i: usize| i < result.len() ==>
+                    result.lookup(i) == self.lookup(i)))]
+                pub fn take(self, count: usize) -> Self {
+                    Self(::std::boxed::Box::new(self.0.take(count)))
+                }
+
+                #[trusted]
+                #[ensures(result.len() == if self.len() < count { 0 } else { self.len() - count })]
+                #[ensures(forall(|i: usize| i < result.len() ==>
+                    result.lookup(i) == self.lookup(i + count)))]
+                pub fn skip(self, count: usize) -> Self {
+                    Self(::std::boxed::Box::new(self.0.skip(count)))
+                }
+
+                #[trusted]
+                #[requires(self.len() <= usize::MAX - other.len())]
+                #[ensures(result.len() == self.len() + other.len())]
+                #[ensures(forall(|i: usize| i < self.len() ==>
+                    result.lookup(i) == self.lookup(i)))]
+                #[ensures(forall(|i: usize| i < other.len() ==>
+                    result.lookup(self.len() + i) == other.lookup(i)))]
+                pub fn chain(self, other: Self) -> Self {
+                    Self(::std::boxed::Box::new(self.0.chain(other.0)))
+                }
+
+                #[trusted]
+                #[ensures(result.len() ==
+                    if self.len() < other.len() { self.len() } else { other.len() })]
+                #[ensures(forall(|i: usize| i < result.len() ==>
+                    result.first(i) == self.lookup(i) && result.second(i) == other.lookup(i)))]
+                pub fn zip(self, other: Self) -> $pairs {
+                    $pairs(::std::boxed::Box::new(self.0.zip(other.0)))
+                }
+
+                #[trusted]
+                #[ensures(match result {
+                    Some(value) => old(self.len()) > 0 && value == old(self.lookup(0)) &&
+                        self.len() == old(self.len()) - 1 &&
+                        forall(|i: usize| i < self.len() ==>
+                            self.lookup(i) == old(self.lookup(i + 1))),
+                    None => old(self.len()) == 0 && self.len() == 0,
+                })]
+                pub fn next(&mut self) -> Option<$t> {
+                    self.0.next()
+                }
+            }
+
+            #[allow(unused_variables)]
+            impl $pairs {
+                #[pure]
+                #[trusted]
+                pub fn len(&self) -> usize {
+                    self.0.size_hint().0
+                }
+
+                #[pure]
+                #[trusted]
+                #[requires(index < self.len())]
+                pub fn first(&self, index: usize) -> $t {
+                    unreachable!()
+                }
+
+                #[pure]
+                #[trusted]
+                #[requires(index < self.len())]
+                pub fn second(&self, index: usize) -> $t {
+                    unreachable!()
+                }
+
+                #[trusted]
+                #[ensures(match result {
+                    Some((first, second)) => old(self.len()) > 0 &&
+                        first == old(self.first(0)) && second == old(self.second(0)) &&
+                        self.len() == old(self.len()) - 1 &&
+                        forall(|i: usize| i < self.len() ==>
+                            self.first(i) == old(self.first(i + 1)) &&
+                            self.second(i) == old(self.second(i + 1))),
+                    None => old(self.len()) == 0 && self.len() == 0,
+                })]
+                pub fn next(&mut self) -> Option<($t, $t)> {
+                    self.0.next()
+                }
+            }
+        };
+    };
+}
+
+/// A macro for specifying `sort`, `sort_unstable` and `binary_search` of
+/// slices: sorting permutes the elements into ascending order, and searching a
+/// sorted slice returns `Ok` of an index of an equal value or `Err` of the
+/// index where it could be inserted. The specifications hold for every element
+/// type and compare the elements with `Ord::cmp`, which is declared pure.
+/// Because an implementation of `Ord` is not checked to be lawful, the methods
+/// require that `cmp` is a total order on the compared values. For each of the
+/// integer types `$t`, `cmp` is specified to agree with the primitive
+/// comparisons, so that these laws are proven automatically. The macro should
+/// be invoked once in a crate.
+///
+/// ```ignore
+/// slice_specs!(i32, usize);
+/// ```
+#[macro_export]
+macro_rules! slice_specs {
+    ($($t:ty),* $(,)?) => {
+        const _: () = {
+            use $crate::*;
+            use ::std::cmp::Ordering;
+
+            #[extern_spec]
+            trait Ord {
+                #[pure]
+                fn cmp(&self, other: &Self) -> Ordering;
+            }
+
+            $(
+                #[extern_spec]
+                impl Ord for $t {
+                    #[pure]
+                    #[ensures(matches!(result, Ordering::Less) == (*self < *other))]
+                    #[ensures(matches!(result, Ordering::Equal) == (*self == *other))]
+                    #[ensures(matches!(result, Ordering::Greater) == (*self > *other))]
+                    fn cmp(&self, other: &$t) -> Ordering;
+                }
+            )*
+
+            #[extern_spec]
+            impl<T> [T] {
+                #[requires(forall(|i: usize, j: usize| i < self.len() && j < self.len() ==>
+                    matches!(self[i].cmp(&self[j]), Ordering::Less) ==
+                    matches!(self[j].cmp(&self[i]), Ordering::Greater)))]
+                #[requires(forall(|i: usize, j: usize, k: usize|
+                    i < self.len() && j < self.len() && k < self.len() &&
+                    !matches!(self[i].cmp(&self[j]), Ordering::Greater) &&
+                    !matches!(self[j].cmp(&self[k]), Ordering::Greater) ==>
+                    !matches!(self[i].cmp(&self[k]), Ordering::Greater)))]
+                #[ensures(self.len() == old(self.len()))]
+                #[ensures(forall(|i: usize, j: usize| i <= j && j < self.len() ==>
+                    !matches!(self[i].cmp(&self[j]), Ordering::Greater)))]
+                #[ensures(forall(|value: T|
+                    count(0..self.len(), |i: usize| self[i] == value) ==
+                    old(count(0..self.len(), |i: usize| self[i] == value))))]
+                pub fn sort(&mut self)
+                where
+                    T: Ord;
+
+                #[requires(forall(|i: usize, j: usize| i < self.len() && j < self.len() ==>
+                    matches!(self[i].cmp(&self[j]), Ordering::Less) ==
+                    matches!(self[j].cmp(&self[i]), Ordering::Greater)))]
+                #[requires(forall(|i: usize, j: usize, k: usize|
+                    i < self.len() && j < self.len() && k < self.len() &&
+                    !matches!(self[i].cmp(&self[j]), Ordering::Greater) &&
+                    !matches!(self[j].cmp(&self[k]), Ordering::Greater) ==>
+                    !matches!(self[i].cmp(&self[k]), Ordering::Greater)))]
+                #[ensures(self.len() == old(self.len()))]
+                #[ensures(forall(|i: usize, j: usize| i <= j && j < self.len() ==>
+                    !matches!(self[i].cmp(&self[j]), Ordering::Greater)))]
+                #[ensures(forall(|value: T|
+                    count(0..self.len(), |i: usize| self[i] == value) ==
+                    old(count(0..self.len(), |i: usize| self[i] == value))))]
+                pub fn sort_unstable(&mut self)
+                where
+                    T: Ord;
+
+                #[requires(forall(|i: usize, j: usize| i < self.len() && j < self.len() ==>
+                    matches!(self[i].cmp(&self[j]), Ordering::Less) ==
+                    matches!(self[j].cmp(&self[i]), Ordering::Greater)))]
+                #[requires(forall(|i: usize, j: usize, k: usize|
+                    i < self.len() && j < self.len() && k < self.len() &&
+                    !matches!(self[i].cmp(&self[j]), Ordering::Greater) &&
+                    !matches!(self[j].cmp(&self[k]), Ordering::Greater) ==>
+                    !matches!(self[i].cmp(&self[k]), Ordering::Greater)))]
+                #[requires(forall(|i: usize| i < self.len() ==>
+                    matches!(self[i].cmp(x), Ordering::Less) ==
+                    matches!(x.cmp(&self[i]), Ordering::Greater)))]
+                #[requires(forall(|i: usize, j: usize| i < self.len() && j < self.len() &&
+                    !matches!(self[i].cmp(&self[j]), Ordering::Greater) ==>
+                    (matches!(self[j].cmp(x), Ordering::Less) ==>
+                        matches!(self[i].cmp(x), Ordering::Less)) &&
+                    (matches!(x.cmp(&self[i]), Ordering::Less) ==>
+                        matches!(x.cmp(&self[j]), Ordering::Less))))]
+                #[requires(forall(|i: usize, j: usize| i <= j && j < self.len() ==>
+                    !matches!(self[i].cmp(&self[j]), Ordering::Greater)))]
+                #[ensures(match result {
+                    Ok(index) => index < self.len() &&
+                        matches!(self[index].cmp(x), Ordering::Equal),
+                    Err(index) => index <= self.len() &&
+                        forall(|i: usize| i < index ==>
+                            matches!(self[i].cmp(x), Ordering::Less)) &&
+                        forall(|i: usize| index <= i && i < self.len() ==>
+                            matches!(x.cmp(&self[i]), Ordering::Less)),
+                })]
+                pub fn binary_search(&self, x: &T) -> Result<usize, usize>
+                where
+                    T: Ord;
+            }
+        };
+    };
+}
+
+/// A macro for specifying the conversions of the unsigned integers `u16`,
+/// `u32` and `u64` to and from their little-endian and big-endian bytes, and
+/// `split_at` of byte slices. The byte at index `i` of `to_le_bytes` is
+/// `self / 256^i % 256`, and `from_le_bytes` is the sum of its bytes
+/// weighted by the same powers of `256`. `split_at` is specified for slices
+/// of every element type, so the macro should be invoked once in a crate.
+///
+/// ```ignore
+/// byte_specs!();
+/// ```
+#[macro_export]
+macro_rules! byte_specs {
+    () => {
+        $crate::byte_specs!(@int u16, 2, 0 1 1; 1 0 256);
+        $crate::byte_specs!(@int u32, 4, 0 3 1; 1 2 256; 2 1 65536; 3 0 16777216);
+        $crate::byte_specs!(@int u64, 8,
+            0 7 1;
+            1 6 256;
+            2 5 65536;
+            3 4 16777216;
+            4 3 4294967296;
+            5 2 1099511627776;
+            6 1 281474976710656;
+            7 0 72057594037927936
+        );
+
+        const _: () = {
+            use $crate::*;
+
+            #[extern_spec]
+            impl<T> [T] {
+                #[requires(mid <= self.len())]
+                #[ensures(result.0.len() == mid)]
+                #[ensures(result.1.len() == self.len() - mid)]
+                #[ensures(forall(|i: usize| i < mid ==> result.0[i] == self[i]))]
+                #[ensures(forall(|i: usize| i < self.len() - mid ==>
+                    result.1[i] == self[mid + i]))]
+                pub fn split_at(&self, mid: usize) -> (&[T], &[T])
+                where
+                    T: PartialEq;
+            }
+        };
+    };
+    // For each byte: its index in the little-endian bytes, its index in the
+    // big-endian bytes and its weight.
+    (@int $t:ty, $n:literal, $($le:literal $be:literal $weight:literal);+) => {
+        const _: () = {
+            use $crate::*;
+
+            #[extern_spec]
+            impl $t {
+                $(#[ensures(result[$le] as $t == self / $weight % 256)])+
+                pub fn to_le_bytes(self) -> [u8; $n];
+
+                $(#[ensures(result[$be] as $t == self / $weight % 256)])+
+                pub fn to_be_bytes(self) -> [u8; $n];
+
+                #[ensures(result == 0 $(+ bytes[$le] as $t * $weight)+)]
+                pub fn from_le_bytes(bytes: [u8; $n]) -> $t;
+
+                #[ensures(result == 0 $(+ bytes[$be] as $t * $weight)+)]
+                pub fn from_be_bytes(bytes: [u8; $n]) -> $t;
+            }
+        };
+    };
+}
+
 pub use private::*;
@@ -0,0 +1,9 @@
+use prusti_contracts::*;
+
+atomic_invariant!(
+    #[derive(Default)]
+    pub struct Counter(AtomicUsize),
+    |value| value <= 10
+);
+
+fn main() {}
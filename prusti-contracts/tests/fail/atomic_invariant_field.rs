@@ -0,0 +1,12 @@
+use prusti_contracts::*;
+use std::sync::atomic::Ordering;
+
+atomic_invariant!(pub struct Counter(AtomicUsize), |value| value <= 10);
+
+pub fn reset(counter: &Counter) {
+    counter.0.store(11, Ordering::SeqCst);
+}
+
+fn main() {
+    reset(&Counter::new(0));
+}
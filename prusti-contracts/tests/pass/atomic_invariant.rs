@@ -0,0 +1,18 @@
+use prusti_contracts::*;
+use std::sync::atomic::Ordering;
+
+const LIMIT: usize = 10;
+
+atomic_invariant!(pub struct Counter(AtomicUsize), |value| value <= LIMIT);
+atomic_invariant!(struct Flag(AtomicBool), |value| !value);
+
+fn main() {
+    let counter = Counter::new(0);
+    assert!(counter.fetch_add(1, Ordering::SeqCst) == 0);
+    counter.store(3, Ordering::SeqCst);
+    assert!(counter.compare_exchange(3, 4, Ordering::SeqCst, Ordering::SeqCst) == Ok(3));
+    assert!(counter.load(Ordering::SeqCst) == 4);
+    let flag = Flag::new(false);
+    flag.store(false, Ordering::SeqCst);
+    assert!(!flag.load(Ordering::SeqCst));
+}
@@ -0,0 +1,23 @@
+use prusti_contracts::*;
+
+binary_heap!(struct MaxHeap(u32));
+binary_heap!(pub struct MinHeap(Reverse<i64>));
+
+fn main() {
+    let mut max_heap = MaxHeap::new();
+    max_heap.push(3);
+    max_heap.push(7);
+    max_heap.push(3);
+    assert!(max_heap.len() == 3 && max_heap.count(3) == 2);
+    assert!(max_heap.peek() == Some(7) && max_heap.top() == 7);
+    assert!(max_heap.pop() == Some(7));
+    assert!(max_heap.pop() == Some(3));
+    assert!(max_heap.pop() == Some(3));
+    assert!(max_heap.pop().is_none() && max_heap.is_empty());
+
+    let mut min_heap = MinHeap::new();
+    min_heap.push(-1);
+    min_heap.push(-5);
+    assert!(min_heap.top() == -5 && min_heap.count(-1) == 1);
+    assert!(min_heap.pop() == Some(-5));
+}
@@ -0,0 +1,21 @@
+use prusti_contracts::*;
+
+#[pure]
+fn double(x: u32) -> u32 {
+    x + x
+}
+
+#[lemma(triggers = [(double(x),)])]
+#[requires(x < 1000)]
+#[ensures(double(x) >= x)]
+fn double_ge(x: u32) {}
+
+#[requires(x < 1000)]
+fn client(x: u32) -> u32 {
+    apply_lemma!(double_ge(x));
+    double(x)
+}
+
+fn main() {
+    assert!(client(3) == 6);
+}
@@ -0,0 +1,18 @@
+use prusti_contracts::*;
+use std::collections::{BTreeMap, HashMap};
+
+map_specs!();
+
+fn main() {
+    let mut ages: HashMap<String, u8> = HashMap::new();
+    assert!(ages.insert("Alice".to_string(), 30).is_none());
+    assert!(ages.insert("Alice".to_string(), 31) == Some(30));
+    assert!(ages.len() == 1 && ages.contains_key("Alice"));
+    assert!(ages.get("Alice") == Some(&31) && ages.get("Bob").is_none());
+    assert!(ages.remove("Alice") == Some(31) && ages.is_empty());
+
+    let mut positions = BTreeMap::new();
+    positions.insert(-3i64, vec![0usize]);
+    assert!(positions.contains_key(&-3) && !positions.is_empty());
+    assert!(positions.remove(&-3).is_some() && positions.len() == 0);
+}
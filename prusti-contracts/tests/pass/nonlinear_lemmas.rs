@@ -0,0 +1,14 @@
+use prusti_contracts::*;
+
+nonlinear_lemmas!(u64_lemmas, u64);
+
+#[requires(0 < b)]
+fn split(a: u64, b: u64) -> (u64, u64) {
+    apply_lemmas!(u64_lemmas::{div_mod(a, b), mod_bounds(a, b)});
+    (a / b, a % b)
+}
+
+fn main() {
+    assert!(split(17, 5) == (3, 2));
+    assert!(u64_lemmas::pow(3, 4) == 81);
+}
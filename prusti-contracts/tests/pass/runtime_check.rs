@@ -0,0 +1,38 @@
+use prusti_contracts::*;
+
+#[insert_runtime_check]
+#[requires(x > 0)]
+fn checked_precondition(x: i32) -> i32 {
+    100 / x
+}
+
+#[requires(x < 100)]
+#[insert_runtime_check]
+#[ensures(result > x)]
+fn checked_postcondition(x: i32) -> i32 {
+    if x == 42 {
+        x
+    } else {
+        x + 1
+    }
+}
+
+#[insert_runtime_check(all)]
+#[requires(v.len() < 10)]
+#[ensures(v.len() == old(v.len()) + 1)]
+fn checked_function(v: &mut Vec<i32>) {
+    v.push(0);
+}
+
+fn main() {
+    assert_eq!(checked_precondition(4), 25);
+    assert_eq!(checked_postcondition(1), 2);
+    let mut v = vec![];
+    checked_function(&mut v);
+    assert_eq!(v.len(), 1);
+
+    assert!(std::panic::catch_unwind(|| checked_precondition(0)).is_err());
+    assert!(std::panic::catch_unwind(|| checked_postcondition(42)).is_err());
+    let mut v = vec![0; 10];
+    assert!(std::panic::catch_unwind(move || checked_function(&mut v)).is_err());
+}
@@ -0,0 +1,12 @@
+use prusti_contracts::*;
+
+slice_specs!(i32, usize);
+
+fn main() {
+    let mut values = [3, -1, 2];
+    values.sort();
+    assert!(values == [-1, 2, 3]);
+    values.sort_unstable();
+    assert!(values.binary_search(&2) == Ok(1));
+    assert!(values.binary_search(&0) == Err(1));
+}
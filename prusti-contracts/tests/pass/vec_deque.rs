@@ -0,0 +1,16 @@
+use prusti_contracts::*;
+
+vec_deque!(pub struct Queue(u32));
+
+fn main() {
+    let mut queue = Queue::new();
+    queue.push_back(2);
+    queue.push_back(3);
+    queue.push_front(1);
+    assert!(queue.len() == 3 && queue.lookup(0) == 1 && queue.lookup(2) == 3);
+    assert!(queue.get(1) == Some(2) && queue.get(3).is_none());
+    assert!(queue.pop_front() == Some(1));
+    assert!(queue.pop_back() == Some(3));
+    assert!(queue.pop_back() == Some(2));
+    assert!(queue.pop_front().is_none() && queue.is_empty());
+}
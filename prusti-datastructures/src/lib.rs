@@ -0,0 +1,12 @@
+//! Verified recursive data structures.
+//!
+//! The types of this crate come with pure functions describing their shape
+//! (e.g. the length of a list or the height of a tree) and lemmas relating
+//! them, so that they can be used in the specifications of client code. The
+//! crate is verified with Prusti as part of its test suite.
+
+pub mod list;
+pub mod tree;
+
+pub use list::List;
+pub use tree::Tree;
@@ -0,0 +1,110 @@
+//! A singly linked list.
+
+use prusti_contracts::*;
+
+/// A singly linked list: either empty, or an element followed by the rest of
+/// the list.
+pub enum List<T> {
+    Nil,
+    Cons(T, Box<List<T>>),
+}
+
+#[extern_spec]
+impl<T> std::option::Option<T> {
+    #[pure]
+    #[ensures(matches!(*self, Some(_)) == result)]
+    pub fn is_some(&self) -> bool;
+
+    #[pure]
+    #[ensures(self.is_some() == !result)]
+    pub fn is_none(&self) -> bool;
+}
+
+impl<T> List<T> {
+    #[ensures(result.is_empty())]
+    pub fn new() -> Self {
+        List::Nil
+    }
+
+    #[pure]
+    pub fn is_empty(&self) -> bool {
+        match self {
+            List::Nil => true,
+            List::Cons(..) => false,
+        }
+    }
+
+    /// The number of elements of the list.
+    #[pure]
+    pub fn len(&self) -> usize {
+        match self {
+            List::Nil => 0,
+            List::Cons(_, tail) => 1 + tail.len(),
+        }
+    }
+
+    #[lemma]
+    #[ensures(self.is_empty() == (self.len() == 0))]
+    pub fn empty_iff_len_zero(&self) {}
+
+    /// The element at `index`, counting from the front of the list.
+    #[requires(index < self.len())]
+    pub fn get(&self, index: usize) -> &T {
+        match self {
+            List::Nil => unreachable!(),
+            List::Cons(elem, tail) => {
+                if index == 0 {
+                    elem
+                } else {
+                    tail.get(index - 1)
+                }
+            }
+        }
+    }
+
+    #[ensures(!self.is_empty())]
+    #[ensures(self.len() == old(self.len()) + 1)]
+    pub fn push_front(&mut self, elem: T) {
+        let tail = self.take();
+        *self = List::Cons(elem, Box::new(tail));
+    }
+
+    #[ensures(old(self.is_empty()) ==> result.is_none() && self.is_empty())]
+    #[ensures(!old(self.is_empty()) ==> result.is_some())]
+    #[ensures(!old(self.is_empty()) ==> self.len() == old(self.len()) - 1)]
+    pub fn pop_front(&mut self) -> Option<T> {
+        match self.take() {
+            List::Nil => None,
+            List::Cons(elem, tail) => {
+                *self = *tail;
+                Some(elem)
+            }
+        }
+    }
+
+    /// Move the elements of `other` to the back of the list.
+    #[ensures(self.len() == old(self.len()) + old(other.len()))]
+    pub fn append(&mut self, other: List<T>) {
+        match self {
+            List::Nil => *self = other,
+            List::Cons(_, tail) => tail.append(other),
+        }
+    }
+
+    /// Take the elements out of the list, leaving it empty.
+    #[trusted]
+    #[ensures(self.is_empty())]
+    #[ensures(result.len() == old(self.len()))]
+    #[ensures(result.is_empty() == old(self.is_empty()))]
+    fn take(&mut self) -> Self {
+        std::mem::replace(self, List::Nil)
+    }
+}
+
+#[refine_trait_spec]
+impl<T> Default for List<T> {
+    #[ensures(result.is_empty())]
+    fn default() -> Self {
+        List::new()
+    }
+}
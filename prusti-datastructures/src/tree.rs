@@ -0,0 +1,110 @@
+//! A binary tree.
+
+use prusti_contracts::*;
+
+/// A binary tree: either a leaf without value, or a node with a value and two
+/// subtrees.
+pub enum Tree<T> {
+    Leaf,
+    Node(Box<Tree<T>>, T, Box<Tree<T>>),
+}
+
+#[pure]
+fn max(a: usize, b: usize) -> usize {
+    if a < b {
+        b
+    } else {
+        a
+    }
+}
+
+impl<T> Tree<T> {
+    #[ensures(result.is_leaf())]
+    #[ensures(result.size() == 0)]
+    pub fn new() -> Self {
+        Tree::Leaf
+    }
+
+    #[ensures(!result.is_leaf())]
+    #[ensures(result.size() == 1 && result.height() == 1)]
+    pub fn singleton(value: T) -> Self {
+        Tree::Node(Box::new(Tree::Leaf), value, Box::new(Tree::Leaf))
+    }
+
+    #[ensures(!result.is_leaf())]
+    #[ensures(result.size() == old(left.size()) + 1 + old(right.size()))]
+    #[ensures(result.height() == 1 + max(old(left.height()), old(right.height())))]
+    pub fn join(left: Tree<T>, value: T, right: Tree<T>) -> Self {
+        Tree::Node(Box::new(left), value, Box::new(right))
+    }
+
+    #[pure]
+    pub fn is_leaf(&self) -> bool {
+        match self {
+            Tree::Leaf => true,
+            Tree::Node(..) => false,
+        }
+    }
+
+    /// The number of values stored in the tree.
+    #[pure]
+    pub fn size(&self) -> usize {
+        match self {
+            Tree::Leaf => 0,
+            Tree::Node(left, _, right) => left.size() + 1 + right.size(),
+        }
+    }
+
+    /// The number of nodes on the longest path from the root to a leaf.
+    #[pure]
+    pub fn height(&self) -> usize {
+        match self {
+            Tree::Leaf => 0,
+            Tree::Node(left, _, right) => 1 + max(left.height(), right.height()),
+        }
+    }
+
+    #[lemma]
+    #[ensures(self.is_leaf() == (self.size() == 0))]
+    #[ensures(self.is_leaf() == (self.height() == 0))]
+    pub fn leaf_iff_empty(&self) {}
+
+    #[lemma]
+    #[ensures(self.height() <= self.size())]
+    pub fn height_le_size(&self) {
+        match self {
+            Tree::Leaf => {}
+            Tree::Node(left, _, right) => {
+                left.height_le_size();
+                right.height_le_size();
+            }
+        }
+    }
+
+    /// The value at the root of the tree.
+    #[requires(!self.is_leaf())]
+    pub fn root(&self) -> &T {
+        match self {
+            Tree::Leaf => unreachable!(),
+            Tree::Node(_, value, _) => value,
+        }
+    }
+
+    /// The number of nodes visited by an in-order traversal, computed
+    /// recursively.
+    #[ensures(result == self.size())]
+    pub fn count(&self) -> usize {
+        match self {
+            Tree::Leaf => 0,
+            Tree::Node(left, _, right) => left.count() + 1 + right.count(),
+        }
+    }
+}
+
+#[refine_trait_spec]
+impl<T> Default for Tree<T> {
+    #[ensures(result.is_leaf())]
+    fn default() -> Self {
+        Tree::new()
+    }
+}
@@ -36,7 +36,7 @@ impl PlaceRegions {
         self.for_place(local.into()).unwrap()
     }
 
-    /// Determines the region of a MIR place. Right now, the only supported places are locals and tuples. Tuples cannot be nested inside other tuples.
+    /// Determines the region of a MIR place. Right now, the only supported places are locals, tuples and structs. Tuples and structs cannot be nested inside each other.
     pub fn for_place(&self, place: mir::Place)
         -> Result<Option<facts::Region>, PlaceRegionsError>
     {
@@ -96,7 +96,12 @@ fn extract_region_id(region: &ty::RegionKind) -> facts::Region {
     }
 }
 
-fn extract_region(place_regions: &mut PlaceRegions, local: mir::Local, ty: ty::Ty<'_>) {
+fn extract_region<'tcx>(
+    place_regions: &mut PlaceRegions,
+    tcx: ty::TyCtxt<'tcx>,
+    local: mir::Local,
+    ty: ty::Ty<'tcx>,
+) {
     match ty.kind() {
         ty::TyKind::Ref(region, _, _) => {
             place_regions.add_local(local, extract_region_id(region));
@@ -104,15 +109,12 @@ fn extract_region(place_regions: &mut PlaceRegions, local: mir::Local, ty: ty::T
         }
         ty::TyKind::Tuple(substs) => {
             for (i, ty) in substs.iter().enumerate() {
-                match ty.kind() {
-                    ty::TyKind::Ref(region, _, _) => {
-                        place_regions.add(local, vec![i], extract_region_id(region))
-                    }
-                    _ => {
-                        debug!("does not contain regions: {:?}[{}]: {:?} {:?}", local, i, ty, ty.kind());
-                    }
-                }
-
+                extract_field_region(place_regions, local, i, ty);
+            }
+        }
+        ty::TyKind::Adt(adt_def, substs) if adt_def.is_struct() => {
+            for (i, field) in adt_def.non_enum_variant().fields.iter().enumerate() {
+                extract_field_region(place_regions, local, i, field.ty(tcx, substs));
             }
         }
         _ => {
@@ -121,14 +123,33 @@ fn extract_region(place_regions: &mut PlaceRegions, local: mir::Local, ty: ty::T
     }
 }
 
-pub fn load_place_regions(body: &mir::Body<'_>) -> io::Result<PlaceRegions> {
+fn extract_field_region(
+    place_regions: &mut PlaceRegions,
+    local: mir::Local,
+    field: usize,
+    ty: ty::Ty<'_>,
+) {
+    match ty.kind() {
+        ty::TyKind::Ref(region, _, _) => {
+            place_regions.add(local, vec![field], extract_region_id(region))
+        }
+        _ => {
+            debug!("does not contain regions: {:?}[{}]: {:?} {:?}", local, field, ty, ty.kind());
+        }
+    }
+}
+
+pub fn load_place_regions<'tcx>(
+    tcx: ty::TyCtxt<'tcx>,
+    body: &mir::Body<'tcx>,
+) -> io::Result<PlaceRegions> {
     trace!("[enter] load_place_regions()");
     let mut place_regions = PlaceRegions::new();
 
     for (local, local_decl) in body.local_decls.iter_enumerated() {
         let ty = local_decl.ty;
         debug!("local: {:?} {:?}", local, ty);
-        extract_region(&mut place_regions,  local, ty);
+        extract_region(&mut place_regions, tcx, local, ty);
     }
 
     trace!("[exit] load_place_regions");
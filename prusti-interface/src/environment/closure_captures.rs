@@ -0,0 +1,56 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The places captured by closures, as computed by the closure capture
+//! analysis of the compiler.
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::{self, TyCtxt};
+
+/// How a closure captures a place.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CaptureKind {
+    /// The place is moved or copied into the closure.
+    ByValue,
+    /// The closure holds a shared reference to the place.
+    BySharedRef,
+    /// The closure holds a mutable reference to the place. This includes the
+    /// unique borrows that the compiler creates to mutate the target of a
+    /// captured mutable reference.
+    ByMutRef,
+}
+
+/// A place captured by a closure, which is stored in the field of the
+/// closure with the same index.
+#[derive(Clone, Debug)]
+pub struct ClosureCapture<'tcx> {
+    /// The captured place as written in the source, e.g. `x.f`.
+    pub place: String,
+    pub kind: CaptureKind,
+    /// The type of the captured place.
+    pub ty: ty::Ty<'tcx>,
+}
+
+/// The places captured by the closure `def_id`, in the order of the fields
+/// of the closure.
+pub fn closure_captures<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> Vec<ClosureCapture<'tcx>> {
+    tcx.closure_min_captures_flattened(def_id)
+        .map(|captured_place| {
+            let kind = match captured_place.info.capture_kind {
+                ty::UpvarCapture::ByValue => CaptureKind::ByValue,
+                ty::UpvarCapture::ByRef(ty::BorrowKind::ImmBorrow) => CaptureKind::BySharedRef,
+                ty::UpvarCapture::ByRef(
+                    ty::BorrowKind::UniqueImmBorrow | ty::BorrowKind::MutBorrow,
+                ) => CaptureKind::ByMutRef,
+            };
+            ClosureCapture {
+                place: captured_place.to_string(tcx),
+                kind,
+                ty: captured_place.place.ty(),
+            }
+        })
+        .collect()
+}
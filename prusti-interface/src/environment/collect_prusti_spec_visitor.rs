@@ -44,6 +44,14 @@ impl<'a, 'tcx> ItemLikeVisitor<'tcx> for CollectPrustiSpecVisitor<'a, 'tcx> {
         if let hir::ItemKind::Fn(..) = item.kind {
             let def_id = self.tcx.hir().local_def_id(item.hir_id()).to_def_id();
             let item_def_path = self.env.get_item_def_path(def_id);
+            // The `#[panic_handler]` and `#[alloc_error_handler]` of `no_std`
+            // crates are only called by the runtime, after a panic that is
+            // already reported.
+            let lang_items = self.tcx.lang_items();
+            if lang_items.panic_impl() == Some(def_id) || lang_items.oom() == Some(def_id) {
+                trace!("Skip runtime handler {}", item_def_path);
+                return;
+            }
             trace!("Add {} to result", item_def_path);
             self.result.push(def_id);
         }
@@ -4,8 +4,9 @@ use rustc_middle::ty;
 
 pub trait AllPlaces<'tcx> {
     /// Returns all places that are below the given local variable. Right now, this only handles
-    /// tuples. For a local variable `_2: u32`, `Place::Local(_2).all_places(&mir) == [_2]`. For a
-    /// local variable `_2: (u32, u32)`, `Place::Local(_2).all_places(&mir) == [_2, _2.0, _2.1]`.
+    /// tuples and structs. For a local variable `_2: u32`,
+    /// `Place::Local(_2).all_places(&mir) == [_2]`. For a local variable `_2: (u32, u32)`,
+    /// `Place::Local(_2).all_places(&mir) == [_2, _2.0, _2.1]`.
     fn all_places(self, tcx: ty::TyCtxt<'tcx>, mir: &mir::Body<'tcx>) -> Vec<mir::Place<'tcx>>;
 }
 
@@ -13,12 +14,22 @@ impl<'tcx> AllPlaces<'tcx> for mir::Local {
     fn all_places(self, tcx: ty::TyCtxt<'tcx>, mir: &mir::Body<'tcx>) -> Vec<mir::Place<'tcx>> {
         let mut places = vec![self.into()];
         let ty = mir.local_decls[self].ty;
-        if let ty::TyKind::Tuple(types) = ty.kind() {
-            for (i, ty) in types.iter().enumerate() {
-                let field = mir::Field::new(i);
-                let place = tcx.mk_place_field(self.into(), field, ty);
-                places.push(place);
+        match ty.kind() {
+            ty::TyKind::Tuple(types) => {
+                for (i, ty) in types.iter().enumerate() {
+                    let field = mir::Field::new(i);
+                    let place = tcx.mk_place_field(self.into(), field, ty);
+                    places.push(place);
+                }
             }
+            ty::TyKind::Adt(adt_def, substs) if adt_def.is_struct() => {
+                for (i, field_def) in adt_def.non_enum_variant().fields.iter().enumerate() {
+                    let field = mir::Field::new(i);
+                    let place = tcx.mk_place_field(self.into(), field, field_def.ty(tcx, substs));
+                    places.push(place);
+                }
+            }
+            _ => {}
         }
         places
     }
@@ -24,6 +24,7 @@ use std::collections::HashMap;
 use std::cell::RefCell;
 
 pub mod borrowck;
+mod closure_captures;
 mod collect_prusti_spec_visitor;
 mod collect_closure_defs_visitor;
 mod dump_borrowck_info;
@@ -41,12 +42,15 @@ mod traits;
 use self::collect_prusti_spec_visitor::CollectPrustiSpecVisitor;
 use self::collect_closure_defs_visitor::CollectClosureDefsVisitor;
 use rustc_hir::intravisit::Visitor;
+pub use self::closure_captures::{CaptureKind, ClosureCapture};
 pub use self::loops::{PlaceAccess, PlaceAccessKind, ProcedureLoops};
 pub use self::loops_utils::*;
 pub use self::procedure::{BasicBlockIndex, Procedure};
 use self::borrowck::facts::BorrowckFacts;
 // use config;
 use crate::data::ProcedureDefId;
+use crate::error_codes::ErrorCode;
+use rustc_errors::DiagnosticId;
 // use syntax::codemap::CodeMap;
 // use syntax::codemap::Span;
 // use utils::get_attr_value;
@@ -137,11 +141,15 @@ impl<'tcx> Environment<'tcx> {
         &self,
         sp: S,
         msg: &str,
+        code: Option<ErrorCode>,
         help: &Option<String>,
         notes: &[(String, Option<S>)],
     ) {
         let mut diagnostic = self.tcx.sess.struct_err(msg);
         diagnostic.set_span(sp);
+        if let Some(code) = code {
+            diagnostic.code(DiagnosticId::Error(code.to_string()));
+        }
         if let Some(help_msg) = help {
             diagnostic.help(help_msg);
         }
@@ -160,11 +168,15 @@ impl<'tcx> Environment<'tcx> {
         &self,
         sp: S,
         msg: &str,
+        code: Option<ErrorCode>,
         help: &Option<String>,
         notes: &[(String, Option<S>)],
     ) {
         let mut diagnostic = self.tcx.sess.struct_warn(msg);
         diagnostic.set_span(sp);
+        if let Some(code) = code {
+            diagnostic.code(DiagnosticId::Error(code.to_string()));
+        }
         if let Some(help_msg) = help {
             diagnostic.help(help_msg);
         }
@@ -197,6 +209,12 @@ impl<'tcx> Environment<'tcx> {
         result
     }
 
+    /// Get the id of the entry point of the crate, i.e. the `main` function of
+    /// a binary, if there is one.
+    pub fn get_entry_fn(&self) -> Option<ProcedureDefId> {
+        self.tcx.entry_fn(()).map(|(def_id, _)| def_id)
+    }
+
     /// Find whether the procedure has a particular `prusti::<name>` attribute.
     pub fn has_prusti_attribute(&self, def_id: ProcedureDefId, name: &str) -> bool {
         let tcx = self.tcx();
@@ -235,6 +253,33 @@ impl<'tcx> Environment<'tcx> {
         self.tcx.def_span(def_id)
     }
 
+    /// Get the module that contains the given local definition.
+    pub fn get_parent_module(&self, def_id: LocalDefId) -> LocalDefId {
+        self.tcx.parent_module_from_def_id(def_id)
+    }
+
+    /// Get the source of a local module, i.e. of its items. For a module
+    /// declared with `mod foo;`, this is the content of its file.
+    pub fn get_module_source(&self, module: LocalDefId) -> Option<String> {
+        let (module, _, _) = self.tcx.hir().get_module(module);
+        self.codemap().span_to_snippet(module.inner).ok()
+    }
+
+    /// Get the path of a local module relative to the crate root, such as
+    /// `parser::lexer`, or `crate` for the crate root.
+    pub fn get_module_path(&self, module: LocalDefId) -> String {
+        let path = self.tcx.def_path(module.to_def_id()).data
+            .iter()
+            .map(|data| data.data.to_string())
+            .collect::<Vec<_>>()
+            .join("::");
+        if path.is_empty() {
+            "crate".to_string()
+        } else {
+            path
+        }
+    }
+
     pub fn get_absolute_item_name(&self, def_id: DefId) -> String {
         self.tcx.def_path_str(def_id)
     }
@@ -466,6 +511,40 @@ impl<'tcx> Environment<'tcx> {
         }
     }
 
+    /// Resolve a call of `Into::into` or `TryInto::try_into` to the
+    /// implementation of `From::from` or `TryFrom::try_from` that the blanket
+    /// implementations of the former call, so that its contract applies to
+    /// the call. Returns `None` for the calls of other functions and of local
+    /// implementations of `Into` and `TryInto`.
+    pub fn resolve_conversion_call(
+        &self,
+        caller_def_id: ProcedureDefId,
+        called_def_id: ProcedureDefId,
+        call_substs: SubstsRef<'tcx>,
+    ) -> Option<(ProcedureDefId, SubstsRef<'tcx>)> {
+        let from_trait_name = match self.tcx.def_path_str(called_def_id).as_str() {
+            "std::convert::Into::into" | "core::convert::Into::into" => rustc_span::sym::From,
+            "std::convert::TryInto::try_into" | "core::convert::TryInto::try_into" => {
+                rustc_span::sym::TryFrom
+            }
+            _ => return None,
+        };
+        let (resolved_def_id, _) =
+            self.resolve_method_call(caller_def_id, called_def_id, call_substs);
+        if resolved_def_id.is_local() {
+            return None;
+        }
+        let from_trait = self.tcx.get_diagnostic_item(from_trait_name)?;
+        let from_method = self.tcx
+            .associated_items(from_trait)
+            .in_definition_order()
+            .find(|item| item.kind == ty::AssocKind::Fn)?;
+        // `Into<U> for T` has the substitutions `[T, U]`, while `From<T> for U`
+        // has the substitutions `[U, T]`.
+        let from_substs = self.tcx.mk_substs([call_substs[1], call_substs[0]].into_iter());
+        Some(self.resolve_method_call(caller_def_id, from_method.def_id, from_substs))
+    }
+
     pub fn type_is_allowed_in_pure_functions(&self, ty: ty::Ty<'tcx>, param_env: ty::ParamEnv<'tcx>) -> bool {
         match ty.kind() {
             ty::TyKind::Never => {
@@ -503,6 +582,12 @@ impl<'tcx> Environment<'tcx> {
         )
     }
 
+    /// The places captured by the closure `def_id`, in the order of the fields
+    /// of the closure.
+    pub fn closure_captures(&self, def_id: DefId) -> Vec<ClosureCapture<'tcx>> {
+        closure_captures::closure_captures(self.tcx, def_id)
+    }
+
     /// Return the default substitutions for a particular item, i.e. where each
     /// generic maps to itself.
     pub fn identity_substs(&self, def_id: ProcedureDefId) -> SubstsRef<'tcx> {
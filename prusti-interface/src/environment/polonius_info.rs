@@ -453,10 +453,23 @@ fn add_fake_facts<'a, 'tcx: 'a>(
                     if place.projection.len() > 0 {
                         unimplemented!();
                     }
-                    if let Some(var_region) = place_regions.for_local(local) {
+                    // A tuple may contain several returned references. They
+                    // all share the same loan, which models the single magic
+                    // wand of the call.
+                    let mut var_regions = vec![];
+                    for place in local.all_places(tcx, mir) {
+                        if let Some(region) = place_regions
+                            .for_place(place)
+                            .map_err(|err| (err, location))? {
+                            var_regions.push(region);
+                        }
+                    }
+                    if !var_regions.is_empty() {
                         let loan = new_loan();
-                        debug!("var_region = {:?} loan = {:?}", var_region, loan);
-                        loan_issued_at.push((var_region, loan, point));
+                        for var_region in var_regions {
+                            debug!("var_region = {:?} loan = {:?}", var_region, loan);
+                            loan_issued_at.push((var_region, loan, point));
+                        }
                         call_magic_wands.insert(loan, local);
                     }
                 }
@@ -674,7 +687,7 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
         //         def_path.to_filename_friendly_no_crate()
         //     ));
         // debug!("Renumber path: {:?}", renumber_path);
-        let place_regions = regions::load_place_regions(mir).unwrap();
+        let place_regions = regions::load_place_regions(tcx, mir).unwrap();
 
         let mut call_magic_wands = FxHashMap::default();
 
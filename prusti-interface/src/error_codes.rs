@@ -0,0 +1,113 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Stable codes of the errors reported by Prusti, and their extended
+//! descriptions, which are shown by `prusti-rustc --explain <code>`.
+//!
+//! Codes `P00xx` identify the kinds of checks that a verification error can
+//! fail, one per context of a check. Codes `P01xx` identify the categories of
+//! the errors that are reported before the verification, such as unsupported
+//! features. Codes must never be reused.
+
+use std::fmt;
+
+macro_rules! error_codes {
+    ($($code:ident,)*) => {
+        /// The code of a Prusti error, printed as `error[P0001]`.
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum ErrorCode {
+            $($code,)*
+        }
+
+        impl ErrorCode {
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$code,)*];
+
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(ErrorCode::$code => stringify!($code),)*
+                }
+            }
+
+            /// The extended description of the error, in Markdown.
+            pub fn explanation(&self) -> &'static str {
+                match self {
+                    $(ErrorCode::$code => include_str!(
+                        concat!("error_codes/", stringify!($code), ".md")
+                    ),)*
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    P0001,
+    P0002,
+    P0003,
+    P0004,
+    P0005,
+    P0006,
+    P0007,
+    P0008,
+    P0009,
+    P0010,
+    P0011,
+    P0012,
+    P0013,
+    P0014,
+    P0015,
+    P0016,
+    P0017,
+    P0018,
+    P0019,
+    P0020,
+    P0021,
+    P0022,
+    P0023,
+    P0024,
+    P0025,
+    P0026,
+    P0027,
+    P0028,
+    P0029,
+    P0030,
+    P0031,
+    P0032,
+    P0033,
+    P0034,
+    P0035,
+    P0036,
+    P0037,
+    P0038,
+    P0039,
+    P0040,
+    P0041,
+    P0042,
+    P0043,
+    P0044,
+    P0045,
+    P0046,
+    P0100,
+    P0101,
+    P0102,
+    P0103,
+}
+
+impl ErrorCode {
+    /// Parse a code such as `P0001`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|error_code| error_code.as_str() == code)
+            .copied()
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -40,6 +40,7 @@ extern crate lazy_static;
 
 pub mod data;
 pub mod environment;
+pub mod error_codes;
 pub mod specs;
 pub mod utils;
 
@@ -6,6 +6,7 @@
 
 use rustc_span::{Span, MultiSpan};
 use crate::environment::Environment;
+use crate::error_codes::ErrorCode;
 use prusti_common::config;
 use ::log::warn;
 
@@ -29,6 +30,8 @@ pub struct PrustiError {
     /// field should be removed.
     is_disabled: bool,
     message: String,
+    /// The stable code of the error, printed as `error[P0001]`.
+    code: Option<ErrorCode>,
     span: MultiSpan,
     help: Option<String>,
     notes: Vec<(String, Option<MultiSpan>)>,
@@ -53,6 +56,7 @@ impl PrustiError {
             is_error: true,
             is_disabled: false,
             message,
+            code: None,
             span,
             help: None,
             notes: vec![],
@@ -84,7 +88,7 @@ impl PrustiError {
         let mut error = PrustiError::new(
             format!("[Prusti: unsupported feature] {}", message.to_string()),
             span
-        );
+        ).set_code(ErrorCode::P0100);
         if config::skip_unsupported_features() {
             error.set_warning();
         }
@@ -97,7 +101,19 @@ impl PrustiError {
         PrustiError::new(
             format!("[Prusti: invalid specification] {}", message.to_string()),
             span
-        )
+        ).set_code(ErrorCode::P0101)
+    }
+
+    /// Warn about a suspicious usage of Prusti (e.g. a trusted function
+    /// without a postcondition)
+    pub fn warning<S: ToString>(message: S, span: MultiSpan) -> Self {
+        check_message(message.to_string());
+        let mut error = PrustiError::new(
+            format!("[Prusti: warning] {}", message.to_string()),
+            span
+        ).set_code(ErrorCode::P0103);
+        error.set_warning();
+        error
     }
 
     /// Report an internal error of Prusti (e.g. failure of the fold-unfold)
@@ -106,7 +122,7 @@ impl PrustiError {
         let mut error = PrustiError::new(
             "[Prusti internal error] Prusti encountered an unexpected internal error".to_string(),
             span
-        ).add_note(
+        ).set_code(ErrorCode::P0102).add_note(
             "We would appreciate a bug report: https://github.com/viperproject/prusti-dev/issues/new",
             None
         ).add_note(
@@ -135,6 +151,20 @@ impl PrustiError {
         self.is_disabled
     }
 
+    pub fn code(&self) -> Option<ErrorCode> {
+        self.code
+    }
+
+    #[must_use]
+    pub fn set_code(mut self, code: ErrorCode) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn has_help(&self) -> bool {
+        self.help.is_some()
+    }
+
     #[must_use]
     pub fn set_help<S: ToString>(mut self, message: S) -> Self {
         self.help = Some(message.to_string());
@@ -158,6 +188,7 @@ impl PrustiError {
             env.span_err_with_help_and_notes(
                 self.span,
                 &self.message,
+                self.code,
                 &self.help,
                 &self.notes,
             );
@@ -165,6 +196,7 @@ impl PrustiError {
             env.span_warn_with_help_and_notes(
                 self.span,
                 &self.message,
+                self.code,
                 &self.help,
                 &self.notes,
             );
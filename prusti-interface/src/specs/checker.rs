@@ -1,6 +1,8 @@
 use log::{debug};
+use prusti_common::config;
 use rustc_hir::{
     self as hir,
+    def::{DefKind, Res},
     def_id::{DefId},
     intravisit::{self, Visitor},
 };
@@ -11,12 +13,14 @@ use std::collections::HashMap;
 
 use crate::{
     environment::Environment,
-    utils::{has_prusti_attr, has_spec_only_attr},
+    specs::typed::{DefSpecificationMap, SpecificationSet},
+    utils::{has_extern_spec_attr, has_prusti_attr, has_spec_only_attr},
     PrustiError,
 };
 
-/// Checker visitor for the specifications. Currently checks that `predicate!`
-/// functions are never used from non-specification code, but more checks may follow.
+/// Checker visitor for the specifications. Checks that `predicate!`
+/// functions are never used from non-specification code, and that
+/// specifications only call pure functions.
 #[derive(Default)]
 pub struct SpecChecker {
     /// Map of the `DefID`s to the `Span`s of `predicate!` functions found in the first pass.
@@ -24,6 +28,21 @@ pub struct SpecChecker {
 
     /// Span of use and definition of predicates used outside of specifications, collected in the second pass.
     pred_usages: Vec<(Span, Span)>,
+
+    /// Span of calls to impure functions from specifications, the called
+    /// function and the span of its external specification, if any.
+    impure_calls: Vec<(Span, DefId, Option<Span>)>,
+
+    /// Trusted functions with outputs but without a postcondition or pledge.
+    trusted_without_postcondition: Vec<DefId>,
+
+    /// Quantifiers without explicit triggers, the names of their bound
+    /// variables that are not mentioned by any trigger candidate and of those
+    /// mentioned only under arithmetic operations.
+    weak_triggers: Vec<(Span, Vec<String>, Vec<String>)>,
+
+    /// Laws that are not declared in a trait.
+    misplaced_laws: Vec<DefId>,
 }
 
 /// First predicate checks visitor: collect all function items that originate
@@ -127,6 +146,332 @@ impl<'v, 'tcx> Visitor<'tcx> for CheckPredicatesVisitor<'v, 'tcx> {
     }
 }
 
+/// Checks that the calls in specifications are to pure functions. The
+/// functions of the crate and the external functions with an external
+/// specification are checked; the other external functions may be built-in,
+/// which is resolved by the encoder.
+struct CheckSpecCallsVisitor<'v, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    def_spec: &'v DefSpecificationMap,
+
+    /// Whether the visited expression is part of a specification.
+    in_spec: bool,
+    impure_calls: &'v mut Vec<(Span, DefId, Option<Span>)>,
+}
+
+impl<'v, 'tcx> CheckSpecCallsVisitor<'v, 'tcx> {
+    /// Whether calling `def_id` from a specification has to be reported.
+    fn is_impure_function(&self, def_id: DefId) -> bool {
+        // The purity of functions without a specification is then inferred by
+        // the encoder.
+        if config::infer_purity() {
+            return false;
+        }
+        if !matches!(self.tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn) {
+            return false;
+        }
+        if !def_id.is_local() && !self.def_spec.extern_specs.contains_key(&def_id) {
+            return false;
+        }
+        // The purity of trait methods and of their implementations may be
+        // refined, which is resolved by the encoder.
+        let is_trait_impl_method = self.tcx.impl_of_method(def_id)
+            .and_then(|impl_id| self.tcx.trait_id_of_impl(impl_id))
+            .is_some();
+        if self.tcx.trait_of_item(def_id).is_some() || is_trait_impl_method {
+            return false;
+        }
+        if def_id.is_local() {
+            let attrs = self.tcx.get_attrs(def_id);
+            if has_spec_only_attr(attrs) || has_extern_spec_attr(attrs) {
+                return false;
+            }
+        }
+        !matches!(
+            self.def_spec.get(&def_id),
+            Some(SpecificationSet::Procedure(spec)) if spec.kind.is_pure().unwrap_or(false)
+        )
+    }
+}
+
+impl<'v, 'tcx> Visitor<'tcx> for CheckSpecCallsVisitor<'v, 'tcx> {
+    type Map = Map<'tcx>;
+    type NestedFilter = rustc_middle::hir::nested_filter::All;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_expr(&mut self, ex: &'tcx hir::Expr<'tcx>) {
+        if self.in_spec {
+            let owner = ex.hir_id.owner;
+            let called_def_id = match ex.kind {
+                hir::ExprKind::Call(callee, _) => match callee.kind {
+                    hir::ExprKind::Path(ref path) => {
+                        match self.tcx.typeck(owner).qpath_res(path, callee.hir_id) {
+                            Res::Def(_, def_id) => Some(def_id),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                },
+                hir::ExprKind::MethodCall(..) => {
+                    self.tcx.typeck(owner).type_dependent_def_id(ex.hir_id)
+                }
+                _ => None,
+            };
+            if let Some(def_id) = called_def_id {
+                if self.is_impure_function(def_id) {
+                    let extern_spec_span = self.def_spec.extern_specs
+                        .get(&def_id)
+                        .map(|spec_id| self.tcx.def_span(spec_id.to_def_id()));
+                    self.impure_calls.push((ex.span, def_id, extern_spec_span));
+                }
+            }
+        }
+
+        intravisit::walk_expr(self, ex);
+    }
+
+    fn visit_fn(
+        &mut self,
+        fk: intravisit::FnKind<'tcx>,
+        fd: &'tcx hir::FnDecl<'tcx>,
+        b: hir::BodyId,
+        s: Span,
+        id: hir::HirId,
+    ) {
+        let attrs = self.tcx.hir().attrs(id);
+        let in_spec = self.in_spec;
+        self.in_spec = in_spec || has_spec_only_attr(attrs);
+        intravisit::walk_fn(self, fk, fd, b, s, id);
+        self.in_spec = in_spec;
+    }
+}
+
+/// Finds the quantifiers without explicit triggers whose bound variables are
+/// not the arguments of any function call, method call or indexing in the
+/// body of the quantifier. The verifier cannot infer triggers for such
+/// variables, or only triggers such as `f(i + 1)` that may cause matching
+/// loops.
+struct CheckTriggersVisitor<'v, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    weak_triggers: &'v mut Vec<(Span, Vec<String>, Vec<String>)>,
+}
+
+impl<'v, 'tcx> CheckTriggersVisitor<'v, 'tcx> {
+    /// The closure of a call to `forall` or `exists` without triggers.
+    fn quantifier_without_triggers(&self, ex: &'tcx hir::Expr<'tcx>) -> Option<hir::BodyId> {
+        let (callee, args) = match ex.kind {
+            hir::ExprKind::Call(callee, args) => (callee, args),
+            _ => return None,
+        };
+        let path = match callee.kind {
+            hir::ExprKind::Path(ref path) => path,
+            _ => return None,
+        };
+        let def_id = match self.tcx.typeck(ex.hir_id.owner).qpath_res(path, callee.hir_id) {
+            Res::Def(_, def_id) => def_id,
+            _ => return None,
+        };
+        if !is_quantifier(self.tcx, def_id) {
+            return None;
+        }
+        match args {
+            [hir::Expr { kind: hir::ExprKind::Tup([]), .. }, hir::Expr {
+                kind: hir::ExprKind::Closure(_, _, body_id, _, _),
+                ..
+            }] => Some(*body_id),
+            _ => None,
+        }
+    }
+}
+
+impl<'v, 'tcx> Visitor<'tcx> for CheckTriggersVisitor<'v, 'tcx> {
+    type Map = Map<'tcx>;
+    type NestedFilter = rustc_middle::hir::nested_filter::All;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_expr(&mut self, ex: &'tcx hir::Expr<'tcx>) {
+        if let Some(body_id) = self.quantifier_without_triggers(ex) {
+            let body = self.tcx.hir().body(body_id);
+            let bound_vars: Vec<(hir::HirId, String)> = body.params
+                .iter()
+                .filter_map(|param| match param.pat.kind {
+                    hir::PatKind::Binding(_, hir_id, ident, _) => {
+                        Some((hir_id, ident.to_string()))
+                    }
+                    _ => None,
+                })
+                .collect();
+            let mut candidates = TriggerCandidatesVisitor {
+                tcx: self.tcx,
+                bound_vars: bound_vars.iter().map(|(hir_id, _)| *hir_id).collect(),
+                covered: vec![],
+                weakly_covered: vec![],
+            };
+            candidates.visit_expr(&body.value);
+            let missing: Vec<String> = bound_vars.iter()
+                .filter(|(hir_id, _)| {
+                    !candidates.covered.contains(hir_id)
+                        && !candidates.weakly_covered.contains(hir_id)
+                })
+                .map(|(_, name)| name.clone())
+                .collect();
+            let weak: Vec<String> = bound_vars.iter()
+                .filter(|(hir_id, _)| {
+                    !candidates.covered.contains(hir_id)
+                        && candidates.weakly_covered.contains(hir_id)
+                })
+                .map(|(_, name)| name.clone())
+                .collect();
+            if !missing.is_empty() || !weak.is_empty() {
+                self.weak_triggers.push((body.value.span, missing, weak));
+            }
+        }
+
+        intravisit::walk_expr(self, ex);
+    }
+}
+
+/// Collects the bound variables of a quantifier that are arguments of the
+/// function calls, method calls, indexing expressions and field accesses of
+/// its body, which are the terms from which the verifier infers triggers.
+struct TriggerCandidatesVisitor<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    bound_vars: Vec<hir::HirId>,
+    /// Bound variables that are arguments of a candidate.
+    covered: Vec<hir::HirId>,
+    /// Bound variables that appear in an argument of a candidate only under
+    /// an arithmetic operation, e.g. `f(i + 1)`.
+    weakly_covered: Vec<hir::HirId>,
+}
+
+impl<'tcx> TriggerCandidatesVisitor<'tcx> {
+    fn add_argument(&mut self, arg: &'tcx hir::Expr<'tcx>) {
+        let mut inner = arg;
+        loop {
+            inner = match inner.kind {
+                hir::ExprKind::AddrOf(_, _, expr)
+                | hir::ExprKind::Unary(hir::UnOp::Deref, expr)
+                | hir::ExprKind::Field(expr, _)
+                | hir::ExprKind::Cast(expr, _)
+                | hir::ExprKind::Type(expr, _)
+                | hir::ExprKind::DropTemps(expr) => expr,
+                _ => break,
+            };
+        }
+        if let Some(hir_id) = self.bound_var(inner) {
+            if !self.covered.contains(&hir_id) {
+                self.covered.push(hir_id);
+            }
+            return;
+        }
+        let mut mentioned = MentionedVarsVisitor {
+            tcx: self.tcx,
+            bound_vars: &self.bound_vars,
+            mentioned: vec![],
+        };
+        mentioned.visit_expr(arg);
+        for hir_id in mentioned.mentioned {
+            if !self.weakly_covered.contains(&hir_id) {
+                self.weakly_covered.push(hir_id);
+            }
+        }
+    }
+
+    fn bound_var(&self, ex: &hir::Expr) -> Option<hir::HirId> {
+        match ex.kind {
+            hir::ExprKind::Path(hir::QPath::Resolved(None, path)) => match path.res {
+                Res::Local(hir_id) if self.bound_vars.contains(&hir_id) => Some(hir_id),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for TriggerCandidatesVisitor<'tcx> {
+    type Map = Map<'tcx>;
+    type NestedFilter = rustc_middle::hir::nested_filter::All;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_expr(&mut self, ex: &'tcx hir::Expr<'tcx>) {
+        match ex.kind {
+            hir::ExprKind::Call(callee, args) => {
+                // The calls to `old(..)`, nested quantifiers and similar are
+                // not trigger candidates themselves.
+                let is_candidate = match callee.kind {
+                    hir::ExprKind::Path(ref path) => matches!(
+                        self.tcx.typeck(ex.hir_id.owner).qpath_res(path, callee.hir_id),
+                        Res::Def(DefKind::Fn | DefKind::AssocFn, def_id)
+                            if !self.tcx.def_path_str(def_id).starts_with("prusti_contracts::")
+                    ),
+                    _ => false,
+                };
+                if is_candidate {
+                    for arg in args {
+                        self.add_argument(arg);
+                    }
+                }
+            }
+            hir::ExprKind::MethodCall(_, args, _) => {
+                for arg in args {
+                    self.add_argument(arg);
+                }
+            }
+            hir::ExprKind::Index(base, index) => {
+                self.add_argument(base);
+                self.add_argument(index);
+            }
+            // Reading a field is encoded as a function of the snapshot.
+            hir::ExprKind::Field(base, _) => self.add_argument(base),
+            _ => {}
+        }
+        intravisit::walk_expr(self, ex);
+    }
+}
+
+/// Collects the bound variables mentioned by an expression.
+struct MentionedVarsVisitor<'v, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    bound_vars: &'v [hir::HirId],
+    mentioned: Vec<hir::HirId>,
+}
+
+impl<'v, 'tcx> Visitor<'tcx> for MentionedVarsVisitor<'v, 'tcx> {
+    type Map = Map<'tcx>;
+    type NestedFilter = rustc_middle::hir::nested_filter::All;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_expr(&mut self, ex: &'tcx hir::Expr<'tcx>) {
+        if let hir::ExprKind::Path(hir::QPath::Resolved(None, path)) = ex.kind {
+            if let Res::Local(hir_id) = path.res {
+                if self.bound_vars.contains(&hir_id) && !self.mentioned.contains(&hir_id) {
+                    self.mentioned.push(hir_id);
+                }
+            }
+        }
+        intravisit::walk_expr(self, ex);
+    }
+}
+
+fn is_quantifier(tcx: TyCtxt, def_id: DefId) -> bool {
+    matches!(
+        tcx.def_path_str(def_id).as_str(),
+        "prusti_contracts::forall" | "prusti_contracts::exists"
+    )
+}
+
 impl<'tcx> SpecChecker {
     pub fn new() -> Self {
         Self::default()
@@ -152,8 +497,90 @@ impl<'tcx> SpecChecker {
         debug!("Predicate usages: {:?}", self.pred_usages);
     }
 
-    pub fn report_errors(&self, env: &Environment<'tcx>) {
-        for &(usage_span, def_span) in &self.pred_usages {
+    /// Check the calls in specifications once the specifications are
+    /// collected, before the encoding, so that all calls to impure functions
+    /// are reported at once.
+    pub fn check_spec_calls(&mut self, tcx: TyCtxt<'tcx>, def_spec: &DefSpecificationMap) {
+        let mut visit = CheckSpecCallsVisitor {
+            tcx,
+            def_spec,
+            in_spec: false,
+            impure_calls: &mut self.impure_calls,
+        };
+        tcx.hir().walk_toplevel_module(&mut visit);
+        tcx.hir().walk_attributes(&mut visit);
+
+        debug!("Impure calls in specifications: {:?}", self.impure_calls);
+    }
+
+    /// Find the trusted functions that return a value or take a mutable
+    /// reference, but whose effect is not described by a postcondition. Calls
+    /// to them havoc their outputs, which is a common source of confusion.
+    pub fn check_trusted_specs(&mut self, tcx: TyCtxt<'tcx>, def_spec: &DefSpecificationMap) {
+        if !config::warn_trusted_without_postcondition() {
+            return;
+        }
+        for (local_def_id, spec_set) in &def_spec.specs {
+            let spec = match spec_set {
+                SpecificationSet::Procedure(spec) => spec,
+                _ => continue,
+            };
+            let def_id = local_def_id.to_def_id();
+            let is_trusted = spec.trusted.extract_inherit().unwrap_or(false);
+            if !is_trusted || spec.kind.is_pure().unwrap_or(true) {
+                continue;
+            }
+            let has_posts = spec.posts
+                .extract_with_selective_replacement()
+                .map_or(false, |posts| !posts.is_empty());
+            let has_pledges = spec.pledges
+                .extract_with_selective_replacement()
+                .map_or(false, |pledges| !pledges.is_empty());
+            if has_posts || has_pledges || has_extern_spec_attr(tcx.get_attrs(def_id)) {
+                continue;
+            }
+            let sig = tcx.fn_sig(def_id).skip_binder();
+            let has_outputs = !sig.output().is_unit()
+                || sig.inputs().iter().any(|input| input.is_mutable_ptr());
+            if has_outputs {
+                self.trusted_without_postcondition.push(def_id);
+            }
+        }
+        self.trusted_without_postcondition.sort_by_key(|&def_id| tcx.def_span(def_id));
+    }
+
+    /// Find the quantifiers without explicit triggers for which the verifier
+    /// cannot infer good triggers.
+    pub fn check_quantifier_triggers(&mut self, tcx: TyCtxt<'tcx>) {
+        if !config::warn_missing_triggers() {
+            return;
+        }
+        let mut visit = CheckTriggersVisitor {
+            tcx,
+            weak_triggers: &mut self.weak_triggers,
+        };
+        tcx.hir().walk_toplevel_module(&mut visit);
+        tcx.hir().walk_attributes(&mut visit);
+        self.weak_triggers.sort_by_key(|(span, _, _)| *span);
+
+        debug!("Quantifiers with weak triggers: {:?}", self.weak_triggers);
+    }
+
+    /// Find the laws that are not declared in a trait, e.g. in an impl of the
+    /// trait, which the procedural macros cannot distinguish from functions.
+    pub fn check_laws(&mut self, tcx: TyCtxt<'tcx>) {
+        for local_def_id in tcx.hir().body_owners() {
+            let def_id = local_def_id.to_def_id();
+            if has_prusti_attr(tcx.get_attrs(def_id), "law") && tcx.trait_of_item(def_id).is_none() {
+                self.misplaced_laws.push(def_id);
+            }
+        }
+        self.misplaced_laws.sort_by_key(|&def_id| tcx.def_span(def_id));
+    }
+
+    /// Report the errors found by the checks run since the last report.
+    pub fn report_errors(&mut self, env: &Environment<'tcx>) {
+        for (usage_span, def_span) in self.pred_usages.drain(..) {
             PrustiError::incorrect(
                 "using predicate from non-specification code is not allowed".to_string(),
                 MultiSpan::from_span(usage_span),
@@ -161,5 +588,104 @@ impl<'tcx> SpecChecker {
             .add_note("this is a specification-only predicate function", Some(def_span))
             .emit(env);
         }
+        for (call_span, def_id, extern_spec_span) in self.impure_calls.drain(..) {
+            let name = env.get_absolute_item_name(def_id);
+            let error = PrustiError::incorrect(
+                format!("use of impure function {:?} in pure code is not allowed", name),
+                MultiSpan::from_span(call_span),
+            );
+            let error = if let Some(extern_spec_span) = extern_spec_span {
+                error
+                    .add_note(
+                        "the external specification of the function does not mark it as pure",
+                        Some(extern_spec_span),
+                    )
+                    .set_help(format!(
+                        "specifications can only call pure functions; if `{}` is \
+                        deterministic and has no side effects, add `#[pure]` to its external \
+                        specification",
+                        name,
+                    ))
+            } else {
+                error
+                    .add_note("the function is not marked as pure", Some(env.get_def_span(def_id)))
+                    .set_help(format!(
+                        "specifications can only call pure functions; if `{}` is \
+                        deterministic and has no side effects, mark it with `#[pure]`, \
+                        otherwise wrap the part of it used by the specification in a \
+                        `#[pure]` function",
+                        name,
+                    ))
+            };
+            error.emit(env);
+        }
+        for def_id in self.trusted_without_postcondition.drain(..) {
+            let name = env.get_absolute_item_name(def_id);
+            PrustiError::warning(
+                format!("trusted function {:?} has no postcondition", name),
+                MultiSpan::from_span(env.get_def_span(def_id)),
+            )
+            .set_help(
+                "the body of a trusted function is not verified, so after a call its result \
+                and the values behind the mutable references passed to it are unconstrained; \
+                describe them with `#[ensures(..)]`",
+            )
+            .emit(env);
+        }
+        for def_id in self.misplaced_laws.drain(..) {
+            let error = PrustiError::incorrect(
+                "a law must be declared in a trait".to_string(),
+                MultiSpan::from_span(env.get_def_span(def_id)),
+            );
+            let error = if env.is_trait_method_impl(def_id) {
+                error.set_help(
+                    "the implementations of a law inherit its contract; remove `#[law]` here \
+                    and add it to the declaration of the method in the trait",
+                )
+            } else {
+                error.set_help("use `#[lemma]` for a lemma that is not part of a trait")
+            };
+            error.emit(env);
+        }
+        for (span, missing, weak) in self.weak_triggers.drain(..) {
+            let format_vars = |vars: &[String]| vars.iter()
+                .map(|var| format!("`{}`", var))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut warning = if missing.is_empty() {
+                PrustiError::warning(
+                    format!(
+                        "the triggers of this quantifier for {} can only contain arithmetic terms",
+                        format_vars(&weak),
+                    ),
+                    MultiSpan::from_span(span),
+                )
+            } else {
+                PrustiError::warning(
+                    format!("no trigger can be inferred for {} in this quantifier", format_vars(&missing)),
+                    MultiSpan::from_span(span),
+                )
+            };
+            warning = warning.add_note(
+                "the verifier instantiates a quantifier only for the terms that match one of \
+                its triggers, the function calls of its body that mention all bound variables; \
+                a quantifier without triggers is never instantiated when it is assumed",
+                None,
+            );
+            if !weak.is_empty() {
+                warning = warning.add_note(
+                    "a trigger such as `f(i + 1)` can cause a matching loop: instantiating the \
+                    quantifier for `f(i + 1)` produces the term `f(i + 2)`, which matches the \
+                    trigger again, and so on until the verifier times out",
+                    None,
+                );
+            }
+            warning
+                .set_help(
+                    "add a pure function call that mentions the bound variables as an explicit \
+                    trigger, e.g. `forall(|i: usize| ..., triggers = [(f(i),)])`",
+                )
+                .emit(env);
+        }
     }
 }
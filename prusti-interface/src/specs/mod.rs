@@ -44,6 +44,30 @@ pub struct SpecCollector<'a, 'tcx: 'a> {
     /// Map from functions/loops and their specifications.
     procedure_specs: HashMap<LocalDefId, ProcedureSpecRefs>,
     loop_specs: Vec<LocalDefId>, // HashMap<LocalDefId, Vec<SpecificationId>>,
+    proof_step_specs: Vec<LocalDefId>,
+    yield_invariant_specs: Vec<LocalDefId>,
+    resume_precondition_specs: Vec<LocalDefId>,
+
+    /// Lemmas that are registered as axioms, with the ID of their axiom.
+    lemma_axioms: Vec<(LocalDefId, SpecificationId)>,
+
+    /// Spec functions of lock invariants, with the function they specify.
+    lock_invariant_specs: Vec<(String, LocalDefId)>,
+
+    /// Spec functions of message invariants, with the function they specify.
+    message_invariant_specs: Vec<(String, LocalDefId)>,
+
+    /// Spec functions of the refinements of structs.
+    refinement_specs: Vec<LocalDefId>,
+
+    /// Spec functions of the constraints of symbolic constants.
+    symbolic_constraint_specs: Vec<LocalDefId>,
+
+    /// Function stubs of domains, with the name of their domain.
+    domain_functions: Vec<(String, LocalDefId)>,
+
+    /// Spec functions of the axioms of domains, with the name of their domain.
+    domain_axiom_specs: Vec<(String, LocalDefId)>,
 }
 
 impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
@@ -56,6 +80,16 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
             spec_functions: HashMap::new(),
             procedure_specs: HashMap::new(),
             loop_specs: vec![],
+            proof_step_specs: vec![],
+            yield_invariant_specs: vec![],
+            resume_precondition_specs: vec![],
+            lemma_axioms: vec![],
+            lock_invariant_specs: vec![],
+            message_invariant_specs: vec![],
+            refinement_specs: vec![],
+            symbolic_constraint_specs: vec![],
+            domain_functions: vec![],
+            domain_axiom_specs: vec![],
         }
     }
 
@@ -64,7 +98,12 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
         self.determine_procedure_specs(&mut def_spec);
         self.determine_extern_specs(&mut def_spec);
         self.determine_loop_specs(&mut def_spec);
+        self.determine_proof_step_specs(&mut def_spec);
+        self.determine_yield_invariant_specs(&mut def_spec);
+        self.determine_resume_precondition_specs(&mut def_spec);
         self.determine_struct_specs(&mut def_spec);
+        self.determine_lemma_axioms(&mut def_spec);
+        self.determine_domains(&mut def_spec);
         // TODO: remove spec functions (make sure none are duplicated or left over)
 
         def_spec
@@ -157,8 +196,68 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
         }
     }
 
-    // TODO: struct specs
-    fn determine_struct_specs(&self, _def_spec: &mut typed::DefSpecificationMap) {}
+    fn determine_proof_step_specs(&self, def_spec: &mut typed::DefSpecificationMap) {
+        for local_id in self.proof_step_specs.iter() {
+            def_spec.specs.insert(*local_id, typed::SpecificationSet::ProofStep(typed::ProofStepSpecification {
+                assertion: *local_id,
+            }));
+        }
+    }
+
+    fn determine_yield_invariant_specs(&self, def_spec: &mut typed::DefSpecificationMap) {
+        for local_id in self.yield_invariant_specs.iter() {
+            def_spec.specs.insert(*local_id, typed::SpecificationSet::YieldInvariant(typed::YieldInvariantSpecification {
+                invariant: *local_id,
+            }));
+        }
+    }
+
+    fn determine_resume_precondition_specs(&self, def_spec: &mut typed::DefSpecificationMap) {
+        for local_id in self.resume_precondition_specs.iter() {
+            def_spec.specs.insert(*local_id, typed::SpecificationSet::ResumePrecondition(typed::ResumePreconditionSpecification {
+                precondition: *local_id,
+            }));
+        }
+    }
+
+    fn determine_struct_specs(&self, def_spec: &mut typed::DefSpecificationMap) {
+        for (target, local_id) in self.lock_invariant_specs.iter() {
+            def_spec.lock_invariants
+                .entry(target.clone())
+                .or_default()
+                .push(*local_id);
+        }
+        for (target, local_id) in self.message_invariant_specs.iter() {
+            def_spec.message_invariants
+                .entry(target.clone())
+                .or_default()
+                .push(*local_id);
+        }
+        def_spec.refinements.extend(self.refinement_specs.iter().copied());
+        def_spec.symbolic_constraints.extend(self.symbolic_constraint_specs.iter().copied());
+    }
+
+    fn determine_lemma_axioms(&self, def_spec: &mut typed::DefSpecificationMap) {
+        for (lemma_id, spec_id) in self.lemma_axioms.iter() {
+            let axiom_id = *self.spec_functions.get(spec_id).unwrap();
+            def_spec.lemma_axioms.insert(*lemma_id, axiom_id);
+        }
+    }
+
+    fn determine_domains(&self, def_spec: &mut typed::DefSpecificationMap) {
+        for (domain, local_id) in self.domain_functions.iter() {
+            def_spec.domain_functions
+                .entry(domain.clone())
+                .or_default()
+                .push(*local_id);
+        }
+        for (domain, local_id) in self.domain_axiom_specs.iter() {
+            def_spec.domain_axioms
+                .entry(domain.clone())
+                .or_default()
+                .push(*local_id);
+        }
+    }
 }
 
 fn parse_spec_id(spec_id: String, def_id: DefId) -> SpecificationId {
@@ -266,6 +365,46 @@ impl<'a, 'tcx> intravisit::Visitor<'tcx> for SpecCollector<'a, 'tcx> {
             if has_prusti_attr(attrs, "loop_body_invariant_spec") {
                 self.loop_specs.push(local_id);
             }
+
+            // Collect the lines of `prusti_calc!` proofs
+            if has_prusti_attr(attrs, "proof_step_spec") {
+                self.proof_step_specs.push(local_id);
+            }
+
+            // Collect the yield invariants of generators
+            if has_prusti_attr(attrs, "yield_invariant_spec") {
+                self.yield_invariant_specs.push(local_id);
+            }
+
+            // Collect the resume preconditions of generators
+            if has_prusti_attr(attrs, "resume_requires_spec") {
+                self.resume_precondition_specs.push(local_id);
+            }
+
+            // Collect lock invariants
+            if let Some(target) = read_prusti_attr("lock_invariant_spec", attrs) {
+                self.lock_invariant_specs.push((target, local_id));
+            }
+
+            // Collect message invariants
+            if let Some(target) = read_prusti_attr("message_invariant_spec", attrs) {
+                self.message_invariant_specs.push((target, local_id));
+            }
+
+            // Collect refinements
+            if has_prusti_attr(attrs, "refinement_spec") {
+                self.refinement_specs.push(local_id);
+            }
+
+            // Collect the constraints of symbolic constants
+            if has_prusti_attr(attrs, "symbolic_spec") {
+                self.symbolic_constraint_specs.push(local_id);
+            }
+
+            // Collect the axioms of domains
+            if let Some(domain) = read_prusti_attr("domain_axiom_spec", attrs) {
+                self.domain_axiom_specs.push((domain, local_id));
+            }
         } else {
             // Don't collect specs "for" spec items
 
@@ -280,6 +419,17 @@ impl<'a, 'tcx> intravisit::Visitor<'tcx> for SpecCollector<'a, 'tcx> {
             if let Some(procedure_spec_ref) = get_procedure_spec_ids(def_id, attrs) {
                 self.procedure_specs.insert(local_id, procedure_spec_ref);
             }
+
+            // Collect lemmas registered as axioms
+            if let Some(raw_spec_id) = read_prusti_attr("lemma_axiom_spec_id_ref", attrs) {
+                let spec_id = parse_spec_id(raw_spec_id, def_id);
+                self.lemma_axioms.push((local_id, spec_id));
+            }
+
+            // Collect the functions of domains
+            if let Some(domain) = read_prusti_attr("domain_function", attrs) {
+                self.domain_functions.push((domain, local_id));
+            }
         }
     }
 
@@ -10,6 +10,9 @@ use std::fmt::{Display, Formatter};
 pub enum SpecificationSet {
     Procedure(ProcedureSpecification),
     Loop(LoopSpecification),
+    ProofStep(ProofStepSpecification),
+    YieldInvariant(YieldInvariantSpecification),
+    ResumePrecondition(ResumePreconditionSpecification),
 }
 
 impl SpecificationSet {
@@ -68,6 +71,30 @@ impl SpecificationSet {
         }
         None
     }
+
+    #[track_caller]
+    pub fn as_proof_step(&self) -> Option<&ProofStepSpecification> {
+        if let SpecificationSet::ProofStep(spec) = self {
+            return Some(spec);
+        }
+        None
+    }
+
+    #[track_caller]
+    pub fn as_yield_invariant(&self) -> Option<&YieldInvariantSpecification> {
+        if let SpecificationSet::YieldInvariant(spec) = self {
+            return Some(spec);
+        }
+        None
+    }
+
+    #[track_caller]
+    pub fn as_resume_precondition(&self) -> Option<&ResumePreconditionSpecification> {
+        if let SpecificationSet::ResumePrecondition(spec) = self {
+            return Some(spec);
+        }
+        None
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -324,11 +351,48 @@ pub struct LoopSpecification {
     pub invariant: LocalDefId,
 }
 
+/// A line of a `prusti_calc!` proof.
+#[derive(Debug, Clone)]
+pub struct ProofStepSpecification {
+    pub assertion: LocalDefId,
+}
+
+/// A `yield_invariant!` of a generator.
+#[derive(Debug, Clone)]
+pub struct YieldInvariantSpecification {
+    pub invariant: LocalDefId,
+}
+
+/// A `resume_requires!` of a generator.
+#[derive(Debug, Clone)]
+pub struct ResumePreconditionSpecification {
+    pub precondition: LocalDefId,
+}
+
 /// A map of specifications keyed by crate-local DefIds.
 #[derive(Default, Debug, Clone)]
 pub struct DefSpecificationMap {
     pub specs: HashMap<LocalDefId, SpecificationSet>,
     pub extern_specs: HashMap<DefId, LocalDefId>,
+    /// Map from lemmas registered as axioms to the spec function of the axiom.
+    pub lemma_axioms: HashMap<LocalDefId, LocalDefId>,
+    /// Map from the functions of `Mutex` and `RwLock` to the spec functions
+    /// of the lock invariants attached to them, e.g.
+    /// `std::sync::MutexGuard::deref`.
+    pub lock_invariants: HashMap<String, Vec<LocalDefId>>,
+    /// Map from the functions of the channels of `std::sync::mpsc` to the
+    /// spec functions of the message invariants attached to them, e.g.
+    /// `std::sync::mpsc::Sender::send`.
+    pub message_invariants: HashMap<String, Vec<LocalDefId>>,
+    /// The spec functions of the refinements of `#[refined]` structs.
+    pub refinements: Vec<LocalDefId>,
+    /// The spec functions of the constraints of `#[symbolic]` constants.
+    pub symbolic_constraints: Vec<LocalDefId>,
+    /// Map from the names of `#[domain]` modules to their function stubs.
+    pub domain_functions: HashMap<String, Vec<LocalDefId>>,
+    /// Map from the names of `#[domain]` modules to the spec functions of
+    /// their axioms.
+    pub domain_axioms: HashMap<String, Vec<LocalDefId>>,
 }
 
 impl DefSpecificationMap {
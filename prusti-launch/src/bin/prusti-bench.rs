@@ -0,0 +1,314 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Verifies a fixed corpus of Rust files and records the encoding and
+//! verification time of each function, as reported by `PERFORMANCE_REPORT`.
+//!
+//! Each file is verified several times and the minimum of each time is kept,
+//! which is less sensitive to the load of the machine than the mean. The
+//! times are written to a JSON file that can be given with `--baseline` to a
+//! later run, which then fails if the time of a file or of a function
+//! regressed by more than the threshold.
+
+use prusti_launch::find_prusti_rustc;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Instant,
+};
+
+const USAGE: &str = "Usage: prusti-bench [--corpus <file>] [--iterations <n>] [--output <file>] \
+                     [--baseline <file>] [--threshold <percent>] [--min-difference <ms>] \
+                     [-- <prusti-rustc arguments>]";
+
+struct Options {
+    /// A file listing the Rust files to verify, one per line.
+    corpus: PathBuf,
+    iterations: usize,
+    output: PathBuf,
+    baseline: Option<PathBuf>,
+    /// The relative increase of a time, in percent, above which it is
+    /// reported as a regression.
+    threshold: f64,
+    /// The absolute increase of a time, in milliseconds, below which it is
+    /// never reported as a regression, to ignore the noise of short times.
+    min_difference: f64,
+    prusti_args: Vec<String>,
+}
+
+/// The times of one file, indexed by the absolute path of the functions.
+#[derive(Default, Serialize, Deserialize)]
+struct FileTimes {
+    /// The wall-clock time of `prusti-rustc`.
+    total_ms: f64,
+    functions: BTreeMap<String, FunctionTimes>,
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+struct FunctionTimes {
+    encoding_ms: f64,
+    foldunfold_ms: f64,
+    viper_ms: f64,
+}
+
+impl FunctionTimes {
+    fn total_ms(&self) -> f64 {
+        self.encoding_ms + self.viper_ms
+    }
+
+    fn min(&self, other: &FunctionTimes) -> FunctionTimes {
+        FunctionTimes {
+            encoding_ms: self.encoding_ms.min(other.encoding_ms),
+            foldunfold_ms: self.foldunfold_ms.min(other.foldunfold_ms),
+            viper_ms: self.viper_ms.min(other.viper_ms),
+        }
+    }
+}
+
+/// An entry of the `prusti-report.json` written by Prusti.
+#[derive(Deserialize)]
+struct ReportEntry {
+    function: String,
+    encoding_ms: f64,
+    foldunfold_ms: f64,
+    viper_ms: f64,
+}
+
+/// The times of all files, indexed by their path.
+type BenchmarkTimes = BTreeMap<String, FileTimes>;
+
+fn main() {
+    let options = match parse_args(env::args().skip(1).collect()) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+    match bench(options) {
+        Ok(0) => {}
+        Ok(regressions) => {
+            eprintln!("error: found {} performance regressions", regressions);
+            std::process::exit(1);
+        }
+        Err(message) => {
+            eprintln!("error: {}", message);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut args = args.into_iter();
+    let mut options = Options {
+        corpus: PathBuf::from("benchmarked-files.csv"),
+        iterations: 3,
+        output: PathBuf::from("benchmark-output/prusti-bench.json"),
+        baseline: None,
+        threshold: 20.0,
+        min_difference: 100.0,
+        prusti_args: vec![],
+    };
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value of {}", name));
+        match arg.as_str() {
+            "--corpus" => options.corpus = PathBuf::from(value("--corpus")?),
+            "--iterations" => {
+                options.iterations = value("--iterations")?
+                    .parse()
+                    .map_err(|_| "the number of iterations must be a number")?
+            }
+            "--output" => options.output = PathBuf::from(value("--output")?),
+            "--baseline" => options.baseline = Some(PathBuf::from(value("--baseline")?)),
+            "--threshold" => {
+                options.threshold = value("--threshold")?
+                    .parse()
+                    .map_err(|_| "the threshold must be a number")?
+            }
+            "--min-difference" => {
+                options.min_difference = value("--min-difference")?
+                    .parse()
+                    .map_err(|_| "the minimum difference must be a number")?
+            }
+            "--" => options.prusti_args.extend(args.by_ref()),
+            _ => return Err(format!("unexpected argument {:?}", arg)),
+        }
+    }
+    if options.iterations == 0 {
+        return Err("the number of iterations must be positive".to_string());
+    }
+    Ok(options)
+}
+
+/// Run the benchmark, returning the number of regressions with respect to
+/// the baseline.
+fn bench(options: Options) -> Result<usize, String> {
+    // Read the baseline first, to not run the benchmark if it is invalid.
+    let baseline = match &options.baseline {
+        Some(path) => Some(read_times(path)?),
+        None => None,
+    };
+    let corpus = fs::read_to_string(&options.corpus)
+        .map_err(|err| format!("failed to read {}: {}", options.corpus.display(), err))?;
+    let files: Vec<&str> = corpus
+        .lines()
+        .filter_map(|line| line.split(',').next())
+        .map(str::trim)
+        .filter(|file| !file.is_empty() && !file.starts_with('#'))
+        .collect();
+    let work_dir = env::temp_dir().join(format!("prusti-bench-{}", std::process::id()));
+    let prusti_rustc = find_prusti_rustc();
+
+    let mut times = BenchmarkTimes::new();
+    for file in files {
+        eprintln!("Benchmarking {}", file);
+        let mut file_times: Option<FileTimes> = None;
+        for _ in 0..options.iterations {
+            let run_times = run(&prusti_rustc, &options.prusti_args, &work_dir, file)?;
+            file_times = Some(match file_times {
+                None => run_times,
+                Some(previous) => min_times(previous, run_times),
+            });
+        }
+        times.insert(file.to_string(), file_times.unwrap());
+    }
+    let _ = fs::remove_dir_all(&work_dir);
+
+    if let Some(parent) = options.output.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("failed to create {}: {}", parent.display(), err))?;
+    }
+    let json = serde_json::to_string_pretty(&times).map_err(|err| err.to_string())?;
+    fs::write(&options.output, json)
+        .map_err(|err| format!("failed to write {}: {}", options.output.display(), err))?;
+    eprintln!("Wrote the times to {}", options.output.display());
+
+    Ok(match baseline {
+        Some(baseline) => compare(&baseline, &times, &options),
+        None => 0,
+    })
+}
+
+fn read_times(path: &Path) -> Result<BenchmarkTimes, String> {
+    let json = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+    serde_json::from_str(&json)
+        .map_err(|err| format!("failed to parse {}: {}", path.display(), err))
+}
+
+/// Verify `file` once, returning its times.
+fn run(
+    prusti_rustc: &Path,
+    prusti_args: &[String],
+    work_dir: &Path,
+    file: &str,
+) -> Result<FileTimes, String> {
+    // Prusti writes its report to the log directory, which is emptied
+    // before each run so that a stale report is never read.
+    let _ = fs::remove_dir_all(work_dir);
+    fs::create_dir_all(work_dir)
+        .map_err(|err| format!("failed to create {}: {}", work_dir.display(), err))?;
+    let mut command = Command::new(prusti_rustc);
+    command
+        .arg("--edition=2018")
+        .args(prusti_args)
+        .arg(file)
+        .env("PRUSTI_PERFORMANCE_REPORT", "true")
+        .env("PRUSTI_LOG_DIR", work_dir);
+    // The default corpus consists of tests that are verified without
+    // overflow checks.
+    if env::var_os("PRUSTI_CHECK_OVERFLOWS").is_none() {
+        command.env("PRUSTI_CHECK_OVERFLOWS", "false");
+    }
+    let start = Instant::now();
+    let output = command
+        .output()
+        .map_err(|err| format!("failed to execute {}: {}", prusti_rustc.display(), err))?;
+    let total_ms = start.elapsed().as_secs_f64() * 1000.0;
+    if !output.status.success() {
+        return Err(format!(
+            "Prusti failed on {}:\n{}{}",
+            file,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let report_path = work_dir.join("prusti-report.json");
+    let report = fs::read_to_string(&report_path)
+        .map_err(|err| format!("failed to read {}: {}", report_path.display(), err))?;
+    let entries: Vec<ReportEntry> = serde_json::from_str(&report)
+        .map_err(|err| format!("failed to parse {}: {}", report_path.display(), err))?;
+    let mut functions = BTreeMap::new();
+    for entry in entries {
+        // A function may be encoded in several Viper programs.
+        let times = functions.entry(entry.function).or_insert(FunctionTimes {
+            encoding_ms: 0.0,
+            foldunfold_ms: 0.0,
+            viper_ms: 0.0,
+        });
+        times.encoding_ms += entry.encoding_ms;
+        times.foldunfold_ms += entry.foldunfold_ms;
+        times.viper_ms += entry.viper_ms;
+    }
+    Ok(FileTimes {
+        total_ms,
+        functions,
+    })
+}
+
+fn min_times(mut previous: FileTimes, next: FileTimes) -> FileTimes {
+    previous.total_ms = previous.total_ms.min(next.total_ms);
+    for (function, next_times) in next.functions {
+        previous
+            .functions
+            .entry(function)
+            .and_modify(|times| *times = times.min(&next_times))
+            .or_insert(next_times);
+    }
+    previous
+}
+
+/// Print the times that regressed with respect to the baseline and return
+/// their number. Files and functions that are not in both runs are ignored.
+fn compare(baseline: &BenchmarkTimes, times: &BenchmarkTimes, options: &Options) -> usize {
+    let mut regressions = 0;
+    let mut check = |name: &str, old_ms: f64, new_ms: f64| {
+        let regressed = new_ms - old_ms > options.min_difference
+            && new_ms > old_ms * (1.0 + options.threshold / 100.0);
+        if regressed {
+            regressions += 1;
+            println!(
+                "Regression: {} took {:.0} ms instead of {:.0} ms (+{:.0}%)",
+                name,
+                new_ms,
+                old_ms,
+                (new_ms / old_ms - 1.0) * 100.0
+            );
+        }
+    };
+    for (file, file_times) in times {
+        let old_file_times = match baseline.get(file) {
+            Some(old_file_times) => old_file_times,
+            None => continue,
+        };
+        check(file, old_file_times.total_ms, file_times.total_ms);
+        for (function, function_times) in &file_times.functions {
+            if let Some(old_function_times) = old_file_times.functions.get(function) {
+                check(
+                    &format!("{} in {}", function, file),
+                    old_function_times.total_ms(),
+                    function_times.total_ms(),
+                );
+            }
+        }
+    }
+    regressions
+}
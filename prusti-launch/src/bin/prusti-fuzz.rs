@@ -0,0 +1,455 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Generates random safe Rust programs over integers and checks that:
+//! - Prusti does not crash or report an internal error on them;
+//! - the programs that Prusti verifies do not panic when they are executed
+//!   with inputs that satisfy the preconditions of their functions, and that
+//!   the results satisfy the postconditions.
+//!
+//! The programs that violate one of these properties are written to the
+//! output directory. Each program is generated from its own seed, printed in
+//! the name of the file, so that it can be generated again with
+//! `--seed <seed> --iterations 1`.
+
+use prusti_launch::find_prusti_rustc;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const USAGE: &str = "Usage: prusti-fuzz [--seed <n>] [--iterations <n>] [--output <dir>] [-- <prusti-rustc arguments>]";
+
+/// The values of the parameters with which the generated functions are
+/// called at runtime.
+const INPUTS: &[&str] = &[
+    "i32::MIN", "-1000", "-100", "-7", "-2", "-1", "0", "1", "2", "3", "100", "1000", "i32::MAX",
+];
+
+const COMPARISONS: &[&str] = &["<", "<=", "==", "!=", ">=", ">"];
+/// The arithmetic operators, with `+` and `-` more likely than the others.
+const OPERATORS: &[&str] = &["+", "+", "-", "-", "*", "/", "%"];
+
+struct Options {
+    seed: u64,
+    iterations: u64,
+    output: PathBuf,
+    prusti_args: Vec<String>,
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1).collect()) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+    if let Err(message) = fuzz(options) {
+        eprintln!("error: {}", message);
+        std::process::exit(1);
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut args = args.into_iter();
+    let mut options = Options {
+        seed: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs()),
+        iterations: 100,
+        output: PathBuf::from("fuzz-findings"),
+        prusti_args: vec![],
+    };
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value of {}", name));
+        match arg.as_str() {
+            "--seed" => {
+                options.seed = value("--seed")?
+                    .parse()
+                    .map_err(|_| "the seed must be a number")?
+            }
+            "--iterations" => {
+                options.iterations = value("--iterations")?
+                    .parse()
+                    .map_err(|_| "the number of iterations must be a number")?
+            }
+            "--output" => options.output = PathBuf::from(value("--output")?),
+            "--" => options.prusti_args.extend(args.by_ref()),
+            _ => return Err(format!("unexpected argument {:?}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+/// The result of running Prusti and the verified program on a generated
+/// program.
+enum Outcome {
+    Verified,
+    /// Prusti reported verification errors or unsupported features.
+    Rejected,
+    /// Prusti crashed or reported an internal error.
+    Crashed(String),
+    /// Prusti verified the program, but its execution failed.
+    Unsound(String),
+}
+
+fn fuzz(options: Options) -> Result<(), String> {
+    let work_dir = env::temp_dir().join(format!("prusti-fuzz-{}", std::process::id()));
+    fs::create_dir_all(&work_dir)
+        .map_err(|err| format!("failed to create {}: {}", work_dir.display(), err))?;
+    let prusti_rustc = find_prusti_rustc();
+
+    let (mut verified, mut rejected, mut findings) = (0, 0, 0);
+    for seed in options.seed..options.seed + options.iterations {
+        let program = generate_program(&mut Rng::new(seed));
+        let (kind, message) = match run(&prusti_rustc, &options.prusti_args, &work_dir, &program) {
+            Outcome::Verified => {
+                verified += 1;
+                continue;
+            }
+            Outcome::Rejected => {
+                rejected += 1;
+                continue;
+            }
+            Outcome::Crashed(message) => ("crash", message),
+            Outcome::Unsound(message) => ("unsound", message),
+        };
+        findings += 1;
+        fs::create_dir_all(&options.output)
+            .map_err(|err| format!("failed to create {}: {}", options.output.display(), err))?;
+        let path = options.output.join(format!("{}-{}.rs", kind, seed));
+        let header: String = message
+            .lines()
+            .map(|line| format!("// {}\n", line))
+            .collect();
+        fs::write(&path, header + &program)
+            .map_err(|err| format!("failed to write {}: {}", path.display(), err))?;
+        eprintln!("Found a {} with seed {}: {}", kind, seed, path.display());
+    }
+    let _ = fs::remove_dir_all(&work_dir);
+    eprintln!(
+        "{} programs: {} verified, {} rejected, {} findings",
+        options.iterations, verified, rejected, findings
+    );
+    Ok(())
+}
+
+fn run(prusti_rustc: &Path, prusti_args: &[String], work_dir: &Path, program: &str) -> Outcome {
+    let source = work_dir.join("fuzz.rs");
+    let binary = work_dir.join("fuzz");
+    fs::write(&source, program).expect("failed to write the generated program");
+    let _ = fs::remove_file(&binary);
+    let output = Command::new(prusti_rustc)
+        .args(prusti_args)
+        .arg("--edition=2018")
+        .arg(&source)
+        .arg("-o")
+        .arg(&binary)
+        .env("PRUSTI_FULL_COMPILATION", "true")
+        .current_dir(work_dir)
+        .output()
+        .unwrap_or_else(|_| panic!("failed to execute prusti-rustc ({:?})", prusti_rustc));
+    let prusti_output = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    if prusti_output.contains("panicked at")
+        || prusti_output.contains("internal compiler error")
+        || prusti_output.contains("[Prusti: internal error]")
+    {
+        return Outcome::Crashed(prusti_output);
+    }
+    if !output.status.success() {
+        return Outcome::Rejected;
+    }
+    let execution = Command::new(&binary)
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .unwrap_or_else(|_| panic!("failed to execute the verified program ({:?})", binary));
+    if execution.status.success() {
+        Outcome::Verified
+    } else {
+        Outcome::Unsound(format!(
+            "the verified program failed at runtime:\n{}",
+            String::from_utf8_lossy(&execution.stderr)
+        ))
+    }
+}
+
+/// A xorshift pseudo-random number generator, so that each program can be
+/// generated again from its seed.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..bound`.
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn range(&mut self, low: i32, high: i32) -> i32 {
+        low + self.below((high - low + 1) as usize) as i32
+    }
+
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    fn choose<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+}
+
+/// The signature of a generated function.
+struct Signature {
+    name: String,
+    params: Vec<String>,
+    /// The precondition, which is also checked at runtime before the call.
+    precondition: Option<String>,
+    /// The postcondition, which is also asserted at runtime after the call.
+    postcondition: Option<String>,
+}
+
+/// Generate a program with a few functions, and a trusted `main` that calls
+/// each of them with all the combinations of `INPUTS` that satisfy its
+/// precondition.
+fn generate_program(rng: &mut Rng) -> String {
+    let mut program = String::from("#![allow(unused)]\nuse prusti_contracts::*;\n");
+    let mut signatures: Vec<Signature> = vec![];
+    for index in 0..rng.range(1, 3) {
+        let (signature, text) =
+            FunctionGenerator::new(rng, &signatures).generate(format!("f{}", index));
+        program.push('\n');
+        program.push_str(&text);
+        signatures.push(signature);
+    }
+
+    program.push_str("\n#[trusted]\nfn main() {\n");
+    program.push_str(&format!("    let inputs = [{}];\n", INPUTS.join(", ")));
+    for signature in &signatures {
+        let mut indentation = String::from("    ");
+        for param in &signature.params {
+            program.push_str(&format!(
+                "{}for &{} in inputs.iter() {{\n",
+                indentation, param
+            ));
+            indentation.push_str("    ");
+        }
+        program.push_str(&format!(
+            "{}if {} {{\n",
+            indentation,
+            signature.precondition.as_deref().unwrap_or("true")
+        ));
+        program.push_str(&format!(
+            "{}    let result = {}({});\n",
+            indentation,
+            signature.name,
+            signature.params.join(", ")
+        ));
+        if let Some(postcondition) = &signature.postcondition {
+            program.push_str(&format!("{}    assert!({});\n", indentation, postcondition));
+        }
+        program.push_str(&format!("{}}}\n", indentation));
+        for _ in &signature.params {
+            indentation.truncate(indentation.len() - 4);
+            program.push_str(&format!("{}}}\n", indentation));
+        }
+    }
+    program.push_str("}\n");
+    program
+}
+
+struct FunctionGenerator<'a> {
+    rng: &'a mut Rng,
+    /// The functions that can be called.
+    callees: &'a [Signature],
+    params: Vec<String>,
+    /// The local variables in scope.
+    locals: Vec<String>,
+    next_local: usize,
+    lines: Vec<String>,
+}
+
+impl<'a> FunctionGenerator<'a> {
+    fn new(rng: &'a mut Rng, callees: &'a [Signature]) -> Self {
+        FunctionGenerator {
+            rng,
+            callees,
+            params: vec![],
+            locals: vec![],
+            next_local: 0,
+            lines: vec![],
+        }
+    }
+
+    fn generate(mut self, name: String) -> (Signature, String) {
+        self.params = (0..self.rng.range(1, 2))
+            .map(|index| format!("a{}", index))
+            .collect();
+        let mut bounds = vec![];
+        for param in &self.params {
+            if self.rng.chance(70) {
+                let low = self.rng.range(-100, 10);
+                let high = low + self.rng.range(0, 100);
+                bounds.push(format!("{} >= {} && {} <= {}", param, low, param, high));
+            }
+        }
+        let precondition = (!bounds.is_empty()).then(|| bounds.join(" && "));
+        let postcondition = self.rng.chance(50).then(|| {
+            let bound = self.atom();
+            format!("result {} {}", self.rng.choose(COMPARISONS), bound)
+        });
+
+        let statements = self.rng.range(1, 4) as usize;
+        self.statements(statements, 2, 1);
+        let result = self.expression(2);
+
+        let mut text = String::new();
+        if let Some(precondition) = &precondition {
+            text.push_str(&format!("#[requires({})]\n", precondition));
+        }
+        if let Some(postcondition) = &postcondition {
+            text.push_str(&format!("#[ensures({})]\n", postcondition));
+        }
+        let params: Vec<_> = self
+            .params
+            .iter()
+            .map(|param| format!("{}: i32", param))
+            .collect();
+        text.push_str(&format!("fn {}({}) -> i32 {{\n", name, params.join(", ")));
+        for line in &self.lines {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text.push_str(&format!("    {}\n}}\n", result));
+        let signature = Signature {
+            name,
+            params: self.params,
+            precondition,
+            postcondition,
+        };
+        (signature, text)
+    }
+
+    fn emit(&mut self, indentation: usize, line: String) {
+        self.lines
+            .push(format!("{}{}", "    ".repeat(indentation), line));
+    }
+
+    fn statements(&mut self, count: usize, depth: usize, indentation: usize) {
+        for _ in 0..count {
+            match self.rng.below(10) {
+                0..=1 if !self.locals.is_empty() => {
+                    let local = self.locals[self.rng.below(self.locals.len())].clone();
+                    let value = self.expression(2);
+                    self.emit(indentation, format!("{} = {};", local, value));
+                }
+                2..=3 if depth > 0 => {
+                    let condition = self.condition(1);
+                    self.emit(indentation, format!("if {} {{", condition));
+                    self.block(depth - 1, indentation + 1);
+                    self.emit(indentation, "} else {".to_string());
+                    self.block(depth - 1, indentation + 1);
+                    self.emit(indentation, "}".to_string());
+                }
+                4 => {
+                    let condition = self.condition(1);
+                    self.emit(indentation, format!("assert!({});", condition));
+                }
+                _ => {
+                    let value = self.expression(2);
+                    let local = format!("x{}", self.next_local);
+                    self.next_local += 1;
+                    self.emit(indentation, format!("let mut {} = {};", local, value));
+                    self.locals.push(local);
+                }
+            }
+        }
+    }
+
+    /// Generate a block whose local variables go out of scope at its end.
+    fn block(&mut self, depth: usize, indentation: usize) {
+        let locals = self.locals.len();
+        let count = self.rng.range(1, 2) as usize;
+        self.statements(count, depth, indentation);
+        self.locals.truncate(locals);
+    }
+
+    fn atom(&mut self) -> String {
+        let variables = self.params.len() + self.locals.len();
+        if self.rng.chance(60) {
+            let index = self.rng.below(variables);
+            self.params
+                .iter()
+                .chain(&self.locals)
+                .nth(index)
+                .unwrap()
+                .clone()
+        } else {
+            self.rng.range(-10, 10).to_string()
+        }
+    }
+
+    fn expression(&mut self, depth: usize) -> String {
+        if depth == 0 || self.rng.chance(30) {
+            return self.atom();
+        }
+        match self.rng.below(10) {
+            0 => {
+                let condition = self.condition(depth - 1);
+                let then_value = self.expression(depth - 1);
+                let else_value = self.expression(depth - 1);
+                format!(
+                    "(if {} {{ {} }} else {{ {} }})",
+                    condition, then_value, else_value
+                )
+            }
+            1 if !self.callees.is_empty() => {
+                let callee = self.rng.below(self.callees.len());
+                let args: Vec<_> = (0..self.callees[callee].params.len())
+                    .map(|_| self.atom())
+                    .collect();
+                format!("{}({})", self.callees[callee].name, args.join(", "))
+            }
+            _ => {
+                let left = self.expression(depth - 1);
+                let right = self.expression(depth - 1);
+                format!("({} {} {})", left, self.rng.choose(OPERATORS), right)
+            }
+        }
+    }
+
+    fn condition(&mut self, depth: usize) -> String {
+        match self.rng.below(4) {
+            0 if depth > 0 => {
+                let left = self.condition(depth - 1);
+                let right = self.condition(depth - 1);
+                format!("({} && {})", left, right)
+            }
+            1 if depth > 0 => format!("!({})", self.condition(depth - 1)),
+            _ => {
+                let left = self.expression(depth);
+                let right = self.expression(depth);
+                format!("{} {} {}", left, self.rng.choose(COMPARISONS), right)
+            }
+        }
+    }
+}
@@ -0,0 +1,340 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Reduces a Rust file on which Prusti fails or crashes to a smaller file on
+//! which Prusti fails in the same way, to be attached to bug reports.
+//!
+//! The failure is identified by the first panic message or error in the
+//! output of `prusti-rustc`, or by the text given with `--match`. The file is
+//! reduced by repeatedly removing a line together with the following lines up
+//! to the matching closing delimiter, and by removing the conjuncts of the
+//! specifications, keeping each change after which Prusti still fails in the
+//! same way. Delimiters in multi-line string literals and block comments are
+//! not recognized.
+
+use prusti_launch::find_prusti_rustc;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+const USAGE: &str =
+    "Usage: prusti-minimize [--match <text>] [--output <file>] <file.rs> [-- <prusti-rustc arguments>]";
+
+/// The specifications whose argument is split into conjuncts.
+const SPEC_MACROS: &[&str] = &[
+    "requires(",
+    "ensures(",
+    "invariant(",
+    "body_invariant!(",
+    "prusti_assert!(",
+    "prusti_assume!(",
+];
+
+struct Options {
+    input: PathBuf,
+    output: Option<PathBuf>,
+    pattern: Option<String>,
+    prusti_args: Vec<String>,
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1).collect()) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+    if let Err(message) = minimize(options) {
+        eprintln!("error: {}", message);
+        std::process::exit(1);
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut args = args.into_iter();
+    let mut input = None;
+    let mut output = None;
+    let mut pattern = None;
+    let mut prusti_args = vec![];
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--match" => pattern = Some(args.next().ok_or("missing value of --match")?),
+            "--output" => {
+                output = Some(PathBuf::from(
+                    args.next().ok_or("missing value of --output")?,
+                ))
+            }
+            "--" => {
+                prusti_args.extend(args.by_ref());
+            }
+            _ if input.is_none() && !arg.starts_with('-') => input = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument {:?}", arg)),
+        }
+    }
+    Ok(Options {
+        input: input.ok_or("missing input file")?,
+        output,
+        pattern,
+        prusti_args,
+    })
+}
+
+fn minimize(options: Options) -> Result<(), String> {
+    let source = fs::read_to_string(&options.input)
+        .map_err(|err| format!("failed to read {}: {}", options.input.display(), err))?;
+    let file_name = options
+        .input
+        .file_name()
+        .ok_or_else(|| format!("{} is not a file", options.input.display()))?;
+    let work_dir = env::temp_dir().join(format!("prusti-minimize-{}", std::process::id()));
+    fs::create_dir_all(&work_dir)
+        .map_err(|err| format!("failed to create {}: {}", work_dir.display(), err))?;
+
+    let mut reducer = Reducer {
+        prusti_rustc: find_prusti_rustc(),
+        prusti_args: options.prusti_args,
+        work_file: work_dir.join(file_name),
+        signature: String::new(),
+        runs: 0,
+    };
+    let output = reducer
+        .run(&source)
+        .ok_or("Prusti does not fail on the input file")?;
+    reducer.signature = match options.pattern {
+        Some(pattern) if output.contains(&pattern) => pattern,
+        Some(pattern) => {
+            return Err(format!(
+                "the output of Prusti does not contain {:?}:\n{}",
+                pattern, output
+            ))
+        }
+        None => failure_signature(&output)
+            .ok_or_else(|| format!("found no error in the output of Prusti:\n{}", output))?,
+    };
+    eprintln!("Preserving the failure: {}", reducer.signature);
+
+    let mut lines: Vec<String> = source
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(String::from)
+        .collect();
+    loop {
+        let removed_lines = reducer.remove_lines(&mut lines);
+        let removed_conjuncts = reducer.remove_conjuncts(&mut lines);
+        if !removed_lines && !removed_conjuncts {
+            break;
+        }
+    }
+
+    let output_path = options
+        .output
+        .unwrap_or_else(|| options.input.with_extension("min.rs"));
+    fs::write(&output_path, lines.join("\n") + "\n")
+        .map_err(|err| format!("failed to write {}: {}", output_path.display(), err))?;
+    let _ = fs::remove_dir_all(&work_dir);
+    eprintln!(
+        "Wrote {} lines to {} after {} runs of Prusti",
+        lines.len(),
+        output_path.display(),
+        reducer.runs
+    );
+    Ok(())
+}
+
+/// The first panic message of the output, or otherwise its first error.
+fn failure_signature(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find(|line| line.contains("panicked at"))
+        .or_else(|| {
+            output
+                .lines()
+                .find(|line| line.starts_with("error") && !line.starts_with("error: aborting"))
+        })
+        .map(|line| line.trim().to_string())
+}
+
+struct Reducer {
+    prusti_rustc: PathBuf,
+    prusti_args: Vec<String>,
+    /// The file on which Prusti is run, named like the input file.
+    work_file: PathBuf,
+    /// The text that identifies the failure to preserve.
+    signature: String,
+    runs: usize,
+}
+
+impl Reducer {
+    /// Run Prusti on `source`, returning its output if it failed.
+    fn run(&mut self, source: &str) -> Option<String> {
+        self.runs += 1;
+        fs::write(&self.work_file, source).expect("failed to write the reduced file");
+        let output = Command::new(&self.prusti_rustc)
+            .args(&self.prusti_args)
+            .arg(&self.work_file)
+            .current_dir(self.work_file.parent().unwrap_or_else(|| Path::new(".")))
+            .output()
+            .unwrap_or_else(|_| panic!("failed to execute prusti-rustc ({:?})", self.prusti_rustc));
+        if output.status.success() {
+            None
+        } else {
+            Some(format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        }
+    }
+
+    fn fails(&mut self, lines: &[String]) -> bool {
+        self.run(&lines.join("\n"))
+            .map_or(false, |output| output.contains(&self.signature))
+    }
+
+    /// Remove the lines that are not needed for the failure, each together
+    /// with the lines up to its matching closing delimiter.
+    fn remove_lines(&mut self, lines: &mut Vec<String>) -> bool {
+        let mut changed = false;
+        let mut start = 0;
+        while start < lines.len() {
+            if let Some(end) = balanced_end(lines, start) {
+                let mut candidate = lines.clone();
+                candidate.drain(start..=end);
+                if self.fails(&candidate) {
+                    *lines = candidate;
+                    changed = true;
+                    eprintln!("Reduced to {} lines", lines.len());
+                    continue;
+                }
+            }
+            start += 1;
+        }
+        changed
+    }
+
+    /// Remove the conjuncts of single-line specifications that are not needed
+    /// for the failure.
+    fn remove_conjuncts(&mut self, lines: &mut Vec<String>) -> bool {
+        let mut changed = false;
+        for index in 0..lines.len() {
+            let mut conjunct = 0;
+            while let Some((prefix, conjuncts, suffix)) = split_conjuncts(&lines[index]) {
+                if conjuncts.len() < 2 || conjunct >= conjuncts.len() {
+                    break;
+                }
+                let mut remaining = conjuncts;
+                remaining.remove(conjunct);
+                let mut candidate = lines.clone();
+                candidate[index] = format!("{}{}{}", prefix, remaining.join(" && "), suffix);
+                if self.fails(&candidate) {
+                    *lines = candidate;
+                    changed = true;
+                    eprintln!("Removed a conjunct on line {}", index + 1);
+                } else {
+                    conjunct += 1;
+                }
+            }
+        }
+        changed
+    }
+}
+
+/// The bytes of `line` that are not part of a string literal, a character
+/// literal or a comment, with their positions.
+fn code_bytes(line: &str) -> Vec<(usize, u8)> {
+    let bytes = line.as_bytes();
+    let mut result = vec![];
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'/' if bytes.get(index + 1) == Some(&b'/') => break,
+            b'"' => {
+                index += 1;
+                while index < bytes.len() && bytes[index] != b'"' {
+                    if bytes[index] == b'\\' {
+                        index += 1;
+                    }
+                    index += 1;
+                }
+            }
+            b'\'' if bytes.get(index + 2) == Some(&b'\'') => index += 2,
+            b'\'' if bytes.get(index + 1) == Some(&b'\\') => {
+                index += 3;
+                while index < bytes.len() && bytes[index] != b'\'' {
+                    index += 1;
+                }
+            }
+            byte => result.push((index, byte)),
+        }
+        index += 1;
+    }
+    result
+}
+
+fn delimiter_depth_change(byte: u8) -> i32 {
+    match byte {
+        b'(' | b'[' | b'{' => 1,
+        b')' | b']' | b'}' => -1,
+        _ => 0,
+    }
+}
+
+/// The index of the last line of the smallest range of lines starting at
+/// `start` in which the delimiters are balanced, or `None` if the line at
+/// `start` closes a delimiter opened before it.
+fn balanced_end(lines: &[String], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (index, line) in lines.iter().enumerate().skip(start) {
+        for (_, byte) in code_bytes(line) {
+            depth += delimiter_depth_change(byte);
+            if depth < 0 {
+                return None;
+            }
+        }
+        if depth == 0 {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// Split the argument of the first specification on `line` at its top-level
+/// `&&`, returning the text before the argument, the conjuncts and the text
+/// after the argument.
+fn split_conjuncts(line: &str) -> Option<(String, Vec<String>, String)> {
+    let open = SPEC_MACROS
+        .iter()
+        .filter_map(|spec_macro| line.find(spec_macro).map(|pos| pos + spec_macro.len()))
+        .min()?;
+    let mut depth = 0;
+    let mut conjunct_start = open;
+    let mut conjuncts = vec![];
+    let mut previous = None;
+    for (index, byte) in code_bytes(line) {
+        if index < open {
+            continue;
+        }
+        depth += delimiter_depth_change(byte);
+        if depth < 0 {
+            conjuncts.push(line[conjunct_start..index].trim().to_string());
+            return Some((
+                line[..open].to_string(),
+                conjuncts,
+                line[index..].to_string(),
+            ));
+        }
+        if depth == 0 && byte == b'&' && previous == Some((index - 1, b'&')) {
+            conjuncts.push(line[conjunct_start..index - 1].trim().to_string());
+            conjunct_start = index + 1;
+        }
+        previous = Some((index, byte));
+    }
+    None
+}
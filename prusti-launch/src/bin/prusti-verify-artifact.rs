@@ -0,0 +1,294 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Checks a verification artifact written by Prusti with
+//! `VERIFICATION_ARTIFACT`, without running the verification again: the
+//! artifact must be signed by the trusted public key, the source files must
+//! still have the recorded hashes, no function may have failed, and the
+//! configuration must not weaken the verification.
+//!
+//! `prusti-verify-artifact generate-key <file>` generates an Ed25519 key pair
+//! to use as `ARTIFACT_SIGNING_KEY`, writing the private key to the file and
+//! printing the public key.
+
+use ring::{
+    digest, rand,
+    signature::{self, Ed25519KeyPair, KeyPair},
+};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+const USAGE: &str = "Usage: prusti-verify-artifact --public-key <hex> [--root <dir>] \
+                     [--allow-unsupported] [--allow-bounded] [--allow-weakening <flag>]... \
+                     <artifact>\n       \
+                     prusti-verify-artifact generate-key <private-key-file>";
+
+/// The version of the format of the artifacts that can be checked.
+const FORMAT_VERSION: u32 = 1;
+
+/// The configuration flags that weaken what a successful verification
+/// guarantees, with a description of the values that do so.
+const WEAKENING_FLAGS: &[(&str, &str)] = &[
+    ("assumed_verified_modules", "not empty"),
+    ("functional_correctness", "false"),
+    ("check_panics", "false"),
+    ("check_overflows", "false"),
+    ("skip_unsupported_features", "true"),
+];
+
+struct Options {
+    artifact: PathBuf,
+    /// The hex-encoded Ed25519 public key that must have signed the artifact.
+    public_key: String,
+    /// The directory against which the paths of the source files are
+    /// resolved.
+    root: PathBuf,
+    allow_unsupported: bool,
+    /// Whether the functions verified only up to a loop bound are accepted.
+    allow_bounded: bool,
+    /// The weakening configuration flags that are accepted.
+    allowed_weakenings: Vec<String>,
+}
+
+/// A verification artifact, as written by Prusti.
+#[derive(Deserialize)]
+struct SignedArtifact {
+    artifact: serde_json::Value,
+    public_key: String,
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct Artifact {
+    format_version: u32,
+    #[serde(rename = "crate")]
+    krate: String,
+    prusti_version: String,
+    configuration: BTreeMap<String, String>,
+    sources: Vec<SourceFile>,
+    functions: Vec<FunctionResult>,
+}
+
+#[derive(Deserialize)]
+struct SourceFile {
+    path: String,
+    sha256: String,
+}
+
+#[derive(Deserialize)]
+struct FunctionResult {
+    function: String,
+    status: String,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = if args.first().map(String::as_str) == Some("generate-key") {
+        match &args[1..] {
+            [path] => generate_key(Path::new(path)),
+            _ => Err("expected the path of the private key".to_string()),
+        }
+        .map(|()| 0)
+    } else {
+        match parse_args(args) {
+            Ok(options) => check(&options),
+            Err(message) => {
+                eprintln!("error: {}", message);
+                eprintln!("{}", USAGE);
+                std::process::exit(2);
+            }
+        }
+    };
+    match result {
+        Ok(0) => {}
+        Ok(problems) => {
+            eprintln!(
+                "error: the verification artifact is invalid ({} problems)",
+                problems
+            );
+            std::process::exit(1);
+        }
+        Err(message) => {
+            eprintln!("error: {}", message);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut args = args.into_iter();
+    let mut artifact = None;
+    let mut public_key = None;
+    let mut root = PathBuf::from(".");
+    let mut allow_unsupported = false;
+    let mut allow_bounded = false;
+    let mut allowed_weakenings = vec![];
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value of {}", name));
+        match arg.as_str() {
+            "--public-key" => public_key = Some(value("--public-key")?),
+            "--root" => root = PathBuf::from(value("--root")?),
+            "--allow-unsupported" => allow_unsupported = true,
+            "--allow-bounded" => allow_bounded = true,
+            "--allow-weakening" => {
+                let flag = value("--allow-weakening")?.to_lowercase();
+                if !WEAKENING_FLAGS.iter().any(|(name, _)| *name == flag) {
+                    return Err(format!("{:?} is not a weakening configuration flag", flag));
+                }
+                allowed_weakenings.push(flag);
+            }
+            _ if arg.starts_with("--") => return Err(format!("unexpected argument {:?}", arg)),
+            _ if artifact.is_none() => artifact = Some(PathBuf::from(arg)),
+            _ => return Err("expected a single artifact".to_string()),
+        }
+    }
+    Ok(Options {
+        artifact: artifact.ok_or("missing the path of the artifact")?,
+        // Without a trusted key, anyone could have signed the artifact.
+        public_key: public_key.ok_or("missing the trusted public key")?,
+        root,
+        allow_unsupported,
+        allow_bounded,
+        allowed_weakenings,
+    })
+}
+
+fn generate_key(path: &Path) -> Result<(), String> {
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rand::SystemRandom::new())
+        .map_err(|_| "failed to generate the key".to_string())?;
+    let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+    fs::write(path, pkcs8.as_ref())
+        .map_err(|err| format!("failed to write {}: {}", path.display(), err))?;
+    eprintln!(
+        "Wrote the private key to {}; the public key is:",
+        path.display()
+    );
+    println!("{}", hex::encode(key_pair.public_key()));
+    Ok(())
+}
+
+/// Check the artifact, returning the number of problems found.
+fn check(options: &Options) -> Result<usize, String> {
+    let path = &options.artifact;
+    let json = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+    let signed_artifact: SignedArtifact = serde_json::from_str(&json)
+        .map_err(|err| format!("failed to parse {}: {}", path.display(), err))?;
+
+    if !signed_artifact
+        .public_key
+        .eq_ignore_ascii_case(options.public_key.trim())
+    {
+        return Err(format!(
+            "the artifact is signed by the key {}, which is not the trusted key",
+            signed_artifact.public_key
+        ));
+    }
+    let public_key =
+        hex::decode(&signed_artifact.public_key).map_err(|_| "invalid public key".to_string())?;
+    let signature =
+        hex::decode(&signed_artifact.signature).map_err(|_| "invalid signature".to_string())?;
+    // The signature covers the compact JSON encoding of the artifact with its
+    // object keys sorted, which is how `serde_json` encodes a `Value`.
+    let message = serde_json::to_vec(&signed_artifact.artifact).unwrap();
+    signature::UnparsedPublicKey::new(&signature::ED25519, public_key)
+        .verify(&message, &signature)
+        .map_err(|_| "the signature of the artifact is invalid".to_string())?;
+
+    let artifact: Artifact = serde_json::from_value(signed_artifact.artifact)
+        .map_err(|err| format!("failed to parse the artifact: {}", err))?;
+    if artifact.format_version != FORMAT_VERSION {
+        return Err(format!(
+            "unsupported version {} of the artifact format",
+            artifact.format_version
+        ));
+    }
+
+    let mut problems = 0;
+    for source in &artifact.sources {
+        let source_path = options.root.join(&source.path);
+        match fs::read(&source_path) {
+            Ok(content) => {
+                let sha256 = hex::encode(digest::digest(&digest::SHA256, &content));
+                if sha256 != source.sha256 {
+                    println!("Modified since the verification: {}", source.path);
+                    problems += 1;
+                }
+            }
+            Err(err) => {
+                println!("Failed to read {}: {}", source_path.display(), err);
+                problems += 1;
+            }
+        }
+    }
+    for (flag, value) in weakening_flags(&artifact.configuration) {
+        if !options.allowed_weakenings.contains(&flag) {
+            println!(
+                "Verified with a weakening configuration: {} = {}",
+                flag.to_uppercase(),
+                value
+            );
+            problems += 1;
+        }
+    }
+    for function in &artifact.functions {
+        let accepted = match function.status.as_str() {
+            "verified" | "no_specs" | "trusted" => true,
+            "unsupported" => options.allow_unsupported,
+            "bounded" => options.allow_bounded,
+            // "failed", or a status of a newer version of Prusti, whose
+            // guarantees are not known.
+            _ => false,
+        };
+        if !accepted {
+            println!("Not verified ({}): {}", function.status, function.function);
+            problems += 1;
+        }
+    }
+
+    if problems == 0 {
+        println!(
+            "The verification artifact of crate {} is valid: {} functions of {} source files \
+            verified with Prusti {}",
+            artifact.krate,
+            artifact.functions.len(),
+            artifact.sources.len(),
+            artifact.prusti_version
+        );
+    }
+    Ok(problems)
+}
+
+/// The weakening configuration flags with which the artifact was verified,
+/// with their values. Flags that are missing from the configuration are
+/// weakening, since their value is not known.
+fn weakening_flags(configuration: &BTreeMap<String, String>) -> Vec<(String, String)> {
+    let configuration: BTreeMap<String, &str> = configuration
+        .iter()
+        .map(|(flag, value)| (flag.to_lowercase(), value.trim()))
+        .collect();
+    let mut flags = vec![];
+    for (flag, weakening_value) in WEAKENING_FLAGS {
+        let is_weakening = match configuration.get(*flag) {
+            Some(value) if *weakening_value == "not empty" => !value
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .trim()
+                .is_empty(),
+            Some(value) => value.eq_ignore_ascii_case(weakening_value),
+            None => true,
+        };
+        if is_weakening {
+            let value = configuration.get(*flag).unwrap_or(&"<missing>");
+            flags.push((flag.to_string(), value.to_string()));
+        }
+    }
+    flags
+}
@@ -143,6 +143,17 @@ fn get_sysroot_from_rustup() -> Option<PathBuf> {
         .map(|s| PathBuf::from(s.trim().to_owned()))
 }
 
+/// Find the `prusti-rustc` executable next to the current executable
+pub fn find_prusti_rustc() -> PathBuf {
+    let mut prusti_rustc = env::current_exe()
+        .expect("current executable path invalid")
+        .with_file_name("prusti-rustc");
+    if cfg!(windows) {
+        prusti_rustc.set_extension("exe");
+    }
+    prusti_rustc
+}
+
 /// Find Viper home
 pub fn find_viper_home(base_dir: &Path) -> Option<PathBuf> {
     let candidates = vec![
@@ -5,9 +5,14 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use glob::glob;
+use ring::{
+    digest, rand,
+    signature::{Ed25519KeyPair, KeyPair},
+};
 use std::{
+    fs,
     io::{BufRead, BufReader},
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::{Child, Command, Stdio},
 };
 
@@ -211,3 +216,164 @@ fn test_prusti_rustc_with_server() {
         cmd
     });
 }
+
+/// Write a verification artifact of `source` signed with `key_pair`.
+fn write_artifact(
+    path: &Path,
+    key_pair: &Ed25519KeyPair,
+    source: &Path,
+    configuration: serde_json::Value,
+    status: &str,
+) {
+    let content = fs::read(source).unwrap();
+    let artifact = serde_json::json!({
+        "format_version": 1,
+        "crate": "test",
+        "created_at": 0,
+        "prusti_version": "test",
+        "backend": { "viper_backend": "Silicon", "z3": null, "viper_jars": {} },
+        "configuration": configuration,
+        "sources": [{
+            "path": source.file_name().unwrap().to_str().unwrap(),
+            "sha256": hex::encode(digest::digest(&digest::SHA256, &content)),
+        }],
+        "functions": [{ "function": "test::f", "status": status }],
+    });
+    let signature = key_pair.sign(&serde_json::to_vec(&artifact).unwrap());
+    let signed_artifact = serde_json::json!({
+        "artifact": artifact,
+        "public_key": hex::encode(key_pair.public_key()),
+        "signature": hex::encode(signature),
+    });
+    fs::write(path, signed_artifact.to_string()).unwrap();
+}
+
+#[test]
+fn test_prusti_verify_artifact() {
+    let prusti_verify_artifact = find_executable_path("prusti-verify-artifact");
+    let directory = std::env::temp_dir().join("prusti-verify-artifact-test");
+    fs::create_dir_all(&directory).unwrap();
+    let source = directory.join("main.rs");
+    fs::write(&source, "fn f() {}").unwrap();
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rand::SystemRandom::new()).unwrap();
+    let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+    let public_key = hex::encode(key_pair.public_key());
+    let default_configuration = serde_json::json!({
+        "assumed_verified_modules": "[]",
+        "check_overflows": "true",
+        "check_panics": "true",
+        "functional_correctness": "true",
+        "skip_unsupported_features": "false",
+    });
+
+    let check = |configuration: &serde_json::Value, status: &str, options: &[&str]| {
+        let artifact = directory.join("artifact.json");
+        write_artifact(&artifact, &key_pair, &source, configuration.clone(), status);
+        Command::new(&prusti_verify_artifact)
+            .arg("--public-key")
+            .arg(&public_key)
+            .arg("--root")
+            .arg(&directory)
+            .args(options)
+            .arg(&artifact)
+            .output()
+            .unwrap()
+            .status
+            .success()
+    };
+
+    assert!(check(&default_configuration, "verified", &[]));
+    assert!(!check(&default_configuration, "failed", &[]));
+    assert!(!check(&default_configuration, "bounded", &[]));
+    assert!(check(&default_configuration, "bounded", &["--allow-bounded"]));
+    for (flag, value) in [
+        ("assumed_verified_modules", "[\"m\"]"),
+        ("check_overflows", "false"),
+        ("check_panics", "false"),
+        ("functional_correctness", "false"),
+        ("skip_unsupported_features", "true"),
+    ] {
+        let mut configuration = default_configuration.clone();
+        configuration[flag] = serde_json::Value::from(value);
+        assert!(!check(&configuration, "verified", &[]), "{}", flag);
+        assert!(
+            check(&configuration, "verified", &["--allow-weakening", flag]),
+            "{}",
+            flag
+        );
+        configuration.as_object_mut().unwrap().remove(flag);
+        assert!(!check(&configuration, "verified", &[]), "{}", flag);
+    }
+}
+
+#[test]
+fn test_prusti_minimize() {
+    let prusti_minimize = find_executable_path("prusti-minimize");
+    let directory = std::env::temp_dir().join("prusti-minimize-test");
+    fs::create_dir_all(&directory).unwrap();
+    let minimize = |source: &str| {
+        let input = directory.join("program.rs");
+        let output = directory.join("program.min.rs");
+        fs::write(&input, source).unwrap();
+        let _ = fs::remove_file(&output);
+        let status = Command::new(&prusti_minimize)
+            .arg(&input)
+            .arg("--")
+            .arg("--edition=2018")
+            .status()
+            .unwrap();
+        (status.success(), fs::read_to_string(&output).ok())
+    };
+
+    let (success, output) = minimize(
+        "use prusti_contracts::*;\n\
+         \n\
+         fn unrelated(x: u32) -> u32 {\n\
+         \x20   x / 2\n\
+         }\n\
+         \n\
+         #[requires(x > 10 && x < 100)]\n\
+         #[ensures(result > x)]\n\
+         fn decrement(x: u32) -> u32 {\n\
+         \x20   x - 1\n\
+         }\n\
+         \n\
+         fn main() {}\n",
+    );
+    assert!(success);
+    let output = output.expect("the reduced program was not written");
+    assert!(output.contains("#[ensures(result > x)]"), "{}", output);
+    assert!(output.contains("fn main()"), "{}", output);
+    assert!(!output.contains("unrelated"), "{}", output);
+    assert!(!output.contains("x < 100"), "{}", output);
+
+    // A program on which Prusti does not fail cannot be reduced.
+    let (success, output) = minimize("fn main() {}\n");
+    assert!(!success);
+    assert!(output.is_none());
+}
+
+#[test]
+fn test_prusti_fuzz() {
+    let prusti_fuzz = find_executable_path("prusti-fuzz");
+    let findings = std::env::temp_dir().join("prusti-fuzz-test");
+    let _ = fs::remove_dir_all(&findings);
+
+    let output = Command::new(&prusti_fuzz)
+        .args(&["--seed", "0", "--iterations", "5", "--output"])
+        .arg(&findings)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains("5 programs: "), "{}", stderr);
+    // Prusti is expected to neither crash nor verify an unsound program.
+    assert!(stderr.contains(" 0 findings"), "{}", stderr);
+    assert!(!findings.exists());
+
+    let output = Command::new(&prusti_fuzz)
+        .args(&["--iterations", "many"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -8,11 +8,13 @@ mod client;
 mod process_verification;
 mod server;
 mod verification_request;
+mod viper_server_client;
 
 pub use client::*;
 pub use process_verification::*;
 pub use server::*;
 pub use verification_request::*;
+pub use viper_server_client::*;
 
 // Futures returned by `Client` need to be executed in a compatible tokio runtime.
 pub use tokio;
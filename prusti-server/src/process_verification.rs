@@ -6,7 +6,16 @@
 
 use crate::{VerificationRequest, ViperBackendConfig};
 use log::info;
-use prusti_common::{config, report::log::report, vir::ToViper, Stopwatch};
+use prusti_common::{
+    config,
+    report::log::report,
+    vir::{
+        program::Program,
+        viper_text::{program_to_viper_text, TextPosition, ViperText},
+        ToViper,
+    },
+    Stopwatch,
+};
 use std::{fs::create_dir_all, path::PathBuf};
 use viper::{Cache, VerificationBackend, VerificationContext};
 
@@ -22,15 +31,20 @@ pub fn process_verification_request<'v, 't: 'v>(
 
     let build_or_dump_viper_program = || {
         let mut stopwatch = Stopwatch::start("prusti-server", "construction of JVM objects");
-        let ast_factory = verification_context.new_ast_factory();
-        let viper_program = request.program.to_viper(&ast_factory);
+        let (viper_program, viper_text) = match parse_viper_program(&ast_utils, &request.program) {
+            Some((viper_program, viper_text)) => (viper_program, Some(viper_text)),
+            None => {
+                let ast_factory = verification_context.new_ast_factory();
+                (request.program.to_viper(&ast_factory), None)
+            }
+        };
 
         if config::dump_viper_program() {
             stopwatch.start_next("dumping viper program");
             dump_viper_program(&ast_utils, viper_program, request.program.get_name());
         }
 
-        viper_program
+        (viper_program, viper_text)
     };
 
     // Print the hash and skip verification. Used for testing.
@@ -62,7 +76,7 @@ pub fn process_verification_request<'v, 't: 'v>(
     };
 
     ast_utils.with_local_frame(16, || {
-        let viper_program = build_or_dump_viper_program();
+        let (viper_program, viper_text) = build_or_dump_viper_program();
 
         // Create a new verifier each time.
         // Workaround for https://github.com/viperproject/prusti-dev/issues/744
@@ -70,7 +84,10 @@ pub fn process_verification_request<'v, 't: 'v>(
         let verifier = new_viper_verifier(verification_context, request.backend_config);
 
         stopwatch.start_next("verification");
-        let result = verifier.verify(viper_program);
+        let mut result = verifier.verify(viper_program);
+        if let Some(viper_text) = viper_text {
+            result = map_text_positions(result, &viper_text);
+        }
 
         if config::enable_cache() {
             cache.insert(hash, result.clone());
@@ -80,6 +97,73 @@ pub fn process_verification_request<'v, 't: 'v>(
     })
 }
 
+/// Build the Viper program by parsing its source text in the JVM, if enabled.
+/// Returns `None` if the program has to be built node by node instead.
+fn parse_viper_program<'v>(
+    ast_utils: &viper::AstUtils<'v>,
+    program: &Program,
+) -> Option<(viper::Program<'v>, ViperText)> {
+    if !config::batch_viper_ast_construction() {
+        return None;
+    }
+    let program = match program {
+        Program::Legacy(program) => program,
+        Program::Low(_) => return None,
+    };
+    let viper_text = if config::verify_only_preamble() {
+        let mut program = program.clone();
+        program.methods.clear();
+        program_to_viper_text(&program)
+    } else {
+        program_to_viper_text(program)
+    };
+    let viper_text = match viper_text {
+        Ok(viper_text) => viper_text,
+        Err(error) => {
+            info!(
+                "Building the Viper program '{}' node by node: {}",
+                program.name, error
+            );
+            return None;
+        }
+    };
+    let viper_program =
+        ast_utils.parse_program(&viper_text.text, &format!("{}.vpr", program.name))?;
+    Some((viper_program, viper_text))
+}
+
+/// The positions of the errors in a program that was parsed from source text
+/// are `line:column` positions. Map them back to the VIR position identifiers.
+fn map_text_positions(
+    result: viper::VerificationResult,
+    viper_text: &ViperText,
+) -> viper::VerificationResult {
+    let map_position = |pos_id: Option<String>| {
+        let pos_id = pos_id?;
+        let (line, column) = pos_id.split_once(':')?;
+        let start = TextPosition {
+            line: line.parse().ok()?,
+            column: column.parse().ok()?,
+        };
+        viper_text
+            .find_position_id(start, None)
+            .map(|id| id.to_string())
+    };
+    match result {
+        viper::VerificationResult::Failure(errors) => viper::VerificationResult::Failure(
+            errors
+                .into_iter()
+                .map(|mut error| {
+                    error.pos_id = map_position(error.pos_id);
+                    error.reason_pos_id = map_position(error.reason_pos_id);
+                    error
+                })
+                .collect(),
+        ),
+        result => result,
+    }
+}
+
 fn dump_viper_program(ast_utils: &viper::AstUtils, program: viper::Program, program_name: &str) {
     let namespace = "viper_program";
     let filename = format!("{}.vpr", program_name);
@@ -45,11 +45,15 @@ impl Default for ViperBackendConfig {
                 if config::use_more_complete_exhale() {
                     verifier_args.push("--enableMoreCompleteExhale".to_string());
                 }
-                if config::produce_counterexample() {
+                if config::produce_counterexample() || config::rank_verification_errors() {
                     verifier_args.push("--counterexample".to_string());
                     verifier_args.push("mapped".to_string());
                 }
 
+                if config::nonlinear_arithmetic() {
+                    verifier_args.extend(nonlinear_arithmetic_args());
+                }
+
                 verifier_args.extend(vec![
                     "--assertTimeout".to_string(),
                     config::assert_timeout().to_string(),
@@ -67,3 +71,20 @@ impl Default for ViperBackendConfig {
         }
     }
 }
+
+impl ViperBackendConfig {
+    /// Enable the nonlinear arithmetic reasoning of the SMT solver, if it is not
+    /// already enabled for all programs. Only Silicon supports this.
+    pub fn enable_nonlinear_arithmetic(&mut self) {
+        if self.backend == VerificationBackend::Silicon && !config::nonlinear_arithmetic() {
+            self.verifier_args.extend(nonlinear_arithmetic_args());
+        }
+    }
+}
+
+fn nonlinear_arithmetic_args() -> Vec<String> {
+    vec![
+        "--z3ConfigArgs".to_string(),
+        "smt.arith.nl=true".to_string(),
+    ]
+}
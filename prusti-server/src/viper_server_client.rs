@@ -0,0 +1,191 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A client of an external ViperServer. The programs are sent as Viper source
+//! text, so no JVM is needed on the Prusti side.
+
+use crate::VerificationRequest;
+use log::info;
+use prusti_common::{
+    config,
+    vir::{
+        program::Program,
+        viper_text::{program_to_viper_text, TextPosition, ViperText},
+    },
+};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::fs;
+use url::{ParseError, Url};
+use viper::{JavaException, VerificationBackend, VerificationError, VerificationResult};
+
+pub struct ViperServerClient {
+    client: Client,
+    server_url: Url,
+}
+
+impl ViperServerClient {
+    pub fn new<S: ToString>(server_url: S) -> Result<Self, ParseError> {
+        let mut address = server_url.to_string();
+        if !address.starts_with("http") {
+            address = format!("http://{}", address);
+        }
+        Ok(Self {
+            client: Client::new(),
+            server_url: Url::parse(address.as_str())?,
+        })
+    }
+
+    pub async fn verify(
+        &self,
+        request: VerificationRequest,
+    ) -> reqwest::Result<VerificationResult> {
+        let mut program = match request.program {
+            Program::Legacy(program) => program,
+            Program::Low(program) => {
+                return Ok(VerificationResult::ConsistencyErrors(vec![format!(
+                    "the program {} uses the low VIR, which cannot be emitted as Viper text",
+                    program.name
+                )]));
+            }
+        };
+        if config::verify_only_preamble() {
+            program.methods.clear();
+        }
+        let viper_text = match program_to_viper_text(&program) {
+            Ok(viper_text) => viper_text,
+            Err(error) => {
+                return Ok(VerificationResult::ConsistencyErrors(vec![
+                    error.to_string()
+                ]));
+            }
+        };
+        let dir = config::log_dir().join("viper_server");
+        fs::create_dir_all(&dir).expect("failed to create the directory of the Viper programs");
+        let path = dir.join(format!("{}.vpr", program.name));
+        fs::write(&path, &viper_text.text).expect("failed to write the Viper program");
+        info!("Verifying '{}' with ViperServer", path.display());
+
+        let backend = match request.backend_config.backend {
+            VerificationBackend::Silicon => "silicon",
+            VerificationBackend::Carbon => "carbon",
+        };
+        let mut args = vec![backend.to_string()];
+        args.extend(request.backend_config.verifier_args);
+        args.push(path.to_string_lossy().into_owned());
+        let arg = args
+            .iter()
+            .map(|arg| {
+                if arg.contains(' ') {
+                    format!("\"{}\"", arg)
+                } else {
+                    arg.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let job: Value = self
+            .client
+            .post(self.server_url.join("verify").unwrap())
+            .json(&json!({ "arg": arg }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let job_id = job["id"]
+            .as_i64()
+            .unwrap_or_else(|| panic!("ViperServer did not start a job: {}", job));
+        let messages = self
+            .client
+            .get(self.server_url.join(&format!("verify/{}", job_id)).unwrap())
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        Ok(parse_messages(&messages, &viper_text))
+    }
+}
+
+/// Extract the verification result from the messages streamed by ViperServer,
+/// either as a JSON array or one message per line.
+fn parse_messages(messages: &str, viper_text: &ViperText) -> VerificationResult {
+    let messages: Vec<Value> = if messages.trim_start().starts_with('[') {
+        serde_json::from_str(messages).expect("failed to parse the ViperServer messages")
+    } else {
+        messages
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).expect("failed to parse a ViperServer message"))
+            .collect()
+    };
+    for message in messages {
+        let body = &message["msg_body"];
+        match message["msg_type"].as_str() {
+            Some("ast_construction_result") if body["status"] == "failure" => {
+                let errors = errors(body)
+                    .map(|error| error["text"].as_str().unwrap_or_default().to_string())
+                    .collect();
+                return VerificationResult::ConsistencyErrors(errors);
+            }
+            Some("verification_result") if body["kind"] == "overall" => {
+                return if body["status"] == "success" {
+                    VerificationResult::Success
+                } else {
+                    VerificationResult::Failure(
+                        errors(body)
+                            .map(|error| verification_error(error, viper_text))
+                            .collect(),
+                    )
+                };
+            }
+            Some("exception_report") => {
+                return VerificationResult::JavaException(JavaException::new(
+                    body["message"].as_str().unwrap_or_default().to_string(),
+                    body["stacktrace"].to_string(),
+                ));
+            }
+            _ => {}
+        }
+    }
+    VerificationResult::JavaException(JavaException::new(
+        "ViperServer did not report a verification result".to_string(),
+        String::new(),
+    ))
+}
+
+fn errors(body: &Value) -> impl Iterator<Item = &Value> {
+    body["details"]["result"]["errors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+}
+
+fn verification_error(error: &Value, viper_text: &ViperText) -> VerificationError {
+    let position = &error["position"];
+    let pos_id = parse_text_position(&position["start"])
+        .and_then(|start| viper_text.find_position_id(start, parse_text_position(&position["end"])))
+        .map(|id| id.to_string());
+    VerificationError::new(
+        error["tag"].as_str().unwrap_or_default().to_string(),
+        pos_id,
+        None,
+        error["text"].as_str().unwrap_or_default().to_string(),
+        None,
+        None,
+    )
+}
+
+/// Parse a `line:column` position.
+fn parse_text_position(position: &Value) -> Option<TextPosition> {
+    let (line, column) = position.as_str()?.split_once(':')?;
+    Some(TextPosition {
+        line: line.parse().ok()?,
+        column: column.parse().ok()?,
+    })
+}
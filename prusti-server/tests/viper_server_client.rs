@@ -0,0 +1,173 @@
+use lazy_static::lazy_static;
+use prusti_common::vir::*;
+use prusti_server::{tokio::runtime::Builder, VerificationRequest, ViperServerClient};
+use serde_json::{json, Value};
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::Path,
+    sync::{mpsc, Mutex},
+    thread,
+};
+use viper::VerificationResult;
+use warp::Filter;
+
+lazy_static! {
+    // only start the mock ViperServer once
+    static ref SERVER_ADDRESS: String = spawn_mock_viper_server().to_string();
+    // the arguments of the verification jobs, indexed by job id
+    static ref JOBS: Mutex<Vec<String>> = Mutex::new(vec![]);
+}
+
+#[test]
+fn success() {
+    let (arg, result) = verify_program("successful");
+
+    assert!(arg.starts_with("silicon "), "unexpected arguments: {}", arg);
+    let path = arg.rsplit(' ').next().unwrap();
+    assert!(
+        path.ends_with("successful.vpr"),
+        "unexpected arguments: {}",
+        arg
+    );
+    assert!(Path::new(path).exists(), "the program was not written");
+    match result {
+        VerificationResult::Success => {}
+        other => panic!(
+            "program not verified successfully, instead found {:?}",
+            other
+        ),
+    }
+}
+
+#[test]
+fn verification_error() {
+    let (_, result) = verify_program("failing");
+
+    match result {
+        VerificationResult::Failure(errors) => {
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].full_id, "assert.failed:assertion.false");
+            assert_eq!(
+                errors[0].message,
+                "Assert might fail. Assertion false might not hold."
+            );
+        }
+        other => panic!("verification error not reported, instead found {:?}", other),
+    }
+}
+
+#[test]
+fn consistency_error() {
+    let (_, result) = verify_program("inconsistent");
+
+    match result {
+        VerificationResult::ConsistencyErrors(errors) => {
+            assert_eq!(errors, vec!["identifier field is reserved".to_string()])
+        }
+        other => panic!("consistency error not reported, instead found {:?}", other),
+    }
+}
+
+/// Verify an empty program with the mock ViperServer, which answers
+/// according to the name of the program. Returns the arguments of the
+/// verification job and the result.
+fn verify_program(name: &str) -> (String, VerificationResult) {
+    let client = ViperServerClient::new(SERVER_ADDRESS.clone()).expect("Invalid server address!");
+
+    let program = Program {
+        name: name.to_string(),
+        domains: vec![],
+        fields: vec![],
+        builtin_methods: vec![],
+        methods: vec![],
+        functions: vec![],
+        viper_predicates: vec![],
+    };
+    let request = VerificationRequest {
+        program: prusti_common::vir::program::Program::Legacy(program),
+        backend_config: Default::default(),
+    };
+
+    let result = Builder::new()
+        .basic_scheduler()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime")
+        .block_on(client.verify(request))
+        .expect("Verification request failed");
+    let arg = JOBS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|arg| arg.ends_with(&format!("{}.vpr", name)))
+        .expect("no verification job was started")
+        .clone();
+    (arg, result)
+}
+
+/// The messages that ViperServer streams for the job with the given
+/// arguments, one per line.
+fn messages(arg: &str) -> String {
+    let result = if arg.ends_with("failing.vpr") {
+        json!({
+            "msg_type": "verification_result",
+            "msg_body": {
+                "kind": "overall",
+                "status": "failure",
+                "details": { "result": { "errors": [{
+                    "tag": "assert.failed:assertion.false",
+                    "text": "Assert might fail. Assertion false might not hold.",
+                    "position": { "start": "1:1", "end": "1:2" },
+                }]}},
+            },
+        })
+    } else if arg.ends_with("inconsistent.vpr") {
+        json!({
+            "msg_type": "ast_construction_result",
+            "msg_body": {
+                "status": "failure",
+                "details": { "result": { "errors": [{
+                    "text": "identifier field is reserved",
+                }]}},
+            },
+        })
+    } else {
+        json!({
+            "msg_type": "verification_result",
+            "msg_body": { "kind": "overall", "status": "success" },
+        })
+    };
+    let start = json!({ "msg_type": "verification_started", "msg_body": {} });
+    format!("{}\n{}\n", start, result)
+}
+
+fn spawn_mock_viper_server() -> SocketAddr {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let start_job = warp::post()
+            .and(warp::path!("verify"))
+            .and(warp::body::json())
+            .map(|body: Value| {
+                let mut jobs = JOBS.lock().unwrap();
+                jobs.push(body["arg"].as_str().unwrap().to_string());
+                warp::reply::json(&json!({ "id": jobs.len() - 1 }))
+            });
+        let stream_messages = warp::get()
+            .and(warp::path!("verify" / usize))
+            .map(|job_id: usize| messages(&JOBS.lock().unwrap()[job_id]));
+
+        Builder::new()
+            .basic_scheduler()
+            .enable_all()
+            .build()
+            .expect("failed to construct Tokio runtime")
+            .block_on(async {
+                let (address, server_loop) = warp::serve(start_job.or(stream_messages))
+                    .bind_ephemeral((Ipv4Addr::LOCALHOST, 0));
+                sender.send(address).unwrap();
+                server_loop.await
+            });
+    });
+    // Return the address received by the server thread.
+    receiver.recv().unwrap()
+}
@@ -13,10 +13,12 @@ mod extern_spec_rewriter;
 mod rewriter;
 mod parse_closure_macro;
 mod spec_attribute_kind;
+mod runtime_checks;
+mod wrapping_arithmetic;
 pub mod specifications;
 
 use proc_macro2::{Span, TokenStream, TokenTree};
-use quote::{quote_spanned, ToTokens};
+use quote::{format_ident, quote_spanned, ToTokens};
 use syn::spanned::Spanned;
 use std::convert::TryInto;
 
@@ -48,7 +50,8 @@ fn extract_prusti_attributes(
                     SpecAttributeKind::Requires
                     | SpecAttributeKind::Ensures
                     | SpecAttributeKind::AfterExpiry
-                    | SpecAttributeKind::AssertOnExpiry => {
+                    | SpecAttributeKind::AssertOnExpiry
+                    | SpecAttributeKind::Transition => {
                         // We need to drop the surrounding parenthesis to make the
                         // tokens identical to the ones passed by the native procedural
                         // macro call.
@@ -57,9 +60,31 @@ fn extract_prusti_attributes(
                         assert!(iter.next().is_none(), "Unexpected shape of an attribute.");
                         tokens
                     }
-                    // Nothing to do for attributes without arguments.
+                    // Pure functions, lemmas, bounded functions and runtime checks take arguments.
                     SpecAttributeKind::Pure
-                    | SpecAttributeKind::Trusted
+                    | SpecAttributeKind::Lemma
+                    | SpecAttributeKind::Bounded
+                    | SpecAttributeKind::InsertRuntimeCheck => {
+                        let mut iter = attr.tokens.into_iter();
+                        let tokens = match iter.next() {
+                            Some(TokenTree::Group(group)) => group.stream(),
+                            None => TokenStream::new(),
+                            _ => panic!("Unexpected shape of an attribute."),
+                        };
+                        assert!(iter.next().is_none(), "Unexpected shape of an attribute.");
+                        tokens
+                    }
+                    // Nothing to do for attributes without arguments.
+                    SpecAttributeKind::Trusted
+                    | SpecAttributeKind::NonlinearArithmetic
+                    | SpecAttributeKind::AllowOverflow
+                    | SpecAttributeKind::BitwiseArithmetic
+                    | SpecAttributeKind::Opaque
+                    | SpecAttributeKind::InlineSpec
+                    | SpecAttributeKind::NeverInlineSpec
+                    | SpecAttributeKind::NoAutoFrame
+                    | SpecAttributeKind::ExplicitExpiry
+                    | SpecAttributeKind::Law
                     | SpecAttributeKind::Predicate => {
                         assert!(attr.tokens.is_empty(), "Unexpected shape of an attribute.");
                         attr.tokens
@@ -77,6 +102,116 @@ fn extract_prusti_attributes(
     prusti_attributes
 }
 
+/// Split the arguments of a `#[cfg_attr(predicate, attr1, attr2(...))]`
+/// attribute into the predicate and the attributes.
+fn parse_cfg_attr(attr: &syn::Attribute) -> Option<(TokenStream, Vec<TokenStream>)> {
+    if !attr.path.is_ident("cfg_attr") {
+        return None;
+    }
+    let mut iter = attr.tokens.clone().into_iter();
+    let arguments = match (iter.next(), iter.next()) {
+        (Some(TokenTree::Group(group)), None) => group.stream(),
+        _ => return None,
+    };
+    let mut split_arguments = vec![TokenStream::new()];
+    for token in arguments {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                split_arguments.push(TokenStream::new());
+            }
+            token => split_arguments.last_mut().unwrap().extend(std::iter::once(token)),
+        }
+    }
+    if split_arguments.last().map_or(false, |argument| argument.is_empty()) {
+        split_arguments.pop();
+    }
+    if split_arguments.len() < 2 {
+        return None;
+    }
+    let predicate = split_arguments.remove(0);
+    Some((predicate, split_arguments))
+}
+
+/// Returns the kind and the tokens of `attr1` or `attr2(...)` if it is a
+/// Prusti attribute, with the tokens as in `extract_prusti_attributes`.
+fn parse_nested_prusti_attribute(tokens: &TokenStream) -> Option<(SpecAttributeKind, TokenStream)> {
+    let mut iter = tokens.clone().into_iter();
+    let attr_kind = match iter.next() {
+        Some(TokenTree::Ident(ident)) => ident.to_string().try_into().ok()?,
+        _ => return None,
+    };
+    let tokens = match iter.next() {
+        Some(TokenTree::Group(group)) => group.stream(),
+        None => TokenStream::new(),
+        _ => return None,
+    };
+    if iter.next().is_some() {
+        return None;
+    }
+    Some((attr_kind, tokens))
+}
+
+/// Generate spec items and attributes for the Prusti attributes nested in the
+/// `#[cfg_attr(predicate, ...)]` attributes of `item`, guarded by the same
+/// predicate. The compiler evaluates `cfg_attr` before running the macros of
+/// an item, but not before running the macros of an enclosing item; this is
+/// needed when `item` is rewritten as part of its enclosing item, e.g. by
+/// `#[refine_trait_spec]`.
+fn generate_for_cfg_attrs(item: &mut untyped::AnyFnItem) -> GeneratedResult {
+    let mut guarded_attributes = vec![];
+    let mut regular_attributes = vec![];
+    for attr in item.attrs_mut().drain(0..) {
+        if let Some((predicate, nested)) = parse_cfg_attr(&attr) {
+            let (prusti_nested, other_nested): (Vec<_>, Vec<_>) = nested
+                .into_iter()
+                .partition(|tokens| parse_nested_prusti_attribute(tokens).is_some());
+            if prusti_nested.is_empty() {
+                regular_attributes.push(attr);
+                continue;
+            }
+            if !other_nested.is_empty() {
+                regular_attributes.push(parse_quote_spanned! {attr.span()=>
+                    #[cfg_attr(#predicate, #(#other_nested),*)]
+                });
+            }
+            for tokens in prusti_nested {
+                let prusti_attribute = parse_nested_prusti_attribute(&tokens).unwrap();
+                guarded_attributes.push((attr.span(), predicate.clone(), prusti_attribute));
+            }
+        } else {
+            regular_attributes.push(attr);
+        }
+    }
+    *item.attrs_mut() = regular_attributes;
+
+    let mut generated_items = vec![];
+    let mut generated_attributes = vec![];
+    for (span, predicate, (attr_kind, tokens)) in guarded_attributes {
+        if matches!(attr_kind, SpecAttributeKind::Predicate | SpecAttributeKind::Lemma) {
+            return Err(syn::Error::new(
+                span,
+                "predicates and lemmas cannot be declared in a `cfg_attr`",
+            ));
+        }
+        let (new_items, new_attributes) =
+            generate_spec_and_assertions(vec![(attr_kind, tokens)], item)?;
+        generated_items.extend(new_items.into_iter().map(|new_item| -> syn::Item {
+            parse_quote_spanned! {span=>
+                #[cfg(#predicate)]
+                #new_item
+            }
+        }));
+        generated_attributes.extend(new_attributes.into_iter().map(|new_attribute| -> syn::Attribute {
+            let path = &new_attribute.path;
+            let tokens = &new_attribute.tokens;
+            parse_quote_spanned! {span=>
+                #[cfg_attr(#predicate, #path #tokens)]
+            }
+        }));
+    }
+    Ok((generated_items, generated_attributes))
+}
+
 /// Rewrite an item as required by *all* its specification attributes.
 ///
 /// The first attribute (the outer one) needs to be passed via `attr_kind` and `attr` because
@@ -107,8 +242,16 @@ pub fn rewrite_prusti_attributes(
         ).to_compile_error();
     }
 
+    let has_attr = |kind| prusti_attributes.iter().any(|(ak, _)| ak == &kind);
+    if has_attr(SpecAttributeKind::InlineSpec) && has_attr(SpecAttributeKind::NeverInlineSpec) {
+        return syn::Error::new(
+            item.span(),
+            "`#[inline_spec]` is incompatible with `#[never_inline_spec]`",
+        ).to_compile_error();
+    }
+
     let (generated_spec_items, generated_attributes) = handle_result!(
-        generate_spec_and_assertions(prusti_attributes, &item)
+        generate_spec_and_assertions(prusti_attributes, &mut item)
     );
 
     quote_spanned! {item.span()=>
@@ -122,12 +265,25 @@ type GeneratedResult = syn::Result<(Vec<syn::Item>, Vec<syn::Attribute>)>;
 
 /// Generate spec items and attributes for `item` from the Prusti attributes
 fn generate_spec_and_assertions(
-    mut prusti_attributes: Vec<(SpecAttributeKind, TokenStream)>,
-    item: &untyped::AnyFnItem,
+    prusti_attributes: Vec<(SpecAttributeKind, TokenStream)>,
+    item: &mut untyped::AnyFnItem,
 ) -> GeneratedResult {
     let mut generated_items = vec![];
     let mut generated_attributes = vec![];
 
+    let (mut prusti_attributes, runtime_checks) =
+        runtime_checks::select_runtime_checks(prusti_attributes)?;
+    if !runtime_checks.is_empty() {
+        runtime_checks::insert_runtime_checks(item, runtime_checks)?;
+    }
+
+    // Lemmas registered as axioms need the whole contract.
+    let contract: Vec<_> = prusti_attributes
+        .iter()
+        .filter(|(attr_kind, _)| matches!(attr_kind, SpecAttributeKind::Requires | SpecAttributeKind::Ensures))
+        .cloned()
+        .collect();
+
     for (attr_kind, attr_tokens) in prusti_attributes.drain(..) {
         let rewriting_result = match attr_kind {
             SpecAttributeKind::Requires => generate_for_requires(attr_tokens, item),
@@ -136,10 +292,24 @@ fn generate_spec_and_assertions(
             SpecAttributeKind::AssertOnExpiry => generate_for_assert_on_expiry(attr_tokens, item),
             SpecAttributeKind::Pure => generate_for_pure(attr_tokens, item),
             SpecAttributeKind::Trusted => generate_for_trusted(attr_tokens, item),
+            SpecAttributeKind::Lemma => generate_for_lemma(attr_tokens, item, &contract),
+            SpecAttributeKind::Law => generate_for_law(attr_tokens, item),
+            SpecAttributeKind::Bounded => generate_for_bounded(attr_tokens, item),
+            SpecAttributeKind::NonlinearArithmetic => generate_for_flag("nonlinear_arithmetic", attr_tokens, item),
+            SpecAttributeKind::AllowOverflow => generate_for_flag("allow_overflow", attr_tokens, item),
+            SpecAttributeKind::BitwiseArithmetic => generate_for_flag("bitwise_arithmetic", attr_tokens, item),
+            SpecAttributeKind::Transition => generate_for_transition(attr_tokens, item),
+            SpecAttributeKind::Opaque => generate_for_flag("opaque", attr_tokens, item),
+            SpecAttributeKind::InlineSpec => generate_for_flag("inline_spec", attr_tokens, item),
+            SpecAttributeKind::NeverInlineSpec => generate_for_flag("never_inline_spec", attr_tokens, item),
+            SpecAttributeKind::NoAutoFrame => generate_for_flag("no_auto_frame", attr_tokens, item),
+            SpecAttributeKind::ExplicitExpiry => generate_for_flag("explicit_expiry", attr_tokens, item),
             // Predicates are handled separately below; the entry in the SpecAttributeKind enum
             // only exists so we successfully parse it and emit an error in
             // `check_incompatible_attrs`; so we'll never reach here.
             SpecAttributeKind::Predicate => unreachable!(),
+            // Removed by `select_runtime_checks` above.
+            SpecAttributeKind::InsertRuntimeCheck => unreachable!(),
         };
         let (new_items, new_attributes) = rewriting_result?;
         generated_items.extend(new_items);
@@ -221,39 +391,469 @@ fn generate_for_assert_on_expiry(attr: TokenStream, item: &untyped::AnyFnItem) -
 }
 
 /// Generate spec items and attributes to typecheck and later retrieve "pure" annotations.
+///
+/// `#[pure(fuel = n)]` sets how many times the definition of the function is
+/// unfolded at its calls.
 fn generate_for_pure(attr: TokenStream, item: &untyped::AnyFnItem) -> GeneratedResult {
+    let mut generated_attributes = vec![parse_quote_spanned! {item.span()=>
+        #[prusti::pure]
+    }];
+    if !attr.is_empty() {
+        let mut attr_iter = attr.clone().into_iter();
+        let fuel = match (attr_iter.next(), attr_iter.next(), attr_iter.next(), attr_iter.next()) {
+            (
+                Some(TokenTree::Ident(ident)),
+                Some(TokenTree::Punct(punct)),
+                Some(TokenTree::Literal(literal)),
+                None,
+            ) if ident == "fuel" && punct.as_char() == '=' => {
+                syn::parse2::<syn::LitInt>(literal.to_token_stream())
+                    .and_then(|literal| literal.base10_parse::<u64>())
+                    .ok()
+            }
+            _ => None,
+        };
+        let fuel_str = fuel.ok_or_else(|| syn::Error::new(
+            attr.span(),
+            "expected `fuel = ...` with a non-negative integer as the argument of `#[pure]`"
+        ))?.to_string();
+        generated_attributes.push(parse_quote_spanned! {item.span()=>
+            #[prusti::fuel = #fuel_str]
+        });
+    }
+    Ok((vec![], generated_attributes))
+}
+
+/// Generate spec items and attributes to typecheck and later retrieve "trusted" annotations.
+fn generate_for_trusted(attr: TokenStream, item: &untyped::AnyFnItem) -> GeneratedResult {
     if !attr.is_empty() {
         return Err(syn::Error::new(
             attr.span(),
-            "the `#[pure]` attribute does not take parameters"
+            "the `#[trusted]` attribute does not take parameters"
         ));
     }
 
     Ok((
         vec![],
         vec![parse_quote_spanned! {item.span()=>
-            #[prusti::pure]
+            #[prusti::trusted]
         }],
     ))
 }
 
-/// Generate spec items and attributes to typecheck and later retrieve "trusted" annotations.
-fn generate_for_trusted(attr: TokenStream, item: &untyped::AnyFnItem) -> GeneratedResult {
+/// Generate the attribute `#[prusti::<name>]` to later retrieve the annotations
+/// that do not take parameters, such as `#[nonlinear_arithmetic]`.
+fn generate_for_flag(name: &str, attr: TokenStream, item: &untyped::AnyFnItem) -> GeneratedResult {
     if !attr.is_empty() {
         return Err(syn::Error::new(
             attr.span(),
-            "the `#[trusted]` attribute does not take parameters"
+            format!("the `#[{}]` attribute does not take parameters", name)
         ));
     }
 
+    let name = syn::Ident::new(name, item.span());
     Ok((
         vec![],
         vec![parse_quote_spanned! {item.span()=>
-            #[prusti::trusted]
+            #[prusti::#name]
+        }],
+    ))
+}
+
+/// Generate spec items and attributes to typecheck and later retrieve "law" annotations.
+///
+/// A law is a lemma declared in a trait. Each implementation of the trait
+/// inherits its contract and is verified against it.
+fn generate_for_law(attr: TokenStream, item: &untyped::AnyFnItem) -> GeneratedResult {
+    if !attr.is_empty() {
+        return Err(syn::Error::new(
+            attr.span(),
+            "the `#[law]` attribute does not take parameters"
+        ));
+    }
+    if let untyped::AnyFnItem::ImplMethod(_) = item {
+        return Err(syn::Error::new(
+            item.span(),
+            "a law must be declared in a trait; its implementations inherit its contract"
+        ));
+    }
+    if let syn::ReturnType::Type(_, ty) = &item.sig().output {
+        return Err(syn::Error::new(
+            ty.span(),
+            "a law must not return a value"
+        ));
+    }
+
+    Ok((
+        vec![],
+        vec![parse_quote_spanned! {item.span()=>
+            #[prusti::law]
+        }],
+    ))
+}
+
+/// Generate spec items and attributes to typecheck and later retrieve "bounded" annotations.
+///
+/// `#[bounded(k)]` verifies the function by unrolling each of its loops `k`
+/// times instead of using loop invariants; executions that iterate more often
+/// are not checked.
+fn generate_for_bounded(attr: TokenStream, item: &untyped::AnyFnItem) -> GeneratedResult {
+    let bound = syn::parse2::<syn::LitInt>(attr.clone())
+        .and_then(|literal| literal.base10_parse::<u64>())
+        .ok()
+        .filter(|&bound| bound > 0);
+    let bound_str = bound.ok_or_else(|| syn::Error::new(
+        attr.span(),
+        "expected a positive integer as the argument of `#[bounded]`, \
+        e.g. `#[bounded(5)]`"
+    ))?.to_string();
+
+    Ok((
+        vec![],
+        vec![parse_quote_spanned! {item.span()=>
+            #[prusti::bounded = #bound_str]
         }],
     ))
 }
 
+/// Generate spec items and attributes to typecheck and later retrieve "transition" annotations.
+///
+/// `#[transition(A | B -> C)]` on a method of a `#[typestate(...)]` struct
+/// requires that `self` is in the state `A` or `B`, and ensures that it is in
+/// the state `C` afterwards. The target state is the state of `self` if it is
+/// passed by mutable reference and of `result` otherwise. Without sources, as
+/// in `#[transition(-> C)]`, the method can be called in any state; without a
+/// target, as in `#[transition(A)]`, a mutable `self` stays in its state.
+///
+/// The precondition is marked with `prusti::typestate_spec`, so that Prusti
+/// reports protocol violations with a tailored message.
+fn generate_for_transition(attr: TokenStream, item: &untyped::AnyFnItem) -> GeneratedResult {
+    let parser = |input: syn::parse::ParseStream| {
+        let sources = if input.peek(syn::Token![->]) {
+            vec![]
+        } else {
+            syn::punctuated::Punctuated::<syn::Ident, syn::Token![|]>::parse_separated_nonempty(input)?
+                .into_iter()
+                .collect()
+        };
+        let target = if input.is_empty() {
+            None
+        } else {
+            input.parse::<syn::Token![->]>()?;
+            Some(input.parse::<syn::Ident>()?)
+        };
+        Ok((sources, target))
+    };
+    let (sources, target): (Vec<syn::Ident>, Option<syn::Ident>) =
+        syn::parse::Parser::parse2(parser, attr.clone())?;
+    let receiver = item.sig().receiver().and_then(|receiver| match receiver {
+        syn::FnArg::Receiver(receiver) => Some(receiver),
+        syn::FnArg::Typed(_) => None,
+    });
+    let is_mut_receiver = matches!(receiver, Some(receiver) if receiver.reference.is_some() && receiver.mutability.is_some());
+    let is_ref_receiver = matches!(receiver, Some(receiver) if receiver.reference.is_some() && receiver.mutability.is_none());
+    if !sources.is_empty() && receiver.is_none() {
+        return Err(syn::Error::new(
+            attr.span(),
+            "a transition from a state requires a method with a `self` parameter",
+        ));
+    }
+    let target = match target {
+        Some(target) => Some(target),
+        None if is_mut_receiver && sources.len() == 1 => Some(sources[0].clone()),
+        None if is_mut_receiver => return Err(syn::Error::new(
+            attr.span(),
+            "a method taking `&mut self` from several states needs a target state",
+        )),
+        None => None,
+    };
+    if is_ref_receiver && target.as_ref().map_or(false, |target| sources.len() != 1 || &sources[0] != target) {
+        return Err(syn::Error::new(
+            attr.span(),
+            "a method taking `&self` cannot change the state of `self`",
+        ));
+    }
+    let in_state = |place: TokenStream, state: &syn::Ident| {
+        let state_fn = syn::Ident::new(&format!("prusti_typestate_{}", state), state.span());
+        quote_spanned! {state.span()=> #place.#state_fn() }
+    };
+
+    let mut rewriter = rewriter::AstRewriter::new();
+    let mut generated_items = vec![];
+    let mut generated_attributes = vec![];
+    if !sources.is_empty() {
+        let spec_id = rewriter.generate_spec_id();
+        let spec_id_str = spec_id.to_string();
+        let pre = sources.iter().map(|source| in_state(quote_spanned! {attr.span()=> self}, source));
+        let mut spec_item = rewriter.process_assertion(
+            rewriter::SpecItemType::Precondition,
+            spec_id,
+            quote_spanned! {attr.span()=> #(#pre)||* },
+            item,
+        )?;
+        if let syn::Item::Fn(spec_fn) = &mut spec_item {
+            spec_fn.attrs.push(parse_quote_spanned! {item.span()=>
+                #[prusti::typestate_spec]
+            });
+        }
+        generated_items.push(spec_item);
+        generated_attributes.push(parse_quote_spanned! {item.span()=>
+            #[prusti::pre_spec_id_ref = #spec_id_str]
+        });
+    }
+    if !is_ref_receiver {
+        if let Some(target) = target {
+            let spec_id = rewriter.generate_spec_id();
+            let spec_id_str = spec_id.to_string();
+            let place = if is_mut_receiver {
+                quote_spanned! {attr.span()=> self}
+            } else {
+                quote_spanned! {attr.span()=> result}
+            };
+            generated_items.push(rewriter.process_assertion(
+                rewriter::SpecItemType::Postcondition,
+                spec_id,
+                in_state(place, &target),
+                item,
+            )?);
+            generated_attributes.push(parse_quote_spanned! {item.span()=>
+                #[prusti::post_spec_id_ref = #spec_id_str]
+            });
+        }
+    }
+    Ok((generated_items, generated_attributes))
+}
+
+/// Generate spec items and attributes to typecheck and later retrieve "lemma" annotations.
+///
+/// `#[lemma(induction = n)]` adds the induction hypothesis, a recursive call
+/// of the lemma with `n - 1` if `n > 0`, to the beginning of the body.
+///
+/// `#[lemma(triggers = [...])]` additionally registers the lemma as the axiom
+/// `forall(|args| pre ==> post, triggers = [...])`, which is assumed in all
+/// other procedures.
+fn generate_for_lemma(
+    attr: TokenStream,
+    item: &mut untyped::AnyFnItem,
+    contract: &[(SpecAttributeKind, TokenStream)],
+) -> GeneratedResult {
+    if let syn::ReturnType::Type(_, ty) = &item.sig().output {
+        return Err(syn::Error::new(
+            ty.span(),
+            "a lemma must not return a value"
+        ));
+    }
+    let mut generated_attributes = vec![parse_quote_spanned! {item.span()=>
+        #[prusti::lemma]
+    }];
+    let (induction, triggers) = parse_lemma_arguments(attr)?;
+    if let Some(variable) = induction {
+        add_induction_hypothesis(item, &variable)?;
+    }
+    let triggers = match triggers {
+        Some(triggers) => triggers,
+        None => return Ok((vec![], generated_attributes)),
+    };
+
+    let sig = item.sig();
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            sig.generics.span(),
+            "a lemma registered as an axiom cannot be generic"
+        ));
+    }
+    if let Some(receiver @ syn::FnArg::Receiver(_)) = sig.inputs.first() {
+        return Err(syn::Error::new(
+            receiver.span(),
+            "a lemma registered as an axiom cannot take `self`"
+        ));
+    }
+    let conjunction = |attr_kind: SpecAttributeKind| -> Option<TokenStream> {
+        let mut conjuncts = contract
+            .iter()
+            .filter(|(kind, _)| *kind == attr_kind)
+            .map(|(_, tokens)| quote_spanned! {tokens.span()=> (#tokens) });
+        let first = conjuncts.next()?;
+        Some(conjuncts.fold(first, |acc, conjunct| quote_spanned! {item.span()=> #acc && #conjunct }))
+    };
+    let postcondition = conjunction(SpecAttributeKind::Ensures).ok_or_else(|| syn::Error::new(
+        item.span(),
+        "a lemma registered as an axiom must have a postcondition"
+    ))?;
+    let body = match conjunction(SpecAttributeKind::Requires) {
+        Some(precondition) => quote_spanned! {item.span()=> (#precondition) ==> (#postcondition) },
+        None => postcondition,
+    };
+    let inputs = &sig.inputs;
+    let axiom = quote_spanned! {item.span()=>
+        forall(|#inputs| #body, triggers = #triggers)
+    };
+
+    // The axiom does not depend on the arguments of the lemma.
+    let mut axiom_sig = sig.clone();
+    axiom_sig.inputs = syn::punctuated::Punctuated::new();
+    let axiom_item = untyped::AnyFnItem::Fn(syn::ItemFn {
+        attrs: vec![],
+        vis: syn::Visibility::Inherited,
+        sig: axiom_sig,
+        block: Box::new(parse_quote_spanned! {item.span()=> {} }),
+    });
+    let mut rewriter = rewriter::AstRewriter::new();
+    let spec_id = rewriter.generate_spec_id();
+    let spec_id_str = spec_id.to_string();
+    let spec_item = rewriter.process_assertion(
+        rewriter::SpecItemType::Axiom,
+        spec_id,
+        axiom,
+        &axiom_item,
+    )?;
+    generated_attributes.push(parse_quote_spanned! {item.span()=>
+        #[prusti::lemma_axiom_spec_id_ref = #spec_id_str]
+    });
+    Ok((vec![spec_item], generated_attributes))
+}
+
+/// Parse the comma-separated arguments `induction = n` and `triggers = [...]`
+/// of `#[lemma]`.
+fn parse_lemma_arguments(attr: TokenStream) -> syn::Result<(Option<syn::Ident>, Option<TokenStream>)> {
+    let mut induction = None;
+    let mut triggers = None;
+    let mut tokens = attr.into_iter().peekable();
+    while tokens.peek().is_some() {
+        let argument: TokenStream = tokens
+            .by_ref()
+            .take_while(|token| !matches!(token, TokenTree::Punct(punct) if punct.as_char() == ','))
+            .collect();
+        let mut argument_iter = argument.clone().into_iter();
+        match (argument_iter.next(), argument_iter.next()) {
+            (Some(TokenTree::Ident(ident)), Some(TokenTree::Punct(punct)))
+                if ident == "triggers" && punct.as_char() == '=' && triggers.is_none() =>
+            {
+                triggers = Some(argument_iter.collect());
+            }
+            (Some(TokenTree::Ident(ident)), Some(TokenTree::Punct(punct)))
+                if ident == "induction" && punct.as_char() == '=' && induction.is_none() =>
+            {
+                induction = Some(syn::parse2(argument_iter.collect())?);
+            }
+            _ => return Err(syn::Error::new(
+                argument.span(),
+                "expected `induction = ...` or `triggers = [...]` as the argument of `#[lemma]`"
+            )),
+        }
+    }
+    Ok((induction, triggers))
+}
+
+/// Prepend `if n > 0 { lemma(.., n - 1, ..); }` to the body of the lemma, so
+/// that the property is proven by induction on `n`.
+fn add_induction_hypothesis(item: &mut untyped::AnyFnItem, variable: &syn::Ident) -> syn::Result<()> {
+    let sig = item.sig();
+    let mut found_variable = false;
+    let mut arguments = vec![];
+    for input in sig.inputs.iter() {
+        let ident = match input {
+            syn::FnArg::Typed(syn::PatType { pat: box syn::Pat::Ident(pat_ident), .. })
+                if pat_ident.by_ref.is_none() && pat_ident.subpat.is_none() => &pat_ident.ident,
+            syn::FnArg::Receiver(_) => return Err(syn::Error::new(
+                input.span(),
+                "a lemma proven by induction cannot take `self`"
+            )),
+            _ => return Err(syn::Error::new(
+                input.span(),
+                "the arguments of a lemma proven by induction must be identifiers"
+            )),
+        };
+        if ident == variable {
+            found_variable = true;
+            arguments.push(quote_spanned! {variable.span()=> #ident - 1 });
+        } else {
+            arguments.push(ident.to_token_stream());
+        }
+    }
+    if !found_variable {
+        return Err(syn::Error::new(
+            variable.span(),
+            format!("`{}` is not an argument of the lemma", variable)
+        ));
+    }
+    let name = &sig.ident;
+    let callee = match item {
+        untyped::AnyFnItem::Fn(_) => name.to_token_stream(),
+        _ => quote_spanned! {name.span()=> Self::#name },
+    };
+    let hypothesis: syn::Stmt = parse_quote_spanned! {variable.span()=>
+        if #variable > 0 {
+            #callee(#(#arguments),*);
+        }
+    };
+    let block = item.block_mut().ok_or_else(|| syn::Error::new(
+        variable.span(),
+        "a lemma proven by induction must have a body"
+    ))?;
+    block.stmts.insert(0, hypothesis);
+    Ok(())
+}
+
+/// Expand `apply_lemma!(lemma(args))` to a call of the lemma, so that its
+/// precondition is checked and its postcondition assumed.
+pub fn apply_lemma(tokens: TokenStream) -> TokenStream {
+    let call: syn::Expr = handle_result!(syn::parse2(tokens));
+    if !matches!(call, syn::Expr::Call(_) | syn::Expr::MethodCall(_)) {
+        return syn::Error::new(
+            call.span(),
+            "`apply_lemma!` expects a call to a lemma",
+        ).to_compile_error();
+    }
+    let callsite_span = Span::call_site();
+    quote_spanned! {callsite_span=>
+        {
+            let _: () = #call;
+        }
+    }
+}
+
+/// Insert the runtime checks of the contracts marked by `#[insert_runtime_check]`
+/// when the program is compiled without Prusti. The other Prusti attributes
+/// of the item are removed, since they have no effect in this case.
+pub fn insert_runtime_check(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    let mut item: untyped::AnyFnItem = handle_result!(syn::parse2(tokens));
+    let mut prusti_attributes = vec![(SpecAttributeKind::InsertRuntimeCheck, attr)];
+    prusti_attributes.extend(extract_prusti_attributes(&mut item));
+    let (_, runtime_checks) = handle_result!(runtime_checks::select_runtime_checks(prusti_attributes));
+    handle_result!(runtime_checks::insert_runtime_checks(&mut item, runtime_checks));
+    item.into_token_stream()
+}
+
+/// When the program is compiled without Prusti, the arithmetic operations of a
+/// function annotated with `#[allow_overflow]` are rewritten to wrap around.
+pub fn allow_overflow_runtime(tokens: TokenStream) -> TokenStream {
+    handle_result!(wrapping_arithmetic::rewrite_function(tokens))
+}
+
+/// When the program is compiled without Prusti, the arithmetic operations of a
+/// `wrapping!` block are rewritten to wrap around.
+pub fn wrapping_runtime(tokens: TokenStream) -> TokenStream {
+    handle_result!(wrapping_arithmetic::rewrite_block(tokens))
+}
+
+/// The arithmetic operations in a `wrapping!` block are recognized by Prusti
+/// through a marker closure, whose span is the whole block.
+pub fn wrapping(tokens: TokenStream) -> TokenStream {
+    let callsite_span = Span::call_site();
+    quote_spanned! {callsite_span=>
+        {
+            #[allow(unused_must_use, unused_variables)]
+            if false {
+                #[prusti::spec_only]
+                #[prusti::wrapping_spec]
+                || {};
+            }
+            #tokens
+        }
+    }
+}
+
 pub fn body_invariant(tokens: TokenStream) -> TokenStream {
     let mut rewriter = rewriter::AstRewriter::new();
     let spec_id = rewriter.generate_spec_id();
@@ -267,6 +867,37 @@ pub fn body_invariant(tokens: TokenStream) -> TokenStream {
     }
 }
 
+pub fn yield_invariant(tokens: TokenStream) -> TokenStream {
+    let mut rewriter = rewriter::AstRewriter::new();
+    let spec_id = rewriter.generate_spec_id();
+    let invariant = handle_result!(rewriter.process_yield_invariant(spec_id, tokens));
+    let callsite_span = Span::call_site();
+    quote_spanned! {callsite_span=>
+        #[allow(unused_must_use, unused_variables)]
+        if false {
+            #invariant
+        }
+    }
+}
+
+pub fn resume_requires(tokens: TokenStream) -> TokenStream {
+    let mut rewriter = rewriter::AstRewriter::new();
+    let spec_id = rewriter.generate_spec_id();
+    let requirement = handle_result!(rewriter.process_resume_requires(spec_id, tokens));
+    let callsite_span = Span::call_site();
+    quote_spanned! {callsite_span=>
+        #[allow(unused_must_use, unused_variables)]
+        if false {
+            #requirement
+        }
+    }
+}
+
+pub fn prusti_calc(tokens: TokenStream) -> TokenStream {
+    let mut rewriter = rewriter::AstRewriter::new();
+    handle_result!(rewriter.process_calc(tokens))
+}
+
 /// Unlike the functions above, which are only called from
 /// prusti-contracts-internal, this function also needs to be called
 /// from prusti-contracts-impl, because we still need to parse the
@@ -372,16 +1003,32 @@ pub fn refine_trait_spec(_attr: TokenStream, tokens: TokenStream) -> TokenStream
     for item in impl_block.items {
         match item {
             syn::ImplItem::Method(method) => {
+                // The spec items are only compiled together with the method.
+                let cfg_attributes: Vec<_> = method
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path.is_ident("cfg"))
+                    .cloned()
+                    .collect();
                 let mut method_item = untyped::AnyFnItem::ImplMethod(method);
                 let prusti_attributes: Vec<_> = extract_prusti_attributes(&mut method_item);
-                let (spec_items, generated_attributes) = handle_result!(
-                    generate_spec_and_assertions(prusti_attributes, &method_item)
+                let (mut spec_items, mut generated_attributes) = handle_result!(
+                    generate_spec_and_assertions(prusti_attributes, &mut method_item)
                 );
+                let (cfg_spec_items, cfg_generated_attributes) = handle_result!(
+                    generate_for_cfg_attrs(&mut method_item)
+                );
+                spec_items.extend(cfg_spec_items);
+                generated_attributes.extend(cfg_generated_attributes);
                 generated_spec_items.extend(spec_items.into_iter().map(|spec_item| {
                     match spec_item {
                         syn::Item::Fn(spec_item_fn) => {
                             syn::ImplItem::Method(syn::ImplItemMethod {
-                                attrs: spec_item_fn.attrs,
+                                attrs: cfg_attributes
+                                    .iter()
+                                    .cloned()
+                                    .chain(spec_item_fn.attrs)
+                                    .collect(),
                                 vis: spec_item_fn.vis,
                                 defaultness: None,
                                 sig: spec_item_fn.sig,
@@ -502,3 +1149,683 @@ pub fn predicate(tokens: TokenStream) -> TokenStream {
         #cleaned_fn
     }
 }
+
+/// Generate the specification of a struct whose values are protected by a
+/// `Mutex` or a `RwLock`. The lock invariant is a predicate of the struct,
+/// and spec functions attach it to the constructors of the locks and to the
+/// dereferencing of their guards:
+///
+/// * `Mutex::new`, `RwLock::new` and their `From` implementations require the
+///   invariant;
+/// * borrowing the value from a guard ensures the invariant;
+/// * a mutable borrow of the value from a guard must reestablish the
+///   invariant when it expires.
+///
+/// Prusti finds these spec functions by the `prusti::lock_invariant_spec`
+/// attribute, which names the function they specify.
+pub fn lock_invariant(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    let item: syn::ItemStruct = handle_result!(syn::parse2(tokens));
+    if !item.generics.params.is_empty() {
+        return syn::Error::new(
+            item.generics.span(),
+            "lock invariants are not supported on generic types",
+        ).to_compile_error();
+    }
+    let item_span = item.span();
+    let item_ident = &item.ident;
+    let invariant = predicate(quote_spanned! {item_span=>
+        fn prusti_lock_invariant(&self) -> bool {
+            #attr
+        }
+    });
+
+    let mut rewriter = rewriter::AstRewriter::new();
+    let mut spec_fns = TokenStream::new();
+    let mut add_spec_fn = |target: String, spec_type: rewriter::SpecItemType, signature: TokenStream, expr: TokenStream| {
+        spec_fns.extend(generate_invariant_spec_fn(
+            &mut rewriter, "lock_invariant_spec", target, spec_type, signature, expr, item_span,
+        )?);
+        syn::Result::Ok(())
+    };
+    for lock in ["Mutex", "RwLock"] {
+        let lock_ident = syn::Ident::new(lock, item_span);
+        handle_result!(add_spec_fn(
+            format!("std::sync::{}::new", lock),
+            rewriter::SpecItemType::Precondition,
+            quote_spanned! {item_span=> fn new(value: #item_ident) -> std::sync::#lock_ident<#item_ident> },
+            quote_spanned! {item_span=> value.prusti_lock_invariant() },
+        ));
+        handle_result!(add_spec_fn(
+            format!("std::sync::{}::from", lock),
+            rewriter::SpecItemType::Precondition,
+            quote_spanned! {item_span=> fn from(value: #item_ident) -> std::sync::#lock_ident<#item_ident> },
+            quote_spanned! {item_span=> value.prusti_lock_invariant() },
+        ));
+    }
+    for (guard, is_mutable) in [("MutexGuard", true), ("RwLockReadGuard", false), ("RwLockWriteGuard", true)] {
+        let guard_ident = syn::Ident::new(guard, item_span);
+        handle_result!(add_spec_fn(
+            format!("std::sync::{}::deref", guard),
+            rewriter::SpecItemType::Postcondition,
+            quote_spanned! {item_span=>
+                fn deref(_self: &std::sync::#guard_ident<'_, #item_ident>) -> &#item_ident
+            },
+            quote_spanned! {item_span=> result.prusti_lock_invariant() },
+        ));
+        if is_mutable {
+            let signature = quote_spanned! {item_span=>
+                fn deref_mut(_self: &mut std::sync::#guard_ident<'_, #item_ident>) -> &mut #item_ident
+            };
+            handle_result!(add_spec_fn(
+                format!("std::sync::{}::deref_mut", guard),
+                rewriter::SpecItemType::Pledge,
+                signature.clone(),
+                quote_spanned! {item_span=> result.prusti_lock_invariant() },
+            ));
+            handle_result!(add_spec_fn(
+                format!("std::sync::{}::deref_mut::after_expiry", guard),
+                rewriter::SpecItemType::Pledge,
+                signature,
+                quote_spanned! {item_span=> true },
+            ));
+        }
+    }
+
+    quote_spanned! {item_span=>
+        #item
+
+        impl #item_ident {
+            #invariant
+        }
+
+        #spec_fns
+    }
+}
+
+/// Generate the specification of a struct whose values are sent through the
+/// channels of `std::sync::mpsc`. The message invariant is a predicate of the
+/// struct, and spec functions attach it to the channels:
+///
+/// * `send`, `try_send` and `send_timeout` of `Sender` and `SyncSender`
+///   require the invariant of the sent value;
+/// * `Receiver::recv` and `Receiver::try_recv` ensure the invariant of the
+///   received value.
+///
+/// Prusti finds these spec functions by the `prusti::message_invariant_spec`
+/// attribute, which names the function they specify.
+pub fn message_invariant(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    let item: syn::ItemStruct = handle_result!(syn::parse2(tokens));
+    if !item.generics.params.is_empty() {
+        return syn::Error::new(
+            item.generics.span(),
+            "message invariants are not supported on generic types",
+        ).to_compile_error();
+    }
+    let item_span = item.span();
+    let item_ident = &item.ident;
+    let invariant = predicate(quote_spanned! {item_span=>
+        fn prusti_message_invariant(&self) -> bool {
+            #attr
+        }
+    });
+
+    let mut rewriter = rewriter::AstRewriter::new();
+    let mut spec_fns = TokenStream::new();
+    let mut add_spec_fn = |target: &str, spec_type: rewriter::SpecItemType, signature: TokenStream, expr: TokenStream| {
+        spec_fns.extend(generate_invariant_spec_fn(
+            &mut rewriter, "message_invariant_spec", target.to_string(), spec_type, signature, expr, item_span,
+        )?);
+        syn::Result::Ok(())
+    };
+    let timeout_arg = quote_spanned! {item_span=> , _timeout: std::time::Duration };
+    for (sender, method, extra_args) in [
+        ("Sender", "send", TokenStream::new()),
+        ("Sender", "send_timeout", timeout_arg.clone()),
+        ("SyncSender", "send", TokenStream::new()),
+        ("SyncSender", "try_send", TokenStream::new()),
+        ("SyncSender", "send_timeout", timeout_arg),
+    ] {
+        let sender_ident = syn::Ident::new(sender, item_span);
+        let method_ident = syn::Ident::new(method, item_span);
+        handle_result!(add_spec_fn(
+            &format!("std::sync::mpsc::{}::{}", sender, method),
+            rewriter::SpecItemType::Precondition,
+            quote_spanned! {item_span=>
+                fn #method_ident(
+                    _self: &std::sync::mpsc::#sender_ident<#item_ident>,
+                    value: #item_ident
+                    #extra_args
+                )
+            },
+            quote_spanned! {item_span=> value.prusti_message_invariant() },
+        ));
+    }
+    for (method, error) in [("recv", "RecvError"), ("try_recv", "TryRecvError")] {
+        let method_ident = syn::Ident::new(method, item_span);
+        let error_ident = syn::Ident::new(error, item_span);
+        handle_result!(add_spec_fn(
+            &format!("std::sync::mpsc::Receiver::{}", method),
+            rewriter::SpecItemType::Postcondition,
+            quote_spanned! {item_span=>
+                fn #method_ident(
+                    _self: &std::sync::mpsc::Receiver<#item_ident>,
+                ) -> Result<#item_ident, std::sync::mpsc::#error_ident>
+            },
+            quote_spanned! {item_span=>
+                match result {
+                    Ok(value) => value.prusti_message_invariant(),
+                    Err(_) => true,
+                }
+            },
+        ));
+    }
+
+    quote_spanned! {item_span=>
+        #item
+
+        impl #item_ident {
+            #invariant
+        }
+
+        #spec_fns
+    }
+}
+
+/// Generate a wrapper around an atomic integer or boolean whose value always
+/// satisfies an invariant, e.g.
+/// `atomic_invariant!(pub struct Counter(AtomicUsize), |value| value <= 10)`.
+/// The wrapper provides `new`, `load`, `store` and `compare_exchange` (and
+/// `fetch_add` for integers), which are trusted and specified to preserve the
+/// invariant.
+///
+/// `load` assumes the invariant, so the atomic must only be reachable through
+/// these methods. The wrapper is therefore defined in a private module
+/// `prusti_atomic_<name>` and re-exported, which keeps its field private even
+/// in the module that invokes the macro, and attributes that derive
+/// constructors, such as `#[derive(Default)]`, are rejected.
+pub fn atomic_invariant(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis: syn::Visibility = input.parse()?;
+        input.parse::<syn::Token![struct]>()?;
+        let name: syn::Ident = input.parse()?;
+        let content;
+        syn::parenthesized!(content in input);
+        let atomic: syn::Ident = content.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        input.parse::<syn::Token![|]>()?;
+        let value: syn::Ident = input.parse()?;
+        input.parse::<syn::Token![|]>()?;
+        let invariant: TokenStream = input.parse()?;
+        Ok((attrs, vis, name, atomic, value, invariant))
+    };
+    let (attrs, vis, name, atomic, value, invariant): (
+        Vec<syn::Attribute>, syn::Visibility, syn::Ident, syn::Ident, syn::Ident, TokenStream,
+    ) = handle_result!(syn::parse::Parser::parse2(parser, tokens));
+    if let Some(attr) = attrs.iter().find(|attr| attr.path.is_ident("derive")) {
+        return syn::Error::new(
+            attr.path.span(),
+            "atomic invariants cannot derive traits, because derived constructors would not \
+            establish the invariant",
+        ).to_compile_error();
+    }
+    if invariant.is_empty() {
+        return syn::Error::new(
+            value.span(),
+            "expected the invariant after the closure parameter",
+        ).to_compile_error();
+    }
+    let (value_ty, is_integer) = match atomic.to_string().as_str() {
+        "AtomicBool" => (quote_spanned! {atomic.span()=> bool }, false),
+        "AtomicUsize" => (quote_spanned! {atomic.span()=> usize }, true),
+        "AtomicIsize" => (quote_spanned! {atomic.span()=> isize }, true),
+        "AtomicU32" => (quote_spanned! {atomic.span()=> u32 }, true),
+        "AtomicI32" => (quote_spanned! {atomic.span()=> i32 }, true),
+        "AtomicU64" => (quote_spanned! {atomic.span()=> u64 }, true),
+        "AtomicI64" => (quote_spanned! {atomic.span()=> i64 }, true),
+        _ => return syn::Error::new(
+            atomic.span(),
+            "expected one of `AtomicBool`, `AtomicUsize`, `AtomicIsize`, `AtomicU32`, \
+            `AtomicI32`, `AtomicU64` and `AtomicI64`",
+        ).to_compile_error(),
+    };
+    let span = name.span();
+    let module = format_ident!("prusti_atomic_{}", name);
+    let fetch_add = if is_integer {
+        quote_spanned! {span=>
+            #[trusted]
+            #[requires(forall(|value: #value_ty| Self::prusti_atomic_invariant(value) ==>
+                <#value_ty>::MIN <= value + val && value + val <= <#value_ty>::MAX &&
+                Self::prusti_atomic_invariant(value + val)))]
+            #[ensures(Self::prusti_atomic_invariant(result))]
+            pub fn fetch_add(&self, val: #value_ty, order: Ordering) -> #value_ty {
+                self.0.fetch_add(val, order)
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    quote_spanned! {span=>
+        #[allow(non_snake_case)]
+        mod #module {
+            #[allow(unused_imports)]
+            use super::*;
+            #[allow(unused_imports)]
+            use ::prusti_contracts::*;
+            use ::core::sync::atomic::Ordering;
+
+            #(#attrs)*
+            pub struct #name(::core::sync::atomic::#atomic);
+
+            impl #name {
+                predicate! {
+                    fn prusti_atomic_invariant(#value: #value_ty) -> bool {
+                        #invariant
+                    }
+                }
+
+                #[trusted]
+                #[requires(Self::prusti_atomic_invariant(value))]
+                pub fn new(value: #value_ty) -> Self {
+                    Self(::core::sync::atomic::#atomic::new(value))
+                }
+
+                #[trusted]
+                #[ensures(Self::prusti_atomic_invariant(result))]
+                pub fn load(&self, order: Ordering) -> #value_ty {
+                    self.0.load(order)
+                }
+
+                #[trusted]
+                #[requires(Self::prusti_atomic_invariant(value))]
+                pub fn store(&self, value: #value_ty, order: Ordering) {
+                    self.0.store(value, order)
+                }
+
+                #[trusted]
+                #[requires(Self::prusti_atomic_invariant(new))]
+                #[ensures(match result {
+                    Ok(previous) => previous == current && Self::prusti_atomic_invariant(previous),
+                    Err(previous) => previous != current && Self::prusti_atomic_invariant(previous),
+                })]
+                pub fn compare_exchange(
+                    &self,
+                    current: #value_ty,
+                    new: #value_ty,
+                    success: Ordering,
+                    failure: Ordering,
+                ) -> Result<#value_ty, #value_ty> {
+                    self.0.compare_exchange(current, new, success, failure)
+                }
+
+                #fetch_add
+            }
+        }
+
+        #vis use self::#module::#name;
+    }
+}
+
+/// Generate the refinement of a struct, e.g. `#[refined(|x| x.0 > 0)]` on a
+/// newtype wrapper. The refinement is a spec function of a value of the
+/// struct, which Prusti adds to the type invariants: it is assumed for the
+/// arguments of every function and checked for its result.
+///
+/// Prusti finds the spec function by the `prusti::refinement_spec` attribute,
+/// which names the refined struct.
+pub fn refined(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    let item: syn::ItemStruct = handle_result!(syn::parse2(tokens));
+    if !item.generics.params.is_empty() {
+        return syn::Error::new(
+            item.generics.span(),
+            "refinements are not supported on generic types",
+        ).to_compile_error();
+    }
+    let closure: syn::ExprClosure = handle_result!(syn::parse2(attr));
+    let param = match closure.inputs.iter().collect::<Vec<_>>().as_slice() {
+        [syn::Pat::Ident(param)] => param.ident.clone(),
+        [syn::Pat::Type(syn::PatType { pat: box syn::Pat::Ident(param), .. })] => param.ident.clone(),
+        _ => return syn::Error::new(
+            closure.inputs.span(),
+            "a refinement must be a closure with a single named parameter",
+        ).to_compile_error(),
+    };
+    let item_span = item.span();
+    let item_ident = &item.ident;
+    let body = &closure.body;
+
+    let mut rewriter = rewriter::AstRewriter::new();
+    let spec_fn = handle_result!(generate_invariant_spec_fn(
+        &mut rewriter,
+        "refinement_spec",
+        item_ident.to_string(),
+        rewriter::SpecItemType::Precondition,
+        quote_spanned! {item_span=> fn refinement(#param: #item_ident) -> bool },
+        body.to_token_stream(),
+        item_span,
+    ));
+
+    quote_spanned! {item_span=>
+        #item
+
+        #spec_fn
+    }
+}
+
+/// Generate an invariant of a struct declared on one of its inherent `impl`
+/// blocks, e.g. `#[invariant(self.0 > 0)]`. Like a refinement, the invariant
+/// is a spec function of a value of the struct, which Prusti adds to the type
+/// invariants. The spec function is a method of the struct, so the
+/// invariants of a struct can be declared on several `impl` blocks, also in
+/// other modules than the struct.
+///
+/// Prusti finds the spec function by the `prusti::refinement_spec` attribute,
+/// which names the struct.
+pub fn invariant(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    let item: syn::ItemImpl = handle_result!(syn::parse2(tokens));
+    if let Some((_, trait_path, _)) = &item.trait_ {
+        return syn::Error::new(
+            trait_path.span(),
+            "invariants can only be declared on inherent impl blocks",
+        ).to_compile_error();
+    }
+    if !item.generics.params.is_empty() {
+        return syn::Error::new(
+            item.generics.span(),
+            "invariants are not supported on generic types",
+        ).to_compile_error();
+    }
+    let item_span = item.span();
+    let self_ty = &item.self_ty;
+    let expr = handle_result!(specifications::preparser::parse_prusti(attr));
+
+    let mut rewriter = rewriter::AstRewriter::new();
+    let spec_fn = handle_result!(generate_invariant_spec_fn(
+        &mut rewriter,
+        "refinement_spec",
+        self_ty.to_token_stream().to_string(),
+        rewriter::SpecItemType::Precondition,
+        quote_spanned! {item_span=> fn invariant(self) -> bool },
+        expr,
+        item_span,
+    ));
+
+    quote_spanned! {item_span=>
+        #item
+
+        impl #self_ty {
+            #spec_fn
+        }
+    }
+}
+
+/// Declare a constant as symbolic, e.g. `#[symbolic(BUF_SIZE > 0)]` on
+/// `const BUF_SIZE: usize = 1024;`. Prusti does not use the value of a
+/// symbolic constant but verifies the crate for all values that satisfy the
+/// constraint, which is a spec function without arguments.
+///
+/// Prusti finds the constant by the `prusti::symbolic` attribute and its
+/// constraint by the `prusti::symbolic_spec` attribute, which names the constant.
+///
+/// The value of the constant is checked against the constraint by the
+/// generated function `prusti_symbolic_check_<name>`, whose postcondition is
+/// the constraint. It is marked with the `prusti::symbolic_check` attribute,
+/// so that Prusti uses the values of the constants given in the code.
+pub fn symbolic(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    let mut item: syn::ItemConst = handle_result!(syn::parse2(tokens));
+    let item_span = item.span();
+    item.attrs.push(parse_quote_spanned! {item_span=>
+        #[prusti::symbolic]
+    });
+    if attr.is_empty() {
+        return item.into_token_stream();
+    }
+    let expr = handle_result!(specifications::preparser::parse_prusti(attr.clone()));
+
+    let mut rewriter = rewriter::AstRewriter::new();
+    let spec_fn = handle_result!(generate_invariant_spec_fn(
+        &mut rewriter,
+        "symbolic_spec",
+        item.ident.to_string(),
+        rewriter::SpecItemType::Precondition,
+        quote_spanned! {item_span=> fn symbolic() -> bool },
+        expr,
+        item_span,
+    ));
+
+    let check_name = format_ident!("prusti_symbolic_check_{}", item.ident);
+    let mut check_fn: syn::ItemFn = parse_quote_spanned! {item_span=>
+        #[allow(dead_code, non_snake_case)]
+        #[prusti::symbolic_check]
+        fn #check_name() {}
+    };
+    let check_item = untyped::AnyFnItem::Fn(check_fn.clone());
+    let (check_spec_items, check_attrs) = handle_result!(generate_for_ensures(attr, &check_item));
+    check_fn.attrs.extend(check_attrs);
+
+    quote_spanned! {item_span=>
+        #item
+
+        #spec_fn
+
+        #(#check_spec_items)*
+
+        #check_fn
+    }
+}
+
+/// Generate a spec function of a lock, message or refinement invariant for the function
+/// `target` with the given signature, marked with the attribute `spec_attr`.
+fn generate_invariant_spec_fn(
+    rewriter: &mut rewriter::AstRewriter,
+    spec_attr: &str,
+    target: String,
+    spec_type: rewriter::SpecItemType,
+    signature: TokenStream,
+    expr: TokenStream,
+    span: Span,
+) -> syn::Result<TokenStream> {
+    let spec_id = rewriter.generate_spec_id();
+    let target_fn: untyped::AnyFnItem = parse_quote_spanned! {span=>
+        #signature {
+            unimplemented!()
+        }
+    };
+    let mut spec_fn = rewriter.generate_spec_item_fn(spec_type, spec_id, expr, &target_fn)?;
+    if let syn::Item::Fn(spec_fn) = &mut spec_fn {
+        let spec_attr = syn::Ident::new(spec_attr, span);
+        spec_fn.attrs.push(parse_quote_spanned! {span=>
+            #[prusti::#spec_attr = #target]
+        });
+    }
+    Ok(spec_fn.into_token_stream())
+}
+
+/// Generate the states of a struct, which are changed by the methods annotated
+/// with `#[transition(...)]`. For each state `S`, the pure function
+/// `prusti_typestate_S` tells whether the struct is in that state. It has the
+/// visibility of the struct, so that contracts can refer to the states.
+///
+/// The states are either defined by an expression of `self`, as in
+/// `#[typestate(Closed = !self.open, Open = self.open)]`, or abstract, as in
+/// `#[typestate(Closed, Open)]`. Abstract states are a ghost value: a trusted
+/// pure function `prusti_typestate` of the struct without a definition, which
+/// is only known through the transitions.
+pub fn typestate(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    let item: syn::ItemStruct = handle_result!(syn::parse2(tokens));
+    let parser = |input: syn::parse::ParseStream| {
+        let mut states = vec![];
+        while !input.is_empty() {
+            let state: syn::Ident = input.parse()?;
+            let definition = if input.peek(syn::Token![=]) {
+                input.parse::<syn::Token![=]>()?;
+                Some(input.parse::<syn::Expr>()?)
+            } else {
+                None
+            };
+            states.push((state, definition));
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+        Ok(states)
+    };
+    let states: Vec<(syn::Ident, Option<syn::Expr>)> =
+        handle_result!(syn::parse::Parser::parse2(parser, attr.clone()));
+    if states.is_empty() {
+        return syn::Error::new(
+            attr.span(),
+            "a typestate needs at least one state",
+        ).to_compile_error();
+    }
+    let is_abstract = states.iter().all(|(_, definition)| definition.is_none());
+    if !is_abstract && states.iter().any(|(_, definition)| definition.is_none()) {
+        return syn::Error::new(
+            attr.span(),
+            "either all states or none of them must be defined by an expression",
+        ).to_compile_error();
+    }
+    let item_span = item.span();
+    let item_ident = &item.ident;
+    let item_vis = &item.vis;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+    let state_fns = states.iter().enumerate().map(|(index, (state, definition))| {
+        let state_fn = syn::Ident::new(&format!("prusti_typestate_{}", state), state.span());
+        let body = match definition {
+            Some(definition) => definition.to_token_stream(),
+            None => {
+                let index = index as u32;
+                quote_spanned! {state.span()=> self.prusti_typestate() == #index }
+            }
+        };
+        quote_spanned! {state.span()=>
+            #[allow(non_snake_case, dead_code)]
+            #[prusti::pure]
+            #item_vis fn #state_fn(&self) -> bool {
+                #body
+            }
+        }
+    });
+    let abstract_state_fn = if is_abstract {
+        quote_spanned! {item_span=>
+            #[allow(dead_code)]
+            #[prusti::pure]
+            #[prusti::trusted]
+            fn prusti_typestate(&self) -> u32 {
+                unimplemented!("typestate")
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    quote_spanned! {item_span=>
+        #item
+
+        impl #impl_generics #item_ident #ty_generics #where_clause {
+            #abstract_state_fn
+
+            #(#state_fns)*
+        }
+    }
+}
+
+/// Generate a Viper domain from a module of function stubs and axioms:
+///
+/// ```ignore
+/// #[domain]
+/// mod peano {
+///     pub fn zero() -> u32;
+///     pub fn succ(n: u32) -> u32;
+///     axiom! { succ_positive: forall(|n: u32| succ(n) > zero()) }
+/// }
+/// ```
+///
+/// Each stub becomes a trusted pure function marked with the attribute
+/// `prusti::domain_function`, which Prusti encodes as an uninterpreted
+/// function of the domain. Each axiom becomes a spec function marked with the
+/// attribute `prusti::domain_axiom_spec`. Both attributes name the domain.
+pub fn domain(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            attr.span(),
+            "the `domain` attribute does not take arguments",
+        ).to_compile_error();
+    }
+    let mut item: syn::ItemMod = handle_result!(syn::parse2(tokens));
+    let item_span = item.span();
+    let domain_name = item.ident.to_string();
+    let items = match item.content.take() {
+        Some((_, items)) => items,
+        None => return syn::Error::new(
+            item_span,
+            "a domain must be a module with a body",
+        ).to_compile_error(),
+    };
+
+    let mut rewriter = rewriter::AstRewriter::new();
+    let mut domain_items: Vec<syn::Item> = vec![];
+    for domain_item in items {
+        match domain_item {
+            syn::Item::Use(_) => domain_items.push(domain_item),
+            syn::Item::Verbatim(tokens) => {
+                // A function stub, with a `;` after the signature instead of
+                // the body.
+                let stub: syn::ForeignItemFn = handle_result!(syn::parse2(tokens.clone())
+                    .map_err(|_| syn::Error::new(tokens.span(), "invalid function signature")));
+                if !stub.sig.generics.params.is_empty() {
+                    return syn::Error::new(
+                        stub.sig.generics.span(),
+                        "domain functions cannot be generic",
+                    ).to_compile_error();
+                }
+                if let Some(receiver @ syn::FnArg::Receiver(_)) = stub.sig.inputs.first() {
+                    return syn::Error::new(
+                        receiver.span(),
+                        "domain functions cannot take `self`",
+                    ).to_compile_error();
+                }
+                let stub_span = stub.span();
+                let syn::ForeignItemFn { attrs, vis, sig, .. } = stub;
+                domain_items.push(parse_quote_spanned! {stub_span=>
+                    #(#attrs)*
+                    #[allow(unused_variables, dead_code)]
+                    #[prusti::pure]
+                    #[prusti::trusted]
+                    #[prusti::domain_function = #domain_name]
+                    #vis #sig {
+                        unimplemented!("domain function")
+                    }
+                });
+            }
+            syn::Item::Macro(syn::ItemMacro { mac, .. }) if mac.path.is_ident("axiom") => {
+                let parser = |input: syn::parse::ParseStream| {
+                    let name: syn::Ident = input.parse()?;
+                    input.parse::<syn::Token![:]>()?;
+                    let assertion: TokenStream = input.parse()?;
+                    Ok((name, assertion))
+                };
+                let (name, assertion) = handle_result!(
+                    syn::parse::Parser::parse2(parser, mac.tokens.clone())
+                );
+                domain_items.push(syn::Item::Verbatim(handle_result!(generate_invariant_spec_fn(
+                    &mut rewriter,
+                    "domain_axiom_spec",
+                    domain_name.clone(),
+                    rewriter::SpecItemType::Axiom,
+                    quote_spanned! {name.span()=> fn #name() -> bool },
+                    handle_result!(specifications::preparser::parse_prusti(assertion)),
+                    mac.span(),
+                ))));
+            }
+            _ => return syn::Error::new(
+                domain_item.span(),
+                "a domain can only contain function stubs and `axiom!` items",
+            ).to_compile_error(),
+        }
+    }
+
+    item.content = Some((Default::default(), domain_items));
+    item.into_token_stream()
+}
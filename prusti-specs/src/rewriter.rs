@@ -10,6 +10,7 @@ use quote::{quote_spanned, format_ident};
 use crate::specifications::preparser::{
     parse_prusti,
     parse_prusti_pledge, parse_prusti_assert_pledge,
+    parse_prusti_calc,
 };
 
 pub(crate) struct AstRewriter {
@@ -22,6 +23,7 @@ pub enum SpecItemType {
     Postcondition,
     Pledge,
     Predicate,
+    Axiom,
 }
 
 impl std::fmt::Display for SpecItemType {
@@ -31,10 +33,47 @@ impl std::fmt::Display for SpecItemType {
             SpecItemType::Postcondition => write!(f, "post"),
             SpecItemType::Pledge => write!(f, "pledge"),
             SpecItemType::Predicate => write!(f, "pred"),
+            SpecItemType::Axiom => write!(f, "axiom"),
         }
     }
 }
 
+/// Whether the receiver, an argument or the return type of `sig` is or
+/// contains `Self` by value, which requires `Self` to be sized.
+fn signature_uses_self_by_value(sig: &syn::Signature) -> bool {
+    let inputs = sig.inputs.iter().any(|input| match input {
+        syn::FnArg::Receiver(receiver) => receiver.reference.is_none(),
+        syn::FnArg::Typed(pat_type) => type_uses_self_by_value(&pat_type.ty),
+    });
+    let output = match &sig.output {
+        syn::ReturnType::Default => false,
+        syn::ReturnType::Type(_, ty) => type_uses_self_by_value(ty),
+    };
+    inputs || output
+}
+
+fn type_uses_self_by_value(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(type_path) => {
+            let path = &type_path.path;
+            if type_path.qself.is_none() && path.is_ident("Self") {
+                return true;
+            }
+            path.segments.iter().any(|segment| match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) => args.args.iter().any(|arg| {
+                    matches!(arg, syn::GenericArgument::Type(ty) if type_uses_self_by_value(ty))
+                }),
+                _ => false,
+            })
+        }
+        syn::Type::Tuple(tuple) => tuple.elems.iter().any(type_uses_self_by_value),
+        syn::Type::Array(array) => type_uses_self_by_value(&array.elem),
+        syn::Type::Paren(paren) => type_uses_self_by_value(&paren.elem),
+        syn::Type::Group(group) => type_uses_self_by_value(&group.elem),
+        _ => false,
+    }
+}
+
 impl AstRewriter {
     pub(crate) fn new() -> Self {
         Self {
@@ -125,6 +164,19 @@ impl AstRewriter {
         spec_item.sig.generics = item.sig().generics.clone();
         spec_item.sig.inputs = item.sig().inputs.clone();
 
+        // The spec items of a required trait method are provided methods of
+        // the trait, whose arguments have to be sized. `Self` is only known
+        // to be sized in the implementations of the trait, where the spec
+        // items are used with `Self` replaced by the implementing type.
+        if let untyped::AnyFnItem::TraitMethod(_) = item {
+            if signature_uses_self_by_value(item.sig()) {
+                spec_item.sig.generics
+                    .make_where_clause()
+                    .predicates
+                    .push(parse_quote_spanned! {item_span=> Self: Sized });
+            }
+        }
+
         // Added by emlaufer
         // Look for with_ghost_var macro so we can add it to the generated contracts
         // TODO: is it even possible to have two ghost args with the current macro?
@@ -226,6 +278,80 @@ impl AstRewriter {
         })
     }
 
+    /// Parse a yield invariant of a generator into a Rust expression
+    pub fn process_yield_invariant(
+        &mut self,
+        spec_id: SpecificationId,
+        tokens: TokenStream,
+    ) -> syn::Result<TokenStream> {
+        let expr = parse_prusti(tokens)?;
+        let spec_id_str = spec_id.to_string();
+        Ok(quote_spanned! {expr.span()=>
+            {
+                #[prusti::spec_only]
+                #[prusti::yield_invariant_spec]
+                #[prusti::spec_id = #spec_id_str]
+                || -> bool {
+                    #expr
+                };
+            }
+        })
+    }
+
+    /// Parse a resume precondition of a generator into a Rust expression
+    pub fn process_resume_requires(
+        &mut self,
+        spec_id: SpecificationId,
+        tokens: TokenStream,
+    ) -> syn::Result<TokenStream> {
+        let expr = parse_prusti(tokens)?;
+        let spec_id_str = spec_id.to_string();
+        Ok(quote_spanned! {expr.span()=>
+            {
+                #[prusti::spec_only]
+                #[prusti::resume_requires_spec]
+                #[prusti::spec_id = #spec_id_str]
+                || -> bool {
+                    #expr
+                };
+            }
+        })
+    }
+
+    /// Parse a `prusti_calc!` proof into a Rust block, in which each line is
+    /// a proof step and the hints are kept in between.
+    pub fn process_calc(&mut self, tokens: TokenStream) -> syn::Result<TokenStream> {
+        let (lines, hints) = parse_prusti_calc(tokens)?;
+        let mut hints = hints.into_iter();
+        let mut result = TokenStream::new();
+        for line in lines {
+            let spec_id_str = self.generate_spec_id().to_string();
+            result.extend(quote_spanned! {line.span()=>
+                #[allow(unused_must_use, unused_variables)]
+                if false {
+                    #[prusti::spec_only]
+                    #[prusti::proof_step_spec]
+                    #[prusti::spec_id = #spec_id_str]
+                    || -> bool {
+                        #line
+                    };
+                }
+            });
+            if let Some(hint) = hints.next() {
+                result.extend(quote_spanned! {hint.span()=>
+                    {
+                        #hint
+                    }
+                });
+            }
+        }
+        Ok(quote_spanned! {Span::call_site()=>
+            {
+                #result
+            }
+        })
+    }
+
     /// Parse a closure with specifications into a Rust expression
     /// TODO: arguments, result (types are typically not known yet after parsing...)
     pub fn process_closure(
@@ -240,15 +366,17 @@ impl AstRewriter {
         {
             let spec_id_str = id.to_string();
             let name = format_ident!("prusti_{}_closure_{}", if is_post { "post" } else { "pre" }, spec_id_str);
-            let callsite_span = Span::call_site();
+            // errors in the generated item are reported on the assertion
+            // rather than on the whole `closure!` invocation
+            let assertion_span = assertion.span();
             let result = if is_post && !inputs.empty_or_trailing() {
-                quote_spanned! {callsite_span=> , result: #output }
+                quote_spanned! {assertion_span=> , result: #output }
             } else if is_post {
-                quote_spanned! {callsite_span=> result: #output }
+                quote_spanned! {assertion_span=> result: #output }
             } else {
                 TokenStream::new()
             };
-            quote_spanned! {callsite_span=>
+            quote_spanned! {assertion_span=>
                 #[prusti::spec_only]
                 #[prusti::spec_id = #spec_id_str]
                 fn #name(#inputs #result) {
@@ -278,8 +406,7 @@ impl AstRewriter {
     ) -> syn::Result<syn::Expr> {
         let expr = parse_prusti(tokens)?;
         let spec_id_str = spec_id.to_string();
-        let callsite_span = Span::call_site();
-        Ok(parse_quote_spanned! {callsite_span=>
+        Ok(parse_quote_spanned! {expr.span()=>
             #[allow(unused_must_use, unused_variables)]
             {
                 #[prusti::spec_only]
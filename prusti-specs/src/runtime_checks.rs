@@ -0,0 +1,274 @@
+//! Contracts that are checked at runtime instead of being verified.
+//!
+//! `#[insert_runtime_check]` marks the `#[requires(...)]` or `#[ensures(...)]`
+//! attribute that follows it, and `#[insert_runtime_check(all)]` all the
+//! contracts that follow it. A check of each marked contract is inserted in
+//! the body of the function, both when the function is compiled normally and
+//! when it is verified. A failing check calls
+//! `prusti_contracts::runtime_check_failed`, which panics and after which
+//! Prusti does not continue the verification, so the contract is assumed
+//! after its check. Consequently, a precondition checked at runtime is not
+//! part of the contract that the callers must establish.
+
+use crate::{
+    specifications::{preparser::parse_prusti, untyped},
+    SpecAttributeKind,
+};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{quote_spanned, ToTokens};
+use syn::{spanned::Spanned, visit_mut::VisitMut};
+
+/// The functions of specifications that cannot be evaluated at runtime.
+const UNCHECKABLE_FUNCTIONS: &[&str] = &[
+    "forall",
+    "exists",
+    "snapshot_equality",
+    "specification_entailment",
+    "before_expiry",
+    "on_entry",
+];
+
+type PrustiAttributes = Vec<(SpecAttributeKind, TokenStream)>;
+
+/// Remove the `#[insert_runtime_check]` attributes, returning the remaining
+/// attributes, from which the preconditions checked at runtime are removed
+/// too, and the contracts to check at runtime.
+pub fn select_runtime_checks(
+    prusti_attributes: PrustiAttributes,
+) -> syn::Result<(PrustiAttributes, PrustiAttributes)> {
+    let mut verified = vec![];
+    let mut checked = vec![];
+    let mut check_next = false;
+    let mut check_all = false;
+    for (attr_kind, tokens) in prusti_attributes {
+        let is_contract = matches!(
+            attr_kind,
+            SpecAttributeKind::Requires | SpecAttributeKind::Ensures
+        );
+        match attr_kind {
+            SpecAttributeKind::InsertRuntimeCheck => {
+                if check_next {
+                    return Err(syn::Error::new(
+                        tokens.span(),
+                        "`#[insert_runtime_check]` must be followed by a `#[requires(...)]` \
+                        or `#[ensures(...)]` attribute",
+                    ));
+                }
+                if parse_check_all(&tokens)? {
+                    if verified.iter().any(|(kind, _)| {
+                        matches!(
+                            kind,
+                            SpecAttributeKind::Requires | SpecAttributeKind::Ensures
+                        )
+                    }) {
+                        return Err(syn::Error::new(
+                            tokens.span(),
+                            "`#[insert_runtime_check(all)]` must precede all the contracts of the function"
+                        ));
+                    }
+                    check_all = true;
+                } else {
+                    check_next = true;
+                }
+            }
+            _ if is_contract && (check_next || check_all) => {
+                check_next = false;
+                checked.push((attr_kind, tokens.clone()));
+                if attr_kind == SpecAttributeKind::Ensures {
+                    verified.push((attr_kind, tokens));
+                }
+            }
+            _ if check_next => {
+                return Err(syn::Error::new(
+                    tokens.span(),
+                    "`#[insert_runtime_check]` must be followed by a `#[requires(...)]` \
+                    or `#[ensures(...)]` attribute",
+                ));
+            }
+            _ => verified.push((attr_kind, tokens)),
+        }
+    }
+    if check_next {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`#[insert_runtime_check]` must be followed by a `#[requires(...)]` \
+            or `#[ensures(...)]` attribute",
+        ));
+    }
+    if !checked.is_empty()
+        && verified
+            .iter()
+            .any(|(kind, _)| matches!(kind, SpecAttributeKind::Pure | SpecAttributeKind::Lemma))
+    {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "contracts of pure functions and lemmas cannot be checked at runtime",
+        ));
+    }
+    Ok((verified, checked))
+}
+
+/// Whether the argument of `#[insert_runtime_check]` is `all`.
+fn parse_check_all(tokens: &TokenStream) -> syn::Result<bool> {
+    let mut iter = tokens.clone().into_iter();
+    match (iter.next(), iter.next()) {
+        (None, _) => Ok(false),
+        (Some(TokenTree::Ident(ident)), None) if ident == "all" => Ok(true),
+        _ => Err(syn::Error::new(
+            tokens.span(),
+            "expected no argument or `all` as the argument of `#[insert_runtime_check]`",
+        )),
+    }
+}
+
+/// Insert the checks of `checked` in the body of `item`: the preconditions at
+/// its start and the postconditions at its end.
+pub fn insert_runtime_checks(
+    item: &mut untyped::AnyFnItem,
+    checked: PrustiAttributes,
+) -> syn::Result<()> {
+    let item_span = item.span();
+    let output_type = match &item.sig().output {
+        syn::ReturnType::Type(_, ty) if !matches!(**ty, syn::Type::ImplTrait(_)) => {
+            Some(ty.to_token_stream())
+        }
+        _ => None,
+    };
+    let mut old_values = OldValueHoister::default();
+    let mut preconditions = vec![];
+    let mut postconditions = vec![];
+    for (attr_kind, tokens) in checked {
+        let kind = if attr_kind == SpecAttributeKind::Requires {
+            "precondition"
+        } else {
+            "postcondition"
+        };
+        let message = format!("runtime check of the {} `{}` failed", kind, tokens);
+        let mut condition: syn::Expr = syn::parse2(parse_prusti(tokens.clone())?)?;
+        if attr_kind == SpecAttributeKind::Ensures {
+            old_values.visit_expr_mut(&mut condition);
+        }
+        let mut checker = UncheckableFinder::default();
+        checker.visit_expr_mut(&mut condition);
+        if let Some(function) = checker.function {
+            return Err(syn::Error::new(
+                tokens.span(),
+                format!("`{}` cannot be checked at runtime", function),
+            ));
+        }
+        let check: syn::Stmt = parse_quote_spanned! {tokens.span()=>
+            if !(#condition) {
+                ::prusti_contracts::runtime_check_failed(#message);
+            }
+        };
+        if attr_kind == SpecAttributeKind::Requires {
+            preconditions.push(check);
+        } else {
+            postconditions.push(check);
+        }
+    }
+
+    let block = item.block_mut().ok_or_else(|| {
+        syn::Error::new(
+            item_span,
+            "contracts can only be checked at runtime in functions with a body",
+        )
+    })?;
+    if postconditions.is_empty() {
+        block.stmts.splice(0..0, preconditions);
+        return Ok(());
+    }
+    let mut early_exit = EarlyExitFinder::default();
+    early_exit.visit_block_mut(block);
+    if let Some(span) = early_exit.span {
+        return Err(syn::Error::new(
+            span,
+            "postconditions cannot be checked at runtime in functions that use `return` or `?`",
+        ));
+    }
+    let body = block.clone();
+    let result_type = output_type.map(|ty| quote_spanned! {item_span=> : #ty });
+    let old_names = old_values.names;
+    let old_exprs = old_values.exprs;
+    *block = parse_quote_spanned! {item_span=>
+        {
+            #(#preconditions)*
+            #(let #old_names = #old_exprs;)*
+            #[allow(unused_variables)]
+            let result #result_type = #body;
+            #(#postconditions)*
+            result
+        }
+    };
+    Ok(())
+}
+
+/// Replaces the `old(...)` expressions of a postcondition by variables that
+/// are initialized at the start of the function.
+#[derive(Default)]
+struct OldValueHoister {
+    names: Vec<syn::Ident>,
+    exprs: Vec<syn::Expr>,
+}
+
+impl VisitMut for OldValueHoister {
+    fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+        if let syn::Expr::Call(call) = expr {
+            if is_call_of(call, "old") && call.args.len() == 1 {
+                let name =
+                    syn::Ident::new(&format!("__prusti_old_{}", self.names.len()), call.span());
+                self.exprs.push(call.args[0].clone());
+                *expr = parse_quote_spanned! {name.span()=> #name };
+                self.names.push(name);
+                return;
+            }
+        }
+        syn::visit_mut::visit_expr_mut(self, expr);
+    }
+}
+
+/// Finds the first function of `UNCHECKABLE_FUNCTIONS` called in an expression.
+#[derive(Default)]
+struct UncheckableFinder {
+    function: Option<&'static str>,
+}
+
+impl VisitMut for UncheckableFinder {
+    fn visit_expr_call_mut(&mut self, call: &mut syn::ExprCall) {
+        if let Some(function) = UNCHECKABLE_FUNCTIONS
+            .iter()
+            .find(|function| is_call_of(call, function))
+        {
+            self.function.get_or_insert(function);
+        }
+        syn::visit_mut::visit_expr_call_mut(self, call);
+    }
+}
+
+fn is_call_of(call: &syn::ExprCall, function: &str) -> bool {
+    matches!(&*call.func, syn::Expr::Path(path) if path.path.is_ident(function))
+}
+
+/// Finds the first `return` or `?` that leaves the function, ignoring those
+/// in closures and nested items.
+#[derive(Default)]
+struct EarlyExitFinder {
+    span: Option<Span>,
+}
+
+impl VisitMut for EarlyExitFinder {
+    fn visit_expr_return_mut(&mut self, expr: &mut syn::ExprReturn) {
+        self.span.get_or_insert(expr.span());
+    }
+
+    fn visit_expr_try_mut(&mut self, expr: &mut syn::ExprTry) {
+        self.span.get_or_insert(expr.question_token.span());
+        syn::visit_mut::visit_expr_try_mut(self, expr);
+    }
+
+    fn visit_expr_closure_mut(&mut self, _: &mut syn::ExprClosure) {}
+
+    fn visit_expr_async_mut(&mut self, _: &mut syn::ExprAsync) {}
+
+    fn visit_item_mut(&mut self, _: &mut syn::Item) {}
+}
@@ -10,6 +10,19 @@ pub enum SpecAttributeKind {
     Pure,
     Trusted,
     Predicate,
+    Lemma,
+    Law,
+    Bounded,
+    NonlinearArithmetic,
+    AllowOverflow,
+    BitwiseArithmetic,
+    Transition,
+    Opaque,
+    InlineSpec,
+    NeverInlineSpec,
+    NoAutoFrame,
+    ExplicitExpiry,
+    InsertRuntimeCheck,
 }
 
 impl TryFrom<String> for SpecAttributeKind {
@@ -24,6 +37,19 @@ impl TryFrom<String> for SpecAttributeKind {
             "pure" => Ok(SpecAttributeKind::Pure),
             "trusted" => Ok(SpecAttributeKind::Trusted),
             "predicate" => Ok(SpecAttributeKind::Predicate),
+            "lemma" => Ok(SpecAttributeKind::Lemma),
+            "law" => Ok(SpecAttributeKind::Law),
+            "bounded" => Ok(SpecAttributeKind::Bounded),
+            "nonlinear_arithmetic" => Ok(SpecAttributeKind::NonlinearArithmetic),
+            "allow_overflow" => Ok(SpecAttributeKind::AllowOverflow),
+            "bitwise_arithmetic" => Ok(SpecAttributeKind::BitwiseArithmetic),
+            "transition" => Ok(SpecAttributeKind::Transition),
+            "opaque" => Ok(SpecAttributeKind::Opaque),
+            "inline_spec" => Ok(SpecAttributeKind::InlineSpec),
+            "never_inline_spec" => Ok(SpecAttributeKind::NeverInlineSpec),
+            "no_auto_frame" => Ok(SpecAttributeKind::NoAutoFrame),
+            "explicit_expiry" => Ok(SpecAttributeKind::ExplicitExpiry),
+            "insert_runtime_check" => Ok(SpecAttributeKind::InsertRuntimeCheck),
             _ => Err(name),
         }
     }
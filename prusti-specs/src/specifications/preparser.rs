@@ -50,6 +50,41 @@ pub fn parse_prusti_assert_pledge(tokens: TokenStream) -> syn::Result<(TokenStre
     Ok((lhs, rhs))
 }
 
+/// Splits the body of `prusti_calc!` into its lines, which are separated by
+/// `==> { hints }`. Returns the parsed lines and the (Rust) hints between them.
+pub fn parse_prusti_calc(tokens: TokenStream) -> syn::Result<(Vec<TokenStream>, Vec<TokenStream>)> {
+    let source = tokens.into_iter().collect::<Vec<_>>();
+    let mut lines = vec![];
+    let mut hints = vec![];
+    let mut line = TokenStream::new();
+    let mut pos = 0;
+    while pos < source.len() {
+        if let (
+            TokenTree::Punct(p1),
+            Some(TokenTree::Punct(p2)),
+            Some(TokenTree::Punct(p3)),
+            Some(TokenTree::Group(group)),
+        ) = (&source[pos], source.get(pos + 1), source.get(pos + 2), source.get(pos + 3)) {
+            if operator3("==>", p1, p2, p3, false) && group.delimiter() == Delimiter::Brace {
+                if line.is_empty() {
+                    return error(p1.span(), "expected a line before `==>`");
+                }
+                lines.push(parse_prusti(std::mem::take(&mut line))?);
+                hints.push(group.stream());
+                pos += 4;
+                continue;
+            }
+        }
+        line.extend(source[pos].to_token_stream());
+        pos += 1;
+    }
+    if line.is_empty() {
+        return error(Span::call_site(), "expected a line at the end of `prusti_calc!`");
+    }
+    lines.push(parse_prusti(line)?);
+    Ok((lines, hints))
+}
+
 /*
 Preparsing consists of two stages:
 
@@ -846,3 +881,18 @@ fn test_preparser() {
         "forall (((# [prusti :: spec_only] | x : i32 | (c) ,) , (# [prusti :: spec_only] | x : i32 | (d) , # [prusti :: spec_only] | x : i32 | (e) ,) ,) , # [prusti :: spec_only] | x : i32 | -> bool { (((! (a) || (b))) : bool) })",
     );
 }
+
+#[test]
+fn test_calc_preparser() {
+    let (lines, hints) = parse_prusti_calc(quote! { a == b ==> { f(b); } b == c ==> {} a ==> c }).unwrap();
+    assert_eq!(
+        lines.iter().map(|line| line.to_string()).collect::<Vec<_>>(),
+        vec!["a == b", "b == c", "(! (a) || (c))"],
+    );
+    assert_eq!(
+        hints.iter().map(|hint| hint.to_string()).collect::<Vec<_>>(),
+        vec!["f (b) ;", ""],
+    );
+    assert!(parse_prusti_calc(quote! { a == b ==> {} }).is_err());
+    assert!(parse_prusti_calc(quote! { ==> {} a == b }).is_err());
+}
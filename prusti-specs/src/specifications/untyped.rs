@@ -63,6 +63,14 @@ impl AnyFnItem {
             AnyFnItem::TraitMethod(item) => item.default.as_ref(),
         }
     }
+
+    pub fn block_mut(&mut self) -> Option<&mut syn::Block> {
+        match self {
+            AnyFnItem::Fn(item) => Some(&mut item.block),
+            AnyFnItem::ImplMethod(item) => Some(&mut item.block),
+            AnyFnItem::TraitMethod(item) => item.default.as_mut(),
+        }
+    }
 }
 
 impl ToTokens for AnyFnItem {
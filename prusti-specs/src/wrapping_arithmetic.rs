@@ -0,0 +1,97 @@
+//! The runtime semantics of `#[allow_overflow]` and `wrapping!`.
+//!
+//! Prusti verifies the additions, subtractions and multiplications of these
+//! functions and blocks as wrapping around on overflow. When the program is
+//! compiled without Prusti, these operations are therefore rewritten to
+//! operations on `core::num::Wrapping`, so that builds with overflow checks do
+//! not panic where Prusti proved that no panic happens. Like in the encoding,
+//! the operations in closures and nested items are not rewritten. The arguments
+//! of a macro invocation are only rewritten if they are a list of expressions.
+
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{punctuated::Punctuated, spanned::Spanned, visit_mut::VisitMut};
+
+/// Rewrite the arithmetic operations of the body of a function annotated with
+/// `#[allow_overflow]`.
+pub fn rewrite_function(tokens: TokenStream) -> syn::Result<TokenStream> {
+    let mut item: syn::ItemFn = syn::parse2(tokens)?;
+    WrappingRewriter.visit_block_mut(&mut item.block);
+    Ok(item.into_token_stream())
+}
+
+/// Rewrite the arithmetic operations of the statements of a `wrapping!` block.
+pub fn rewrite_block(tokens: TokenStream) -> syn::Result<TokenStream> {
+    let mut block: syn::Block = syn::parse2(quote::quote! { { #tokens } })?;
+    WrappingRewriter.visit_block_mut(&mut block);
+    Ok(block.into_token_stream())
+}
+
+struct WrappingRewriter;
+
+impl VisitMut for WrappingRewriter {
+    fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+        syn::visit_mut::visit_expr_mut(self, expr);
+        let span = expr.span();
+        let rewritten = match expr {
+            syn::Expr::Binary(syn::ExprBinary { left, op, right, .. }) => {
+                let op = if let Some(op) = wrapped_operator(op) {
+                    op
+                } else {
+                    return;
+                };
+                parse_quote_spanned! {span=>
+                    (::core::num::Wrapping(#left) #op ::core::num::Wrapping(#right)).0
+                }
+            }
+            // Like for the primitive compound assignments, the right operand
+            // is evaluated before the assigned place.
+            syn::Expr::AssignOp(syn::ExprAssignOp { left, op, right, .. }) => {
+                let op = if let Some(op) = wrapped_operator(op) {
+                    op
+                } else {
+                    return;
+                };
+                parse_quote_spanned! {span=> {
+                    let __prusti_rhs = #right;
+                    let __prusti_place = &mut #left;
+                    *__prusti_place = (
+                        ::core::num::Wrapping(*__prusti_place) #op ::core::num::Wrapping(__prusti_rhs)
+                    ).0;
+                }}
+            }
+            _ => return,
+        };
+        *expr = rewritten;
+    }
+
+    fn visit_macro_mut(&mut self, mac: &mut syn::Macro) {
+        // The arguments of macros such as `assert!` are rewritten when they
+        // are a list of expressions.
+        let parser = Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated;
+        if let Ok(mut args) = mac.parse_body_with(parser) {
+            for arg in args.iter_mut() {
+                self.visit_expr_mut(arg);
+            }
+            mac.tokens = args.into_token_stream();
+        }
+    }
+
+    fn visit_expr_closure_mut(&mut self, _closure: &mut syn::ExprClosure) {}
+
+    fn visit_item_mut(&mut self, _item: &mut syn::Item) {}
+}
+
+/// The operator applied to the `Wrapping` values for an operation that may
+/// overflow.
+fn wrapped_operator(op: &syn::BinOp) -> Option<syn::BinOp> {
+    match op {
+        syn::BinOp::Add(token) => Some(syn::BinOp::Add(*token)),
+        syn::BinOp::Sub(token) => Some(syn::BinOp::Sub(*token)),
+        syn::BinOp::Mul(token) => Some(syn::BinOp::Mul(*token)),
+        syn::BinOp::AddEq(token) => Some(syn::BinOp::Add(syn::token::Add(token.spans[0]))),
+        syn::BinOp::SubEq(token) => Some(syn::BinOp::Sub(syn::token::Sub(token.spans[0]))),
+        syn::BinOp::MulEq(token) => Some(syn::BinOp::Mul(syn::token::Star(token.spans[0]))),
+        _ => None,
+    }
+}
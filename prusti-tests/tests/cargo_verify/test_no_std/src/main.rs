@@ -17,7 +17,6 @@ pub extern fn rust_eh_personality() {}
 
 #[lang = "panic_impl"]
 #[no_mangle]
-#[trusted]
 pub extern fn rust_begin_panic(_info: &core::panic::PanicInfo) -> ! {
     // SAFETY: this program has no open streams or active locks, so this is safe.
     unsafe { libc::abort() }
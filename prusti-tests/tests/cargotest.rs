@@ -227,4 +227,10 @@ fn test_overflow_checks() {
     test_local_project("overflow_checks");
 }
 
+/// Verify the sources of the `prusti-datastructures` crate.
+#[cargo_test]
+fn test_prusti_datastructures() {
+    test_local_project("prusti_datastructures");
+}
+
 // TODO: automatically create a test for each folder in `test/cargo_verify`.
@@ -11,7 +11,12 @@
 
 use compiletest_rs::{common::Mode, run_tests, Config};
 use prusti_server::spawn_server_thread;
-use std::{env, path::PathBuf};
+use serde_json::Value;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 fn find_prusti_rustc_path() -> PathBuf {
     let target_directory = if cfg!(debug_assertions) {
@@ -82,6 +87,9 @@ fn run_prusti_tests(group_name: &str, filter: &Option<String>, rustc_flags: Opti
         config.filters.push(filter.clone());
     }
 
+    // Overwrite the expected output of the UI tests with the actual one
+    config.bless = env::var_os("BLESS").is_some();
+
     // Add compilation flags
     config.target_rustcflags = Some(format!("--edition=2018 {}", rustc_flags.unwrap_or("")));
 
@@ -108,6 +116,237 @@ fn run_prusti_tests(group_name: &str, filter: &Option<String>, rustc_flags: Opti
         config.mode = Mode::CompileFail;
         config.src_base = path;
         run_tests(&config);
+        check_expected_diagnostics(&config);
+    }
+}
+
+/// A diagnostic expected by a `//@ KIND text` annotation, where `KIND` is
+/// `ERROR`, `WARNING`, `NOTE`, `HELP` or `SUGGESTION`. Like the `//~`
+/// annotations of compiletest, `//@^` refers to the previous line and `//@|`
+/// to the line of the previous annotation.
+///
+/// Unlike the `//~` annotations, which must list either all or none of the
+/// notes and help messages of a test, the `//@` annotations only require the
+/// given diagnostics to be reported, so that a test can check a single note.
+/// They match the whole text of multi-line messages, such as counterexamples,
+/// with any sequence of whitespace matching a single space.
+#[derive(Debug)]
+struct ExpectedDiagnostic {
+    line: usize,
+    kind: String,
+    text: String,
+}
+
+const DIAGNOSTIC_KINDS: &[&str] = &["error", "warning", "note", "help", "suggestion"];
+
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn parse_expected_diagnostics(source: &str) -> Result<Vec<ExpectedDiagnostic>, String> {
+    let mut expected = vec![];
+    let mut previous_line = None;
+    for (index, line) in source.lines().enumerate() {
+        let annotation = match line.find("//@") {
+            Some(start) => &line[start + 3..],
+            None => continue,
+        };
+        let (line_number, rest) = if let Some(rest) = annotation.strip_prefix('|') {
+            let line_number = previous_line.ok_or_else(|| {
+                format!("line {}: `//@|` without a previous annotation", index + 1)
+            })?;
+            (line_number, rest)
+        } else {
+            let adjust = annotation.chars().take_while(|&c| c == '^').count();
+            (index + 1 - adjust, &annotation[adjust..])
+        };
+        let rest = rest.trim_start();
+        let kind_end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let kind = rest[..kind_end].to_lowercase();
+        if !DIAGNOSTIC_KINDS.contains(&kind.as_str()) {
+            return Err(format!(
+                "line {}: unknown diagnostic kind {:?}",
+                index + 1,
+                &rest[..kind_end]
+            ));
+        }
+        previous_line = Some(line_number);
+        expected.push(ExpectedDiagnostic {
+            line: line_number,
+            kind,
+            text: normalize_whitespace(rest[kind_end..].trim_start_matches(':')),
+        });
+    }
+    Ok(expected)
+}
+
+/// The span in `file` of a span or of the macro call that expanded to it.
+fn span_in_file<'a>(span: &'a Value, file: &str) -> Option<&'a Value> {
+    if span.is_null() {
+        None
+    } else if span["file_name"].as_str() == Some(file) {
+        Some(span)
+    } else {
+        span_in_file(&span["expansion"]["span"], file)
+    }
+}
+
+/// Collect the `(line, kind, text)` of a JSON diagnostic and of its children.
+/// Children without a span in `file` are reported at the line of their
+/// parent, like in compiletest.
+fn collect_diagnostics(
+    diagnostic: &Value,
+    file: &str,
+    parent_line: Option<usize>,
+    diagnostics: &mut Vec<(usize, String, String)>,
+) {
+    let spans: Vec<&Value> = diagnostic["spans"]
+        .as_array()
+        .map(|spans| {
+            spans
+                .iter()
+                .filter_map(|span| span_in_file(span, file))
+                .collect()
+        })
+        .unwrap_or_default();
+    let line_start = |span: &Value| span["line_start"].as_u64().map(|line| line as usize);
+    let line = spans
+        .iter()
+        .find(|span| span["is_primary"].as_bool() == Some(true))
+        .and_then(|span| line_start(span))
+        .or(parent_line);
+    if let (Some(line), Some(level), Some(message)) = (
+        line,
+        diagnostic["level"].as_str(),
+        diagnostic["message"].as_str(),
+    ) {
+        diagnostics.push((line, level.to_string(), normalize_whitespace(message)));
+    }
+    for span in spans {
+        if let Some(span_line) = line_start(span) {
+            if let Some(replacement) = span["suggested_replacement"].as_str() {
+                diagnostics.push((
+                    span_line,
+                    "suggestion".to_string(),
+                    normalize_whitespace(replacement),
+                ));
+            }
+            if let Some(label) = span["label"].as_str() {
+                diagnostics.push((span_line, "note".to_string(), normalize_whitespace(label)));
+            }
+        }
+    }
+    for child in diagnostic["children"].as_array().into_iter().flatten() {
+        collect_diagnostics(child, file, line, diagnostics);
+    }
+}
+
+/// Check the `//@` annotations of one test, returning the expected
+/// diagnostics that were not reported.
+fn check_test_diagnostics(config: &Config, file: &Path) -> Result<Vec<String>, String> {
+    let source = fs::read_to_string(file).map_err(|err| err.to_string())?;
+    let expected = parse_expected_diagnostics(&source)?;
+    if expected.is_empty() {
+        return Ok(vec![]);
+    }
+    let compile_flags = source
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("// compile-flags:"))
+        .flat_map(str::split_whitespace);
+    let out_dir = env::temp_dir().join("prusti-tests-diagnostics");
+    fs::create_dir_all(&out_dir).map_err(|err| err.to_string())?;
+    let output = Command::new(&config.rustc_path)
+        .args(
+            config
+                .target_rustcflags
+                .iter()
+                .flat_map(|flags| flags.split_whitespace()),
+        )
+        .args(compile_flags)
+        .arg("--error-format=json")
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg(file)
+        .output()
+        .map_err(|err| format!("failed to run {:?}: {}", config.rustc_path, err))?;
+    let file_name = file.to_string_lossy();
+    let mut diagnostics = vec![];
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        if line.starts_with('{') {
+            let diagnostic: Value = serde_json::from_str(line).map_err(|err| err.to_string())?;
+            collect_diagnostics(&diagnostic, &file_name, None, &mut diagnostics);
+        }
+    }
+    Ok(expected
+        .into_iter()
+        .filter(|expected| {
+            !diagnostics.iter().any(|(line, kind, text)| {
+                *line == expected.line && *kind == expected.kind && text.contains(&expected.text)
+            })
+        })
+        .map(|expected| {
+            let reported: Vec<_> = diagnostics
+                .iter()
+                .filter(|(line, _, _)| *line == expected.line)
+                .map(|(_, kind, text)| format!("\n    {}: {}", kind, text))
+                .collect();
+            format!(
+                "{}:{}: expected {} not found: {}\n  reported at this line:{}",
+                file_name,
+                expected.line,
+                expected.kind,
+                expected.text,
+                reported.concat()
+            )
+        })
+        .collect())
+}
+
+fn collect_test_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_test_files(&path, files);
+        } else if path
+            .extension()
+            .map_or(false, |extension| extension == "rs")
+        {
+            files.push(path);
+        }
+    }
+}
+
+/// Check the `//@` annotations of the tests in `config.src_base`, which are
+/// not understood by compiletest.
+fn check_expected_diagnostics(config: &Config) {
+    let mut files = vec![];
+    collect_test_files(&config.src_base, &mut files);
+    let mut failures = vec![];
+    for file in files {
+        let name = file.to_string_lossy();
+        if !config
+            .filters
+            .iter()
+            .all(|filter| name.contains(filter.as_str()))
+        {
+            continue;
+        }
+        match check_test_diagnostics(config, &file) {
+            Ok(not_found) => failures.extend(not_found),
+            Err(error) => failures.push(format!("{}: {}", name, error)),
+        }
+    }
+    if !failures.is_empty() {
+        for failure in &failures {
+            println!("{}", failure);
+        }
+        panic!("{} expected diagnostics not found", failures.len());
     }
 }
 
@@ -150,6 +389,44 @@ fn run_verification_core_proof(group_name: &str, filter: &Option<String>) {
     run_verification_base(group_name, filter);
 }
 
+/// The UI tests of `group_name` that have no expected output yet, which is
+/// only generated when the tests are run with `BLESS`.
+fn find_unblessed_tests(group_name: &str) -> Vec<PathBuf> {
+    let path: PathBuf = ["tests", group_name, "ui"].iter().collect();
+    let mut tests: Vec<PathBuf> = fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().map_or(false, |ext| ext == "rs"))
+                .filter(|path| !path.with_extension("stdout").exists())
+                .collect()
+        })
+        .unwrap_or_default();
+    tests.sort();
+    tests
+}
+
+fn run_encoding(group_name: &str, filter: &Option<String>) {
+    let _temporary_env_vars = (TemporaryEnvVar::set("PRUSTI_PRINT_VIPER_PROGRAM", "true"),);
+
+    // A test without expected output would fail with the whole program as
+    // the difference, which is not useful to review.
+    if env::var_os("BLESS").is_none() {
+        let unblessed = find_unblessed_tests(group_name);
+        if !unblessed.is_empty() {
+            for test in &unblessed {
+                println!(
+                    "{}: no expected output, run the tests with BLESS=1",
+                    test.display()
+                );
+            }
+            panic!("{} encoding tests have no expected output", unblessed.len());
+        }
+    }
+
+    run_verification_base(group_name, filter);
+}
+
 fn run_lifetimes_dump(group_name: &str, filter: &Option<String>) {
     let _temporary_env_vars = (
         TemporaryEnvVar::set("PRUSTI_NO_VERIFY", "true"),
@@ -197,6 +474,10 @@ fn test_runner(_tests: &[&()]) {
     println!("[core_proof]");
     run_verification_core_proof("core_proof", &filter);
 
+    // Compare the generated Viper programs with the expected ones.
+    println!("[encoding]");
+    run_encoding("encoding", &filter);
+
     // Test the verifier with panic checks disabled (i.e. verify only the core proof).
     println!("[lifetimes_dump]");
     run_lifetimes_dump("lifetimes_dump", &filter);
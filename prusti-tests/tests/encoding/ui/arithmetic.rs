@@ -0,0 +1,12 @@
+// The encoding of integer arithmetic, with the overflow checks and the
+// bounds of the arguments and results.
+
+use prusti_contracts::*;
+
+#[requires(x < 100)]
+#[ensures(result == x + 1)]
+fn increment(x: u32) -> u32 {
+    x + 1
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// The folds and unfolds of the predicates of a struct and an enum, which are
+// the statements most affected by changes of the fold-unfold algorithm.
+
+use prusti_contracts::*;
+
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+enum Shape {
+    Dot(Point),
+    Empty,
+}
+
+fn swap(point: &mut Point) {
+    let x = point.x;
+    point.x = point.y;
+    point.y = x;
+}
+
+fn first_coordinate(shape: &Shape) -> u32 {
+    match shape {
+        Shape::Dot(point) => point.x,
+        Shape::Empty => 0,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+// The encoding of a loop with an invariant, which is checked on entry and
+// after each iteration.
+
+use prusti_contracts::*;
+
+#[requires(n < 1000)]
+#[ensures(result >= n)]
+fn count(n: u32) -> u32 {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n);
+        i += 1;
+    }
+    i
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+// The encoding of a pure function as a Viper function and of its call in a
+// postcondition.
+
+use prusti_contracts::*;
+
+#[pure]
+fn max(a: i32, b: i32) -> i32 {
+    if a > b { a } else { b }
+}
+
+#[ensures(result == max(a, b))]
+fn call_max(a: i32, b: i32) -> i32 {
+    max(a, b)
+}
+
+fn main() {}
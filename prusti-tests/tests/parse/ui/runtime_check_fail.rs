@@ -0,0 +1,36 @@
+// compile-flags: -Pprint_desugared_specs=true -Pprint_typeckd_specs=true -Pno_verify=true -Phide_uuids=true
+// normalize-stdout-test: "[a-z0-9]{32}" -> "$(NUM_UUID)"
+// normalize-stdout-test: "[a-z0-9]{8}-[a-z0-9]{4}-[a-z0-9]{4}-[a-z0-9]{4}-[a-z0-9]{12}" -> "$(UUID)"
+// normalize-stdout-test: "\[[a-z0-9]{4}\]::" -> "[$(CRATE_ID)]::"
+
+use prusti_contracts::*;
+
+#[insert_runtime_check]
+#[ensures(forall(|i: u32| i < result ==> i < 10))]
+fn quantifier(x: u32) -> u32 {
+    x
+}
+
+#[insert_runtime_check]
+#[ensures(result > 0)]
+fn early_return(x: u32) -> u32 {
+    if x == 0 {
+        return 1;
+    }
+    x
+}
+
+#[requires(x > 0)]
+#[insert_runtime_check(all)]
+#[ensures(result > 0)]
+fn misplaced_all(x: u32) -> u32 {
+    x
+}
+
+#[insert_runtime_check(everything)]
+#[requires(x > 0)]
+fn unknown_argument(x: u32) -> u32 {
+    x
+}
+
+fn main() {}
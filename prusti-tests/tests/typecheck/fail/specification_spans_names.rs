@@ -0,0 +1,19 @@
+// This test checks that the unknown identifiers in specifications are
+// reported at the faulty part of the attribute. Name resolution errors stop
+// the compilation before type checking, hence the separate test.
+
+use prusti_contracts::*;
+
+#[ensures(result ==
+    y //~ ERROR cannot find value `y` in this scope
+)]
+pub fn unknown_identifier(x: u32) -> u32 {
+    x
+}
+
+#[requires(
+    is_even(x) //~ ERROR cannot find function `is_even` in this scope
+)]
+pub fn unknown_function(x: u32) {}
+
+fn main() {}
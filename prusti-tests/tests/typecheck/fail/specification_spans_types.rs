@@ -0,0 +1,39 @@
+// This test checks that the type errors in specifications are reported
+// with a span at the faulty part of the attribute.
+
+use prusti_contracts::*;
+
+#[pure]
+pub fn is_positive(x: i32) -> bool {
+    x > 0
+}
+
+#[requires(
+    x > 0 ==>
+    x + 1 //~ ERROR mismatched types
+)]
+pub fn implication_rhs(x: u32) {}
+
+#[ensures(forall(|i: usize|
+    i + result //~ ERROR mismatched types
+))]
+pub fn quantifier_body() -> usize {
+    0
+}
+
+#[requires(is_positive(
+    x //~ ERROR mismatched types
+))]
+pub fn pure_call_argument(x: u32) {}
+
+pub fn loop_invariant(n: u32) {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(
+            i //~ ERROR mismatched types
+        );
+        i += 1;
+    }
+}
+
+fn main() {}
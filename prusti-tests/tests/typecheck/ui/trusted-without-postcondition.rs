@@ -0,0 +1,24 @@
+// compile-flags: -Pwarn_trusted_without_postcondition=true
+#![allow(dead_code)]
+use prusti_contracts::*;
+
+#[trusted]
+fn read() -> u32 {
+    42
+}
+
+#[trusted]
+fn reset(x: &mut u32) {
+    *x = 0;
+}
+
+#[trusted]
+#[ensures(result == 42)]
+fn read_specified() -> u32 {
+    42
+}
+
+#[trusted]
+fn log(_x: u32) {}
+
+fn main() {}
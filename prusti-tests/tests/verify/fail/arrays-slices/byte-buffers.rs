@@ -0,0 +1,19 @@
+use prusti_contracts::*;
+
+byte_specs!();
+
+#[ensures(result == value)] //~ ERROR postcondition might not hold
+fn mixed_endianness(value: u32) -> u32 {
+    u32::from_be_bytes(value.to_le_bytes())
+}
+
+#[ensures(result[0] == 0x12)] //~ ERROR postcondition might not hold
+fn encode() -> [u8; 4] {
+    0x12345678u32.to_le_bytes()
+}
+
+fn split_too_far(buffer: &[u8]) {
+    let (_first, _second) = buffer.split_at(4); //~ ERROR precondition might not hold
+}
+
+fn main() {}
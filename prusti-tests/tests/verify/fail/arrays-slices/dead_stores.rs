@@ -0,0 +1,24 @@
+// The values of these variables are never read, but their computation must
+// still be checked.
+
+use prusti_contracts::*;
+
+#[pure]
+#[requires(i < 3)]
+fn get(a: [u32; 3], i: usize) -> u32 {
+    a[i]
+}
+
+fn unused_index(a: [u32; 3], i: usize) {
+    let _x = a[i]; //~ ERROR the array or slice index may be out of bounds
+}
+
+fn unused_pure_call(a: [u32; 3], i: usize) {
+    let _x = get(a, i); //~ ERROR precondition of pure function call might not hold
+}
+
+fn unused_slice_index(s: &[u32], i: usize) {
+    let _x = s[i]; //~ ERROR the array or slice index may be out of bounds
+}
+
+fn main() {}
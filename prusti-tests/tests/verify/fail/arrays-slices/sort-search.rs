@@ -0,0 +1,22 @@
+use prusti_contracts::*;
+
+slice_specs!(u32);
+
+#[requires(values.len() == 3)]
+fn search_unsorted(values: &[u32]) {
+    let _ = values.binary_search(&2); //~ ERROR precondition might not hold
+}
+
+#[requires(values.len() == 3)]
+fn sort_descending(values: &mut [u32]) {
+    values.sort();
+    assert!(values[0] >= values[2]); //~ ERROR the asserted expression might not hold
+}
+
+// `i64` is not listed in `slice_specs!`, so `cmp` is not known to be a total
+// order on the elements.
+fn sort_unlisted(values: &mut [i64]) {
+    values.sort(); //~ ERROR precondition might not hold
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use prusti_contracts::*;
+
+#[bitwise_arithmetic]
+#[ensures(result < 8)] //~ ERROR postcondition might not hold
+fn mask(value: u32) -> u32 {
+    value & 15
+}
+
+#[bitwise_arithmetic]
+#[ensures(result == value * 2)] //~ ERROR postcondition might not hold
+fn double(value: u8) -> u8 {
+    value << 1
+}
+
+fn not_annotated(value: u32) -> u32 {
+    value & 7 //~ ERROR bitwise operations on non-boolean types are experimental
+}
+
+fn main() {}
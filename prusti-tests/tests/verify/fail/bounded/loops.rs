@@ -0,0 +1,71 @@
+use prusti_contracts::*;
+
+#[bounded(3)]
+#[ensures(result == 2 * n)] //~ ERROR postcondition might not hold
+fn double(n: u32) -> u32 {
+    let mut i = 0;
+    let mut result = 0;
+    while i < n {
+        if i == 2 {
+            result += 1;
+        } else {
+            result += 2;
+        }
+        i += 1;
+    }
+    result
+}
+
+#[bounded(4)]
+fn sum(values: &[u32; 3]) -> u32 {
+    let mut i = 0;
+    let mut total = 0;
+    while i < 4 {
+        let value = values[i]; //~ ERROR the array or slice index may be out of bounds
+        total += value;
+        i += 1;
+    }
+    total
+}
+
+#[bounded(3)]
+fn countdown(n: u32) -> u32 { //~ WARNING "countdown" was only verified for the executions that iterate each loop at most 3 times
+    let mut i = n;
+    while i > 0 {
+        i -= 1;
+    }
+    i
+}
+
+// Loops are unrolled instead of relying on their invariants.
+#[bounded(2)]
+fn wrong_invariant(n: u32) -> u32 { //~ WARNING "wrong_invariant" was only verified for the executions that iterate each loop at most 2 times
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i > 100);
+        i += 1;
+    }
+    i
+}
+
+#[bounded(2)]
+#[ensures(result == n)]
+fn copy(n: u32) -> u32 { //~ WARNING "copy" was only verified for the executions that iterate each loop at most 2 times
+    let mut i = 0;
+    while i < n {
+        i += 1;
+    }
+    i
+}
+
+// The callers of a bounded function rely on its contract.
+#[ensures(result == n)]
+fn caller(n: u32) -> u32 { //~ WARNING "caller" was verified assuming the contract of "copy", which was only verified for the executions that iterate each loop at most 2 times
+    copy(n)
+}
+
+fn indirect_caller(n: u32) -> u32 { //~ WARNING "indirect_caller" was verified assuming the contract of "copy", which was only verified for the executions that iterate each loop at most 2 times
+    caller(n)
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+use prusti_contracts::*;
+
+// `all()` always holds, `any()` never holds.
+
+#[cfg_attr(all(), requires(x < 10))]
+#[cfg_attr(any(), requires(x < 100))]
+#[ensures(result > x)]
+fn increment(x: u32) -> u32 {
+    x + 1
+}
+
+trait Value {
+    fn value(&self) -> u32;
+}
+
+struct Five;
+
+#[refine_trait_spec]
+impl Value for Five {
+    #[cfg_attr(all(), ensures(result == 6))] //~ ERROR postcondition
+    #[cfg_attr(any(), ensures(result == 5))]
+    fn value(&self) -> u32 {
+        5
+    }
+}
+
+fn test() {
+    increment(50); //~ ERROR precondition
+}
+
+fn main() {}
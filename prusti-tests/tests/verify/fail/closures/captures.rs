@@ -0,0 +1,32 @@
+use prusti_contracts::*;
+
+struct Counter {
+    value: u32,
+}
+
+fn mutable_capture_is_havocked() {
+    let mut counter = Counter { value: 5 };
+    let mut reset = || {
+        counter.value = 0;
+    };
+    reset();
+    assert!(counter.value == 5); //~ ERROR the asserted expression might not hold
+}
+
+fn mutable_capture_before_call() {
+    let mut counter = Counter { value: 5 };
+    let mut increment = || {
+        counter.value = 6;
+    };
+    increment();
+    assert!(counter.value == 6); //~ ERROR the asserted expression might not hold
+}
+
+fn by_value(counter: Counter) -> u32 {
+    let consume = move || counter.value;
+    let result = consume();
+    assert!(result == 0); //~ ERROR the asserted expression might not hold
+    result
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use prusti_contracts::*;
+
+binary_heap!(struct MaxHeap(u32));
+
+fn main() {
+    let mut heap = MaxHeap::new();
+    heap.push(2);
+    heap.push(5);
+    let _ = heap.top();
+    let _ = heap.pop();
+    let _ = heap.pop();
+    let _ = heap.top(); //~ ERROR precondition might not hold
+    assert!(heap.count(2) == 1); //~ ERROR the asserted expression might not hold
+}
@@ -0,0 +1,21 @@
+use prusti_contracts::*;
+
+iterator!(pub struct Numbers(u32), pub struct NumberPairs);
+
+fn take_more() {
+    let numbers = Numbers::repeat(1, 2).take(3);
+    assert!(numbers.len() == 3); //~ ERROR the asserted expression might not hold
+}
+
+fn zip_shorter() {
+    let mut pairs = Numbers::once(1).zip(Numbers::repeat(2, 3));
+    let _ = pairs.next();
+    assert!(pairs.next().is_some()); //~ ERROR the asserted expression might not hold
+}
+
+fn lookup_out_of_bounds() {
+    let numbers = Numbers::repeat(1, 2);
+    assert!(numbers.lookup(2) == 1); //~ ERROR precondition of pure function call might not hold
+}
+
+fn main() {}
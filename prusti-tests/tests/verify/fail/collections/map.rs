@@ -0,0 +1,24 @@
+use prusti_contracts::*;
+use std::collections::HashMap;
+
+map_specs!();
+
+#[ensures(ages.contains_key(&person))] //~ ERROR postcondition might not hold
+fn forget(ages: &mut HashMap<u32, String>, person: u32) {
+    let _ = ages.remove(&person);
+}
+
+// The inserted keys are not known to be distinct.
+#[requires(ages.is_empty())]
+#[ensures(ages.len() == 2)] //~ ERROR postcondition might not hold
+fn record_both(ages: &mut HashMap<u32, String>, first: u32, second: u32) {
+    let _ = ages.insert(first, String::new());
+    let _ = ages.insert(second, String::new());
+}
+
+fn main() {
+    let mut ages = HashMap::new();
+    let _ = ages.insert(1, String::new());
+    let _ = ages.remove(&1);
+    assert!(ages.len() == 1); //~ ERROR the asserted expression might not hold
+}
@@ -0,0 +1,12 @@
+use prusti_contracts::*;
+
+vec_deque!(struct Queue(u32));
+
+fn main() {
+    let mut queue = Queue::new();
+    queue.push_back(1);
+    queue.push_front(2);
+    let _ = queue.lookup(1);
+    let _ = queue.lookup(2); //~ ERROR precondition might not hold
+    assert!(queue.lookup(0) == 1); //~ ERROR the asserted expression might not hold
+}
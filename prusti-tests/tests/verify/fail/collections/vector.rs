@@ -0,0 +1,23 @@
+#![feature(allocator_api)]
+
+use prusti_contracts::*;
+
+vec_specs!();
+
+#[requires(values.len() == 3)]
+#[ensures(vec_lookup(values, 0) == old(vec_lookup(values, 0)))] //~ ERROR postcondition might not hold
+fn remove_first(values: &mut Vec<u32>) -> u32 {
+    values.swap_remove(0)
+}
+
+fn remove_from_empty() {
+    let mut values: Vec<u32> = Vec::new();
+    values.swap_remove(0); //~ ERROR precondition might not hold
+}
+
+#[ensures(values.len() == old(values.len()))] //~ ERROR postcondition might not hold
+fn keep_even(values: &mut Vec<u32>) {
+    values.retain(|value| value % 2 == 0);
+}
+
+fn main() {}
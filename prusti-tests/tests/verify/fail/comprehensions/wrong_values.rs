@@ -0,0 +1,25 @@
+use prusti_contracts::*;
+
+#[ensures(sum(3..4, |i: usize| i * i) == 10)] //~ ERROR postcondition might not hold
+fn wrong_sum() {}
+
+#[ensures(count(3..3, |i: usize| i > 0) == 1)] //~ ERROR postcondition might not hold
+fn wrong_count() {}
+
+#[ensures(max(2..3, |i: u32| i + 1) == 2)] //~ ERROR postcondition might not hold
+fn wrong_max() {}
+
+#[ensures(result == sum(0..n, |i: usize| i + 1))] //~ ERROR postcondition might not hold
+fn sum_off_by_one(n: usize) -> usize {
+    let mut result = 0;
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n);
+        body_invariant!(result == sum(0..i, |k: usize| k));
+        result += i;
+        i += 1;
+    }
+    result
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// compile-flags: -Pcounterexample=true
+
+use prusti_contracts::*;
+
+#[ensures(result != 86)] //~ ERROR postcondition might not hold
+fn test(x: i32) -> i32 {
+    //@^ NOTE counterexample for "x" initial value: 42 final value: 42
+    //@^^ NOTE counterexample for result final value: 86
+    let y = x + 1; //@ NOTE counterexample for "y" final value: 43
+    y * 2
+}
+
+fn main() {}
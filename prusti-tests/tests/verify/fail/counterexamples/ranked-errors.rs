@@ -0,0 +1,15 @@
+// compile-flags: -Prank_verification_errors=true
+
+use prusti_contracts::*;
+
+#[ensures(result != 86)] //~ ERROR postcondition might not hold
+fn double(x: i32) -> i32 {
+    x * 2
+}
+
+#[requires(x < 100)]
+fn not_42(x: u32) {
+    assert!(x != 42); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+// compile-flags: -Pcoverage_report=true -Pquiet=false
+// error-pattern: Verification coverage:
+// error-pattern: total                                              1           1           0           1           0           1
+// error-pattern: [no specs]
+// error-pattern: [trusted]
+// error-pattern: [failed]
+use prusti_contracts::*;
+
+#[ensures(result == x + 1)]
+fn verified(x: i32) -> i32 {
+    x + 1
+}
+
+#[trusted]
+#[ensures(result == 0)]
+fn trusted() -> i32 {
+    1
+}
+
+#[ensures(result == x)]
+fn failed(x: i32) -> i32 {
+    x + 1
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+// compile-flags: -Preport_dead_code=true
+
+use prusti_contracts::*;
+
+#[requires(x >= 0)]
+fn checked(x: i32) -> Result<i32, ()> {
+    if x < 0 {
+        return Err(()); //~ WARNING this code is unreachable
+    }
+    Ok(x)
+}
+
+#[requires(n == 1 || n == 2)]
+fn name(n: u32) -> u32 {
+    match n {
+        1 => 10,
+        2 => 20,
+        _ => 0, //~ WARNING this code is unreachable
+    }
+}
+
+// Both branches are reachable.
+fn positive_part(x: i32) -> i32 {
+    if x > 0 {
+        x
+    } else {
+        0
+    }
+}
+
+// The branches of a function with verification errors are not reported.
+#[requires(x > 0)]
+#[ensures(result > 0)] //~ ERROR postcondition might not hold
+fn decrement(x: i32) -> i32 {
+    if x <= 0 {
+        return 1;
+    }
+    x - 1
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+// compile-flags: -Preport_dead_specs=true
+
+use prusti_contracts::*;
+
+#[trusted]
+#[ensures(result == x * 2)]
+fn unused_double(x: u32) -> u32 { //~ WARNING the specification of trusted function "unused_double" is never used
+    x + x
+}
+
+#[trusted]
+#[requires(x > 0)]
+#[ensures(result < x)]
+fn unused_decrement(x: u32) -> u32 { //~ WARNING the specification of trusted function "unused_decrement" is never used
+    x - 1
+}
+
+// The contract of a trusted function that is called is assumed, so it is not
+// reported.
+#[trusted]
+#[ensures(result >= x)]
+fn at_least(x: u32) -> u32 {
+    x
+}
+
+#[ensures(result > x)] //~ ERROR postcondition might not hold
+fn greater(x: u32) -> u32 {
+    at_least(x)
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use prusti_contracts::*;
+
+#[domain]
+mod peano {
+    use prusti_contracts::*;
+
+    pub fn zero() -> u32;
+    pub fn succ(n: u32) -> u32;
+    pub fn add(a: u32, b: u32) -> u32;
+
+    axiom! { add_zero: forall(|a: u32| add(a, zero()) == a) }
+}
+
+#[ensures(peano::add(x, peano::zero()) == x)]
+fn add_zero(x: u32) {}
+
+// Nothing is known about `succ`.
+#[ensures(peano::succ(x) != x)] //~ ERROR postcondition
+fn succ_differs(x: u32) {}
+
+fn main() {}
@@ -12,4 +12,11 @@ fn client_1() {}
 //~^ ERROR use of impure function "get_u32" in pure code
 fn client_2() {}
 
+#[trusted]
+#[ensures(get_u32() == result)]
+//~^ ERROR use of impure function "get_u32" in pure code
+fn client_3() -> u32 {
+    123
+}
+
 fn main() {}
@@ -0,0 +1,14 @@
+use prusti_contracts::*;
+
+#[ensures(result.is_ok())] //~ ERROR postcondition might not hold
+fn parse(values: Option<u32>) -> Result<u32, ()> {
+    let value = values.ok_or(())?;
+    Ok(value)
+}
+
+fn main() -> Result<(), u32> {
+    let value: Result<u32, u32> = Ok(4);
+    let value = value?;
+    assert!(value == 3); //~ ERROR the asserted expression might not hold
+    Ok(())
+}
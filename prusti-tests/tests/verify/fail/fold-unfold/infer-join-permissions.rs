@@ -0,0 +1,47 @@
+// compile-flags: -Pfoldunfold_infer_join_permissions=true
+use prusti_contracts::*;
+
+struct Counter {
+    value: u32,
+    steps: u32,
+}
+
+struct Pair {
+    left: Counter,
+    right: Counter,
+}
+
+#[pure]
+fn total(p: &Pair) -> u32 {
+    p.left.value + p.right.value
+}
+
+#[requires(n < 100)]
+fn count(p: &mut Pair, n: u32) {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n);
+        if i % 2 == 0 {
+            p.left.steps = i;
+        } else {
+            let t = total(p);
+            assert!(t == p.left.value); //~ ERROR the asserted expression might not hold
+            p.right.steps = t;
+        }
+        i += 1;
+    }
+}
+
+#[requires(p.left.value < 100 && p.right.value < 100)]
+#[ensures(total(p) == old(total(p)) + 1)] //~ ERROR postcondition might not hold
+fn swap(p: &mut Pair, b: bool) {
+    if b {
+        let value = p.left.value;
+        p.left.value = p.right.value;
+        p.right.value = value;
+    } else {
+        assert!(total(p) == p.left.value + p.right.value);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,45 @@
+// compile-flags: -Pfoldunfold_read_unfolded_discriminants=true
+use prusti_contracts::*;
+
+enum Shape {
+    Circle(u32),
+    Rectangle(u32, u32),
+    Empty,
+}
+
+#[pure]
+fn is_empty(shape: &Shape) -> bool {
+    match shape {
+        Shape::Empty => true,
+        _ => false,
+    }
+}
+
+fn grow(shape: &mut Shape) {
+    match shape {
+        Shape::Circle(radius) => *radius += 1,
+        Shape::Rectangle(width, _) => {
+            *width += 1;
+            if let Shape::Rectangle(_, height) = shape {
+                *height += 1;
+            }
+        }
+        Shape::Empty => unreachable!(), //~ ERROR unreachable!(..) statement might be reachable
+    }
+}
+
+#[ensures(!is_empty(shape))] //~ ERROR postcondition might not hold
+fn scale(shape: &mut Shape, factor: u32) {
+    if let Shape::Circle(radius) = shape {
+        *radius *= factor;
+    }
+    match shape {
+        Shape::Rectangle(width, height) => {
+            *width *= factor;
+            *height *= factor;
+        }
+        _ => {}
+    }
+}
+
+fn main() {}
@@ -0,0 +1,46 @@
+// compile-flags: -Pauto_frame_conditions=true
+
+use prusti_contracts::*;
+
+struct Counter {
+    count: u32,
+    limit: u32,
+    enabled: bool,
+}
+
+impl Counter {
+    #[no_auto_frame]
+    fn disable(&mut self) {
+        self.enabled = false;
+    }
+
+    fn reset_count(&mut self) {
+        self.count = 0;
+    }
+
+    fn reset(&mut self) {
+        self.reset_count();
+    }
+}
+
+fn opted_out(counter: &mut Counter) {
+    let limit = counter.limit;
+    counter.disable();
+    assert!(counter.limit == limit); //~ ERROR the asserted expression might not hold
+}
+
+fn modified_field(counter: &mut Counter) {
+    let count = counter.count;
+    let limit = counter.limit;
+    counter.reset_count();
+    assert!(counter.limit == limit);
+    assert!(counter.count == count); //~ ERROR the asserted expression might not hold
+}
+
+fn reborrowed_self(counter: &mut Counter) {
+    let limit = counter.limit;
+    counter.reset();
+    assert!(counter.limit == limit); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
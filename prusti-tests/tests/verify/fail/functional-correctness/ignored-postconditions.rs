@@ -0,0 +1,26 @@
+// compile-flags: -Pfunctional_correctness=false
+
+use prusti_contracts::*;
+
+#[ensures(result > 0)]
+fn positive() -> u32 {
+    1
+}
+
+#[requires(divisor > 0)]
+fn divide(value: u32, divisor: u32) -> u32 {
+    value / divisor
+}
+
+#[trusted]
+#[ensures(result > 0)]
+fn trusted_positive() -> u32 {
+    1
+}
+
+fn main() {
+    // The postcondition of `positive` is not assumed.
+    divide(1, positive()); //~ ERROR precondition might not hold
+    // The postconditions of trusted functions are still assumed.
+    divide(1, trusted_positive());
+}
@@ -0,0 +1,20 @@
+// compile-flags: -Pfunctional_correctness=false
+
+use prusti_contracts::*;
+
+#[pure]
+fn double(n: u32) -> u64 {
+    2 * n as u64
+}
+
+// Lemmas are still verified, because their axioms are assumed in the other
+// functions.
+#[lemma(triggers = [(double(n),)])]
+#[ensures(false)] //~ ERROR postcondition might not hold
+fn wrong_axiom(n: u32) {}
+
+#[lemma]
+#[ensures(double(n) == 2 * n as u64 + 1)] //~ ERROR postcondition might not hold
+fn wrong_lemma(n: u32) {}
+
+fn main() {}
@@ -0,0 +1,27 @@
+// compile-flags: -Penable_generators=true
+
+#![feature(generators)]
+
+use prusti_contracts::*;
+
+fn main() {
+    let _counter = || {
+        let mut count = 0u32;
+        yield_invariant!(count < 3);
+        while count < 3 {
+            body_invariant!(count < 3);
+            count += 1;
+            yield count; //~ ERROR the yield invariant might not hold
+        }
+    };
+
+    let _resumed = |mut input: u32| {
+        let mut last = 0u32;
+        yield_invariant!(last <= 100);
+        loop {
+            body_invariant!(last <= 100);
+            last = input;
+            input = yield last; //~ ERROR the yield invariant might not hold
+        }
+    };
+}
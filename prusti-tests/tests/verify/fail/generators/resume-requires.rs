@@ -0,0 +1,26 @@
+// compile-flags: -Penable_generators=true
+
+#![feature(generators, generator_trait)]
+
+use prusti_contracts::*;
+use std::ops::Generator;
+use std::pin::Pin;
+
+fn main() {
+    let mut bounded = |mut input: u32| {
+        resume_requires!(input <= 100);
+        assert!(input <= 100);
+        input = yield input;
+        assert!(input <= 100);
+        input = yield input;
+        assert!(input <= 10); //~ ERROR the asserted expression might not hold
+    };
+    let _ = Pin::new(&mut bounded).resume(5);
+    let _ = Pin::new(&mut bounded).resume(500); //~ ERROR the resume precondition of the generator might not hold
+
+    let _unbounded = |input: u32| {
+        let limit = 100u32;
+        resume_requires!(input <= limit); //~ ERROR the resume precondition of a generator can only mention its resume argument
+        yield input;
+    };
+}
@@ -0,0 +1,29 @@
+use prusti_contracts::*;
+
+#[pure]
+fn narrow(x: Int) -> u8 {
+    x.to() //~ ERROR value might not fit into the target type
+}
+
+#[pure]
+#[requires(Int::new(0u8) <= x && x < Int::new(256u32))]
+fn checked_narrow(x: Int) -> u8 {
+    x.to()
+}
+
+#[pure]
+fn to_nat(x: i32) -> Nat {
+    Nat::new(x) //~ ERROR value might not fit into the target type
+}
+
+#[pure]
+fn predecessor(n: Nat) -> Nat {
+    n - Nat::new(1u8) //~ ERROR value might not fit into the target type
+}
+
+#[ensures(Int::new(result) == Int::new(a) + Int::new(b))] //~ ERROR postcondition might not hold
+fn sum(a: u32, b: u32) -> u32 {
+    a
+}
+
+fn main() {}
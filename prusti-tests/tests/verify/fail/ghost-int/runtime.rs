@@ -0,0 +1,36 @@
+use prusti_contracts::*;
+
+// Ghost integers are represented by an `i128` at runtime, so the operations of
+// executable code must not overflow it.
+
+#[pure]
+fn square(x: Int) -> Int {
+    x * x //~ ERROR the result of the ghost integer operation might not fit into an `i128`
+}
+
+#[pure]
+fn successor(x: Int) -> Int {
+    x + Int::new(1u8) //~ ERROR the result of the ghost integer operation might not fit into an `i128`
+}
+
+#[pure]
+#[requires(x < Int::new(i128::MAX))]
+fn checked_successor(x: Int) -> Int {
+    x + Int::new(1u8)
+}
+
+#[pure]
+fn sum(a: u64, b: u64) -> Int {
+    Int::new(a) + Int::new(b)
+}
+
+// Specifications are not executed, so they are not restricted.
+#[requires(x * x * x >= Int::new(0u8))]
+#[ensures(false)] //~ ERROR postcondition might not hold
+fn in_specification(x: Int) {}
+
+fn in_procedure(a: u64) -> u64 {
+    (Int::new(a) + Int::new(1u8)).to() //~ ERROR ghost integers can only be used in specifications and pure functions
+}
+
+fn main() {}
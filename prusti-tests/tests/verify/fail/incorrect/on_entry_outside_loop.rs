@@ -0,0 +1,8 @@
+use prusti_contracts::*;
+
+#[ensures(*x == on_entry(*x) + 1)] //~ ERROR on_entry(..) can only be used in loop body invariants
+fn inc(x: &mut u32) {
+    *x += 1;
+}
+
+fn main() {}
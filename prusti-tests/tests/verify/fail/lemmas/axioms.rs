@@ -0,0 +1,26 @@
+use prusti_contracts::*;
+
+#[pure]
+fn id(n: u32) -> u32 {
+    n
+}
+
+// Each lemma would be proven by the axiom of the other one.
+#[lemma(triggers = [(id(n),)])]
+#[ensures(id(n) != n)] //~ ERROR postcondition might not hold
+fn first(n: u32) {}
+
+#[lemma(triggers = [(id(n),)])]
+#[ensures(id(n) != n)] //~ ERROR postcondition might not hold
+fn second(n: u32) {}
+
+#[ensures(id(n) != n)]
+fn uses_axiom(n: u32) {}
+
+#[lemma]
+#[ensures(id(n) != n)]
+fn through_function(n: u32) {
+    uses_axiom(n); //~ ERROR lemmas can only call other lemmas and trusted functions
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+// compile-flags: -Pcheck_overflows=false
+
+use prusti_contracts::*;
+
+#[pure]
+#[requires(n <= 1000)]
+fn sum(n: u32) -> u32 {
+    if n == 0 {
+        0
+    } else {
+        n + sum(n - 1)
+    }
+}
+
+#[lemma(induction = n)]
+#[requires(n <= 1000)]
+#[ensures(sum(n) >= 2 * n)] //~ ERROR postcondition might not hold
+fn sum_ge_double(n: u32) {}
+
+// The induction hypothesis is called with `n - 1`, which may violate the precondition.
+#[lemma(induction = n)] //~ ERROR precondition might not hold
+#[requires(m <= n && n <= 1000)]
+#[ensures(sum(m) <= sum(n))]
+fn sum_monotonic(m: u32, n: u32) {}
+
+fn main() {}
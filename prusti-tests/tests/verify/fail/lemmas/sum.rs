@@ -0,0 +1,39 @@
+// compile-flags: -Pcheck_overflows=false
+
+use prusti_contracts::*;
+
+#[pure]
+#[requires(n <= 1000)]
+fn sum(n: u32) -> u32 {
+    if n == 0 {
+        0
+    } else {
+        n + sum(n - 1)
+    }
+}
+
+#[lemma]
+#[requires(m <= n && n <= 1000)]
+#[ensures(sum(m) <= sum(n))]
+fn sum_monotonic(m: u32, n: u32) {
+    if m < n {
+        sum_monotonic(m, n - 1);
+    }
+}
+
+#[lemma]
+#[requires(n <= 1000)]
+#[ensures(sum(n) > n)] //~ ERROR postcondition might not hold
+fn sum_gt(n: u32) {}
+
+#[requires(n <= 1000)]
+#[ensures(sum(m) <= sum(n))]
+fn apply_monotonic(m: u32, n: u32) {
+    apply_lemma!(sum_monotonic(m, n)); //~ ERROR precondition might not hold
+}
+
+#[requires(m <= n && n <= 1000)]
+#[ensures(sum(m) <= sum(n))] //~ ERROR postcondition might not hold
+fn without_lemma(m: u32, n: u32) {}
+
+fn main() {}
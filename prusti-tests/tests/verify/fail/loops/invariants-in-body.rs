@@ -0,0 +1,27 @@
+use prusti_contracts::*;
+
+fn invariant_before_continue(n: u32) {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n);
+        i += 1;
+        if i % 2 == 0 {
+            body_invariant!(i % 4 == 0); //~ ERROR loop invariant might not hold at this point of the loop body
+            continue;
+        }
+    }
+}
+
+fn second_group(n: u32) {
+    let mut i = 0;
+    let mut j = 0;
+    while i < n {
+        body_invariant!(i < n);
+        body_invariant!(j == i);
+        j += 2;
+        body_invariant!(j == i + 1); //~ ERROR loop invariant might not hold at this point of the loop body
+        i += 1;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use prusti_contracts::*;
+
+// The assertion holds before the loop, but the loop modifies `x` and has no
+// invariant about it. Prusti points at the loop head and suggests adding
+// `body_invariant!(x == 5)`.
+fn test(n: u32) {
+    let mut x = 5;
+    let mut i = 0;
+    while i < n {
+        if i > n {
+            x = 6;
+        }
+        i += 1;
+    }
+    assert!(x == 5); //~ ERROR the asserted expression might not hold
+                     //@^ HELP consider adding `body_invariant!(x == 5)` to the loop body
+}
+
+fn test_with_invariant(n: u32) {
+    let mut x = 5;
+    let mut i = 0;
+    while i < n {
+        body_invariant!(x == 5);
+        if i > n {
+            x = 6;
+        }
+        i += 1;
+    }
+    assert!(x == 5);
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+use prusti_contracts::*;
+
+// Only the failure after a loop that modifies a mentioned variable comes with
+// a suggested body invariant.
+fn modified_in_loop(n: u32) {
+    let mut x = 5;
+    let mut i = 0;
+    while i < n {
+        if i > n {
+            x = 6;
+        }
+        i += 1;
+    }
+    assert!(x == 5); //~ ERROR the asserted expression might not hold
+    //~| HELP consider adding `body_invariant!(x == 5)` to the loop body
+}
+
+fn not_modified_in_loop(n: u32) {
+    let x = 5;
+    let mut i = 0;
+    while i < n {
+        i += 1;
+    }
+    assert!(x == 7); //~ ERROR the asserted expression might not hold
+}
+
+fn before_loop(n: u32) {
+    let mut x = 5;
+    assert!(x == 6); //~ ERROR the asserted expression might not hold
+    let mut i = 0;
+    while i < n {
+        x += 1;
+        i += 1;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+use prusti_contracts::*;
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[explicit_expiry]
+#[after_expiry(p.x == before_expiry(*result))]
+fn borrow_x(p: &mut Point) -> &mut i32 {
+    &mut p.x
+}
+
+#[explicit_expiry]
+#[after_expiry(p.y == old(p.y) + 1)] //~ ERROR pledge
+fn borrow_x_wrong(p: &mut Point) -> &mut i32 {
+    &mut p.x
+}
+
+fn client() {
+    let mut p = Point { x: 1, y: 2 };
+    let x = borrow_x(&mut p);
+    *x = 5;
+    assert!(p.x == 5);
+    assert!(p.y == 2); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+use prusti_contracts::*;
+
+struct T {
+    val: i32,
+}
+
+#[after_expiry(x.val == before_expiry(*result.1) + 1)] //~ ERROR pledge
+fn peek_and_borrow(x: &mut T) -> (i32, &mut i32) {
+    (x.val, &mut x.val)
+}
+
+#[after_expiry(x.val == before_expiry(*result.0) && y.val == before_expiry(*result.1))]
+fn borrow_both<'a, 'b>(x: &'a mut T, y: &'b mut T) -> (&'a mut i32, &'b mut i32) {
+    (&mut x.val, &mut y.val)
+}
+
+fn use_both() {
+    let mut a = T { val: 1 };
+    let mut b = T { val: 2 };
+    let (x, y) = borrow_both(&mut a, &mut b);
+    *x = 3;
+    *y = 4;
+    assert!(a.val == 3);
+    assert!(b.val == 3); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
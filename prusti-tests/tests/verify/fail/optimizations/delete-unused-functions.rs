@@ -0,0 +1,32 @@
+// compile-flags: -Poptimizations=inline_constant_functions,delete_unused_functions
+use prusti_contracts::*;
+
+#[pure]
+fn zero() -> i32 {
+    0
+}
+
+#[pure]
+fn is_positive(x: i32) -> bool {
+    x > zero()
+}
+
+#[pure]
+#[requires(is_positive(x))]
+fn predecessor(x: i32) -> i32 {
+    x - 1
+}
+
+#[requires(x >= zero())]
+#[ensures(result >= zero())]
+fn decrement(x: i32) -> i32 {
+    predecessor(x) //~ ERROR precondition of pure function call might not hold
+}
+
+#[requires(is_positive(x))]
+#[ensures(result > zero())] //~ ERROR postcondition might not hold
+fn decrement_positive(x: i32) -> i32 {
+    predecessor(x)
+}
+
+fn main() {}
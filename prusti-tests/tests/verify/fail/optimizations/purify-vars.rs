@@ -0,0 +1,17 @@
+// compile-flags: -Poptimizations=purify_vars
+use prusti_contracts::*;
+
+#[requires(n < 1000)]
+#[ensures(result == n)] //~ ERROR postcondition might not hold
+fn successor(n: usize) -> usize {
+    let m = n;
+    m + 1
+}
+
+fn unused_copy(n: usize) -> usize {
+    let unused = n;
+    let _other = unused;
+    n - 1 //~ ERROR attempt to subtract with overflow
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+// compile-flags: -Poptimizations=remove_empty_blocks,clean_cfg
+use prusti_contracts::*;
+
+#[ensures(result > x && result >= y)] //~ ERROR postcondition might not hold
+fn max(x: i32, y: i32) -> i32 {
+    if x > y {
+        x
+    } else if x == y {
+        y
+    } else {
+        y
+    }
+}
+
+#[requires(n >= 0)]
+#[ensures(result >= n)]
+fn count(n: i32) -> i32 {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n);
+        if i % 2 == 0 {
+            i += 1;
+        } else {
+            assert!(i > 1); //~ ERROR the asserted expression might not hold
+            i += 1;
+        }
+    }
+    i
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+// compile-flags: -Poptimizations=remove_redundant_fold_unfold
+use prusti_contracts::*;
+
+struct Pair {
+    first: u32,
+    second: u32,
+}
+
+#[pure]
+fn sum(p: &Pair) -> u32 {
+    p.first + p.second
+}
+
+#[requires(p.first < 100 && p.second < 100)]
+#[ensures(sum(p) == old(sum(p)) + 1)]
+#[ensures(p.first == old(p.first))] //~ ERROR postcondition might not hold
+fn increment(p: &mut Pair) {
+    let before = sum(p);
+    p.first += 1;
+    let after = sum(p);
+    assert!(after == before + 1);
+}
+
+#[requires(p.first < 100 && p.second < 100)]
+fn increment_second(p: &mut Pair) {
+    let before = sum(p);
+    p.second += 1;
+    let after = sum(p);
+    assert!(after == before); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
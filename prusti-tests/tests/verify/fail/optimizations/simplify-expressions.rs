@@ -0,0 +1,27 @@
+// compile-flags: -Poptimizations=simplify_expressions,remove_trivial_assertions
+use prusti_contracts::*;
+
+#[pure]
+#[ensures(result == 2 * 3)] //~ ERROR postcondition might not hold
+fn seven() -> i32 {
+    7
+}
+
+#[requires(x > 0 && (x < 10 && !!(x != 5)))]
+#[ensures(result == 2 * 3 + x)]
+fn add_six(x: i32) -> i32 {
+    assert!(x + 2 * 3 > 7); //~ ERROR the asserted expression might not hold
+    x + 6
+}
+
+#[requires(x > 0 && (x < 10 && !!(x != 5)))]
+fn not_four(x: i32) {
+    assert!(!!(x != 4) || 1 > 2); //~ ERROR the asserted expression might not hold
+}
+
+#[ensures(result == 10 / 3 * 3)] //~ ERROR postcondition might not hold
+fn nine() -> i32 {
+    10
+}
+
+fn main() {}
@@ -0,0 +1,4 @@
+// compile-flags: -Pcheck_panics=sometimes
+// error-pattern: invalid value "sometimes" of the configuration flag "check_panics"
+
+fn main() {}
@@ -0,0 +1,34 @@
+// compile-flags: -Pcheck_panics=strict -Pcheck_overflows=false
+
+use prusti_contracts::*;
+
+fn add(a: u32, b: u32) -> u32 {
+    a + b //~ ERROR assertion might fail with "attempt to add with overflow"
+}
+
+fn first(x: Option<u32>) -> u32 {
+    x.unwrap() //~ ERROR the unwrapped value might be `None` or `Err`
+}
+
+fn parse(x: Result<u32, ()>) -> u32 {
+    x.expect("not a number") //~ ERROR the unwrapped value might be `None` or `Err`
+}
+
+fn error_of(x: Result<u32, u32>) -> u32 {
+    x.unwrap_err() //~ ERROR the unwrapped value might be `Ok`
+}
+
+fn remove_first(v: &mut Vec<u32>) -> u32 {
+    v.remove(0) //~ ERROR the called function might panic
+}
+
+fn slice_len(a: [u32; 4]) -> usize {
+    let s = &a[2..5]; //~ ERROR the array or slice index may be out of bounds
+    s.len()
+}
+
+fn divide(a: u32, b: u32) -> u32 {
+    a / b //~ ERROR assertion might fail with "attempt to divide by zero"
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+// compile-flags: -Pbatch_viper_ast_construction=true
+// The errors of programs built from Viper source text are reported at the
+// positions of the Rust source.
+use prusti_contracts::*;
+
+struct Account {
+    balance: u32,
+}
+
+#[pure]
+fn balance(account: &Account) -> u32 {
+    account.balance
+}
+
+#[requires(account.balance < 1000 && amount < 1000)]
+#[ensures(balance(account) == old(balance(account)))] //~ ERROR postcondition might not hold
+fn deposit(account: &mut Account, amount: u32) {
+    account.balance += amount;
+}
+
+fn withdraw(account: &mut Account, amount: u32) {
+    assert!(account.balance >= amount); //~ ERROR the asserted expression might not hold
+    account.balance -= amount;
+}
+
+fn main() {}
@@ -0,0 +1,40 @@
+// Each procedure is verified in its own Viper program, so an error in one
+// procedure does not hide the errors in the others, nor makes them fail.
+use prusti_contracts::*;
+
+struct Account {
+    balance: u32,
+}
+
+#[pure]
+fn balance(account: &Account) -> u32 {
+    account.balance
+}
+
+#[requires(account.balance < 1000 && amount < 1000)]
+#[ensures(balance(account) == old(balance(account)) + amount)]
+fn deposit(account: &mut Account, amount: u32) {
+    account.balance += amount;
+}
+
+#[requires(account.balance < 1000 && amount < 1000)]
+#[ensures(balance(account) == old(balance(account)))] //~ ERROR postcondition might not hold
+fn wrong_deposit(account: &mut Account, amount: u32) {
+    account.balance += amount;
+}
+
+#[requires(from.balance >= amount && to.balance < 1000 && amount < 1000)]
+#[ensures(balance(from) + balance(to) == old(balance(from) + balance(to)))]
+fn transfer(from: &mut Account, to: &mut Account, amount: u32) {
+    from.balance -= amount;
+    deposit(to, amount);
+}
+
+#[requires(from.balance >= amount && to.balance < 1000 && amount < 1000)]
+#[ensures(balance(from) + balance(to) == old(balance(from) + balance(to)))] //~ ERROR postcondition might not hold
+fn wrong_transfer(from: &mut Account, to: &mut Account, amount: u32) {
+    from.balance -= amount;
+    wrong_deposit(to, amount);
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+// compile-flags: -Pdump_viper_program=true -Pprint_viper_program=true
+// check-stdout
+// error-pattern: // [rust]
+// error-pattern: let sum = first + second;
+// error-pattern: assert!(sum > first)
+// error-pattern: // [mir]
+// error-pattern: the asserted expression might not hold
+// The dumped Viper program is annotated with the Rust source code of the
+// encoded statements.
+use prusti_contracts::*;
+
+#[requires(first < 1000 && second < 1000)]
+fn add(first: u32, second: u32) -> u32 {
+    let sum = first + second;
+    assert!(sum > first);
+    sum
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use prusti_contracts::*;
+use std::cmp::Ordering;
+
+#[pure]
+fn is_after(a: i32, b: i32) -> bool {
+    a.cmp(&b) == Ordering::Greater
+}
+
+fn unchecked_clamp(x: i32, lo: i32, hi: i32) -> i32 {
+    x.clamp(lo, hi) //~ ERROR statement might panic
+}
+
+#[ensures(result == a)] //~ ERROR postcondition might not hold
+fn wrong_min(a: u32, b: u32) -> u32 {
+    a.min(b)
+}
+
+fn main() {
+    assert!(is_after(1, 1)); //~ ERROR the asserted expression might not hold
+}
@@ -0,0 +1,41 @@
+// compile-flags: -Pcheck_overflows=false
+
+use prusti_contracts::*;
+
+#[pure(fuel = 0)]
+#[ensures(result >= n)]
+fn identity(n: u32) -> u32 {
+    n
+}
+
+#[ensures(result >= 3)]
+fn postcondition_is_known() -> u32 {
+    identity(3)
+}
+
+#[ensures(result == 3)] //~ ERROR postcondition
+fn definition_is_not_unfolded() -> u32 {
+    identity(3)
+}
+
+#[pure(fuel = 3)]
+#[requires(n <= 1000)]
+fn fib(n: u32) -> u32 {
+    if n <= 1 {
+        n
+    } else {
+        fib(n - 1) + fib(n - 2)
+    }
+}
+
+#[ensures(result == 2)]
+fn fib_of_three() -> u32 {
+    fib(3)
+}
+
+#[ensures(result == 5)] //~ ERROR postcondition
+fn fib_needs_more_fuel() -> u32 {
+    fib(5)
+}
+
+fn main() {}
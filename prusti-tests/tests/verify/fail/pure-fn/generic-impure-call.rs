@@ -0,0 +1,24 @@
+use prusti_contracts::*;
+
+fn identity<T>(x: T) -> T {
+    x
+}
+
+// The body of a generic pure function is checked even if it is only used with
+// type parameters.
+#[pure]
+fn wrapped<T: Copy>(x: T) -> T {
+    identity(x) //~ ERROR use of impure function "identity" in pure code is not allowed
+}
+
+#[pure]
+fn contains<T: PartialEq + Copy>(values: &(T, T), x: T) -> bool {
+    values.0 == x || values.1 == x
+}
+
+#[requires(contains(&values, x))]
+fn generic<T: PartialEq + Copy>(values: (T, T), x: T) {}
+
+fn main() {
+    generic((1, 2), 1);
+}
@@ -0,0 +1,28 @@
+// compile-flags: -Pinfer_purity=true
+
+use prusti_contracts::*;
+
+fn sum_to(n: u32) -> u32 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < n {
+        i += 1;
+        sum += i;
+    }
+    sum
+}
+
+#[ensures(result == (x > 0))]
+fn is_positive(x: i32) -> bool {
+    x > 0
+}
+
+#[requires(sum_to(0) == 0)]
+//~^ ERROR use of impure function "sum_to" in pure code
+fn client_1() {}
+
+#[requires(is_positive(1))]
+//~^ ERROR use of impure function "is_positive" in pure code
+fn client_2() {}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use prusti_contracts::*;
+
+#[pure]
+#[inline_spec]
+#[requires(x <= 1000)]
+fn double(x: u32) -> u32 {
+    2 * x
+}
+
+fn unchecked_double(x: u32) -> u32 {
+    double(x) //~ ERROR precondition of pure function call might not hold
+}
+
+#[requires(x <= 1000)]
+#[ensures(result == double(x) + 1)] //~ ERROR postcondition
+fn wrong_double(x: u32) -> u32 {
+    2 * x
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+use prusti_contracts::*;
+
+#[pure]
+#[opaque]
+#[requires(x <= 1000)]
+#[ensures(result >= x)]
+fn double(x: u32) -> u32 {
+    2 * x
+}
+
+#[requires(x <= 1000)]
+#[ensures(result == 2 * x)] //~ ERROR postcondition
+fn not_revealed(x: u32) -> u32 {
+    double(x)
+}
+
+#[requires(x <= 1000)]
+#[ensures(result == 2 * x)]
+fn revealed(x: u32) -> u32 {
+    reveal!(double);
+    double(x)
+}
+
+fn main() {}
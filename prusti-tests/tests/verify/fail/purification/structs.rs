@@ -0,0 +1,37 @@
+// compile-flags: -Penable_purification_optimization=true -Penable_struct_purification=true
+
+use prusti_contracts::*;
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[ensures(result.0 == a)]
+fn make_pair(a: u32, b: bool) -> (u32, bool) {
+    (a, b)
+}
+
+fn swap() {
+    let mut pair = (1, 2);
+    let tmp = pair.0;
+    pair.0 = pair.1;
+    pair.1 = tmp;
+    assert!(pair.0 == 1); //~ ERROR the asserted expression might not hold
+}
+
+fn from_call() {
+    let pair = make_pair(3, true);
+    assert!(pair.0 == 3);
+    assert!(pair.1); //~ ERROR the asserted expression might not hold
+}
+
+fn borrowed() {
+    let mut p = Point { x: 1, y: 2 };
+    let r = &mut p;
+    r.x = 5;
+    assert!(p.x == 1); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+// compile-flags: -Pwarn_missing_triggers=true
+
+use prusti_contracts::*;
+
+#[pure]
+fn f(x: u32) -> u32 {
+    x
+}
+
+#[requires(forall(|i: u32| i < 10 ==> i + 1 <= 10))] //~ WARNING no trigger can be inferred for `i` in this quantifier
+fn arithmetic_only() {}
+
+#[requires(forall(|i: u32| i < 10 ==> f(i + 1) > 0))] //~ WARNING the triggers of this quantifier for `i` can only contain arithmetic terms
+fn arithmetic_trigger() {}
+
+#[requires(forall(|i: u32| i < 10 ==> f(i) > 0))]
+fn function_trigger() {}
+
+#[requires(forall(|i: u32| i < 10 ==> i + 1 <= 10, triggers = [(f(i),)]))]
+fn explicit_trigger() {}
+
+#[requires(forall(|i: u32, j: u32| i < j ==> f(i) < j))] //~ WARNING no trigger can be inferred for `j` in this quantifier
+fn partial_trigger() {}
+
+fn caller() {
+    arithmetic_only();
+    explicit_trigger();
+    function_trigger(); //~ ERROR precondition might not hold
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use prusti_contracts::*;
+
+pub struct Node {
+    value: i32,
+}
+
+#[requires(forall(|n: &mut Node| n.value >= 0))] //~ ERROR quantifiers cannot bind mutable references
+fn test() {}
+
+fn main() {}
@@ -0,0 +1,55 @@
+use prusti_contracts::*;
+
+#[derive(Clone, Copy)]
+pub struct Node {
+    value: i32,
+    weight: u32,
+}
+
+pub enum Shape {
+    Circle(u32),
+    Square(u32),
+}
+
+#[pure]
+fn is_light(n: &Node) -> bool {
+    n.weight < 10
+}
+
+#[pure]
+fn size(s: &Shape) -> u32 {
+    match s {
+        Shape::Circle(r) => *r,
+        Shape::Square(a) => *a,
+    }
+}
+
+// A light node can have a negative value.
+#[requires(forall(|n: &Node| n.weight < 10 ==> is_light(n)))]
+#[ensures(forall(|n: &Node| is_light(n) ==> n.value >= 0))] //~ ERROR postcondition might not hold
+fn light_nodes() {}
+
+#[requires(forall(|n: &Node| is_light(n) ==> n.value >= 0))]
+fn use_quantified(node: Node) -> i32 {
+    if is_light(&node) {
+        node.value
+    } else {
+        0
+    }
+}
+
+fn call_use_quantified() {
+    let node = Node {
+        value: -1,
+        weight: 0,
+    };
+    use_quantified(node); //~ ERROR precondition might not hold
+}
+
+#[requires(forall(|s: &Shape| size(s) <= 100))]
+#[ensures(result <= 150)] //~ ERROR postcondition might not hold
+fn sum_sizes(a: &Shape, b: &Shape) -> u32 {
+    size(a) + size(b)
+}
+
+fn main() {}
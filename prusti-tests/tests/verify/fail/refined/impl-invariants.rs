@@ -0,0 +1,42 @@
+use prusti_contracts::*;
+
+mod range {
+    pub struct Range {
+        pub start: u32,
+        pub end: u32,
+    }
+}
+
+mod ordered {
+    use super::range::Range;
+    use prusti_contracts::*;
+
+    #[invariant(self.start <= self.end)]
+    impl Range {}
+}
+
+mod bounded {
+    use super::range::Range;
+    use prusti_contracts::*;
+
+    #[invariant(self.end <= 100)]
+    impl Range {}
+}
+
+use range::Range;
+
+fn extend(range: &mut Range) {
+    //~ ERROR type invariants might not hold at the end of the method
+    range.end += 1;
+}
+
+fn shrink(range: &mut Range) {
+    //~ ERROR type invariants might not hold at the end of the method
+    range.end -= 1;
+}
+
+fn reset(range: &mut Range) {
+    range.start = 0;
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+use prusti_contracts::*;
+
+#[refined(|x| x.0 > 0)]
+struct Positive(u32);
+
+fn positive(value: u32) -> Positive {
+    //~ ERROR type invariants might not hold at the end of the method
+    Positive(value)
+}
+
+fn decrement(x: &mut Positive) {
+    //~ ERROR type invariants might not hold at the end of the method
+    x.0 -= 1;
+}
+
+fn get(x: Positive) -> u32 {
+    x.0
+}
+
+fn main() {
+    get(Positive(0)); //~ ERROR precondition might not hold
+}
@@ -0,0 +1,23 @@
+use prusti_contracts::*;
+
+#[insert_runtime_check]
+#[requires(x > 0)]
+#[requires(x < 10)]
+fn only_first_checked(x: u32) -> u32 {
+    x
+}
+
+fn client() {
+    only_first_checked(0);
+    only_first_checked(20); //~ ERROR precondition might not hold
+}
+
+// The checks only justify the contracts, the rest of the body is verified.
+#[insert_runtime_check]
+#[ensures(result > 0)]
+fn body_verified(x: u32) -> u32 {
+    assert!(x != 5); //~ ERROR the asserted expression might not hold
+    x + 1
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use prusti_contracts::*;
+
+enum Shape {
+    Circle(u32),
+    Square(u32),
+}
+
+#[ensures(discriminant_of(shape) == old(discriminant_of(shape)))] //~ ERROR postcondition might not hold
+fn square(shape: &mut Shape) {
+    *shape = Shape::Square(1);
+}
+
+#[ensures(discriminant_of(&result) == 0)] //~ ERROR postcondition might not hold
+fn square_of(side: u32) -> Shape {
+    Shape::Square(side)
+}
+
+fn main() {}
@@ -0,0 +1,38 @@
+// Failures are reported at the operand of the specification that cannot be
+// proven, not at the whole attribute.
+use prusti_contracts::*;
+
+struct Pair {
+    a: u32,
+    b: u32,
+}
+
+#[ensures(
+    result > 0
+    && result < 100 //~ ERROR postcondition might not hold
+)]
+fn bounded(x: u32) -> u32 {
+    if x == 0 {
+        1
+    } else {
+        x
+    }
+}
+
+#[ensures(
+    result.a == old(p.a)
+    && result.b == old(p.b) //~ ERROR postcondition might not hold
+)]
+fn copy_first(p: Pair) -> Pair {
+    Pair { a: p.a, b: p.a }
+}
+
+#[ensures(
+    result.a == old(p.a)
+    && result.b == old(p.b)
+)]
+fn copy(p: Pair) -> Pair {
+    Pair { a: p.a, b: p.b }
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use prusti_contracts::*;
+
+#[symbolic(LIMIT >= 10)]
+const LIMIT: u32 = 100;
+
+#[symbolic]
+const VERBOSE: bool = true;
+
+fn uses_value() {
+    assert!(LIMIT == 100); //~ ERROR the asserted expression might not hold
+}
+
+fn uses_constraint() {
+    assert!(LIMIT >= 10);
+}
+
+#[requires(x < 10)]
+fn below_limit(x: u32) -> u32 {
+    LIMIT - x
+}
+
+fn uses_flag() {
+    assert!(VERBOSE); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
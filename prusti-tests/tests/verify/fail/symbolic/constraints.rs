@@ -0,0 +1,16 @@
+use prusti_contracts::*;
+
+// The value of a symbolic constant has to satisfy its constraint.
+#[symbolic(THRESHOLD > 10)] //~ ERROR postcondition might not hold
+const THRESHOLD: u32 = 5;
+
+#[symbolic(false)] //~ ERROR postcondition might not hold
+const NEVER: bool = true;
+
+#[symbolic(LOW < HIGH)]
+const LOW: u32 = 1;
+
+#[symbolic(HIGH <= 100)]
+const HIGH: u32 = 100;
+
+fn main() {}
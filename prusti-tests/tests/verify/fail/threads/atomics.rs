@@ -0,0 +1,26 @@
+use prusti_contracts::*;
+use std::sync::atomic::Ordering;
+
+atomic_invariant!(struct Counter(AtomicUsize), |value| value <= 10);
+
+fn increment(counter: &Counter) {
+    let value = counter.load(Ordering::SeqCst);
+    counter.store(value + 1, Ordering::SeqCst); //~ ERROR precondition might not hold
+}
+
+fn add_one(counter: &Counter) {
+    counter.fetch_add(1, Ordering::SeqCst); //~ ERROR precondition might not hold
+}
+
+fn stable(counter: &Counter) {
+    let first = counter.load(Ordering::SeqCst);
+    let second = counter.load(Ordering::SeqCst);
+    assert!(first == second); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {
+    let counter = Counter::new(0);
+    increment(&counter);
+    add_one(&counter);
+    stable(&counter);
+}
@@ -0,0 +1,17 @@
+use prusti_contracts::*;
+use std::sync::Mutex;
+
+#[lock_invariant(self.value <= 10)]
+struct Counter {
+    value: u32,
+}
+
+fn increment(counter: &Mutex<Counter>) {
+    let mut guard = counter.lock().unwrap();
+    guard.value += 1; //~ ERROR obligation might not hold on borrow expiry
+}
+
+fn main() {
+    let counter = Mutex::new(Counter { value: 11 }); //~ ERROR precondition might not hold
+    increment(&counter);
+}
@@ -0,0 +1,27 @@
+use prusti_contracts::*;
+use std::sync::{Mutex, RwLock};
+
+#[lock_invariant(self.value <= 10)]
+#[derive(Default)]
+struct Counter {
+    value: u32,
+}
+
+fn reset(counter: &mut Mutex<Counter>) {
+    let value = counter.get_mut().unwrap(); //~ ERROR calling `Mutex::get_mut` is not supported on values with a lock invariant
+    value.value = 20;
+}
+
+fn take(counter: RwLock<Counter>) -> Counter {
+    counter.into_inner().unwrap() //~ ERROR calling `RwLock::into_inner` is not supported on values with a lock invariant
+}
+
+fn create() -> Mutex<Counter> {
+    Mutex::default() //~ ERROR calling `Mutex::default` is not supported on values with a lock invariant
+}
+
+fn convert() -> Mutex<Counter> {
+    Mutex::from(Counter { value: 11 }) //~ ERROR precondition might not hold
+}
+
+fn main() {}
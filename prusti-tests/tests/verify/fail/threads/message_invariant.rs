@@ -0,0 +1,25 @@
+use prusti_contracts::*;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+#[message_invariant(self.value <= 10)]
+struct Message {
+    value: u32,
+}
+
+fn produce(sender: &Sender<Message>, value: u32) {
+    let _ = sender.send(Message { value }); //~ ERROR precondition might not hold
+}
+
+#[ensures(result <= 5)] //~ ERROR postcondition might not hold
+fn consume(receiver: &Receiver<Message>) -> u32 {
+    match receiver.recv() {
+        Ok(message) => message.value,
+        Err(_) => 0,
+    }
+}
+
+fn main() {
+    let (sender, receiver) = channel();
+    produce(&sender, 5);
+    consume(&receiver);
+}
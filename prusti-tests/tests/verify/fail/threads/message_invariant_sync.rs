@@ -0,0 +1,23 @@
+use prusti_contracts::*;
+use std::sync::mpsc::{sync_channel, SyncSender};
+
+#[message_invariant(self.value <= 10)]
+struct Message {
+    value: u32,
+}
+
+fn try_produce(sender: &SyncSender<Message>, value: u32) {
+    let _ = sender.try_send(Message { value }); //~ ERROR precondition might not hold
+}
+
+fn try_produce_checked(sender: &SyncSender<Message>, value: u32) {
+    if value <= 10 {
+        let _ = sender.try_send(Message { value });
+    }
+}
+
+fn main() {
+    let (sender, _receiver) = sync_channel(1);
+    try_produce(&sender, 5);
+    try_produce_checked(&sender, 5);
+}
@@ -0,0 +1,30 @@
+use prusti_contracts::*;
+use std::thread;
+
+#[ensures(result == 43)] //~ ERROR postcondition might not hold
+fn wrong_answer_in_thread() -> u32 {
+    let handle = thread::spawn(closure!(ensures(result == 42), || -> u32 { 6 * 7 }));
+    match handle.join() {
+        Ok(result) => result,
+        Err(_) => unreachable!(),
+    }
+}
+
+fn wrong_closure() -> u32 {
+    let handle = thread::spawn(closure!(ensures(result == 42), || -> u32 { 6 * 6 })); //~ ERROR postcondition might not hold
+    match handle.join() {
+        Ok(result) => result,
+        Err(_) => unreachable!(),
+    }
+}
+
+#[ensures(result == 42)] //~ ERROR postcondition might not hold
+fn unspecified_closure() -> u32 {
+    let handle = thread::spawn(closure!(|| -> u32 { 6 * 7 }));
+    match handle.join() {
+        Ok(result) => result,
+        Err(_) => unreachable!(),
+    }
+}
+
+fn main() {}
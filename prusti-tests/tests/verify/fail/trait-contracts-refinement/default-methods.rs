@@ -0,0 +1,50 @@
+use prusti_contracts::*;
+
+trait Counter {
+    #[pure]
+    fn value(&self) -> u32;
+
+    #[requires(self.value() < 99)]
+    #[ensures(self.value() == old(self.value()) + 2)]
+    fn increment_twice(&mut self);
+
+    #[requires(self.value() < 97)]
+    #[ensures(self.value() == old(self.value()) + 4)] //~ ERROR postcondition might not hold
+    fn increment_four_times(&mut self) {
+        self.increment_twice();
+        self.increment_twice();
+    }
+}
+
+struct Fast {
+    count: u32,
+}
+
+impl Counter for Fast {
+    #[pure]
+    fn value(&self) -> u32 {
+        self.count
+    }
+
+    fn increment_twice(&mut self) {
+        self.count += 2;
+    }
+
+    fn increment_four_times(&mut self) {
+        // originates here
+        self.count += 3;
+    }
+}
+
+fn test_default() {
+    let mut c = Fast { count: 0 };
+    c.increment_twice();
+    assert!(c.value() == 3); //~ ERROR the asserted expression might not hold
+}
+
+fn test_precondition() {
+    let mut c = Fast { count: 97 };
+    c.increment_four_times(); //~ ERROR precondition
+}
+
+fn main() {}
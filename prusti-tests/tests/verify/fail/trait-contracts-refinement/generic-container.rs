@@ -0,0 +1,50 @@
+use prusti_contracts::*;
+
+trait Weighted {
+    #[pure]
+    fn weight(&self) -> u32;
+
+    #[requires(self.weight() < 100)]
+    #[ensures(self.weight() == old(self.weight()) + 1)] //~ ERROR postcondition might not hold
+    fn grow(&mut self);
+}
+
+struct Pair<T: Weighted> {
+    first: T,
+    second: T,
+}
+
+impl<T: Weighted> Pair<T> {
+    #[requires(self.first.weight() < 100)]
+    #[ensures(self.first.weight() == old(self.first.weight()) + 2)] //~ ERROR postcondition might not hold
+    fn grow_first(&mut self) {
+        self.first.grow();
+    }
+
+    fn grow_second(&mut self) {
+        self.second.grow(); //~ ERROR precondition might not hold
+    }
+}
+
+struct Parcel {
+    size: u32,
+}
+
+impl Weighted for Parcel {
+    #[pure]
+    fn weight(&self) -> u32 {
+        self.size
+    }
+
+    fn grow(&mut self) { // originates here
+        self.size += 2;
+    }
+}
+
+fn main() {
+    let mut pair = Pair {
+        first: Parcel { size: 100 },
+        second: Parcel { size: 2 },
+    };
+    pair.grow_first(); //~ ERROR precondition might not hold
+}
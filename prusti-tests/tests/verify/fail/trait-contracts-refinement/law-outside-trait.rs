@@ -0,0 +1,15 @@
+use prusti_contracts::*;
+
+#[law]
+#[ensures(x + 0 == x)]
+fn neutral(x: i32) {} //~ ERROR a law must be declared in a trait
+
+struct Counter;
+
+impl Counter {
+    #[law]
+    #[ensures(x * 1 == x)]
+    fn unit(x: i32) {} //~ ERROR a law must be declared in a trait
+}
+
+fn main() {}
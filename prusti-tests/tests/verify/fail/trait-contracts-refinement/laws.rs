@@ -0,0 +1,86 @@
+use prusti_contracts::*;
+
+trait Monoid {
+    #[pure]
+    fn identity() -> i32;
+
+    #[pure]
+    fn op(a: i32, b: i32) -> i32;
+
+    #[law]
+    #[ensures(Self::op(Self::op(a, b), c) == Self::op(a, Self::op(b, c)))] //~ ERROR postcondition might not hold
+    fn associativity(a: i32, b: i32, c: i32);
+
+    #[law]
+    #[ensures(Self::op(Self::identity(), a) == a && Self::op(a, Self::identity()) == a)] //~ ERROR postcondition might not hold
+    fn identity_element(a: i32);
+}
+
+struct Max;
+
+impl Monoid for Max {
+    #[pure]
+    fn identity() -> i32 {
+        i32::MIN
+    }
+
+    #[pure]
+    fn op(a: i32, b: i32) -> i32 {
+        if a >= b { a } else { b }
+    }
+
+    fn associativity(a: i32, b: i32, c: i32) {}
+
+    fn identity_element(a: i32) {}
+}
+
+// Not associative, and 0 is only a left identity.
+struct Choice;
+
+impl Monoid for Choice {
+    #[pure]
+    fn identity() -> i32 {
+        0
+    }
+
+    #[pure]
+    fn op(a: i32, b: i32) -> i32 {
+        if a == 0 { b } else { 0 }
+    }
+
+    fn associativity(a: i32, b: i32, c: i32) {}
+
+    fn identity_element(a: i32) {}
+}
+
+trait Scaled {
+    #[pure]
+    fn scale(x: i32) -> i32;
+
+    // The contracts of the trait do not imply the law, so its default body
+    // fails to prove it.
+    #[law]
+    #[requires(x >= 0)]
+    #[ensures(Self::scale(x) >= 0)] //~ ERROR postcondition might not hold
+    fn preserves_sign(x: i32) {}
+}
+
+struct Identity;
+
+impl Scaled for Identity {
+    #[pure]
+    fn scale(x: i32) -> i32 {
+        x
+    }
+}
+
+struct Constant;
+
+impl Scaled for Constant {
+    #[pure]
+    fn scale(x: i32) -> i32 {
+        -1
+    }
+}
+
+fn main() {}
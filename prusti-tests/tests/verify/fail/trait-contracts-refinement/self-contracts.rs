@@ -0,0 +1,65 @@
+use prusti_contracts::*;
+
+trait Weighted {
+    const ZERO: i32;
+
+    #[pure]
+    fn weight(&self) -> i32;
+
+    #[ensures(result.weight() == Self::ZERO)] //~ ERROR postcondition might not hold
+    fn zero() -> Self;
+
+    #[ensures(result.weight() == self.weight() + other.weight() - Self::ZERO)] //~ ERROR postcondition might not hold
+    fn combine(self, other: Self) -> Self;
+}
+
+struct Mass {
+    grams: i32,
+}
+
+impl Weighted for Mass {
+    const ZERO: i32 = 0;
+
+    #[pure]
+    fn weight(&self) -> i32 {
+        self.grams
+    }
+
+    fn zero() -> Self {
+        Mass { grams: 0 }
+    }
+
+    fn combine(self, other: Self) -> Self {
+        Mass { grams: self.grams + other.grams }
+    }
+}
+
+struct Offset {
+    value: i32,
+}
+
+impl Weighted for Offset {
+    const ZERO: i32 = 10;
+
+    #[pure]
+    fn weight(&self) -> i32 {
+        self.value
+    }
+
+    // The zero of `Offset` is 10, not 0.
+    fn zero() -> Self {
+        Offset { value: 0 }
+    }
+
+    // Does not subtract the zero.
+    fn combine(self, other: Self) -> Self {
+        Offset { value: self.value + other.value }
+    }
+}
+
+fn test_mass() {
+    let zero = Mass::zero();
+    assert!(zero.weight() == 10); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
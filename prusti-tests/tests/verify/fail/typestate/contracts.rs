@@ -0,0 +1,59 @@
+use prusti_contracts::*;
+
+#[typestate(Idle, Connected)]
+struct Connection {
+    handle: u32,
+}
+
+impl Connection {
+    #[trusted]
+    #[transition(-> Idle)]
+    fn new() -> Self {
+        Connection { handle: 0 }
+    }
+
+    #[trusted]
+    #[transition(Idle -> Connected)]
+    fn connect(&mut self) {}
+
+    #[trusted]
+    #[transition(Connected)]
+    fn send(&mut self, _data: u8) {}
+
+    #[trusted]
+    #[transition(Connected -> Idle)]
+    fn disconnect(&mut self) {}
+}
+
+fn unknown_state(connection: &mut Connection) {
+    connection.connect(); //~ ERROR the method might be called in a state that its transition does not allow
+}
+
+#[requires(connection.prusti_typestate_Idle())]
+#[ensures(connection.prusti_typestate_Idle())]
+fn session(connection: &mut Connection) {
+    connection.connect();
+    connection.send(1);
+    connection.disconnect();
+}
+
+#[requires(connection.prusti_typestate_Idle())]
+#[ensures(connection.prusti_typestate_Idle())] //~ ERROR postcondition might not hold
+fn unfinished_session(connection: &mut Connection) {
+    connection.connect();
+    connection.send(1);
+}
+
+fn send_after_session() {
+    let mut connection = Connection::new();
+    session(&mut connection);
+    connection.send(2); //~ ERROR the method might be called in a state that its transition does not allow
+}
+
+fn session_when_connected() {
+    let mut connection = Connection::new();
+    connection.connect();
+    session(&mut connection); //~ ERROR precondition might not hold
+}
+
+fn main() {}
@@ -0,0 +1,44 @@
+use prusti_contracts::*;
+
+#[typestate(Idle, Connected, Authenticated)]
+struct Connection {
+    handle: u32,
+}
+
+impl Connection {
+    #[trusted]
+    #[transition(-> Idle)]
+    fn new() -> Self {
+        Connection { handle: 0 }
+    }
+
+    #[trusted]
+    #[transition(Idle -> Connected)]
+    fn connect(&mut self) {}
+
+    #[trusted]
+    #[transition(Connected -> Authenticated)]
+    fn login(&mut self) {}
+
+    #[trusted]
+    #[transition(Authenticated)]
+    fn send(&mut self, _data: u8) {}
+}
+
+#[typestate(Closed = !self.open, Open = self.open)]
+struct File {
+    open: bool,
+}
+
+impl File {
+    #[transition(Closed -> Open)] //~ ERROR postcondition might not hold
+    fn open(&mut self) {}
+}
+
+fn main() {
+    let mut connection = Connection::new();
+    connection.connect();
+    connection.send(1); //~ ERROR the method might be called in a state that its transition does not allow
+    let mut other = Connection::new();
+    other.login(); //~ ERROR the method might be called in a state that its transition does not allow
+}
@@ -0,0 +1,31 @@
+use prusti_contracts::*;
+
+struct Cursor {
+    offset: usize,
+    len: usize,
+    capacity: usize,
+}
+
+impl Cursor {
+    #[requires(self.len < self.capacity)]
+    #[ensures(unchanged!(self except self.len))] //~ ERROR postcondition might not hold
+    fn push(&mut self) {
+        self.len += 1;
+        self.capacity -= 1;
+    }
+
+    #[ensures(unchanged!(self))] //~ ERROR postcondition might not hold
+    fn reset(&mut self) {
+        self.offset = 0;
+    }
+}
+
+#[requires(cursor.len < cursor.capacity)]
+fn client(cursor: &mut Cursor) {
+    let offset = cursor.offset;
+    cursor.push();
+    assert!(cursor.offset == offset);
+    assert!(cursor.len == 0); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
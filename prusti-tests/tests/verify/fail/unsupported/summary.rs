@@ -0,0 +1,20 @@
+// compile-flags: -Punsupported_features_summary=true -Pquiet=false
+// error-pattern: Summary of unsupported features
+// error-pattern: raw pointers
+// error-pattern: unions
+// error-pattern: Occurrences:
+// error-pattern: [raw pointers]
+// error-pattern: [unions]
+
+union Foo {
+    a: [i32; 1],
+    b: [i32; 1],
+}
+
+fn is_null(p: *const i32) -> bool {
+    p.is_null()
+}
+
+fn main() {
+    let _ = Foo { a: [0] };
+}
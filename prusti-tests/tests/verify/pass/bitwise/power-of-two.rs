@@ -0,0 +1,35 @@
+use prusti_contracts::*;
+
+const CAPACITY: usize = 16;
+const MASK: usize = CAPACITY - 1;
+
+#[bitwise_arithmetic]
+#[ensures(result < CAPACITY)]
+#[ensures(result == index % CAPACITY)]
+fn wrap_index(index: usize) -> usize {
+    index & MASK
+}
+
+#[bitwise_arithmetic]
+#[ensures(result == value / 8)]
+fn eighth(value: u32) -> u32 {
+    value >> 3
+}
+
+#[bitwise_arithmetic]
+#[requires(value < 1000)]
+#[ensures(result == value * 4)]
+fn quadruple(value: u32) -> u32 {
+    value << 2
+}
+
+#[pure]
+#[bitwise_arithmetic]
+fn low_byte(value: u64) -> u64 {
+    0xff & value
+}
+
+#[ensures(low_byte(value) < 256)]
+fn check_low_byte(value: u64) {}
+
+fn main() {}
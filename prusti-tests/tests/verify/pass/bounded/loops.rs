@@ -0,0 +1,58 @@
+use prusti_contracts::*;
+
+#[bounded(3)]
+#[requires(n <= 3)]
+#[ensures(result == 2 * n)]
+fn double(n: u32) -> u32 {
+    let mut i = 0;
+    let mut result = 0;
+    while i < n {
+        result += 2;
+        i += 1;
+    }
+    result
+}
+
+#[bounded(4)]
+#[requires(n <= 4)]
+#[ensures(result == n * n)]
+fn square(n: u32) -> u32 {
+    let mut result = 0;
+    let mut i = 0;
+    while i < n {
+        let mut j = 0;
+        while j < n {
+            result += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    result
+}
+
+#[bounded(2)]
+fn first_even(a: u32, b: u32) -> u32 {
+    let mut i = 0;
+    let mut found = a;
+    loop {
+        if found % 2 == 0 || i == 1 {
+            break;
+        }
+        found = b;
+        i += 1;
+    }
+    found
+}
+
+// The executions that iterate more often are not verified.
+#[bounded(2)]
+#[ensures(result < 3)]
+fn count(n: u32) -> u32 {
+    let mut i = 0;
+    while i < n {
+        i += 1;
+    }
+    i
+}
+
+fn main() {}
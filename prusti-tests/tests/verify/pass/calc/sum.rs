@@ -0,0 +1,37 @@
+// compile-flags: -Pcheck_overflows=false
+
+use prusti_contracts::*;
+
+#[pure]
+#[requires(n <= 1000)]
+fn sum(n: u32) -> u32 {
+    if n == 0 {
+        0
+    } else {
+        n + sum(n - 1)
+    }
+}
+
+#[lemma]
+#[requires(n <= 1000)]
+#[ensures(2 * sum(n) == n * (n + 1))]
+fn sum_closed_form(n: u32) {
+    if n > 0 {
+        sum_closed_form(n - 1);
+    }
+}
+
+#[requires(1 <= n && n <= 1000)]
+#[ensures(2 * result == n * (n + 1))]
+fn triangle(n: u32) -> u32 {
+    prusti_calc! {
+        sum(n) == n + sum(n - 1)
+        ==> { apply_lemma!(sum_closed_form(n - 1)); }
+        2 * sum(n - 1) == (n - 1) * n
+        ==> {}
+        2 * sum(n) == n * (n + 1)
+    }
+    sum(n)
+}
+
+fn main() {}
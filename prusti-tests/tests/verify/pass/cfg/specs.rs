@@ -0,0 +1,47 @@
+use prusti_contracts::*;
+
+// `all()` always holds, `any()` never holds.
+
+#[cfg_attr(all(), requires(x < 100))]
+#[cfg_attr(any(), requires(x < 10))]
+#[ensures(result > x)]
+fn increment(x: u32) -> u32 {
+    x + 1
+}
+
+trait Value {
+    fn value(&self) -> u32;
+
+    fn double(&self) -> u32;
+}
+
+struct Five;
+
+#[refine_trait_spec]
+impl Value for Five {
+    #[cfg_attr(all(), ensures(result == 5))]
+    #[cfg_attr(any(), ensures(result == 6))]
+    fn value(&self) -> u32 {
+        5
+    }
+
+    #[cfg(all())]
+    #[ensures(result == 10)]
+    fn double(&self) -> u32 {
+        10
+    }
+
+    #[cfg(any())]
+    #[ensures(result == 12)]
+    fn double(&self) -> u32 {
+        12
+    }
+}
+
+fn test() {
+    assert!(increment(50) > 50);
+    assert!(Five.value() == 5);
+    assert!(Five.double() == 10);
+}
+
+fn main() {}
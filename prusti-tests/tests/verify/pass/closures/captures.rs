@@ -0,0 +1,45 @@
+use prusti_contracts::*;
+
+struct Counter {
+    value: u32,
+}
+
+fn by_shared_reference(counter: &Counter) -> u32 {
+    let read = || counter.value;
+    read()
+}
+
+fn by_mutable_reference() {
+    let mut counter = Counter { value: 5 };
+    let mut reset = || {
+        counter.value = 0;
+    };
+    reset();
+    reset();
+}
+
+fn shared_capture_is_unchanged() {
+    let counter = Counter { value: 5 };
+    let read = || counter.value;
+    read();
+    read();
+    assert!(counter.value == 5);
+}
+
+fn by_value(counter: Counter) -> u32 {
+    let consume = move || counter.value;
+    consume()
+}
+
+#[ensures(result == 0)]
+fn mutated_after_expiry() -> u32 {
+    let mut counter = Counter { value: 5 };
+    let mut reset = || {
+        counter.value = 0;
+    };
+    reset();
+    counter.value = 0;
+    counter.value
+}
+
+fn main() {}
@@ -0,0 +1,51 @@
+use prusti_contracts::*;
+
+binary_heap!(struct MaxHeap(u32));
+binary_heap!(struct MinHeap(Reverse<usize>));
+
+#[requires(heap.len() < 1000)]
+#[ensures(heap.len() == old(heap.len()) + 2)]
+#[ensures(heap.count(value) == old(heap.count(value)) + 2)]
+fn push_twice(heap: &mut MaxHeap, value: u32) {
+    heap.push(value);
+    heap.push(value);
+}
+
+#[ensures(match result {
+    Some(value) => value == old(heap.top()),
+    None => old(heap.is_empty()),
+})]
+fn pop_max(heap: &mut MaxHeap) -> Option<u32> {
+    heap.pop()
+}
+
+#[requires(heap.len() > 0)]
+#[ensures(result <= distance)]
+fn shortest(heap: &MinHeap, distance: usize) -> usize {
+    let closest = heap.top();
+    if heap.count(distance) > 0 {
+        assert!(closest <= distance);
+        closest
+    } else {
+        distance
+    }
+}
+
+fn main() {
+    let mut heap = MaxHeap::new();
+    heap.push(2);
+    heap.push(5);
+    assert!(heap.top() == 5);
+    assert!(heap.count(2) == 1 && heap.count(4) == 0);
+    match heap.pop() {
+        Some(value) => assert!(value == 5),
+        None => unreachable!(),
+    }
+    assert!(heap.len() == 1 && heap.top() == 2);
+
+    let mut frontier = MinHeap::new();
+    frontier.push(10);
+    frontier.push(3);
+    assert!(frontier.peek() == Some(3));
+    assert!(shortest(&frontier, 4) <= 4);
+}
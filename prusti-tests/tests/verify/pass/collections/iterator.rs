@@ -0,0 +1,42 @@
+use prusti_contracts::*;
+
+iterator!(pub struct Numbers(u32), pub struct NumberPairs);
+
+#[requires(value <= 1000)]
+#[ensures(result == 2 * value)]
+fn sum_of_two(value: u32) -> u32 {
+    let mut numbers = Numbers::repeat(value, 5).take(2);
+    let mut sum = 0;
+    if let Some(number) = numbers.next() {
+        sum += number;
+    }
+    if let Some(number) = numbers.next() {
+        sum += number;
+    }
+    assert!(numbers.next().is_none());
+    sum
+}
+
+fn first_pair() {
+    let mut pairs = Numbers::once(1).chain(Numbers::repeat(2, 3)).zip(Numbers::repeat(7, 2));
+    assert!(pairs.len() == 2);
+    match pairs.next() {
+        Some((first, second)) => assert!(first == 1 && second == 7),
+        None => unreachable!(),
+    }
+    match pairs.next() {
+        Some((first, second)) => assert!(first == 2 && second == 7),
+        None => unreachable!(),
+    }
+    assert!(pairs.next().is_none());
+}
+
+fn skipped() {
+    let mut numbers = Numbers::repeat(4, 2).skip(5);
+    assert!(numbers.len() == 0);
+    assert!(numbers.next().is_none());
+    let empty = Numbers::empty();
+    assert!(empty.len() == 0);
+}
+
+fn main() {}
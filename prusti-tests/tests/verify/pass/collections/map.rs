@@ -0,0 +1,50 @@
+use prusti_contracts::*;
+use std::collections::{BTreeMap, HashMap};
+
+map_specs!();
+
+#[requires(ages.len() < 1000)]
+#[ensures(ages.contains_key(&person))]
+#[ensures(!old(ages.contains_key(&person)) ==> ages.len() == old(ages.len()) + 1)]
+#[ensures(forall(|other: u32| other != person ==>
+    ages.contains_key(&other) == old(ages.contains_key(&other))))]
+fn record(ages: &mut HashMap<u32, String>, person: u32, name: String) {
+    let _ = ages.insert(person, name);
+}
+
+#[ensures(result == old(ages.contains_key(&person)))]
+#[ensures(!ages.contains_key(&person))]
+#[ensures(forall(|other: u32| other != person ==>
+    ages.contains_key(&other) == old(ages.contains_key(&other))))]
+fn forget(ages: &mut HashMap<u32, String>, person: u32) -> bool {
+    match ages.remove(&person) {
+        Some(_) => true,
+        None => false,
+    }
+}
+
+#[ensures(result == visited.contains_key(&node))]
+fn is_visited(visited: &BTreeMap<usize, Vec<usize>>, node: usize) -> bool {
+    match visited.get(&node) {
+        Some(_) => true,
+        None => false,
+    }
+}
+
+fn main() {
+    let mut ages = HashMap::new();
+    assert!(ages.is_empty());
+    record(&mut ages, 1, String::new());
+    record(&mut ages, 2, String::new());
+    assert!(ages.contains_key(&1) && ages.contains_key(&2));
+    assert!(!ages.contains_key(&3));
+    assert!(ages.len() == 2);
+    assert!(forget(&mut ages, 2));
+    assert!(!forget(&mut ages, 2));
+    assert!(ages.contains_key(&1));
+
+    let mut visited = BTreeMap::new();
+    let _ = visited.insert(0, vec![]);
+    assert!(is_visited(&visited, 0));
+    assert!(!is_visited(&visited, 1));
+}
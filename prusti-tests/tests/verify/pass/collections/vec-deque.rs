@@ -0,0 +1,37 @@
+use prusti_contracts::*;
+
+vec_deque!(struct Queue(usize));
+
+#[requires(queue.len() < 1000)]
+#[ensures(queue.len() == old(queue.len()))]
+#[ensures(queue.len() > 0 ==> queue.lookup(queue.len() - 1) == old(queue.lookup(0)))]
+fn rotate(queue: &mut Queue) {
+    if let Some(value) = queue.pop_front() {
+        queue.push_back(value);
+    }
+}
+
+#[requires(frontier.len() < 1000)]
+#[ensures(frontier.len() == old(frontier.len()) + 1)]
+#[ensures(frontier.lookup(frontier.len() - 1) == node)]
+#[ensures(forall(|i: usize| i < old(frontier.len()) ==>
+    frontier.lookup(i) == old(frontier.lookup(i))))]
+fn enqueue(frontier: &mut Queue, node: usize) {
+    frontier.push_back(node);
+}
+
+fn main() {
+    let mut frontier = Queue::new();
+    enqueue(&mut frontier, 4);
+    enqueue(&mut frontier, 7);
+    assert!(frontier.lookup(0) == 4 && frontier.lookup(1) == 7);
+    rotate(&mut frontier);
+    assert!(frontier.lookup(1) == 4);
+    frontier.push_front(1);
+    assert!(frontier.get(0) == Some(1) && frontier.len() == 3);
+    match frontier.pop_back() {
+        Some(node) => assert!(node == 4),
+        None => unreachable!(),
+    }
+    assert!(frontier.len() == 2);
+}
@@ -0,0 +1,50 @@
+#![feature(allocator_api)]
+
+use prusti_contracts::*;
+
+vec_specs!();
+
+#[requires(values.len() == 3)]
+#[requires(vec_lookup(values, 0) == 1 && vec_lookup(values, 1) == 2)]
+#[requires(vec_lookup(values, 2) == 3)]
+#[ensures(values.len() == 2)]
+#[ensures(vec_lookup(values, 0) == 3 && vec_lookup(values, 1) == 2)]
+#[ensures(result == 1)]
+fn remove_first(values: &mut Vec<u32>) -> u32 {
+    values.swap_remove(0)
+}
+
+#[ensures(values.len() <= 2)]
+#[ensures(forall(|i: usize| i < values.len() ==>
+    vec_lookup(values, i) == old(vec_lookup(values, i))))]
+fn keep_two(values: &mut Vec<u32>) {
+    values.truncate(2);
+}
+
+#[requires(values.len() == 2)]
+#[requires(vec_lookup(values, 0) == 4 && vec_lookup(values, 1) == 4)]
+#[ensures(forall(|i: usize| i < values.len() ==> vec_lookup(values, i) == 4))]
+fn keep_even(values: &mut Vec<u32>) {
+    values.retain(|value| value % 2 == 0);
+}
+
+fn main() {
+    let mut values = Vec::new();
+    values.push(1);
+    values.push(2);
+    values.extend_from_slice(&[3, 4]);
+    assert!(values.len() == 4 && vec_lookup(&values, 2) == 3);
+    let mut more = Vec::new();
+    more.push(5);
+    values.append(&mut more);
+    assert!(values.len() == 5 && vec_lookup(&values, 4) == 5);
+    assert!(more.is_empty());
+    match values.pop() {
+        Some(value) => assert!(value == 5),
+        None => unreachable!(),
+    }
+    values.truncate(10);
+    assert!(values.len() == 4 && vec_lookup(&values, 0) == 1);
+    values.clear();
+    assert!(values.is_empty());
+}
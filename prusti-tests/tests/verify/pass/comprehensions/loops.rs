@@ -0,0 +1,53 @@
+use prusti_contracts::*;
+
+#[ensures(result == sum(0..n, |i: usize| i))]
+fn sum_up_to(n: usize) -> usize {
+    let mut result = 0;
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n);
+        body_invariant!(result == sum(0..i, |k: usize| k));
+        result += i;
+        i += 1;
+    }
+    result
+}
+
+#[ensures(result == count(0..n, |i: usize| i % 2 == 0))]
+#[ensures(result <= n)]
+fn count_even(n: usize) -> usize {
+    let mut result = 0;
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n);
+        body_invariant!(result == count(0..i, |k: usize| k % 2 == 0));
+        if i % 2 == 0 {
+            result += 1;
+        }
+        i += 1;
+    }
+    result
+}
+
+#[requires(0 < n)]
+#[ensures(result == max(0..n, |i: u32| 7 * i % 5))]
+fn max_residue(n: u32) -> u32 {
+    let mut result = 0;
+    let mut i = 1;
+    while i < n {
+        body_invariant!(0 < i && i < n);
+        body_invariant!(result == max(0..i, |k: u32| 7 * k % 5));
+        let value = 7 * i % 5;
+        if value > result {
+            result = value;
+        }
+        i += 1;
+    }
+    result
+}
+
+#[ensures(sum(3..3, |i: usize| i * i) == 0)]
+#[ensures(sum(3..4, |i: usize| i * i) == 9)]
+fn small_ranges() {}
+
+fn main() {}
@@ -0,0 +1,59 @@
+use prusti_contracts::*;
+use std::convert::{TryFrom, TryInto};
+
+#[derive(Clone, Copy)]
+struct Celsius(i32);
+
+#[derive(Clone, Copy)]
+struct Percentage(u8);
+
+impl From<Celsius> for i64 {
+    #[ensures(result == celsius.0 as i64)]
+    fn from(celsius: Celsius) -> i64 {
+        celsius.0 as i64
+    }
+}
+
+impl TryFrom<u32> for Percentage {
+    type Error = u32;
+
+    #[ensures(match result {
+        Ok(percentage) => value <= 100 && percentage.0 as u32 == value,
+        Err(error) => value > 100 && error == value,
+    })]
+    fn try_from(value: u32) -> Result<Percentage, u32> {
+        if value <= 100 {
+            Ok(Percentage(value as u8))
+        } else {
+            Err(value)
+        }
+    }
+}
+
+#[ensures(result == temperature.0 as i64)]
+fn widen(temperature: Celsius) -> i64 {
+    temperature.into()
+}
+
+#[ensures(result == temperature.0 as i64)]
+fn widen_with_from(temperature: Celsius) -> i64 {
+    i64::from(temperature)
+}
+
+#[requires(value <= 100)]
+#[ensures(result.0 as u32 == value)]
+fn checked(value: u32) -> Percentage {
+    match value.try_into() {
+        Ok(percentage) => percentage,
+        Err(_) => unreachable!(),
+    }
+}
+
+fn rejected() {
+    match Percentage::try_from(150) {
+        Ok(_) => unreachable!(),
+        Err(error) => assert!(error == 150),
+    }
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+// compile-flags: -Pcoverage_report=true
+use prusti_contracts::*;
+
+mod counter {
+    use prusti_contracts::*;
+
+    #[ensures(result == x + 1)]
+    pub fn increment(x: i32) -> i32 {
+        x + 1
+    }
+
+    #[trusted]
+    #[ensures(result >= 0)]
+    pub fn random() -> i32 {
+        4
+    }
+}
+
+fn main() {
+    let x = counter::random();
+    assert!(counter::increment(x) > 0);
+}
@@ -0,0 +1,35 @@
+// compile-flags: -Preport_dead_specs=true
+
+use prusti_contracts::*;
+
+// The contracts of trusted functions that are called from verified code,
+// from specifications or from pure functions are used.
+#[trusted]
+#[ensures(result >= x)]
+fn at_least(x: u32) -> u32 {
+    x
+}
+
+#[pure]
+#[trusted]
+#[ensures(result == a || result == b)]
+fn pick(a: u32, b: u32) -> u32 {
+    a
+}
+
+#[ensures(result >= x)]
+fn call(x: u32) -> u32 {
+    at_least(x)
+}
+
+#[requires(pick(x, x) == x)]
+fn spec_use(x: u32) {}
+
+// Trusted functions without a contract are not reported.
+#[trusted]
+fn log(_x: u32) {}
+
+fn main() {
+    log(call(1));
+    spec_use(2);
+}
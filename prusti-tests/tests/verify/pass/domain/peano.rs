@@ -0,0 +1,21 @@
+use prusti_contracts::*;
+
+#[domain]
+mod peano {
+    use prusti_contracts::*;
+
+    pub fn zero() -> u32;
+    pub fn succ(n: u32) -> u32;
+    pub fn add(a: u32, b: u32) -> u32;
+
+    axiom! { add_zero: forall(|a: u32| add(a, zero()) == a) }
+    axiom! { add_succ: forall(|a: u32, b: u32| add(a, succ(b)) == succ(add(a, b))) }
+}
+
+#[ensures(peano::add(x, peano::zero()) == x)]
+fn add_zero(x: u32) {}
+
+#[ensures(peano::add(x, peano::succ(peano::zero())) == peano::succ(x))]
+fn add_one(x: u32) {}
+
+fn main() {}
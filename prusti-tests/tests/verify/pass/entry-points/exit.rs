@@ -0,0 +1,18 @@
+use prusti_contracts::*;
+
+#[ensures(result > 0)]
+fn checked_input(value: u32) -> u32 {
+    if value == 0 {
+        std::process::exit(1);
+    }
+    value
+}
+
+fn main() {
+    let value = checked_input(3);
+    assert!(value > 0);
+    if value > 10 {
+        std::process::abort();
+    }
+    assert!(value <= 10);
+}
@@ -0,0 +1,34 @@
+use prusti_contracts::*;
+
+#[ensures(match result {
+    Ok(half) => value % 2 == 0 && half * 2 == value,
+    Err(odd) => value % 2 == 1 && odd == value,
+})]
+fn halve(value: u32) -> Result<u32, u32> {
+    if value % 2 == 0 { Ok(value / 2) } else { Err(value) }
+}
+
+#[ensures(match result {
+    Ok(quarter) => quarter * 4 == value,
+    Err(_) => true,
+})]
+fn quarter(value: u32) -> Result<u32, u32> {
+    let half = halve(value)?;
+    halve(half)
+}
+
+#[ensures(match result {
+    Some(previous) => value > 0 && previous == value - 1,
+    None => value == 0,
+})]
+fn predecessor(value: u32) -> Option<u32> {
+    let nonzero = if value > 0 { Some(value) } else { None };
+    Some(nonzero? - 1)
+}
+
+fn main() -> Result<(), u32> {
+    let value = quarter(8)?;
+    assert!(value == 2);
+    assert!(predecessor(value) == Some(1));
+    Ok(())
+}
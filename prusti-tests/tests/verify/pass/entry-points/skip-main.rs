@@ -0,0 +1,5 @@
+// compile-flags: -Pverify_entry_points=false
+
+fn main() {
+    assert!(std::env::args().count() == 1);
+}
@@ -0,0 +1,29 @@
+// compile-flags: -Pdump_foldunfold_state=true
+use prusti_contracts::*;
+
+enum List {
+    Nil,
+    Cons(u32, Box<List>),
+}
+
+#[pure]
+fn head(list: &List) -> u32 {
+    match list {
+        List::Nil => 0,
+        List::Cons(value, _) => *value,
+    }
+}
+
+#[ensures(head(list) == value)]
+fn set_head(list: &mut List, value: u32) {
+    match list {
+        List::Nil => *list = List::Cons(value, Box::new(List::Nil)),
+        List::Cons(head, _) => *head = value,
+    }
+}
+
+fn main() {
+    let mut list = List::Nil;
+    set_head(&mut list, 3);
+    assert!(head(&list) == 3);
+}
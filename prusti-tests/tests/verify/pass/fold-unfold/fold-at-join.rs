@@ -0,0 +1,59 @@
+// compile-flags: -Pfoldunfold_fold_at_join=true
+use prusti_contracts::*;
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+struct Segment {
+    start: Point,
+    end: Point,
+}
+
+#[pure]
+fn width(s: &Segment) -> i32 {
+    s.end.x - s.start.x
+}
+
+#[requires(s.start.x <= s.end.x)]
+#[ensures(width(s) >= 0)]
+#[ensures(s.end.y == s.start.y)]
+fn flatten(s: &mut Segment, b: bool, c: u32) {
+    if b {
+        s.start.y = 0;
+    } else {
+        assert!(width(s) >= 0);
+    }
+    match c {
+        0 => s.end.y = 1,
+        1 => assert!(width(s) >= 0),
+        _ => {}
+    }
+    s.end.y = s.start.y;
+}
+
+#[requires(s.start.x <= s.end.x)]
+fn shift(mut s: Segment, n: u32) -> Segment {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(s.start.x <= s.end.x);
+        if i % 2 == 0 {
+            s.start.y += 1;
+        } else {
+            assert!(width(&s) >= 0);
+        }
+        i += 1;
+    }
+    s
+}
+
+fn main() {
+    let mut s = Segment {
+        start: Point { x: 0, y: 0 },
+        end: Point { x: 2, y: 3 },
+    };
+    flatten(&mut s, true, 0);
+    assert!(s.end.y == s.start.y);
+    shift(s, 3);
+}
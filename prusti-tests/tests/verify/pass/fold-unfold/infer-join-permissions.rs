@@ -0,0 +1,56 @@
+// compile-flags: -Pfoldunfold_infer_join_permissions=true
+use prusti_contracts::*;
+
+struct Counter {
+    value: u32,
+    steps: u32,
+}
+
+struct Pair {
+    left: Counter,
+    right: Counter,
+}
+
+#[pure]
+fn total(p: &Pair) -> u32 {
+    p.left.value + p.right.value
+}
+
+#[requires(n < 100)]
+fn count(p: &mut Pair, n: u32) {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n);
+        if i % 2 == 0 {
+            p.left.steps = i;
+        } else {
+            let t = total(p);
+            p.right.steps = t;
+        }
+        i += 1;
+    }
+    let t = total(p);
+    p.left.value = t;
+}
+
+#[requires(p.left.value < 100 && p.right.value < 100)]
+#[ensures(total(p) == old(total(p)))]
+fn swap(p: &mut Pair, b: bool) {
+    if b {
+        let value = p.left.value;
+        p.left.value = p.right.value;
+        p.right.value = value;
+    } else {
+        assert!(total(p) == p.left.value + p.right.value);
+    }
+}
+
+fn main() {
+    let mut p = Pair {
+        left: Counter { value: 1, steps: 0 },
+        right: Counter { value: 2, steps: 0 },
+    };
+    swap(&mut p, true);
+    assert!(total(&p) == 3);
+    count(&mut p, 3);
+}
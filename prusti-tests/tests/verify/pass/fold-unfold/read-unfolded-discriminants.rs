@@ -0,0 +1,52 @@
+// compile-flags: -Pfoldunfold_read_unfolded_discriminants=true
+use prusti_contracts::*;
+
+enum Shape {
+    Circle(u32),
+    Rectangle(u32, u32),
+    Empty,
+}
+
+#[pure]
+fn is_empty(shape: &Shape) -> bool {
+    match shape {
+        Shape::Empty => true,
+        _ => false,
+    }
+}
+
+#[requires(!is_empty(shape))]
+#[ensures(!is_empty(shape))]
+fn grow(shape: &mut Shape) {
+    match shape {
+        Shape::Circle(radius) => *radius += 1,
+        Shape::Rectangle(width, _) => {
+            *width += 1;
+            if let Shape::Rectangle(_, height) = shape {
+                *height += 1;
+            }
+        }
+        Shape::Empty => unreachable!(),
+    }
+}
+
+#[ensures(is_empty(shape) == old(is_empty(shape)))]
+fn scale(shape: &mut Shape, factor: u32) {
+    if let Shape::Circle(radius) = shape {
+        *radius *= factor;
+    }
+    match shape {
+        Shape::Rectangle(width, height) => {
+            *width *= factor;
+            *height *= factor;
+        }
+        _ => {}
+    }
+}
+
+fn main() {
+    let mut shape = Shape::Rectangle(1, 2);
+    grow(&mut shape);
+    scale(&mut shape, 2);
+    assert!(!is_empty(&shape));
+}
@@ -0,0 +1,50 @@
+//! Reading nested enums through shared references only requires read
+//! permissions when they are unfolded and folded back.
+
+use prusti_contracts::*;
+
+enum Inner {
+    Leaf(u32),
+    Pair(u32, u32),
+}
+
+enum Outer {
+    Empty,
+    Wrapped(Inner),
+    Both(Inner, Option<Inner>),
+}
+
+fn inner_sum(inner: &Inner) -> u64 {
+    match inner {
+        Inner::Leaf(a) => *a as u64,
+        Inner::Pair(a, b) => *a as u64 + *b as u64,
+    }
+}
+
+fn outer_sum(outer: &Outer) -> u64 {
+    match outer {
+        Outer::Empty => 0,
+        Outer::Wrapped(Inner::Leaf(a)) => *a as u64,
+        Outer::Wrapped(inner) => inner_sum(inner),
+        Outer::Both(first, Some(Inner::Pair(a, _))) => inner_sum(first) + *a as u64,
+        Outer::Both(first, _) => inner_sum(first),
+    }
+}
+
+fn read_twice(outer: &Outer) -> bool {
+    let x = outer_sum(outer);
+    let y = outer_sum(outer);
+    x == y
+}
+
+fn borrow_shared(outer: &mut Outer) -> u64 {
+    let shared = &*outer;
+    let x = outer_sum(shared);
+    *outer = Outer::Empty;
+    x
+}
+
+fn main() {
+    let outer = Outer::Both(Inner::Leaf(1), Some(Inner::Pair(2, 3)));
+    read_twice(&outer);
+}
@@ -0,0 +1,44 @@
+// compile-flags: -Pauto_frame_conditions=true
+
+use prusti_contracts::*;
+
+struct Counter {
+    count: u32,
+    limit: u32,
+    enabled: bool,
+}
+
+impl Counter {
+    #[requires(self.count < self.limit)]
+    #[ensures(self.count == old(self.count) + 1)]
+    fn increment(&mut self) {
+        self.count += 1;
+    }
+
+    fn disable(&mut self) {
+        self.enabled = false;
+    }
+
+    fn set_limit(&mut self, limit: u32) {
+        let limit_ref = &mut self.limit;
+        *limit_ref = limit;
+    }
+}
+
+#[requires(counter.count < counter.limit)]
+fn client(counter: &mut Counter) {
+    let count = counter.count;
+    let limit = counter.limit;
+    let enabled = counter.enabled;
+    counter.increment();
+    assert!(counter.count == count + 1);
+    assert!(counter.limit == limit);
+    assert!(counter.enabled == enabled);
+    counter.disable();
+    assert!(counter.count == count + 1);
+    assert!(counter.limit == limit);
+    counter.set_limit(10);
+    assert!(counter.count == count + 1);
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+// compile-flags: -Pfunctional_correctness=false
+
+use prusti_contracts::*;
+
+// The postcondition is wrong, but it is not checked.
+#[ensures(result == a)]
+fn max(a: u32, b: u32) -> u32 {
+    if a < b { b } else { a }
+}
+
+#[pure]
+#[ensures(result >= a)]
+fn pure_max(a: u32, b: u32) -> u32 {
+    if a < b { b } else { a }
+}
+
+#[requires(divisor > 0)]
+fn divide(value: u32, divisor: u32) -> u32 {
+    value / divisor
+}
+
+fn main() {
+    let value = max(3, 5);
+    // Pure functions keep their postconditions.
+    assert!(pure_max(value, 2) >= value);
+    divide(value, 1);
+}
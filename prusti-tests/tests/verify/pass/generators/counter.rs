@@ -0,0 +1,17 @@
+// compile-flags: -Penable_generators=true
+
+#![feature(generators)]
+
+use prusti_contracts::*;
+
+fn main() {
+    let _counter = || {
+        let mut count = 0u32;
+        yield_invariant!(count <= 10);
+        while count < 10 {
+            body_invariant!(count < 10);
+            count += 1;
+            yield count;
+        }
+    };
+}
@@ -0,0 +1,20 @@
+// compile-flags: -Penable_generators=true
+
+#![feature(generators)]
+
+use prusti_contracts::*;
+
+fn main() {
+    // Keeps the largest input up to 100 that it was resumed with.
+    let _clamped_max = |mut input: u32| {
+        let mut max = 0u32;
+        yield_invariant!(max <= 100);
+        loop {
+            body_invariant!(max <= 100);
+            if input <= 100 && input > max {
+                max = input;
+            }
+            input = yield max;
+        }
+    };
+}
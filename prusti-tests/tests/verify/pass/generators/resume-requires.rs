@@ -0,0 +1,26 @@
+// compile-flags: -Penable_generators=true
+
+#![feature(generators, generator_trait)]
+
+use prusti_contracts::*;
+use std::ops::Generator;
+use std::pin::Pin;
+
+fn main() {
+    // Keeps the largest input that it was resumed with.
+    let mut max = |mut input: u32| {
+        resume_requires!(input <= 100);
+        let mut max = 0u32;
+        yield_invariant!(max <= 100);
+        loop {
+            body_invariant!(max <= 100 && input <= 100);
+            if input > max {
+                max = input;
+            }
+            input = yield max;
+        }
+    };
+    let _ = Pin::new(&mut max).resume(5);
+    let limit = 100;
+    let _ = Pin::new(&mut max).resume(limit);
+}
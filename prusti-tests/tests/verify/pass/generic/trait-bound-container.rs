@@ -0,0 +1,55 @@
+use prusti_contracts::*;
+
+trait Weighted {
+    #[pure]
+    fn weight(&self) -> u32;
+
+    #[requires(self.weight() < 100)]
+    #[ensures(self.weight() == old(self.weight()) + 1)]
+    fn grow(&mut self);
+}
+
+struct Pair<T: Weighted> {
+    first: T,
+    second: T,
+}
+
+impl<T: Weighted> Pair<T> {
+    #[pure]
+    #[requires(self.first.weight() <= 1000 && self.second.weight() <= 1000)]
+    fn total(&self) -> u32 {
+        self.first.weight() + self.second.weight()
+    }
+
+    #[requires(self.first.weight() < 100)]
+    #[ensures(self.first.weight() == old(self.first.weight()) + 1)]
+    #[ensures(self.second.weight() == old(self.second.weight()))]
+    fn grow_first(&mut self) {
+        self.first.grow();
+    }
+}
+
+struct Parcel {
+    size: u32,
+}
+
+impl Weighted for Parcel {
+    #[pure]
+    fn weight(&self) -> u32 {
+        self.size
+    }
+
+    fn grow(&mut self) {
+        self.size += 1;
+    }
+}
+
+fn main() {
+    let mut pair = Pair {
+        first: Parcel { size: 1 },
+        second: Parcel { size: 2 },
+    };
+    pair.grow_first();
+    assert!(pair.first.weight() == 2);
+    assert!(pair.total() == 4);
+}
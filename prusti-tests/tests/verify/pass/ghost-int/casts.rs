@@ -0,0 +1,45 @@
+use prusti_contracts::*;
+
+#[pure]
+fn sum(a: u64, b: u64) -> Int {
+    Int::new(a) + Int::new(b)
+}
+
+#[pure]
+#[requires(sum(a, b) <= Int::new(u64::MAX))]
+fn checked_sum(a: u64, b: u64) -> u64 {
+    sum(a, b).to()
+}
+
+#[pure]
+#[requires(Int::new(a) * Int::new(b) <= Int::new(u32::MAX))]
+fn product(a: u32, b: u32) -> u32 {
+    (Int::new(a) * Int::new(b)).to()
+}
+
+#[pure]
+#[requires(Nat::new(b) <= Nat::new(a))]
+fn difference(a: u32, b: u32) -> Nat {
+    Nat::new(a) - Nat::new(b)
+}
+
+#[pure]
+fn negate(x: i32) -> Int {
+    -Int::new(x)
+}
+
+#[requires(Int::new(1u8) <= sum(a, b) && sum(a, b) <= Int::new(u64::MAX) + Int::new(1u8))]
+#[ensures(Int::new(result) == sum(a, b) - Int::new(1u8))]
+fn sum_minus_one(a: u64, b: u64) -> u64 {
+    if a > 0 {
+        (a - 1) + b
+    } else {
+        a + (b - 1)
+    }
+}
+
+#[requires(x <= 0)]
+#[ensures(negate(x).to_nat().to::<i64>() == -(x as i64))]
+fn negation_is_natural(x: i32) {}
+
+fn main() {}
@@ -0,0 +1,32 @@
+// compile-flags: -Pcheck_overflows=false
+
+use prusti_contracts::*;
+
+#[pure]
+#[requires(n <= 1000)]
+fn sum(n: u32) -> u32 {
+    if n == 0 {
+        0
+    } else {
+        n + sum(n - 1)
+    }
+}
+
+#[lemma(induction = n)]
+#[requires(n <= 1000)]
+#[ensures(2 * sum(n) == n * (n + 1))]
+fn sum_closed_form(n: u32) {}
+
+#[lemma(induction = n)]
+#[requires(n <= 1000)]
+#[ensures(sum(n) >= n)]
+fn sum_ge(k: u32, n: u32) {}
+
+#[requires(n <= 1000)]
+#[ensures(2 * result == n * (n + 1))]
+fn triangle(n: u32) -> u32 {
+    apply_lemma!(sum_closed_form(n));
+    sum(n)
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+// compile-flags: -Pcheck_overflows=false
+
+use prusti_contracts::*;
+
+#[pure]
+#[requires(n <= 1000)]
+fn sum(n: u32) -> u32 {
+    if n == 0 {
+        0
+    } else {
+        n + sum(n - 1)
+    }
+}
+
+#[lemma]
+#[requires(m <= n && n <= 1000)]
+#[ensures(sum(m) <= sum(n))]
+fn sum_monotonic(m: u32, n: u32) {
+    if m < n {
+        sum_monotonic(m, n - 1);
+    }
+}
+
+#[lemma(triggers = [(sum(n),)])]
+#[requires(n <= 1000)]
+#[ensures(sum(n) >= n)]
+fn sum_ge(n: u32) {}
+
+#[requires(m <= n && n <= 1000)]
+#[ensures(sum(m) <= sum(n))]
+fn apply_monotonic(m: u32, n: u32) {
+    apply_lemma!(sum_monotonic(m, n));
+}
+
+#[requires(n <= 1000)]
+#[ensures(result >= n)]
+fn use_axiom(n: u32) -> u32 {
+    sum(n)
+}
+
+fn main() {}
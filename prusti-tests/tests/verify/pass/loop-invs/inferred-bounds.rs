@@ -0,0 +1,52 @@
+// compile-flags: -Pinfer_loop_invariants=true
+
+use prusti_contracts::*;
+
+#[ensures(result == n)]
+fn count(n: usize) -> usize {
+    let mut i = 0;
+    while i < n {
+        i += 1;
+    }
+    i
+}
+
+#[requires(n <= 100)]
+#[ensures(result == 2 * n)]
+fn double(n: u32) -> u32 {
+    let mut i = 0;
+    let mut j = 0;
+    while i < n {
+        body_invariant!(j == 2 * i);
+        i += 1;
+        j += 2;
+    }
+    j
+}
+
+// The bound `i <= s.len()` is inferred from the loop condition.
+#[ensures(result == s.len())]
+fn count_elements(s: &[u32]) -> usize {
+    let mut i = 0;
+    while i < s.len() {
+        i += 1;
+    }
+    i
+}
+
+// The bound `i <= 4` is inferred from the length of the array.
+#[ensures(result == 4)]
+fn clear(a: &mut [u32; 4]) -> usize {
+    let mut i = 0;
+    while i < a.len() {
+        a[i] = 0;
+        i += 1;
+    }
+    i
+}
+
+fn main() {
+    assert!(count(3) == 3);
+    assert!(count_elements(&[1, 2]) == 2);
+    assert!(clear(&mut [1, 2, 3, 4]) == 4);
+}
@@ -0,0 +1,44 @@
+use prusti_contracts::*;
+
+fn invariant_before_continue(n: u32) {
+    let mut i = 0;
+    let mut skipped = 0;
+    while i < n {
+        body_invariant!(i < n);
+        body_invariant!(skipped <= i);
+        i += 1;
+        if i % 2 == 0 {
+            skipped += 1;
+            body_invariant!(skipped <= i);
+            continue;
+        }
+    }
+}
+
+fn invariant_before_break(n: u32) -> u32 {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n);
+        if i == 10 {
+            body_invariant!(i == 10 && i < n);
+            break;
+        }
+        i += 1;
+    }
+    i
+}
+
+fn second_group(n: u32) {
+    let mut i = 0;
+    let mut j = 0;
+    while i < n {
+        body_invariant!(i < n);
+        body_invariant!(j == i);
+        j += 1;
+        body_invariant!(j == i + 1);
+        i += 1;
+    }
+    assert!(i == j);
+}
+
+fn main() {}
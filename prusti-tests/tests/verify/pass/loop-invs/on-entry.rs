@@ -0,0 +1,54 @@
+use prusti_contracts::*;
+
+pub struct VecWrapper {
+    v: Vec<i32>,
+}
+
+impl VecWrapper {
+    #[trusted]
+    #[pure]
+    pub fn len(&self) -> usize {
+        self.v.len()
+    }
+
+    #[trusted]
+    #[pure]
+    #[requires(index < self.len())]
+    pub fn lookup(&self, index: usize) -> i32 {
+        self.v[index]
+    }
+
+    #[trusted]
+    #[requires(self.len() > 0)]
+    #[ensures(self.len() == old(self.len()) - 1)]
+    #[ensures(forall(|i: usize| i < self.len() ==> self.lookup(i) == old(self.lookup(i))))]
+    pub fn pop(&mut self) {
+        self.v.pop();
+    }
+}
+
+#[requires(v.len() >= n)]
+fn pop_n(v: &mut VecWrapper, n: usize) {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n);
+        body_invariant!(v.len() + i == on_entry(v.len()));
+        body_invariant!(forall(|j: usize| j < v.len() ==> v.lookup(j) == on_entry(v.lookup(j))));
+        v.pop();
+        i += 1;
+    }
+}
+
+fn count_down(n: u32) {
+    let mut x = n;
+    let mut steps = 0;
+    while x > 0 {
+        body_invariant!(x > 0);
+        body_invariant!(x + steps == on_entry(x));
+        body_invariant!(on_entry(steps) == 0);
+        x -= 1;
+        steps += 1;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+use prusti_contracts::*;
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[explicit_expiry]
+#[ensures(*result == old(p.x))]
+#[after_expiry(p.x == before_expiry(*result) && p.y == old(p.y))]
+fn borrow_x(p: &mut Point) -> &mut i32 {
+    &mut p.x
+}
+
+#[explicit_expiry]
+#[after_expiry(p.x == before_expiry(*result.0) && p.y == before_expiry(*result.1))]
+fn borrow_both(p: &mut Point) -> (&mut i32, &mut i32) {
+    (&mut p.x, &mut p.y)
+}
+
+fn client() {
+    let mut p = Point { x: 1, y: 2 };
+    let x = borrow_x(&mut p);
+    assert!(*x == 1);
+    *x = 5;
+    assert!(p.x == 5 && p.y == 2);
+    let (x, y) = borrow_both(&mut p);
+    *x += 1;
+    *y += 1;
+    assert!(p.x == 6 && p.y == 3);
+}
+
+fn main() {}
@@ -0,0 +1,57 @@
+use prusti_contracts::*;
+
+struct T {
+    val: i32,
+}
+
+struct Split<'a> {
+    first: &'a mut i32,
+    second: &'a mut i32,
+}
+
+#[ensures(result.0 == old(x.val) && *result.1 == old(x.val))]
+#[after_expiry(x.val == before_expiry(*result.1))]
+fn peek_and_borrow(x: &mut T) -> (i32, &mut i32) {
+    (x.val, &mut x.val)
+}
+
+#[after_expiry(x.val == before_expiry(*result.0) && y.val == before_expiry(*result.1))]
+fn borrow_both<'a, 'b>(x: &'a mut T, y: &'b mut T) -> (&'a mut i32, &'b mut i32) {
+    (&mut x.val, &mut y.val)
+}
+
+#[after_expiry(x.val == before_expiry(*result.first) && y.val == before_expiry(*result.second))]
+fn split<'a>(x: &'a mut T, y: &'a mut T) -> Split<'a> {
+    Split {
+        first: &mut x.val,
+        second: &mut y.val,
+    }
+}
+
+fn use_peek() {
+    let mut t = T { val: 5 };
+    let (old_val, r) = peek_and_borrow(&mut t);
+    *r = old_val + 1;
+    assert!(t.val == 6);
+}
+
+fn use_both() {
+    let mut a = T { val: 1 };
+    let mut b = T { val: 2 };
+    let (x, y) = borrow_both(&mut a, &mut b);
+    *x = 3;
+    *y = 4;
+    assert!(a.val == 3);
+    assert!(b.val == 4);
+}
+
+fn use_split() {
+    let mut a = T { val: 1 };
+    let mut b = T { val: 2 };
+    let s = split(&mut a, &mut b);
+    *s.first = 5;
+    *s.second = 6;
+    assert!(a.val == 5 && b.val == 6);
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+// compile-flags: -Pinfer_procedure_summaries=true
+
+use prusti_contracts::*;
+
+fn clamp_to_ten(x: u32) -> u32 {
+    if x > 10 {
+        10
+    } else {
+        x
+    }
+}
+
+fn read(counter: &mut u32, limit: u32) -> u32 {
+    if *counter == 0 || limit < 5 {
+        limit
+    } else {
+        5
+    }
+}
+
+#[ensures(result <= 10)]
+pub fn client_1(x: u32) -> u32 {
+    clamp_to_ten(x)
+}
+
+#[ensures(*counter == old(*counter))]
+#[ensures(result <= limit)]
+pub fn client_2(counter: &mut u32, limit: u32) -> u32 {
+    read(counter, limit)
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use prusti_contracts::*;
+
+nonlinear_lemmas!(u32_lemmas, u32);
+
+use u32_lemmas::pow;
+
+#[ensures(pow(2, 3) == 8)]
+fn eight() {
+    apply_lemmas!(u32_lemmas::{
+        pow_base(2),
+        pow_succ(2, 1),
+        pow_succ(2, 2),
+    });
+}
+
+#[requires(0 < b)]
+#[ensures(b * result.0 + result.1 == a && result.1 < b)]
+fn divide(a: u32, b: u32) -> (u32, u32) {
+    apply_lemmas!(u32_lemmas::{div_mod(a, b), mod_bounds(a, b)});
+    (a / b, a % b)
+}
+
+#[requires(0 < b && a <= 1000 && b <= 1000)]
+#[ensures(result == a)]
+fn round_trip(a: u32, b: u32) -> u32 {
+    apply_lemma!(u32_lemmas::mul_le_mul_right(a, 1000, b));
+    apply_lemma!(u32_lemmas::mul_le_mul_left(b, 1000, 1000));
+    apply_lemma!(u32_lemmas::mul_div_cancel(a, b));
+    a * b / b
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+use prusti_contracts::*;
+
+nonlinear_lemmas!(u32_lemmas, u32);
+
+#[nonlinear_arithmetic]
+#[requires(a <= 100 && b <= 100)]
+#[ensures(result <= 10000)]
+fn product(a: u32, b: u32) -> u32 {
+    a * b
+}
+
+#[requires(a <= 100 && b <= 100)]
+#[ensures(result <= 10000)]
+fn product_with_lemmas(a: u32, b: u32) -> u32 {
+    apply_lemma!(u32_lemmas::mul_le_mul_right(a, 100, b));
+    apply_lemma!(u32_lemmas::mul_le_mul_left(b, 100, 100));
+    a * b
+}
+
+#[requires(0 < b)]
+#[ensures(result < b)]
+fn remainder(a: u32, b: u32) -> u32 {
+    apply_lemma!(u32_lemmas::mod_bounds(a, b));
+    a % b
+}
+
+fn main() {}
@@ -0,0 +1,78 @@
+use prusti_contracts::*;
+
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+pub struct Segment {
+    start: Point,
+    end: Point,
+}
+
+impl Segment {
+    #[pure]
+    pub fn length_x(&self) -> i32 {
+        self.end.x - self.start.x
+    }
+}
+
+#[pure]
+fn sum(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[requires(s.start.x < 1000 && s.end.x < 1000)]
+#[ensures(s.start.x == old(s.start.x) + 1)]
+#[ensures(s.end.x == old(s.end.x) + 1)]
+#[ensures(s.length_x() == old(s.length_x()))]
+#[ensures(sum(s.start.x, s.end.y) == old(sum(s.start.x, s.end.y)) + 1)]
+#[ensures(old(sum(s.start.y, s.end.y)) == sum(s.start.y, s.end.y))]
+fn shift_right(s: &mut Segment) {
+    s.start.x += 1;
+    s.end.x += 1;
+}
+
+#[pure]
+#[requires(i < v.len())]
+fn lookup(v: &VecWrapper, i: usize) -> i32 {
+    v.lookup(i)
+}
+
+pub struct VecWrapper {
+    v: Vec<i32>,
+}
+
+impl VecWrapper {
+    #[trusted]
+    #[pure]
+    pub fn len(&self) -> usize {
+        self.v.len()
+    }
+
+    #[trusted]
+    #[pure]
+    #[requires(index < self.len())]
+    pub fn lookup(&self, index: usize) -> i32 {
+        self.v[index]
+    }
+
+    #[trusted]
+    #[requires(index < self.len())]
+    #[ensures(self.len() == old(self.len()))]
+    #[ensures(self.lookup(index) == value)]
+    #[ensures(forall(|i: usize| (i < self.len() && i != index) ==>
+        self.lookup(i) == old(self.lookup(i))))]
+    pub fn store(&mut self, index: usize, value: i32) {
+        self.v[index] = value;
+    }
+}
+
+#[requires(v.len() > 1)]
+#[ensures(lookup(v, 0) == 0)]
+#[ensures(forall(|i: usize| (0 < i && i < v.len()) ==> lookup(v, i) == old(lookup(v, i))))]
+fn reset_first(v: &mut VecWrapper) {
+    v.store(0, 0);
+}
+
+fn main() {}
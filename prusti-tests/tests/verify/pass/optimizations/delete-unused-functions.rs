@@ -0,0 +1,35 @@
+// compile-flags: -Poptimizations=inline_constant_functions,delete_unused_functions
+use prusti_contracts::*;
+
+#[pure]
+fn zero() -> i32 {
+    0
+}
+
+#[pure]
+fn is_positive(x: i32) -> bool {
+    x > zero()
+}
+
+#[pure]
+#[requires(is_positive(x))]
+fn predecessor(x: i32) -> i32 {
+    x - 1
+}
+
+#[pure]
+#[ensures(result == is_positive(predecessor(1)))]
+fn unused() -> bool {
+    false
+}
+
+#[requires(is_positive(x))]
+#[ensures(result >= zero())]
+#[ensures(result == x - 1)]
+fn decrement(x: i32) -> i32 {
+    predecessor(x)
+}
+
+fn main() {
+    assert!(decrement(1) == 0);
+}
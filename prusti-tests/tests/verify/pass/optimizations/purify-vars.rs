@@ -0,0 +1,30 @@
+// compile-flags: -Poptimizations=purify_vars
+use prusti_contracts::*;
+
+// The usize variables are replaced by integer variables.
+#[requires(n < 1000)]
+#[ensures(result == n + 1)]
+fn successor(n: usize) -> usize {
+    let m = n;
+    m + 1
+}
+
+// `unused` is folded and unfolded, but its value is never read.
+fn unused_copy(n: usize) -> usize {
+    let unused = n;
+    let _other = unused;
+    0
+}
+
+#[requires(n < 1000)]
+#[ensures(result >= n)]
+fn count(n: usize) -> usize {
+    let mut i: usize = 0;
+    while i < n {
+        body_invariant!(i < n);
+        i += 1;
+    }
+    i
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+// compile-flags: -Poptimizations=remove_empty_blocks,clean_cfg
+use prusti_contracts::*;
+
+#[ensures(result >= x && result >= y)]
+#[ensures(result == x || result == y)]
+fn max(x: i32, y: i32) -> i32 {
+    if x > y {
+        x
+    } else if x == y {
+        y
+    } else {
+        y
+    }
+}
+
+#[requires(n >= 0)]
+#[ensures(result >= n)]
+fn count(n: i32) -> i32 {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n);
+        if i % 2 == 0 {
+            i += 1;
+        } else {
+            i += 1;
+        }
+    }
+    i
+}
+
+fn main() {
+    assert!(max(1, 2) == 2);
+    assert!(count(3) >= 3);
+}
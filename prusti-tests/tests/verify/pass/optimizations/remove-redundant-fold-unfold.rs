@@ -0,0 +1,37 @@
+// compile-flags: -Poptimizations=remove_redundant_fold_unfold
+use prusti_contracts::*;
+
+struct Pair {
+    first: u32,
+    second: u32,
+}
+
+#[pure]
+fn sum(p: &Pair) -> u32 {
+    p.first + p.second
+}
+
+#[requires(p.first < 100 && p.second < 100)]
+#[ensures(sum(p) == old(sum(p)) + 1)]
+#[ensures(p.second == old(p.second))]
+fn increment(p: &mut Pair) {
+    let before = sum(p);
+    p.first += 1;
+    let after = sum(p);
+    assert!(after == before + 1);
+}
+
+#[ensures(p.first == old(p.second) && p.second == old(p.first))]
+fn swap(p: &mut Pair) {
+    let first = p.first;
+    p.first = p.second;
+    p.second = first;
+}
+
+fn main() {
+    let mut p = Pair { first: 1, second: 2 };
+    increment(&mut p);
+    assert!(sum(&p) == 4);
+    swap(&mut p);
+    assert!(p.first == 2 && p.second == 2);
+}
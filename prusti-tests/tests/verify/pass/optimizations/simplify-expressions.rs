@@ -0,0 +1,32 @@
+// compile-flags: -Poptimizations=simplify_expressions,remove_trivial_assertions
+use prusti_contracts::*;
+
+#[pure]
+#[ensures(result == 2 * 3 + 1)]
+fn seven() -> i32 {
+    7
+}
+
+#[pure]
+#[requires(x > 0 && (x < 10 && !!(x != 5)))]
+#[ensures(result > 1 + 1)]
+#[ensures(!(result < 0) && (result == x + 2 && true))]
+fn add_two(x: i32) -> i32 {
+    x + 2
+}
+
+#[requires(x > 0 && (x < 10 && !!(x != 5)))]
+#[ensures(result == 2 * 3 + x)]
+fn add_six(x: i32) -> i32 {
+    assert!(x + 2 * 3 > 6);
+    assert!(!!(x != 5) || 1 > 2);
+    let y = add_two(x) + 4;
+    assert!(y == x + (10 - 4));
+    y
+}
+
+fn main() {
+    assert!(seven() == 7);
+    assert!(add_two(3) == 5);
+    assert!(add_six(4) == 10);
+}
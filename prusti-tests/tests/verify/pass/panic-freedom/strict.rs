@@ -0,0 +1,66 @@
+// compile-flags: -Pcheck_panics=strict
+
+#![feature(allocator_api)]
+
+use prusti_contracts::*;
+
+#[requires(a <= 1000 && b <= 1000)]
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[pure]
+fn is_some(x: &Option<u32>) -> bool {
+    matches!(x, Some(_))
+}
+
+#[requires(is_some(&x))]
+fn first(x: Option<u32>) -> u32 {
+    x.unwrap()
+}
+
+fn parse(x: Result<u32, ()>) -> u32 {
+    match x {
+        Ok(_) => x.expect("checked above"),
+        Err(_) => 0,
+    }
+}
+
+fn error_of(x: Result<u32, u32>) -> u32 {
+    match x {
+        Err(_) => x.unwrap_err(),
+        Ok(_) => 0,
+    }
+}
+
+#[extern_spec]
+impl<T, A: std::alloc::Allocator> Vec<T, A> {
+    #[pure]
+    fn len(&self) -> usize;
+
+    #[requires(index < self.len())]
+    fn remove(&mut self, index: usize) -> T;
+}
+
+#[requires(v.len() > 0)]
+fn remove_first(v: &mut Vec<u32>) -> u32 {
+    v.remove(0)
+}
+
+fn slice_len(a: [u32; 4]) -> usize {
+    let s = &a[1..3];
+    s.len()
+}
+
+fn wrapped(a: u32, b: u32) -> u32 {
+    wrapping! { a + b }
+}
+
+fn main() {
+    let sum = add(1, 2);
+    let value = first(Some(sum));
+    let _ = parse(Ok(value));
+    let _ = wrapped(sum, value);
+    let _ = error_of(Err(value));
+    let _ = slice_len([1, 2, 3, 4]);
+}
@@ -0,0 +1,32 @@
+// compile-flags: -Pbatch_viper_ast_construction=true
+use prusti_contracts::*;
+
+struct Account {
+    balance: u32,
+}
+
+#[pure]
+fn balance(account: &Account) -> u32 {
+    account.balance
+}
+
+#[requires(account.balance < 1000 && amount < 1000)]
+#[ensures(balance(account) == old(balance(account)) + amount)]
+fn deposit(account: &mut Account, amount: u32) {
+    account.balance += amount;
+}
+
+#[requires(account.balance >= amount)]
+#[ensures(balance(account) == old(balance(account)) - amount)]
+fn withdraw(account: &mut Account, amount: u32) {
+    assert!(account.balance >= amount);
+    account.balance -= amount;
+}
+
+fn main() {
+    let mut account = Account { balance: 1 };
+    deposit(&mut account, 2);
+    assert!(balance(&account) == 3);
+    withdraw(&mut account, 3);
+    assert!(balance(&account) == 0);
+}
@@ -0,0 +1,40 @@
+// Each procedure is verified in its own Viper program, which contains only
+// the definitions it uses. A pure function is abstract in the programs of
+// the procedures that only call it, but its body is available where it is
+// needed.
+use prusti_contracts::*;
+
+struct Account {
+    balance: u32,
+}
+
+#[pure]
+fn balance(account: &Account) -> u32 {
+    account.balance
+}
+
+#[pure]
+#[ensures(result == a + b)]
+fn sum(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[requires(account.balance < 1000 && amount < 1000)]
+#[ensures(balance(account) == old(balance(account)) + amount)]
+fn deposit(account: &mut Account, amount: u32) {
+    account.balance = sum(account.balance, amount);
+}
+
+#[requires(from.balance >= amount && to.balance < 1000 && amount < 1000)]
+#[ensures(balance(from) + balance(to) == old(balance(from) + balance(to)))]
+fn transfer(from: &mut Account, to: &mut Account, amount: u32) {
+    from.balance -= amount;
+    deposit(to, amount);
+}
+
+fn main() {
+    let mut a = Account { balance: 10 };
+    let mut b = Account { balance: 0 };
+    transfer(&mut a, &mut b, 5);
+    assert!(balance(&a) + balance(&b) == 10);
+}
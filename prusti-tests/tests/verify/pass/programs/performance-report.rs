@@ -0,0 +1,24 @@
+// compile-flags: -Pperformance_report=true
+// Writing the report does not change the verification.
+
+use prusti_contracts::*;
+
+#[requires(x < 100)]
+#[ensures(result == x + 1)]
+fn increment(x: u32) -> u32 {
+    x + 1
+}
+
+#[ensures(result >= 10)]
+fn at_least_ten(x: u32) -> u32 {
+    if x < 10 {
+        10
+    } else {
+        x
+    }
+}
+
+fn main() {
+    assert!(increment(1) == 2);
+    assert!(at_least_ten(3) == 10);
+}
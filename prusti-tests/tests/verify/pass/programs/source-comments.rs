@@ -0,0 +1,19 @@
+// compile-flags: -Pdump_viper_program=true
+// The comments with the Rust source code do not change the verification.
+use prusti_contracts::*;
+
+#[requires(first < 1000 && second < 1000)]
+#[ensures(result >= first)]
+fn add(first: u32, second: u32) -> u32 {
+    let sum = first + second;
+    assert!(sum >= first);
+    sum
+}
+
+#[ensures(result.0 == old(pair.1) && result.1 == old(pair.0))]
+fn swap(pair: (i32, i32)) -> (i32, i32) {
+    let (first, second) = pair;
+    (second, first)
+}
+
+fn main() {}
@@ -0,0 +1,55 @@
+use prusti_contracts::*;
+use std::cmp::{max, min, Ordering};
+
+#[pure]
+fn compare(a: i32, b: i32) -> Ordering {
+    a.cmp(&b)
+}
+
+#[pure]
+fn is_before(a: u32, b: u32) -> bool {
+    match a.cmp(&b) {
+        Ordering::Less => true,
+        _ => false,
+    }
+}
+
+#[ensures(result == (a < b))]
+fn is_before_impure(a: u32, b: u32) -> bool {
+    match a.cmp(&b) {
+        Ordering::Less => true,
+        Ordering::Equal | Ordering::Greater => false,
+    }
+}
+
+#[ensures(compare(a, b).is_lt() == (a < b))]
+#[ensures(compare(a, b).is_eq() == (a == b))]
+#[ensures(compare(a, b).reverse().is_gt() == (a < b))]
+#[ensures(compare(a, b).is_le() == compare(b, a).is_ge())]
+fn antisymmetry(a: i32, b: i32) {}
+
+#[requires(lo <= hi)]
+#[ensures(lo <= result && result <= hi)]
+#[ensures(lo <= x && x <= hi ==> result == x)]
+fn clamp(x: i64, lo: i64, hi: i64) -> i64 {
+    x.clamp(lo, hi)
+}
+
+#[ensures(result == max(a, b) - min(a, b))]
+#[ensures(result >= 0)]
+fn distance(a: i32, b: i32) -> i32 {
+    let lo = min(a, b);
+    let hi = a.max(b);
+    hi - lo
+}
+
+fn main() {
+    assert!(is_before(1, 2));
+    assert!(!is_before_impure(2, 2));
+    assert!(match 5u8.partial_cmp(&3) {
+        Some(Ordering::Greater) => true,
+        _ => false,
+    });
+    assert!(1 < 2 && 'a'.lt(&'b'));
+    assert!(clamp(7, 0, 5) == 5);
+}
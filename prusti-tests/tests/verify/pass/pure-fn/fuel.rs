@@ -0,0 +1,20 @@
+// compile-flags: -Pcheck_overflows=false
+
+use prusti_contracts::*;
+
+#[pure(fuel = 4)]
+#[requires(n <= 1000)]
+fn sum(n: u32) -> u32 {
+    if n == 0 {
+        0
+    } else {
+        n + sum(n - 1)
+    }
+}
+
+#[ensures(result == 6)]
+fn sum_to_three() -> u32 {
+    sum(3)
+}
+
+fn main() {}
@@ -0,0 +1,56 @@
+use prusti_contracts::*;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Pair<T> {
+    first: T,
+    second: T,
+}
+
+#[pure]
+fn contains<T: PartialEq + Copy>(p: &Pair<T>, x: T) -> bool {
+    p.first == x || p.second == x
+}
+
+#[pure]
+fn swapped<T: Copy>(p: &Pair<T>) -> Pair<T> {
+    Pair {
+        first: p.second,
+        second: p.first,
+    }
+}
+
+fn test_int() {
+    let p = Pair {
+        first: 1,
+        second: 2,
+    };
+    assert!(contains(&p, 1));
+    assert!(contains(&p, 2));
+    assert!(!contains(&p, 3));
+    assert!(contains(&swapped(&p), 1));
+}
+
+fn test_bool() {
+    let p = Pair {
+        first: true,
+        second: true,
+    };
+    assert!(contains(&p, true));
+    assert!(!contains(&p, false));
+}
+
+#[requires(contains(&p, x))]
+#[ensures(contains(&p, x))]
+fn test_generic<T: PartialEq + Copy>(p: Pair<T>, x: T) {}
+
+#[requires(contains(&p, x))]
+fn use_generic(p: Pair<u32>, x: u32) {
+    test_generic(p, x);
+    let q = Pair {
+        first: x,
+        second: x,
+    };
+    test_generic(q, x);
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+// compile-flags: -Pinfer_purity=true
+
+use prusti_contracts::*;
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl Point {
+    fn get_x(&self) -> i32 {
+        self.x
+    }
+
+    fn is_origin(&self) -> bool {
+        self.get_x() == 0 && self.y == 0
+    }
+}
+
+#[requires(p.get_x() > 0)]
+#[ensures(!p.is_origin())]
+fn check(p: Point) {}
+
+fn main() {
+    check(Point { x: 1, y: 0 });
+}
@@ -0,0 +1,63 @@
+use prusti_contracts::*;
+
+#[pure]
+#[inline_spec]
+#[requires(x <= 1000)]
+fn double(x: u32) -> u32 {
+    2 * x
+}
+
+#[pure]
+#[inline_spec]
+#[requires(x <= 250)]
+fn quadruple(x: u32) -> u32 {
+    double(double(x))
+}
+
+#[pure]
+#[inline_spec]
+#[requires(n <= 1000)]
+fn sum(n: u32) -> u32 {
+    if n == 0 {
+        0
+    } else {
+        n + sum(n - 1)
+    }
+}
+
+#[pure]
+#[never_inline_spec]
+fn answer() -> u32 {
+    42
+}
+
+#[requires(x <= 250)]
+#[ensures(result == quadruple(x))]
+#[ensures(result == 4 * x)]
+fn times_four(x: u32) -> u32 {
+    double(2 * x)
+}
+
+#[ensures(result == 1)]
+fn sum_to_one() -> u32 {
+    sum(1)
+}
+
+#[ensures(result == answer())]
+#[ensures(result == 42)]
+fn get_answer() -> u32 {
+    answer()
+}
+
+// The triggers of the quantifiers are not inlined.
+#[requires(forall(|i: u32| i <= 500 ==> double(i) <= 1000, triggers = [(double(i),)]))]
+#[ensures(result <= 1000)]
+fn double_bounded(x: u32) -> u32 {
+    if x <= 500 {
+        double(x)
+    } else {
+        0
+    }
+}
+
+fn main() {}
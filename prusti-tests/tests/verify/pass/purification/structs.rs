@@ -0,0 +1,44 @@
+// compile-flags: -Penable_purification_optimization=true -Penable_struct_purification=true
+
+use prusti_contracts::*;
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[ensures(result.0 == a && result.1 == b)]
+fn make_pair(a: u32, b: bool) -> (u32, bool) {
+    (a, b)
+}
+
+#[requires(x < 100 && y < 100)]
+#[ensures(result == x + y)]
+fn sum(x: i32, y: i32) -> i32 {
+    let p = Point { x, y };
+    p.x + p.y
+}
+
+fn swap() {
+    let mut pair = (1, 2);
+    let tmp = pair.0;
+    pair.0 = pair.1;
+    pair.1 = tmp;
+    assert!(pair.0 == 2 && pair.1 == 1);
+}
+
+fn from_call() {
+    let pair = make_pair(3, true);
+    assert!(pair.0 == 3);
+    assert!(pair.1);
+}
+
+fn borrowed() {
+    let mut p = Point { x: 1, y: 2 };
+    let r = &mut p;
+    r.x = 5;
+    assert!(p.x == 5 && p.y == 2);
+}
+
+fn main() {}
@@ -0,0 +1,63 @@
+use prusti_contracts::*;
+
+#[derive(Clone, Copy)]
+pub struct Node {
+    value: i32,
+    weight: u32,
+}
+
+pub enum Shape {
+    Circle(u32),
+    Square(u32),
+}
+
+#[pure]
+fn is_light(n: &Node) -> bool {
+    n.weight < 10
+}
+
+#[pure]
+fn size(s: &Shape) -> u32 {
+    match s {
+        Shape::Circle(r) => *r,
+        Shape::Square(a) => *a,
+    }
+}
+
+#[pure]
+fn scaled(n: Node) -> Node {
+    Node {
+        value: n.value,
+        weight: n.weight / 2,
+    }
+}
+
+// The weight of the scaled node is below the limit of `is_light`.
+#[ensures(forall(|n: Node| n.weight < 20 ==> is_light(&scaled(n))))]
+fn quantify_over_structs() {}
+
+// The precondition follows from the definition of `is_light`.
+#[requires(forall(|n: &Node| n.weight < 10 ==> is_light(n)))]
+fn light_nodes() {}
+
+fn call_light_nodes() {
+    light_nodes();
+}
+
+#[requires(forall(|n: &Node| is_light(n) ==> n.value >= 0))]
+#[ensures(result >= 0)]
+fn use_quantified(node: Node) -> i32 {
+    if is_light(&node) {
+        node.value
+    } else {
+        0
+    }
+}
+
+#[requires(forall(|s: &Shape| size(s) <= 100))]
+#[ensures(result <= 200)]
+fn sum_sizes(a: &Shape, b: &Shape) -> u32 {
+    size(a) + size(b)
+}
+
+fn main() {}
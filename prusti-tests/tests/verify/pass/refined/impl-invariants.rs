@@ -0,0 +1,42 @@
+use prusti_contracts::*;
+
+mod range {
+    pub struct Range {
+        pub start: u32,
+        pub end: u32,
+    }
+}
+
+mod ordered {
+    use super::range::Range;
+    use prusti_contracts::*;
+
+    #[invariant(self.start <= self.end)]
+    impl Range {
+        #[ensures(result <= self.end)]
+        pub fn start(&self) -> u32 {
+            self.start
+        }
+    }
+}
+
+mod bounded {
+    use super::range::Range;
+    use prusti_contracts::*;
+
+    #[invariant(self.end <= 100)]
+    impl Range {}
+}
+
+use range::Range;
+
+#[ensures(result <= 100)]
+fn len(range: &Range) -> u32 {
+    range.end - range.start
+}
+
+fn main() {
+    let range = Range { start: 1, end: 2 };
+    assert!(range.start() <= 2);
+    assert!(len(&range) <= 100);
+}
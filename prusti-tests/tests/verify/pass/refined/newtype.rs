@@ -0,0 +1,39 @@
+use prusti_contracts::*;
+
+#[refined(|x| x.0 > 0)]
+struct Positive(u32);
+
+#[refined(|range| range.start <= range.end)]
+struct Range {
+    start: u32,
+    end: u32,
+}
+
+#[requires(value > 0)]
+fn positive(value: u32) -> Positive {
+    Positive(value)
+}
+
+#[ensures(result > 0)]
+fn get(x: &Positive) -> u32 {
+    x.0
+}
+
+fn double(x: &mut Positive) {
+    if x.0 <= u32::MAX / 2 {
+        x.0 *= 2;
+    }
+}
+
+#[ensures(result <= range.end)]
+fn start(range: Range) -> u32 {
+    range.start
+}
+
+fn main() {
+    let mut x = positive(3);
+    double(&mut x);
+    assert!(get(&x) > 0);
+    let range = Range { start: 1, end: 2 };
+    assert!(start(range) <= 2);
+}
@@ -0,0 +1,51 @@
+use prusti_contracts::*;
+
+#[pure]
+fn is_even(x: u32) -> bool {
+    x % 2 == 0
+}
+
+// The precondition is checked at runtime, so the callers do not need to
+// establish it.
+#[insert_runtime_check]
+#[requires(x > 0)]
+fn divide(x: u32) -> u32 {
+    100 / x
+}
+
+// The postcondition is checked at runtime and assumed by the callers.
+#[insert_runtime_check]
+#[ensures(is_even(result))]
+fn next_even(x: u32) -> u32 {
+    if x < 1000 {
+        x + 2 - x % 2
+    } else {
+        0
+    }
+}
+
+#[insert_runtime_check(all)]
+#[requires(*x < 100)]
+#[ensures(*x == old(*x) + 1)]
+fn increment_in_place(x: &mut u32) {
+    *x += 1;
+}
+
+#[requires(x < 100)]
+#[insert_runtime_check]
+#[ensures(result > x)]
+fn increment(x: u32) -> u32 {
+    x + 1
+}
+
+fn client(x: u32) {
+    let y = divide(x);
+    let z = next_even(y);
+    assert!(is_even(z));
+    let mut w = increment(5);
+    assert!(w > 5);
+    increment_in_place(&mut w);
+    assert!(w > 6);
+}
+
+fn main() {}
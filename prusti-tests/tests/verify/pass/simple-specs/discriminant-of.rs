@@ -0,0 +1,38 @@
+use prusti_contracts::*;
+
+enum Shape {
+    Circle(u32),
+    Square(u32),
+    Point,
+}
+
+enum State {
+    Idle = 10,
+    Running = 20,
+}
+
+#[ensures(discriminant_of(shape) == old(discriminant_of(shape)))]
+fn grow(shape: &mut Shape) {
+    match shape {
+        Shape::Circle(radius) if *radius < 1000 => *radius += 1,
+        Shape::Square(side) if *side < 1000 => *side += 1,
+        _ => {}
+    }
+}
+
+#[ensures(discriminant_of(&result) == 1)]
+fn running() -> State {
+    State::Running
+}
+
+#[requires(discriminant_of(&shape) == 2)]
+#[ensures(result == 0)]
+fn size(shape: Shape) -> u32 {
+    match shape {
+        Shape::Circle(radius) => radius,
+        Shape::Square(side) => side,
+        Shape::Point => 0,
+    }
+}
+
+fn main() {}
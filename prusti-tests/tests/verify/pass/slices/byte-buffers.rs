@@ -0,0 +1,36 @@
+use prusti_contracts::*;
+
+byte_specs!();
+
+#[ensures(result == value)]
+fn round_trip_le(value: u32) -> u32 {
+    u32::from_le_bytes(value.to_le_bytes())
+}
+
+#[ensures(result == value)]
+fn round_trip_be(value: u16) -> u16 {
+    u16::from_be_bytes(value.to_be_bytes())
+}
+
+#[ensures(result[0] == 0x78 && result[3] == 0x12)]
+fn encode() -> [u8; 4] {
+    0x12345678u32.to_le_bytes()
+}
+
+#[requires(buffer.len() >= 4)]
+#[ensures(result == buffer[0] as u32 + 256 * buffer[1] as u32
+    + 65536 * buffer[2] as u32 + 16777216 * buffer[3] as u32)]
+fn read_u32(buffer: &[u8]) -> u32 {
+    let (header, _rest) = buffer.split_at(4);
+    u32::from_le_bytes([header[0], header[1], header[2], header[3]])
+}
+
+#[requires(buffer.len() == 6)]
+fn parse(buffer: &[u8]) {
+    let (first, second) = buffer.split_at(2);
+    assert!(first.len() == 2 && second.len() == 4);
+    assert!(first[1] == buffer[1]);
+    assert!(second[0] == buffer[2]);
+}
+
+fn main() {}
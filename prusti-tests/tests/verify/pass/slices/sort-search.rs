@@ -0,0 +1,29 @@
+use prusti_contracts::*;
+
+slice_specs!(u32);
+
+#[requires(values.len() > 0)]
+#[ensures(forall(|i: usize| i < values.len() ==> result <= values[i]))]
+fn smallest(values: &mut [u32]) -> u32 {
+    values.sort();
+    values[0]
+}
+
+#[requires(forall(|i: usize, j: usize| i <= j && j < values.len() ==> values[i] <= values[j]))]
+#[ensures(result ==> exists(|i: usize| i < values.len() && values[i] == value))]
+#[ensures(!result ==> forall(|i: usize| i < values.len() ==> values[i] != value))]
+fn contains(values: &[u32], value: u32) -> bool {
+    match values.binary_search(&value) {
+        Ok(_) => true,
+        Err(_) => false,
+    }
+}
+
+#[requires(values.len() == 2)]
+#[ensures(values[0] <= values[1])]
+#[ensures(count(0..2, |i: usize| values[i] == 7) == old(count(0..2, |i: usize| values[i] == 7)))]
+fn order_pair(values: &mut [u32]) {
+    values.sort_unstable();
+}
+
+fn main() {}
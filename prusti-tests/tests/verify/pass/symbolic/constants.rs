@@ -0,0 +1,39 @@
+use prusti_contracts::*;
+
+#[symbolic(BUF_SIZE > 0 && BUF_SIZE <= 4096)]
+const BUF_SIZE: usize = 1024;
+
+#[symbolic(MAX_RETRIES <= BUF_SIZE)]
+const MAX_RETRIES: usize = 3;
+
+#[symbolic]
+const VERBOSE: bool = false;
+
+#[requires(len <= BUF_SIZE)]
+fn clear(buf: &mut [u8; 4096], len: usize) {
+    let mut i = 0;
+    while i < len {
+        body_invariant!(i < len && len <= 4096);
+        buf[i] = 0;
+        i += 1;
+    }
+}
+
+#[ensures(result >= 1)]
+fn capacity() -> usize {
+    BUF_SIZE
+}
+
+fn retries_fit() {
+    assert!(MAX_RETRIES <= 4096);
+}
+
+#[ensures(result == VERBOSE)]
+fn verbose() -> bool {
+    VERBOSE
+}
+
+fn main() {
+    let mut buf = [0; 4096];
+    clear(&mut buf, BUF_SIZE);
+}
@@ -0,0 +1,42 @@
+use prusti_contracts::*;
+use std::sync::atomic::Ordering;
+
+atomic_invariant!(struct Counter(AtomicUsize), |value| value <= 10);
+atomic_invariant!(struct Flag(AtomicBool), |value| !value);
+atomic_invariant!(struct Even(AtomicU32), |value| value % 2 == 0 && value <= 100);
+
+#[ensures(result <= 10)]
+fn read(counter: &Counter) -> usize {
+    counter.load(Ordering::SeqCst)
+}
+
+fn increment(counter: &Counter) {
+    let value = counter.load(Ordering::SeqCst);
+    if value < 10 {
+        match counter.compare_exchange(value, value + 1, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(previous) => assert!(previous == value),
+            Err(previous) => assert!(previous != value && previous <= 10),
+        }
+    }
+}
+
+fn clear(flag: &Flag) {
+    flag.store(false, Ordering::SeqCst);
+    assert!(!flag.load(Ordering::SeqCst));
+}
+
+fn add_zero(even: &Even) {
+    let previous = even.fetch_add(0, Ordering::SeqCst);
+    assert!(previous % 2 == 0);
+}
+
+fn main() {
+    let counter = Counter::new(0);
+    increment(&counter);
+    counter.store(10, Ordering::SeqCst);
+    assert!(read(&counter) <= 10);
+    let flag = Flag::new(false);
+    clear(&flag);
+    let even = Even::new(4);
+    add_zero(&even);
+}
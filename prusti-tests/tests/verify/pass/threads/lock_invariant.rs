@@ -0,0 +1,27 @@
+use prusti_contracts::*;
+use std::sync::{Mutex, RwLock};
+
+#[lock_invariant(self.value <= 10)]
+struct Counter {
+    value: u32,
+}
+
+fn increment(counter: &Mutex<Counter>) {
+    let mut guard = counter.lock().unwrap();
+    if guard.value < 10 {
+        guard.value += 1;
+    }
+}
+
+fn read(counter: &RwLock<Counter>) -> u32 {
+    let value = counter.read().unwrap().value;
+    assert!(value <= 10);
+    value
+}
+
+fn main() {
+    let counter = Mutex::new(Counter { value: 0 });
+    increment(&counter);
+    let counter = RwLock::new(Counter { value: 10 });
+    read(&counter);
+}
@@ -0,0 +1,40 @@
+use prusti_contracts::*;
+use std::sync::mpsc::{channel, sync_channel, Receiver, Sender};
+
+#[message_invariant(self.value <= 10)]
+struct Message {
+    value: u32,
+}
+
+fn produce(sender: &Sender<Message>, value: u32) {
+    if value <= 10 {
+        let _ = sender.send(Message { value });
+    }
+}
+
+#[ensures(result <= 10)]
+fn consume(receiver: &Receiver<Message>) -> u32 {
+    match receiver.recv() {
+        Ok(message) => message.value,
+        Err(_) => 0,
+    }
+}
+
+#[ensures(result <= 10)]
+fn poll(receiver: &Receiver<Message>) -> u32 {
+    if let Ok(message) = receiver.try_recv() {
+        message.value
+    } else {
+        0
+    }
+}
+
+fn main() {
+    let (sender, receiver) = channel();
+    produce(&sender, 5);
+    let value = consume(&receiver);
+    assert!(value <= 10);
+    let (sender, receiver) = sync_channel(1);
+    let _ = sender.send(Message { value: 10 });
+    assert!(poll(&receiver) <= 10);
+}
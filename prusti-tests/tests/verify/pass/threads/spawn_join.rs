@@ -0,0 +1,16 @@
+use prusti_contracts::*;
+use std::thread;
+
+#[ensures(result == 42)]
+fn answer_in_thread() -> u32 {
+    let handle = thread::spawn(closure!(ensures(result == 42), || -> u32 { 6 * 7 }));
+    match handle.join() {
+        Ok(result) => result,
+        Err(_) => unreachable!(),
+    }
+}
+
+fn main() {
+    let x = answer_in_thread();
+    assert!(x == 42);
+}
@@ -0,0 +1,100 @@
+use prusti_contracts::*;
+
+trait Counter {
+    #[pure]
+    fn value(&self) -> u32;
+
+    #[requires(self.value() < 100)]
+    #[ensures(self.value() == old(self.value()) + 1)]
+    fn increment(&mut self);
+
+    #[requires(self.value() < 99)]
+    #[ensures(self.value() == old(self.value()) + 2)]
+    fn increment_twice(&mut self) {
+        self.increment();
+        self.increment();
+    }
+}
+
+struct Simple {
+    count: u32,
+}
+
+impl Counter for Simple {
+    #[pure]
+    fn value(&self) -> u32 {
+        self.count
+    }
+
+    fn increment(&mut self) {
+        self.count += 1;
+    }
+}
+
+struct Fast {
+    count: u32,
+}
+
+impl Counter for Fast {
+    #[pure]
+    fn value(&self) -> u32 {
+        self.count
+    }
+
+    fn increment(&mut self) {
+        self.count += 1;
+    }
+
+    // Inherits the contract of the default method.
+    fn increment_twice(&mut self) {
+        self.count += 2;
+    }
+}
+
+struct Large {
+    count: u32,
+}
+
+#[refine_trait_spec]
+impl Counter for Large {
+    #[pure]
+    fn value(&self) -> u32 {
+        self.count
+    }
+
+    fn increment(&mut self) {
+        self.count += 1;
+    }
+
+    #[requires(self.value() < 1000)]
+    #[ensures(self.value() == old(self.value()) + 2)]
+    fn increment_twice(&mut self) {
+        self.count += 2;
+    }
+}
+
+fn test_default() {
+    let mut c = Simple { count: 0 };
+    c.increment_twice();
+    assert!(c.value() == 2);
+}
+
+fn test_inherited() {
+    let mut c = Fast { count: 0 };
+    c.increment_twice();
+    assert!(c.value() == 2);
+}
+
+fn test_refined() {
+    let mut c = Large { count: 500 };
+    c.increment_twice();
+    assert!(c.value() == 502);
+}
+
+#[requires(c.value() == 0)]
+fn test_generic<C: Counter>(c: &mut C) {
+    c.increment_twice();
+    assert!(c.value() == 2);
+}
+
+fn main() {}
@@ -0,0 +1,83 @@
+use prusti_contracts::*;
+
+trait Monoid {
+    #[pure]
+    fn identity() -> i32;
+
+    #[pure]
+    fn op(a: i32, b: i32) -> i32;
+
+    #[law]
+    #[ensures(Self::op(Self::op(a, b), c) == Self::op(a, Self::op(b, c)))]
+    fn associativity(a: i32, b: i32, c: i32);
+
+    #[law]
+    #[ensures(Self::op(Self::identity(), a) == a && Self::op(a, Self::identity()) == a)]
+    fn identity_element(a: i32);
+}
+
+struct Max;
+
+impl Monoid for Max {
+    #[pure]
+    fn identity() -> i32 {
+        i32::MIN
+    }
+
+    #[pure]
+    fn op(a: i32, b: i32) -> i32 {
+        if a >= b { a } else { b }
+    }
+
+    fn associativity(a: i32, b: i32, c: i32) {}
+
+    fn identity_element(a: i32) {}
+}
+
+struct Min;
+
+impl Monoid for Min {
+    #[pure]
+    fn identity() -> i32 {
+        i32::MAX
+    }
+
+    #[pure]
+    fn op(a: i32, b: i32) -> i32 {
+        if a <= b { a } else { b }
+    }
+
+    fn associativity(a: i32, b: i32, c: i32) {}
+
+    fn identity_element(a: i32) {}
+}
+
+trait Scaled {
+    #[pure]
+    #[ensures(x >= 0 ==> result >= 0)]
+    fn scale(x: i32) -> i32;
+
+    // A law that follows from the contracts of the trait is proven once by
+    // its default body.
+    #[law]
+    #[requires(x >= 0)]
+    #[ensures(Self::scale(x) >= 0)]
+    fn preserves_sign(x: i32) {}
+}
+
+struct Identity;
+
+impl Scaled for Identity {
+    #[pure]
+    fn scale(x: i32) -> i32 {
+        x
+    }
+}
+
+// A law can be used like a lemma.
+#[ensures(Max::op(Max::op(a, 0), b) == Max::op(a, Max::op(0, b)))]
+fn use_law(a: i32, b: i32) {
+    Max::associativity(a, 0, b);
+}
+
+fn main() {}
@@ -0,0 +1,102 @@
+use prusti_contracts::*;
+
+trait Weighted {
+    const ZERO: i32;
+
+    #[pure]
+    fn weight(&self) -> i32;
+
+    #[ensures(result.weight() == Self::ZERO)]
+    fn zero() -> Self;
+
+    #[pure]
+    #[ensures(result == Self::ZERO + 1)]
+    fn one() -> i32;
+
+    #[requires(self.weight() >= Self::ZERO)]
+    #[ensures(result.weight() == Self::one())]
+    fn unit(self) -> Self;
+
+    #[ensures(result.weight() == self.weight() + other.weight() - Self::ZERO)]
+    fn combine(self, other: Self) -> Self;
+}
+
+struct Mass {
+    grams: i32,
+}
+
+impl Weighted for Mass {
+    const ZERO: i32 = 0;
+
+    #[pure]
+    fn weight(&self) -> i32 {
+        self.grams
+    }
+
+    fn zero() -> Self {
+        Mass { grams: 0 }
+    }
+
+    #[pure]
+    fn one() -> i32 {
+        1
+    }
+
+    fn unit(self) -> Self {
+        Mass { grams: 1 }
+    }
+
+    fn combine(self, other: Self) -> Self {
+        Mass { grams: self.grams + other.grams }
+    }
+}
+
+struct Offset {
+    value: i32,
+}
+
+impl Weighted for Offset {
+    const ZERO: i32 = 10;
+
+    #[pure]
+    fn weight(&self) -> i32 {
+        self.value
+    }
+
+    fn zero() -> Self {
+        Offset { value: 10 }
+    }
+
+    #[pure]
+    fn one() -> i32 {
+        11
+    }
+
+    fn unit(self) -> Self {
+        Offset { value: 11 }
+    }
+
+    fn combine(self, other: Self) -> Self {
+        Offset { value: self.value + other.value - 10 }
+    }
+}
+
+fn test_mass() {
+    let zero = Mass::zero();
+    assert!(zero.weight() == 0);
+    let one = zero.unit();
+    assert!(one.weight() == 1);
+    let two = one.combine(Mass::zero().unit());
+    assert!(two.weight() == 2);
+}
+
+fn test_offset() {
+    let zero = Offset::zero();
+    assert!(zero.weight() == 10);
+    let one = zero.unit();
+    assert!(one.weight() == Offset::one());
+    let same = one.combine(Offset::zero());
+    assert!(same.weight() == 11);
+}
+
+fn main() {}
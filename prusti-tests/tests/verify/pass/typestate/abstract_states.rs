@@ -0,0 +1,47 @@
+use prusti_contracts::*;
+
+#[typestate(Idle, Connected, Authenticated)]
+struct Connection {
+    handle: u32,
+}
+
+impl Connection {
+    #[trusted]
+    #[transition(-> Idle)]
+    fn new() -> Self {
+        Connection { handle: 0 }
+    }
+
+    #[trusted]
+    #[transition(Idle -> Connected)]
+    fn connect(&mut self) {}
+
+    #[trusted]
+    #[transition(Connected -> Authenticated)]
+    fn login(&mut self) {}
+
+    #[trusted]
+    #[transition(Authenticated)]
+    fn send(&mut self, _data: u8) {}
+
+    #[trusted]
+    #[transition(Connected | Authenticated -> Idle)]
+    fn disconnect(&mut self) {}
+}
+
+#[requires(connection.prusti_typestate_Idle())]
+#[ensures(connection.prusti_typestate_Idle())]
+fn session(connection: &mut Connection) {
+    connection.connect();
+    connection.login();
+    connection.send(1);
+    connection.send(2);
+    connection.disconnect();
+}
+
+fn main() {
+    let mut connection = Connection::new();
+    session(&mut connection);
+    connection.connect();
+    connection.disconnect();
+}
@@ -0,0 +1,48 @@
+use prusti_contracts::*;
+
+#[typestate(Closed = !self.open, Open = self.open)]
+struct File {
+    open: bool,
+    position: u32,
+}
+
+impl File {
+    #[transition(-> Closed)]
+    fn new() -> Self {
+        File {
+            open: false,
+            position: 0,
+        }
+    }
+
+    #[transition(Closed -> Open)]
+    fn open(&mut self) {
+        self.open = true;
+        self.position = 0;
+    }
+
+    #[transition(Open)]
+    fn position(&self) -> u32 {
+        self.position
+    }
+
+    #[transition(Open)]
+    fn seek(&mut self, position: u32) {
+        self.position = position;
+    }
+
+    #[transition(Open | Closed -> Closed)]
+    fn close(&mut self) {
+        self.open = false;
+    }
+}
+
+fn main() {
+    let mut file = File::new();
+    file.open();
+    file.seek(3);
+    let _ = file.position();
+    file.close();
+    file.close();
+    file.open();
+}
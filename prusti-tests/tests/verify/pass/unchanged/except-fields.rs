@@ -0,0 +1,53 @@
+use prusti_contracts::*;
+
+#[derive(Clone, Copy)]
+struct Position {
+    line: u32,
+    column: u32,
+}
+
+struct Cursor {
+    position: Position,
+    offset: usize,
+    len: usize,
+    capacity: usize,
+}
+
+impl Cursor {
+    #[requires(self.len < self.capacity)]
+    #[ensures(self.len == old(self.len) + 1)]
+    #[ensures(unchanged!(self except self.len))]
+    fn push(&mut self) {
+        self.len += 1;
+    }
+
+    #[requires(self.offset < usize::MAX)]
+    #[ensures(unchanged!(self except self.offset, self.position))]
+    fn advance(&mut self) {
+        self.offset += 1;
+    }
+
+    #[ensures(unchanged!(self))]
+    fn check(&mut self) {}
+}
+
+#[requires(cursor.len < cursor.capacity)]
+#[ensures(unchanged!(cursor except cursor.len))]
+fn push_twice_if_possible(cursor: &mut Cursor) {
+    cursor.push();
+    if cursor.len < cursor.capacity {
+        cursor.push();
+    }
+}
+
+#[requires(cursor.offset < usize::MAX)]
+fn client(cursor: &mut Cursor) {
+    let capacity = cursor.capacity;
+    let len = cursor.len;
+    cursor.advance();
+    cursor.check();
+    assert!(cursor.capacity == capacity);
+    assert!(cursor.len == len);
+}
+
+fn main() {}
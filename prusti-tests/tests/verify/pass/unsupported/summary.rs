@@ -0,0 +1,21 @@
+// compile-flags: -Pskip_unsupported_features=true -Punsupported_features_summary=true
+use prusti_contracts::*;
+
+union Foo {
+    a: [i32; 1],
+    b: [i32; 1],
+}
+
+fn is_null(p: *const i32) -> bool {
+    p.is_null()
+}
+
+#[ensures(result == x + 1)]
+fn increment(x: i32) -> i32 {
+    x + 1
+}
+
+fn main() {
+    let _ = Foo { a: [0] };
+    assert!(increment(1) == 2);
+}
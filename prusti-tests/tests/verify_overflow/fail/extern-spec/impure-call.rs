@@ -0,0 +1,16 @@
+// The extern spec below does not mark `Option::is_some` as pure, so it cannot
+// be called from specifications
+
+extern crate prusti_contracts;
+use prusti_contracts::*;
+
+#[extern_spec]
+impl<T> std::option::Option<T> {
+    #[ensures(true)]
+    fn is_some(&self) -> bool;
+}
+
+#[requires(x.is_some())] //~ ERROR use of impure function
+fn client(x: Option<u32>) {}
+
+fn main() {}
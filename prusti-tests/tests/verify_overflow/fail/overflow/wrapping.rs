@@ -0,0 +1,14 @@
+use prusti_contracts::*;
+
+fn outside_block(a: u32, b: u32) -> u32 {
+    let c = wrapping!(a * b);
+    c + 1 //~ ERROR attempt to add with overflow
+}
+
+#[allow_overflow]
+#[ensures(result as u16 == a as u16 + b as u16)] //~ ERROR postcondition might not hold
+fn wrapped_result(a: u8, b: u8) -> u8 {
+    a + b
+}
+
+fn main() {}
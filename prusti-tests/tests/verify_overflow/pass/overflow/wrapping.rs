@@ -0,0 +1,37 @@
+use prusti_contracts::*;
+
+#[allow_overflow]
+#[ensures(a == u32::MAX && b == 1 ==> result == 0)]
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[allow_overflow]
+fn hash(a: u8, b: u8, c: u8) -> u8 {
+    a * 31 + b * 7 + c
+}
+
+#[allow_overflow]
+#[pure]
+fn wrapping_sub(a: i8, b: i8) -> i8 {
+    a - b
+}
+
+#[ensures(wrapping_sub(-128, 1) == 127)]
+fn test_wrapping_sub() {}
+
+#[ensures(a == 0 && b == 1 ==> result == u64::MAX)]
+fn sub(a: u64, b: u64) -> u64 {
+    wrapping!(a - b)
+}
+
+fn block(a: u16) -> u16 {
+    let b = wrapping! {
+        let c = a * 3;
+        c + 1
+    };
+    assert!(b <= 65535);
+    b
+}
+
+fn main() {}
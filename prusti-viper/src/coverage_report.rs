@@ -0,0 +1,164 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A report of which Rust functions of the crate were verified, written when
+//! `COVERAGE_REPORT` is enabled.
+
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Write as _, fs, io, path::Path};
+
+/// The outcome of the verification of one Rust function.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageStatus {
+    /// The function has a specification and was verified without errors.
+    Verified,
+    /// The function has no specification; it was only checked for the
+    /// absence of panics and overflows.
+    NoSpecs,
+    /// The function is `#[bounded(..)]`, or calls such a function; it was
+    /// verified without errors, but only for the executions that leave each
+    /// loop of the `#[bounded(..)]` functions within the bound.
+    Bounded,
+    /// The function is `#[trusted]` and was not verified.
+    Trusted,
+    /// The function uses a feature that Prusti does not support, so its
+    /// encoding was skipped.
+    Unsupported,
+    /// The verification of the function reported errors.
+    Failed,
+}
+
+impl CoverageStatus {
+    pub const ALL: &'static [CoverageStatus] = &[
+        CoverageStatus::Verified,
+        CoverageStatus::NoSpecs,
+        CoverageStatus::Bounded,
+        CoverageStatus::Trusted,
+        CoverageStatus::Unsupported,
+        CoverageStatus::Failed,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            CoverageStatus::Verified => "verified",
+            CoverageStatus::NoSpecs => "no specs",
+            CoverageStatus::Bounded => "bounded",
+            CoverageStatus::Trusted => "trusted",
+            CoverageStatus::Unsupported => "unsupported",
+            CoverageStatus::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct FunctionCoverage {
+    /// The absolute path of the Rust function.
+    pub function: String,
+    pub status: CoverageStatus,
+}
+
+#[derive(Default)]
+pub struct CoverageReport {
+    /// The functions indexed by the path of their module.
+    modules: BTreeMap<String, Vec<FunctionCoverage>>,
+}
+
+impl CoverageReport {
+    pub fn record(&mut self, module: String, function: String, status: CoverageStatus) {
+        self.modules
+            .entry(module)
+            .or_default()
+            .push(FunctionCoverage { function, status });
+    }
+
+    fn count<'a>(
+        functions: impl IntoIterator<Item = &'a FunctionCoverage>,
+        status: CoverageStatus,
+    ) -> usize {
+        functions
+            .into_iter()
+            .filter(|function| function.status == status)
+            .count()
+    }
+
+    /// A table with the number of functions of each status per module,
+    /// followed by the functions that were not verified.
+    pub fn summary(&self) -> String {
+        let mut summary = String::from("Verification coverage:\n");
+        write!(summary, "  {:<40}", "module").unwrap();
+        for status in CoverageStatus::ALL {
+            write!(summary, " {:>11}", status.description()).unwrap();
+        }
+        summary.push('\n');
+        let all_functions: Vec<_> = self.modules.values().flatten().collect();
+        for (module, functions) in &self.modules {
+            write!(summary, "  {:<40}", module).unwrap();
+            for &status in CoverageStatus::ALL {
+                write!(summary, " {:>11}", Self::count(functions, status)).unwrap();
+            }
+            summary.push('\n');
+        }
+        write!(summary, "  {:<40}", "total").unwrap();
+        for &status in CoverageStatus::ALL {
+            let count = Self::count(all_functions.iter().copied(), status);
+            write!(summary, " {:>11}", count).unwrap();
+        }
+        summary.push('\n');
+        for function in all_functions {
+            if function.status != CoverageStatus::Verified {
+                writeln!(
+                    summary,
+                    "  [{}] {}",
+                    function.status.description(),
+                    function.function
+                )
+                .unwrap();
+            }
+        }
+        summary
+    }
+
+    /// Write `prusti-coverage.json` to `dir`.
+    pub fn write(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let json = serde_json::to_string_pretty(&self.modules)?;
+        fs::write(dir.join("prusti-coverage.json"), json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let mut report = CoverageReport::default();
+        for (function, status) in [
+            ("a::f", CoverageStatus::Verified),
+            ("a::g", CoverageStatus::Failed),
+            ("b::h", CoverageStatus::Trusted),
+            ("b::i", CoverageStatus::Verified),
+        ] {
+            report.record(function[..1].to_string(), function.to_string(), status);
+        }
+
+        let summary = report.summary();
+        let lines: Vec<_> = summary.lines().collect();
+        let row = |name: &str, counts: [usize; 6]| {
+            let mut row = format!("  {:<40}", name);
+            for count in counts {
+                write!(row, " {:>11}", count).unwrap();
+            }
+            row
+        };
+        assert_eq!(lines[2], row("a", [1, 0, 0, 0, 0, 1]));
+        assert_eq!(lines[3], row("b", [1, 0, 0, 1, 0, 0]));
+        assert_eq!(lines[4], row("total", [2, 0, 0, 1, 0, 1]));
+        // Only the functions that were not verified are listed.
+        assert_eq!(lines[5..], ["  [failed] a::g", "  [trusted] b::h"]);
+    }
+}
@@ -5,6 +5,7 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::encoder::places;
+use prusti_common::config;
 use prusti_interface::data::ProcedureDefId;
 use prusti_interface::environment::Environment;
 use rustc_hir::{self as hir, Mutability};
@@ -160,6 +161,22 @@ impl<L: fmt::Debug, P: fmt::Debug> ProcedureContractGeneric<L, P> {
         }
     }
 
+    /// The substitutions with which the pledges have to be encoded. Inherited
+    /// pledges are declared on the trait method, so the same comment as in
+    /// `functional_precondition` applies.
+    pub fn pledges_substs<'tcx>(
+        &self,
+        env: &Environment<'tcx>,
+        substs: SubstsRef<'tcx>,
+    ) -> SubstsRef<'tcx> {
+        if let typed::SpecificationSet::Procedure(spec) = &self.specification {
+            if let typed::SpecificationItem::Inherited(_) = &spec.pledges {
+                return env.find_trait_method_substs(self.def_id, substs).unwrap().1;
+            }
+        }
+        substs
+    }
+
     pub fn pledges(&self) -> impl Iterator<Item = &typed::Pledge> + '_ {
         if let typed::SpecificationSet::Procedure(spec) = &self.specification {
             spec.pledges.extract_with_selective_replacement_iter()
@@ -524,6 +541,7 @@ where
             && !info.blocking_paths.is_empty()
             && info.blocked_paths.iter().any(|(_, mutability)| matches!(mutability, Mutability::Mut))
         ).collect();
+    let borrow_infos = merge_borrow_infos(borrow_infos);
     let is_not_blocked = |place: &mir::Place<'tcx>| {
         !borrow_infos.iter().any(|info| {
             info.blocked_paths
@@ -537,6 +555,11 @@ where
         .into_iter()
         .filter(|(place, _)| is_not_blocked(place))
         .collect();
+    let specification = if config::functional_correctness() {
+        specification
+    } else {
+        drop_functional_postconditions(env, proc_def_id, specification)
+    };
     let contract = ProcedureContractGeneric {
         def_id: proc_def_id,
         args: fake_mir_args,
@@ -549,3 +572,49 @@ where
     trace!("[compute_borrow_infos] exit result={}", contract);
     Ok(contract)
 }
+
+/// Merge the borrows of a procedure returning references with several
+/// lifetimes, such as `(&'a mut T, &'b mut U)`, into a single borrow. The
+/// caller then has to give back all returned references at once to regain
+/// the blocked arguments, which is what the magic wand of the postcondition
+/// (and the pledge, if any) expresses.
+fn merge_borrow_infos<P: fmt::Debug>(borrow_infos: Vec<BorrowInfo<P>>) -> Vec<BorrowInfo<P>> {
+    let mut borrow_infos = borrow_infos.into_iter();
+    let mut merged = match borrow_infos.next() {
+        Some(borrow_info) => borrow_info,
+        None => return Vec::new(),
+    };
+    for borrow_info in borrow_infos {
+        merged.blocking_paths.extend(borrow_info.blocking_paths);
+        merged.blocked_paths.extend(borrow_info.blocked_paths);
+    }
+    vec![merged]
+}
+
+/// Remove the postconditions and pledges of an impure procedure that is
+/// verified, so that they are neither checked nor assumed at its calls. The
+/// contracts of trusted and external procedures are kept, because they are
+/// never checked, and so are those of pure functions, which are also used in
+/// the encoding of the functions. The postconditions of lemmas and laws are
+/// kept as well, because they are the proven facts, which are assumed e.g.
+/// as axioms in the other procedures.
+fn drop_functional_postconditions(
+    env: &Environment<'_>,
+    proc_def_id: ProcedureDefId,
+    specification: typed::SpecificationSet,
+) -> typed::SpecificationSet {
+    match specification {
+        typed::SpecificationSet::Procedure(mut spec) => {
+            let is_trusted = spec.trusted.extract_inherit().unwrap_or(false);
+            let is_impure = spec.kind.is_impure().unwrap_or(false);
+            let is_proof = env.has_prusti_attribute(proc_def_id, "lemma")
+                || env.has_prusti_attribute(proc_def_id, "law");
+            if proc_def_id.is_local() && is_impure && !is_trusted && !is_proof {
+                spec.posts = typed::SpecificationItem::Empty;
+                spec.pledges = typed::SpecificationItem::Empty;
+            }
+            typed::SpecificationSet::Procedure(spec)
+        }
+        specification => specification,
+    }
+}
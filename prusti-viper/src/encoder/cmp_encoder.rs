@@ -0,0 +1,187 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The built-in encoding of the comparison functions of `std::cmp` on
+//! integers, ghost integers and characters, and of the methods of
+//! `Ordering`. Their result is encoded directly, in pure and in impure code,
+//! so that they can be used in specifications and their result is known
+//! after a call.
+
+use crate::encoder::{
+    errors::EncodingResult, ghost_int_encoder::GhostInt, mir::types::compute_discriminant_values,
+    snapshot::interface::SnapshotEncoderInterface, Encoder,
+};
+use rustc_middle::{ty, ty::subst::SubstsRef};
+use vir_crate::polymorphic as vir;
+
+/// A comparison function with a built-in encoding.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum CmpFunction {
+    /// `Ord::cmp`
+    Cmp,
+    /// `PartialOrd::partial_cmp`
+    PartialCmp,
+    /// `PartialOrd::lt`, `le`, `gt` or `ge`
+    Compare(vir::BinaryOpKind),
+    /// `Ord::min` or `std::cmp::min`
+    Min,
+    /// `Ord::max` or `std::cmp::max`
+    Max,
+    /// `Ord::clamp`, which panics if its lower bound is greater than its
+    /// upper bound.
+    Clamp,
+    /// `Ordering::is_eq`, `is_ne`, `is_lt`, `is_gt`, `is_le` or `is_ge`,
+    /// which compare the discriminant of the ordering with 0.
+    OrderingIs(vir::BinaryOpKind),
+    /// `Ordering::reverse`
+    Reverse,
+}
+
+/// The encoded result of a call of a comparison function.
+pub(crate) enum CmpResult {
+    /// An integer or boolean value.
+    Value(vir::Expr),
+    /// An `Ordering` with the given discriminant.
+    Ordering(vir::Expr),
+    /// `Some` of an `Ordering` with the given discriminant.
+    SomeOrdering(vir::Expr),
+}
+
+impl CmpFunction {
+    /// The comparison function at `path`, if its call with `substs` has a
+    /// built-in encoding.
+    pub(crate) fn from_call<'tcx>(
+        tcx: ty::TyCtxt<'tcx>,
+        path: &str,
+        substs: SubstsRef<'tcx>,
+    ) -> Option<Self> {
+        let path = path
+            .strip_prefix("std::cmp::")
+            .or_else(|| path.strip_prefix("core::cmp::"))?;
+        let function = match path {
+            "Ord::cmp" => CmpFunction::Cmp,
+            "PartialOrd::partial_cmp" => CmpFunction::PartialCmp,
+            "PartialOrd::lt" => CmpFunction::Compare(vir::BinaryOpKind::LtCmp),
+            "PartialOrd::le" => CmpFunction::Compare(vir::BinaryOpKind::LeCmp),
+            "PartialOrd::gt" => CmpFunction::Compare(vir::BinaryOpKind::GtCmp),
+            "PartialOrd::ge" => CmpFunction::Compare(vir::BinaryOpKind::GeCmp),
+            "Ord::min" | "min" => CmpFunction::Min,
+            "Ord::max" | "max" => CmpFunction::Max,
+            "Ord::clamp" => CmpFunction::Clamp,
+            "Ordering::is_eq" => return Some(CmpFunction::OrderingIs(vir::BinaryOpKind::EqCmp)),
+            "Ordering::is_ne" => return Some(CmpFunction::OrderingIs(vir::BinaryOpKind::NeCmp)),
+            "Ordering::is_lt" => return Some(CmpFunction::OrderingIs(vir::BinaryOpKind::LtCmp)),
+            "Ordering::is_gt" => return Some(CmpFunction::OrderingIs(vir::BinaryOpKind::GtCmp)),
+            "Ordering::is_le" => return Some(CmpFunction::OrderingIs(vir::BinaryOpKind::LeCmp)),
+            "Ordering::is_ge" => return Some(CmpFunction::OrderingIs(vir::BinaryOpKind::GeCmp)),
+            "Ordering::reverse" => return Some(CmpFunction::Reverse),
+            _ => return None,
+        };
+        // The comparisons of other types are user-defined.
+        let compared_ty = substs.type_at(0);
+        if compared_ty.is_integral()
+            || compared_ty.is_char()
+            || GhostInt::from_ty(tcx, compared_ty).is_some()
+        {
+            Some(function)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the arguments are passed by reference.
+    pub(crate) fn takes_references(&self) -> bool {
+        matches!(
+            self,
+            CmpFunction::Cmp | CmpFunction::PartialCmp | CmpFunction::Compare(_)
+        )
+    }
+
+    /// The condition under which the call does not panic.
+    pub(crate) fn precondition(&self, args: &[vir::Expr]) -> Option<vir::Expr> {
+        match self {
+            CmpFunction::Clamp => Some(vir::Expr::le_cmp(args[1].clone(), args[2].clone())),
+            _ => None,
+        }
+    }
+
+    /// Encode the result of the call. `args` are the values of the compared
+    /// integers or the discriminants of the `Ordering` arguments.
+    pub(crate) fn encode_result(&self, args: Vec<vir::Expr>) -> CmpResult {
+        let arg = |index: usize| args[index].clone();
+        match self {
+            CmpFunction::Cmp => CmpResult::Ordering(ordering_discriminant(arg(0), arg(1))),
+            CmpFunction::PartialCmp => {
+                CmpResult::SomeOrdering(ordering_discriminant(arg(0), arg(1)))
+            }
+            CmpFunction::Compare(op_kind) => {
+                CmpResult::Value(vir::Expr::bin_op(*op_kind, arg(0), arg(1)))
+            }
+            // As in `std`, the first argument is returned if both are equal.
+            CmpFunction::Min => CmpResult::Value(vir::Expr::ite(
+                vir::Expr::le_cmp(arg(0), arg(1)),
+                arg(0),
+                arg(1),
+            )),
+            CmpFunction::Max => CmpResult::Value(vir::Expr::ite(
+                vir::Expr::le_cmp(arg(0), arg(1)),
+                arg(1),
+                arg(0),
+            )),
+            CmpFunction::Clamp => CmpResult::Value(vir::Expr::ite(
+                vir::Expr::lt_cmp(arg(0), arg(1)),
+                arg(1),
+                vir::Expr::ite(vir::Expr::gt_cmp(arg(0), arg(2)), arg(2), arg(0)),
+            )),
+            CmpFunction::OrderingIs(op_kind) => {
+                CmpResult::Value(vir::Expr::bin_op(*op_kind, arg(0), 0.into()))
+            }
+            CmpFunction::Reverse => CmpResult::Ordering(vir::Expr::minus(arg(0))),
+        }
+    }
+}
+
+/// The discriminant of the `Ordering` of `left` and `right`: -1 for `Less`,
+/// 0 for `Equal` and 1 for `Greater`.
+fn ordering_discriminant(left: vir::Expr, right: vir::Expr) -> vir::Expr {
+    vir::Expr::ite(
+        vir::Expr::lt_cmp(left.clone(), right.clone()),
+        (-1).into(),
+        vir::Expr::ite(vir::Expr::eq_cmp(left, right), 0.into(), 1.into()),
+    )
+}
+
+/// The discriminant of the variant `variant_index` of the enum `ty`.
+pub(crate) fn variant_discriminant<'tcx>(
+    encoder: &Encoder<'_, 'tcx>,
+    ty: ty::Ty<'tcx>,
+    variant_index: usize,
+) -> i128 {
+    match ty.kind() {
+        ty::TyKind::Adt(adt_def, _) => {
+            compute_discriminant_values(*adt_def, encoder.env().tcx())[variant_index]
+        }
+        _ => unreachable!("{:?} is not an enum", ty),
+    }
+}
+
+/// The snapshot of the `Ordering` of type `ordering_ty` with the given
+/// discriminant.
+pub(crate) fn encode_ordering_snapshot<'tcx>(
+    encoder: &Encoder<'_, 'tcx>,
+    ordering_ty: ty::Ty<'tcx>,
+    discriminant: vir::Expr,
+) -> EncodingResult<vir::Expr> {
+    // The variants `Less`, `Equal` and `Greater`, in this order.
+    let less = encoder.encode_snapshot(ordering_ty, Some(0), vec![])?;
+    let equal = encoder.encode_snapshot(ordering_ty, Some(1), vec![])?;
+    let greater = encoder.encode_snapshot(ordering_ty, Some(2), vec![])?;
+    Ok(vir::Expr::ite(
+        vir::Expr::lt_cmp(discriminant.clone(), 0.into()),
+        less,
+        vir::Expr::ite(vir::Expr::eq_cmp(discriminant, 0.into()), equal, greater),
+    ))
+}
@@ -51,6 +51,7 @@ pub(super) fn collect_definitions(
         used_predicates: Default::default(),
         used_fields: Default::default(),
         used_domains: Default::default(),
+        used_user_domains: Default::default(),
         used_snap_domain_functions: Default::default(),
         used_functions: Default::default(),
         checked_function_contracts: Default::default(),
@@ -77,6 +78,9 @@ struct Collector<'p, 'v: 'p, 'tcx: 'v> {
     new_unfolded_predicates: FxHashSet<vir::Type>,
     used_fields: FxHashSet<vir::Field>,
     used_domains: FxHashSet<String>,
+    /// The set of the domains of `#[domain]` modules that are mentioned in
+    /// the method.
+    used_user_domains: FxHashSet<String>,
     used_snap_domain_functions: FxHashSet<vir::FunctionIdentifier>,
     /// The set of all functions that are mentioned in the method.
     used_functions: FxHashSet<vir::FunctionIdentifier>,
@@ -177,6 +181,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> Collector<'p, 'v, 'tcx> {
                         vir::Predicate::Struct(vir::StructPredicate {
                             typ: predicate.typ,
                             this: predicate.this,
+                            extra_params: predicate.extra_params,
                             body: None,
                         })
                     }
@@ -274,6 +279,11 @@ impl<'p, 'v: 'p, 'tcx: 'v> Collector<'p, 'v, 'tcx> {
             });
             domains.push(mirror_domain);
         }
+        domains.extend(
+            self.used_user_domains
+                .iter()
+                .filter_map(|name| self.encoder.get_user_domain(name)),
+        );
         domains.sort_by_cached_key(|domain| domain.name.clone());
         domains
     }
@@ -451,6 +461,11 @@ impl<'p, 'v: 'p, 'tcx: 'v> FallibleExprWalker for Collector<'p, 'v, 'tcx> {
                     self.used_domains
                         .insert(domain_function.domain_name.clone());
                 }
+                name if name.starts_with("Domain$") => {
+                    // Domains of `#[domain]` modules are included with all
+                    // their functions and axioms.
+                    self.used_user_domains.insert(name.to_string());
+                }
                 name => {
                     unreachable!("Unexpected domain: {}", name);
                 }
@@ -8,14 +8,17 @@ use ::log::{info, debug, trace};
 use crate::encoder::borrows::{compute_procedure_contract, ProcedureContract, ProcedureContractMirDef};
 use crate::encoder::builtin_encoder::BuiltinEncoder;
 use crate::encoder::builtin_encoder::BuiltinMethodKind;
-use crate::encoder::errors::{ErrorManager, SpannedEncodingError, EncodingError};
+use crate::encoder::errors::{ErrorManager, SpannedEncodingError, EncodingError, EncodingErrorKind, UnsupportedFeatures, WithSpan};
 use crate::encoder::foldunfold;
+use crate::encoder::frame_conditions::{infer_frame_condition, FrameCondition};
 use crate::encoder::places;
 use crate::encoder::procedure_encoder::ProcedureEncoder;
+use crate::encoder::procedure_summary::{infer_procedure_summary, ProcedureSummary};
 use crate::encoder::SpecFunctionKind;
 use crate::encoder::spec_function_encoder::SpecFunctionEncoder;
 use prusti_common::{vir_expr, vir_local};
 use prusti_common::config;
+use prusti_common::vir::optimizations::functions::inline_spec_functions;
 use prusti_common::report::log;
 use prusti_interface::data::ProcedureDefId;
 use prusti_interface::environment::Environment;
@@ -23,15 +26,19 @@ use prusti_interface::specs::typed;
 use prusti_interface::PrustiError;
 use vir_crate::polymorphic::{self as vir};
 use vir_crate::common::identifier::WithIdentifier;
-use rustc_hir::def_id::DefId;
+use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_middle::mir;
 use rustc_middle::ty;
-use rustc_middle::ty::subst::SubstsRef;
+use rustc_middle::ty::subst::{Subst, SubstsRef};
 use std::cell::{Cell, RefCell, RefMut, Ref};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::io::Write;
 use std::rc::Rc;
+use std::panic::{self, AssertUnwindSafe};
+use rustc_span::fatal_error::FatalErrorMarker;
+use std::time::Instant;
 use crate::encoder::stub_procedure_encoder::StubProcedureEncoder;
+use crate::performance_report::PerformanceReport;
 use std::ops::AddAssign;
 use crate::encoder::name_interner::NameInterner;
 use crate::encoder::errors::EncodingResult;
@@ -46,7 +53,7 @@ use super::mir::procedures::MirProcedureEncoderState;
 use super::mir::type_layouts::MirTypeLayoutsEncoderState;
 use super::mir::{
     pure::{
-        PureFunctionEncoderState, PureFunctionEncoderInterface,
+        PureFunctionEncoderState, PureFunctionEncoderInterface, SpecificationEncoderInterface,
     },
     types::{
         compute_discriminant_bounds,
@@ -65,6 +72,10 @@ pub struct Encoder<'v, 'tcx: 'v> {
         ProcedureDefId,
         EncodingResult<ProcedureContractMirDef<'tcx>>
     >>,
+    /// The summaries inferred for functions without a contract.
+    procedure_summaries: RefCell<FxHashMap<ProcedureDefId, Option<Rc<ProcedureSummary>>>>,
+    /// The frame conditions inferred for methods taking `&mut self`.
+    frame_conditions: RefCell<FxHashMap<ProcedureDefId, Option<Rc<FrameCondition>>>>,
     /// A map containing all functions: identifier → function definition.
     functions: RefCell<FxHashMap<vir::FunctionIdentifier, Rc<vir::Function>>>,
     builtin_methods: RefCell<FxHashMap<BuiltinMethodKind, vir::BodylessMethod>>,
@@ -81,6 +92,10 @@ pub struct Encoder<'v, 'tcx: 'v> {
     spec_functions: RefCell<FxHashMap<ProcedureDefId, Vec<vir::FunctionIdentifier>>>,
     type_discriminant_funcs: RefCell<FxHashMap<String, vir::FunctionIdentifier>>,
     type_cast_functions: RefCell<FxHashMap<(ty::Ty<'tcx>, ty::Ty<'tcx>), vir::FunctionIdentifier>>,
+    /// The functions that encode `sum`, `count` and `max` comprehensions.
+    comprehension_functions: RefCell<FxHashMap<String, vir::FunctionIdentifier>>,
+    /// The Viper domains of `#[domain]` modules, by the name of the domain.
+    user_domains: RefCell<FxHashMap<String, vir::Domain>>,
     pub(super) snapshot_encoder_state: SnapshotEncoderState,
     pub(super) mirror_encoder: RefCell<MirrorEncoder>,
     array_types_encoder: RefCell<SequenceTypesEncoder<'tcx>>,
@@ -88,9 +103,28 @@ pub struct Encoder<'v, 'tcx: 'v> {
     vir_program_before_foldunfold_writer: Option<RefCell<Box<dyn Write>>>,
     vir_program_before_viper_writer: Option<RefCell<Box<dyn Write>>>,
     encoding_errors_counter: RefCell<usize>,
+    /// The unsupported features encountered while encoding, for the summary
+    /// printed with `UNSUPPORTED_FEATURES_SUMMARY`.
+    unsupported_features: RefCell<UnsupportedFeatures>,
+    /// The procedures whose encoding failed, mapped to whether the failure
+    /// was caused by an unsupported feature.
+    failed_procedures: RefCell<FxHashMap<ProcedureDefId, bool>>,
+    /// The procedures whose contract was used at a call site.
+    used_contracts: RefCell<FxHashSet<ProcedureDefId>>,
     name_interner: RefCell<NameInterner>,
     /// Maps locals to the local of their discriminant.
     discriminants_info: RefCell<FxHashMap<(ProcedureDefId, String), Vec<String>>>,
+    /// The time spent encoding and verifying each program.
+    performance_report: RefCell<PerformanceReport>,
+    /// The names of the programs of procedures annotated with `#[nonlinear_arithmetic]`.
+    nonlinear_arithmetic_programs: RefCell<FxHashSet<String>>,
+    /// The names of the encoded pure functions annotated with `#[inline_spec]`.
+    inline_spec_functions: RefCell<FxHashSet<String>>,
+    /// The names of the encoded pure functions annotated with `#[never_inline_spec]`.
+    never_inline_spec_functions: RefCell<FxHashSet<String>>,
+    /// The values given in the code of the encoded `#[symbolic]` constants, by
+    /// the name of the Viper function that encodes the constant.
+    symbolic_constant_values: RefCell<FxHashMap<String, vir::Expr>>,
     /// Whether the current pure expression that's being encoded sits inside a trigger closure.
     /// Viper limits the type of expressions that are allowed in quantifier triggers and
     /// this requires special care when encoding array/slice accesses which may come with
@@ -137,6 +171,8 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             env,
             error_manager: RefCell::new(ErrorManager::new(env.codemap())),
             procedure_contracts: RefCell::new(FxHashMap::default()),
+            procedure_summaries: RefCell::new(FxHashMap::default()),
+            frame_conditions: RefCell::new(FxHashMap::default()),
             functions: RefCell::new(FxHashMap::default()),
             builtin_methods: RefCell::new(FxHashMap::default()),
             high_builtin_function_encoder_state: Default::default(),
@@ -151,6 +187,8 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             spec_functions: RefCell::new(FxHashMap::default()),
             type_discriminant_funcs: RefCell::new(FxHashMap::default()),
             type_cast_functions: RefCell::new(FxHashMap::default()),
+            comprehension_functions: RefCell::new(FxHashMap::default()),
+            user_domains: RefCell::new(FxHashMap::default()),
             encoding_queue: RefCell::new(vec![]),
             vir_program_before_foldunfold_writer,
             vir_program_before_viper_writer,
@@ -158,8 +196,16 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             mirror_encoder: RefCell::new(MirrorEncoder::new()),
             array_types_encoder: RefCell::new(SequenceTypesEncoder::new()),
             encoding_errors_counter: RefCell::new(0),
+            unsupported_features: RefCell::new(UnsupportedFeatures::default()),
+            failed_procedures: RefCell::new(FxHashMap::default()),
+            used_contracts: RefCell::new(FxHashSet::default()),
             name_interner: RefCell::new(NameInterner::new()),
             discriminants_info: RefCell::new(FxHashMap::default()),
+            performance_report: RefCell::new(PerformanceReport::default()),
+            nonlinear_arithmetic_programs: RefCell::new(FxHashSet::default()),
+            inline_spec_functions: RefCell::new(FxHashSet::default()),
+            never_inline_spec_functions: RefCell::new(FxHashSet::default()),
+            symbolic_constant_values: RefCell::new(FxHashMap::default()),
             is_encoding_trigger: Cell::new(false),
             specifications_state: SpecificationsState::new(def_spec)
         }
@@ -210,9 +256,38 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         self.error_manager.borrow_mut()
     }
 
+    pub fn performance_report(&self) -> RefMut<PerformanceReport> {
+        self.performance_report.borrow_mut()
+    }
+
+    /// Whether the program `program_name` should be verified with the nonlinear
+    /// arithmetic reasoning of the SMT solver enabled.
+    pub fn uses_nonlinear_arithmetic(&self, program_name: &str) -> bool {
+        self.nonlinear_arithmetic_programs.borrow().contains(program_name)
+    }
+
+    /// Record that the encoded pure function `function_name` of `proc_def_id`
+    /// is annotated with `#[inline_spec]` or `#[never_inline_spec]`.
+    pub(super) fn record_spec_inlining(&self, proc_def_id: ProcedureDefId, function_name: &str) {
+        if self.env.has_prusti_attribute(proc_def_id, "inline_spec") {
+            self.inline_spec_functions.borrow_mut().insert(function_name.to_string());
+        }
+        if self.env.has_prusti_attribute(proc_def_id, "never_inline_spec") {
+            self.never_inline_spec_functions.borrow_mut().insert(function_name.to_string());
+        }
+    }
+
+    /// Whether the pure function `function_name` must not be inlined at its
+    /// call sites by the optimizations.
+    pub fn is_never_inlined_spec(&self, function_name: &str) -> bool {
+        self.never_inline_spec_functions.borrow().contains(function_name)
+    }
+
     pub fn finalize_viper_program(&self, name: String, proc_def_id: DefId) -> SpannedEncodingResult<vir::Program> {
         let error_span = self.env.get_def_span(proc_def_id);
-        super::definition_collector::collect_definitions(error_span, self, name, self.get_used_viper_methods())
+        let program = super::definition_collector::collect_definitions(error_span, self, name, self.get_used_viper_methods())?;
+        let inlined_functions = self.inline_spec_functions.borrow();
+        Ok(inline_spec_functions(program, |function_name| inlined_functions.contains(function_name)))
     }
 
     pub fn get_viper_programs(&mut self) -> Vec<vir::Program> {
@@ -225,6 +300,11 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
 
     pub(in crate::encoder) fn register_encoding_error(&self, encoding_error: SpannedEncodingError) {
         debug!("Encoding error: {:?}", encoding_error);
+        if let EncodingErrorKind::Unsupported(message) = encoding_error.kind() {
+            self.unsupported_features
+                .borrow_mut()
+                .record(message, encoding_error.span().clone());
+        }
         let prusti_error: PrustiError = encoding_error.into();
         if prusti_error.is_error() {
             self.encoding_errors_counter.borrow_mut().add_assign(1);
@@ -236,10 +316,44 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         *self.encoding_errors_counter.borrow()
     }
 
+    pub fn unsupported_features(&self) -> Ref<UnsupportedFeatures> {
+        self.unsupported_features.borrow()
+    }
+
+    /// Register an error that prevented the encoding of `proc_def_id`.
+    fn register_procedure_encoding_error(
+        &self,
+        proc_def_id: ProcedureDefId,
+        encoding_error: SpannedEncodingError,
+    ) {
+        let is_unsupported = matches!(encoding_error.kind(), EncodingErrorKind::Unsupported(_));
+        self.failed_procedures.borrow_mut().insert(proc_def_id, is_unsupported);
+        self.register_encoding_error(encoding_error);
+    }
+
+    /// Returns `Some(is_unsupported)` if the encoding of `proc_def_id` failed.
+    pub fn procedure_encoding_failure(&self, proc_def_id: ProcedureDefId) -> Option<bool> {
+        self.failed_procedures.borrow().get(&proc_def_id).copied()
+    }
+
+    /// Record that the contract of `proc_def_id` is assumed at a call site.
+    pub(super) fn register_contract_use(&self, proc_def_id: ProcedureDefId) {
+        self.used_contracts.borrow_mut().insert(proc_def_id);
+    }
+
+    /// Whether the contract of `proc_def_id` was assumed at some call site.
+    pub fn is_contract_used(&self, proc_def_id: ProcedureDefId) -> bool {
+        self.used_contracts.borrow().contains(&proc_def_id)
+    }
+
     pub(super) fn get_mirror_domain(&self) -> Option<vir::Domain> {
         self.mirror_encoder.borrow().get_domain().cloned()
     }
 
+    pub(super) fn get_user_domain(&self, domain_name: &str) -> Option<vir::Domain> {
+        self.user_domains.borrow().get(domain_name).cloned()
+    }
+
     pub(super) fn insert_function(&self, function: vir::Function) -> vir::FunctionIdentifier {
         let identifier: vir::FunctionIdentifier = function.get_identifier().into();
         assert!(self.functions.borrow_mut().insert(identifier.clone(), Rc::new(function)).is_none(), "{:?} is not unique", identifier);
@@ -290,8 +404,19 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
                 const_value.try_to_scalar()
             }
             ty::ConstKind::Unevaluated(ct) => {
+                use rustc_middle::ty::TypeFoldable;
                 let tcx = self.env().tcx();
                 let param_env = tcx.param_env(ct.def.did);
+                // An associated constant of `Self` in the contract of a trait
+                // method is resolved to the constant of the implementation
+                // when the contract is used with `Self` replaced by the
+                // implementing type, but not for a generic type.
+                if ct.substs.has_param_types_or_consts() {
+                    return Err(EncodingError::unsupported(format!(
+                        "the associated constant {} of a generic type cannot be evaluated",
+                        tcx.def_path_str(ct.def.did),
+                    )));
+                }
                 tcx.const_eval_resolve(param_env, ct, None)
                     .ok()
                     .and_then(|const_value| const_value.try_to_scalar())
@@ -333,6 +458,54 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             .map_err(|err| err.clone())
     }
 
+    /// The summary of a private function without a contract, inferred if
+    /// `INFER_PROCEDURE_SUMMARIES` is enabled.
+    pub fn get_procedure_summary(&self, proc_def_id: ProcedureDefId) -> Option<Rc<ProcedureSummary>> {
+        if !config::infer_procedure_summaries() {
+            return None;
+        }
+        if let Some(summary) = self.procedure_summaries.borrow().get(&proc_def_id) {
+            return summary.clone();
+        }
+        let summary = if self.is_pure(proc_def_id) || self.get_procedure_specs(proc_def_id).is_some() {
+            None
+        } else {
+            infer_procedure_summary(self.env, proc_def_id)
+                .filter(|summary| !summary.is_empty())
+                .map(Rc::new)
+        };
+        self.procedure_summaries
+            .borrow_mut()
+            .insert(proc_def_id, summary.clone());
+        summary
+    }
+
+    /// The frame condition of a method taking `&mut self`, inferred if
+    /// `AUTO_FRAME_CONDITIONS` is enabled and the method is not annotated with
+    /// `#[no_auto_frame]`.
+    pub fn get_frame_condition(&self, proc_def_id: ProcedureDefId) -> Option<Rc<FrameCondition>> {
+        if !config::auto_frame_conditions() {
+            return None;
+        }
+        if let Some(frame_condition) = self.frame_conditions.borrow().get(&proc_def_id) {
+            return frame_condition.clone();
+        }
+        // The body of a trusted method, e.g. `unimplemented!()`, does not
+        // describe which fields it modifies.
+        let frame_condition = if self.is_pure(proc_def_id)
+            || self.is_trusted(proc_def_id)
+            || self.env.has_prusti_attribute(proc_def_id, "no_auto_frame")
+        {
+            None
+        } else {
+            infer_frame_condition(self.env, proc_def_id).map(Rc::new)
+        };
+        self.frame_conditions
+            .borrow_mut()
+            .insert(proc_def_id, frame_condition.clone());
+        frame_condition
+    }
+
     pub fn get_procedure_contract_for_call(
         &self,
         caller_def_id: ProcedureDefId,
@@ -341,10 +514,14 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         target: places::Local,
         call_substs: SubstsRef<'tcx>,
     ) -> EncodingResult<ProcedureContract<'tcx>> {
+        self.register_contract_use(called_def_id);
         let (called_def_id, call_substs) = self.env()
             .resolve_method_call(caller_def_id, called_def_id, call_substs);
-        let spec = self.get_procedure_specs(called_def_id)
+        self.register_contract_use(called_def_id);
+        let mut spec = self.get_procedure_specs(called_def_id)
             .unwrap_or_else(typed::ProcedureSpecification::empty);
+        self.add_lock_invariants(called_def_id, call_substs, &mut spec)?;
+        self.add_message_invariants(called_def_id, call_substs, &mut spec);
         let contract = compute_procedure_contract(
             called_def_id,
             self.env(),
@@ -354,6 +531,184 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         Ok(contract.to_call_site_contract(args, target))
     }
 
+    /// Add the lock invariant of a `#[lock_invariant]` struct to the
+    /// specification of a function of `Mutex` or `RwLock` called on values of
+    /// that struct:
+    /// - `new` and `from` require the invariant;
+    /// - `deref` ensures the invariant;
+    /// - `deref_mut` ensures the invariant and asserts it on expiry.
+    ///
+    /// The functions that create or access the protected value without the
+    /// invariant, such as `get_mut`, `into_inner` and `default`, are rejected.
+    fn add_lock_invariants(
+        &self,
+        called_def_id: ProcedureDefId,
+        call_substs: SubstsRef<'tcx>,
+        spec: &mut typed::ProcedureSpecification,
+    ) -> EncodingResult<()> {
+        let target = match self.get_invariant_target(called_def_id) {
+            Some(target) => target,
+            None => return Ok(()),
+        };
+        self.check_lock_invariant_bypass(called_def_id, call_substs, &target)?;
+        let find_invariants = |target: &str| -> Vec<LocalDefId> {
+            self.filter_invariant_specs(called_def_id, call_substs, self.get_lock_invariant_specs(target))
+        };
+        let invariants = find_invariants(&target);
+        if invariants.is_empty() {
+            return Ok(());
+        }
+        match self.env().tcx().item_name(called_def_id).as_str() {
+            "new" | "from" => extend_specification_item(&mut spec.pres, invariants),
+            "deref" => extend_specification_item(&mut spec.posts, invariants),
+            "deref_mut" => {
+                let after_expiry = find_invariants(&format!("{}::after_expiry", target));
+                let pledges = invariants.iter()
+                    .zip(after_expiry)
+                    .map(|(invariant, rhs)| typed::Pledge {
+                        reference: None,
+                        lhs: Some(*invariant),
+                        rhs,
+                    })
+                    .collect();
+                extend_specification_item(&mut spec.pledges, pledges);
+                extend_specification_item(&mut spec.posts, invariants);
+            }
+            _ => unreachable!("unexpected lock invariant of {}", target),
+        }
+        Ok(())
+    }
+
+    /// Reject the calls of the functions of `Mutex` and `RwLock` that create
+    /// or modify the protected value without the lock invariant, when the
+    /// protected struct has one.
+    fn check_lock_invariant_bypass(
+        &self,
+        called_def_id: ProcedureDefId,
+        call_substs: SubstsRef<'tcx>,
+        target: &str,
+    ) -> EncodingResult<()> {
+        let tcx = self.env().tcx();
+        let lock = match ["std::sync::Mutex", "std::sync::RwLock"]
+            .iter()
+            .find(|lock| target.strip_prefix(lock).map_or(false, |name| name.starts_with("::")))
+        {
+            Some(lock) => lock,
+            None => return Ok(()),
+        };
+        let method_name = tcx.item_name(called_def_id);
+        if !matches!(method_name.as_str(), "get_mut" | "into_inner" | "default") {
+            return Ok(());
+        }
+        let impl_def_id = match tcx.impl_of_method(called_def_id) {
+            Some(impl_def_id) => impl_def_id,
+            None => return Ok(()),
+        };
+        let protected_ty = match tcx.type_of(impl_def_id).subst(tcx, call_substs).kind() {
+            ty::TyKind::Adt(_, substs) => tcx.erase_regions(substs.type_at(0)),
+            _ => return Ok(()),
+        };
+        let has_invariant = self
+            .get_lock_invariant_specs(&format!("{}::new", lock))
+            .into_iter()
+            .any(|spec_fn| {
+                let sig = tcx.fn_sig(spec_fn.to_def_id());
+                tcx.erase_regions(tcx.erase_late_bound_regions(sig).inputs()[0]) == protected_ty
+            });
+        if has_invariant {
+            return Err(EncodingError::unsupported(format!(
+                "calling `{}::{}` is not supported on values with a lock invariant, because it \
+                bypasses the invariant",
+                lock.trim_start_matches("std::sync::"),
+                method_name,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Add the message invariant of a `#[message_invariant]` struct to the
+    /// specification of a function of a channel of that struct:
+    /// - `send`, `try_send` and `send_timeout` require the invariant of the
+    ///   sent value;
+    /// - `recv` and `try_recv` ensure the invariant of the received value.
+    fn add_message_invariants(
+        &self,
+        called_def_id: ProcedureDefId,
+        call_substs: SubstsRef<'tcx>,
+        spec: &mut typed::ProcedureSpecification,
+    ) {
+        let target = match self.get_invariant_target(called_def_id) {
+            Some(target) => target,
+            None => return,
+        };
+        let invariants = self.filter_invariant_specs(
+            called_def_id,
+            call_substs,
+            self.get_message_invariant_specs(&target),
+        );
+        if invariants.is_empty() {
+            return;
+        }
+        match self.env().tcx().item_name(called_def_id).as_str() {
+            "send" | "try_send" | "send_timeout" => {
+                extend_specification_item(&mut spec.pres, invariants)
+            }
+            "recv" | "try_recv" => extend_specification_item(&mut spec.posts, invariants),
+            _ => unreachable!("unexpected message invariant of {}", target),
+        }
+    }
+
+    /// The name under which lock and message invariants are attached to a
+    /// method, e.g. `std::sync::MutexGuard::deref`.
+    fn get_invariant_target(&self, called_def_id: ProcedureDefId) -> Option<String> {
+        let tcx = self.env().tcx();
+        let impl_self_ty = tcx.impl_of_method(called_def_id)
+            .map(|impl_def_id| tcx.type_of(impl_def_id));
+        match impl_self_ty.map(|ty| ty.kind()) {
+            Some(ty::TyKind::Adt(adt_def, _)) => Some(format!(
+                "{}::{}",
+                tcx.def_path_str(adt_def.did()),
+                tcx.item_name(called_def_id),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Keep the spec functions of the invariants of the type of a call.
+    /// The spec functions of all lock and message invariants have the same
+    /// signature as the function they specify, so the invariants of the
+    /// protected or transmitted type are found by comparing the first argument.
+    fn filter_invariant_specs(
+        &self,
+        called_def_id: ProcedureDefId,
+        call_substs: SubstsRef<'tcx>,
+        spec_fns: Vec<LocalDefId>,
+    ) -> Vec<LocalDefId> {
+        let tcx = self.env().tcx();
+        let first_input_ty = |sig: ty::PolyFnSig<'tcx>| {
+            tcx.erase_regions(tcx.erase_late_bound_regions(sig).inputs()[0])
+        };
+        let called_input_ty = first_input_ty(tcx.fn_sig(called_def_id).subst(tcx, call_substs));
+        spec_fns
+            .into_iter()
+            .filter(|spec_fn| first_input_ty(tcx.fn_sig(spec_fn.to_def_id())) == called_input_ty)
+            .collect()
+    }
+
+    /// Get the spec functions of the refinements of the `#[refined]` struct
+    /// `ty`, which take a value of the struct as their only argument.
+    pub fn get_refinements(&self, ty: ty::Ty<'tcx>) -> Vec<LocalDefId> {
+        let tcx = self.env().tcx();
+        let ty = tcx.erase_regions(ty);
+        self.get_refinement_specs()
+            .into_iter()
+            .filter(|spec_fn| {
+                let sig = tcx.fn_sig(spec_fn.to_def_id());
+                tcx.erase_late_bound_regions(sig).inputs()[0] == ty
+            })
+            .collect()
+    }
+
     /// Encodes a value in a field if the base expression is a reference or
     /// a primitive types.
     /// For composed data structures, the base expression is returned.
@@ -538,6 +893,113 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         Ok(function_name)
     }
 
+    /// Encodes the function of a `sum`, `count` or `max` comprehension, which
+    /// is built by `build_function` the first time that it is used.
+    pub fn encode_comprehension_function_use<F>(&self, function_name: &str, build_function: F)
+        -> EncodingResult<()>
+        where F: FnOnce() -> EncodingResult<vir::Function>
+    {
+        trace!("encode_comprehension_function_use(function_name={})", function_name);
+        if !self.comprehension_functions.borrow().contains_key(function_name) {
+            let function = self.patch_snapshots_function(build_function()?)?;
+            self.log_vir_program_before_viper(function.to_string());
+            let identifier = self.insert_function(function);
+            self.comprehension_functions.borrow_mut().insert(function_name.to_string(), identifier);
+        }
+        Ok(())
+    }
+
+    /// Encodes a call of a function stub of a `#[domain]` module as an
+    /// application of the uninterpreted function of its Viper domain.
+    pub fn encode_domain_func_app(&self, def_id: ProcedureDefId, args: Vec<vir::Expr>)
+        -> SpannedEncodingResult<vir::Expr>
+    {
+        trace!("encode_domain_func_app(def_id={:?})", def_id);
+        let domain = self.get_domain_of_function(def_id).unwrap();
+        let domain_name = self.encode_user_domain(&domain)?;
+        let function_name = format!("{}${}", domain, self.env.tcx().item_name(def_id));
+        let domain_function = self.user_domains.borrow()[&domain_name]
+            .functions
+            .iter()
+            .find(|function| function.name == function_name)
+            .cloned()
+            .unwrap();
+        Ok(vir::Expr::domain_func_app(domain_function, args))
+    }
+
+    /// Encodes the Viper domain of a `#[domain]` module, with an uninterpreted
+    /// function for each function stub and a domain axiom for each `axiom!`.
+    /// Returns the name of the Viper domain.
+    fn encode_user_domain(&self, domain: &str) -> SpannedEncodingResult<String> {
+        let domain_name = format!("Domain${}", domain);
+        if self.user_domains.borrow().contains_key(&domain_name) {
+            return Ok(domain_name);
+        }
+        let tcx = self.env.tcx();
+        let mut functions = vec![];
+        for function in self.get_domain_functions(domain) {
+            let def_id = function.to_def_id();
+            let span = tcx.def_span(def_id);
+            let sig = tcx.erase_late_bound_regions(tcx.fn_sig(def_id));
+            let formal_args = sig.inputs()
+                .iter()
+                .enumerate()
+                .map(|(arg_idx, arg_ty)| {
+                    Ok(vir::LocalVar::new(
+                        format!("_{}", arg_idx + 1),
+                        self.encode_snapshot_type(*arg_ty)?,
+                    ))
+                })
+                .collect::<EncodingResult<_>>()
+                .with_span(span)?;
+            functions.push(vir::DomainFunc {
+                name: format!("{}${}", domain, tcx.item_name(def_id)),
+                type_arguments: vec![],
+                formal_args,
+                return_type: self.encode_snapshot_type(sig.output()).with_span(span)?,
+                unique: false,
+                domain_name: domain_name.clone(),
+            });
+        }
+        // The domain is registered before encoding the axioms, which use its
+        // functions.
+        self.user_domains.borrow_mut().insert(domain_name.clone(), vir::Domain {
+            name: domain_name.clone(),
+            functions,
+            axioms: vec![],
+            type_vars: vec![],
+        });
+        for axiom in self.get_domain_axiom_specs(domain) {
+            let def_id = axiom.to_def_id();
+            let expr = self.encode_assertion(
+                &axiom,
+                None,
+                &[],
+                None,
+                false,
+                def_id,
+                self.env.identity_substs(def_id),
+            )?;
+            // The spec function is named `prusti_axiom_item_<axiom>_<spec id>`.
+            let spec_fn_name = tcx.item_name(def_id).to_ident_string();
+            let axiom_name = spec_fn_name
+                .trim_start_matches("prusti_axiom_item_")
+                .rsplit_once('_')
+                .map_or(spec_fn_name.as_str(), |(axiom_name, _)| axiom_name);
+            let domain_axiom = vir::DomainAxiom {
+                name: format!("{}${}", domain, axiom_name),
+                expr,
+                domain_name: domain_name.clone(),
+            };
+            self.user_domains.borrow_mut()
+                .get_mut(&domain_name)
+                .unwrap()
+                .axioms
+                .push(domain_axiom);
+        }
+        Ok(domain_name)
+    }
+
     /// This encodes the Rust function as a Viper method for verification. It
     /// does this also for pure functions.
     pub fn encode_procedure(&self, def_id: ProcedureDefId) -> SpannedEncodingResult<()> {
@@ -632,6 +1094,20 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         value: ty::ConstKind<'tcx>
     ) -> EncodingResult<vir::Expr> {
         trace!("encode_const_expr {:?}", value);
+        if let ty::ConstKind::Unevaluated(ct) = value {
+            if self.env().has_prusti_attribute(ct.def.did, "symbolic") {
+                return self.encode_symbolic_constant(ct.def.did, ty, value);
+            }
+        }
+        self.encode_const_value(ty, value)
+    }
+
+    /// Encode the value of a constant given in the code.
+    fn encode_const_value(
+        &self,
+        ty: ty::Ty<'tcx>,
+        value: ty::ConstKind<'tcx>
+    ) -> EncodingResult<vir::Expr> {
         let scalar_value = self.const_eval_intlike(value)?;
 
         let expr = match ty.kind() {
@@ -679,6 +1155,79 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         Ok(expr)
     }
 
+    /// Encode the value of a `#[symbolic]` constant as the application of a
+    /// Viper function without a body, so that the verification holds for all
+    /// values of the constant in the range of its type. Its constraint is
+    /// assumed by the procedures, like the axiom of a lemma.
+    ///
+    /// The value given in the code is recorded for the procedures that check
+    /// the constraints, see `replace_symbolic_constants`.
+    fn encode_symbolic_constant(
+        &self,
+        const_def_id: DefId,
+        ty: ty::Ty<'tcx>,
+        value: ty::ConstKind<'tcx>,
+    ) -> EncodingResult<vir::Expr> {
+        let return_type = match ty.kind() {
+            ty::TyKind::Bool => vir::Type::Bool,
+            ty::TyKind::Int(_) | ty::TyKind::Uint(_) | ty::TyKind::Char => vir::Type::Int,
+            _ => {
+                return Err(EncodingError::unsupported(format!(
+                    "symbolic constants of type {:?} are not supported",
+                    ty,
+                )));
+            }
+        };
+        let function_name = format!("symbolic${}", self.encode_item_name(const_def_id));
+        let result = vir::Expr::local(vir::LocalVar::new("__result", return_type.clone()));
+        let function = vir::Function {
+            name: function_name.clone(),
+            type_arguments: vec![],
+            formal_args: vec![],
+            return_type: return_type.clone(),
+            pres: vec![],
+            posts: self.encode_type_bounds(&result, ty),
+            body: None,
+        };
+        let identifier: vir::FunctionIdentifier = function.get_identifier().into();
+        if !self.functions.borrow().contains_key(&identifier) {
+            let concrete_value = self.encode_const_value(ty, value)?;
+            self.symbolic_constant_values
+                .borrow_mut()
+                .insert(function_name.clone(), concrete_value);
+            self.insert_function(function);
+        }
+        Ok(vir::Expr::func_app(
+            function_name,
+            vec![],
+            vec![],
+            vec![],
+            return_type,
+            vir::Position::default(),
+        ))
+    }
+
+    /// Replace the `#[symbolic]` constants in `method` by their values given
+    /// in the code. This is used to check that these values satisfy the
+    /// constraints of the constants, which the other procedures assume.
+    pub fn replace_symbolic_constants(&self, mut method: vir::CfgMethod) -> vir::CfgMethod {
+        let values = self.symbolic_constant_values.borrow();
+        let mut replacer = SymbolicConstantReplacer { values: &values };
+        for block in &mut method.basic_blocks {
+            block.stmts = block
+                .stmts
+                .drain(..)
+                .map(|stmt| vir::StmtFolder::fold(&mut replacer, stmt))
+                .collect();
+            if let vir::Successor::GotoSwitch(guarded_targets, _) = &mut block.successor {
+                for (guard, _) in guarded_targets {
+                    *guard = vir::ExprFolder::fold(&mut replacer, guard.clone());
+                }
+            }
+        }
+        method
+    }
+
     pub fn encode_int_cast(&self, value: u128, ty: ty::Ty<'tcx>) -> vir::Expr {
         trace!("encode_int_cast {:?} as {:?}", value, ty);
 
@@ -790,44 +1339,75 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             info!("Encoding: {} ({})", proc_name, proc_def_path);
             assert!(substs.is_empty());
 
-            if config::unsafe_core_proof() {
-                if let Err(error) = self.encode_lifetimes_core_proof(proc_def_id) {
-                    self.register_encoding_error(error);
-                    debug!("Error encoding function: {:?}", proc_def_id);
+            if config::catch_encoder_panics() {
+                // Report a panic as an internal error of this procedure and
+                // continue with the others.
+                if let Err(message) = catch_panic(|| self.encode_queued_procedure(proc_def_id, proc_name)) {
+                    let error = SpannedEncodingError::internal(
+                        format!("the encoding of this function panicked: {}", message),
+                        self.env.get_def_span(proc_def_id),
+                    );
+                    self.register_procedure_encoding_error(proc_def_id, error);
+                    debug!("Panic while encoding function: {:?}", proc_def_id);
                 }
-                continue;
+            } else {
+                self.encode_queued_procedure(proc_def_id, proc_name);
             }
+        }
+    }
 
-            if self.is_pure(proc_def_id) {
-                // Check that the pure Rust function satisfies the basic
-                // requirements by trying to encode it as a Viper function,
-                // which will automatically run the validity checks.
+    fn encode_queued_procedure(&mut self, proc_def_id: ProcedureDefId, proc_name: String) {
+        let encoding_start = Instant::now();
 
-                // TODO: Make sure that this encoded function does not end up in
-                // the Viper file because that would be unsound.
-                let identity_substs = self.env().identity_substs(proc_def_id);
-                if let Err(error) = self.encode_pure_function_def(proc_def_id, identity_substs) {
-                    self.register_encoding_error(error);
-                    debug!("Error encoding function: {:?}", proc_def_id);
-                    // Skip encoding the function as a method.
-                    continue;
-                }
+        if config::unsafe_core_proof() {
+            if let Err(error) = self.encode_lifetimes_core_proof(proc_def_id) {
+                self.register_procedure_encoding_error(proc_def_id, error);
+                debug!("Error encoding function: {:?}", proc_def_id);
             }
-            if self.is_trusted(proc_def_id) {
-                debug!(
-                    "Trusted procedure will not be encoded or verified: {:?}",
-                    proc_def_id
-                );
-            } else if let Err(error) = self.encode_procedure(proc_def_id) {
-                self.register_encoding_error(error);
+            return;
+        }
+
+        if self.is_pure(proc_def_id) {
+            // Check that the pure Rust function satisfies the basic
+            // requirements by trying to encode it as a Viper function,
+            // which will automatically run the validity checks.
+
+            // TODO: Make sure that this encoded function does not end up in
+            // the Viper file because that would be unsound.
+            let identity_substs = self.env().identity_substs(proc_def_id);
+            if let Err(error) = self.encode_pure_function_def(proc_def_id, identity_substs) {
+                self.register_procedure_encoding_error(proc_def_id, error);
                 debug!("Error encoding function: {:?}", proc_def_id);
-            } else {
-                match self.finalize_viper_program(proc_name, proc_def_id) {
-                    Ok(program) => self.programs.push(program),
-                    Err(error) => {
-                        self.register_encoding_error(error);
-                        debug!("Error finalizing program: {:?}", proc_def_id);
+                // Skip encoding the function as a method.
+                return;
+            }
+        }
+        if self.is_trusted(proc_def_id) {
+            debug!(
+                "Trusted procedure will not be encoded or verified: {:?}",
+                proc_def_id
+            );
+        } else if let Err(error) = self.encode_procedure(proc_def_id) {
+            self.register_procedure_encoding_error(proc_def_id, error);
+            debug!("Error encoding function: {:?}", proc_def_id);
+        } else {
+            match self.finalize_viper_program(proc_name.clone(), proc_def_id) {
+                Ok(program) => {
+                    {
+                        let mut performance_report = self.performance_report();
+                        let cost = performance_report.cost_mut(&proc_name);
+                        cost.function = self.env.get_absolute_item_name(proc_def_id);
+                        cost.encoding_time = encoding_start.elapsed();
+                        performance_report.record_branches(&program);
                     }
+                    if self.env.has_prusti_attribute(proc_def_id, "nonlinear_arithmetic") {
+                        self.nonlinear_arithmetic_programs.borrow_mut().insert(proc_name);
+                    }
+                    self.programs.push(program);
+                }
+                Err(error) => {
+                    self.register_procedure_encoding_error(proc_def_id, error);
+                    debug!("Error finalizing program: {:?}", proc_def_id);
                 }
             }
         }
@@ -923,3 +1503,86 @@ pub(crate) fn encode_identifier(ident: String) -> String {
         .replace('&', "$amp$")
         .replace('*', "$star$")
 }
+
+fn extend_specification_item<T>(item: &mut typed::SpecificationItem<Vec<T>>, extension: Vec<T>) {
+    match item {
+        typed::SpecificationItem::Empty => {
+            *item = typed::SpecificationItem::Inherent(extension);
+        }
+        typed::SpecificationItem::Inherent(items)
+        | typed::SpecificationItem::Inherited(items)
+        | typed::SpecificationItem::Refined(_, items) => items.extend(extension),
+    }
+}
+
+struct SymbolicConstantReplacer<'a> {
+    values: &'a FxHashMap<String, vir::Expr>,
+}
+
+impl<'a> vir::ExprFolder for SymbolicConstantReplacer<'a> {
+    fn fold_func_app(&mut self, func_app: vir::FuncApp) -> vir::Expr {
+        if let Some(value) = self.values.get(&func_app.function_name) {
+            return value.clone().set_default_pos(func_app.position);
+        }
+        let vir::FuncApp {
+            function_name,
+            type_arguments,
+            arguments,
+            formal_arguments,
+            return_type,
+            position,
+        } = func_app;
+        vir::Expr::FuncApp(vir::FuncApp {
+            function_name,
+            type_arguments,
+            arguments: arguments.into_iter().map(|arg| vir::ExprFolder::fold(self, arg)).collect(),
+            formal_arguments,
+            return_type,
+            position,
+        })
+    }
+}
+
+impl<'a> vir::StmtFolder for SymbolicConstantReplacer<'a> {
+    fn fold_expr(&mut self, expr: vir::Expr) -> vir::Expr {
+        vir::ExprFolder::fold(self, expr)
+    }
+}
+
+/// Run `f`, returning the message of its panic, if any. The panic is still
+/// reported by the panic hook of the process, with its location. The
+/// `FatalError` of the compiler, which aborts the compilation after an error
+/// was emitted, is not caught.
+fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        if payload.is::<FatalErrorMarker>() {
+            panic::resume_unwind(payload);
+        }
+        payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "<unknown panic payload>".to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_span::fatal_error::FatalError;
+
+    #[test]
+    fn catch_panic_returns_the_message() {
+        assert_eq!(catch_panic(|| 42), Ok(42));
+        assert_eq!(
+            catch_panic::<()>(|| panic!("failed to encode {}", "x")),
+            Err("failed to encode x".to_string())
+        );
+    }
+
+    #[test]
+    fn catch_panic_does_not_catch_fatal_errors() {
+        let result = panic::catch_unwind(|| catch_panic(|| FatalError.raise()));
+        assert!(result.unwrap_err().is::<FatalErrorMarker>());
+    }
+}
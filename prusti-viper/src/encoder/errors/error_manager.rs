@@ -7,9 +7,11 @@
 use vir_crate::polymorphic::Position;
 use rustc_hash::FxHashMap;
 use rustc_span::source_map::SourceMap;
-use rustc_span::MultiSpan;
+use rustc_span::{MultiSpan, Span};
 use viper::VerificationError;
 use prusti_interface::PrustiError;
+use prusti_interface::error_codes::ErrorCode;
+use prusti_common::config;
 use log::{debug, trace};
 use super::PositionManager;
 use prusti_interface::data::ProcedureDefId;
@@ -30,6 +32,90 @@ pub enum PanicCause {
     Unreachable,
     /// Caused by an unimplemented!()
     Unimplemented,
+    /// Caused by `unwrap()` or `expect(..)` on a `None` or an `Err`
+    Unwrap,
+    /// Caused by `unwrap_err()` or `expect_err(..)` on an `Ok`
+    UnwrapErr,
+    /// Caused by a standard library function that panics on some arguments,
+    /// such as `Vec::remove` with an index that is out of bounds
+    StdCall,
+}
+
+/// How confident the verifier is that a verification error is a real violation
+/// of the specification, from the most to the least confident.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum FailureKind {
+    /// The verifier found a counterexample.
+    Violated,
+    /// The verifier found neither a proof nor a counterexample, e.g. because
+    /// of quantifiers or non-linear arithmetic.
+    Unknown,
+    /// The verifier ran out of time or memory.
+    ResourceLimit,
+}
+
+impl FailureKind {
+    /// Classify a verification error using the counterexample and the reason
+    /// for an unknown result reported by the verifier.
+    pub fn of(ver_error: &VerificationError) -> Self {
+        let reason = ver_error.reason_unknown.as_deref().unwrap_or_default().to_lowercase();
+        if ["timeout", "canceled", "resource", "memout", "memory"]
+            .iter()
+            .any(|limit| reason.contains(limit))
+        {
+            FailureKind::ResourceLimit
+        } else if ver_error.counterexample.is_some() {
+            FailureKind::Violated
+        } else {
+            FailureKind::Unknown
+        }
+    }
+
+    /// Explain the kind of the failure in the reported error.
+    pub fn annotate_error(self, prusti_error: PrustiError, ver_error: &VerificationError) -> PrustiError {
+        let reason = ver_error.reason_unknown.as_ref()
+            .map(|reason| format!(" (reason: {})", reason))
+            .unwrap_or_default();
+        match self {
+            FailureKind::Violated => prusti_error.add_note(
+                "definitely violated: the verifier found a counterexample",
+                None,
+            ),
+            FailureKind::Unknown => {
+                let prusti_error = prusti_error.add_note(
+                    format!(
+                        "unknown: the verifier could neither prove this nor find a counterexample{}",
+                        reason,
+                    ),
+                    None,
+                );
+                if prusti_error.has_help() {
+                    prusti_error
+                } else {
+                    prusti_error.set_help(
+                        "this might be caused by an incompleteness of the verifier (e.g. \
+                        quantifiers without suitable triggers, or non-linear arithmetic) \
+                        rather than by a wrong specification"
+                    )
+                }
+            }
+            FailureKind::ResourceLimit => {
+                let prusti_error = prusti_error.add_note(
+                    format!("resource limit: the verifier ran out of resources{}", reason),
+                    None,
+                );
+                if prusti_error.has_help() {
+                    prusti_error
+                } else {
+                    prusti_error.set_help(format!(
+                        "the property might hold; try increasing the configuration parameter \
+                        ASSERT_TIMEOUT (currently {} ms)",
+                        config::assert_timeout(),
+                    ))
+                }
+            }
+        }
+    }
 }
 
 /// The kind of the method whose proof failed.
@@ -48,6 +134,9 @@ pub enum ErrorCtxt {
     Panic(PanicCause),
     /// A Viper `exhale expr` that encodes the call of a Rust procedure with precondition `expr`
     ExhaleMethodPrecondition,
+    /// A Viper `assert expr` that encodes the call of a Rust procedure whose
+    /// `#[transition(...)]` requires the state `expr`
+    AssertTypestateTransition,
     /// A Viper `assert expr` that encodes the call of a Rust procedure with precondition `expr`
     AssertMethodPostcondition,
     /// A Viper `assert expr` that encodes the call of a Rust procedure with precondition `expr`
@@ -60,6 +149,18 @@ pub enum ErrorCtxt {
     /// A Viper `assert expr` that asserts the functional specification of a loop invariant `expr`
     AssertLoopInvariantOnEntry,
     AssertLoopInvariantAfterIteration,
+    /// A Viper `assert expr` that asserts a loop invariant `expr` where it is
+    /// written in the loop body, when it is not part of the invariant at the
+    /// loop head
+    AssertLoopInvariantInBody,
+    /// A Viper `assert expr` that asserts a loop invariant inferred by `INFER_LOOP_INVARIANTS`
+    AssertInferredLoopInvariant,
+    /// A Viper `assert expr` that asserts a line of a `prusti_calc!` proof
+    AssertProofStep,
+    /// A Viper `assert expr` that asserts a `yield_invariant!` of a generator at a `yield`
+    AssertYieldInvariant,
+    /// A Viper `assert expr` that asserts a `resume_requires!` of a generator when it is resumed
+    AssertResumePrecondition,
     /// A Viper `assert false` that encodes the failure (panic) of an `assert` Rust terminator
     /// Arguments: the message of the Rust assertion
     AssertTerminator(String),
@@ -69,6 +170,9 @@ pub enum ErrorCtxt {
     AbortTerminator,
     /// A Viper `assert false` that encodes an `unreachable` Rust terminator
     UnreachableTerminator,
+    /// A Viper `assert false` at the beginning of a branch of a Rust `if` or
+    /// `match`, which fails iff the branch is reachable
+    ReachabilityCheck,
     /// An error that should never happen
     Unexpected,
     /// An unexpected verification error happenning inside built-in method.
@@ -112,6 +216,9 @@ pub enum ErrorCtxt {
     AssertMethodPostconditionStrengthening,
     /// A cast like `usize as u32`.
     TypeCast,
+    /// An arithmetic operation on ghost integers in executable code, whose
+    /// result needs to fit into their runtime representation.
+    GhostIntOverflow,
     /// A Viper `assert false` that encodes an unsupported feature
     Unsupported(String),
     /// Failed to obtain capability by unfolding.
@@ -128,6 +235,9 @@ pub struct ErrorManager<'tcx> {
     position_manager: PositionManager<'tcx>,
     error_contexts: FxHashMap<u64, ErrorCtxt>,
     inner_positions: FxHashMap<u64, Position>,
+    /// For each procedure, the loop heads together with the names of the
+    /// local variables whose values are havocked there.
+    loop_havocs: FxHashMap<ProcedureDefId, Vec<(Span, Vec<String>)>>,
 }
 
 impl<'tcx> ErrorManager<'tcx> {
@@ -136,6 +246,7 @@ impl<'tcx> ErrorManager<'tcx> {
             position_manager: PositionManager::new(codemap),
             error_contexts: FxHashMap::default(),
             inner_positions: FxHashMap::default(),
+            loop_havocs: FxHashMap::default(),
         }
     }
 
@@ -190,12 +301,57 @@ impl<'tcx> ErrorManager<'tcx> {
         pos
     }
 
+    /// Register the local variables that are havocked at the head of a loop,
+    /// so that failures after the loop can suggest a loop invariant.
+    pub fn register_loop_havoc(&mut self, def_id: ProcedureDefId, loop_head_span: Span, variables: Vec<String>) {
+        trace!("Register loop havoc of {:?} at {:?} in {:?}", variables, loop_head_span, def_id);
+        self.loop_havocs.entry(def_id).or_default().push((loop_head_span, variables));
+    }
+
     pub fn get_def_id(&self, ver_error: &VerificationError) -> Option<ProcedureDefId> {
         ver_error.pos_id.as_ref()
             .and_then(|id| id.parse().ok())
             .and_then(|id| self.position_manager.def_id.get(&id).copied())
     }
 
+    /// Whether the verification error is a potential panic, e.g. a reachable
+    /// `panic!(..)`, an overflow or an out-of-bounds access.
+    pub fn is_panic_error(&self, ver_error: &VerificationError) -> bool {
+        let error_ctxt = ver_error.pos_id.as_ref()
+            .and_then(|id| id.parse().ok())
+            .and_then(|id: u64| self.error_contexts.get(&id));
+        matches!(
+            error_ctxt,
+            Some(ErrorCtxt::Panic(_))
+            | Some(ErrorCtxt::AssertTerminator(_))
+            | Some(ErrorCtxt::BoundsCheckAssert)
+            | Some(ErrorCtxt::AbortTerminator)
+        )
+    }
+
+    /// Whether the verification error is the failure of a reachability check,
+    /// i.e. the branch of the check is reachable.
+    pub fn is_reachability_check(&self, ver_error: &VerificationError) -> bool {
+        let error_ctxt = ver_error.pos_id.as_ref()
+            .and_then(|id| id.parse().ok())
+            .and_then(|id: u64| self.error_contexts.get(&id));
+        matches!(error_ctxt, Some(ErrorCtxt::ReachabilityCheck))
+    }
+
+    /// The position ids of the reachability checks, together with the
+    /// procedure and the branch that they check.
+    pub fn reachability_checks(&self) -> Vec<(u64, ProcedureDefId, MultiSpan)> {
+        self.error_contexts
+            .iter()
+            .filter(|(_, error_ctxt)| matches!(error_ctxt, ErrorCtxt::ReachabilityCheck))
+            .filter_map(|(&pos_id, _)| {
+                let def_id = *self.position_manager.def_id.get(&pos_id)?;
+                let span = self.position_manager.source_span.get(&pos_id)?.clone();
+                Some((pos_id, def_id, span))
+            })
+            .collect()
+    }
+
     pub fn translate_verification_error(&self, ver_error: &VerificationError) -> PrustiError {
         debug!("Verification error: {:?}", ver_error);
         let opt_pos_id: Option<u64> = match ver_error.pos_id {
@@ -247,12 +403,21 @@ impl<'tcx> ErrorManager<'tcx> {
         if let Some(error_ctxt) = opt_error_ctxts {
             debug_assert!(opt_error_span.is_some());
             let error_span = opt_error_span.cloned().unwrap_or_else(MultiSpan::new);
-            self.translate_verification_error_with_context(
+            let prusti_error = self.translate_verification_error_with_context(
                 ver_error,
-                error_span,
+                error_span.clone(),
                 opt_cause_span,
                 error_ctxt
-            )
+            ).set_code(verification_error_code(error_ctxt));
+            match opt_pos_id.and_then(|pos_id| self.position_manager.def_id.get(&pos_id)) {
+                Some(def_id) => self.suggest_loop_invariant(
+                    prusti_error,
+                    *def_id,
+                    &error_span,
+                    opt_cause_span,
+                ),
+                None => prusti_error,
+            }
         } else {
             debug!("Unregistered verification error: {:?}", ver_error);
             let error_span = if let Some(error_span) = opt_error_span {
@@ -292,6 +457,99 @@ impl<'tcx> ErrorManager<'tcx> {
         }
     }
 
+    /// If the failure happens after the head of a loop that havocs a variable
+    /// mentioned by the failing code or assertion, the information needed to
+    /// prove it might have been lost at the loop head. Point the user to the
+    /// loop and suggest a `body_invariant!(..)` that preserves it.
+    ///
+    /// The suggestion is only based on the source code: whether the assertion
+    /// holds at the loop entry is not checked, so the help is phrased as a
+    /// condition for the user to check.
+    fn suggest_loop_invariant(
+        &self,
+        prusti_error: PrustiError,
+        def_id: ProcedureDefId,
+        error_span: &MultiSpan,
+        opt_cause_span: Option<&MultiSpan>,
+    ) -> PrustiError {
+        if !prusti_error.is_error() || prusti_error.is_disabled() || prusti_error.has_help() {
+            return prusti_error;
+        }
+        let loops = match self.loop_havocs.get(&def_id) {
+            Some(loops) => loops,
+            None => return prusti_error,
+        };
+        let error_primary_span = match error_span.primary_span() {
+            Some(span) => span.source_callsite(),
+            None => return prusti_error,
+        };
+        let codemap = self.position_manager.codemap;
+        let opt_cause_snippet = opt_cause_span
+            .and_then(|span| span.primary_span())
+            .and_then(|span| codemap.span_to_snippet(span).ok());
+        let snippets: Vec<String> = codemap.span_to_snippet(error_primary_span).ok()
+            .into_iter()
+            .chain(opt_cause_snippet.clone())
+            .collect();
+
+        // Pick the innermost loop head that precedes the error and havocs one
+        // of the mentioned variables.
+        let opt_candidate = loops.iter()
+            .filter(|(loop_head_span, _)| {
+                let loop_head_span = loop_head_span.source_callsite();
+                codemap.lookup_source_file(loop_head_span.lo()).start_pos
+                    == codemap.lookup_source_file(error_primary_span.lo()).start_pos
+                    && loop_head_span.lo() <= error_primary_span.lo()
+            })
+            .filter_map(|(loop_head_span, variables)| {
+                let mentioned: Vec<&String> = variables.iter()
+                    .filter(|var| snippets.iter().any(|snippet| mentions_variable(snippet, var)))
+                    .collect();
+                if mentioned.is_empty() {
+                    None
+                } else {
+                    Some((loop_head_span, mentioned))
+                }
+            })
+            .max_by_key(|(loop_head_span, _)| loop_head_span.lo());
+        let (loop_head_span, mentioned) = match opt_candidate {
+            Some(candidate) => candidate,
+            None => return prusti_error,
+        };
+
+        let variables = mentioned.iter()
+            .map(|var| format!("`{}`", var))
+            .collect::<Vec<_>>()
+            .join(", ");
+        // Suggest the failing assertion itself as the invariant, if we can
+        // recover it from the source code.
+        let opt_assertion = opt_cause_snippet.or_else(|| {
+            snippets.first().and_then(|snippet| {
+                ["assert!(", "debug_assert!(", "prusti_assert!("].iter()
+                    .find_map(|prefix| snippet.strip_prefix(prefix))
+                    .and_then(|rest| rest.strip_suffix(')'))
+                    .map(|assertion| assertion.to_string())
+            })
+        });
+        let suggestion = match opt_assertion {
+            Some(ref assertion) if !assertion.contains('\n') && mentioned.iter()
+                .any(|var| mentions_variable(assertion, var)) => format!("body_invariant!({})", assertion),
+            _ => "body_invariant!(..)".to_string(),
+        };
+        prusti_error
+            .add_note(
+                format!("the values of {} are havocked at this loop head", variables),
+                Some(*loop_head_span),
+            )
+            .set_help(format!(
+                "the information about {} might have been lost at the loop head; if the \
+                assertion holds before the loop and is preserved by every iteration, \
+                consider adding `{}` to the loop body",
+                variables,
+                suggestion,
+            ))
+    }
+
     fn translate_verification_error_with_context(
         &self,
         ver_error: &VerificationError,
@@ -326,6 +584,27 @@ impl<'tcx> ErrorManager<'tcx> {
                     .set_failing_assertion(opt_cause_span)
             }
 
+            ("assert.failed:assertion.false", ErrorCtxt::Panic(PanicCause::Unwrap)) => {
+                PrustiError::verification(
+                    "the unwrapped value might be `None` or `Err`",
+                    error_span
+                ).set_failing_assertion(opt_cause_span)
+            }
+
+            ("assert.failed:assertion.false", ErrorCtxt::Panic(PanicCause::UnwrapErr)) => {
+                PrustiError::verification("the unwrapped value might be `Ok`", error_span)
+                    .set_failing_assertion(opt_cause_span)
+            }
+
+            ("assert.failed:assertion.false", ErrorCtxt::Panic(PanicCause::StdCall)) => {
+                PrustiError::verification("the called function might panic", error_span)
+                    .set_failing_assertion(opt_cause_span)
+                    .set_help(
+                        "give the function a specification with `#[extern_spec]` whose \
+                        precondition excludes the panics"
+                    )
+            }
+
             ("assert.failed:assertion.false", ErrorCtxt::AssertTerminator(ref message)) => {
                 PrustiError::verification(format!("assertion might fail with \"{}\"", message), error_span)
                     .set_failing_assertion(opt_cause_span)
@@ -349,6 +628,14 @@ impl<'tcx> ErrorManager<'tcx> {
                     .set_failing_assertion(opt_cause_span)
             }
 
+            ("assert.failed:assertion.false", ErrorCtxt::AssertTypestateTransition) => {
+                PrustiError::verification(
+                    "the method might be called in a state that its transition does not allow.",
+                    error_span
+                ).set_failing_assertion(opt_cause_span)
+                    .set_help("The allowed states are given by the `#[transition(...)]` attribute of the method.")
+            }
+
             ("fold.failed:assertion.false", ErrorCtxt::ExhaleMethodPrecondition) => {
                 PrustiError::verification(
                     "implicit type invariant expected by the function call might not hold.",
@@ -392,6 +679,35 @@ impl<'tcx> ErrorManager<'tcx> {
                 ).push_primary_span(opt_cause_span)
             }
 
+            ("assert.failed:assertion.false", ErrorCtxt::AssertLoopInvariantInBody) => {
+                PrustiError::verification(
+                    "loop invariant might not hold at this point of the loop body.",
+                    error_span
+                ).push_primary_span(opt_cause_span)
+            }
+
+            ("assert.failed:assertion.false", ErrorCtxt::AssertInferredLoopInvariant) => {
+                PrustiError::internal("the inferred loop invariant might not hold.", error_span)
+                    .set_help("disable the INFER_LOOP_INVARIANTS flag to verify this loop")
+            }
+
+            ("assert.failed:assertion.false", ErrorCtxt::AssertProofStep) => {
+                PrustiError::verification("the proof step might not hold.", error_span)
+                    .push_primary_span(opt_cause_span)
+            }
+
+            ("assert.failed:assertion.false", ErrorCtxt::AssertYieldInvariant) => {
+                PrustiError::verification("the yield invariant might not hold.", error_span)
+                    .push_primary_span(opt_cause_span)
+            }
+
+            ("assert.failed:assertion.false", ErrorCtxt::AssertResumePrecondition) => {
+                PrustiError::verification(
+                    "the resume precondition of the generator might not hold.",
+                    error_span
+                ).set_failing_assertion(opt_cause_span)
+            }
+
             ("application.precondition:assertion.false", ErrorCtxt::PureFunctionCall) => {
                 PrustiError::verification(
                     "precondition of pure function call might not hold.",
@@ -480,6 +796,14 @@ impl<'tcx> ErrorManager<'tcx> {
                 ).set_failing_assertion(opt_cause_span)
             },
 
+            ("application.precondition:assertion.false", ErrorCtxt::GhostIntOverflow) => {
+                PrustiError::verification(
+                    "the result of the ghost integer operation might not fit into an `i128`, \
+                    which represents ghost integers at runtime",
+                    error_span
+                ).set_failing_assertion(opt_cause_span)
+            }
+
             ("application.precondition:assertion.false", ErrorCtxt::TypeCast) => {
                 PrustiError::verification(
                     "value might not fit into the target type.",
@@ -492,6 +816,11 @@ impl<'tcx> ErrorManager<'tcx> {
                     .set_failing_assertion(opt_cause_span)
             }
 
+            ("exhale.failed:assertion.false", ErrorCtxt::ApplyMagicWandOnExpiry) => {
+                PrustiError::verification("obligation might not hold on borrow expiry", error_span)
+                    .set_failing_assertion(opt_cause_span)
+            }
+
             ("assert.failed:assertion.false", ErrorCtxt::AssertMethodPostcondition) => {
                 PrustiError::verification("postcondition might not hold.".to_string(), error_span)
                     .push_primary_span(opt_cause_span)
@@ -587,3 +916,71 @@ impl<'tcx> ErrorManager<'tcx> {
         }
     }
 }
+
+/// The code of a verification error, determined by the kind of check that
+/// failed. See `prusti_interface::error_codes`.
+fn verification_error_code(error_ctxt: &ErrorCtxt) -> ErrorCode {
+    match error_ctxt {
+        ErrorCtxt::Panic(PanicCause::Generic)
+        | ErrorCtxt::Panic(PanicCause::Unwrap)
+        | ErrorCtxt::Panic(PanicCause::UnwrapErr)
+        | ErrorCtxt::Panic(PanicCause::StdCall) => ErrorCode::P0001,
+        ErrorCtxt::Panic(PanicCause::Panic)
+        | ErrorCtxt::Panic(PanicCause::Unreachable)
+        | ErrorCtxt::Panic(PanicCause::Unimplemented) => ErrorCode::P0002,
+        ErrorCtxt::Panic(PanicCause::Assert)
+        | ErrorCtxt::Panic(PanicCause::DebugAssert) => ErrorCode::P0003,
+        ErrorCtxt::AssertTerminator(_) => ErrorCode::P0004,
+        ErrorCtxt::BoundsCheckAssert => ErrorCode::P0005,
+        ErrorCtxt::ExhaleMethodPrecondition => ErrorCode::P0006,
+        ErrorCtxt::AssertMethodPostcondition => ErrorCode::P0007,
+        ErrorCtxt::AssertLoopInvariantOnEntry => ErrorCode::P0008,
+        ErrorCtxt::AssertLoopInvariantAfterIteration => ErrorCode::P0009,
+        ErrorCtxt::PureFunctionCall => ErrorCode::P0010,
+        ErrorCtxt::PackageMagicWandForPostcondition => ErrorCode::P0011,
+        ErrorCtxt::AssertMethodPostconditionTypeInvariants => ErrorCode::P0012,
+        ErrorCtxt::TypeCast => ErrorCode::P0013,
+        ErrorCtxt::AssertMethodPreconditionWeakening => ErrorCode::P0014,
+        ErrorCtxt::AssertProofStep => ErrorCode::P0015,
+        ErrorCtxt::AssertTypestateTransition => ErrorCode::P0016,
+        ErrorCtxt::PanicInPureFunction(_) => ErrorCode::P0017,
+        ErrorCtxt::UnfoldUnionVariant => ErrorCode::P0018,
+        ErrorCtxt::AssertYieldInvariant => ErrorCode::P0019,
+        ErrorCtxt::AssertResumePrecondition => ErrorCode::P0020,
+        ErrorCtxt::AbortTerminator => ErrorCode::P0021,
+        ErrorCtxt::GhostIntOverflow => ErrorCode::P0022,
+        ErrorCtxt::ExhaleMethodPostcondition => ErrorCode::P0023,
+        ErrorCtxt::ExhaleLoopInvariantOnEntry => ErrorCode::P0024,
+        ErrorCtxt::ExhaleLoopInvariantAfterIteration => ErrorCode::P0025,
+        ErrorCtxt::AssertLoopInvariantInBody => ErrorCode::P0026,
+        ErrorCtxt::ApplyMagicWandOnExpiry => ErrorCode::P0027,
+        ErrorCtxt::AssertMethodPostconditionStrengthening => ErrorCode::P0028,
+        ErrorCtxt::DivergingCallInPureFunction => ErrorCode::P0029,
+        ErrorCtxt::PureFunctionAssertTerminator(_) => ErrorCode::P0030,
+        ErrorCtxt::PureFunctionDefinition => ErrorCode::P0031,
+        ErrorCtxt::AssertInferredLoopInvariant => ErrorCode::P0032,
+        ErrorCtxt::UnreachableTerminator => ErrorCode::P0033,
+        ErrorCtxt::ReachabilityCheck => ErrorCode::P0034,
+        ErrorCtxt::Unsupported(_) => ErrorCode::P0035,
+        ErrorCtxt::Unexpected => ErrorCode::P0036,
+        ErrorCtxt::UnexpectedBuiltinMethod(_) => ErrorCode::P0037,
+        ErrorCtxt::UnexpectedStorageLive => ErrorCode::P0038,
+        ErrorCtxt::UnexpectedStorageDead => ErrorCode::P0039,
+        ErrorCtxt::MovePlace => ErrorCode::P0040,
+        ErrorCtxt::CopyPlace => ErrorCode::P0041,
+        ErrorCtxt::WritePlace => ErrorCode::P0042,
+        ErrorCtxt::Assign => ErrorCode::P0043,
+        ErrorCtxt::PureFunctionPostconditionValueRangeOfResult => ErrorCode::P0044,
+        ErrorCtxt::Unfold => ErrorCode::P0045,
+        ErrorCtxt::ProcedureCall => ErrorCode::P0046,
+    }
+}
+
+/// Check whether `snippet` contains `variable` as a whole identifier.
+fn mentions_variable(snippet: &str, variable: &str) -> bool {
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_';
+    snippet.match_indices(variable).any(|(start, _)| {
+        let end = start + variable.len();
+        !snippet[..start].ends_with(is_ident_char) && !snippet[end..].starts_with(is_ident_char)
+    })
+}
@@ -16,7 +16,7 @@ use prusti_interface::data::ProcedureDefId;
 /// span, that should be done by adding the span to `ErrorCtxt`, not by registering a new span.
 #[derive(Clone)]
 pub struct PositionManager<'tcx> {
-    codemap: &'tcx SourceMap,
+    pub(crate) codemap: &'tcx SourceMap,
     next_pos_id: u64,
     /// The def_id of the procedure that generated the given VIR position.
     pub(crate) def_id: FxHashMap<u64, ProcedureDefId>,
@@ -86,6 +86,10 @@ impl SpannedEncodingError {
         &self.error
     }
 
+    pub fn span(&self) -> &MultiSpan {
+        &self.span
+    }
+
     pub fn with_span<S: Into<MultiSpan>>(self, span: S) -> SpannedEncodingError {
         // TODO: Stack error spans
         SpannedEncodingError {
@@ -0,0 +1,151 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use rustc_span::{source_map::SourceMap, MultiSpan};
+use std::fmt::Write;
+
+/// A coarse classification of the unsupported Rust features, used to
+/// summarize how much of a crate Prusti can verify.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum UnsupportedFeatureCategory {
+    Closures,
+    Generators,
+    RawPointers,
+    TraitObjects,
+    FunctionPointers,
+    Strings,
+    Floats,
+    Unions,
+    ArraysAndSlices,
+    Casts,
+    BitwiseOperations,
+    Borrows,
+    Iterators,
+    Types,
+    Other,
+}
+
+impl UnsupportedFeatureCategory {
+    /// Classify an unsupported feature by the message of its error.
+    pub fn from_message(message: &str) -> Self {
+        let message = message.to_lowercase();
+        let mentions = |keywords: &[&str]| keywords.iter().any(|keyword| message.contains(keyword));
+        if mentions(&["closure"]) {
+            UnsupportedFeatureCategory::Closures
+        } else if mentions(&["generator", "async"]) {
+            UnsupportedFeatureCategory::Generators
+        } else if mentions(&["raw pointer", "raw address"]) {
+            UnsupportedFeatureCategory::RawPointers
+        } else if mentions(&["trait object", "dyn "]) {
+            UnsupportedFeatureCategory::TraitObjects
+        } else if mentions(&["function pointer", "foreign function", "variadic"]) {
+            UnsupportedFeatureCategory::FunctionPointers
+        } else if mentions(&["str slice", "string", "str "]) {
+            UnsupportedFeatureCategory::Strings
+        } else if mentions(&["float"]) {
+            UnsupportedFeatureCategory::Floats
+        } else if mentions(&["union"]) {
+            UnsupportedFeatureCategory::Unions
+        } else if mentions(&["slic", "array", "index", "length"]) {
+            UnsupportedFeatureCategory::ArraysAndSlices
+        } else if mentions(&["cast", "unsizing"]) {
+            UnsupportedFeatureCategory::Casts
+        } else if mentions(&["bitwise", "bitvector", "shift"]) {
+            UnsupportedFeatureCategory::BitwiseOperations
+        } else if mentions(&["borrow", "loan", "reference"]) {
+            UnsupportedFeatureCategory::Borrows
+        } else if mentions(&["iterator"]) {
+            UnsupportedFeatureCategory::Iterators
+        } else if mentions(&["type"]) {
+            UnsupportedFeatureCategory::Types
+        } else {
+            UnsupportedFeatureCategory::Other
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            UnsupportedFeatureCategory::Closures => "closures",
+            UnsupportedFeatureCategory::Generators => "generators and async",
+            UnsupportedFeatureCategory::RawPointers => "raw pointers",
+            UnsupportedFeatureCategory::TraitObjects => "trait objects",
+            UnsupportedFeatureCategory::FunctionPointers => "function pointers and FFI",
+            UnsupportedFeatureCategory::Strings => "strings",
+            UnsupportedFeatureCategory::Floats => "floating point numbers",
+            UnsupportedFeatureCategory::Unions => "unions",
+            UnsupportedFeatureCategory::ArraysAndSlices => "arrays and slices",
+            UnsupportedFeatureCategory::Casts => "casts",
+            UnsupportedFeatureCategory::BitwiseOperations => "bitwise operations",
+            UnsupportedFeatureCategory::Borrows => "borrows",
+            UnsupportedFeatureCategory::Iterators => "iterators",
+            UnsupportedFeatureCategory::Types => "other types",
+            UnsupportedFeatureCategory::Other => "other",
+        }
+    }
+}
+
+/// The unsupported features encountered while encoding a crate.
+///
+/// The encoding of a procedure stops at its first unsupported feature, so
+/// there is at most one occurrence per procedure.
+#[derive(Clone, Debug, Default)]
+pub struct UnsupportedFeatures {
+    occurrences: Vec<(UnsupportedFeatureCategory, MultiSpan, String)>,
+}
+
+impl UnsupportedFeatures {
+    pub fn record(&mut self, message: &str, span: MultiSpan) {
+        let category = UnsupportedFeatureCategory::from_message(message);
+        self.occurrences.push((category, span, message.to_string()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.occurrences.is_empty()
+    }
+
+    /// A table with the number of occurrences of each category, followed by
+    /// the location of each occurrence.
+    pub fn summary(&self, codemap: &SourceMap) -> String {
+        let mut occurrences: Vec<_> = self.occurrences.iter().collect();
+        occurrences.sort_by_key(|(category, span, _)| (*category, span.primary_span()));
+
+        let mut counts: Vec<(UnsupportedFeatureCategory, usize)> = vec![];
+        for (category, _, _) in &occurrences {
+            match counts.last_mut() {
+                Some((last, count)) if last == category => *count += 1,
+                _ => counts.push((*category, 1)),
+            }
+        }
+
+        let mut summary = String::new();
+        writeln!(
+            summary,
+            "Summary of unsupported features ({} occurrences):",
+            occurrences.len()
+        )
+        .unwrap();
+        writeln!(summary, "  {:<28} {:>5}", "category", "count").unwrap();
+        for (category, count) in &counts {
+            writeln!(summary, "  {:<28} {:>5}", category.description(), count).unwrap();
+        }
+        writeln!(summary, "Occurrences:").unwrap();
+        for (category, span, message) in occurrences {
+            let location = span
+                .primary_span()
+                .map(|span| codemap.span_to_diagnostic_string(span))
+                .unwrap_or_else(|| "<unknown location>".to_string());
+            writeln!(
+                summary,
+                "  [{}] {}: {}",
+                category.description(),
+                location,
+                message
+            )
+            .unwrap();
+        }
+        summary
+    }
+}
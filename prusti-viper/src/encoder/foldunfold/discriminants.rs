@@ -0,0 +1,97 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Reading the discriminant of an enum through the discriminant function
+//! requires the enum predicate. If the enum is currently unfolded (e.g. because
+//! one of its variants was just accessed), obtaining the predicate would
+//! require folding it back, which in turn requires the permissions of all the
+//! fields of the active variant. Instead, such reads are resolved from the
+//! discriminant field, to which we have access.
+
+use super::path_ctxt::PathCtxt;
+use log::trace;
+use vir_crate::polymorphic::{self as vir, ExprFolder, StmtFolder};
+
+/// The suffix of the names of the discriminant functions.
+const DISCRIMINANT_FUNCTION_SUFFIX: &str = "$$discriminant$$";
+
+/// Replace the applications of discriminant functions on current places of
+/// unfolded enums by reads of the discriminant fields.
+pub(super) fn read_unfolded_discriminants(stmt: vir::Stmt, pctxt: &PathCtxt) -> vir::Stmt {
+    StmtFolder::fold(&mut DiscriminantResolver { pctxt }, stmt)
+}
+
+struct DiscriminantResolver<'a, 'p> {
+    pctxt: &'a PathCtxt<'p>,
+}
+
+impl<'a, 'p> DiscriminantResolver<'a, 'p> {
+    /// Returns the discriminant field of `place` if the enum is unfolded.
+    fn get_unfolded_discriminant(&self, place: &vir::Expr) -> Option<vir::Expr> {
+        if !place.is_place() || !place.is_curr() {
+            return None;
+        }
+        let state = self.pctxt.state();
+        if state.contains_pred(place) {
+            return None;
+        }
+        let predicate = self.pctxt.predicates().get(place.get_type())?;
+        if let vir::Predicate::Enum(enum_predicate) = predicate {
+            let discriminant = place
+                .clone()
+                .field(enum_predicate.discriminant_field.clone());
+            if state.contains_acc(&discriminant) {
+                return Some(discriminant);
+            }
+        }
+        None
+    }
+}
+
+impl<'a, 'p> ExprFolder for DiscriminantResolver<'a, 'p> {
+    fn fold_func_app(&mut self, func_app: vir::FuncApp) -> vir::Expr {
+        if func_app
+            .function_name
+            .ends_with(DISCRIMINANT_FUNCTION_SUFFIX)
+            && func_app.arguments.len() == 1
+        {
+            if let Some(discriminant) = self.get_unfolded_discriminant(&func_app.arguments[0]) {
+                trace!(
+                    "Reading the discriminant of an unfolded enum: {}",
+                    discriminant
+                );
+                return discriminant.set_default_pos(func_app.position);
+            }
+        }
+        let vir::FuncApp {
+            function_name,
+            type_arguments,
+            arguments,
+            formal_arguments,
+            return_type,
+            position,
+        } = func_app;
+        vir::Expr::FuncApp(vir::FuncApp {
+            function_name,
+            type_arguments,
+            arguments: arguments.into_iter().map(|arg| self.fold(arg)).collect(),
+            formal_arguments,
+            return_type,
+            position,
+        })
+    }
+
+    fn fold_labelled_old(&mut self, labelled_old: vir::LabelledOld) -> vir::Expr {
+        // The state describes only the current permissions.
+        vir::Expr::LabelledOld(labelled_old)
+    }
+}
+
+impl<'a, 'p> StmtFolder for DiscriminantResolver<'a, 'p> {
+    fn fold_expr(&mut self, expr: vir::Expr) -> vir::Expr {
+        ExprFolder::fold(self, expr)
+    }
+}
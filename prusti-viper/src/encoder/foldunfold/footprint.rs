@@ -6,6 +6,7 @@
 
 use super::{
     places_utils::{union, union3},
+    predicates::MemoKind,
     Predicates,
 };
 use crate::encoder::foldunfold::perm::{Perm::*, *};
@@ -22,160 +23,171 @@ pub trait ExprFootprintGetter {
 
 impl ExprFootprintGetter for vir::Expr {
     fn get_footprint(&self, predicates: &Predicates) -> FxHashSet<Perm> {
-        trace!("get_footprint {}", self);
-        let res = match self {
-            vir::Expr::Local(_)
-            | vir::Expr::Field(_)
-            | vir::Expr::Variant(_)
-            | vir::Expr::AddrOf(_)
-            | vir::Expr::LabelledOld(_)
-            | vir::Expr::Const(_)
-            | vir::Expr::FuncApp(_)
-            | vir::Expr::DomainFuncApp(_)
-            | vir::Expr::InhaleExhale(_) => FxHashSet::default(),
-
-            vir::Expr::Unfolding(vir::Unfolding {
-                arguments,
-                base,
-                permission,
-                variant,
-                ..
-            }) => {
-                assert_eq!(arguments.len(), 1);
-                let place = &arguments[0];
-                debug_assert!(place.is_place());
-
-                // We want to temporarly unfold place
-                let predicate_type = place.get_type();
-                let predicate = predicates.get(predicate_type).unwrap();
-
-                let pred_self_place: vir::Expr = predicate.self_place();
-                let places_in_pred: FxHashSet<Perm> = predicate
-                    .get_body_footprint(variant)
-                    .into_iter()
-                    .map(|aop| {
-                        aop.map_place(|p| p.replace_place(&pred_self_place, place))
-                            .init_perm_amount(*permission)
-                    })
-                    .collect();
-
-                // Simulate temporary unfolding of `place`
-                let expr_access_places = base.get_footprint(predicates);
-
-                // inhaled = inhaled in body - unfolding
-                perm_difference(expr_access_places, places_in_pred)
-            }
+        // Only the footprints of whole expressions are memoized, because
+        // building the memo key takes time linear in the size of the
+        // expression.
+        predicates.memoize(MemoKind::Footprint, self, || {
+            compute_footprint(self, predicates)
+        })
+    }
+}
 
-            vir::Expr::UnaryOp(vir::UnaryOp { argument, .. }) => argument.get_footprint(predicates),
-
-            vir::Expr::BinOp(vir::BinOp {
-                box left,
-                box right,
-                ..
-            }) => union(
-                &left.get_footprint(predicates),
-                &right.get_footprint(predicates),
-            ),
-
-            vir::Expr::ContainerOp(vir::ContainerOp {
-                box left,
-                box right,
-                ..
-            }) => union(
-                &left.get_footprint(predicates),
-                &right.get_footprint(predicates),
-            ),
-
-            vir::Expr::Seq(vir::Seq { elements, .. }) => elements
-                .iter()
-                .map(|e| e.get_footprint(predicates))
-                .fold(FxHashSet::default(), |mut hs, fp| {
-                    hs.extend(fp);
-                    hs
-                }),
-
-            vir::Expr::Cond(vir::Cond {
-                box guard,
-                box then_expr,
-                box else_expr,
-                ..
-            }) => union3(
-                &guard.get_footprint(predicates),
-                &then_expr.get_footprint(predicates),
-                &else_expr.get_footprint(predicates),
-            ),
-
-            vir::Expr::ForAll(vir::ForAll {
-                variables,
-                box body,
-                ..
-            })
-            | vir::Expr::Exists(vir::Exists {
-                variables,
-                box body,
-                ..
-            }) => {
-                assert!(variables
-                    .iter()
-                    .all(|var| !var.typ.is_typed_ref_or_type_var()));
-                let vars_places: FxHashSet<Perm> = variables
-                    .iter()
-                    .map(|var| Acc(vir::Expr::local(var.clone()), PermAmount::Write))
-                    .collect();
-                perm_difference(body.get_footprint(predicates), vars_places)
-            }
+/// Computes the footprint of `expr` without memoizing its subexpressions.
+fn compute_footprint(expr: &vir::Expr, predicates: &Predicates) -> FxHashSet<Perm> {
+    trace!("get_footprint {}", expr);
+    let res = match expr {
+        vir::Expr::Local(_)
+        | vir::Expr::Field(_)
+        | vir::Expr::Variant(_)
+        | vir::Expr::AddrOf(_)
+        | vir::Expr::LabelledOld(_)
+        | vir::Expr::Const(_)
+        | vir::Expr::FuncApp(_)
+        | vir::Expr::DomainFuncApp(_)
+        | vir::Expr::InhaleExhale(_) => FxHashSet::default(),
+
+        vir::Expr::Unfolding(vir::Unfolding {
+            arguments,
+            base,
+            permission,
+            variant,
+            ..
+        }) => {
+            let place = &arguments[0];
+            debug_assert!(place.is_place());
+
+            // We want to temporarly unfold place
+            let predicate_type = place.get_type();
+            let predicate = predicates.get(predicate_type).unwrap();
+            assert_eq!(arguments.len(), predicate.arity());
+
+            let places_in_pred: FxHashSet<Perm> = predicate
+                .get_body_footprint(variant)
+                .into_iter()
+                .map(|aop| {
+                    aop.map_place(|p| predicate.instantiate(p, arguments))
+                        .init_perm_amount(*permission)
+                })
+                .collect();
+
+            // Simulate temporary unfolding of `place`
+            let expr_access_places = compute_footprint(base, predicates);
+
+            // inhaled = inhaled in body - unfolding
+            perm_difference(expr_access_places, places_in_pred)
+        }
 
-            vir::Expr::PredicateAccessPredicate(vir::PredicateAccessPredicate {
-                box ref argument,
-                permission,
-                ..
-            }) => {
-                let opt_perm = if argument.is_place() {
-                    Some(match argument.get_label() {
-                        None => Perm::Pred(argument.clone(), *permission),
-                        Some(label) => Perm::Pred(argument.clone().old(label), *permission),
-                    })
-                } else {
-                    None
-                };
+        vir::Expr::UnaryOp(vir::UnaryOp { argument, .. }) => {
+            compute_footprint(argument, predicates)
+        }
 
-                opt_perm.into_iter().collect()
-            }
+        vir::Expr::BinOp(vir::BinOp {
+            box left,
+            box right,
+            ..
+        }) => union(
+            &compute_footprint(left, predicates),
+            &compute_footprint(right, predicates),
+        ),
+
+        vir::Expr::ContainerOp(vir::ContainerOp {
+            box left,
+            box right,
+            ..
+        }) => union(
+            &compute_footprint(left, predicates),
+            &compute_footprint(right, predicates),
+        ),
+
+        vir::Expr::Seq(vir::Seq { elements, .. }) => elements
+            .iter()
+            .map(|e| compute_footprint(e, predicates))
+            .fold(FxHashSet::default(), |mut hs, fp| {
+                hs.extend(fp);
+                hs
+            }),
+
+        vir::Expr::Cond(vir::Cond {
+            box guard,
+            box then_expr,
+            box else_expr,
+            ..
+        }) => union3(
+            &compute_footprint(guard, predicates),
+            &compute_footprint(then_expr, predicates),
+            &compute_footprint(else_expr, predicates),
+        ),
+
+        vir::Expr::ForAll(vir::ForAll {
+            variables,
+            box body,
+            ..
+        })
+        | vir::Expr::Exists(vir::Exists {
+            variables,
+            box body,
+            ..
+        }) => {
+            assert!(variables
+                .iter()
+                .all(|var| !var.typ.is_typed_ref_or_type_var()));
+            let vars_places: FxHashSet<Perm> = variables
+                .iter()
+                .map(|var| Acc(vir::Expr::local(var.clone()), PermAmount::Write))
+                .collect();
+            perm_difference(compute_footprint(body, predicates), vars_places)
+        }
 
-            vir::Expr::FieldAccessPredicate(vir::FieldAccessPredicate {
-                box ref base,
-                permission,
-                ..
-            }) => {
-                // In Prusti we assume to have only places here
-                debug_assert!(base.is_place());
-                debug_assert!(base.is_curr());
+        vir::Expr::PredicateAccessPredicate(vir::PredicateAccessPredicate {
+            box ref argument,
+            permission,
+            ..
+        }) => {
+            let opt_perm = if argument.is_place() {
+                Some(match argument.get_label() {
+                    None => Perm::Pred(argument.clone(), *permission),
+                    Some(label) => Perm::Pred(argument.clone().old(label), *permission),
+                })
+            } else {
+                None
+            };
+
+            opt_perm.into_iter().collect()
+        }
 
-                let perm = Acc(base.clone(), *permission);
+        vir::Expr::FieldAccessPredicate(vir::FieldAccessPredicate {
+            box ref base,
+            permission,
+            ..
+        }) => {
+            // In Prusti we assume to have only places here
+            debug_assert!(base.is_place());
+            debug_assert!(base.is_curr());
 
-                Some(perm).into_iter().collect()
-            }
+            let perm = Acc(base.clone(), *permission);
 
-            vir::Expr::MagicWand(_) => {
-                // We don't track magic wands resources
-                FxHashSet::default()
-            }
+            Some(perm).into_iter().collect()
+        }
 
-            vir::Expr::LetExpr(_) => {
-                unreachable!("Let expressions should be introduced after fold/unfold.");
-            }
+        vir::Expr::MagicWand(_) => {
+            // We don't track magic wands resources
+            FxHashSet::default()
+        }
 
-            vir::Expr::Downcast(vir::DowncastExpr { ref base, .. }) => {
-                base.get_footprint(predicates)
-            }
+        vir::Expr::LetExpr(_) => {
+            unreachable!("Let expressions should be introduced after fold/unfold.");
+        }
 
-            vir::Expr::SnapApp(vir::SnapApp { ref base, .. }) => base.get_footprint(predicates),
+        vir::Expr::Downcast(vir::DowncastExpr { ref base, .. }) => {
+            compute_footprint(base, predicates)
+        }
 
-            vir::Expr::Cast(vir::Cast { ref base, .. }) => base.get_footprint(predicates),
-        };
-        trace!("get_footprint {} = {:?}", self, res);
-        res
-    }
+        vir::Expr::SnapApp(vir::SnapApp { ref base, .. }) => compute_footprint(base, predicates),
+
+        vir::Expr::Cast(vir::Cast { ref base, .. }) => compute_footprint(base, predicates),
+    };
+    trace!("get_footprint {} = {:?}", expr, res);
+    res
 }
 
 pub trait PredicateFootprintGetter {
@@ -222,7 +234,7 @@ impl StructPredicateFootprintGetter for vir::StructPredicate {
         match self.body {
             Some(ref body) => {
                 // A predicate body should not contain unfolding expression
-                let predicates = FxHashMap::default();
+                let predicates = Predicates::new(FxHashMap::default());
                 body.get_footprint(&predicates)
             }
             None => FxHashSet::default(),
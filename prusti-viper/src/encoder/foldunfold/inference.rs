@@ -0,0 +1,140 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A backward analysis that computes, for each basic block, the predicate
+//! permissions that will be required after its entry, before something else
+//! provides them.
+//!
+//! The forward fold-unfold algorithm only knows the permissions required by
+//! the current statement, so at a join it often keeps the branches in
+//! incompatible states and has to drop permissions that are needed later
+//! (e.g. at a loop head). The result of this analysis is used to obtain, at
+//! the end of each branch, the predicates that are required after the join.
+//! The analysis is only a hint: the forward algorithm still obtains all the
+//! permissions that each statement requires.
+
+use super::{
+    footprint::ExprFootprintGetter,
+    perm::Perm,
+    requirements::{RequiredExprPermissionsGetter, RequiredStmtPermissionsGetter},
+    FoldUnfoldError, Predicates,
+};
+use log::trace;
+use rustc_hash::{FxHashMap, FxHashSet};
+use vir_crate::polymorphic as vir;
+
+/// Returns, for each basic block that has more than one predecessor, the
+/// sorted predicate permissions required at its entry.
+pub(super) fn compute_join_requirements(
+    cfg: &vir::CfgMethod,
+    predicates: &Predicates,
+) -> Result<FxHashMap<usize, Vec<Perm>>, FoldUnfoldError> {
+    let entry_requirements = compute_entry_requirements(cfg, predicates)?;
+    let mut join_requirements = FxHashMap::default();
+    for (block_index, preceding) in cfg.predecessors() {
+        if preceding.len() > 1 {
+            let mut perms: Vec<_> = entry_requirements[block_index].iter().cloned().collect();
+            perms.sort();
+            trace!(
+                "Permissions required at join block {}: {:?}",
+                block_index,
+                perms
+            );
+            join_requirements.insert(block_index, perms);
+        }
+    }
+    Ok(join_requirements)
+}
+
+/// Compute the fixpoint of the backward analysis.
+fn compute_entry_requirements(
+    cfg: &vir::CfgMethod,
+    predicates: &Predicates,
+) -> Result<Vec<FxHashSet<Perm>>, FoldUnfoldError> {
+    let block_count = cfg.basic_blocks.len();
+    let mut entry_requirements: Vec<FxHashSet<Perm>> = vec![FxHashSet::default(); block_count];
+    let mut order: Vec<usize> = cfg
+        .get_topological_sort()
+        .iter()
+        .map(|index| index.block_index)
+        .collect();
+    order.reverse();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &block_index in &order {
+            let block = &cfg.basic_blocks[block_index];
+            let mut requirements: FxHashSet<Perm> = block
+                .successor
+                .get_following()
+                .iter()
+                .flat_map(|target| entry_requirements[target.block_index].iter().cloned())
+                .collect();
+            if let vir::Successor::GotoSwitch(ref guarded_targets, _) = block.successor {
+                for (guard, _) in guarded_targets {
+                    add_requirements(
+                        &mut requirements,
+                        guard.get_required_stmt_permissions(predicates),
+                    );
+                }
+            }
+            for stmt in block.stmts.iter().rev() {
+                transfer(stmt, &mut requirements, predicates)?;
+            }
+            if requirements != entry_requirements[block_index] {
+                entry_requirements[block_index] = requirements;
+                changed = true;
+            }
+        }
+    }
+    Ok(entry_requirements)
+}
+
+/// Compute the requirements before `stmt` from those after it.
+fn transfer(
+    stmt: &vir::Stmt,
+    requirements: &mut FxHashSet<Perm>,
+    predicates: &Predicates,
+) -> Result<(), FoldUnfoldError> {
+    // Remove the requirements that are satisfied by the statement.
+    match stmt {
+        vir::Stmt::Assign(vir::Assign { target, .. }) => {
+            requirements.retain(|perm| !perm.get_place().has_prefix(target));
+        }
+        vir::Stmt::MethodCall(vir::MethodCall { targets, .. }) => {
+            requirements.retain(|perm| {
+                let base = perm.get_place().get_base();
+                !targets.iter().any(|target| *target == base)
+            });
+        }
+        vir::Stmt::Inhale(vir::Inhale { expr }) => {
+            let footprint = expr.get_footprint(predicates);
+            requirements.retain(|perm| {
+                !footprint
+                    .iter()
+                    .any(|inhaled| perm.get_place().has_prefix(inhaled.get_place()))
+            });
+        }
+        vir::Stmt::Fold(vir::Fold { arguments, .. }) => {
+            requirements.retain(|perm| perm.get_place() != &arguments[0]);
+        }
+        vir::Stmt::Unfold(vir::Unfold { arguments, .. }) => {
+            requirements.retain(|perm| !perm.has_proper_prefix(&arguments[0]));
+        }
+        _ => {}
+    }
+    // Add the requirements of the statement.
+    add_requirements(requirements, stmt.get_required_permissions(predicates)?);
+    Ok(())
+}
+
+fn add_requirements(requirements: &mut FxHashSet<Perm>, new_requirements: FxHashSet<Perm>) {
+    requirements.extend(
+        new_requirements
+            .into_iter()
+            .filter(|perm| perm.is_pred() && perm.is_curr()),
+    );
+}
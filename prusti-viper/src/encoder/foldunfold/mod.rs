@@ -27,17 +27,21 @@ use vir_crate::{
 
 mod action;
 mod borrows;
+mod discriminants;
 mod footprint;
+mod inference;
 mod log;
 mod path_ctxt;
 mod perm;
 mod places_utils;
+mod predicates;
 mod process_expire_borrows;
 mod requirements;
 mod semantics;
 mod state;
+mod state_dump;
 
-pub type Predicates = FxHashMap<vir::Type, vir::Predicate>;
+pub use self::predicates::Predicates;
 
 #[derive(Clone, Debug)]
 pub enum FoldUnfoldError {
@@ -60,12 +64,18 @@ pub enum FoldUnfoldError {
     /// The algorithms tried to remove a predicate that is not in the
     /// fold-unfold state.
     FailedToRemovePred(vir::Expr, PermAmount),
+    /// The algorithm tried to join two paths that hold incomparable
+    /// permission amounts of the same place.
+    IncompatiblePermAmounts(vir::Expr, PermAmount, PermAmount),
     /// The algorithm tried to lookup a never-seen-before label
     MissingLabel(String),
     /// Other encoding error.
     SpannedEncodingError(SpannedEncodingError),
     /// Unsupported feature
     Unsupported(String),
+    /// The algorithm cannot compute the permissions required by a statement,
+    /// or the effect of the statement on the fold-unfold state.
+    UnsupportedStmt(vir::Stmt, vir::Position),
 }
 
 impl fmt::Display for FoldUnfoldError {
@@ -97,6 +107,13 @@ impl fmt::Display for FoldUnfoldError {
                     expr, frac
                 )
             }
+            FoldUnfoldError::IncompatiblePermAmounts(place, left, right) => {
+                writeln!(
+                    f,
+                    "Cannot join the permission amounts {} and {} of {}.",
+                    left, right, place
+                )
+            }
             FoldUnfoldError::MissingLabel(label) => {
                 writeln!(
                     f,
@@ -110,6 +127,13 @@ impl fmt::Display for FoldUnfoldError {
             FoldUnfoldError::Unsupported(error) => {
                 writeln!(f, "Unsupported feature: {}.", error)
             }
+            FoldUnfoldError::UnsupportedStmt(stmt, _pos) => {
+                writeln!(
+                    f,
+                    "The permissions of the statement '{}' cannot be computed.",
+                    stmt
+                )
+            }
         }
     }
 }
@@ -159,6 +183,7 @@ pub fn add_folding_unfolding_to_function(
     // Viper functions cannot contain label statements, so knowing all usages of old expressions
     // is not needed.
     let old_exprs = FxHashMap::default();
+    let predicates = Predicates::new(predicates);
     let mut pctxt = PathCtxt::new(formal_vars, &predicates, &old_exprs);
     for pre in &function.pres {
         pctxt.apply_stmt(&vir::Stmt::Inhale(vir::Inhale { expr: pre.clone() }))?;
@@ -203,7 +228,7 @@ pub fn add_fold_unfold<'p, 'v: 'p, 'tcx: 'v>(
     let _stopwatch =
         Stopwatch::start_debug("prusti-client", "add fold-unfold statements to a method");
     let cfg_vars = cfg.get_all_vars();
-    let predicates = encoder.get_used_viper_predicates_map()?;
+    let predicates = Predicates::new(encoder.get_used_viper_predicates_map()?);
     // Collect all old expressions used in the CFG
     let old_exprs = {
         struct OldExprCollector {
@@ -232,15 +257,26 @@ pub fn add_fold_unfold<'p, 'v: 'p, 'tcx: 'v>(
         old_expr_collector.old_exprs
     };
     let initial_pctxt = PathCtxt::new(cfg_vars, &predicates, &old_exprs);
-    FoldUnfold::new(
+    let mut foldunfold = FoldUnfold::new(
         encoder,
         initial_pctxt,
         &cfg,
         borrow_locations,
         cfg_map,
         method_pos,
-    )
-    .replace_cfg(&cfg)
+    );
+    if config::foldunfold_infer_join_permissions() {
+        foldunfold.join_requirements =
+            Some(inference::compute_join_requirements(&cfg, &predicates)?);
+    }
+    let result = foldunfold.replace_cfg(&cfg);
+    // The state is dumped also on failure, because that is when it is most useful.
+    if let Some(state_dump) = &foldunfold.state_dump {
+        let source_path = encoder.env().source_path();
+        let source_filename = source_path.file_name().unwrap().to_str().unwrap();
+        state_dump.report(source_filename, &cfg.name());
+    }
+    result
 }
 
 #[derive(Clone)]
@@ -251,6 +287,11 @@ struct FoldUnfold<'p, 'v: 'p, 'tcx: 'v> {
     dump_debug_info: bool,
     /// Used for debugging the dump
     foldunfold_state_filter: String,
+    /// The per-statement report, if `dump_foldunfold_state` is enabled.
+    state_dump: Option<state_dump::StateDump>,
+    /// The predicates to obtain before jumping to a join block, if
+    /// `foldunfold_infer_join_permissions` is enabled.
+    join_requirements: Option<FxHashMap<usize, Vec<Perm>>>,
     /// Generate additional assertions to check that the state of the fold-unfold algorithm
     /// under-approximates the set of permissions actually available in Viper.
     check_foldunfold_state: bool,
@@ -277,6 +318,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> FoldUnfold<'p, 'v, 'tcx> {
             dump_debug_info: config::dump_debug_info_during_fold(),
             check_foldunfold_state: config::check_foldunfold_state(),
             foldunfold_state_filter: config::foldunfold_state_filter(),
+            state_dump: if config::dump_foldunfold_state() {
+                Some(Default::default())
+            } else {
+                None
+            },
+            join_requirements: None,
             cfg,
             borrow_locations,
             cfg_map,
@@ -582,11 +629,14 @@ impl<'p, 'v: 'p, 'tcx: 'v> vir::CfgReplacer<PathCtxt<'p>, ActionVec> for FoldUnf
         // 1. Insert "unfolding in" inside old expressions. This handles *old* requirements.
         trace!("[step.1] replace_stmt: {}", stmt);
         stmt = self.rewrite_stmt_with_unfoldings_in_old(stmt, pctxt)?;
+        if config::foldunfold_read_unfolded_discriminants() {
+            stmt = discriminants::read_unfolded_discriminants(stmt, pctxt);
+        }
 
         // 2. Obtain required *curr* permissions. *old* requirements will be handled at steps 0 and/or 4.
         trace!("[step.2] replace_stmt: {}", stmt);
         {
-            let all_perms = stmt.get_required_permissions(pctxt.predicates());
+            let all_perms = stmt.get_required_permissions(pctxt.predicates())?;
             let pred_permissions: Vec<_> =
                 all_perms.iter().cloned().filter(|p| p.is_pred()).collect();
 
@@ -616,8 +666,16 @@ impl<'p, 'v: 'p, 'tcx: 'v> vir::CfgReplacer<PathCtxt<'p>, ActionVec> for FoldUnf
                     .join(",\n")
             );
 
+            if let Some(state_dump) = &mut self.state_dump {
+                state_dump.record(curr_block_index, &stmt, pctxt, &perms);
+            }
+
             if !perms.is_empty() {
-                stmts.extend(pctxt.obtain_permissions(perms)?.iter().map(|a| a.to_stmt()));
+                let actions = pctxt.obtain_permissions(perms);
+                if let Some(state_dump) = &mut self.state_dump {
+                    state_dump.record_actions(&actions);
+                }
+                stmts.extend(actions?.iter().map(|a| a.to_stmt()));
 
                 if self.check_foldunfold_state && !is_last_before_return && label.is_none() {
                     stmts.push(vir::Stmt::comment("Assert content of fold/unfold state"));
@@ -1031,6 +1089,20 @@ impl<'p, 'v: 'p, 'tcx: 'v> vir::CfgReplacer<PathCtxt<'p>, ActionVec> for FoldUnf
             }
         }
 
+        // Prepare the state for the join, if we know what will be required after it.
+        if let (Some(join_requirements), vir::Successor::Goto(target)) =
+            (&self.join_requirements, succ)
+        {
+            if let Some(perms) = join_requirements.get(&target.block_index) {
+                stmts.extend(
+                    pctxt
+                        .try_obtain_permissions(perms)
+                        .iter()
+                        .map(|a| a.to_stmt()),
+                );
+            }
+        }
+
         if some_perms_required && self.check_foldunfold_state {
             stmts.push(vir::Stmt::comment("Assert content of fold/unfold state"));
             stmts.push(vir::Stmt::Assert(vir::Assert {
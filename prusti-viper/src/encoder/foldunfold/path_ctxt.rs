@@ -10,9 +10,9 @@ use crate::encoder::foldunfold::{
     state::*, FoldUnfoldError, FoldUnfoldError::FailedToObtain,
 };
 use log::{debug, trace};
-use prusti_common::utils::to_string::ToString;
+use prusti_common::{config, utils::to_string::ToString};
 use rustc_hash::{FxHashMap, FxHashSet};
-use std::iter::FromIterator;
+use std::{cmp::Ordering, iter::FromIterator, slice};
 use vir_crate::polymorphic::{self as vir, PermAmount};
 
 /// The fold-unfold context of a CFG path
@@ -109,8 +109,6 @@ impl<'a> PathCtxt<'a> {
             .map(Ok)
             .unwrap_or_else(|| Err(FoldUnfoldError::MissingPredicate(predicate_type.clone())))?;
 
-        let pred_self_place: vir::Expr = predicate.self_place();
-
         trace!(
             "Pred state before unfold: {{\n{}\n}}",
             self.state.display_pred()
@@ -124,8 +122,7 @@ impl<'a> PathCtxt<'a> {
                     .get_body_footprint(&variant)
                     .into_iter()
                     .map(|perm| {
-                        // TODO polymorphic
-                        perm.map_place(|p| p.replace_place(&pred_self_place, pred_place))
+                        perm.map_place(|p| predicate.instantiate(p, slice::from_ref(pred_place)))
                             .update_perm_amount(perm_amount)
                     }),
             )?;
@@ -192,6 +189,13 @@ impl<'a> PathCtxt<'a> {
             other.state.set_moved(moved_paths.clone());
             trace!("moved_paths: {}", moved_paths.iter().to_string());
 
+            // Anti-unify the two states: fold places that are folded in the
+            // other branch, instead of later unfolding them there.
+            if config::foldunfold_fold_at_join() {
+                fold_common_predicates(self, &mut other, &moved_paths, &mut right_actions);
+                fold_common_predicates(&mut other, self, &moved_paths, &mut left_actions);
+            }
+
             trace!("left acc: {{\n{}\n}}", self.state.display_acc());
             trace!("right acc: {{\n{}\n}}", other.state.display_acc());
 
@@ -443,7 +447,9 @@ impl<'a> PathCtxt<'a> {
                 right_actions.push(Action::Drop(perm.clone(), perm));
             }
 
-            // If we have `Read` and `Write`, make both `Read`.
+            // If the permission amounts differ, drop the difference from the
+            // larger one. For example, if we have `Read` and `Write`, make both
+            // `Read`; if we have `1/2` and `1/3`, make both `1/3`.
             for acc_place in self.state.acc_places() {
                 assert!(
                     other.state.acc().contains_key(&acc_place),
@@ -452,32 +458,50 @@ impl<'a> PathCtxt<'a> {
                 );
                 let left_perm = self.state.acc()[&acc_place];
                 let right_perm = other.state.acc()[&acc_place];
-                if left_perm == PermAmount::Write && right_perm == PermAmount::Read {
-                    self.state.remove_acc(&acc_place, PermAmount::Remaining)?;
-                    let perm = Perm::acc(acc_place.clone(), PermAmount::Remaining);
-                    left_actions.push(Action::Drop(perm.clone(), perm));
-                }
-                if left_perm == PermAmount::Read && right_perm == PermAmount::Write {
-                    other.state.remove_acc(&acc_place, PermAmount::Remaining)?;
-                    let perm = Perm::acc(acc_place.clone(), PermAmount::Remaining);
-                    right_actions.push(Action::Drop(perm.clone(), perm));
+                match left_perm.partial_cmp(&right_perm) {
+                    Some(Ordering::Greater) => {
+                        let difference = (left_perm - right_perm)?;
+                        self.state.remove_acc(&acc_place, difference)?;
+                        let perm = Perm::acc(acc_place.clone(), difference);
+                        left_actions.push(Action::Drop(perm.clone(), perm));
+                    }
+                    Some(Ordering::Less) => {
+                        let difference = (right_perm - left_perm)?;
+                        other.state.remove_acc(&acc_place, difference)?;
+                        let perm = Perm::acc(acc_place.clone(), difference);
+                        right_actions.push(Action::Drop(perm.clone(), perm));
+                    }
+                    Some(Ordering::Equal) => {}
+                    None => {
+                        return Err(FoldUnfoldError::IncompatiblePermAmounts(
+                            acc_place, left_perm, right_perm,
+                        ));
+                    }
                 }
             }
             for pred_place in self.state.pred_places() {
                 assert!(other.state.pred().contains_key(&pred_place));
                 let left_perm = self.state.pred()[&pred_place];
                 let right_perm = other.state.pred()[&pred_place];
-                if left_perm == PermAmount::Write && right_perm == PermAmount::Read {
-                    self.state.remove_pred(&pred_place, PermAmount::Remaining)?;
-                    let perm = Perm::pred(pred_place.clone(), PermAmount::Remaining);
-                    left_actions.push(Action::Drop(perm.clone(), perm));
-                }
-                if left_perm == PermAmount::Read && right_perm == PermAmount::Write {
-                    other
-                        .state
-                        .remove_pred(&pred_place, PermAmount::Remaining)?;
-                    let perm = Perm::pred(pred_place.clone(), PermAmount::Remaining);
-                    right_actions.push(Action::Drop(perm.clone(), perm));
+                match left_perm.partial_cmp(&right_perm) {
+                    Some(Ordering::Greater) => {
+                        let difference = (left_perm - right_perm)?;
+                        self.state.remove_pred(&pred_place, difference)?;
+                        let perm = Perm::pred(pred_place.clone(), difference);
+                        left_actions.push(Action::Drop(perm.clone(), perm));
+                    }
+                    Some(Ordering::Less) => {
+                        let difference = (right_perm - left_perm)?;
+                        other.state.remove_pred(&pred_place, difference)?;
+                        let perm = Perm::pred(pred_place.clone(), difference);
+                        right_actions.push(Action::Drop(perm.clone(), perm));
+                    }
+                    Some(Ordering::Equal) => {}
+                    None => {
+                        return Err(FoldUnfoldError::IncompatiblePermAmounts(
+                            pred_place, left_perm, right_perm,
+                        ));
+                    }
                 }
             }
 
@@ -585,7 +609,7 @@ impl<'a> PathCtxt<'a> {
             let predicate = self
                 .predicates
                 .get(predicate_type)
-                .unwrap_or_else(|| panic!("not found: {}", predicate_type));
+                .ok_or_else(|| FoldUnfoldError::MissingPredicate(predicate_type.clone()))?;
 
             let variant = find_unfolded_variant(&self.state, req.get_place());
 
@@ -596,13 +620,12 @@ impl<'a> PathCtxt<'a> {
                 .into_iter()
                 .find(|p| p.has_proper_prefix(req.get_place()));
 
-            let pred_self_place: vir::Expr = predicate.self_place();
-
-            // TODO polymorphic
             let places_in_pred: Vec<Perm> = predicate
                 .get_body_footprint(&variant)
                 .into_iter()
-                .map(|perm| perm.map_place(|p| p.replace_place(&pred_self_place, req.get_place())))
+                .map(|perm| {
+                    perm.map_place(|p| predicate.instantiate(p, slice::from_ref(req.get_place())))
+                })
                 .collect();
 
             // Check that there exists something that would make the fold possible.
@@ -640,7 +663,15 @@ impl<'a> PathCtxt<'a> {
 
                 for fold_req_place in &places_in_pred {
                     let pos = req.get_place().pos();
-                    let new_req_place = fold_req_place.clone().set_default_pos(pos);
+                    // The body of the predicate is obtained with the amount
+                    // we fold with, so that folding a predicate for which we
+                    // only have read permissions (e.g. an enum nested behind a
+                    // shared reference) does not require write permissions
+                    // for the nested predicates.
+                    let new_req_place = fold_req_place
+                        .clone()
+                        .update_perm_amount(perm_amount)
+                        .set_default_pos(pos);
                     let obtain_result = self.obtain(&new_req_place, false)?;
                     match obtain_result {
                         ObtainResult::Success(new_actions) => {
@@ -778,6 +809,26 @@ Predicates: {{
         trace!("[exit] obtain_permissions: {}", actions.iter().to_string());
         Ok(actions)
     }
+
+    /// Try to obtain each of the given permissions, in order, skipping those
+    /// that cannot be obtained in the current state. Used to obtain
+    /// permissions that are not required, but that make joins more precise.
+    pub fn try_obtain_permissions(&mut self, permissions: &[Perm]) -> Vec<Action> {
+        let mut actions = vec![];
+        for perm in permissions {
+            let mut new_ctxt = self.clone();
+            if let Ok(ObtainResult::Success(new_actions)) = new_ctxt.obtain(perm, true) {
+                trace!(
+                    "Obtained {} with actions {}",
+                    perm,
+                    new_actions.iter().to_string()
+                );
+                *self = new_ctxt;
+                actions.extend(new_actions);
+            }
+        }
+        actions
+    }
 }
 
 /// Find in `variant_place` the variant index of the enum encoded by `enum_place`.
@@ -820,6 +871,45 @@ pub fn find_unfolded_variant(state: &State, enum_place: &vir::Expr) -> vir::Mayb
     variants.into_iter().next()
 }
 
+/// Folds in `unfolded` the predicates that are held by `folded`, but that are
+/// unfolded in `unfolded`. This computes the least common generalization of the
+/// two states: instead of unfolding `P(x)` in `folded` to match the access
+/// permissions below `x` in `unfolded`, it tries to fold `P(x)` in `unfolded`.
+///
+/// A predicate is folded only if this succeeds without dropping any
+/// permission; otherwise, `unfolded` is left unchanged and the usual join
+/// algorithm takes care of the place.
+fn fold_common_predicates(
+    folded: &PathCtxt,
+    unfolded: &mut PathCtxt,
+    moved_paths: &FxHashSet<vir::Expr>,
+    unfolded_actions: &mut Vec<Action>,
+) {
+    let unfolded_acc_places = unfolded.state.acc_places();
+    let mut candidates: Vec<_> = folded
+        .state
+        .pred()
+        .iter()
+        .filter(|(place, _)| {
+            place.is_simple_place()
+                && !unfolded.state.contains_pred(place)
+                && unfolded_acc_places
+                    .iter()
+                    .any(|acc_place| acc_place.has_proper_prefix(place))
+                && !moved_paths
+                    .iter()
+                    .any(|moved_place| moved_place.has_prefix(place))
+        })
+        .map(|(place, &perm_amount)| Perm::pred(place.clone(), perm_amount))
+        .collect();
+    candidates.sort();
+    trace!(
+        "Try to fold in the unfolded branch: {}",
+        candidates.iter().to_string()
+    );
+    unfolded_actions.extend(unfolded.try_obtain_permissions(&candidates));
+}
+
 /// Computes a pair of sets of places that should be obtained. The first
 /// element of the pair is the set of places that should be obtained by
 /// unfolding while the second element should be obtained by folding.
@@ -935,3 +1025,189 @@ impl ObtainResult {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoder::foldunfold::requirements::{
+        RequiredExprPermissionsGetter, RequiredStmtPermissionsGetter,
+    };
+    use vir_crate::polymorphic::generator::Generator;
+
+    fn locals() -> Vec<vir::LocalVar> {
+        vec![
+            vir::LocalVar::new("i", vir::Type::Int),
+            vir::LocalVar::new("b", vir::Type::Bool),
+            vir::LocalVar::new("x", vir::Type::typed_ref("T")),
+            vir::LocalVar::new("y", vir::Type::typed_ref("T")),
+        ]
+    }
+
+    fn references() -> Vec<vir::Expr> {
+        locals()
+            .into_iter()
+            .filter(|var| var.typ.is_typed_ref_or_type_var())
+            .map(vir::Expr::local)
+            .collect()
+    }
+
+    /// The predicate `T(self) { acc(self.val_int) }` of the references.
+    fn predicates() -> Predicates {
+        let typ = vir::Type::typed_ref("T");
+        let predicate =
+            vir::Predicate::new_primitive_value(typ.clone(), Generator::ref_field(), None, None);
+        Predicates::new(FxHashMap::from_iter([(typ, predicate)]))
+    }
+
+    /// Run the fold-unfold algorithm on `stmts`, from which the folds and
+    /// unfolds are removed, like `FoldUnfold::replace_stmt` does for each
+    /// statement and at each join.
+    fn insert_fold_unfold(
+        stmts: &[vir::Stmt],
+        pctxt: &mut PathCtxt,
+    ) -> Result<Vec<vir::Stmt>, FoldUnfoldError> {
+        let mut result = vec![];
+        for stmt in stmts {
+            match stmt {
+                vir::Stmt::Fold(_) | vir::Stmt::Unfold(_) => {}
+                vir::Stmt::If(vir::If {
+                    guard,
+                    then_stmts,
+                    else_stmts,
+                }) => {
+                    let perms = guard.get_required_stmt_permissions(pctxt.predicates());
+                    let actions = pctxt.obtain_permissions(perms.into_iter().collect())?;
+                    result.extend(actions.iter().map(Action::to_stmt));
+                    let mut then_pctxt = pctxt.clone();
+                    let mut then_stmts = insert_fold_unfold(then_stmts, &mut then_pctxt)?;
+                    let mut else_pctxt = pctxt.clone();
+                    let mut else_stmts = insert_fold_unfold(else_stmts, &mut else_pctxt)?;
+                    let (then_actions, else_actions) = then_pctxt.join(else_pctxt)?;
+                    then_stmts.extend(then_actions.iter().map(Action::to_stmt));
+                    else_stmts.extend(else_actions.iter().map(Action::to_stmt));
+                    *pctxt = then_pctxt;
+                    result.push(vir::Stmt::If(vir::If {
+                        guard: guard.clone(),
+                        then_stmts,
+                        else_stmts,
+                    }));
+                }
+                _ => {
+                    let perms = stmt.get_required_permissions(pctxt.predicates())?;
+                    let actions = pctxt.obtain_permissions(perms.into_iter().collect())?;
+                    result.extend(actions.iter().map(Action::to_stmt));
+                    pctxt.apply_stmt(stmt)?;
+                    result.push(stmt.clone());
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Check that the statements access the field of a reference only while
+    /// its predicate is unfolded, fold only unfolded predicates and unfold
+    /// only folded ones. `unfolded` holds the references whose predicate is
+    /// unfolded, and is updated to the state after the statements.
+    fn check_permissions(stmts: &[vir::Stmt], unfolded: &mut Vec<vir::Expr>) -> Result<(), String> {
+        struct FieldBases(Vec<vir::Expr>);
+        impl vir::ExprWalker for FieldBases {
+            fn walk_field(&mut self, vir::FieldExpr { base, .. }: &vir::FieldExpr) {
+                self.0.push((**base).clone());
+                vir::ExprWalker::walk(self, base);
+            }
+        }
+        impl vir::StmtWalker for FieldBases {
+            fn walk_expr(&mut self, expr: &vir::Expr) {
+                vir::ExprWalker::walk(self, expr);
+            }
+        }
+
+        for stmt in stmts {
+            match stmt {
+                vir::Stmt::Fold(vir::Fold { arguments, .. }) => {
+                    let index = unfolded
+                        .iter()
+                        .position(|place| place == &arguments[0])
+                        .ok_or_else(|| format!("{} without permission", stmt))?;
+                    unfolded.remove(index);
+                }
+                vir::Stmt::Unfold(vir::Unfold { arguments, .. }) => {
+                    if unfolded.contains(&arguments[0]) {
+                        return Err(format!("{} without permission", stmt));
+                    }
+                    unfolded.push(arguments[0].clone());
+                }
+                vir::Stmt::If(vir::If {
+                    then_stmts,
+                    else_stmts,
+                    ..
+                }) => {
+                    let mut then_unfolded = unfolded.clone();
+                    check_permissions(then_stmts, &mut then_unfolded)?;
+                    let mut else_unfolded = unfolded.clone();
+                    check_permissions(else_stmts, &mut else_unfolded)?;
+                    if then_unfolded.len() != else_unfolded.len()
+                        || then_unfolded
+                            .iter()
+                            .any(|place| !else_unfolded.contains(place))
+                    {
+                        return Err(format!("the branches of {} do not join", stmt));
+                    }
+                    *unfolded = then_unfolded;
+                }
+                _ => {}
+            }
+            let mut bases = FieldBases(vec![]);
+            match stmt {
+                vir::Stmt::If(vir::If { guard, .. }) => vir::ExprWalker::walk(&mut bases, guard),
+                _ => vir::StmtWalker::walk(&mut bases, stmt),
+            }
+            if let Some(base) = bases.0.iter().find(|base| !unfolded.contains(base)) {
+                return Err(format!("{} accesses {} without permission", stmt, base));
+            }
+        }
+        Ok(())
+    }
+
+    /// The fold-unfold algorithm reconstructs the folds and unfolds of random
+    /// statements that are well-formed with respect to the permissions: the
+    /// resulting statements are well-formed too, and the algorithm accounts
+    /// for the same permissions as the statements.
+    #[test]
+    fn fold_unfold_restores_well_formedness() {
+        let predicates = predicates();
+        let old_exprs = FxHashMap::default();
+        for seed in 0..1000 {
+            let stmts = Generator::new(seed, locals()).stmts(10, 2);
+            let mut pctxt = PathCtxt::new(locals(), &predicates, &old_exprs);
+            for reference in references() {
+                let expr = vir::Expr::pred_permission(reference, PermAmount::Write).unwrap();
+                pctxt
+                    .apply_stmt(&vir::Stmt::Inhale(vir::Inhale { expr }))
+                    .unwrap();
+            }
+
+            let mut result = insert_fold_unfold(&stmts, &mut pctxt)
+                .unwrap_or_else(|error| panic!("seed {}: {}", seed, error));
+            let final_perms = references()
+                .into_iter()
+                .map(|reference| Perm::pred(reference, PermAmount::Write))
+                .collect();
+            let final_actions = pctxt
+                .obtain_permissions(final_perms)
+                .unwrap_or_else(|error| panic!("seed {}: {}", seed, error));
+            result.extend(final_actions.iter().map(Action::to_stmt));
+
+            let mut unfolded = vec![];
+            if let Err(error) = check_permissions(&result, &mut unfolded) {
+                panic!("seed {}: {}", seed, error);
+            }
+            assert!(unfolded.is_empty(), "seed {}", seed);
+            for reference in references() {
+                assert!(pctxt.state().contains_pred(&reference), "seed {}", seed);
+                let field = reference.field(Generator::ref_field());
+                assert!(!pctxt.state().contains_acc(&field), "seed {}", seed);
+            }
+        }
+    }
+}
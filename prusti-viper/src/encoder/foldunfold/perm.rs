@@ -6,7 +6,7 @@
 
 use log::trace;
 use rustc_hash::{FxHashMap, FxHashSet};
-use std::fmt;
+use std::{cmp::Ordering, fmt};
 use vir_crate::polymorphic::{Expr, PermAmount, Position, Type};
 
 /// An access or predicate permission to a place
@@ -209,10 +209,8 @@ fn place_perm_difference(
 ) -> FxHashMap<Expr, PermAmount> {
     for (place, right_perm_amount) in right.drain() {
         if let Some(left_perm_amount) = left.get(&place) {
-            match (*left_perm_amount, right_perm_amount) {
-                (PermAmount::Read, PermAmount::Read)
-                | (PermAmount::Read, PermAmount::Write)
-                | (PermAmount::Write, PermAmount::Write) => {
+            match left_perm_amount.partial_cmp(&right_perm_amount) {
+                Some(Ordering::Less) | Some(Ordering::Equal) => {
                     left.remove(&place);
                 }
                 _ => unreachable!("left={} right={}", left_perm_amount, right_perm_amount),
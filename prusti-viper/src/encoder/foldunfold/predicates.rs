@@ -0,0 +1,103 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::perm::Perm;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{cell::RefCell, fmt};
+use vir_crate::polymorphic::{self as vir, ExprWalker};
+
+/// The predicate definitions used by the fold-unfold algorithm.
+///
+/// The permissions computed from an expression depend only on the expression
+/// and on these definitions, so they are memoized here. This avoids
+/// recomputing them for the identical expressions that appear in many
+/// statements of large methods. Subexpressions are not memoized, because the
+/// key of each of them would take time linear in its size.
+pub struct Predicates {
+    definitions: FxHashMap<vir::Type, vir::Predicate>,
+    memo: RefCell<FxHashMap<MemoKey, FxHashSet<Perm>>>,
+    /// The identifiers of the expressions of the memo keys.
+    interner: RefCell<vir::ExprInterner>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(super) enum MemoKind {
+    Footprint,
+    RequiredStmtPermissions,
+}
+
+/// The equality of expressions ignores positions, but the computed
+/// permissions contain places with positions that are used for error
+/// reporting. Therefore, the key also contains all the positions of the
+/// expression.
+#[derive(PartialEq, Eq, Hash)]
+struct MemoKey {
+    kind: MemoKind,
+    expr: vir::ExprId,
+    positions: Vec<vir::Position>,
+}
+
+impl MemoKey {
+    fn new(kind: MemoKind, expr: &vir::Expr, interner: &mut vir::ExprInterner) -> Self {
+        struct PositionCollector {
+            positions: Vec<vir::Position>,
+        }
+        impl ExprWalker for PositionCollector {
+            fn walk(&mut self, expr: &vir::Expr) {
+                self.positions.push(expr.pos());
+                vir::default_walk_expr(self, expr);
+            }
+        }
+        let mut collector = PositionCollector {
+            positions: Vec::new(),
+        };
+        collector.walk(expr);
+        MemoKey {
+            kind,
+            expr: interner.intern(expr),
+            positions: collector.positions,
+        }
+    }
+}
+
+impl fmt::Debug for Predicates {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // The memo table and the interner are omitted, because they are only a cache.
+        fmt::Debug::fmt(&self.definitions, f)
+    }
+}
+
+impl Predicates {
+    pub fn new(definitions: FxHashMap<vir::Type, vir::Predicate>) -> Self {
+        Predicates {
+            definitions,
+            memo: RefCell::new(FxHashMap::default()),
+            interner: RefCell::new(vir::ExprInterner::new()),
+        }
+    }
+
+    pub fn get(&self, predicate_type: &vir::Type) -> Option<&vir::Predicate> {
+        self.definitions.get(predicate_type)
+    }
+
+    /// Returns the memoized permissions of `expr`, calling `compute` if they
+    /// are not known yet.
+    pub(super) fn memoize(
+        &self,
+        kind: MemoKind,
+        expr: &vir::Expr,
+        compute: impl FnOnce() -> FxHashSet<Perm>,
+    ) -> FxHashSet<Perm> {
+        let key = MemoKey::new(kind, expr, &mut self.interner.borrow_mut());
+        if let Some(perms) = self.memo.borrow().get(&key) {
+            return perms.clone();
+        }
+        // `compute` may recursively use the memo table, so it must not be borrowed here.
+        let perms = compute();
+        self.memo.borrow_mut().insert(key, perms.clone());
+        perms
+    }
+}
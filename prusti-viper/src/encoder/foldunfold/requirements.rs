@@ -4,7 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use super::Predicates;
+use super::{predicates::MemoKind, FoldUnfoldError, Predicates};
 use crate::encoder::foldunfold::{
     footprint::*,
     perm::{Perm::*, *},
@@ -16,7 +16,10 @@ use vir_crate::polymorphic::{self as vir, PermAmount};
 
 pub trait RequiredStmtPermissionsGetter {
     /// Returns the permissions required for the statement to be well-defined.
-    fn get_required_permissions(&self, predicates: &Predicates) -> FxHashSet<Perm>;
+    fn get_required_permissions(
+        &self,
+        predicates: &Predicates,
+    ) -> Result<FxHashSet<Perm>, FoldUnfoldError>;
 }
 
 pub trait RequiredExprPermissionsGetter {
@@ -33,7 +36,10 @@ pub trait RequiredExprPermissionsGetter {
 }
 
 impl<'a, A: RequiredStmtPermissionsGetter> RequiredStmtPermissionsGetter for &'a A {
-    fn get_required_permissions(&self, predicates: &Predicates) -> FxHashSet<Perm> {
+    fn get_required_permissions(
+        &self,
+        predicates: &Predicates,
+    ) -> Result<FxHashSet<Perm>, FoldUnfoldError> {
         (*self).get_required_permissions(predicates)
     }
 }
@@ -48,8 +54,11 @@ impl<'a, A: RequiredExprPermissionsGetter> RequiredExprPermissionsGetter for &'a
 }
 
 impl RequiredStmtPermissionsGetter for vir::Stmt {
-    fn get_required_permissions(&self, predicates: &Predicates) -> FxHashSet<Perm> {
-        match self {
+    fn get_required_permissions(
+        &self,
+        predicates: &Predicates,
+    ) -> Result<FxHashSet<Perm>, FoldUnfoldError> {
+        let perms = match self {
             &vir::Stmt::Comment(_) | &vir::Stmt::Label(_) => FxHashSet::default(),
 
             &vir::Stmt::Inhale(vir::Inhale { ref expr }) => perm_difference(
@@ -92,7 +101,12 @@ impl RequiredStmtPermissionsGetter for vir::Stmt {
                 ..
             }) => {
                 // Preconditions and postconditions are empty
-                assert!(arguments.is_empty());
+                if !arguments.is_empty() {
+                    return Err(FoldUnfoldError::UnsupportedStmt(
+                        self.clone(),
+                        vir::Position::default(),
+                    ));
+                }
                 FxHashSet::from_iter(
                     targets
                         .iter()
@@ -120,21 +134,21 @@ impl RequiredStmtPermissionsGetter for vir::Stmt {
                 ref enum_variant,
                 ..
             }) => {
-                assert_eq!(arguments.len(), 1);
                 let place = &arguments[0];
                 debug_assert!(place.is_place());
 
                 // We want to temporarily unfold place
                 let predicate_type = place.get_type();
-                let predicate = predicates.get(predicate_type).unwrap();
+                let predicate = predicates
+                    .get(predicate_type)
+                    .ok_or_else(|| FoldUnfoldError::MissingPredicate(predicate_type.clone()))?;
+                assert_eq!(arguments.len(), predicate.arity());
 
-                let pred_self_place: vir::Expr = predicate.self_place();
                 let places_in_pred: FxHashSet<Perm> = predicate
                     .get_body_footprint(enum_variant)
                     .into_iter()
                     .map(|perm| {
-                        // TODO polymorphic: add replace_place
-                        perm.map_place(|p| p.replace_place(&pred_self_place, place))
+                        perm.map_place(|p| predicate.instantiate(p, arguments))
                             .init_perm_amount(permission)
                     })
                     .collect();
@@ -147,9 +161,13 @@ impl RequiredStmtPermissionsGetter for vir::Stmt {
                 permission,
                 ..
             }) => {
-                assert_eq!(arguments.len(), 1);
                 let place = &arguments[0];
                 debug_assert!(place.is_place());
+                let predicate_type = place.get_type();
+                let predicate = predicates
+                    .get(predicate_type)
+                    .ok_or_else(|| FoldUnfoldError::MissingPredicate(predicate_type.clone()))?;
+                assert_eq!(arguments.len(), predicate.arity());
                 place
                     .get_required_stmt_permissions(predicates)
                     .into_iter()
@@ -194,14 +212,14 @@ impl RequiredStmtPermissionsGetter for vir::Stmt {
                 ref else_stmts,
             }) => {
                 let guard_reqs = guard.get_required_stmt_permissions(predicates);
-                let then_reqs: FxHashSet<_> = then_stmts
-                    .iter()
-                    .flat_map(|x| x.get_required_permissions(predicates))
-                    .collect();
-                let else_reqs: FxHashSet<_> = else_stmts
-                    .iter()
-                    .flat_map(|x| x.get_required_permissions(predicates))
-                    .collect();
+                let mut then_reqs = FxHashSet::default();
+                for stmt in then_stmts {
+                    then_reqs.extend(stmt.get_required_permissions(predicates)?);
+                }
+                let mut else_reqs = FxHashSet::default();
+                for stmt in else_stmts {
+                    else_reqs.extend(stmt.get_required_permissions(predicates)?);
+                }
                 let then_else_reqs = then_reqs.intersection(&else_reqs).cloned().collect();
                 guard_reqs.union(&then_else_reqs).cloned().collect()
             }
@@ -210,8 +228,14 @@ impl RequiredStmtPermissionsGetter for vir::Stmt {
                 base.get_required_stmt_permissions(predicates)
             }
 
-            ref x => unimplemented!("{}", x),
-        }
+            ref x => {
+                return Err(FoldUnfoldError::UnsupportedStmt(
+                    x.clone(),
+                    x.pos().copied().unwrap_or_default(),
+                ));
+            }
+        };
+        Ok(perms)
     }
 }
 
@@ -357,20 +381,19 @@ fn get_all_required_expr_permissions(
             variant,
             ..
         }) => {
-            assert_eq!(arguments.len(), 1);
             let place = &arguments[0];
             debug_assert!(place.is_place());
 
             // We want to temporarly unfold `place`
             let predicate_type = place.get_type();
             let predicate = preds.get(predicate_type).unwrap();
+            assert_eq!(arguments.len(), predicate.arity());
 
-            let pred_self_place: vir::Expr = predicate.self_place();
             let places_in_pred: FxHashSet<Perm> = predicate
                 .get_body_footprint(variant)
                 .into_iter()
                 .map(|aop| {
-                    aop.map_place(|p| p.replace_place(&pred_self_place, place))
+                    aop.map_place(|p| predicate.instantiate(p, arguments))
                         .update_perm_amount(*permission)
                 })
                 .collect();
@@ -395,17 +418,19 @@ fn get_all_required_expr_permissions(
 impl RequiredExprPermissionsGetter for vir::Expr {
     fn get_required_stmt_permissions(&self, preds: &Predicates) -> FxHashSet<Perm> {
         trace!("[enter] get_required_stmt_permissions(expr={})", self);
-        let reqs = get_all_required_expr_permissions(self, preds)
-            .0
-            .into_iter()
-            .filter(|p| p.is_pred())
-            .collect();
-        trace!(
-            "[exit] get_required_stmt_permissions(expr={}) {:#?}",
-            self,
+        preds.memoize(MemoKind::RequiredStmtPermissions, self, || {
+            let reqs = get_all_required_expr_permissions(self, preds)
+                .0
+                .into_iter()
+                .filter(|p| p.is_pred())
+                .collect();
+            trace!(
+                "[exit] get_required_stmt_permissions(expr={}) {:#?}",
+                self,
+                reqs
+            );
             reqs
-        );
-        reqs
+        })
     }
 
     fn get_required_expr_permissions(&self, predicates: &Predicates) -> FxHashSet<Perm> {
@@ -416,9 +441,10 @@ impl RequiredExprPermissionsGetter for vir::Expr {
                 permission,
                 ..
             }) => {
-                assert_eq!(arguments.len(), 1);
                 let place = &arguments[0];
                 debug_assert!(place.is_place());
+                let predicate = predicates.get(place.get_type()).unwrap();
+                assert_eq!(arguments.len(), predicate.arity());
                 vec![Pred(place.clone(), *permission)].into_iter().collect()
             }
 
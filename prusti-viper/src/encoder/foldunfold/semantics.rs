@@ -185,7 +185,6 @@ impl ApplyOnState for vir::Stmt {
                 ref enum_variant,
                 ..
             }) => {
-                assert_eq!(arguments.len(), 1);
                 let place = &arguments[0];
                 debug_assert!(place.is_place());
                 assert!(!state.contains_pred(place));
@@ -193,14 +192,16 @@ impl ApplyOnState for vir::Stmt {
 
                 // We want to fold place
                 let predicate_type = place.get_type();
-                let predicate = predicates.get(predicate_type).unwrap();
+                let predicate = predicates
+                    .get(predicate_type)
+                    .ok_or_else(|| FoldUnfoldError::MissingPredicate(predicate_type.clone()))?;
+                assert_eq!(arguments.len(), predicate.arity());
 
-                let pred_self_place: vir::Expr = predicate.self_place();
                 let places_in_pred: Vec<Perm> = predicate
                     .get_body_footprint(enum_variant)
                     .into_iter()
                     .map(|perm| {
-                        perm.map_place(|p| p.replace_place(&pred_self_place, place))
+                        perm.map_place(|p| predicate.instantiate(p, arguments))
                             .init_perm_amount(permission)
                     })
                     .collect();
@@ -221,7 +222,6 @@ impl ApplyOnState for vir::Stmt {
                 ref enum_variant,
                 ..
             }) => {
-                assert_eq!(arguments.len(), 1);
                 let self_place = &arguments[0];
                 debug_assert!(self_place.is_place());
                 assert!(state.contains_pred(self_place));
@@ -229,16 +229,18 @@ impl ApplyOnState for vir::Stmt {
 
                 // We want to unfold place
                 let predicate_type = self_place.get_type();
-                let predicate = predicates.get(predicate_type).unwrap();
+                let predicate = predicates
+                    .get(predicate_type)
+                    .ok_or_else(|| FoldUnfoldError::MissingPredicate(predicate_type.clone()))?;
+                assert_eq!(arguments.len(), predicate.arity());
 
-                let pred_self_place: vir::Expr = predicate.self_place();
                 let places_in_pred: Vec<_> = predicate
                     .get_body_footprint(enum_variant)
                     .into_iter()
                     .map(|perm| {
                         debug_assert_eq!(perm.get_perm_amount(), vir::PermAmount::Write);
                         // Scale permission
-                        perm.map_place(|place| place.replace_place(&pred_self_place, self_place))
+                        perm.map_place(|place| predicate.instantiate(place, arguments))
                             .update_perm_amount(permission)
                     })
                     .collect();
@@ -486,7 +488,9 @@ impl ApplyOnState for vir::Stmt {
                 } else {
                     trace!("Downcast {} to {}", enum_place, field);
                     let predicate_type = enum_place.get_type();
-                    let predicate = predicates.get(predicate_type).unwrap();
+                    let predicate = predicates
+                        .get(predicate_type)
+                        .ok_or_else(|| FoldUnfoldError::MissingPredicate(predicate_type.clone()))?;
                     if let vir::Predicate::Enum(enum_predicate) = predicate {
                         let discriminant_place = enum_place
                             .clone()
@@ -512,7 +516,12 @@ impl ApplyOnState for vir::Stmt {
                 }
             }
 
-            ref x => unimplemented!("{}", x),
+            ref x => {
+                return Err(FoldUnfoldError::UnsupportedStmt(
+                    x.clone(),
+                    x.pos().copied().unwrap_or_default(),
+                ));
+            }
         }
         Ok(())
     }
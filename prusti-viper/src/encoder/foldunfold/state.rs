@@ -59,8 +59,7 @@ impl State {
                     false
                 };
                 if !contains_parent_pred
-                    && self.pred[place] != PermAmount::Remaining
-                    && self.pred[place] != PermAmount::Read
+                    && self.pred[place] == PermAmount::Write
                     && !place.is_mir_reference()
                 {
                     trace!("place: {:?}", place);
@@ -77,7 +76,7 @@ impl State {
             if place.is_simple_place() && !place.is_local() {
                 let parent = place.clone().get_parent().unwrap();
                 if !self.contains_acc(&parent) {
-                    if self.acc[place] == PermAmount::Read {
+                    if matches!(self.acc[place], PermAmount::Read | PermAmount::Fraction(_)) {
                         let grand_parent = parent.clone().get_parent().unwrap();
                         if grand_parent.is_local() {
                             continue;
@@ -97,9 +96,11 @@ impl State {
                 if place.is_simple_place()
                     && other_place.is_simple_place()
                     && place.has_proper_prefix(other_place)
-                    && !((self.pred[place] == PermAmount::Read
-                        || self.pred[place] == PermAmount::Remaining)
-                        && self.pred[other_place] == PermAmount::Read)
+                    && !(self.pred[place] != PermAmount::Write
+                        && matches!(
+                            self.pred[other_place],
+                            PermAmount::Read | PermAmount::Fraction(_)
+                        ))
                 {
                     panic!(
                         "Consistency error: state has pred {} ({}), but also pred {} ({})",
@@ -356,7 +357,7 @@ impl State {
         if self.acc.contains_key(&place) {
             let new_perm = self.acc[&place].add(perm)?;
             assert!(
-                new_perm == PermAmount::Write || new_perm == PermAmount::Read,
+                new_perm.is_valid_for_specs(),
                 "Trying to inhale {} access permission, while there is already {}",
                 perm,
                 self.acc[&place]
@@ -387,7 +388,7 @@ impl State {
         if self.pred.contains_key(&place) {
             let new_perm = self.pred[&place].add(perm)?;
             assert!(
-                new_perm == PermAmount::Write || new_perm == PermAmount::Read,
+                new_perm.is_valid_for_specs(),
                 "Trying to inhale {} predicate permission, while there is already {}",
                 perm,
                 self.pred[&place]
@@ -0,0 +1,145 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A per-statement report of the fold-unfold algorithm, enabled by the
+//! `dump_foldunfold_state` configuration flag.
+
+use super::{action::Action, path_ctxt::PathCtxt, perm::Perm, FoldUnfoldError};
+use prusti_common::report;
+use std::fmt::Write;
+use vir_crate::polymorphic as vir;
+
+#[derive(Clone, Default)]
+pub(super) struct StateDump {
+    entries: Vec<Entry>,
+}
+
+#[derive(Clone)]
+struct Entry {
+    block_index: usize,
+    stmt: String,
+    acc: String,
+    pred: String,
+    required: Vec<String>,
+    /// The chosen actions, or the error raised while obtaining the required
+    /// permissions.
+    actions: Result<Vec<String>, String>,
+}
+
+impl StateDump {
+    /// Record the state before `stmt` and the permissions it requires.
+    pub(super) fn record(
+        &mut self,
+        block_index: vir::CfgBlockIndex,
+        stmt: &vir::Stmt,
+        pctxt: &PathCtxt,
+        required: &[Perm],
+    ) {
+        let mut required: Vec<_> = required.iter().map(|perm| perm.to_string()).collect();
+        required.sort();
+        self.entries.push(Entry {
+            block_index: block_index.block_index,
+            stmt: stmt.to_string(),
+            acc: pctxt.state().display_acc(),
+            pred: pctxt.state().display_pred(),
+            required,
+            actions: Ok(vec![]),
+        });
+    }
+
+    /// Record the actions chosen to obtain the permissions of the last
+    /// recorded statement.
+    pub(super) fn record_actions(&mut self, actions: &Result<Vec<Action>, FoldUnfoldError>) {
+        if let Some(entry) = self.entries.last_mut() {
+            entry.actions = match actions {
+                Ok(actions) => Ok(actions.iter().map(|action| action.to_string()).collect()),
+                Err(error) => Err(format!("{:?}", error)),
+            };
+        }
+    }
+
+    pub(super) fn report(&self, source_filename: &str, method_name: &str) {
+        report::log::report(
+            "foldunfold_state",
+            format!("{}.{}.html", source_filename, method_name),
+            self.to_html(method_name),
+        );
+    }
+
+    fn to_html(&self, method_name: &str) -> String {
+        let mut html = String::new();
+        writeln!(
+            html,
+            "<html>\n<head><title>Fold-unfold state of {0}</title></head>\n<body>\n\
+             <h1>Fold-unfold state of {0}</h1>\n<table border=\"1\">\n\
+             <tr><th>Block</th><th>Statement</th><th>Acc</th><th>Pred</th>\
+             <th>Required</th><th>Actions</th></tr>",
+            escape(method_name)
+        )
+        .unwrap();
+        for entry in &self.entries {
+            let actions = match &entry.actions {
+                Ok(actions) => escape(&actions.join("\n")),
+                Err(error) => format!("<b>Error:</b> {}", escape(error)),
+            };
+            writeln!(
+                html,
+                "<tr><td>{}</td><td><pre>{}</pre></td><td><pre>{}</pre></td>\
+                 <td><pre>{}</pre></td><td><pre>{}</pre></td><td><pre>{}</pre></td></tr>",
+                entry.block_index,
+                escape(&entry.stmt),
+                escape(&entry.acc),
+                escape(&entry.pred),
+                escape(&entry.required.join("\n")),
+                actions
+            )
+            .unwrap();
+        }
+        writeln!(html, "</table>\n</body>\n</html>").unwrap();
+        html
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The report has a row per statement, escapes the Viper syntax and
+    /// highlights the statements whose permissions could not be obtained.
+    #[test]
+    fn reports_statements_and_errors() {
+        let entry = |stmt: &str, actions| Entry {
+            block_index: 1,
+            stmt: stmt.to_string(),
+            acc: "acc(x.f, write)".to_string(),
+            pred: "P(x) && Q<T>(y)".to_string(),
+            required: vec!["acc(x.f.g, write)".to_string()],
+            actions,
+        };
+        let state_dump = StateDump {
+            entries: vec![
+                entry(
+                    "x.f.g := 1",
+                    Ok(vec!["unfold acc(P(x.f), write)".to_string()]),
+                ),
+                entry("assert x.f.g > 0", Err("FailedToObtain(x.f.g)".to_string())),
+            ],
+        };
+        let html = state_dump.to_html("m<T>");
+        assert!(html.contains("Fold-unfold state of m&lt;T&gt;"));
+        assert_eq!(html.matches("<tr><td>1</td>").count(), 2);
+        assert!(html.contains("P(x) &amp;&amp; Q&lt;T&gt;(y)"));
+        assert!(html.contains("assert x.f.g &gt; 0"));
+        assert!(html.contains("<pre>unfold acc(P(x.f), write)</pre>"));
+        assert!(html.contains("<b>Error:</b> FailedToObtain(x.f.g)"));
+    }
+}
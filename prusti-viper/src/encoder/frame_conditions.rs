@@ -0,0 +1,123 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Frame conditions of methods taking `&mut self`, inferred from their MIR
+//! when `AUTO_FRAME_CONDITIONS` is enabled. The fields of `self` that the
+//! method never modifies are added to its postcondition: they are checked
+//! when the method is verified and assumed at its call sites.
+
+use log::debug;
+use prusti_interface::environment::Environment;
+use rustc_hir::def_id::DefId;
+use rustc_middle::{
+    mir::{
+        self,
+        visit::{MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor},
+    },
+    ty,
+};
+
+#[derive(Debug)]
+pub struct FrameCondition {
+    /// The indices of the fields of primitive type of the struct behind
+    /// `self` that keep their value.
+    pub unmodified_fields: Vec<usize>,
+}
+
+/// Infer the frame condition of the local method `def_id`, if it takes
+/// `&mut self` with `Self` a struct. The caller is responsible for checking
+/// that the method is neither pure nor trusted.
+pub fn infer_frame_condition(env: &Environment, def_id: DefId) -> Option<FrameCondition> {
+    let tcx = env.tcx();
+    let local_def_id = def_id.as_local()?;
+    if !tcx.opt_associated_item(def_id)?.fn_has_self_parameter || !tcx.is_mir_available(def_id) {
+        return None;
+    }
+    let mir = env.local_mir(local_def_id, env.identity_substs(def_id));
+    let self_arg = mir::Local::new(1);
+    let (adt_def, substs) = match mir.local_decls[self_arg].ty.kind() {
+        ty::TyKind::Ref(_, target_ty, mir::Mutability::Mut) => match target_ty.kind() {
+            ty::TyKind::Adt(adt_def, substs) if adt_def.is_struct() => (*adt_def, *substs),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let modified_fields = find_modified_fields(&mir, self_arg)?;
+    let unmodified_fields: Vec<_> = adt_def
+        .non_enum_variant()
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(index, field)| {
+            !modified_fields.contains(index) && is_primitive(field.ty(tcx, substs))
+        })
+        .map(|(index, _)| index)
+        .collect();
+    debug!("Unmodified fields of {:?}: {:?}", def_id, unmodified_fields);
+    if unmodified_fields.is_empty() {
+        None
+    } else {
+        Some(FrameCondition { unmodified_fields })
+    }
+}
+
+fn is_primitive(ty: ty::Ty) -> bool {
+    ty.is_bool() || ty.is_integral() || ty.is_char()
+}
+
+/// The indices of the fields of the struct behind the mutable reference `arg`
+/// that may be modified, or `None` if the whole struct may be modified
+/// because the reference is moved or reborrowed.
+fn find_modified_fields(mir: &mir::Body, arg: mir::Local) -> Option<Vec<usize>> {
+    struct ModificationFinder {
+        arg: mir::Local,
+        modified_fields: Vec<usize>,
+        is_fully_modified: bool,
+    }
+
+    impl<'tcx> Visitor<'tcx> for ModificationFinder {
+        fn visit_place(
+            &mut self,
+            place: &mir::Place<'tcx>,
+            context: PlaceContext,
+            _location: mir::Location,
+        ) {
+            if place.local != self.arg {
+                return;
+            }
+            match context {
+                PlaceContext::MutatingUse(MutatingUseContext::Retag)
+                | PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy)
+                | PlaceContext::NonMutatingUse(NonMutatingUseContext::Inspect)
+                | PlaceContext::NonMutatingUse(NonMutatingUseContext::SharedBorrow)
+                | PlaceContext::NonMutatingUse(NonMutatingUseContext::ShallowBorrow)
+                | PlaceContext::NonUse(_) => {}
+                PlaceContext::NonMutatingUse(NonMutatingUseContext::Move)
+                    if !place.projection.is_empty() => {}
+                _ => match place.projection[..] {
+                    [mir::ProjectionElem::Deref, mir::ProjectionElem::Field(field, _), ..] => {
+                        if !self.modified_fields.contains(&field.index()) {
+                            self.modified_fields.push(field.index());
+                        }
+                    }
+                    _ => self.is_fully_modified = true,
+                },
+            }
+        }
+    }
+
+    let mut finder = ModificationFinder {
+        arg,
+        modified_fields: Vec::new(),
+        is_fully_modified: false,
+    };
+    finder.visit_body(mir);
+    if finder.is_fully_modified {
+        None
+    } else {
+        Some(finder.modified_fields)
+    }
+}
@@ -0,0 +1,92 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The built-in encoding of the ghost integers `prusti_contracts::Int` and
+//! `prusti_contracts::Nat`. Their values are encoded as unbounded
+//! mathematical integers, their arithmetic operations are encoded without
+//! overflow checks, and the conversions between them and machine integers
+//! are encoded as casts that check that the value fits into the target type.
+
+use rustc_middle::{ty, ty::subst::SubstsRef};
+use vir_crate::polymorphic as vir;
+
+/// A ghost integer type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum GhostInt {
+    /// `prusti_contracts::Int`
+    Int,
+    /// `prusti_contracts::Nat`, which is never negative.
+    Nat,
+}
+
+impl GhostInt {
+    /// The ghost integer type `ty`, if it is one.
+    pub(crate) fn from_ty<'tcx>(tcx: ty::TyCtxt<'tcx>, ty: ty::Ty<'tcx>) -> Option<Self> {
+        match ty.kind() {
+            ty::TyKind::Adt(adt_def, _) => match tcx.def_path_str(adt_def.did()).as_str() {
+                "prusti_contracts::Int" => Some(GhostInt::Int),
+                "prusti_contracts::Nat" => Some(GhostInt::Nat),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// A function on ghost integers with a built-in encoding.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum GhostIntFunction {
+    /// A conversion between a ghost integer and a machine integer or another
+    /// ghost integer, such as `Int::new` or `Int::to`.
+    Cast,
+    /// `Add::add`, `Sub::sub` or `Mul::mul`
+    BinOp(vir::BinaryOpKind),
+    /// `Neg::neg`
+    Neg,
+}
+
+impl GhostIntFunction {
+    /// The function on ghost integers at `path`, if its call with `substs`
+    /// has a built-in encoding.
+    pub(crate) fn from_call<'tcx>(
+        tcx: ty::TyCtxt<'tcx>,
+        path: &str,
+        substs: SubstsRef<'tcx>,
+    ) -> Option<Self> {
+        match path {
+            "prusti_contracts::Int::new"
+            | "prusti_contracts::Int::to"
+            | "prusti_contracts::Int::to_nat"
+            | "prusti_contracts::Nat::new"
+            | "prusti_contracts::Nat::to"
+            | "prusti_contracts::Nat::to_int" => return Some(GhostIntFunction::Cast),
+            _ => {}
+        }
+        let path = path
+            .strip_prefix("std::ops::")
+            .or_else(|| path.strip_prefix("core::ops::"))?;
+        let function = match path {
+            "Add::add" => GhostIntFunction::BinOp(vir::BinaryOpKind::Add),
+            "Sub::sub" => GhostIntFunction::BinOp(vir::BinaryOpKind::Sub),
+            "Mul::mul" => GhostIntFunction::BinOp(vir::BinaryOpKind::Mul),
+            "Neg::neg" => GhostIntFunction::Neg,
+            _ => return None,
+        };
+        GhostInt::from_ty(tcx, substs.type_at(0)).map(|_| function)
+    }
+
+    /// Encode the result of the call of an arithmetic operation on the
+    /// values `args`. The result of a subtraction of natural numbers still
+    /// needs to be cast to `Nat`.
+    pub(crate) fn encode_result(&self, args: Vec<vir::Expr>) -> vir::Expr {
+        let arg = |index: usize| args[index].clone();
+        match self {
+            GhostIntFunction::Cast => arg(0),
+            GhostIntFunction::BinOp(op_kind) => vir::Expr::bin_op(*op_kind, arg(0), arg(1)),
+            GhostIntFunction::Neg => vir::Expr::minus(arg(0)),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::encoder::{
     errors::{EncodingError, EncodingResult, SpannedEncodingResult, WithSpan},
+    ghost_int_encoder::GhostInt,
     high::lower::{predicates::IntoPredicates, IntoPolymorphic},
     mir::types::MirTypeEncoderInterface,
 };
@@ -312,6 +313,8 @@ impl<'v, 'tcx: 'v> HighTypeEncoderInterface<'tcx> for super::super::super::Encod
                 vir_poly::Expr::le_cmp(lower_bound.lower(self), var.clone()),
                 vir_poly::Expr::le_cmp(var.clone(), upper_bound.lower(self)),
             ]
+        } else if GhostInt::from_ty(self.env().tcx(), ty) == Some(GhostInt::Nat) {
+            vec![vir_poly::Expr::le_cmp(0.into(), var.clone())]
         } else {
             Vec::new()
         }
@@ -148,9 +148,9 @@ impl<'p, 'tcx: 'p> LoopEncoder<'p, 'tcx> {
             .collect();
 
         let loop_exit_blocks = loop_info.get_loop_exit_blocks(loop_head);
-        let before_invariant_block: BasicBlockIndex = loop_body
+        let spec_blocks_attachments: Vec<BasicBlockIndex> = loop_body
             .iter()
-            .find(|&&bb| {
+            .filter(|&&bb| {
                 loop_info.get_loop_depth(bb) == loop_depth
                     && self.mir()[bb].terminator().successors().any(|&succ_bb| {
                         self.procedure.is_reachable_block(succ_bb)
@@ -158,6 +158,15 @@ impl<'p, 'tcx: 'p> LoopEncoder<'p, 'tcx> {
                     })
             })
             .cloned()
+            .collect();
+        // The loop invariant is the first group of specifications on the path
+        // that every iteration takes. The other ones (e.g. before a
+        // `continue`) are checked where they are written.
+        let before_invariant_block: BasicBlockIndex = spec_blocks_attachments
+            .iter()
+            .find(|&&bb| !loop_info.is_conditional_branch(loop_head, bb))
+            .or_else(|| spec_blocks_attachments.first())
+            .cloned()
             .unwrap_or_else(|| loop_exit_blocks.get(0).cloned().unwrap_or(loop_head));
 
         if loop_info.is_conditional_branch(loop_head, before_invariant_block) {
@@ -63,7 +63,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> AddressesInterface for Lowerer<'p, 'v, 'tcx> {
         assert!(place.is_place());
         let result = match place {
             vir_mid::Expression::Local(local) => self.root_address(local)?,
-            vir_mid::Expression::LabelledOld(_) => unimplemented!(),
+            vir_mid::Expression::LabelledOld(old) => self.extract_root_address(&old.base)?,
             _ => self.extract_root_address(place.get_parent_ref().unwrap())?,
         };
         Ok(result)
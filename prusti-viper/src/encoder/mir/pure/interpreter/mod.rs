@@ -17,7 +17,7 @@ use crate::encoder::{
         specifications::SpecificationsInterface,
         types::MirTypeEncoderInterface,
     },
-    mir_encoder::MirEncoder,
+    mir_encoder::{MirEncoder, LOOP_ENTRY_LABEL, PRECONDITION_LABEL, WAND_LHS_LABEL},
     mir_interpreter::BackwardMirInterpreter,
     Encoder,
 };
@@ -436,12 +436,37 @@ impl<'p, 'v: 'p, 'tcx: 'v> ExpressionBackwardInterpreter<'p, 'v, 'tcx> {
                     .with_span(span)?;
                 match full_func_proc_name {
                     "prusti_contracts::old" => {
-                        unimplemented!();
-                        // self.encode_call_old()?
+                        trace!("Encoding old expression {:?}", args[0]);
+                        assert_eq!(args.len(), 1);
+                        let encoded_rhs = vir_high::Expression::labelled_old_no_pos(
+                            PRECONDITION_LABEL.to_string(),
+                            encoded_args.pop().unwrap(),
+                        );
+                        let mut state = states[target_block].clone();
+                        state.substitute_value(&encoded_lhs, encoded_rhs);
+                        state
+                    }
+                    "prusti_contracts::on_entry" => {
+                        trace!("Encoding on_entry expression {:?}", args[0]);
+                        assert_eq!(args.len(), 1);
+                        let encoded_rhs = vir_high::Expression::labelled_old_no_pos(
+                            LOOP_ENTRY_LABEL.to_string(),
+                            encoded_args.pop().unwrap(),
+                        );
+                        let mut state = states[target_block].clone();
+                        state.substitute_value(&encoded_lhs, encoded_rhs);
+                        state
                     }
                     "prusti_contracts::before_expiry" => {
-                        // self.encode_call_before_expiry()?
-                        unimplemented!();
+                        trace!("Encoding before_expiry expression {:?}", args[0]);
+                        assert_eq!(args.len(), 1);
+                        let encoded_rhs = vir_high::Expression::labelled_old_no_pos(
+                            WAND_LHS_LABEL.to_string(),
+                            encoded_args.pop().unwrap(),
+                        );
+                        let mut state = states[target_block].clone();
+                        state.substitute_value(&encoded_lhs, encoded_rhs);
+                        state
                     }
                     "std::cmp::PartialEq::eq" | "core::cmp::PartialEq::eq"
                         if self.has_structural_eq_impl(&args[0]).with_span(span)? =>
@@ -496,6 +521,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> ExpressionBackwardInterpreter<'p, 'v, 'tcx> {
                     // TODO: check we are in a spec function
                     "prusti_contracts::exists"
                     | "prusti_contracts::forall"
+                    | "prusti_contracts::sum"
+                    | "prusti_contracts::count"
+                    | "prusti_contracts::max"
                     | "prusti_contracts::specification_entailment"
                     | "prusti_contracts::call_description" => {
                         let expr = self.encoder.encode_prusti_operation_high(
@@ -19,7 +19,12 @@ use crate::encoder::{
     Encoder,
 };
 use log::{debug, trace};
-use prusti_common::{config, vir::optimizations::functions::Simplifier, vir_local};
+use prusti_common::{
+    config,
+    vir::optimizations::functions::{unroll_recursive_calls, Simplifier},
+    vir_local,
+};
+use prusti_interface::utils::read_prusti_attr;
 
 use rustc_hir as hir;
 use rustc_hir::def_id::{DefId, LocalDefId};
@@ -146,6 +151,14 @@ impl<'p, 'v: 'p, 'tcx: 'v> PureFunctionEncoder<'p, 'v, 'tcx> {
         self.encode_function_given_body(Some(body_expr))
     }
 
+    /// The number of times the definition of the function is unfolded at its
+    /// calls, from `#[pure(fuel = ...)]` or the `PURE_FUNCTION_FUEL` flag.
+    pub fn fuel(&self) -> u64 {
+        read_prusti_attr("fuel", self.encoder.env().tcx().get_attrs(self.proc_def_id))
+            .map(|fuel| fuel.parse().unwrap())
+            .unwrap_or_else(config::pure_function_fuel)
+    }
+
     pub fn encode_bodyless_function(&self) -> SpannedEncodingResult<vir::Function> {
         let function_name = self.encode_function_name();
         debug!("Encode trusted (bodyless) pure function {}", function_name);
@@ -321,6 +334,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> PureFunctionEncoder<'p, 'v, 'tcx> {
             function.body = Some(body)
         }
 
+        // The definition of a `#[never_inline_spec]` function is only known
+        // through its axiom, so its recursive calls are not unrolled either.
+        if !self.encoder.env().has_prusti_attribute(self.proc_def_id, "never_inline_spec") {
+            function = unroll_recursive_calls(function, self.fuel());
+        }
+
         // Add folding/unfolding
         Ok(function)
     }
@@ -2,18 +2,29 @@
 
 use super::encoder::{FunctionCallInfo, FunctionCallInfoHigh, PureFunctionEncoder};
 use crate::encoder::{
-    errors::{SpannedEncodingResult, WithSpan},
+    errors::{SpannedEncodingError, SpannedEncodingResult, WithSpan},
     mir::{generics::MirGenericsEncoderInterface, specifications::SpecificationsInterface},
     snapshot::interface::SnapshotEncoderInterface,
     stub_function_encoder::StubFunctionEncoder,
 };
 use log::{debug, trace};
-use prusti_interface::data::ProcedureDefId;
+use prusti_interface::{data::ProcedureDefId, environment::Environment};
 use rustc_hash::{FxHashMap, FxHashSet};
-use rustc_middle::ty::subst::SubstsRef;
+use rustc_middle::{
+    mir,
+    ty::{
+        self,
+        subst::{Subst, SubstsRef},
+        TypeFoldable,
+    },
+};
 
 use std::cell::RefCell;
-use vir_crate::{common::identifier::WithIdentifier, high as vir_high, polymorphic as vir_poly};
+use vir_crate::{
+    common::identifier::WithIdentifier,
+    high as vir_high,
+    polymorphic::{self as vir_poly, ExprIterator},
+};
 
 /// Key of stored call infos, consisting of the DefId of the called function
 /// and (the VIR encoding of) the type substitutions applied to it. This means
@@ -102,6 +113,16 @@ pub(crate) trait PureFunctionEncoderInterface<'v, 'tcx> {
         substs: SubstsRef<'tcx>,
     ) -> SpannedEncodingResult<()>;
 
+    /// Encode the definition of the pure function as an assertion
+    /// `forall args :: { f(args) } pres ==> f(args) == body`. This is used to
+    /// expose the body of an `#[opaque]` function with `reveal!`.
+    fn encode_pure_function_definition(
+        &self,
+        proc_def_id: ProcedureDefId,
+        parent_def_id: ProcedureDefId,
+        substs: SubstsRef<'tcx>,
+    ) -> SpannedEncodingResult<vir_poly::Expr>;
+
     /// Ensure that the function with the specified identifier is encoded.
     fn ensure_pure_function_encoded(
         &self,
@@ -286,6 +307,32 @@ impl<'v, 'tcx: 'v> PureFunctionEncoderInterface<'v, 'tcx>
                         )
                     } else if self.is_trusted(proc_def_id) {
                         (pure_function_encoder.encode_bodyless_function()?, false)
+                    } else if self.env().has_prusti_attribute(proc_def_id, "opaque")
+                        || pure_function_encoder.fuel() == 0
+                    {
+                        // The definition of an opaque function is only assumed
+                        // where the function is revealed; see
+                        // `encode_pure_function_definition`. The one of a
+                        // function without fuel is never unfolded.
+                        (pure_function_encoder.encode_bodyless_function()?, false)
+                    } else if substs.has_param_types_or_consts()
+                        && calls_unresolved_trait_method(
+                            self.env(),
+                            proc_def_id,
+                            &mir,
+                            substs,
+                            |def_id| self.is_pure(def_id),
+                        )
+                    {
+                        // The body of this generic instantiation can only be
+                        // encoded once the type parameters are known, so only
+                        // its contract is known. The concrete instantiations
+                        // keep their body.
+                        debug!(
+                            "Encoding generic instantiation of {:?} without body",
+                            proc_def_id
+                        );
+                        (pure_function_encoder.encode_bodyless_function()?, false)
                     } else {
                         let function = pure_function_encoder.encode_function()?;
                         // Test the new encoding.
@@ -309,6 +356,7 @@ impl<'v, 'tcx: 'v> PureFunctionEncoderInterface<'v, 'tcx>
                     .patch_snapshots_function(function)
                     .with_span(mir_span)?;
 
+                self.record_spec_inlining(proc_def_id, &function.name);
                 self.log_vir_program_before_viper(function.to_string());
                 Ok(self.insert_function(function))
             })(
@@ -343,6 +391,66 @@ impl<'v, 'tcx: 'v> PureFunctionEncoderInterface<'v, 'tcx>
         Ok(())
     }
 
+    fn encode_pure_function_definition(
+        &self,
+        proc_def_id: ProcedureDefId,
+        parent_def_id: ProcedureDefId,
+        substs: SubstsRef<'tcx>,
+    ) -> SpannedEncodingResult<vir_poly::Expr> {
+        // Make sure that the definition collector picks up the function.
+        self.encode_pure_function_use(proc_def_id, parent_def_id, substs)?;
+
+        let wrapper_def_id = self.get_wrapper_def_id(proc_def_id);
+        let mir = self.env().local_mir(wrapper_def_id.expect_local(), substs);
+        let mir_span = mir.span;
+        let pure_function_encoder = PureFunctionEncoder::new(
+            self,
+            proc_def_id,
+            &mir,
+            PureEncodingContext::Code,
+            proc_def_id,
+            substs,
+        );
+        // No mirrors are encoded, because their axioms would expose the body.
+        let function = pure_function_encoder.encode_function()?;
+        let function = self
+            .patch_snapshots_function(function)
+            .with_span(mir_span)?;
+        if !function.pres.iter().all(|pre| pre.is_pure()) {
+            return Err(SpannedEncodingError::unsupported(
+                "the precondition of a revealed pure function cannot contain permissions",
+                mir_span,
+            ));
+        }
+
+        let call = vir_poly::Expr::func_app(
+            function.name,
+            function.type_arguments,
+            function
+                .formal_args
+                .iter()
+                .cloned()
+                .map(vir_poly::Expr::local)
+                .collect(),
+            function.formal_args.clone(),
+            function.return_type,
+            vir_poly::Position::default(),
+        );
+        let definition = vir_poly::Expr::implies(
+            function.pres.into_iter().conjoin(),
+            vir_poly::Expr::eq_cmp(call.clone(), function.body.unwrap()),
+        );
+        if function.formal_args.is_empty() {
+            Ok(definition)
+        } else {
+            Ok(vir_poly::Expr::forall(
+                function.formal_args,
+                vec![vir_poly::Trigger::new(vec![call])],
+                definition,
+            ))
+        }
+    }
+
     fn ensure_pure_function_encoded(
         &self,
         identifier: &vir_poly::FunctionIdentifier,
@@ -377,6 +485,7 @@ impl<'v, 'tcx: 'v> PureFunctionEncoderInterface<'v, 'tcx>
             "procedure is not marked as pure: {:?}",
             proc_def_id
         );
+        self.register_contract_use(proc_def_id);
 
         let mir_span = self.env().tcx().def_span(proc_def_id);
         let substs_key = self
@@ -444,6 +553,7 @@ impl<'v, 'tcx: 'v> PureFunctionEncoderInterface<'v, 'tcx>
             "procedure is not marked as pure: {:?}",
             proc_def_id
         );
+        self.register_contract_use(proc_def_id);
 
         let mir_span = self.env().tcx().def_span(proc_def_id);
         let substs_key = self
@@ -528,3 +638,36 @@ impl<'v, 'tcx: 'v> PureFunctionEncoderInterface<'v, 'tcx>
         Ok(())
     }
 }
+
+/// Whether the body `mir` of a pure function, instantiated with `substs`,
+/// calls a trait method that is not pure and that can only be resolved to an
+/// implementation once the type parameters are known, e.g. `==` on a
+/// `T: PartialEq`.
+fn calls_unresolved_trait_method<'tcx>(
+    env: &Environment<'tcx>,
+    proc_def_id: ProcedureDefId,
+    mir: &mir::Body<'tcx>,
+    substs: SubstsRef<'tcx>,
+    is_pure: impl Fn(ProcedureDefId) -> bool,
+) -> bool {
+    let tcx = env.tcx();
+    mir.basic_blocks().iter().any(|block| {
+        if let mir::TerminatorKind::Call {
+            func: mir::Operand::Constant(func),
+            ..
+        } = &block.terminator().kind
+        {
+            if let ty::TyKind::FnDef(called_def_id, call_substs) = func.ty().kind() {
+                let call_substs = call_substs.subst(tcx, substs);
+                if tcx.trait_of_item(*called_def_id).is_some()
+                    && call_substs.has_param_types_or_consts()
+                {
+                    let (resolved_def_id, _) =
+                        env.resolve_method_call(proc_def_id, *called_def_id, call_substs);
+                    return resolved_def_id == *called_def_id && !is_pure(resolved_def_id);
+                }
+            }
+        }
+        false
+    })
+}
@@ -1,10 +1,12 @@
 use super::interface::PureFunctionEncoderInterface;
 use crate::encoder::{
     builtin_encoder::BuiltinFunctionKind,
+    cmp_encoder::{encode_ordering_snapshot, variant_discriminant, CmpFunction, CmpResult},
     errors::{
-        EncodingError, EncodingResult, ErrorCtxt, SpannedEncodingError, SpannedEncodingResult,
-        WithSpan,
+        EncodingError, EncodingResult, ErrorCtxt, PanicCause, SpannedEncodingError,
+        SpannedEncodingResult, WithSpan,
     },
+    ghost_int_encoder::GhostIntFunction,
     high::{
         builtin_functions::HighBuiltinFunctionEncoderInterface,
         generics::HighGenericsEncoderInterface, types::HighTypeEncoderInterface,
@@ -12,9 +14,12 @@ use crate::encoder::{
     mir::{
         pure::{specifications::SpecificationEncoderInterface, PureEncodingContext},
         specifications::SpecificationsInterface,
-        types::MirTypeEncoderInterface,
+        types::{compute_discriminant_values, MirTypeEncoderInterface},
+    },
+    mir_encoder::{
+        MirEncoder, PlaceEncoder, PlaceEncoding, LOOP_ENTRY_LABEL, PRECONDITION_LABEL,
+        WAND_LHS_LABEL,
     },
-    mir_encoder::{MirEncoder, PlaceEncoder, PlaceEncoding, PRECONDITION_LABEL, WAND_LHS_LABEL},
     mir_interpreter::{BackwardMirInterpreter, ExprBackwardInterpreterState},
     snapshot::interface::SnapshotEncoderInterface,
     Encoder,
@@ -30,8 +35,9 @@ use rustc_middle::{
     ty::subst::{Subst, SubstsRef},
 };
 
+use rustc_span::Span;
 use std::{convert::TryInto, mem};
-use vir_crate::polymorphic::{self as vir};
+use vir_crate::polymorphic::{self as vir, ExprIterator};
 
 pub(crate) struct PureFunctionBackwardInterpreter<'p, 'v: 'p, 'tcx: 'v> {
     encoder: &'p Encoder<'v, 'tcx>,
@@ -188,6 +194,113 @@ impl<'p, 'v: 'p, 'tcx: 'v> PureFunctionBackwardInterpreter<'p, 'v, 'tcx> {
             }
         })
     }
+
+    /// The right-hand side of the assignment to the temporary `local`, if
+    /// there is one.
+    fn find_assigned_rvalue(&self, local: mir::Local) -> Option<&'p mir::Rvalue<'tcx>> {
+        self.mir
+            .basic_blocks()
+            .iter()
+            .flat_map(|block| &block.statements)
+            .find_map(|stmt| match &stmt.kind {
+                mir::StatementKind::Assign(box (lhs, rvalue)) if lhs.as_local() == Some(local) => {
+                    Some(rvalue)
+                }
+                _ => None,
+            })
+    }
+
+    /// The place borrowed by the temporary reference `operand`.
+    fn find_borrowed_place(&self, operand: &mir::Operand<'tcx>) -> Option<mir::Place<'tcx>> {
+        match self.find_assigned_rvalue(operand.place()?.as_local()?)? {
+            mir::Rvalue::Ref(_, _, place) => Some(*place),
+            _ => None,
+        }
+    }
+
+    /// The indices of the fields borrowed in the tuple `except` of a call of
+    /// `prusti_contracts::unchanged`, or `None` if one of the borrowed places
+    /// is not a field of the struct borrowed by `value`.
+    fn find_unchanged_fields_exceptions(
+        &self,
+        value: &mir::Operand<'tcx>,
+        except: &mir::Operand<'tcx>,
+    ) -> Option<Vec<usize>> {
+        let except_local = match except.place() {
+            Some(place) => place.as_local()?,
+            // `unchanged!` without `except` passes the constant `()`.
+            None => return Some(Vec::new()),
+        };
+        let struct_place = self.find_borrowed_place(value)?;
+        let operands = match self.find_assigned_rvalue(except_local)? {
+            mir::Rvalue::Aggregate(box mir::AggregateKind::Tuple, operands) => operands,
+            _ => return None,
+        };
+        operands
+            .iter()
+            .map(|operand| {
+                let place = self.find_borrowed_place(operand)?;
+                match place.projection.split_last()? {
+                    (mir::ProjectionElem::Field(field, _), base)
+                        if place.local == struct_place.local
+                            && base == &struct_place.projection[..] =>
+                    {
+                        Some(field.index())
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Generate a function call that leaves the expression undefined.
+    fn unreachable_expr(&self, pos: vir::Position) -> EncodingResult<vir::Expr> {
+        self.encoder
+            .encode_snapshot_type(self.mir.return_ty())
+            .map(|encoded_type| {
+                let (function_name, type_arguments) = self
+                    .encoder
+                    .encode_builtin_function_use(BuiltinFunctionKind::Unreachable(
+                        encoded_type.clone(),
+                    ));
+                vir::Expr::func_app(
+                    function_name,
+                    type_arguments,
+                    vec![],
+                    vec![],
+                    encoded_type,
+                    pos,
+                )
+            })
+    }
+
+    /// Require that the `bounds` on the result of a ghost integer operation
+    /// hold: in executable code, a failure is reported with `error_ctxt`,
+    /// while in an assertion it makes the assertion false.
+    fn encode_ghost_int_obligation(
+        &self,
+        state: ExprBackwardInterpreterState,
+        bounds: Vec<vir::Expr>,
+        error_ctxt: ErrorCtxt,
+        span: Span,
+    ) -> SpannedEncodingResult<ExprBackwardInterpreterState> {
+        let precondition = bounds.into_iter().conjoin();
+        let failure_encoding = match self.pure_encoding_context {
+            PureEncodingContext::Trigger => return Ok(state),
+            PureEncodingContext::Assertion => false.into(),
+            PureEncodingContext::Code => {
+                let pos = self.encoder.error_manager().register_error(
+                    span,
+                    error_ctxt,
+                    self.caller_def_id,
+                );
+                self.unreachable_expr(pos).with_span(span)?
+            }
+        };
+        Ok(ExprBackwardInterpreterState::new(state.into_expr().map(
+            |expr| vir::Expr::ite(precondition, expr, failure_encoding),
+        )))
+    }
 }
 
 impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
@@ -207,26 +320,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
         let span = term.source_info.span;
         let location = self.mir.terminator_loc(bb);
 
-        // Generate a function call that leaves the expression undefined.
-        let unreachable_expr = |pos| {
-            self.encoder
-                .encode_snapshot_type(self.mir.return_ty())
-                .map(|encoded_type| {
-                    let (function_name, type_arguments) =
-                        self.encoder
-                            .encode_builtin_function_use(BuiltinFunctionKind::Unreachable(
-                                encoded_type.clone(),
-                            ));
-                    vir::Expr::func_app(
-                        function_name,
-                        type_arguments,
-                        vec![],
-                        vec![],
-                        encoded_type,
-                        pos,
-                    )
-                })
-        };
+        let unreachable_expr = |pos| self.unreachable_expr(pos);
 
         // Generate a function call that leaves the expression undefined.
         let undef_expr = |pos| {
@@ -415,6 +509,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
 
                     // compose substitutions
                     let composed_substs = call_substs.subst(self.encoder.env().tcx(), self.substs);
+                    let cmp_function = CmpFunction::from_call(tcx, full_func_proc_name, composed_substs);
+                    let ghost_int_function =
+                        GhostIntFunction::from_call(tcx, full_func_proc_name, composed_substs);
 
                     let state = if destination.is_some() {
                         let (ref lhs_place, target_block) = destination.as_ref().unwrap();
@@ -441,6 +538,20 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                                 state
                             }
 
+                            "prusti_contracts::on_entry" => {
+                                trace!("Encoding on_entry expression {:?}", args[0]);
+                                assert_eq!(args.len(), 1);
+                                // The label is replaced with the one of the
+                                // loop when encoding the loop invariant.
+                                let encoded_rhs = self.mir_encoder.encode_old_expr(
+                                    vir::Expr::snap_app(encoded_args[0].clone()),
+                                    LOOP_ENTRY_LABEL,
+                                );
+                                let mut state = states[target_block].clone();
+                                state.substitute_value(&encoded_lhs, encoded_rhs);
+                                state
+                            }
+
                             "prusti_contracts::before_expiry" => {
                                 trace!("Encoding before_expiry expression {:?}", args[0]);
                                 assert_eq!(args.len(), 1);
@@ -573,11 +684,121 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                                 state
                             }
 
+                            "prusti_contracts::discriminant_of" => {
+                                let enum_ty = composed_substs.type_at(0);
+                                let adt_def = match enum_ty.kind() {
+                                    ty::TyKind::Adt(adt_def, _) if adt_def.is_enum() => *adt_def,
+                                    _ => {
+                                        return Err(SpannedEncodingError::incorrect(
+                                            format!(
+                                                "`discriminant_of` can only be applied to enums, not to {}",
+                                                enum_ty
+                                            ),
+                                            span,
+                                        ));
+                                    }
+                                };
+                                let discr_values = compute_discriminant_values(adt_def, tcx);
+                                // The index of the variant, which does not depend
+                                // on the values of the discriminants.
+                                let variant_index = if discr_values.len() <= 1 {
+                                    0usize.into()
+                                } else {
+                                    let deref_field = self
+                                        .encoder
+                                        .encode_dereference_field(enum_ty)
+                                        .with_span(span)?;
+                                    let discriminant = encoded_args[0]
+                                        .clone()
+                                        .field(deref_field)
+                                        .field(self.encoder.encode_discriminant_field());
+                                    let last_index = discr_values.len() - 1;
+                                    discr_values[..last_index].iter().enumerate().rev().fold(
+                                        last_index.into(),
+                                        |other_variants, (index, value)| {
+                                            vir::Expr::ite(
+                                                vir::Expr::eq_cmp(
+                                                    discriminant.clone(),
+                                                    (*value).into(),
+                                                ),
+                                                index.into(),
+                                                other_variants,
+                                            )
+                                        },
+                                    )
+                                };
+                                let mut state = states[target_block].clone();
+                                state.substitute_value(&encoded_lhs, variant_index);
+                                state
+                            }
+
+                            "prusti_contracts::unchanged" => {
+                                let struct_ty = composed_substs.type_at(0);
+                                let (adt_def, adt_substs) = match struct_ty.kind() {
+                                    ty::TyKind::Adt(adt_def, adt_substs) if adt_def.is_struct() => {
+                                        (*adt_def, *adt_substs)
+                                    }
+                                    _ => {
+                                        return Err(SpannedEncodingError::incorrect(
+                                            format!(
+                                                "`unchanged!` can only be applied to references to structs, not to references to {}",
+                                                struct_ty
+                                            ),
+                                            span,
+                                        ));
+                                    }
+                                };
+                                let changed_fields = self
+                                    .find_unchanged_fields_exceptions(&args[0], &args[1])
+                                    .ok_or_else(|| {
+                                        SpannedEncodingError::incorrect(
+                                            "the places listed after `except` in `unchanged!` must be fields of the struct",
+                                            span,
+                                        )
+                                    })?;
+                                let deref_field = self
+                                    .encoder
+                                    .encode_dereference_field(struct_ty)
+                                    .with_span(span)?;
+                                let encoded_struct = encoded_args[0].clone().field(deref_field);
+                                let mut equalities = vec![];
+                                for (index, field) in
+                                    adt_def.non_enum_variant().fields.iter().enumerate()
+                                {
+                                    if changed_fields.contains(&index) {
+                                        continue;
+                                    }
+                                    let encoded_field = self
+                                        .encoder
+                                        .encode_struct_field(
+                                            field.ident(tcx).as_str(),
+                                            field.ty(tcx, adt_substs),
+                                        )
+                                        .with_span(span)?;
+                                    let current = vir::Expr::snap_app(
+                                        encoded_struct.clone().field(encoded_field),
+                                    );
+                                    let old = self
+                                        .mir_encoder
+                                        .encode_old_expr(current.clone(), PRECONDITION_LABEL);
+                                    equalities.push(vir::Expr::eq_cmp(current, old));
+                                }
+                                let mut state = states[target_block].clone();
+                                state.substitute_value(
+                                    &encoded_lhs,
+                                    equalities.into_iter().conjoin(),
+                                );
+                                state
+                            }
+
                             // Prusti-specific syntax
                             // TODO: check we are in a spec function
                             "prusti_contracts::implication"
                             | "prusti_contracts::exists"
                             | "prusti_contracts::forall"
+                            | "prusti_contracts::sum"
+                            | "prusti_contracts::count"
+                            | "prusti_contracts::max"
                             | "prusti_contracts::specification_entailment"
                             | "prusti_contracts::call_description" => {
                                 let expr = self.encoder.encode_prusti_operation(
@@ -592,12 +813,179 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                                 state
                             }
 
+                            // comparison of integers or characters, or method of `Ordering`
+                            _ if cmp_function.is_some() => {
+                                let cmp_function = cmp_function.unwrap();
+                                let cmp_args = args
+                                    .iter()
+                                    .zip(encoded_args)
+                                    .map(|(arg, encoded_arg)| {
+                                        if self.mir_encoder.get_operand_ty(arg).is_enum() {
+                                            encoded_arg
+                                                .field(self.encoder.encode_discriminant_field())
+                                        } else {
+                                            vir::Expr::snap_app(encoded_arg)
+                                        }
+                                    })
+                                    .collect::<Vec<_>>();
+                                let mut state = states[target_block].clone();
+                                match cmp_function.encode_result(cmp_args.clone()) {
+                                    CmpResult::Value(expr) => {
+                                        state.substitute_value(&encoded_lhs, expr);
+                                    }
+                                    CmpResult::Ordering(discriminant) => {
+                                        let ordering = encode_ordering_snapshot(
+                                            self.encoder,
+                                            ty,
+                                            discriminant.clone(),
+                                        )
+                                        .with_span(span)?;
+                                        state.substitute_value(
+                                            &encoded_lhs
+                                                .clone()
+                                                .field(self.encoder.encode_discriminant_field()),
+                                            discriminant,
+                                        );
+                                        state.substitute_value(&encoded_lhs, ordering);
+                                    }
+                                    CmpResult::SomeOrdering(discriminant) => {
+                                        let ordering_ty = match ty.kind() {
+                                            ty::TyKind::Adt(_, substs) => substs.type_at(0),
+                                            _ => unreachable!(),
+                                        };
+                                        let ordering = encode_ordering_snapshot(
+                                            self.encoder,
+                                            ordering_ty,
+                                            discriminant.clone(),
+                                        )
+                                        .with_span(span)?;
+                                        let ordering_field = self
+                                            .encoder
+                                            .encode_struct_field("0", ordering_ty)
+                                            .with_span(span)?;
+                                        let encoded_ordering = encoded_lhs
+                                            .clone()
+                                            .variant("Some")
+                                            .field(ordering_field);
+                                        state.substitute_value(
+                                            &encoded_lhs
+                                                .clone()
+                                                .field(self.encoder.encode_discriminant_field()),
+                                            variant_discriminant(self.encoder, ty, 1).into(),
+                                        );
+                                        state.substitute_value(
+                                            &encoded_ordering
+                                                .clone()
+                                                .field(self.encoder.encode_discriminant_field()),
+                                            discriminant,
+                                        );
+                                        state.substitute_value(&encoded_ordering, ordering.clone());
+                                        let option = self
+                                            .encoder
+                                            .encode_snapshot(ty, Some(1), vec![ordering])
+                                            .with_span(span)?;
+                                        state.substitute_value(&encoded_lhs, option);
+                                    }
+                                }
+                                if let Some(precondition) = cmp_function.precondition(&cmp_args) {
+                                    // The call panics if its precondition does not hold.
+                                    state = match self.pure_encoding_context {
+                                        PureEncodingContext::Trigger => state,
+                                        PureEncodingContext::Assertion => {
+                                            ExprBackwardInterpreterState::new(
+                                                state.into_expr().map(|expr| {
+                                                    vir::Expr::ite(precondition, expr, false.into())
+                                                }),
+                                            )
+                                        }
+                                        PureEncodingContext::Code => {
+                                            let pos = self.encoder.error_manager().register_error(
+                                                span,
+                                                ErrorCtxt::PanicInPureFunction(PanicCause::Generic),
+                                                self.caller_def_id,
+                                            );
+                                            let failure_encoding =
+                                                unreachable_expr(pos).with_span(span)?;
+                                            ExprBackwardInterpreterState::new(
+                                                state.into_expr().map(|expr| {
+                                                    vir::Expr::ite(
+                                                        precondition,
+                                                        expr,
+                                                        failure_encoding,
+                                                    )
+                                                }),
+                                            )
+                                        }
+                                    };
+                                }
+                                state
+                            }
+
+                            // conversion or arithmetic operation of ghost integers
+                            _ if ghost_int_function.is_some() => {
+                                let ghost_int_function = ghost_int_function.unwrap();
+                                let ghost_int_args =
+                                    encoded_args.into_iter().map(vir::Expr::snap_app).collect();
+                                let value = ghost_int_function.encode_result(ghost_int_args);
+                                let mut state = states[target_block].clone();
+                                state.substitute_value(&encoded_lhs, value.clone());
+                                // The result of a cast, or of a subtraction of
+                                // natural numbers, needs to fit into its type.
+                                let bounds = match ghost_int_function {
+                                    GhostIntFunction::Cast | GhostIntFunction::BinOp(_) => {
+                                        self.encoder.encode_type_bounds(&value, ty)
+                                    }
+                                    GhostIntFunction::Neg => vec![],
+                                };
+                                if !bounds.is_empty() {
+                                    state = self.encode_ghost_int_obligation(
+                                        state,
+                                        bounds,
+                                        ErrorCtxt::TypeCast,
+                                        span,
+                                    )?;
+                                }
+                                // At runtime, ghost integers are represented by
+                                // an `i128`, so the arithmetic operations of
+                                // executable code must not overflow it.
+                                if !matches!(ghost_int_function, GhostIntFunction::Cast)
+                                    && self.pure_encoding_context == PureEncodingContext::Code
+                                {
+                                    let runtime_bounds = vec![
+                                        vir::Expr::le_cmp(i128::MIN.into(), value.clone()),
+                                        vir::Expr::le_cmp(value, i128::MAX.into()),
+                                    ];
+                                    state = self.encode_ghost_int_obligation(
+                                        state,
+                                        runtime_bounds,
+                                        ErrorCtxt::GhostIntOverflow,
+                                        span,
+                                    )?;
+                                }
+                                state
+                            }
+
+                            // uninterpreted function of a `#[domain]` module
+                            _ if self.encoder.get_domain_of_function(def_id).is_some() => {
+                                let expr =
+                                    self.encoder.encode_domain_func_app(def_id, encoded_args)?;
+                                let mut state = states[target_block].clone();
+                                state.substitute_value(&encoded_lhs, expr);
+                                state
+                            }
+
                             // simple function call
                             _ => {
-                                let (called_def_id, composed_substs) = self
-                                    .encoder
-                                    .env()
-                                    .resolve_method_call(self.def_id, def_id, composed_substs);
+                                let env = self.encoder.env();
+                                let (called_def_id, composed_substs) = env
+                                    .resolve_conversion_call(self.def_id, def_id, composed_substs)
+                                    .unwrap_or_else(|| {
+                                        env.resolve_method_call(
+                                            self.def_id,
+                                            def_id,
+                                            composed_substs,
+                                        )
+                                    });
                                 trace!("Resolved function call: {:?}", called_def_id);
 
                                 let is_pure_function = self.encoder.is_pure(called_def_id);
@@ -913,7 +1301,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                                     let discr_field = self.encoder.encode_discriminant_field();
                                     state.substitute_value(
                                         &encoded_lhs.clone().field(discr_field),
-                                        variant_index.index().into(),
+                                        compute_discriminant_values(adt_def, tcx)
+                                            [variant_index.index()]
+                                        .into(),
                                     );
                                     encoded_lhs_variant =
                                         encoded_lhs_variant.variant(variant_def.ident(tcx).as_str());
@@ -1044,17 +1434,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                         let encoded_right = self.mir_encoder.encode_operand_expr(right)
                             .with_span(span)?;
 
-                        let encoded_value = self.mir_encoder.encode_bin_op_expr(
-                            op,
-                            vir::Expr::snap_app(encoded_left.clone()),
-                            vir::Expr::snap_app(encoded_right.clone()),
-                            operand_ty,
-                        ).with_span(span)?;
-                        let encoded_check = self.mir_encoder.encode_bin_op_check(
+                        let (encoded_value, encoded_check) = self.mir_encoder.encode_checked_bin_op(
                             op,
                             vir::Expr::snap_app(encoded_left),
                             vir::Expr::snap_app(encoded_right),
                             operand_ty,
+                            span,
                         ).with_span(span)?;
 
                         let field_types = if let ty::TyKind::Tuple(ref x) = ty.kind() {
@@ -7,7 +7,10 @@
 use crate::encoder::{
     errors::SpannedEncodingResult,
     mir::{
-        pure::{specifications::utils::extract_closure_from_ty, PureFunctionEncoderInterface},
+        pure::{
+            specifications::utils::{check_quantifiable, extract_closure_from_ty},
+            PureFunctionEncoderInterface,
+        },
         types::MirTypeEncoderInterface,
     },
     mir_encoder::{MirEncoder, PlaceEncoder},
@@ -78,7 +81,7 @@ pub(super) fn inline_spec_item_high<'tcx>(
 
 pub(super) fn encode_quantifier_high<'tcx>(
     encoder: &Encoder<'_, 'tcx>,
-    _span: Span, // TODO: use span somehow? or remove arg
+    span: Span,
     encoded_args: Vec<Expression>,
     is_exists: bool,
     parent_def_id: DefId,
@@ -104,7 +107,8 @@ pub(super) fn encode_quantifier_high<'tcx>(
     let mut encoded_qvars = vec![];
     let mut bounds = vec![];
     for (arg_idx, arg_ty) in args.into_iter().enumerate() {
-        let qvar_ty = encoder.encode_type_high(arg_ty).unwrap();
+        check_quantifiable(arg_ty, span)?;
+        let qvar_ty = encoder.encode_type_high(arg_ty)?;
         let qvar_name = format!("_{}_quant_{}", arg_idx, body_def_id.index.index());
         let encoded_qvar = VariableDecl::new(qvar_name, qvar_ty);
         if config::check_overflows() {
@@ -5,13 +5,19 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::encoder::{
-    errors::{EncodingError, EncodingResult, SpannedEncodingResult, WithSpan},
+    errors::{
+        EncodingError, EncodingResult, SpannedEncodingError, SpannedEncodingResult, WithSpan,
+    },
     high::types::HighTypeEncoderInterface,
     mir::{
-        pure::{specifications::utils::extract_closure_from_ty, PureFunctionEncoderInterface},
+        pure::{
+            specifications::utils::{check_quantifiable, extract_closure_from_ty},
+            PureFunctionEncoderInterface,
+        },
         types::MirTypeEncoderInterface,
     },
     mir_encoder::{MirEncoder, PlaceEncoder},
+    snapshot::interface::SnapshotEncoderInterface,
     Encoder,
 };
 use prusti_common::config;
@@ -102,7 +108,7 @@ pub(super) fn inline_spec_item<'tcx>(
 
 pub(super) fn encode_quantifier<'tcx>(
     encoder: &Encoder<'_, 'tcx>,
-    _span: Span,
+    span: Span,
     encoded_args: Vec<vir_crate::polymorphic::Expr>,
     is_exists: bool,
     parent_def_id: DefId,
@@ -128,7 +134,8 @@ pub(super) fn encode_quantifier<'tcx>(
     let mut encoded_qvars = vec![];
     let mut bounds = vec![];
     for (arg_idx, arg_ty) in args.into_iter().enumerate() {
-        let qvar_ty = encoder.encode_type(arg_ty).unwrap();
+        check_quantifiable(arg_ty, span)?;
+        let qvar_ty = encoder.encode_type(arg_ty).with_span(span)?;
         let qvar_name = format!(
             "_{}_quant_{}",
             arg_idx,
@@ -257,6 +264,287 @@ pub(super) fn encode_quantifier<'tcx>(
     }
 }
 
+/// The aggregate computed by a comprehension over a range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ComprehensionKind {
+    Sum,
+    Count,
+    Max,
+}
+
+impl ComprehensionKind {
+    fn name(self) -> &'static str {
+        match self {
+            ComprehensionKind::Sum => "sum",
+            ComprehensionKind::Count => "count",
+            ComprehensionKind::Max => "max",
+        }
+    }
+}
+
+/// Encodes `sum(lo..hi, |i| e)`, `count(lo..hi, |i| e)` and `max(lo..hi, |i| e)`
+/// as a call to a bodyless function `builtin$sum$<closure>$<types>(lo, hi, ...)`,
+/// whose postconditions define the aggregate over an empty range, over a
+/// single element and over a range split in two. The places of `e` that do
+/// not depend on `i` are passed to the function as additional arguments.
+pub(super) fn encode_comprehension<'tcx>(
+    encoder: &Encoder<'_, 'tcx>,
+    span: Span,
+    encoded_args: Vec<vir_crate::polymorphic::Expr>,
+    kind: ComprehensionKind,
+    parent_def_id: DefId,
+    substs: ty::subst::SubstsRef<'tcx>,
+) -> SpannedEncodingResult<vir_crate::polymorphic::Expr> {
+    use vir_crate::polymorphic::{
+        Expr, ExprFolder, Function, InhaleExhale, LocalVar, Position, Trigger, Type,
+    };
+    let tcx = encoder.env().tcx();
+
+    // Comprehensions are encoded as:
+    //   sum(
+    //     Range { start: <lo>, end: <hi> },
+    //     |index| -> R { <term expr> },
+    //   )
+
+    let index_ty = substs.type_at(0);
+    if !matches!(index_ty.kind(), ty::TyKind::Int(_) | ty::TyKind::Uint(_)) {
+        return Err(SpannedEncodingError::unsupported(
+            format!(
+                "{}(..) is only supported over ranges of integers",
+                kind.name()
+            ),
+            span,
+        ));
+    }
+    let return_type = if kind == ComprehensionKind::Count {
+        Type::Int
+    } else {
+        let term_ty = substs.type_at(2);
+        if !matches!(term_ty.kind(), ty::TyKind::Int(_) | ty::TyKind::Uint(_)) {
+            return Err(SpannedEncodingError::unsupported(
+                format!("{}(..) is only supported over integer terms", kind.name()),
+                span,
+            ));
+        }
+        encoder.encode_snapshot_type(term_ty).with_span(span)?
+    };
+    let (body_def_id, body_substs, _, _, _) = extract_closure_from_ty(tcx, substs.type_at(1));
+
+    let encode_bound = |field_name: &str| -> SpannedEncodingResult<Expr> {
+        let field = encoder
+            .encode_struct_field(field_name, index_ty)
+            .with_span(span)?;
+        encoder
+            .encode_value_expr(encoded_args[0].clone().field(field), index_ty)
+            .with_span(span)
+    };
+    let lo = encode_bound("start")?;
+    let hi = encode_bound("end")?;
+
+    let index = LocalVar::new("_comprehension_index", Type::Int);
+    let mut term = inline_closure(
+        encoder,
+        body_def_id,
+        encoded_args[1].clone(),
+        vec![index.clone()],
+        parent_def_id,
+        body_substs,
+    )?;
+    if kind == ComprehensionKind::Count {
+        term = Expr::ite(term, 1u32.into(), 0u32.into());
+    }
+
+    // the places that do not depend on the index become arguments
+    let mut lifter = PlaceLifter {
+        index: Expr::local(index.clone()),
+        bound_vars: vec![],
+        places: vec![],
+    };
+    let term = lifter.fold(term);
+    let lo_arg = LocalVar::new("_lo", Type::Int);
+    let hi_arg = LocalVar::new("_hi", Type::Int);
+    let mut formal_args = vec![lo_arg.clone(), hi_arg.clone()];
+    let mut arg_replacements = vec![];
+    for (arg_idx, place) in lifter.places.iter().enumerate() {
+        let arg_name = format!("_comprehension_arg_{}", arg_idx);
+        let place_type = place.get_type().clone();
+        let arg_type = if let Type::TypedRef(_) = place_type {
+            let arg_ty = encoder
+                .decode_type_predicate_type(&place_type)
+                .with_span(span)?;
+            encoder.encode_snapshot_type(arg_ty).with_span(span)?
+        } else {
+            place_type.clone()
+        };
+        let arg = LocalVar::new(&arg_name, arg_type);
+        arg_replacements.push((
+            Expr::local(LocalVar::new(&arg_name, place_type)),
+            Expr::local(arg.clone()),
+        ));
+        formal_args.push(arg);
+    }
+    let term = term.replace_multiple_places(&arg_replacements);
+
+    // The closure of a generic function is the same item for every
+    // instantiation of the function, so its type substitutions are part of
+    // the name.
+    let mut function_name = format!(
+        "builtin${}${}",
+        kind.name(),
+        encoder.encode_item_name(body_def_id)
+    );
+    for arg in body_substs.as_closure().parent_substs() {
+        if let ty::subst::GenericArgKind::Type(arg_ty) = arg.unpack() {
+            let encoded_ty = encoder.encode_type(arg_ty).with_span(span)?;
+            function_name.push('$');
+            function_name.push_str(&encoded_ty.encode_as_string());
+        }
+    }
+    let apply = |lo: Expr, hi: Expr, args: Vec<Expr>| {
+        let mut arguments = vec![lo, hi];
+        arguments.extend(args);
+        Expr::func_app(
+            function_name.clone(),
+            vec![],
+            arguments,
+            formal_args.clone(),
+            return_type.clone(),
+            Position::default(),
+        )
+    };
+    encoder
+        .encode_comprehension_function_use(&function_name, || {
+            let lo = Expr::local(lo_arg.clone());
+            let hi = Expr::local(hi_arg.clone());
+            let args = formal_args[2..]
+                .iter()
+                .cloned()
+                .map(Expr::local)
+                .collect::<Vec<_>>();
+            let result = Expr::local(LocalVar::new("__result", return_type.clone()));
+            let mid_var = LocalVar::new("_mid", Type::Int);
+            let mid = Expr::local(mid_var.clone());
+            let left = apply(lo.clone(), mid.clone(), args.clone());
+            let right = apply(mid.clone(), hi.clone(), args);
+            let triggers = vec![
+                Trigger::new(vec![left.clone()]),
+                Trigger::new(vec![right.clone()]),
+            ];
+            let single_term = term.replace_place(&Expr::local(index.clone()), &lo);
+            let mut axioms = vec![Expr::implies(
+                Expr::eq_cmp(hi.clone(), Expr::add(lo.clone(), 1u32.into())),
+                Expr::eq_cmp(result.clone(), single_term),
+            )];
+            match kind {
+                ComprehensionKind::Sum | ComprehensionKind::Count => {
+                    axioms.push(Expr::implies(
+                        Expr::le_cmp(hi.clone(), lo.clone()),
+                        Expr::eq_cmp(result.clone(), 0u32.into()),
+                    ));
+                    axioms.push(Expr::forall(
+                        vec![mid_var],
+                        triggers,
+                        Expr::implies(
+                            Expr::and(
+                                Expr::le_cmp(lo.clone(), mid.clone()),
+                                Expr::le_cmp(mid, hi.clone()),
+                            ),
+                            Expr::eq_cmp(result.clone(), Expr::add(left, right)),
+                        ),
+                    ));
+                }
+                ComprehensionKind::Max => {
+                    axioms.push(Expr::forall(
+                        vec![mid_var],
+                        triggers,
+                        Expr::implies(
+                            Expr::and(
+                                Expr::lt_cmp(lo.clone(), mid.clone()),
+                                Expr::lt_cmp(mid, hi.clone()),
+                            ),
+                            Expr::eq_cmp(
+                                result.clone(),
+                                Expr::ite(Expr::ge_cmp(left.clone(), right.clone()), left, right),
+                            ),
+                        ),
+                    ));
+                }
+            }
+            if kind == ComprehensionKind::Count {
+                axioms.push(Expr::implies(
+                    Expr::le_cmp(lo.clone(), hi.clone()),
+                    Expr::and(
+                        Expr::le_cmp(0u32.into(), result.clone()),
+                        Expr::le_cmp(result, Expr::sub(hi, lo)),
+                    ),
+                ));
+            }
+            // The axioms are not checked, only assumed where the function is used.
+            let posts = axioms
+                .into_iter()
+                .map(|axiom| {
+                    Expr::InhaleExhale(InhaleExhale {
+                        inhale_expr: Box::new(axiom),
+                        exhale_expr: Box::new(true.into()),
+                        position: Position::default(),
+                    })
+                })
+                .collect();
+            Ok(Function {
+                name: function_name.clone(),
+                type_arguments: vec![],
+                formal_args: formal_args.clone(),
+                return_type: return_type.clone(),
+                pres: vec![],
+                posts,
+                body: None,
+            })
+        })
+        .with_span(span)?;
+
+    Ok(apply(lo, hi, lifter.places))
+}
+
+/// Replaces the maximal places of an expression that do not mention `index`
+/// (nor variables bound inside the expression) with fresh local variables.
+struct PlaceLifter {
+    index: vir_crate::polymorphic::Expr,
+    bound_vars: Vec<vir_crate::polymorphic::LocalVar>,
+    places: Vec<vir_crate::polymorphic::Expr>,
+}
+
+impl vir_crate::polymorphic::ExprFolder for PlaceLifter {
+    fn fold(&mut self, e: vir_crate::polymorphic::Expr) -> vir_crate::polymorphic::Expr {
+        use vir_crate::polymorphic::{Exists, Expr, ForAll, LocalVar};
+        match e {
+            Expr::ForAll(ForAll { ref variables, .. })
+            | Expr::Exists(Exists { ref variables, .. }) => {
+                let depth = self.bound_vars.len();
+                self.bound_vars.extend(variables.iter().cloned());
+                let e = vir_crate::polymorphic::default_fold_expr(self, e);
+                self.bound_vars.truncate(depth);
+                e
+            }
+            _ if e.is_place()
+                && !e.find(&self.index)
+                && !self.bound_vars.contains(&e.get_base()) =>
+            {
+                let arg_idx = if let Some(arg_idx) = self.places.iter().position(|p| p == &e) {
+                    arg_idx
+                } else {
+                    self.places.push(e.clone());
+                    self.places.len() - 1
+                };
+                Expr::local(LocalVar::new(
+                    format!("_comprehension_arg_{}", arg_idx),
+                    e.get_type().clone(),
+                ))
+            }
+            _ => vir_crate::polymorphic::default_fold_expr(self, e),
+        }
+    }
+}
+
 fn find_quantifier_depth(expr: &vir_crate::polymorphic::Expr) -> usize {
     use vir_crate::polymorphic::ExprWalker;
     struct DepthChecker {
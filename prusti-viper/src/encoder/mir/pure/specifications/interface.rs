@@ -5,15 +5,18 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::encoder::{
-    errors::{SpannedEncodingResult, WithSpan},
+    errors::{SpannedEncodingError, SpannedEncodingResult, WithSpan},
     mir::pure::{
         specifications::{
             encoder_high::{encode_quantifier_high, inline_spec_item_high},
-            encoder_poly::{encode_quantifier, inline_closure, inline_spec_item},
+            encoder_poly::{
+                encode_comprehension, encode_quantifier, inline_closure, inline_spec_item,
+                ComprehensionKind,
+            },
         },
         PureEncodingContext, PureFunctionBackwardInterpreter,
     },
-    mir_encoder::{MirEncoder, PlaceEncoder, PRECONDITION_LABEL},
+    mir_encoder::{MirEncoder, PlaceEncoder, LOOP_ENTRY_LABEL, PRECONDITION_LABEL},
     mir_interpreter::{run_backward_interpretation_point_to_point, ExprBackwardInterpreterState},
     snapshot::interface::SnapshotEncoderInterface,
 };
@@ -91,6 +94,12 @@ impl<'v, 'tcx: 'v> SpecificationEncoderInterface<'tcx> for crate::encoder::Encod
                 parent_def_id,
                 substs,
             ),
+            "prusti_contracts::sum" | "prusti_contracts::count" | "prusti_contracts::max" => {
+                Err(SpannedEncodingError::unsupported(
+                    "comprehensions are not supported in this encoding",
+                    span,
+                ))
+            }
             _ => unimplemented!(),
         }
     }
@@ -137,6 +146,14 @@ impl<'v, 'tcx: 'v> SpecificationEncoderInterface<'tcx> for crate::encoder::Encod
                 parent_def_id,
                 substs,
             ),
+            "prusti_contracts::sum" | "prusti_contracts::count" | "prusti_contracts::max" => {
+                let kind = match fn_name {
+                    "prusti_contracts::sum" => ComprehensionKind::Sum,
+                    "prusti_contracts::count" => ComprehensionKind::Count,
+                    _ => ComprehensionKind::Max,
+                };
+                encode_comprehension(self, span, encoded_args, kind, parent_def_id, substs)
+            }
             _ => unimplemented!(),
         }
     }
@@ -173,6 +190,12 @@ impl<'v, 'tcx: 'v> SpecificationEncoderInterface<'tcx> for crate::encoder::Encod
         }
 
         let span = self.env().tcx().def_span(assertion.to_def_id());
+        if encoded_assertion.uses_label(LOOP_ENTRY_LABEL) {
+            return Err(SpannedEncodingError::incorrect(
+                "on_entry(..) can only be used in loop body invariants",
+                span,
+            ));
+        }
         encoded_assertion = self.patch_snapshots(encoded_assertion).with_span(span)?;
 
         Ok(encoded_assertion
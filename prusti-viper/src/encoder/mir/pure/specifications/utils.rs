@@ -4,6 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use crate::encoder::errors::{SpannedEncodingError, SpannedEncodingResult};
 use rustc_hir::def_id::DefId;
 use rustc_middle::{ty, ty::subst::SubstsRef};
 use rustc_span::Span;
@@ -21,7 +22,9 @@ pub(super) fn extract_closure_from_ty<'tcx>(
     match ty.kind() {
         ty::TyKind::Closure(def_id, substs) => {
             let cl_substs = substs.as_closure();
-            let sig = cl_substs.sig().no_bound_vars().unwrap();
+            // Closures taking references, such as `|n: &Node| ...`, have a
+            // late-bound region in their signature.
+            let sig = tcx.erase_late_bound_regions(cl_substs.sig());
             (
                 *def_id,
                 substs,
@@ -33,3 +36,35 @@ pub(super) fn extract_closure_from_ty<'tcx>(
         _ => unreachable!("expected closure type"),
     }
 }
+
+/// Check that values of the given type can be bound by a quantifier. Such
+/// variables range over the snapshots of the type, which is why mutable
+/// references, raw pointers and functions are not allowed.
+pub(super) fn check_quantifiable<'tcx>(ty: ty::Ty<'tcx>, span: Span) -> SpannedEncodingResult<()> {
+    match ty.kind() {
+        ty::TyKind::Bool
+        | ty::TyKind::Char
+        | ty::TyKind::Int(_)
+        | ty::TyKind::Uint(_)
+        | ty::TyKind::Float(_)
+        | ty::TyKind::Adt(_, _)
+        | ty::TyKind::Param(_) => Ok(()),
+        ty::TyKind::Tuple(_) => {
+            for field_ty in ty.tuple_fields() {
+                check_quantifiable(field_ty, span)?;
+            }
+            Ok(())
+        }
+        ty::TyKind::Ref(_, target_ty, rustc_hir::Mutability::Not) => {
+            check_quantifiable(*target_ty, span)
+        }
+        ty::TyKind::Ref(_, _, rustc_hir::Mutability::Mut) => Err(SpannedEncodingError::incorrect(
+            "quantifiers cannot bind mutable references",
+            span,
+        )),
+        _ => Err(SpannedEncodingError::unsupported(
+            format!("quantification over values of type {} is not supported", ty),
+            span,
+        )),
+    }
+}
@@ -1,20 +1,25 @@
-use crate::encoder::mir::specifications::specs::Specifications;
+use crate::encoder::mir::specifications::{purity::is_side_effect_free, specs::Specifications};
 use log::trace;
+use prusti_common::config;
 use prusti_interface::{
     specs::{typed, typed::DefSpecificationMap},
     utils::has_spec_only_attr,
 };
+use rustc_hash::FxHashMap;
 use rustc_hir::def_id::{DefId, LocalDefId};
 use std::cell::RefCell;
 
 pub(crate) struct SpecificationsState {
     specs: RefCell<Specifications>,
+    /// The purity inferred for functions without a specification.
+    inferred_purity: RefCell<FxHashMap<DefId, bool>>,
 }
 
 impl SpecificationsState {
     pub fn new(user_typed_specs: DefSpecificationMap) -> Self {
         Self {
             specs: RefCell::new(Specifications::new(user_typed_specs)),
+            inferred_purity: RefCell::new(FxHashMap::default()),
         }
     }
 }
@@ -22,6 +27,10 @@ impl SpecificationsState {
 pub(crate) trait SpecificationsInterface {
     fn is_pure(&self, def_id: DefId) -> bool;
 
+    /// Is the function without a specification inferred to be pure? Always
+    /// false unless `INFER_PURITY` is enabled.
+    fn is_inferred_pure(&self, def_id: DefId) -> bool;
+
     fn is_trusted(&self, def_id: DefId) -> bool;
 
     fn get_predicate_body(&self, def_id: DefId) -> Option<LocalDefId>;
@@ -32,6 +41,18 @@ pub(crate) trait SpecificationsInterface {
     /// `prusti::loop_body_invariant_spec` attribute.
     fn get_loop_specs(&self, def_id: DefId) -> Option<typed::LoopSpecification>;
 
+    /// Get the line of a `prusti_calc!` proof attached to a function with a
+    /// `prusti::proof_step_spec` attribute.
+    fn get_proof_step_specs(&self, def_id: DefId) -> Option<typed::ProofStepSpecification>;
+
+    /// Get the yield invariant attached to a function with a
+    /// `prusti::yield_invariant_spec` attribute.
+    fn get_yield_invariant_specs(&self, def_id: DefId) -> Option<typed::YieldInvariantSpecification>;
+
+    /// Get the resume precondition attached to a function with a
+    /// `prusti::resume_requires_spec` attribute.
+    fn get_resume_precondition_specs(&self, def_id: DefId) -> Option<typed::ResumePreconditionSpecification>;
+
     /// Get the specifications attached to the `def_id` function.
     fn get_procedure_specs(&self, def_id: DefId) -> Option<typed::ProcedureSpecification>;
 
@@ -41,22 +62,80 @@ pub(crate) trait SpecificationsInterface {
 
     /// Is the closure specified with the `def_id` is spec only?
     fn is_spec_closure(&self, def_id: DefId) -> bool;
+
+    /// Get the pairs `(lemma, axiom)` of the lemmas registered as axioms,
+    /// where `axiom` is the spec function of the quantified axiom.
+    fn get_lemma_axioms(&self) -> Vec<(LocalDefId, LocalDefId)>;
+
+    /// Get the spec functions of the lock invariants attached to a function
+    /// of `Mutex` or `RwLock`, e.g. `std::sync::MutexGuard::deref`.
+    fn get_lock_invariant_specs(&self, target: &str) -> Vec<LocalDefId>;
+
+    /// Get the spec functions of the message invariants attached to a
+    /// function of a channel, e.g. `std::sync::mpsc::Sender::send`.
+    fn get_message_invariant_specs(&self, target: &str) -> Vec<LocalDefId>;
+
+    /// Get the spec functions of the refinements of `#[refined]` structs.
+    fn get_refinement_specs(&self) -> Vec<LocalDefId>;
+
+    /// Get the spec functions of the constraints of `#[symbolic]` constants.
+    fn get_symbolic_constraint_specs(&self) -> Vec<LocalDefId>;
+
+    /// Get the name of the `#[domain]` module that declares the function
+    /// stub `def_id`, if any.
+    fn get_domain_of_function(&self, def_id: DefId) -> Option<String>;
+
+    /// Get the function stubs of a `#[domain]` module.
+    fn get_domain_functions(&self, domain: &str) -> Vec<LocalDefId>;
+
+    /// Get the spec functions of the axioms of a `#[domain]` module.
+    fn get_domain_axiom_specs(&self, domain: &str) -> Vec<LocalDefId>;
 }
 
 impl<'v, 'tcx: 'v> SpecificationsInterface for super::super::super::Encoder<'v, 'tcx> {
     fn is_pure(&self, def_id: DefId) -> bool {
-        let result = self
+        let spec_kind_is_pure = self
             .specifications_state
             .specs
             .borrow_mut()
             .get_and_refine_proc_spec(self.env(), def_id)
             // In case of error -> It is emitted in get_and_refine_proc_spec
-            .map(|spec| spec.kind.is_pure().unwrap_or(false))
-            .unwrap_or(false);
+            .map(|spec| spec.kind.is_pure().unwrap_or(false));
+        let result = match spec_kind_is_pure {
+            Some(is_pure) => is_pure,
+            None => self.is_inferred_pure(def_id),
+        };
         trace!("is_pure {:?} = {}", def_id, result);
         result
     }
 
+    fn is_inferred_pure(&self, def_id: DefId) -> bool {
+        if !config::infer_purity() {
+            return false;
+        }
+        if let Some(&result) = self
+            .specifications_state
+            .inferred_purity
+            .borrow()
+            .get(&def_id)
+        {
+            return result;
+        }
+        // Assume impurity while the body is checked, so that cycles of calls
+        // are not inferred to be pure.
+        self.specifications_state
+            .inferred_purity
+            .borrow_mut()
+            .insert(def_id, false);
+        let result = is_side_effect_free(self, def_id);
+        self.specifications_state
+            .inferred_purity
+            .borrow_mut()
+            .insert(def_id, result);
+        trace!("is_inferred_pure {:?} = {}", def_id, result);
+        result
+    }
+
     fn is_trusted(&self, def_id: DefId) -> bool {
         let result = self
             .specifications_state
@@ -104,6 +183,30 @@ impl<'v, 'tcx: 'v> SpecificationsInterface for super::super::super::Encoder<'v,
             .cloned()
     }
 
+    fn get_proof_step_specs(&self, def_id: DefId) -> Option<typed::ProofStepSpecification> {
+        self.specifications_state
+            .specs
+            .borrow()
+            .get_proof_step_spec(def_id)
+            .cloned()
+    }
+
+    fn get_yield_invariant_specs(&self, def_id: DefId) -> Option<typed::YieldInvariantSpecification> {
+        self.specifications_state
+            .specs
+            .borrow()
+            .get_yield_invariant_spec(def_id)
+            .cloned()
+    }
+
+    fn get_resume_precondition_specs(&self, def_id: DefId) -> Option<typed::ResumePreconditionSpecification> {
+        self.specifications_state
+            .specs
+            .borrow()
+            .get_resume_precondition_spec(def_id)
+            .cloned()
+    }
+
     fn get_procedure_specs(&self, def_id: DefId) -> Option<typed::ProcedureSpecification> {
         let mut specs = self.specifications_state.specs.borrow_mut();
         let spec = specs.get_and_refine_proc_spec(self.env(), def_id)?;
@@ -124,4 +227,88 @@ impl<'v, 'tcx: 'v> SpecificationsInterface for super::super::super::Encoder<'v,
     fn is_spec_closure(&self, def_id: DefId) -> bool {
         has_spec_only_attr(self.env().tcx().get_attrs(def_id))
     }
+
+    fn get_lemma_axioms(&self) -> Vec<(LocalDefId, LocalDefId)> {
+        let mut lemma_axioms: Vec<_> = self
+            .specifications_state
+            .specs
+            .borrow()
+            .get_lemma_axioms()
+            .iter()
+            .map(|(lemma, axiom)| (*lemma, *axiom))
+            .collect();
+        // Keep the encoding deterministic.
+        lemma_axioms.sort_by_key(|(lemma, _)| self.env().get_absolute_item_name(lemma.to_def_id()));
+        lemma_axioms
+    }
+
+    fn get_lock_invariant_specs(&self, target: &str) -> Vec<LocalDefId> {
+        self.specifications_state
+            .specs
+            .borrow()
+            .get_lock_invariants()
+            .get(target)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn get_message_invariant_specs(&self, target: &str) -> Vec<LocalDefId> {
+        self.specifications_state
+            .specs
+            .borrow()
+            .get_message_invariants()
+            .get(target)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn get_refinement_specs(&self) -> Vec<LocalDefId> {
+        self.specifications_state
+            .specs
+            .borrow()
+            .get_refinements()
+            .to_vec()
+    }
+
+    fn get_symbolic_constraint_specs(&self) -> Vec<LocalDefId> {
+        let mut constraints = self
+            .specifications_state
+            .specs
+            .borrow()
+            .get_symbolic_constraints()
+            .to_vec();
+        constraints.sort_by_key(|constraint| self.env().get_def_span(constraint.to_def_id()));
+        constraints
+    }
+
+    fn get_domain_of_function(&self, def_id: DefId) -> Option<String> {
+        let local_id = def_id.as_local()?;
+        self.specifications_state
+            .specs
+            .borrow()
+            .get_domain_functions()
+            .iter()
+            .find(|(_, functions)| functions.contains(&local_id))
+            .map(|(domain, _)| domain.clone())
+    }
+
+    fn get_domain_functions(&self, domain: &str) -> Vec<LocalDefId> {
+        self.specifications_state
+            .specs
+            .borrow()
+            .get_domain_functions()
+            .get(domain)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn get_domain_axiom_specs(&self, domain: &str) -> Vec<LocalDefId> {
+        self.specifications_state
+            .specs
+            .borrow()
+            .get_domain_axioms()
+            .get(domain)
+            .cloned()
+            .unwrap_or_default()
+    }
 }
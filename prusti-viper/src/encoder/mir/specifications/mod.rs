@@ -1,6 +1,7 @@
 //! Specifications
 
 mod interface;
+mod purity;
 mod specs;
 
 pub(crate) use self::interface::{SpecificationsInterface, SpecificationsState};
@@ -0,0 +1,127 @@
+//! Inference of the purity of functions without a specification, enabled by
+//! `INFER_PURITY`.
+
+use super::interface::SpecificationsInterface;
+use crate::encoder::Encoder;
+use log::debug;
+use prusti_interface::utils::has_spec_only_attr;
+use rustc_hir::{def::DefKind, def_id::DefId, Unsafety};
+use rustc_middle::mir;
+
+/// Returns whether the body of the local function `def_id` has no side
+/// effects, its signature is supported by pure functions, and it calls only
+/// pure functions. Such a function can be encoded as if it was `#[pure]`.
+///
+/// The check is conservative: functions with loops, mutable borrows, writes
+/// through references, drops or calls to impure functions are rejected.
+pub(super) fn is_side_effect_free(encoder: &Encoder, def_id: DefId) -> bool {
+    let env = encoder.env();
+    let tcx = env.tcx();
+    let local_def_id = match def_id.as_local() {
+        Some(local_def_id) => local_def_id,
+        None => return false,
+    };
+    if !matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+        || tcx.trait_of_item(def_id).is_some()
+        || env.find_trait_method(def_id).is_some()
+        || has_spec_only_attr(tcx.get_attrs(def_id))
+        || tcx.fn_sig(def_id).unsafety() == Unsafety::Unsafe
+    {
+        return false;
+    }
+
+    let mir = env.local_mir(local_def_id, env.identity_substs(def_id));
+    let param_env = tcx.param_env(def_id);
+    let return_ty = mir.return_ty();
+    if return_ty.is_unit()
+        || return_ty.is_never()
+        || !env.type_is_allowed_in_pure_functions(return_ty, param_env)
+    {
+        debug!(
+            "{:?} has an unsupported return type {:?}",
+            def_id, return_ty
+        );
+        return false;
+    }
+    if !mir
+        .args_iter()
+        .all(|arg| env.type_is_copy(mir.local_decls[arg].ty, param_env))
+    {
+        debug!("{:?} has arguments that are not copy", def_id);
+        return false;
+    }
+    if mir.is_cfg_cyclic() {
+        debug!("{:?} contains loops", def_id);
+        return false;
+    }
+
+    let result = mir.basic_blocks().iter().all(|block| {
+        block.statements.iter().all(is_side_effect_free_statement)
+            && is_side_effect_free_terminator(encoder, def_id, block.terminator())
+    });
+    debug!("{:?} is side-effect free: {}", def_id, result);
+    result
+}
+
+fn is_local_write(place: &mir::Place) -> bool {
+    !place.is_indirect()
+}
+
+fn is_side_effect_free_statement(statement: &mir::Statement) -> bool {
+    match &statement.kind {
+        mir::StatementKind::Assign(box (place, rvalue)) => {
+            is_local_write(place)
+                && !matches!(
+                    rvalue,
+                    mir::Rvalue::Ref(_, mir::BorrowKind::Mut { .. }, _)
+                        | mir::Rvalue::AddressOf(..)
+                )
+        }
+        mir::StatementKind::SetDiscriminant { place, .. } => is_local_write(place),
+        mir::StatementKind::CopyNonOverlapping(_) => false,
+        mir::StatementKind::FakeRead(_)
+        | mir::StatementKind::StorageLive(_)
+        | mir::StatementKind::StorageDead(_)
+        | mir::StatementKind::Retag(..)
+        | mir::StatementKind::AscribeUserType(..)
+        | mir::StatementKind::Coverage(_)
+        | mir::StatementKind::Nop => true,
+    }
+}
+
+fn is_side_effect_free_terminator(
+    encoder: &Encoder,
+    def_id: DefId,
+    terminator: &mir::Terminator,
+) -> bool {
+    match &terminator.kind {
+        mir::TerminatorKind::Call {
+            func, destination, ..
+        } => {
+            let called_def_id = match func.const_fn_def() {
+                Some((called_def_id, _)) => called_def_id,
+                None => return false,
+            };
+            // Recursive functions are never inferred to be pure.
+            called_def_id != def_id
+                && destination
+                    .as_ref()
+                    .map_or(false, |(place, _)| is_local_write(place))
+                && encoder.is_pure(called_def_id)
+        }
+        mir::TerminatorKind::Goto { .. }
+        | mir::TerminatorKind::SwitchInt { .. }
+        | mir::TerminatorKind::Resume
+        | mir::TerminatorKind::Abort
+        | mir::TerminatorKind::Return
+        | mir::TerminatorKind::Unreachable
+        | mir::TerminatorKind::Assert { .. }
+        | mir::TerminatorKind::FalseEdge { .. }
+        | mir::TerminatorKind::FalseUnwind { .. } => true,
+        mir::TerminatorKind::Drop { .. }
+        | mir::TerminatorKind::DropAndReplace { .. }
+        | mir::TerminatorKind::Yield { .. }
+        | mir::TerminatorKind::GeneratorDrop
+        | mir::TerminatorKind::InlineAsm { .. } => false,
+    }
+}
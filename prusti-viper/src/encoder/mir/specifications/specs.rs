@@ -4,7 +4,8 @@ use prusti_interface::{
     environment::Environment,
     specs::typed::{
         DefSpecificationMap, LoopSpecification, ProcedureSpecification, ProcedureSpecificationKind,
-        ProcedureSpecificationKindError, Refinable, SpecificationItem,
+        ProcedureSpecificationKindError, ProofStepSpecification, Refinable, SpecificationItem,
+        ResumePreconditionSpecification, YieldInvariantSpecification,
     },
     PrustiError,
 };
@@ -34,12 +35,58 @@ impl Specifications {
         &self.get_user_typed_specs().extern_specs
     }
 
+    pub(super) fn get_lemma_axioms(&self) -> &HashMap<LocalDefId, LocalDefId> {
+        &self.get_user_typed_specs().lemma_axioms
+    }
+
+    pub(super) fn get_lock_invariants(&self) -> &HashMap<String, Vec<LocalDefId>> {
+        &self.get_user_typed_specs().lock_invariants
+    }
+
+    pub(super) fn get_message_invariants(&self) -> &HashMap<String, Vec<LocalDefId>> {
+        &self.get_user_typed_specs().message_invariants
+    }
+
+    pub(super) fn get_refinements(&self) -> &[LocalDefId] {
+        &self.get_user_typed_specs().refinements
+    }
+
+    pub(super) fn get_symbolic_constraints(&self) -> &[LocalDefId] {
+        &self.get_user_typed_specs().symbolic_constraints
+    }
+
+    pub(super) fn get_domain_functions(&self) -> &HashMap<String, Vec<LocalDefId>> {
+        &self.get_user_typed_specs().domain_functions
+    }
+
+    pub(super) fn get_domain_axioms(&self) -> &HashMap<String, Vec<LocalDefId>> {
+        &self.get_user_typed_specs().domain_axioms
+    }
+
     pub(super) fn get_loop_spec(&self, def_id: DefId) -> Option<&LoopSpecification> {
         trace!("Get loop specs of {:?}", def_id);
         let spec = self.get_user_typed_specs().get(&def_id)?;
         spec.as_loop()
     }
 
+    pub(super) fn get_proof_step_spec(&self, def_id: DefId) -> Option<&ProofStepSpecification> {
+        trace!("Get proof step specs of {:?}", def_id);
+        let spec = self.get_user_typed_specs().get(&def_id)?;
+        spec.as_proof_step()
+    }
+
+    pub(super) fn get_yield_invariant_spec(&self, def_id: DefId) -> Option<&YieldInvariantSpecification> {
+        trace!("Get yield invariant specs of {:?}", def_id);
+        let spec = self.get_user_typed_specs().get(&def_id)?;
+        spec.as_yield_invariant()
+    }
+
+    pub(super) fn get_resume_precondition_spec(&self, def_id: DefId) -> Option<&ResumePreconditionSpecification> {
+        trace!("Get resume precondition specs of {:?}", def_id);
+        let spec = self.get_user_typed_specs().get(&def_id)?;
+        spec.as_resume_precondition()
+    }
+
     pub(super) fn get_and_refine_proc_spec<'tcx>(
         &mut self,
         env: &Environment<'tcx>,
@@ -7,6 +7,7 @@
 use super::{helpers::compute_discriminant_values, interface::MirTypeEncoderInterface};
 use crate::encoder::{
     errors::{EncodingResult, SpannedEncodingError, SpannedEncodingResult},
+    ghost_int_encoder::GhostInt,
     high::types::HighTypeEncoderInterface,
     mir::{
         generics::MirGenericsEncoderInterface, types::helpers::compute_discriminant_bounds_high,
@@ -35,6 +36,10 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
         TypeEncoder { encoder, ty }
     }
 
+    fn ghost_int(&self) -> Option<GhostInt> {
+        GhostInt::from_ty(self.encoder.env().tcx(), self.ty)
+    }
+
     fn encode_substs(&self, substs: rustc_middle::ty::subst::SubstsRef<'tcx>) -> Vec<vir::Type> {
         substs
             .iter()
@@ -111,6 +116,11 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
                 vir::Type::reference(self.encoder.encode_type_high(*ty)?, lifetime)
             }
 
+            ty::TyKind::Adt(_, _) if self.ghost_int().is_some() => match self.ghost_int() {
+                Some(GhostInt::Nat) => vir::Type::Int(vir::ty::Int::Nat),
+                _ => vir::Type::Int(vir::ty::Int::Unbounded),
+            },
+
             ty::TyKind::Adt(adt_def, substs) if adt_def.is_struct() => vir::Type::struct_(
                 encode_struct_name(self.encoder, adt_def.did()),
                 self.encode_substs(substs),
@@ -319,6 +329,15 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
                     .filter_map(|ty| self.encoder.encode_type_high(ty).ok())
                     .collect(),
             ),
+            ty::TyKind::Adt(_, _) if self.ghost_int().is_some() => {
+                // Ghost integers are unbounded, natural numbers only from below.
+                let lower_bound = if self.ghost_int() == Some(GhostInt::Nat) {
+                    Some(Box::new(0usize.into()))
+                } else {
+                    None
+                };
+                vir::TypeDecl::int(lower_bound, None)
+            }
             ty::TyKind::Adt(adt_def, substs) => {
                 encode_adt_def(self.encoder, *adt_def, substs, None)?
             }
@@ -746,6 +765,28 @@ fn encode_variant<'v, 'tcx: 'v>(
     Ok(variant)
 }
 
+/// Types from the standard library whose internals are not modelled. Threads
+/// are encoded by the procedure encoder, and the values protected by locks are
+/// specified by lock invariants.
+const OPAQUE_ADTS: &[&str] = &[
+    "std::thread::JoinHandle",
+    "std::sync::Mutex",
+    "std::sync::MutexGuard",
+    "std::sync::RwLock",
+    "std::sync::RwLockReadGuard",
+    "std::sync::RwLockWriteGuard",
+    "std::sync::mpsc::Sender",
+    "std::sync::mpsc::SyncSender",
+    "std::sync::mpsc::Receiver",
+    "std::sync::atomic::AtomicBool",
+    "std::sync::atomic::AtomicUsize",
+    "std::sync::atomic::AtomicIsize",
+    "std::sync::atomic::AtomicU32",
+    "std::sync::atomic::AtomicI32",
+    "std::sync::atomic::AtomicU64",
+    "std::sync::atomic::AtomicI64",
+];
+
 pub(super) fn encode_adt_def<'v, 'tcx>(
     encoder: &Encoder<'v, 'tcx>,
     adt_def: ty::AdtDef<'tcx>,
@@ -759,6 +800,10 @@ pub(super) fn encode_adt_def<'v, 'tcx>(
         let boxed_ty = encoder.encode_type_high(substs.type_at(0))?;
         let field = vir::FieldDecl::new("val_ref", boxed_ty);
         Ok(vir::TypeDecl::struct_(encode_box_name(), vec![field]))
+    } else if OPAQUE_ADTS.contains(&tcx.def_path_str(adt_def.did()).as_str()) {
+        debug!("ADT {:?} is opaque", adt_def);
+        let name = encode_struct_name(encoder, adt_def.did());
+        Ok(vir::TypeDecl::struct_(name, vec![]))
     } else if adt_def.is_struct() {
         debug!("ADT {:?} is a struct", adt_def);
         assert!(variant_index.is_none());
@@ -24,6 +24,7 @@ use prusti_interface::environment::mir_utils::MirPlace;
 use crate::encoder::mir::types::MirTypeEncoderInterface;
 use super::high::types::HighTypeEncoderInterface;
 use rustc_span::MultiSpan;
+use std::cell::RefCell;
 
 mod downcast_detector;
 mod place_encoding;
@@ -32,6 +33,7 @@ pub use place_encoding::{PlaceEncoding, ExprOrArrayBase};
 
 pub static PRECONDITION_LABEL: &str = "pre";
 pub static WAND_LHS_LABEL: &str = "lhs";
+pub static LOOP_ENTRY_LABEL: &str = "loop_entry";
 
 pub trait PlaceEncoder<'v, 'tcx: 'v> {
 
@@ -403,6 +405,16 @@ pub struct MirEncoder<'p, 'v: 'p, 'tcx: 'v> {
     encoder: &'p Encoder<'v, 'tcx>,
     mir: &'p mir::Body<'tcx>,
     def_id: DefId,
+    /// Where arithmetic operations may overflow, computed on first use.
+    overflow_allowance: RefCell<Option<OverflowAllowance>>,
+}
+
+/// The parts of a function in which arithmetic operations may overflow.
+enum OverflowAllowance {
+    /// The function is annotated with `#[allow_overflow]`.
+    Everywhere,
+    /// The spans of the `wrapping!` blocks of the function.
+    InBlocks(Vec<Span>),
 }
 
 impl<'p, 'v: 'p, 'tcx: 'v> PlaceEncoder<'v, 'tcx> for MirEncoder<'p, 'v, 'tcx> {
@@ -431,6 +443,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
             encoder,
             mir,
             def_id,
+            overflow_allowance: RefCell::new(None),
         }
     }
 
@@ -528,6 +541,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
         right: vir::Expr,
         ty: ty::Ty<'tcx>,
     ) -> EncodingResult<vir::Expr> {
+        if let Some(expr) = self.encode_power_of_two_op(op, &left, &right, ty) {
+            return Ok(expr);
+        }
         let is_bool = ty.kind() == &ty::TyKind::Bool;
         let is_signed = matches!(ty.kind(), ty::TyKind::Int(_));
         Ok(match op {
@@ -575,6 +591,51 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
         })
     }
 
+    /// Encode a mask `x & (2^k - 1)`, a right shift `x >> k` or a left shift
+    /// `x << k` of an unsigned integer `x` with a constant `k` as `x % 2^k`,
+    /// `x / 2^k` or `x * 2^k` wrapped around, if the function is annotated
+    /// with `#[bitwise_arithmetic]`.
+    fn encode_power_of_two_op(
+        &self,
+        op: mir::BinOp,
+        left: &vir::Expr,
+        right: &vir::Expr,
+        ty: ty::Ty<'tcx>,
+    ) -> Option<vir::Expr> {
+        if config::encode_bitvectors()
+            || !matches!(ty.kind(), ty::TyKind::Uint(_))
+            || !self.encoder.env().has_prusti_attribute(self.def_id, "bitwise_arithmetic")
+        {
+            return None;
+        }
+        let power_of_two = |exponent: &vir::Expr| {
+            let exponent: u32 = constant_value(exponent)?.try_into().ok()?;
+            1u128.checked_shl(exponent)
+        };
+        match op {
+            mir::BinOp::BitAnd => {
+                let (value, mask) = match (constant_value(left), constant_value(right)) {
+                    (_, Some(mask)) => (left, mask),
+                    (Some(mask), None) => (right, mask),
+                    (None, None) => return None,
+                };
+                let modulus = mask.checked_add(1).filter(|modulus| modulus.is_power_of_two())?;
+                Some(vir::Expr::modulo(value.clone(), modulus.into()))
+            }
+            mir::BinOp::Shr => {
+                Some(vir::Expr::div(left.clone(), power_of_two(right)?.into()))
+            }
+            mir::BinOp::Shl => {
+                let (_, upper) = self.get_integer_bounds(ty)?;
+                Some(vir::Expr::modulo(
+                    vir::Expr::mul(left.clone(), power_of_two(right)?.into()),
+                    vir::Expr::add(upper, 1.into()),
+                ))
+            }
+            _ => None,
+        }
+    }
+
     pub fn encode_unary_op_expr(&self, op: mir::UnOp, expr: vir::Expr) -> vir::Expr {
         match op {
             mir::UnOp::Not => vir::Expr::not(expr),
@@ -582,6 +643,89 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
         }
     }
 
+    /// Encode a checked binary operation, returning its value and whether an
+    /// overflow happened. Additions, subtractions and multiplications for which
+    /// overflows are allowed wrap around instead of overflowing.
+    pub fn encode_checked_bin_op(
+        &self,
+        op: mir::BinOp,
+        left: vir::Expr,
+        right: vir::Expr,
+        ty: ty::Ty<'tcx>,
+        span: Span,
+    ) -> EncodingResult<(vir::Expr, vir::Expr)> {
+        let value = self.encode_bin_op_expr(op, left.clone(), right.clone(), ty)?;
+        if config::check_overflows()
+            && matches!(op, mir::BinOp::Add | mir::BinOp::Sub | mir::BinOp::Mul)
+            && self.allows_overflow(span)
+        {
+            if let Some((lower, upper)) = self.get_integer_bounds(ty) {
+                // lower + (value - lower) % (upper - lower + 1)
+                let range_size = vir::Expr::add(vir::Expr::sub(upper, lower.clone()), 1.into());
+                let wrapped_value = vir::Expr::add(
+                    lower.clone(),
+                    vir::Expr::modulo(vir::Expr::sub(value, lower), range_size),
+                );
+                return Ok((wrapped_value, false.into()));
+            }
+        }
+        let check = self.encode_bin_op_check(op, left, right, ty)?;
+        Ok((value, check))
+    }
+
+    /// Whether arithmetic operations at `span` may overflow, because the
+    /// function is annotated with `#[allow_overflow]` or the operation is in a
+    /// `wrapping!` block.
+    fn allows_overflow(&self, span: Span) -> bool {
+        let mut overflow_allowance = self.overflow_allowance.borrow_mut();
+        let overflow_allowance = overflow_allowance
+            .get_or_insert_with(|| self.compute_overflow_allowance());
+        match overflow_allowance {
+            OverflowAllowance::Everywhere => true,
+            OverflowAllowance::InBlocks(spans) => {
+                spans.iter().any(|block_span| block_span.contains(span))
+            }
+        }
+    }
+
+    fn compute_overflow_allowance(&self) -> OverflowAllowance {
+        let env = self.encoder.env();
+        if env.has_prusti_attribute(self.def_id, "allow_overflow") {
+            return OverflowAllowance::Everywhere;
+        }
+        // The marker of a `wrapping!` block spans the whole block.
+        let spans = self.mir.basic_blocks().iter()
+            .flat_map(|bb_data| bb_data.statements.iter())
+            .filter(|stmt| match &stmt.kind {
+                mir::StatementKind::Assign(box (
+                    _,
+                    mir::Rvalue::Aggregate(box mir::AggregateKind::Closure(def_id, _), _),
+                )) => env.has_prusti_attribute(*def_id, "wrapping_spec"),
+                _ => false,
+            })
+            .map(|stmt| stmt.source_info.span)
+            .collect();
+        OverflowAllowance::InBlocks(spans)
+    }
+
+    fn get_integer_bounds(&self, ty: ty::Ty<'tcx>) -> Option<(vir::Expr, vir::Expr)> {
+        Some(match ty.kind() {
+            ty::TyKind::Int(ty::IntTy::I8) => (std::i8::MIN.into(), std::i8::MAX.into()),
+            ty::TyKind::Int(ty::IntTy::I16) => (std::i16::MIN.into(), std::i16::MAX.into()),
+            ty::TyKind::Int(ty::IntTy::I32) => (std::i32::MIN.into(), std::i32::MAX.into()),
+            ty::TyKind::Int(ty::IntTy::I64) => (std::i64::MIN.into(), std::i64::MAX.into()),
+            ty::TyKind::Int(ty::IntTy::I128) => (std::i128::MIN.into(), std::i128::MAX.into()),
+            ty::TyKind::Int(ty::IntTy::Isize) => (std::isize::MIN.into(), std::isize::MAX.into()),
+            ty::TyKind::Uint(ty::UintTy::U8) => (0.into(), std::u8::MAX.into()),
+            ty::TyKind::Uint(ty::UintTy::U16) => (0.into(), std::u16::MAX.into()),
+            ty::TyKind::Uint(ty::UintTy::U32) => (0.into(), std::u32::MAX.into()),
+            ty::TyKind::Uint(ty::UintTy::U64) => (0.into(), std::u64::MAX.into()),
+            ty::TyKind::Uint(ty::UintTy::U128) => (0.into(), std::u128::MAX.into()),
+            ty::TyKind::Uint(ty::UintTy::Usize) => (0.into(), std::usize::MAX.into()),
+            _ => return None,
+        })
+    }
+
     /// Returns `true` is an overflow happened
     pub fn encode_bin_op_check(
         &self,
@@ -890,3 +1034,16 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
         }
     }
 }
+
+/// The value of a non-negative integer constant.
+fn constant_value(expr: &vir::Expr) -> Option<u128> {
+    match expr {
+        vir::Expr::Const(vir::ConstExpr { value: vir::Const::Int(value), .. }) => {
+            (*value).try_into().ok()
+        }
+        vir::Expr::Const(vir::ConstExpr { value: vir::Const::BigInt(value), .. }) => {
+            value.parse().ok()
+        }
+        _ => None,
+    }
+}
@@ -5,13 +5,18 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 pub use self::encoder::Encoder;
+pub(crate) use self::mir::specifications::SpecificationsInterface;
 
 mod borrows;
 mod builtin_encoder;
+mod cmp_encoder;
+mod ghost_int_encoder;
 #[allow(clippy::module_inception)]
 mod encoder;
 mod errors;
+pub use errors::FailureKind;
 mod foldunfold;
+mod frame_conditions;
 mod initialisation;
 mod loop_encoder;
 mod mir_encoder;
@@ -20,6 +25,7 @@ mod mir_interpreter;
 mod name_interner;
 mod places;
 mod procedure_encoder;
+mod procedure_summary;
 mod spec_function_encoder;
 pub use spec_function_encoder::SpecFunctionKind;
 mod stub_function_encoder;
@@ -6,18 +6,22 @@
 
 use crate::encoder::borrows::ProcedureContract;
 use crate::encoder::builtin_encoder::{BuiltinMethodKind};
+use crate::encoder::cmp_encoder::{variant_discriminant, CmpFunction, CmpResult};
 use crate::encoder::errors::{
     SpannedEncodingError, ErrorCtxt, EncodingError, WithSpan,
-    EncodingResult, SpannedEncodingResult
+    EncodingResult, SpannedEncodingResult, PanicCause
 };
 use crate::encoder::foldunfold;
+use crate::encoder::ghost_int_encoder::GhostIntFunction;
 use crate::encoder::high::types::HighTypeEncoderInterface;
 use crate::encoder::initialisation::InitInfo;
 use crate::encoder::loop_encoder::{LoopEncoder, LoopEncoderError};
 use crate::encoder::mir_encoder::{MirEncoder, FakeMirEncoder, PlaceEncoder, PlaceEncoding, ExprOrArrayBase};
-use crate::encoder::mir_encoder::PRECONDITION_LABEL;
+use crate::encoder::mir_encoder::{LOOP_ENTRY_LABEL, PRECONDITION_LABEL};
 use crate::encoder::mir_successor::MirSuccessor;
 use crate::encoder::places::{Local, LocalVariableManager, Place};
+use crate::encoder::frame_conditions::FrameCondition;
+use crate::encoder::procedure_summary::ProcedureSummary;
 use crate::encoder::Encoder;
 use crate::encoder::snapshot::interface::SnapshotEncoderInterface;
 use prusti_common::{
@@ -41,7 +45,8 @@ use prusti_interface::{
             LoanPlaces, PoloniusInfo, PoloniusInfoError, ReborrowingDAG, ReborrowingDAGNode,
             ReborrowingKind, ReborrowingZombity,
         },
-        BasicBlockIndex, PermissionKind, Procedure,
+        mir_utils::AllPlaces,
+        BasicBlockIndex, CaptureKind, PermissionKind, Procedure,
     },
 };
 use prusti_interface::utils;
@@ -58,6 +63,7 @@ use ::log::{trace, debug};
 use prusti_interface::environment::borrowck::regions::PlaceRegionsError;
 use crate::encoder::errors::EncodingErrorKind;
 use std::convert::TryInto;
+use std::time::Instant;
 use prusti_interface::specs::typed::{Pledge, SpecificationItem};
 use vir_crate::polymorphic::Float;
 use crate::utils::is_reference;
@@ -66,6 +72,11 @@ use crate::encoder::mir::types::MirTypeEncoderInterface;
 use crate::encoder::mir::pure::SpecificationEncoderInterface;
 use crate::encoder::mir::specifications::SpecificationsInterface;
 use super::high::generics::HighGenericsEncoderInterface;
+use analysis::{
+    abstract_interpretation::{AbstractState, FixpointEngine},
+    domains::{IntervalAnalysis, IntervalsState},
+    PointwiseState,
+};
 
 pub struct ProcedureEncoder<'p, 'v: 'p, 'tcx: 'v> {
     encoder: &'p Encoder<'v, 'tcx>,
@@ -95,6 +106,8 @@ pub struct ProcedureEncoder<'p, 'v: 'p, 'tcx: 'v> {
     array_magic_wand_at: FxHashMap<mir::Location, (vir::Expr, vir::Expr, vir::Expr)>,
     /// Labels for array equalities in loops
     array_loop_old_label: FxHashMap<BasicBlockIndex, String>,
+    /// Labels of the state before loops, used by `on_entry(..)` in loop invariants
+    loop_entry_label: FxHashMap<BasicBlockIndex, String>,
     /// Slices created at certain locations
     slice_created_at: FxHashMap<mir::Location, vir::Expr>,
     // /// Contracts of functions called at given locations with map for replacing fake expressions.
@@ -114,6 +127,19 @@ pub struct ProcedureEncoder<'p, 'v: 'p, 'tcx: 'v> {
     /// Type substitutions inside this procedure. Most likely identity for the
     /// given proc_def_id.
     substs: SubstsRef<'tcx>,
+    /// The numeric facts inferred by the interval analysis, if
+    /// `INFER_LOOP_INVARIANTS` is enabled and the analysis succeeded.
+    inferred_invariants: Option<PointwiseState<'p, 'tcx, IntervalsState>>,
+    /// The join handles returned by `std::thread::spawn`, with the spawned
+    /// closure, its type substitutions, the local holding the closure and the
+    /// label of the state in which the thread was spawned.
+    spawned_threads: FxHashMap<mir::Local, (ProcedureDefId, SubstsRef<'tcx>, Local, String)>,
+    /// The span of the last Rust source comment, to avoid repeating the same
+    /// source comment for consecutive statements.
+    last_source_comment_span: Option<Span>,
+    /// How many times the loops are unrolled, if the procedure is
+    /// `#[bounded(..)]`.
+    loop_unrolling: Option<usize>,
 }
 
 impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
@@ -130,6 +156,32 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         let init_info = InitInfo::new(mir, tcx, def_id, &mir_encoder)
             .with_default_span(procedure.get_span())?;
 
+        let inferred_invariants = if config::infer_loop_invariants() {
+            match IntervalAnalysis::new(tcx, def_id, mir).run_fwd_analysis() {
+                Ok(result) => Some(result),
+                Err(error) => {
+                    debug!("Failed to infer the loop invariants of {:?}: {:?}", def_id, error);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let loop_unrolling = match utils::read_prusti_attr("bounded", tcx.get_attrs(def_id)) {
+            Some(bound) => Some(bound.parse::<usize>().ok().filter(|&bound| bound > 0).ok_or_else(
+                || SpannedEncodingError::incorrect(
+                    format!(
+                        "the bound {} of `#[bounded(..)]` must be a positive integer of at most {}",
+                        bound,
+                        usize::MAX,
+                    ),
+                    procedure.get_span(),
+                ),
+            )?),
+            None => None,
+        };
+
         let cfg_method = vir::CfgMethod::new(
             // method name
             encoder.encode_item_name(def_id),
@@ -163,6 +215,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             magic_wand_at_location: FxHashMap::default(),
             array_magic_wand_at: FxHashMap::default(),
             array_loop_old_label: FxHashMap::default(),
+            loop_entry_label: FxHashMap::default(),
             slice_created_at: FxHashMap::default(),
             procedure_contracts: FxHashMap::default(),
             pure_var_for_preserving_value_map: FxHashMap::default(),
@@ -171,6 +224,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             old_ghost_vars: FxHashMap::default(),
             cached_loop_invariant_block: FxHashMap::default(),
             substs: encoder.env().identity_substs(def_id),
+            inferred_invariants,
+            spawned_threads: FxHashMap::default(),
+            last_source_comment_span: None,
+            loop_unrolling,
         })
     }
 
@@ -383,6 +440,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             );
         }
 
+        // The procedure generated for a `#[symbolic]` constant checks its
+        // constraint with the values of the constants given in the code.
+        if self.is_symbolic_check() {
+            self.cfg_method = self.encoder.replace_symbolic_constants(self.cfg_method);
+        }
+
         // Patch snapshots
         self.cfg_method = self.encoder.patch_snapshots_method(self.cfg_method)
             .with_span(mir_span)?;
@@ -398,6 +461,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 self.mir.span,
                 ErrorCtxt::Unexpected
             );
+        let foldunfold_start = Instant::now();
         let method_with_fold_unfold = foldunfold::add_fold_unfold(
             self.encoder,
             self.cfg_method,
@@ -411,6 +475,60 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     SpannedEncodingError::unsupported(msg, mir_span)
                 }
 
+                foldunfold::FoldUnfoldError::UnsupportedStmt(stmt, pos) => {
+                    // Point to the Rust code that generated the statement, if known.
+                    let span = self
+                        .encoder
+                        .error_manager()
+                        .position_manager()
+                        .get_span(pos)
+                        .cloned()
+                        .unwrap_or_else(|| mir_span.into());
+                    SpannedEncodingError::unsupported(
+                        format!(
+                            "the permissions of {} cannot be computed",
+                            self.describe_stmt(&stmt)
+                        ),
+                        span,
+                    )
+                }
+
+                foldunfold::FoldUnfoldError::FailedToObtain(perm) => {
+                    let place = perm.get_place();
+                    let what = if perm.is_pred() { "ownership" } else { "access permission" };
+                    SpannedEncodingError::unsupported(
+                        format!(
+                            "cannot obtain the {} of `{}` in this procedure",
+                            what,
+                            self.rust_place(place)
+                        ),
+                        mir_span,
+                    )
+                }
+
+                foldunfold::FoldUnfoldError::FailedToRemovePred(place, _) => {
+                    SpannedEncodingError::unsupported(
+                        format!(
+                            "the ownership of `{}` is not available where it is released",
+                            self.rust_place(&place)
+                        ),
+                        mir_span,
+                    )
+                }
+
+                foldunfold::FoldUnfoldError::IncompatiblePermAmounts(place, _, _) => {
+                    SpannedEncodingError::unsupported(
+                        format!(
+                            "the control flow paths of this procedure hold incomparable \
+                            permissions of `{}`",
+                            self.rust_place(&place)
+                        ),
+                        mir_span,
+                    )
+                }
+
+                foldunfold::FoldUnfoldError::SpannedEncodingError(error) => error,
+
                 _ => SpannedEncodingError::internal(
                     format!(
                         "cannot generate fold-unfold Viper statements. {}",
@@ -420,6 +538,11 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 ),
             }
         })?;
+        let program_name = self.encoder.env().get_unique_item_name(self.proc_def_id);
+        self.encoder
+            .performance_report()
+            .cost_mut(&program_name)
+            .foldunfold_time += foldunfold_start.elapsed();
 
         // Fix variable declarations.
         let method_with_fold_unfold = fix_ghost_vars(method_with_fold_unfold);
@@ -463,7 +586,11 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 let is_loop_head = loop_info.is_loop_head(curr_bb);
                 if curr_loop_depth == group_loop_depth + 1 && is_loop_head {
                     // Encode a nested loop
-                    self.encode_loop(label_prefix, curr_bb, return_block)?
+                    if let Some(bound) = self.loop_unrolling {
+                        self.encode_unrolled_loop(label_prefix, curr_bb, return_block, bound)?
+                    } else {
+                        self.encode_loop(label_prefix, curr_bb, return_block)?
+                    }
                 } else {
                     debug_assert!(curr_loop_depth > group_loop_depth + 1 || !is_loop_head);
                     // Skip the inner block of a nested loop
@@ -682,6 +809,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 self.encode_loop_invariant_inhale_stmts(loop_head, before_invariant_block, false)?;
             self.cfg_method.add_stmts(inv_post_block, stmts);
         }
+        // The state referred to by `on_entry(..)` is the one before the loop guard
+        if let Some(label) = self.loop_entry_label.get(&loop_head) {
+            self.cfg_method.add_stmt(start_block, vir::Stmt::label(label));
+        }
 
         // Encode the last B2 group (start - G - B1 - invariant - *B2* - G - B1 - end)
         let (last_b2_head, last_b2_edges) = self.encode_blocks_group(
@@ -823,11 +954,251 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             });
             self.cfg_method.add_stmt(inv_pre_block, stmt);
         }
+        self.register_loop_havoc(loop_head);
 
         // Done. Phew!
         Ok((start_block, still_unresolved_edges))
 }
 
+    /// Encodes a loop of a `#[bounded(k)]` procedure, without using its
+    /// invariants.
+    ///
+    /// Returns:
+    /// * The first CFG block of the encoding
+    /// * A vector of unresolved CFG edges
+    ///
+    /// The encoding transforms
+    /// ```text
+    /// while { g = G; g } { B }
+    /// ```
+    /// into `k` copies of the loop, followed by a last evaluation of the
+    /// guard:
+    /// ```text
+    /// g = G
+    /// if (g) {
+    ///   B
+    ///   // ... `k - 1` more copies of `g = G; if (g) { B ...`
+    ///   g = G
+    ///   if (g) {
+    ///     assume false
+    ///   }
+    /// }
+    /// assume !g
+    /// ```
+    /// Thus, only the executions that leave the loop within `k` iterations
+    /// are verified.
+    fn encode_unrolled_loop(
+        &mut self,
+        label_prefix: &str,
+        loop_head: BasicBlockIndex,
+        return_block: CfgBlockIndex,
+        bound: usize,
+    ) -> SpannedEncodingResult<(CfgBlockIndex, Vec<(CfgBlockIndex, BasicBlockIndex)>)> {
+        let loop_info = self.loop_encoder.loops();
+        debug_assert!(loop_info.is_loop_head(loop_head));
+        trace!("encode_unrolled_loop: {:?}", loop_head);
+        let loop_label_prefix = format!("{}loop{}", label_prefix, loop_head.index());
+        let loop_depth = loop_info.get_loop_head_depth(loop_head);
+
+        let loop_body: Vec<BasicBlockIndex> = loop_info
+            .get_loop_body(loop_head)
+            .iter()
+            .filter(
+                |&&bb| self.procedure.is_reachable_block(bb) && !self.procedure.is_spec_block(bb)
+            )
+            .cloned()
+            .collect();
+        let loop_body_set: FxHashSet<_> = loop_body.iter().cloned().collect();
+
+        // HEURISTIC: as in `encode_loop`, the guard G ends with the last exit
+        // block before the place of the loop invariant, or with the first exit
+        // block if there is no invariant.
+        let loop_exit_blocks: FxHashSet<_> = loop_info
+            .get_loop_exit_blocks(loop_head)
+            .iter()
+            .cloned()
+            .collect();
+        let before_invariant_block = self.cached_loop_invariant_block[&loop_head];
+        let after_inv_block_pos = 1 + loop_body
+            .iter()
+            .position(|&bb| bb == before_invariant_block)
+            .unwrap();
+        let after_guard_block_pos = loop_body[0..after_inv_block_pos]
+            .iter()
+            .rposition(|bb| loop_exit_blocks.contains(bb))
+            .map_or(0, |pos| pos + 1);
+        let loop_guard_evaluation = &loop_body[0..after_guard_block_pos];
+
+        // Build the block that kills the executions that iterate more often.
+        let bound_block = self.cfg_method.add_block(
+            &format!("{}_bound", loop_label_prefix),
+            vec![vir::Stmt::comment(format!(
+                "========== {}_bound ==========",
+                loop_label_prefix
+            ))],
+        );
+        self.cfg_method.add_stmt(
+            bound_block,
+            vir::Stmt::Inhale( vir::Inhale {expr: false.into()} ),
+        );
+        self.cfg_method
+            .set_successor(bound_block, vir::Successor::Return);
+
+        // Encode the last evaluation of the guard, whose edges into the loop
+        // body lead to the "bound" block.
+        let mut still_unresolved_edges = vec![];
+        let (last_g_head, last_g_edges) = self.encode_blocks_group(
+            &format!("{}_last_", loop_label_prefix),
+            loop_guard_evaluation,
+            loop_depth,
+            return_block,
+        )?;
+        still_unresolved_edges.extend(self.encode_unresolved_edges(last_g_edges, |bb| {
+            if loop_body_set.contains(&bb) {
+                Some(bound_block)
+            } else {
+                None
+            }
+        })?);
+
+        // Encode the iterations backwards, linking the back edges of each one
+        // to the head of the following one.
+        let mut next_head = last_g_head.unwrap_or(bound_block);
+        for iteration in (1..=bound).rev() {
+            let (iteration_head, iteration_edges) = self.encode_blocks_group(
+                &format!("{}_iter{}_", loop_label_prefix, iteration),
+                &loop_body,
+                loop_depth,
+                return_block,
+            )?;
+            still_unresolved_edges.extend(self.encode_unresolved_edges(iteration_edges, |bb| {
+                if bb == loop_head {
+                    Some(next_head)
+                } else {
+                    None
+                }
+            })?);
+            next_head = iteration_head.unwrap();
+        }
+
+        Ok((next_head, still_unresolved_edges))
+    }
+
+    /// Whether the procedure is a lemma.
+    fn is_lemma(&self) -> bool {
+        self.encoder.env().has_prusti_attribute(self.proc_def_id, "lemma")
+    }
+
+    /// Whether the procedure checks the constraint of a `#[symbolic]` constant.
+    fn is_symbolic_check(&self) -> bool {
+        self.encoder.env().has_prusti_attribute(self.proc_def_id, "symbolic_check")
+    }
+
+    /// Record which user variables are modified in the loop, and hence lose
+    /// the information about their values at the loop head.
+    fn register_loop_havoc(&self, loop_head: BasicBlockIndex) {
+        let (write_leaves, mut_borrow_leaves, _) = self
+            .loop_encoder
+            .loops()
+            .compute_read_and_write_leaves(loop_head, self.mir, None);
+        let mut variables: Vec<String> = write_leaves
+            .iter()
+            .chain(mut_borrow_leaves.iter())
+            .filter_map(|place| {
+                self.mir.var_debug_info.iter().find_map(|vdi| match vdi.value {
+                    mir::VarDebugInfoContents::Place(var_place)
+                        if var_place.as_local() == Some(place.local) =>
+                    {
+                        Some(vdi.name.to_ident_string())
+                    }
+                    _ => None,
+                })
+            })
+            .collect();
+        variables.sort();
+        variables.dedup();
+        if !variables.is_empty() {
+            let loop_head_span = self.mir_encoder.get_span_of_basic_block(loop_head);
+            self.encoder.error_manager().register_loop_havoc(
+                self.proc_def_id,
+                loop_head_span,
+                variables,
+            );
+        }
+    }
+
+    /// Render an encoded place in Rust syntax, for the error messages of the
+    /// fold-unfold algorithm. Temporary variables and the places that have no
+    /// Rust counterpart are rendered as in Viper.
+    fn rust_place(&self, place: &vir::Expr) -> String {
+        match place {
+            vir::Expr::Local(vir::Local { variable, .. }) => variable
+                .name
+                .strip_prefix('_')
+                .and_then(|index| index.parse::<usize>().ok())
+                .and_then(|index| {
+                    let local = mir::Local::from_usize(index);
+                    self.mir.var_debug_info.iter().find_map(|vdi| match vdi.value {
+                        mir::VarDebugInfoContents::Place(var_place)
+                            if var_place.as_local() == Some(local) =>
+                        {
+                            Some(vdi.name.to_ident_string())
+                        }
+                        _ => None,
+                    })
+                })
+                .unwrap_or_else(|| variable.name.clone()),
+            vir::Expr::Field(vir::FieldExpr { base, field, .. }) => {
+                let base = self.rust_place(base);
+                // Fields are accessed through references by auto-deref.
+                let receiver = base.strip_prefix('*').unwrap_or(&base);
+                if field.name == "val_ref" {
+                    format!("*{}", base)
+                } else if let Some(name) = field.name.strip_prefix("f$") {
+                    format!("{}.{}", receiver, name)
+                } else if let Some(index) = field.name.strip_prefix("tuple_") {
+                    format!("{}.{}", receiver, index)
+                } else {
+                    // The values of primitive types, the discriminant and the
+                    // data of an enumeration belong to the place itself.
+                    base
+                }
+            }
+            vir::Expr::Variant(vir::Variant { base, .. })
+            | vir::Expr::LabelledOld(vir::LabelledOld { base, .. }) => self.rust_place(base),
+            _ => place.to_string(),
+        }
+    }
+
+    /// Describe the Rust operation that generated a statement, for the error
+    /// messages of the fold-unfold algorithm.
+    fn describe_stmt(&self, stmt: &vir::Stmt) -> String {
+        match stmt {
+            vir::Stmt::MethodCall(_) => "this call".to_string(),
+            vir::Stmt::Assign(vir::Assign { target, .. }) => {
+                format!("the assignment to `{}`", self.rust_place(target))
+            }
+            vir::Stmt::Fold(vir::Fold { arguments, .. })
+            | vir::Stmt::Unfold(vir::Unfold { arguments, .. }) if !arguments.is_empty() => {
+                format!("the ownership of `{}`", self.rust_place(&arguments[0]))
+            }
+            vir::Stmt::TransferPerm(vir::TransferPerm { left, right, .. }) => format!(
+                "the transfer of `{}` back to `{}`",
+                self.rust_place(left),
+                self.rust_place(right)
+            ),
+            vir::Stmt::PackageMagicWand(_) => "this reborrow".to_string(),
+            vir::Stmt::ApplyMagicWand(_) | vir::Stmt::ExpireBorrows(_) => {
+                "the expiration of these borrows".to_string()
+            }
+            vir::Stmt::Downcast(vir::Downcast { base, .. }) => {
+                format!("the match on `{}`", self.rust_place(base))
+            }
+            vir::Stmt::If(_) => "this conditional statement".to_string(),
+            _ => "this operation".to_string(),
+        }
+    }
+
     /// Encode a block.
     ///
     /// Returns:
@@ -879,6 +1250,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 complete_resolution = false;
             }
         }
+        if config::report_dead_code() && force_block_on_edge {
+            self.encode_reachability_checks(bbi, &targets_map);
+        }
         let unresolved_edges = if complete_resolution {
             // Resolve successor and return the edge blocks
             let curr_successor =
@@ -903,6 +1277,60 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok((curr_block, unresolved_edges))
     }
 
+    /// Check whether the branches of a Rust `if` or `match` are reachable, for
+    /// `REPORT_DEAD_CODE`. The check is an `assert false` at the beginning of
+    /// the edge block of each branch, which fails iff the branch is reachable.
+    /// It is guarded by an unconstrained boolean, so that the verification of
+    /// the branch continues in the other case.
+    fn encode_reachability_checks(
+        &mut self,
+        bbi: BasicBlockIndex,
+        edge_blocks: &FxHashMap<BasicBlockIndex, CfgBlockIndex>,
+    ) {
+        let terminator = self.mir[bbi].terminator();
+        if !matches!(terminator.kind, TerminatorKind::SwitchInt { .. })
+            || terminator.source_info.span.from_expansion()
+        {
+            return;
+        }
+        let mut edge_blocks: Vec<_> = edge_blocks.iter().collect();
+        edge_blocks.sort_by_key(|(&target, _)| target);
+        for (&target, &edge_block) in edge_blocks {
+            let target_data = &self.mir[target];
+            // Skip the branches that only drop a value, e.g. on a drop flag.
+            if target_data.statements.is_empty()
+                && matches!(target_data.terminator().kind, TerminatorKind::Drop { .. })
+            {
+                continue;
+            }
+            let opt_span = target_data.statements.iter()
+                .map(|stmt| stmt.source_info.span)
+                .chain(std::iter::once(target_data.terminator().source_info.span))
+                .find(|span| !span.from_expansion());
+            let span = match opt_span {
+                Some(span) => span,
+                None => continue,
+            };
+            let guard = self.cfg_method.add_fresh_local_var(vir::Type::Bool);
+            let pos = self.register_error(span, ErrorCtxt::ReachabilityCheck);
+            self.cfg_method.add_stmt(
+                edge_block,
+                vir::Stmt::comment(format!("Check whether {:?} is reachable", target)),
+            );
+            self.cfg_method.add_stmt(
+                edge_block,
+                vir::Stmt::If(vir::If {
+                    guard: guard.into(),
+                    then_stmts: vec![vir::Stmt::Assert(vir::Assert {
+                        expr: false.into(),
+                        position: pos,
+                    })],
+                    else_stmts: vec![],
+                }),
+            );
+        }
+    }
+
     /// Store a flag that becomes true the first time the block is executed
     fn encode_execution_flag(
         &mut self,
@@ -1039,7 +1467,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             stmt.kind, stmt.source_info.span
         );
 
-        let mut stmts = vec![vir::Stmt::comment(format!("[mir] {:?}", stmt))];
+        let mut stmts: Vec<vir::Stmt> = self.encode_source_comment(stmt.source_info.span)
+            .into_iter()
+            .collect();
+        stmts.push(vir::Stmt::comment(format!("[mir] {:?}", stmt)));
         let span = self.mir_encoder.get_span_of_location(location);
 
         let encoding_stmts = match stmt.kind {
@@ -1086,6 +1517,27 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok(self.set_stmts_default_pos(stmts, stmt.source_info.span))
     }
 
+    /// When the Viper program is dumped, encode a comment with the location and
+    /// the first line of the Rust source code of a statement, unless it is
+    /// the same as the one of the previous statement.
+    fn encode_source_comment(&mut self, span: Span) -> Option<vir::Stmt> {
+        if !config::dump_viper_program() {
+            return None;
+        }
+        let span = span.source_callsite();
+        if span.is_dummy() || self.last_source_comment_span == Some(span) {
+            return None;
+        }
+        self.last_source_comment_span = Some(span);
+        let source_map = self.encoder.env().codemap();
+        let location = source_map.span_to_diagnostic_string(span);
+        let snippet = source_map.span_to_snippet(span).unwrap_or_default();
+        let mut lines = snippet.lines();
+        let first_line = lines.next().unwrap_or_default().trim();
+        let ellipsis = if lines.next().is_some() { " ..." } else { "" };
+        Some(vir::Stmt::comment(format!("[rust] {}: {}{}", location, first_line, ellipsis)))
+    }
+
     fn set_stmts_default_pos(&self, stmts: Vec<vir::Stmt>, default_span: Span) -> Vec<vir::Stmt> {
         let pos = self.encoder.error_manager().register_span(self.proc_def_id, default_span);
         stmts
@@ -1752,20 +2204,34 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     self.mir.source_info(loan_location).span, // the source of the ref
                     ErrorCtxt::ApplyMagicWandOnExpiry,
                 );
-                // Inhale the magic wand.
-                let magic_wand = vir::Expr::MagicWand( vir::MagicWand {
-                    left: box lhs.clone(),
-                    right: box rhs.clone(),
-                    borrow: Some(loan.index().into()),
-                    position: pos,
-                });
-                stmts.push(vir::Stmt::Inhale( vir::Inhale {
-                    expr: magic_wand
-                }));
-                // Emit the apply statement.
-                let statement = vir::Stmt::apply_magic_wand(lhs, rhs, loan.index().into(), pos);
-                debug!("{:?} at {:?}", statement, loan_location);
-                stmts.push(statement);
+                if self.encoder.env().has_prusti_attribute(contract.def_id, "explicit_expiry") {
+                    // The callee opted out of magic wands: the expiry of the
+                    // returned borrow is an obligation to give back the
+                    // blocking places (framed by the labels of the call),
+                    // after which the blocked places are regained.
+                    stmts.push(vir::Stmt::Exhale(vir::Exhale {
+                        expr: lhs,
+                        position: pos,
+                    }));
+                    stmts.push(vir::Stmt::Inhale(vir::Inhale {
+                        expr: rhs,
+                    }));
+                } else {
+                    // Inhale the magic wand.
+                    let magic_wand = vir::Expr::MagicWand( vir::MagicWand {
+                        left: box lhs.clone(),
+                        right: box rhs.clone(),
+                        borrow: Some(loan.index().into()),
+                        position: pos,
+                    });
+                    stmts.push(vir::Stmt::Inhale( vir::Inhale {
+                        expr: magic_wand
+                    }));
+                    // Emit the apply statement.
+                    let statement = vir::Stmt::apply_magic_wand(lhs, rhs, loan.index().into(), pos);
+                    debug!("{:?} at {:?}", statement, loan_location);
+                    stmts.push(statement);
+                }
             }
         }
 
@@ -1842,7 +2308,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             "Encode terminator '{:?}', span: {:?}",
             term.kind, term.source_info.span
         );
-        let mut stmts: Vec<vir::Stmt> = vec![vir::Stmt::comment(format!("[mir] {:?}", term.kind))];
+        let mut stmts: Vec<vir::Stmt> = self.encode_source_comment(term.source_info.span)
+            .into_iter()
+            .collect();
+        stmts.push(vir::Stmt::comment(format!("[mir] {:?}", term.kind)));
         let span = self.mir_encoder.get_span_of_location(location);
 
         let result = match term.kind {
@@ -1947,9 +2416,11 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 if self.procedure.is_spec_block(default_target) {
                     stmts.push(vir::Stmt::comment(format!(
                         "Ignore default target {:?}, as it is only used by Prusti to type-check \
-                        a loop invariant.",
+                        a specification.",
                         default_target
                     )));
+                    stmts.extend(self.encode_proof_steps(default_target)?);
+                    stmts.extend(self.encode_loop_invariants_in_body(location.block, default_target)?);
                     kill_default_target = true;
                 };
 
@@ -2043,6 +2514,30 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
 
                     let full_func_proc_name: &str =
                         &self.encoder.env().tcx().def_path_str(called_def_id);
+                    let cmp_function = CmpFunction::from_call(
+                        self.encoder.env().tcx(),
+                        full_func_proc_name,
+                        call_substs,
+                    );
+
+                    if config::strict_panic_freedom() {
+                        stmts.extend(self.encode_panic_obligation(
+                            full_func_proc_name,
+                            called_def_id,
+                            call_substs,
+                            term.source_info.span,
+                            args,
+                        )?);
+                    }
+
+                    if config::enable_generators() {
+                        stmts.extend(self.encode_resume_precondition_check(
+                            full_func_proc_name,
+                            call_substs,
+                            args,
+                            term.source_info.span,
+                        )?);
+                    }
 
                     match full_func_proc_name {
                         "std::rt::begin_panic"
@@ -2116,6 +2611,34 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                             );
                         }
 
+                        "std::thread::spawn" => {
+                            debug!("Encoding call of thread::spawn");
+                            stmts.extend(
+                                self.encode_thread_spawn(
+                                    location,
+                                    term.source_info.span,
+                                    args,
+                                    destination,
+                                    called_def_id,
+                                    call_substs,
+                                )?
+                            );
+                        }
+
+                        "std::thread::JoinHandle::<T>::join" => {
+                            debug!("Encoding call of JoinHandle::join");
+                            stmts.extend(
+                                self.encode_thread_join(
+                                    location,
+                                    term.source_info.span,
+                                    args,
+                                    destination,
+                                    called_def_id,
+                                    call_substs,
+                                )?
+                            );
+                        }
+
                         "std::cmp::PartialEq::eq" |
                         "core::cmp::PartialEq::eq"
                             if args.len() == 2 &&
@@ -2158,8 +2681,108 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                             );
                         }
 
+                        _ if cmp_function.is_some() => {
+                            debug!("Encoding call of {}", full_func_proc_name);
+                            stmts.extend(
+                                self.encode_builtin_cmp_call(
+                                    cmp_function.unwrap(),
+                                    location,
+                                    term.source_info.span,
+                                    args,
+                                    destination,
+                                )?
+                            );
+                        }
+
+                        "std::process::exit"
+                        | "std::process::abort" => {
+                            // The program terminates without unwinding, so
+                            // neither the rest of the function nor its
+                            // postcondition are reached.
+                            stmts.push(vir::Stmt::comment(format!(
+                                "Terminate the program with {}",
+                                full_func_proc_name
+                            )));
+                        }
+
+                        "std::ops::Try::branch"
+                        | "core::ops::Try::branch"
+                        | "std::ops::FromResidual::from_residual"
+                        | "core::ops::FromResidual::from_residual" => {
+                            debug!("Encoding call of {}", full_func_proc_name);
+                            let is_branch = full_func_proc_name.ends_with("::branch");
+                            let try_stmts = self.encode_builtin_try_call(
+                                is_branch,
+                                location,
+                                term.source_info.span,
+                                args,
+                                destination,
+                                call_substs,
+                            )?;
+                            if let Some(try_stmts) = try_stmts {
+                                stmts.extend(try_stmts);
+                            } else {
+                                let (called_def_id, call_substs) = self.encoder.env()
+                                    .resolve_method_call(self.proc_def_id, called_def_id, call_substs);
+                                stmts.extend(
+                                    self.encode_impure_function_call(
+                                        location,
+                                        term.source_info.span,
+                                        args,
+                                        destination,
+                                        called_def_id,
+                                        call_substs,
+                                    )?
+                                );
+                            }
+                        }
+
+                        "prusti_contracts::runtime_check_failed" => {
+                            // The checked contract does not hold, but the
+                            // check panics at runtime; the verification does
+                            // not continue after the call, so the contract is
+                            // assumed after the check.
+                            stmts.push(vir::Stmt::comment("Failed runtime check"));
+                        }
+
+                        "prusti_contracts::reveal" => {
+                            debug!("Encoding call of reveal");
+                            let revealed_ty = call_substs.type_at(0);
+                            match revealed_ty.kind() {
+                                ty::TyKind::FnDef(revealed_def_id, revealed_substs)
+                                    if self.encoder.is_pure(*revealed_def_id) &&
+                                        self.encoder.env().has_prusti_attribute(*revealed_def_id, "opaque")
+                                => {
+                                    let definition = self.encoder
+                                        .encode_pure_function_definition(
+                                            *revealed_def_id,
+                                            self.proc_def_id,
+                                            *revealed_substs,
+                                        )
+                                        .with_default_span(term.source_info.span)?;
+                                    stmts.push(vir::Stmt::comment(format!(
+                                        "Reveal the definition of {}",
+                                        self.encoder.env().tcx().def_path_str(*revealed_def_id)
+                                    )));
+                                    stmts.push(vir::Stmt::Inhale( vir::Inhale {
+                                        expr: definition,
+                                    }));
+                                }
+                                _ => {
+                                    return Err(SpannedEncodingError::incorrect(
+                                        "only #[opaque] pure functions can be revealed",
+                                        term.source_info.span,
+                                    ));
+                                }
+                            }
+                        }
+
                         "std::ops::Fn::call"
-                        | "core::ops::Fn::call" => {
+                        | "core::ops::Fn::call"
+                        | "std::ops::FnMut::call_mut"
+                        | "core::ops::FnMut::call_mut"
+                        | "std::ops::FnOnce::call_once"
+                        | "core::ops::FnOnce::call_once" => {
                             let cl_type: ty::Ty = call_substs[0].expect_ty();
                             match cl_type.kind() {
                                 ty::TyKind::Closure(cl_def_id, _) => {
@@ -2231,9 +2854,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                         _ => {
                             // The called method might be a trait method.
                             // We try to resolve it to the concrete implementation
-                            // and type substitutions.
+                            // and type substitutions. `x.into()` calls `U::from(x)`,
+                            // so it has the contract of the implementation of `From`.
                             let (called_def_id, call_substs) = self.encoder.env()
-                                .resolve_method_call(self.proc_def_id, called_def_id, call_substs);
+                                .resolve_conversion_call(self.proc_def_id, called_def_id, call_substs)
+                                .unwrap_or_else(|| self.encoder.env()
+                                    .resolve_method_call(self.proc_def_id, called_def_id, call_substs));
 
                             let is_pure_function = self.encoder.is_pure(called_def_id) &&
                                 // We are verifying this pure function and,
@@ -2353,12 +2979,232 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 (stmts, MirSuccessor::Goto(target))
             }
 
-            TerminatorKind::Resume
-            | TerminatorKind::Yield { .. }
-            | TerminatorKind::GeneratorDrop
-            | TerminatorKind::InlineAsm { .. } => unimplemented!("{:?}", term.kind),
-        };
-        Ok(result)
+            TerminatorKind::Yield {
+                resume,
+                resume_arg,
+                ..
+            } => {
+                if !config::enable_generators() {
+                    return Err(SpannedEncodingError::unsupported(
+                        "generators are not supported; enable the ENABLE_GENERATORS flag to \
+                        verify them",
+                        term.source_info.span,
+                    ));
+                }
+
+                // The generator is suspended: its yield invariants must hold
+                stmts.push(vir::Stmt::comment("Assert the yield invariants"));
+                let yield_invariants = self.encode_yield_invariants()?;
+                for invariant in &yield_invariants {
+                    stmts.push(vir::Stmt::Assert( vir::Assert {
+                        expr: invariant.clone(),
+                        position: self.register_error(
+                            term.source_info.span,
+                            ErrorCtxt::AssertYieldInvariant,
+                        ),
+                    }));
+                }
+
+                // The generator is resumed with an arbitrary argument that
+                // satisfies its resume preconditions. Its state is owned by
+                // the generator, so it is unchanged while the generator is
+                // suspended.
+                let (encoded_resume_arg, encode_stmts, _, _) = self.encode_place(
+                    &resume_arg,
+                    ArrayAccessKind::Shared,
+                    location,
+                )?;
+                stmts.extend(encode_stmts);
+                stmts.extend(self.encode_havoc_and_initialization(&encoded_resume_arg));
+                stmts.push(vir::Stmt::comment("Assume the resume preconditions"));
+                let resume_arg_local = self.encode_resume_arg_local(self.procedure)?;
+                for precondition in self.encode_resume_preconditions(self.procedure)? {
+                    stmts.push(vir::Stmt::Inhale( vir::Inhale {
+                        expr: precondition.replace_place(&resume_arg_local, &encoded_resume_arg),
+                    }));
+                }
+
+                // The yield invariants hold after the generator is resumed,
+                // unless they depend on the place that receives the resume
+                // argument.
+                stmts.push(vir::Stmt::comment("Assume the yield invariants"));
+                let resume_arg_base = vir::Expr::local(encoded_resume_arg.get_base());
+                for invariant in yield_invariants {
+                    if !invariant.find(&resume_arg_base) {
+                        stmts.push(vir::Stmt::Inhale( vir::Inhale {
+                            expr: invariant,
+                        }));
+                    }
+                }
+
+                // The generator may also be dropped while it is suspended,
+                // which does not need to be verified.
+                (stmts, MirSuccessor::Goto(resume))
+            }
+
+            TerminatorKind::Resume
+            | TerminatorKind::GeneratorDrop
+            | TerminatorKind::InlineAsm { .. } => unimplemented!("{:?}", term.kind),
+        };
+        Ok(result)
+    }
+
+    /// Encode a call of `std::thread::spawn`. The precondition of the spawned
+    /// closure is checked at the call, and the closure moves the permissions
+    /// it captured into the returned join handle.
+    fn encode_thread_spawn(
+        &mut self,
+        location: mir::Location,
+        span: Span,
+        args: &[mir::Operand<'tcx>],
+        destination: &Option<(mir::Place<'tcx>, BasicBlockIndex)>,
+        called_def_id: ProcedureDefId,
+        call_substs: SubstsRef<'tcx>,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        // args[0]: the closure run by the new thread
+        assert_eq!(args.len(), 1);
+        let cl_ty = self.mir_encoder.get_operand_ty(&args[0]);
+        let cl_def_id = if let ty::TyKind::Closure(cl_def_id, _) = cl_ty.kind() {
+            *cl_def_id
+        } else {
+            return Err(SpannedEncodingError::unsupported(
+                format!("only closures can be spawned as threads, not {:?}", cl_ty.kind()),
+                span,
+            ));
+        };
+        let cl_local: Local = args[0].place()
+            .and_then(|place| place.as_local())
+            .map_or_else(
+                || self.locals.get_fresh(cl_ty),
+                |local| local.into()
+            );
+        let result_local = self.locals.get_fresh(call_substs.type_at(1));
+        let contract = self.encoder.get_procedure_contract_for_call(
+            self.proc_def_id,
+            cl_def_id,
+            &[cl_local],
+            result_local,
+            call_substs,
+        ).with_span(span)?;
+        let encoded_args: Vec<vir::Expr> = vec![self.encode_prusti_local(cl_local).into()];
+
+        let mut stmts = vec![];
+        let pos = self.register_error(span, ErrorCtxt::ExhaleMethodPrecondition);
+        for (assertion, assertion_substs) in contract.functional_precondition(self.encoder.env(), call_substs) {
+            let expr = self.encoder.encode_assertion(
+                &assertion,
+                None,
+                &encoded_args,
+                None,
+                false,
+                self.proc_def_id,
+                assertion_substs,
+            )?;
+            stmts.push(vir::Stmt::Assert( vir::Assert {
+                expr,
+                position: pos,
+            }));
+        }
+
+        stmts.extend(
+            self.encode_impure_function_call(
+                location,
+                span,
+                args,
+                destination,
+                called_def_id,
+                call_substs,
+            )?
+        );
+
+        // Remember the state in which the thread was spawned, so that the
+        // postcondition of the closure can be inhaled when it is joined.
+        if let Some(handle) = destination.as_ref().and_then(|(place, _)| place.as_local()) {
+            let spawn_label = self.label_after_location[&location].clone();
+            self.spawned_threads.insert(handle, (cl_def_id, call_substs, cl_local, spawn_label));
+        }
+        Ok(stmts)
+    }
+
+    /// Encode a call of `std::thread::JoinHandle::join`. If the handle was
+    /// returned by `std::thread::spawn` in this procedure, joining is known to
+    /// succeed because the closure does not panic, and the postcondition of
+    /// the closure holds for the value wrapped in `Ok`.
+    fn encode_thread_join(
+        &mut self,
+        location: mir::Location,
+        span: Span,
+        args: &[mir::Operand<'tcx>],
+        destination: &Option<(mir::Place<'tcx>, BasicBlockIndex)>,
+        called_def_id: ProcedureDefId,
+        call_substs: SubstsRef<'tcx>,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        // args[0]: the join handle
+        assert_eq!(args.len(), 1);
+        let mut stmts = self.encode_impure_function_call(
+            location,
+            span,
+            args,
+            destination,
+            called_def_id,
+            call_substs,
+        )?;
+
+        let spawned = args[0].place()
+            .and_then(|place| place.as_local())
+            .and_then(|handle| self.spawned_threads.get(&handle).cloned());
+        let (cl_def_id, cl_substs, cl_local, spawn_label) = match spawned {
+            Some(spawned) if self.check_panics => spawned,
+            _ => return Ok(stmts),
+        };
+        let (target_place, _) = destination.as_ref().unwrap();
+        let (dst, pre_stmts, dst_ty, _) = self.encode_place(target_place, ArrayAccessKind::Shared, location)?;
+        stmts.extend(pre_stmts);
+        let (adt_def, result_substs) = if let ty::TyKind::Adt(adt_def, substs) = dst_ty.kind() {
+            (*adt_def, substs)
+        } else {
+            unreachable!("join returns a Result, not {:?}", dst_ty);
+        };
+
+        // The result is `Ok`.
+        let tcx = self.encoder.env().tcx();
+        let ok_index: rustc_target::abi::VariantIdx = 0usize.into();
+        let ok_variant = &adt_def.variants()[ok_index];
+        let discriminant = self.encoder.encode_discriminant_func_app(dst.clone(), adt_def)?;
+        stmts.push(vir::Stmt::Inhale( vir::Inhale {
+            expr: vir::Expr::eq_cmp(
+                discriminant,
+                adt_def.discriminant_for_variant(tcx, ok_index).val.into(),
+            ),
+        }));
+
+        // The value returned by the closure satisfies its postcondition.
+        let payload_ty = ok_variant.fields[0].ty(tcx, result_substs);
+        let payload_field = self.encoder.encode_struct_field("0", payload_ty)
+            .with_span(span)?;
+        let payload = dst.variant(&ok_variant.ident(tcx).to_string()).field(payload_field);
+        let result_local = self.locals.get_fresh(payload_ty);
+        let contract = self.encoder.get_procedure_contract_for_call(
+            self.proc_def_id,
+            cl_def_id,
+            &[cl_local],
+            result_local,
+            cl_substs,
+        ).with_span(span)?;
+        let encoded_args: Vec<vir::Expr> = vec![self.encode_prusti_local(cl_local).into()];
+        for (assertion, assertion_substs) in contract.functional_postcondition(self.encoder.env(), cl_substs) {
+            let expr = self.encoder.encode_assertion(
+                &assertion,
+                Some(spawn_label.as_str()),
+                &encoded_args,
+                Some(&payload),
+                false,
+                self.proc_def_id,
+                assertion_substs,
+            )?;
+            stmts.push(vir::Stmt::Inhale( vir::Inhale { expr }));
+        }
+        Ok(stmts)
     }
 
     fn encode_slice_len_call(
@@ -2507,6 +3353,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             "std::ops::RangeToInclusive" | "core::ops::RangeToInclusive" => vir::Expr::from(0usize),
             _ => unreachable!("{}", idx_ident)
         };
+        let base_len = enc_sequence_types.len(self.encoder, base_seq_expr);
         let end = match &*idx_ident {
             "std::ops::Range" | "core::ops::Range" |
             "std::ops::RangeTo" | "core::ops::RangeTo" =>
@@ -2519,14 +3366,24 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 vir_expr!{ [end_expr] + [vir::Expr::from(1usize)] }
             }
             "std::ops::RangeFrom" | "core::ops::RangeFrom" |
-            "std::ops::RangeFull" | "core::ops::RangeFull" => {
-                enc_sequence_types.len(self.encoder, base_seq_expr)
-            }
+            "std::ops::RangeFull" | "core::ops::RangeFull" => base_len.clone(),
             _ => unreachable!("{}", idx_ident)
         };
 
         trace!("start: {}, end: {}", start, end);
 
+        if config::strict_panic_freedom() {
+            // Slicing panics if the range is not within the bounds of the sequence.
+            let span = self.mir_encoder.get_span_of_location(location);
+            stmts.push(vir::Stmt::Assert( vir::Assert {
+                expr: vir::Expr::and(
+                    vir::Expr::le_cmp(start.clone(), end.clone()),
+                    vir::Expr::le_cmp(end.clone(), base_len),
+                ),
+                position: self.register_error(span, ErrorCtxt::BoundsCheckAssert),
+            }));
+        }
+
         let slice_types_lhs = self.encoder.encode_sequence_types(lhs_slice_ty)?;
         let elem_snap_ty = self.encoder.encode_snapshot_type(slice_types_lhs.elem_ty_rs)?;
 
@@ -2656,6 +3513,323 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         }
     }
 
+    /// Encode a call of a comparison function of integers or characters, or
+    /// of a method of `Ordering`, by inhaling its result.
+    fn encode_builtin_cmp_call(
+        &mut self,
+        cmp_function: CmpFunction,
+        location: mir::Location,
+        call_site_span: Span,
+        args: &[mir::Operand<'tcx>],
+        destination: &Option<(mir::Place<'tcx>, BasicBlockIndex)>,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let mut cmp_args = vec![];
+        for arg in args {
+            let encoded_arg = self.mir_encoder.encode_operand_expr(arg)
+                .with_span(call_site_span)?;
+            cmp_args.push(match self.mir_encoder.get_operand_ty(arg).kind() {
+                // An `Ordering`
+                ty::TyKind::Adt(adt_def, _) => {
+                    self.encoder.encode_discriminant_func_app(encoded_arg, *adt_def)?
+                }
+                _ => vir::Expr::snap_app(encoded_arg),
+            });
+        }
+
+        let mut stmts = vec![];
+        if let Some(precondition) = cmp_function.precondition(&cmp_args) {
+            if self.check_panics {
+                let pos = self.register_error(
+                    call_site_span,
+                    ErrorCtxt::Panic(PanicCause::Generic),
+                );
+                stmts.push(vir::Stmt::Assert( vir::Assert {
+                    expr: precondition,
+                    position: pos,
+                }));
+            } else {
+                // The call does not return if it panics.
+                stmts.push(vir::Stmt::Inhale( vir::Inhale { expr: precondition }));
+            }
+        }
+
+        let (target_place, pre_stmts, target_ty, _) = self.encode_place(
+            &destination.as_ref().unwrap().0,
+            ArrayAccessKind::Shared,
+            location,
+        )?;
+        stmts.extend(pre_stmts);
+        let inhaled_expr = match cmp_function.encode_result(cmp_args) {
+            CmpResult::Value(expr) => {
+                let target_value = self.encoder.encode_value_expr(target_place, target_ty)
+                    .with_span(call_site_span)?;
+                vir::Expr::eq_cmp(target_value, expr)
+            }
+            CmpResult::Ordering(discriminant) => {
+                let adt_def = target_ty.ty_adt_def().unwrap();
+                vir::Expr::eq_cmp(
+                    self.encoder.encode_discriminant_func_app(target_place, adt_def)?,
+                    discriminant,
+                )
+            }
+            CmpResult::SomeOrdering(discriminant) => {
+                let (option_adt_def, ordering_ty) = match target_ty.kind() {
+                    ty::TyKind::Adt(adt_def, substs) => (*adt_def, substs.type_at(0)),
+                    _ => unreachable!("partial_cmp returns an Option, not {:?}", target_ty),
+                };
+                let ordering_field = self.encoder.encode_struct_field("0", ordering_ty)
+                    .with_span(call_site_span)?;
+                let ordering = target_place.clone().variant("Some").field(ordering_field);
+                vir::Expr::and(
+                    vir::Expr::eq_cmp(
+                        self.encoder.encode_discriminant_func_app(target_place, option_adt_def)?,
+                        variant_discriminant(self.encoder, target_ty, 1).into(),
+                    ),
+                    vir::Expr::eq_cmp(
+                        self.encoder.encode_discriminant_func_app(
+                            ordering,
+                            ordering_ty.ty_adt_def().unwrap(),
+                        )?,
+                        discriminant,
+                    ),
+                )
+            }
+        };
+
+        let (call_stmts, label) = self.encode_pure_function_call_site(
+            location,
+            destination,
+            inhaled_expr,
+        )?;
+        stmts.extend(call_stmts);
+
+        self.encode_transfer_args_permissions(location, args,  &mut stmts, label, false)?;
+
+        Ok(stmts)
+    }
+
+    /// With `check_panics=strict`, assert that the called standard library
+    /// function does not panic, unless the user gave it a specification.
+    /// `unwrap()` and `expect(..)` must be called on a `Some` or an `Ok`, and
+    /// `unwrap_err()` and `expect_err(..)` on an `Err`. The other functions
+    /// that panic on some arguments, such as `Vec::remove`, are only known
+    /// not to panic through their specification.
+    fn encode_panic_obligation(
+        &self,
+        full_func_proc_name: &str,
+        called_def_id: ProcedureDefId,
+        call_substs: SubstsRef<'tcx>,
+        call_site_span: Span,
+        args: &[mir::Operand<'tcx>],
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        if self.encoder.has_extern_spec(called_def_id) {
+            return Ok(vec![]);
+        }
+        // The variants `Some` of an `Option`, and `Ok` and `Err` of a `Result`.
+        let (value_variant, cause) = match full_func_proc_name {
+            "std::option::Option::<T>::unwrap"
+            | "std::option::Option::<T>::expect"
+            | "core::option::Option::<T>::unwrap"
+            | "core::option::Option::<T>::expect" => (Some(1), PanicCause::Unwrap),
+            "std::result::Result::<T, E>::unwrap"
+            | "std::result::Result::<T, E>::expect"
+            | "core::result::Result::<T, E>::unwrap"
+            | "core::result::Result::<T, E>::expect" => (Some(0), PanicCause::Unwrap),
+            "std::result::Result::<T, E>::unwrap_err"
+            | "std::result::Result::<T, E>::expect_err"
+            | "core::result::Result::<T, E>::unwrap_err"
+            | "core::result::Result::<T, E>::expect_err" => (Some(1), PanicCause::UnwrapErr),
+            "std::ops::Index::index"
+            | "std::ops::IndexMut::index_mut"
+            | "core::ops::Index::index"
+            | "core::ops::IndexMut::index_mut" => {
+                // Indexing a collection of the standard library, such as a
+                // `Vec` or a `HashMap`, panics if the index is not in it. The
+                // bounds of arrays and slices are checked by their encoding.
+                let base_ty = self.mir_encoder.get_operand_ty(&args[0]).peel_refs();
+                if matches!(base_ty.kind(), ty::TyKind::Array(..) | ty::TyKind::Slice(..)) {
+                    return Ok(vec![]);
+                }
+                let (resolved_def_id, _) = self.encoder.env().resolve_method_call(
+                    self.proc_def_id,
+                    called_def_id,
+                    call_substs,
+                );
+                if resolved_def_id == called_def_id
+                    || resolved_def_id.is_local()
+                    || self.encoder.has_extern_spec(resolved_def_id)
+                {
+                    return Ok(vec![]);
+                }
+                (None, PanicCause::StdCall)
+            }
+            _ if is_panicking_std_function(full_func_proc_name) => (None, PanicCause::StdCall),
+            _ => return Ok(vec![]),
+        };
+        let expr = match value_variant {
+            Some(value_variant) => {
+                let self_ty = self.mir_encoder.get_operand_ty(&args[0]);
+                let self_adt_def = self_ty.ty_adt_def().unwrap();
+                let arg = self.mir_encoder.encode_operand_expr(&args[0])
+                    .with_span(call_site_span)?;
+                vir::Expr::eq_cmp(
+                    self.encoder.encode_discriminant_func_app(arg, self_adt_def)?,
+                    variant_discriminant(self.encoder, self_ty, value_variant).into(),
+                )
+            }
+            None => false.into(),
+        };
+        Ok(vec![
+            vir::Stmt::comment(format!("Rust panic - {} might panic", full_func_proc_name)),
+            vir::Stmt::Assert( vir::Assert {
+                expr,
+                position: self.register_error(call_site_span, ErrorCtxt::Panic(cause)),
+            }),
+        ])
+    }
+
+    /// Encode a call of `Try::branch` or `FromResidual::from_residual`, which
+    /// implement the `?` operator, on a `Result` or an `Option` by inhaling its
+    /// result. Returns `None` if the call has no built-in encoding, e.g.
+    /// because the error is converted with `From::from`.
+    fn encode_builtin_try_call(
+        &mut self,
+        is_branch: bool,
+        location: mir::Location,
+        call_site_span: Span,
+        args: &[mir::Operand<'tcx>],
+        destination: &Option<(mir::Place<'tcx>, BasicBlockIndex)>,
+        call_substs: SubstsRef<'tcx>,
+    ) -> SpannedEncodingResult<Option<Vec<vir::Stmt>>> {
+        let tcx = self.encoder.env().tcx();
+        // The `Result` or `Option` on which `?` is applied, or which is
+        // returned early.
+        let self_ty = call_substs.type_at(0);
+        let (self_adt_def, self_substs) = match self_ty.kind() {
+            ty::TyKind::Adt(adt_def, substs) => (*adt_def, *substs),
+            _ => return Ok(None),
+        };
+        let is_result = match tcx.def_path_str(self_adt_def.did()).as_str() {
+            "std::result::Result" | "core::result::Result" => true,
+            "std::option::Option" | "core::option::Option" => false,
+            _ => return Ok(None),
+        };
+        let value_ty = self_substs.type_at(0);
+        let error_ty = if is_result { Some(self_substs.type_at(1)) } else { None };
+        for ty in std::iter::once(value_ty).chain(error_ty) {
+            if !self.encoder.supports_snapshot_equality(ty).with_span(call_site_span)? {
+                return Ok(None);
+            }
+        }
+        // The variants `Err` of a `Result` and `None` of an `Option`.
+        let error_variant = if is_result { 1 } else { 0 };
+
+        let arg = self.mir_encoder.encode_operand_expr(&args[0])
+            .with_span(call_site_span)?;
+        let (target_place, pre_stmts, target_ty, _) = self.encode_place(
+            &destination.as_ref().unwrap().0,
+            ArrayAccessKind::Shared,
+            location,
+        )?;
+        let target_adt_def = target_ty.ty_adt_def().unwrap();
+
+        let inhaled_expr = if is_branch {
+            // `branch` returns `ControlFlow::Continue` of the value of an `Ok`
+            // or a `Some`, and `ControlFlow::Break` of the residual otherwise.
+            let residual_ty = match target_ty.kind() {
+                ty::TyKind::Adt(_, substs) => substs.type_at(0),
+                _ => unreachable!("branch returns a ControlFlow, not {:?}", target_ty),
+            };
+            let value_field = self.encoder.encode_struct_field("0", value_ty)
+                .with_span(call_site_span)?;
+            let residual_field = self.encoder.encode_struct_field("0", residual_ty)
+                .with_span(call_site_span)?;
+            let residual = target_place.clone().variant("Break").field(residual_field);
+            let is_value = vir::Expr::ne_cmp(
+                self.encoder.encode_discriminant_func_app(arg.clone(), self_adt_def)?,
+                variant_discriminant(self.encoder, self_ty, error_variant).into(),
+            );
+            let continue_expr = vir::Expr::and(
+                vir::Expr::eq_cmp(
+                    self.encoder.encode_discriminant_func_app(target_place.clone(), target_adt_def)?,
+                    variant_discriminant(self.encoder, target_ty, 0).into(),
+                ),
+                vir::Expr::eq_cmp(
+                    vir::Expr::snap_app(
+                        target_place.clone().variant("Continue").field(value_field.clone())
+                    ),
+                    vir::Expr::snap_app(
+                        arg.clone().variant(if is_result { "Ok" } else { "Some" }).field(value_field)
+                    ),
+                ),
+            );
+            let mut break_expr = vir::Expr::and(
+                vir::Expr::eq_cmp(
+                    self.encoder.encode_discriminant_func_app(target_place.clone(), target_adt_def)?,
+                    variant_discriminant(self.encoder, target_ty, 1).into(),
+                ),
+                vir::Expr::eq_cmp(
+                    self.encoder.encode_discriminant_func_app(
+                        residual.clone(),
+                        residual_ty.ty_adt_def().unwrap(),
+                    )?,
+                    variant_discriminant(self.encoder, residual_ty, error_variant).into(),
+                ),
+            );
+            if let Some(error_ty) = error_ty {
+                let error_field = self.encoder.encode_struct_field("0", error_ty)
+                    .with_span(call_site_span)?;
+                break_expr = vir::Expr::and(
+                    break_expr,
+                    vir::Expr::eq_cmp(
+                        vir::Expr::snap_app(residual.variant("Err").field(error_field.clone())),
+                        vir::Expr::snap_app(arg.variant("Err").field(error_field)),
+                    ),
+                );
+            }
+            vir::Expr::ite(is_value, continue_expr, break_expr)
+        } else {
+            // `from_residual` returns the `Err` or `None` of the residual.
+            let mut expr = vir::Expr::eq_cmp(
+                self.encoder.encode_discriminant_func_app(target_place.clone(), target_adt_def)?,
+                variant_discriminant(self.encoder, target_ty, error_variant).into(),
+            );
+            if let Some(error_ty) = error_ty {
+                let residual_ty = call_substs.type_at(1);
+                let residual_error_ty = match residual_ty.kind() {
+                    ty::TyKind::Adt(_, substs) => substs.type_at(1),
+                    _ => return Ok(None),
+                };
+                if residual_error_ty != error_ty {
+                    // The error is converted with `From::from`.
+                    return Ok(None);
+                }
+                let error_field = self.encoder.encode_struct_field("0", error_ty)
+                    .with_span(call_site_span)?;
+                expr = vir::Expr::and(
+                    expr,
+                    vir::Expr::eq_cmp(
+                        vir::Expr::snap_app(target_place.variant("Err").field(error_field.clone())),
+                        vir::Expr::snap_app(arg.variant("Err").field(error_field)),
+                    ),
+                );
+            }
+            expr
+        };
+
+        let mut stmts = pre_stmts;
+        let (call_stmts, label) = self.encode_pure_function_call_site(
+            location,
+            destination,
+            inhaled_expr,
+        )?;
+        stmts.extend(call_stmts);
+
+        self.encode_transfer_args_permissions(location, args, &mut stmts, label, false)?;
+
+        Ok(Some(stmts))
+    }
+
     /// Encode an edge of the MIR graph
     fn encode_edge_block(
         &mut self,
@@ -2711,6 +3885,32 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             // .absolute_item_path_str(called_def_id);
         debug!("Encoding non-pure function call '{}' with args {:?} and substs {:?}", full_func_proc_name, mir_args, substs);
 
+        // The other verified functions assume the axioms of the lemmas, so a
+        // lemma could prove its own axiom by calling them.
+        if self.is_lemma()
+            && called_def_id.is_local()
+            && !self.encoder.env().has_prusti_attribute(called_def_id, "lemma")
+            && !self.encoder.is_trusted(called_def_id)
+        {
+            return Err(SpannedEncodingError::incorrect(
+                format!(
+                    "lemmas can only call other lemmas and trusted functions, but `{}` is neither",
+                    full_func_proc_name,
+                ),
+                call_site_span,
+            ));
+        }
+
+        // Ghost integers have a built-in encoding only in specifications and
+        // pure functions; at runtime, their operations may panic.
+        let tcx = self.encoder.env().tcx();
+        if GhostIntFunction::from_call(tcx, full_func_proc_name, substs).is_some() {
+            return Err(SpannedEncodingError::unsupported(
+                "ghost integers can only be used in specifications and pure functions",
+                call_site_span,
+            ));
+        }
+
         // First we construct the "operands" vector. This construction differs
         // for closure calls, where we need to unpack a tuple into the actual
         // call arguments. The components of the operands tuples are:
@@ -2941,6 +4141,17 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             stmts.extend(self.encode_havoc_and_initialization(constant_arg));
         }
 
+        // Check the states required by a `#[transition(...)]` before the rest
+        // of the precondition, to report protocol violations separately.
+        let typestate_spec = self.encode_typestate_precondition_expr(&procedure_contract, substs)?;
+        if !typestate_spec.is_empty() {
+            let pos = self.register_error(call_site_span, ErrorCtxt::AssertTypestateTransition);
+            stmts.push(vir::Stmt::Assert( vir::Assert {
+                expr: replace_fake_exprs(typestate_spec.into_iter().conjoin()),
+                position: pos,
+            }));
+        }
+
         // Encode precondition.
         let (
             pre_type_spec,
@@ -3445,6 +4656,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                         self.encode_prusti_local(*arg).into(),
                     ).with_span(precondition_spans.clone())?
                 );
+                invs_spec.extend(self.encode_refinements(ty, self.encode_prusti_local(*arg).into())?);
             }
         }
         Ok((
@@ -3455,6 +4667,65 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         ))
     }
 
+    /// Encode the refinements of the `#[refined]` structs that constrain the
+    /// value of type `ty` at `place`, looking through references.
+    fn encode_refinements(
+        &self,
+        ty: ty::Ty<'tcx>,
+        place: vir::Expr,
+    ) -> SpannedEncodingResult<Vec<vir::Expr>> {
+        match ty.kind() {
+            ty::TyKind::Ref(_, target_ty, _) => {
+                let field = self.encoder.encode_dereference_field(*target_ty)
+                    .with_span(self.mir.span)?;
+                self.encode_refinements(*target_ty, place.field(field))
+            }
+            ty::TyKind::Adt(..) => self.encoder.get_refinements(ty)
+                .iter()
+                .map(|refinement| self.encoder.encode_assertion(
+                    refinement,
+                    None,
+                    &[place.clone()],
+                    None,
+                    false,
+                    self.proc_def_id,
+                    ty::List::empty(),
+                ))
+                .collect(),
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Encode the preconditions of a call that require the states of a
+    /// `#[transition(...)]`.
+    fn encode_typestate_precondition_expr(
+        &self,
+        contract: &ProcedureContract<'tcx>,
+        substs: SubstsRef<'tcx>,
+    ) -> SpannedEncodingResult<Vec<vir::Expr>> {
+        let encoded_args: Vec<vir::Expr> = contract
+            .args
+            .iter()
+            .map(|local| self.encode_prusti_local(*local).into())
+            .collect();
+        contract.functional_precondition(self.encoder.env(), substs)
+            .iter()
+            .filter(|(assertion, _)| self.encoder.env().has_prusti_attribute(
+                assertion.to_def_id(),
+                "typestate_spec",
+            ))
+            .map(|(assertion, assertion_substs)| self.encoder.encode_assertion(
+                assertion,
+                None,
+                &encoded_args,
+                None,
+                false,
+                self.proc_def_id,
+                assertion_substs,
+            ))
+            .collect()
+    }
+
     fn encode_spec_refinement(
         &self,
         pre_label: &str,
@@ -3580,7 +4851,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         }
 
         if let SpecificationItem::Refined(_, _) = &procedure_spec.pledges {
-            unimplemented!("Refining specifications with pledges is not supported");
+            return Err(SpannedEncodingError::unsupported(
+                "refining specifications with pledges is not supported",
+                self.mir.span,
+            ));
         }
 
         Ok((weakening, strengthening))
@@ -3623,18 +4897,79 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             let pos = self.register_error(weakening_spec.spec_functions_span, ErrorCtxt::AssertMethodPreconditionWeakening);
             self.cfg_method.add_stmt(
                 start_cfg_block,
-                vir::Stmt::Assert( vir::Assert {
-                    expr: weakening_spec.refinement_check_expr,
-                    position: pos
+                vir::Stmt::Assert( vir::Assert {
+                    expr: weakening_spec.refinement_check_expr,
+                    position: pos
+                }),
+            );
+        }
+        self.cfg_method.add_stmt(
+            start_cfg_block,
+            vir::Stmt::Inhale( vir::Inhale {
+                expr: func_spec
+            }),
+        );
+        // A generator is started with a resume argument that satisfies its
+        // resume preconditions.
+        if config::enable_generators() && self.mir.generator_kind().is_some() {
+            for precondition in self.encode_resume_preconditions(self.procedure)? {
+                self.cfg_method.add_stmt(
+                    start_cfg_block,
+                    vir::Stmt::Inhale( vir::Inhale {
+                        expr: precondition,
+                    }),
+                );
+            }
+        }
+        // Lemmas registered as axioms hold in every procedure but the lemmas,
+        // whose bodies prove the axioms. Otherwise, two lemmas could prove
+        // each other's axiom.
+        let lemma_axioms = if self.is_lemma() {
+            vec![]
+        } else {
+            self.encoder.get_lemma_axioms()
+        };
+        for (lemma, axiom) in lemma_axioms {
+            let encoded_axiom = self.encoder.encode_assertion(
+                &axiom,
+                None,
+                &[],
+                None,
+                false,
+                lemma.to_def_id(),
+                self.encoder.env().identity_substs(lemma.to_def_id()),
+            )?;
+            self.cfg_method.add_stmt(
+                start_cfg_block,
+                vir::Stmt::Inhale( vir::Inhale {
+                    expr: encoded_axiom,
+                }),
+            );
+        }
+        // Symbolic constants satisfy their constraints in every procedure but
+        // the ones that check the constraints.
+        let symbolic_constraints = if self.is_symbolic_check() {
+            vec![]
+        } else {
+            self.encoder.get_symbolic_constraint_specs()
+        };
+        for constraint in symbolic_constraints {
+            let encoded_constraint = self.encoder.encode_assertion(
+                &constraint,
+                None,
+                &[],
+                None,
+                false,
+                constraint.to_def_id(),
+                self.encoder.env().identity_substs(constraint.to_def_id()),
+            )?;
+            self.cfg_method.add_stmt(
+                start_cfg_block,
+                vir::Stmt::Inhale( vir::Inhale {
+                    expr: encoded_constraint,
                 }),
             );
         }
-        self.cfg_method.add_stmt(
-            start_cfg_block,
-            vir::Stmt::Inhale( vir::Inhale {
-                expr: func_spec
-            }),
-        );
         self.cfg_method.add_stmt(
             start_cfg_block,
             vir::Stmt::label(PRECONDITION_LABEL),
@@ -3676,6 +5011,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             );
             let borrow_info = &borrow_infos[0];
             let pledges: Vec<&Pledge> = contract.pledges().collect();
+            let pledges_substs = contract.pledges_substs(self.encoder.env(), substs);
             assert!(
                 pledges.len() <= 1,
                 "There can be at most one pledge in the function postcondition."
@@ -3724,7 +5060,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                         Some(&encoded_return),
                         false,
                         self.proc_def_id,
-                        substs,
+                        pledges_substs,
                     )?
                 } else {
                     true.into()
@@ -3736,7 +5072,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     Some(&encoded_return),
                     false,
                     self.proc_def_id,
-                    substs,
+                    pledges_substs,
                 )?;
                 assertion_lhs = self.wrap_arguments_into_old(
                     assertion_lhs,
@@ -3750,21 +5086,18 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     contract,
                     &encoded_args,
                 )?;
-                let ty = self.locals.get_type(contract.returned_value);
-                let return_span = self.mir_encoder.get_local_span(
-                    contract.returned_value.into()
-                );
-                let (encoded_deref, ..) = self
-                    .mir_encoder
-                    .encode_deref(encoded_return, ty)
-                    .with_span(return_span)?;
-
-                let original_expr = encoded_deref;
-                let old_expr = vir::Expr::labelled_old(post_label, original_expr.clone());
-                // TODO ??
-                assertion_lhs = assertion_lhs.replace_place(&original_expr, &old_expr);
+                // The pledge refers to the returned references, such as `*_0`
+                // or `*(_0.1)` when a tuple is returned, as they were at the
+                // end of the function.
+                for (place, _) in &borrow_info.blocking_paths {
+                    let (original_expr, ..) = self.encode_generic_place(
+                        contract.def_id, location, place
+                    ).with_span(span)?;
+                    let old_expr = vir::Expr::labelled_old(post_label, original_expr.clone());
+                    assertion_lhs = assertion_lhs.replace_place(&original_expr, &old_expr);
+                    assertion_rhs = assertion_rhs.replace_place(&original_expr, &old_expr);
+                }
                 assertion_lhs = assertion_lhs.remove_redundant_old();
-                assertion_rhs = assertion_rhs.replace_place(&original_expr, &old_expr);
                 assertion_rhs = assertion_rhs.remove_redundant_old();
                 lhs.push(assertion_lhs);
                 rhs.push(assertion_rhs);
@@ -3819,6 +5152,97 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok(assertion.remove_redundant_old())
     }
 
+    /// Encode the summary inferred for a function without a contract, with
+    /// the arguments not yet wrapped into `old`.
+    fn encode_procedure_summary(
+        &self,
+        summary: &ProcedureSummary,
+        pre_label: &str,
+        contract: &ProcedureContract<'tcx>,
+        encoded_args: &[vir::Expr],
+        encoded_return: &vir::Expr,
+    ) -> EncodingResult<vir::Expr> {
+        let encode_arg_value = |arg: mir::Local| -> EncodingResult<vir::Expr> {
+            let index = arg.index() - 1;
+            let ty = self.locals.get_type(contract.args[index]);
+            self.encoder.encode_value_expr(encoded_args[index].clone(), ty)
+        };
+        let mut conjuncts = vec![];
+        for &arg in &summary.unmodified_targets {
+            let index = arg.index() - 1;
+            let ty = self.locals.get_type(contract.args[index]);
+            let (target, target_ty, _) = self
+                .mir_encoder
+                .encode_deref(encoded_args[index].clone(), ty)?;
+            let value = self.encoder.encode_value_expr(target, target_ty)?;
+            conjuncts.push(vir::Expr::eq_cmp(value.clone(), value.old(pre_label)));
+        }
+        let return_ty = self.locals.get_type(contract.returned_value);
+        let result = self.encoder.encode_value_expr(encoded_return.clone(), return_ty)?;
+        if let Some(interval) = summary.result_interval {
+            if let Some(lower) = interval.lower {
+                conjuncts.push(vir::Expr::le_cmp(lower.into(), result.clone()));
+            }
+            if let Some(upper) = interval.upper {
+                conjuncts.push(vir::Expr::le_cmp(result.clone(), upper.into()));
+            }
+        }
+        let add_constant = |value: vir::Expr, c: i128| {
+            if c == 0 {
+                value
+            } else {
+                vir::Expr::add(value, c.into())
+            }
+        };
+        for &(arg, c) in &summary.result_upper_bounds {
+            conjuncts.push(vir::Expr::le_cmp(
+                result.clone(),
+                add_constant(encode_arg_value(arg)?, c),
+            ));
+        }
+        for &(arg, c) in &summary.result_lower_bounds {
+            conjuncts.push(vir::Expr::le_cmp(
+                encode_arg_value(arg)?,
+                add_constant(result.clone(), c),
+            ));
+        }
+        Ok(conjuncts.into_iter().conjoin())
+    }
+
+    /// Encode the frame condition inferred for a method taking `&mut self`,
+    /// with the arguments not yet wrapped into `old`.
+    fn encode_frame_condition(
+        &self,
+        frame_condition: &FrameCondition,
+        pre_label: &str,
+        contract: &ProcedureContract<'tcx>,
+        encoded_args: &[vir::Expr],
+    ) -> EncodingResult<vir::Expr> {
+        let tcx = self.encoder.env().tcx();
+        let self_ty = self.locals.get_type(contract.args[0]);
+        let (target, target_ty, _) = self
+            .mir_encoder
+            .encode_deref(encoded_args[0].clone(), self_ty)?;
+        let (adt_def, substs) = match target_ty.kind() {
+            ty::TyKind::Adt(adt_def, substs) => (adt_def, substs),
+            _ => unreachable!("the frame condition of a method whose `self` is not a struct"),
+        };
+        let fields = &adt_def.non_enum_variant().fields;
+        let mut conjuncts = vec![];
+        for &index in &frame_condition.unmodified_fields {
+            let field = &fields[index];
+            let field_ty = field.ty(tcx, substs);
+            let encoded_field = self
+                .encoder
+                .encode_struct_field(field.ident(tcx).as_str(), field_ty)?;
+            let value = self
+                .encoder
+                .encode_value_expr(target.clone().field(encoded_field), field_ty)?;
+            conjuncts.push(vir::Expr::eq_cmp(value.clone(), value.old(pre_label)));
+        }
+        Ok(conjuncts.into_iter().conjoin())
+    }
+
     /// Encode the postcondition with three expressions:
     /// - one for the type encoding
     /// - one for the type invariants
@@ -3880,10 +5304,11 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     add_type_spec(vir::PermAmount::Write);
                     let inv = self
                         .encoder
-                        .encode_invariant_func_app(place_ty, old_place_expr)
+                        .encode_invariant_func_app(place_ty, old_place_expr.clone())
                         // TODO: Use a better span
                         .with_span(self.mir.span)?;
                     invs_spec.push(inv);
+                    invs_spec.extend(self.encode_refinements(place_ty, old_place_expr)?);
                 }
             };
         }
@@ -3967,16 +5392,47 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             )?;
             func_spec.push(assertion.set_default_pos(assertion_pos));
         }
+        if let Some(summary) = self.encoder.get_procedure_summary(contract.def_id) {
+            let summary_spec = self.encode_procedure_summary(
+                &summary,
+                pre_label,
+                contract,
+                &encoded_args,
+                &encoded_return,
+            ).with_span(span)?;
+            func_spec.push(self.wrap_arguments_into_old(
+                summary_spec,
+                pre_label,
+                contract,
+                &encoded_args,
+            )?);
+        }
+        if let Some(frame_condition) = self.encoder.get_frame_condition(contract.def_id) {
+            let frame_spec = self.encode_frame_condition(
+                &frame_condition,
+                pre_label,
+                contract,
+                &encoded_args,
+            ).with_span(span)?;
+            func_spec.push(self.wrap_arguments_into_old(
+                frame_spec,
+                pre_label,
+                contract,
+                &encoded_args,
+            )?);
+        }
         let postcondition_span = MultiSpan::from_spans(func_spec_spans);
         let func_spec_pos = self.mir_encoder.register_span(postcondition_span.clone());
 
         // Encode invariant for return value
+        let return_ty = self.locals.get_type(contract.returned_value);
         invs_spec.push(
             self.encoder.encode_invariant_func_app(
-                self.locals.get_type(contract.returned_value),
-                encoded_return,
+                return_ty,
+                encoded_return.clone(),
             ).with_span(postcondition_span)?
         );
+        invs_spec.extend(self.encode_refinements(return_ty, encoded_return)?);
 
         let full_func_spec = func_spec.into_iter().conjoin()
             .set_default_pos(func_spec_pos);
@@ -4081,13 +5537,32 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 .polonius_info()
                 .get_point(location, facts::PointType::Start);
 
-            let opt_region = self.polonius_info()
-                .place_regions
-                .for_local(blocker);
-            let mut package_stmts = if let Some(region) = opt_region {
-                    let (all_loans, zombie_loans) = self
-                        .polonius_info()
-                        .get_all_loans_kept_alive_by(start_point, region);
+            // A returned tuple or struct may contain several references.
+            let regions: Vec<_> = blocker
+                .all_places(self.encoder.env().tcx(), self.mir)
+                .into_iter()
+                .filter_map(|place| {
+                    self.polonius_info().place_regions.for_place(place).ok().flatten()
+                })
+                .collect();
+            let mut package_stmts = if !regions.is_empty() {
+                    let mut all_loans = Vec::new();
+                    let mut zombie_loans = Vec::new();
+                    for region in regions {
+                        let (region_loans, region_zombie_loans) = self
+                            .polonius_info()
+                            .get_all_loans_kept_alive_by(start_point, region);
+                        for loan in region_loans {
+                            if !all_loans.contains(&loan) {
+                                all_loans.push(loan);
+                            }
+                        }
+                        for loan in region_zombie_loans {
+                            if !zombie_loans.contains(&loan) {
+                                zombie_loans.push(loan);
+                            }
+                        }
+                    }
                     self.encode_expiration_of_loans(all_loans, &zombie_loans, location, None, true)?
                 } else {
                     // This happens when encoding the following function
@@ -4758,13 +6233,67 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         res
     }
 
-    /// Encode the functional specification of a loop
+    /// The non-specification block that jumps to the specification block
+    /// `spec_block`.
+    fn get_spec_block_attachment(&self, spec_block: BasicBlockIndex) -> BasicBlockIndex {
+        let mut bbi = spec_block;
+        while self.procedure.is_spec_block(bbi) {
+            match self.mir.predecessors()[bbi].first() {
+                Some(&pred_bbi) => bbi = pred_bbi,
+                None => break,
+            }
+        }
+        bbi
+    }
+
+    /// The blocks that jump to the specification blocks of the loop invariant:
+    /// the block at whose end the invariant holds, followed by the blocks of
+    /// the `body_invariant!(..)` written right after the first one.
+    fn get_loop_invariant_group(&self, loop_head: BasicBlockIndex) -> Vec<BasicBlockIndex> {
+        let loop_info = self.loop_encoder.loops();
+        let is_trivial_statement = |stmt: &mir::Statement| matches!(
+            stmt.kind,
+            mir::StatementKind::StorageLive(_)
+                | mir::StatementKind::StorageDead(_)
+                | mir::StatementKind::Nop
+                | mir::StatementKind::FakeRead(_)
+                | mir::StatementKind::Assign(box (_, mir::Rvalue::Use(mir::Operand::Constant(_))))
+        );
+        let mut group = vec![self.cached_loop_invariant_block[&loop_head]];
+        loop {
+            let last = *group.last().unwrap();
+            let next_blocks: Vec<_> = self.procedure.successors(last)
+                .iter()
+                .filter(|&&bbi| !self.procedure.is_spec_block(bbi))
+                .copied()
+                .collect();
+            match next_blocks[..] {
+                [next] if loop_info.get_loop_head(next) == Some(loop_head)
+                    && !group.contains(&next)
+                    && self.mir[next].statements.iter().all(is_trivial_statement)
+                    && self.procedure.successors(next)
+                        .iter()
+                        .any(|&bbi| self.procedure.is_spec_block(bbi)) => group.push(next),
+                _ => break,
+            }
+        }
+        group
+    }
+
+    /// Encode the functional specification of a loop. The specifications
+    /// written elsewhere in the loop body are not part of it; they are encoded
+    /// by `encode_loop_invariants_in_body`.
     fn encode_loop_invariant_specs(
-        &self,
+        &mut self,
         loop_head: BasicBlockIndex,
         _loop_inv_block: BasicBlockIndex,
     ) -> SpannedEncodingResult<(Vec<vir::Expr>, MultiSpan)> {
-        let spec_blocks = self.get_loop_spec_blocks(loop_head);
+        let group = self.get_loop_invariant_group(loop_head);
+        let spec_blocks: Vec<_> = self
+            .get_loop_spec_blocks(loop_head)
+            .into_iter()
+            .filter(|&bbi| group.contains(&self.get_spec_block_attachment(bbi)))
+            .collect();
         trace!(
             "loop head {:?} has spec blocks {:?}",
             loop_head,
@@ -4795,9 +6324,380 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         }
         trace!("encoded specs: {:?}", encoded_specs);
 
+        if encoded_specs.iter().any(|spec| spec.uses_label(LOOP_ENTRY_LABEL)) {
+            let cfg_method = &mut self.cfg_method;
+            let entry_label = self
+                .loop_entry_label
+                .entry(loop_head)
+                .or_insert_with(|| cfg_method.get_fresh_label_name())
+                .clone();
+            encoded_specs = encoded_specs
+                .into_iter()
+                .map(|spec| {
+                    spec.map_old_expr_label(|label| {
+                        if label == LOOP_ENTRY_LABEL {
+                            entry_label.clone()
+                        } else {
+                            label
+                        }
+                    })
+                })
+                .collect();
+        }
+
         Ok((encoded_specs, MultiSpan::from_spans(encoded_spec_spans)))
     }
 
+    /// Encode the `body_invariant!(..)` specifications found in the
+    /// specification blocks starting at `spec_block` as assertions, if `bbi`
+    /// (the block that jumps to them) is not part of the loop invariant. Such
+    /// invariants are written later in the loop body, or in a conditional
+    /// branch of the loop (e.g. before a `continue` or `break`), and are
+    /// checked each time the execution reaches them.
+    fn encode_loop_invariants_in_body(
+        &self,
+        bbi: BasicBlockIndex,
+        spec_block: BasicBlockIndex,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        // The loops of a `#[bounded(..)]` procedure are unrolled, so their
+        // invariants are not used.
+        if self.loop_unrolling.is_some() {
+            return Ok(vec![]);
+        }
+        match self.loop_encoder.loops().get_loop_head(bbi) {
+            Some(loop_head) if !self.get_loop_invariant_group(loop_head).contains(&bbi) => {}
+            _ => return Ok(vec![]),
+        }
+        let mut stmts = vec![];
+        let mut to_visit = vec![spec_block];
+        let mut visited = FxHashSet::default();
+        while let Some(bbi) = to_visit.pop() {
+            if !self.procedure.is_spec_block(bbi) || !visited.insert(bbi) {
+                continue;
+            }
+            for stmt in &self.mir.basic_blocks()[bbi].statements {
+                if let mir::StatementKind::Assign(box (
+                    _,
+                    mir::Rvalue::Aggregate(box mir::AggregateKind::Closure(cl_def_id, cl_substs), _),
+                )) = stmt.kind {
+                    if let Some(spec) = self.encoder.get_loop_specs(cl_def_id) {
+                        let encoded_invariant = self.encoder.encode_invariant(
+                            self.mir,
+                            bbi,
+                            self.proc_def_id,
+                            cl_substs,
+                        )?;
+                        let span = self.encoder.env().tcx().def_span(spec.invariant.to_def_id());
+                        if encoded_invariant.uses_label(LOOP_ENTRY_LABEL) {
+                            return Err(SpannedEncodingError::unsupported(
+                                "on_entry(..) is only supported in the body invariants written \
+                                together with the first one",
+                                span,
+                            ));
+                        }
+                        stmts.push(vir::Stmt::Assert(vir::Assert {
+                            expr: encoded_invariant,
+                            position: self.register_error(span, ErrorCtxt::AssertLoopInvariantInBody),
+                        }));
+                    }
+                }
+            }
+            to_visit.extend(self.procedure.successors(bbi).iter().copied());
+        }
+        Ok(stmts)
+    }
+
+    /// Encode the lines of a `prusti_calc!` proof found in the specification
+    /// blocks starting at `spec_block` as assertions.
+    fn encode_proof_steps(
+        &self,
+        spec_block: BasicBlockIndex,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let mut stmts = vec![];
+        let mut to_visit = vec![spec_block];
+        let mut visited = FxHashSet::default();
+        while let Some(bbi) = to_visit.pop() {
+            if !self.procedure.is_spec_block(bbi) || !visited.insert(bbi) {
+                continue;
+            }
+            for stmt in &self.mir.basic_blocks()[bbi].statements {
+                if let mir::StatementKind::Assign(box (
+                    _,
+                    mir::Rvalue::Aggregate(box mir::AggregateKind::Closure(cl_def_id, cl_substs), _),
+                )) = stmt.kind {
+                    if let Some(spec) = self.encoder.get_proof_step_specs(cl_def_id) {
+                        let encoded_step = self.encoder.encode_invariant(
+                            self.mir,
+                            bbi,
+                            self.proc_def_id,
+                            cl_substs,
+                        )?;
+                        let span = self.encoder.env().tcx().def_span(spec.assertion.to_def_id());
+                        if encoded_step.uses_label(LOOP_ENTRY_LABEL) {
+                            return Err(SpannedEncodingError::incorrect(
+                                "on_entry(..) can only be used in loop body invariants",
+                                span,
+                            ));
+                        }
+                        stmts.push(vir::Stmt::Assert( vir::Assert {
+                            expr: encoded_step,
+                            position: self.register_error(span, ErrorCtxt::AssertProofStep),
+                        }));
+                    }
+                }
+            }
+            to_visit.extend(self.procedure.successors(bbi).iter().copied());
+        }
+        Ok(stmts)
+    }
+
+    /// Encode the `yield_invariant!` specifications of the generator being
+    /// verified.
+    fn encode_yield_invariants(&self) -> SpannedEncodingResult<Vec<vir::Expr>> {
+        let mut invariants = vec![];
+        for bbi in self.procedure.get_reachable_cfg_blocks() {
+            if !self.procedure.is_spec_block(bbi) {
+                continue;
+            }
+            for stmt in &self.mir.basic_blocks()[bbi].statements {
+                if let mir::StatementKind::Assign(box (
+                    _,
+                    mir::Rvalue::Aggregate(box mir::AggregateKind::Closure(cl_def_id, cl_substs), _),
+                )) = stmt.kind {
+                    if self.encoder.get_yield_invariant_specs(cl_def_id).is_some() {
+                        invariants.push(self.encoder.encode_invariant(
+                            self.mir,
+                            bbi,
+                            self.proc_def_id,
+                            cl_substs,
+                        )?);
+                    }
+                }
+            }
+        }
+        Ok(invariants)
+    }
+
+    /// Encode the local variable that holds the resume argument of the
+    /// generator `procedure` when it is started.
+    fn encode_resume_arg_local(&self, procedure: &Procedure<'tcx>) -> SpannedEncodingResult<vir::Expr> {
+        // The first argument of a generator is the generator itself
+        let local = mir::Local::new(2);
+        let local_decl = &procedure.get_mir().local_decls[local];
+        let typ = self.encoder
+            .encode_type(local_decl.ty)
+            .with_span(local_decl.source_info.span)?;
+        Ok(vir::Expr::local(vir::LocalVar::new(format!("{:?}", local), typ)))
+    }
+
+    /// Encode the `resume_requires!` specifications of the generator
+    /// `procedure` as expressions over its resume argument.
+    fn encode_resume_preconditions(&self, procedure: &Procedure<'tcx>) -> SpannedEncodingResult<Vec<vir::Expr>> {
+        #[derive(Default)]
+        struct LocalNameCollector {
+            names: FxHashSet<String>,
+        }
+        impl vir::ExprWalker for LocalNameCollector {
+            fn walk_local_var(&mut self, var: &vir::LocalVar) {
+                self.names.insert(var.name.clone());
+            }
+        }
+
+        let mir = procedure.get_mir();
+        let resume_arg = mir::Local::new(2);
+        let mut preconditions = vec![];
+        for bbi in procedure.get_reachable_cfg_blocks() {
+            if !procedure.is_spec_block(bbi) {
+                continue;
+            }
+            for stmt in &mir.basic_blocks()[bbi].statements {
+                if let mir::StatementKind::Assign(box (
+                    _,
+                    mir::Rvalue::Aggregate(box mir::AggregateKind::Closure(cl_def_id, cl_substs), _),
+                )) = stmt.kind {
+                    if self.encoder.get_resume_precondition_specs(cl_def_id).is_some() {
+                        let precondition = self.encoder.encode_invariant(
+                            mir,
+                            bbi,
+                            procedure.get_id(),
+                            cl_substs,
+                        )?;
+                        // The code that resumes the generator only knows the
+                        // resume argument, not the state of the generator.
+                        let mut collector = LocalNameCollector::default();
+                        vir::ExprWalker::walk(&mut collector, &precondition);
+                        let mentions_state = mir.local_decls.indices().any(|local| {
+                            local != resume_arg && collector.names.contains(&format!("{:?}", local))
+                        });
+                        if mentions_state {
+                            return Err(SpannedEncodingError::incorrect(
+                                "the resume precondition of a generator can only mention its \
+                                resume argument",
+                                self.encoder.env().tcx().def_span(cl_def_id),
+                            ));
+                        }
+                        preconditions.push(precondition);
+                    }
+                }
+            }
+        }
+        Ok(preconditions)
+    }
+
+    /// Assert the resume preconditions of a generator at a call of
+    /// `Generator::resume`. Generators of other crates have no resume
+    /// preconditions.
+    fn encode_resume_precondition_check(
+        &self,
+        full_func_proc_name: &str,
+        call_substs: SubstsRef<'tcx>,
+        args: &[mir::Operand<'tcx>],
+        span: Span,
+    ) -> SpannedEncodingResult<Vec<vir::Stmt>> {
+        let gen_def_id = match full_func_proc_name {
+            "std::ops::Generator::resume" | "core::ops::Generator::resume" => {
+                match call_substs.type_at(0).kind() {
+                    ty::TyKind::Generator(gen_def_id, _, _) if gen_def_id.is_local() => *gen_def_id,
+                    _ => return Ok(vec![]),
+                }
+            }
+            _ => return Ok(vec![]),
+        };
+        let procedure = Procedure::new(self.encoder.env(), gen_def_id);
+        let preconditions = self.encode_resume_preconditions(&procedure)?;
+        if preconditions.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // The resume argument is passed as a place or as a constant, whose
+        // value replaces the value of the resume argument.
+        let resume_arg_local = self.encode_resume_arg_local(&procedure)?;
+        let (target, replacement) = if let Some(place) = self.mir_encoder
+            .encode_operand_place(&args[1])
+            .with_span(span)?
+        {
+            (resume_arg_local.clone(), place)
+        } else {
+            let arg_ty = self.mir_encoder.get_operand_ty(&args[1]);
+            let value_field = self.encoder.encode_value_field(arg_ty).with_span(span)?;
+            let value = self.mir_encoder.encode_operand_expr(&args[1]).with_span(span)?;
+            (resume_arg_local.clone().field(value_field), value)
+        };
+
+        let mut stmts = vec![
+            vir::Stmt::comment("Assert the resume preconditions of the generator"),
+        ];
+        for precondition in preconditions {
+            let precondition = precondition.replace_place(&target, &replacement);
+            if precondition.find(&resume_arg_local) {
+                return Err(SpannedEncodingError::unsupported(
+                    "resuming a generator that has resume preconditions with a constant of a \
+                    non-primitive type is not supported",
+                    span,
+                ));
+            }
+            stmts.push(vir::Stmt::Assert( vir::Assert {
+                expr: precondition,
+                position: self.register_error(span, ErrorCtxt::AssertResumePrecondition),
+            }));
+        }
+        Ok(stmts)
+    }
+
+    /// Encode the bounds inferred by the interval analysis at the loop head.
+    /// Only locals whose predicate is in `permissions`, and the lengths of the
+    /// slices referenced by such locals, are constrained, so that the result
+    /// is well-defined wherever the permissions are held.
+    fn encode_inferred_loop_invariant(
+        &self,
+        loop_head: BasicBlockIndex,
+        permissions: &[vir::Expr],
+    ) -> SpannedEncodingResult<Vec<vir::Expr>> {
+        let location = mir::Location {
+            block: loop_head,
+            statement_index: 0,
+        };
+        let state = match self
+            .inferred_invariants
+            .as_ref()
+            .and_then(|result| result.lookup_before(location))
+        {
+            Some(state) if !state.is_bottom() => state,
+            _ => return Ok(vec![]),
+        };
+        let span = self.mir_encoder.get_span_of_basic_block(loop_head);
+        let encode_local_value = |local: mir::Local| -> SpannedEncodingResult<Option<vir::Expr>> {
+            let place: vir::Expr = self.mir_encoder.encode_local(local)?.into();
+            let is_framed = permissions.iter().any(|permission| matches!(
+                permission,
+                vir::Expr::PredicateAccessPredicate(vir::PredicateAccessPredicate {
+                    argument, ..
+                }) if **argument == place
+            ));
+            if !is_framed {
+                return Ok(None);
+            }
+            let ty = self.mir_encoder.get_local_ty(local);
+            Ok(Some(self.encoder.encode_value_expr(place, ty).with_span(span)?))
+        };
+        // The length of an array is a constant and the length of a slice is
+        // encoded with the length function of its type. The length of a
+        // vector has no built-in encoding.
+        let encode_value = |local: mir::Local| -> SpannedEncodingResult<Option<vir::Expr>> {
+            let sequence = match state.get_sequence(local) {
+                Some(sequence) => sequence,
+                None => return encode_local_value(local),
+            };
+            let length_interval = state.get_type_interval(local);
+            if let (Some(lower), Some(upper)) = (length_interval.lower, length_interval.upper) {
+                if lower == upper {
+                    return Ok(Some(lower.into()));
+                }
+            }
+            let slice_ty = match self.mir_encoder.get_local_ty(sequence).kind() {
+                ty::TyKind::Ref(_, target_ty, _) if target_ty.is_slice() => *target_ty,
+                _ => return Ok(None),
+            };
+            let slice = match encode_local_value(sequence)? {
+                Some(slice) => slice,
+                None => return Ok(None),
+            };
+            let slice_types = self.encoder.encode_sequence_types(slice_ty).with_span(span)?;
+            Ok(Some(slice_types.len(self.encoder, slice)))
+        };
+
+        let mut invariant = vec![];
+        let mut intervals: Vec<_> = state.get_intervals().collect();
+        intervals.sort_by_key(|(local, _)| *local);
+        for (local, interval) in intervals {
+            let value = match encode_value(local)? {
+                Some(value) => value,
+                None => continue,
+            };
+            let type_interval = state.get_type_interval(local);
+            if let Some(lower) = interval.lower.filter(|&lower| Some(lower) != type_interval.lower) {
+                invariant.push(vir::Expr::le_cmp(lower.into(), value.clone()));
+            }
+            if let Some(upper) = interval.upper.filter(|&upper| Some(upper) != type_interval.upper) {
+                invariant.push(vir::Expr::le_cmp(value, upper.into()));
+            }
+        }
+        let mut upper_bounds: Vec<_> = state.get_upper_bounds().collect();
+        upper_bounds.sort();
+        for (x, y, c) in upper_bounds {
+            if let (Some(x_value), Some(y_value)) = (encode_value(x)?, encode_value(y)?) {
+                let bound = if c == 0 {
+                    y_value
+                } else {
+                    vir::Expr::add(y_value, c.into())
+                };
+                invariant.push(vir::Expr::le_cmp(x_value, bound));
+            }
+        }
+        trace!("inferred loop invariant at {:?}: {:?}", loop_head, invariant);
+        Ok(invariant)
+    }
+
     fn encode_loop_invariant_exhale_stmts(
         &mut self,
         loop_head: BasicBlockIndex,
@@ -4819,6 +6719,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         let (permissions, equalities, invs_spec) =
             self.encode_loop_invariant_permissions(loop_head, loop_inv_block, true)
                 .with_span(func_spec_span.clone())?;
+        let inferred_spec = self.encode_inferred_loop_invariant(loop_head, &permissions)?;
 
         // TODO: use different positions, and generate different error messages, for the exhale
         // before the loop and after the loop body
@@ -4872,6 +6773,16 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             expr: func_spec.into_iter().conjoin(),
             position: assert_pos,
         }));
+        if !inferred_spec.is_empty() {
+            let inferred_pos = self.register_error(
+                self.get_loop_span(loop_head),
+                ErrorCtxt::AssertInferredLoopInvariant,
+            );
+            stmts.push(vir::Stmt::Assert( vir::Assert {
+                expr: inferred_spec.into_iter().conjoin(),
+                position: inferred_pos,
+            }));
+        }
         stmts.push(vir::Stmt::Assert( vir::Assert {
             expr: invs_spec.into_iter().conjoin(),
             position: exhale_pos,
@@ -4905,6 +6816,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         let (permissions, equalities, invs_spec) =
             self.encode_loop_invariant_permissions(loop_head, loop_inv_block, true)
                 .with_span(func_spec_span)?;
+        let inferred_spec = self.encode_inferred_loop_invariant(loop_head, &permissions)?;
 
         let permission_expr = permissions.into_iter().conjoin();
         let equality_expr = equalities.into_iter().conjoin();
@@ -4925,6 +6837,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         stmts.push(vir::Stmt::Inhale( vir::Inhale {
             expr: func_spec.into_iter().conjoin(),
         }));
+        stmts.push(vir::Stmt::Inhale( vir::Inhale {
+            expr: inferred_spec.into_iter().conjoin(),
+        }));
         Ok(stmts)
     }
 
@@ -5367,16 +7282,13 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             .with_span(span)?;
         let encoded_right = self.mir_encoder.encode_operand_expr(right)
             .with_span(span)?;
-        let encoded_value = self.mir_encoder.encode_bin_op_expr(
+        let (encoded_value, encoded_check) = self.mir_encoder.encode_checked_bin_op(
             op,
-            encoded_left.clone(),
-            encoded_right.clone(),
+            encoded_left,
+            encoded_right,
             operand_ty,
+            span,
         ).with_span(span)?;
-        let encoded_check =
-            self.mir_encoder
-                .encode_bin_op_check(op, encoded_left, encoded_right, operand_ty)
-                .with_span(span)?;
         let field_types = if let ty::TyKind::Tuple(ref x) = ty.kind() {
             x
         } else {
@@ -6210,9 +8122,26 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 // TODO: might need to assert history invariants?
                 assert!(!self.encoder.is_spec_closure(def_id), "spec closure: {:?}", def_id);
                 let cl_substs = substs.as_closure();
+                let captures = self.encoder.env().closure_captures(def_id);
                 for (field_index, field_ty) in cl_substs.upvar_tys().enumerate() {
                     let operand = &operands[field_index];
                     let field_name = format!("closure_{}", field_index);
+                    // The operand of a place captured by value is moved into
+                    // the closure with its permission. The operand of a place
+                    // captured by reference is a borrow of the place, which
+                    // expires when the closure is no longer used.
+                    if let Some(capture) = captures.get(field_index) {
+                        stmts.push(vir::Stmt::comment(format!(
+                            "Capture {} {} in {}",
+                            capture.place,
+                            match capture.kind {
+                                CaptureKind::ByValue => "by value",
+                                CaptureKind::BySharedRef => "by shared reference",
+                                CaptureKind::ByMutRef => "by mutable reference",
+                            },
+                            field_name,
+                        )));
+                    }
                     let encoded_field = self.encoder
                         .encode_raw_ref_field(field_name, field_ty)
                         .with_span(span)?;
@@ -6506,6 +8435,48 @@ fn mir_constantkind_to_ty_val(literal: mir::ConstantKind) -> (ty::Ty, ty::ConstK
     }
 }
 
+/// Whether a function of the standard library panics on some of its
+/// arguments, e.g. with an index that is out of bounds, an index that is not
+/// on a character boundary, a chunk size of zero or a value that is already
+/// borrowed.
+fn is_panicking_std_function(full_func_proc_name: &str) -> bool {
+    matches!(
+        full_func_proc_name,
+        "std::vec::Vec::<T, A>::remove"
+            | "std::vec::Vec::<T, A>::insert"
+            | "std::vec::Vec::<T, A>::swap_remove"
+            | "std::vec::Vec::<T, A>::split_off"
+            | "std::vec::Vec::<T, A>::drain"
+            | "std::string::String::remove"
+            | "std::string::String::insert"
+            | "std::string::String::insert_str"
+            | "std::string::String::truncate"
+            | "std::string::String::split_off"
+            | "std::string::String::drain"
+            | "core::slice::<impl [T]>::split_at"
+            | "core::slice::<impl [T]>::split_at_mut"
+            | "core::slice::<impl [T]>::swap"
+            | "core::slice::<impl [T]>::copy_from_slice"
+            | "core::slice::<impl [T]>::clone_from_slice"
+            | "core::slice::<impl [T]>::chunks"
+            | "core::slice::<impl [T]>::chunks_mut"
+            | "core::slice::<impl [T]>::chunks_exact"
+            | "core::slice::<impl [T]>::windows"
+            | "core::slice::<impl [T]>::rotate_left"
+            | "core::slice::<impl [T]>::rotate_right"
+            | "core::str::<impl str>::split_at"
+            | "std::cell::RefCell::<T>::borrow"
+            | "std::cell::RefCell::<T>::borrow_mut"
+            | "core::cell::RefCell::<T>::borrow"
+            | "core::cell::RefCell::<T>::borrow_mut"
+    ) || (
+        // Euclidean division by zero, e.g. `core::num::<impl u32>::div_euclid`.
+        full_func_proc_name.starts_with("core::num::<impl ")
+            && (full_func_proc_name.ends_with(">::div_euclid")
+                || full_func_proc_name.ends_with(">::rem_euclid"))
+    )
+}
+
 // Checks if a type is a reference to a string, or a reference to a reference to a string, etc.
 fn is_str(ty: ty::Ty<'_>) -> bool {
     match ty.kind() {
@@ -0,0 +1,184 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Summaries of private helper functions without a contract, inferred from
+//! their MIR when `INFER_PROCEDURE_SUMMARIES` is enabled. A summary is added
+//! to the postcondition of the function: it is checked when the function is
+//! verified and assumed at its call sites.
+
+use analysis::{
+    abstract_interpretation::{AbstractState, FixpointEngine},
+    domains::{Interval, IntervalAnalysis, IntervalsState},
+};
+use log::debug;
+use prusti_interface::environment::Environment;
+use rustc_hir::def_id::DefId;
+use rustc_middle::{
+    mir::{
+        self,
+        visit::{MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor},
+    },
+    ty,
+};
+
+#[derive(Debug, Default)]
+pub struct ProcedureSummary {
+    /// The arguments of type `&mut T`, with `T` a primitive type, whose
+    /// target is never modified.
+    pub unmodified_targets: Vec<mir::Local>,
+    /// The bounds of the returned value that are tighter than its type;
+    /// `None` bounds are implied by the type.
+    pub result_interval: Option<Interval>,
+    /// The pairs `(arg, c)` such that `result <= arg + c`.
+    pub result_upper_bounds: Vec<(mir::Local, i128)>,
+    /// The pairs `(arg, c)` such that `arg <= result + c`.
+    pub result_lower_bounds: Vec<(mir::Local, i128)>,
+}
+
+impl ProcedureSummary {
+    pub fn is_empty(&self) -> bool {
+        self.unmodified_targets.is_empty()
+            && self.result_interval.is_none()
+            && self.result_upper_bounds.is_empty()
+            && self.result_lower_bounds.is_empty()
+    }
+}
+
+/// Infer the summary of the local, non-public function `def_id`. The caller
+/// is responsible for checking that the function has no contract.
+pub fn infer_procedure_summary(env: &Environment, def_id: DefId) -> Option<ProcedureSummary> {
+    let tcx = env.tcx();
+    let local_def_id = def_id.as_local()?;
+    if tcx.visibility(def_id).is_public() || tcx.is_closure(def_id) {
+        return None;
+    }
+    let mir = env.local_mir(local_def_id, env.identity_substs(def_id));
+    let mut summary = ProcedureSummary::default();
+
+    for arg in mir.args_iter() {
+        if let ty::TyKind::Ref(_, target_ty, mir::Mutability::Mut) = mir.local_decls[arg].ty.kind()
+        {
+            if is_primitive(*target_ty) && !is_target_modified(&mir, arg) {
+                summary.unmodified_targets.push(arg);
+            }
+        }
+    }
+
+    match IntervalAnalysis::new(tcx, def_id, &mir).run_fwd_analysis() {
+        Ok(result) => {
+            if let Some(state) = join_return_states(&mir, |location| result.lookup_before(location))
+            {
+                summarize_result(&mir, &state, &mut summary);
+            }
+        }
+        Err(error) => debug!("Failed to analyze {:?}: {:?}", def_id, error),
+    }
+    debug!("Summary of {:?}: {:?}", def_id, summary);
+    Some(summary)
+}
+
+fn is_primitive(ty: ty::Ty) -> bool {
+    ty.is_bool() || ty.is_integral() || ty.is_char()
+}
+
+/// The join of the states before all `return` terminators, if any is reachable.
+fn join_return_states<'a>(
+    mir: &mir::Body,
+    lookup_before: impl Fn(mir::Location) -> Option<&'a IntervalsState>,
+) -> Option<IntervalsState> {
+    let mut joined: Option<IntervalsState> = None;
+    for (block, data) in mir.basic_blocks().iter_enumerated() {
+        if !matches!(data.terminator().kind, mir::TerminatorKind::Return) {
+            continue;
+        }
+        let location = mir::Location {
+            block,
+            statement_index: data.statements.len(),
+        };
+        if let Some(state) = lookup_before(location).filter(|state| !state.is_bottom()) {
+            match &mut joined {
+                Some(joined) => joined.join(state),
+                None => joined = Some(state.clone()),
+            }
+        }
+    }
+    joined
+}
+
+fn summarize_result(mir: &mir::Body, state: &IntervalsState, summary: &mut ProcedureSummary) {
+    let result = mir::RETURN_PLACE;
+    if !state.is_tracked(result) {
+        return;
+    }
+    let interval = state.get_interval(result);
+    let type_interval = state.get_type_interval(result);
+    if interval != type_interval {
+        // Keep only the bounds that the type does not already imply.
+        summary.result_interval = Some(Interval::new(
+            interval
+                .lower
+                .filter(|&lower| Some(lower) != type_interval.lower),
+            interval
+                .upper
+                .filter(|&upper| Some(upper) != type_interval.upper),
+        ));
+    }
+    // Arguments declared as `mut` may be reassigned, so the relation would not
+    // hold with the values passed by the caller.
+    let is_immutable_arg = |local: mir::Local| {
+        (1..=mir.arg_count).contains(&local.index())
+            && mir.local_decls[local].mutability == mir::Mutability::Not
+    };
+    for (x, y, c) in state.get_upper_bounds() {
+        if x == result && is_immutable_arg(y) {
+            summary.result_upper_bounds.push((y, c));
+        } else if y == result && is_immutable_arg(x) {
+            summary.result_lower_bounds.push((x, c));
+        }
+    }
+    summary.result_upper_bounds.sort_unstable();
+    summary.result_lower_bounds.sort_unstable();
+}
+
+/// Whether the target of the mutable reference `arg` may be modified, either
+/// directly or by moving or reborrowing the reference.
+fn is_target_modified(mir: &mir::Body, arg: mir::Local) -> bool {
+    struct ModificationFinder {
+        arg: mir::Local,
+        is_modified: bool,
+    }
+
+    impl<'tcx> Visitor<'tcx> for ModificationFinder {
+        fn visit_place(
+            &mut self,
+            place: &mir::Place<'tcx>,
+            context: PlaceContext,
+            _location: mir::Location,
+        ) {
+            if place.local != self.arg {
+                return;
+            }
+            match context {
+                PlaceContext::MutatingUse(MutatingUseContext::Retag)
+                | PlaceContext::NonMutatingUse(NonMutatingUseContext::Copy)
+                | PlaceContext::NonMutatingUse(NonMutatingUseContext::Inspect)
+                | PlaceContext::NonMutatingUse(NonMutatingUseContext::SharedBorrow)
+                | PlaceContext::NonMutatingUse(NonMutatingUseContext::ShallowBorrow)
+                | PlaceContext::NonUse(_) => {}
+                PlaceContext::NonMutatingUse(NonMutatingUseContext::Move)
+                    if !place.projection.is_empty() => {}
+                _ => self.is_modified = true,
+            }
+        }
+    }
+
+    let mut finder = ModificationFinder {
+        arg,
+        is_modified: false,
+    };
+    finder.visit_body(mir);
+    finder.is_modified
+}
@@ -8,6 +8,7 @@ use crate::encoder::{
     encoder::encode_field_name,
     errors::{EncodingError, EncodingResult},
     foldunfold,
+    ghost_int_encoder::GhostInt,
     high::types::HighTypeEncoderInterface,
     mir::types::MirTypeEncoderInterface,
     snapshot::{decls::Snapshot, patcher::SnapshotPatcher},
@@ -213,6 +214,11 @@ impl SnapshotEncoder {
                         expr,
                         vir::Field::new("val_int", Type::Int),
                     ),
+                    ty::TyKind::Adt(_, _)
+                        if GhostInt::from_ty(encoder.env().tcx(), ty).is_some() => Expr::field(
+                        expr,
+                        vir::Field::new("val_int", Type::Int),
+                    ),
                     ty::TyKind::Float(ty::FloatTy::F32) => Expr::field(
                         expr,
                         vir::Field::new("val_float32", Type::Float(F32)),
@@ -612,6 +618,9 @@ impl SnapshotEncoder {
             ty::TyKind::Int(_) => Type::Int,
             ty::TyKind::Uint(_) => Type::Int,
             ty::TyKind::Char => Type::Int,
+            ty::TyKind::Adt(_, _) if GhostInt::from_ty(encoder.env().tcx(), ty).is_some() => {
+                Type::Int
+            }
             ty::TyKind::Float(ty::FloatTy::F32) => Type::Float(vir::Float::F32),
             ty::TyKind::Float(ty::FloatTy::F64) => Type::Float(vir::Float::F64),
             ty::TyKind::Bool => Type::Bool,
@@ -662,6 +671,9 @@ impl SnapshotEncoder {
             ty::TyKind::Int(_) | ty::TyKind::Uint(_) | ty::TyKind::Char => {
                 Ok(Snapshot::Primitive(Type::Int))
             }
+            ty::TyKind::Adt(_, _) if GhostInt::from_ty(tcx, ty).is_some() => {
+                Ok(Snapshot::Primitive(Type::Int))
+            }
             ty::TyKind::Float(ty::FloatTy::F32) => {
                 Ok(Snapshot::Primitive(Type::Float(vir::Float::F32)))
             }
@@ -1828,11 +1840,16 @@ impl SnapshotEncoder {
 
                 // encode type validity axiom for field
                 // TODO: encode type invariants rather than just integer bounds
-                match field.mir_type.kind() {
+                let has_bounds = matches!(
+                    field.mir_type.kind(),
                     ty::TyKind::Int(_)
-                    | ty::TyKind::Uint(_)
-                    | ty::TyKind::Float(_)
-                    | ty::TyKind::Char => domain_axioms.push({
+                        | ty::TyKind::Uint(_)
+                        | ty::TyKind::Float(_)
+                        | ty::TyKind::Char
+                ) || GhostInt::from_ty(encoder.env().tcx(), field.mir_type)
+                    == Some(GhostInt::Nat);
+                if has_bounds {
+                    domain_axioms.push({
                         let self_local = vir::LocalVar::new("self", snapshot_type.clone());
                         let self_expr = Expr::local(self_local.clone());
                         let field_of_self = field_access_func.apply(vec![self_expr.clone()]);
@@ -1852,9 +1869,7 @@ impl SnapshotEncoder {
                             ),
                             domain_name: domain_name.to_string(),
                         }
-                    }),
-
-                    _ => {}
+                    });
                 }
             }
 
@@ -22,10 +22,17 @@ impl<'p, 'v: 'p, 'tcx: 'v> FallibleExprFolder for SnapshotPatcher<'p, 'v, 'tcx>
 
     fn fallible_fold_snap_app(
         &mut self,
-        vir::SnapApp { mut base, .. }: vir::SnapApp,
+        vir::SnapApp { mut base, position }: vir::SnapApp,
     ) -> Result<vir::Expr, Self::Error> {
         base = self.fallible_fold_boxed(base)?;
-        self.snapshot_encoder.snap_app(self.encoder, *base)
+        let pos = if position.is_default() {
+            base.pos()
+        } else {
+            position
+        };
+        self.snapshot_encoder
+            .snap_app(self.encoder, *base)
+            .map(|expr| expr.set_outer_default_pos(pos))
     }
 
     fn fallible_fold_func_app(
@@ -47,7 +54,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> FallibleExprFolder for SnapshotPatcher<'p, 'v, 'tcx>
                 // TODO: this patches more than it should
                 // so it could cover up/muddle some type errors in the VIR
                 if *arg.get_type() != formal_arg.typ {
-                    self.snapshot_encoder.snap_app(self.encoder, arg)
+                    let pos = arg.pos();
+                    self.snapshot_encoder
+                        .snap_app(self.encoder, arg)
+                        .map(|expr| expr.set_outer_default_pos(pos))
                 } else {
                     Ok(arg)
                 }
@@ -78,7 +88,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> FallibleExprFolder for SnapshotPatcher<'p, 'v, 'tcx>
                 let folded_arg = FallibleExprFolder::fallible_fold(self, arg)?;
                 // TODO: same note as for fallible_fold_func_app applies
                 if *folded_arg.get_type() != formal_arg.typ {
-                    self.snapshot_encoder.snap_app(self.encoder, folded_arg)
+                    let pos = folded_arg.pos();
+                    self.snapshot_encoder
+                        .snap_app(self.encoder, folded_arg)
+                        .map(|expr| expr.set_outer_default_pos(pos))
                 } else {
                     Ok(folded_arg)
                 }
@@ -100,7 +113,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> FallibleExprFolder for SnapshotPatcher<'p, 'v, 'tcx>
             position,
         }: vir::FieldExpr,
     ) -> Result<vir::Expr, Self::Error> {
-        match receiver {
+        // Snapshot field accesses are replaced by domain function calls, which
+        // should keep the position of the original field access.
+        let patched = match receiver {
             box vir::Expr::Variant(vir::Variant {
                 base: receiver,
                 variant_index: variant,
@@ -147,7 +162,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> FallibleExprFolder for SnapshotPatcher<'p, 'v, 'tcx>
                     })),
                 }
             }
-        }
+        };
+        patched.map(|expr| expr.set_outer_default_pos(position))
     }
 
     fn fallible_fold_forall(
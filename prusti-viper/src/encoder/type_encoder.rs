@@ -365,26 +365,22 @@ impl<'p, 'v, 'r: 'v, 'tcx: 'v> TypeEncoder<'p, 'v, 'tcx> {
             }
 
             ty::TyKind::Closure(_def_id, internal_substs) => {
-                let closure_substs = internal_substs.as_closure();
-                match closure_substs.tupled_upvars_ty().kind() {
-                    ty::TyKind::Tuple(_upvar_substs) => {
-                        // TODO: this should encode the state of a closure, i.e.
-                        // the "self" parameter passed into the implementation
-                        // function generated for every closure. This should
-                        // work using snapshots. For now, the "self" parameter
-                        // is skipped in encoding.
-
-                        // let field_name = "upvars".to_owned();
-                        // let field = self.encoder.encode_raw_ref_field(field_name, cl_upvars);
-                        // let pred = vir::Predicate::new_struct(typ, vec![field.clone()]);
-                        let pred = vir::Predicate::new_struct(typ.clone(), vec![]);
-                        // trace!("Encoded closure type {:?} as {:?} with field {:?}", typ, pred, field);
-                        trace!("Encoded closure type {:?} as {:?}", typ, pred);
-                        vec![pred]
-                    }
-
-                    _ => unreachable!()
-                }
+                // A closure is a struct of the places that it captures, which
+                // are stored by value or as references. The permissions of
+                // the places captured by value and of the targets of the
+                // captured references are thereby held by the closure.
+                let fields = internal_substs
+                    .as_closure()
+                    .upvar_tys()
+                    .enumerate()
+                    .map(|(field_num, ty)| {
+                        let field_name = format!("closure_{}", field_num);
+                        self.encoder.encode_raw_ref_field(field_name, ty)
+                    })
+                    .collect::<Result<_, _>>()?;
+                let pred = vir::Predicate::new_struct(typ, fields);
+                trace!("Encoded closure type {:?} as {:?}", self.ty, pred);
+                vec![pred]
             }
 
             ty::TyKind::Array(..) => {
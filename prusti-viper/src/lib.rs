@@ -28,6 +28,10 @@ extern crate rustc_data_structures;
 extern crate lazy_static;
 extern crate rustc_hash;
 
+mod coverage_report;
 pub mod encoder;
+mod performance_report;
 mod utils;
+mod verification_artifact;
+pub mod verification_manifest;
 pub mod verifier;
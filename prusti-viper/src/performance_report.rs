@@ -0,0 +1,170 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A report of how much time the verification of each Rust function takes,
+//! written when `PERFORMANCE_REPORT` is enabled.
+
+use rustc_hash::FxHashMap;
+use serde::{Serialize, Serializer};
+use std::{fmt::Write as _, fs, io, path::Path, time::Duration};
+use vir_crate::{common::graphviz::escape_html, polymorphic as vir};
+
+/// The cost of verifying one Viper program, which corresponds to one Rust
+/// function.
+#[derive(Default, Serialize)]
+pub struct FunctionCost {
+    /// The absolute path of the Rust function.
+    pub function: String,
+    /// The name of the Viper program.
+    pub program: String,
+    /// The time spent encoding the function, including the fold-unfold time.
+    #[serde(rename = "encoding_ms", serialize_with = "serialize_millis")]
+    pub encoding_time: Duration,
+    /// The time spent in the fold-unfold algorithm.
+    #[serde(rename = "foldunfold_ms", serialize_with = "serialize_millis")]
+    pub foldunfold_time: Duration,
+    /// The time spent verifying the Viper program.
+    #[serde(rename = "viper_ms", serialize_with = "serialize_millis")]
+    pub viper_time: Duration,
+    /// The number of conditional branches in the encoded methods.
+    pub branches: usize,
+}
+
+impl FunctionCost {
+    pub fn total_time(&self) -> Duration {
+        self.encoding_time + self.viper_time
+    }
+}
+
+fn serialize_millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+#[derive(Default)]
+pub struct PerformanceReport {
+    /// The costs indexed by the name of the Viper program.
+    costs: FxHashMap<String, FunctionCost>,
+}
+
+impl PerformanceReport {
+    pub fn cost_mut(&mut self, program: &str) -> &mut FunctionCost {
+        self.costs
+            .entry(program.to_string())
+            .or_insert_with(|| FunctionCost {
+                function: program.to_string(),
+                program: program.to_string(),
+                ..Default::default()
+            })
+    }
+
+    pub fn record_branches(&mut self, program: &vir::Program) {
+        let branches = program
+            .methods
+            .iter()
+            .flat_map(|method| &method.basic_blocks)
+            .map(|block| match &block.successor {
+                vir::Successor::GotoSwitch(guarded_targets, _) => guarded_targets.len(),
+                _ => 0,
+            })
+            .sum();
+        self.cost_mut(&program.name).branches = branches;
+    }
+
+    /// The costs, most expensive first.
+    pub fn sorted_costs(&self) -> Vec<&FunctionCost> {
+        let mut costs: Vec<_> = self.costs.values().collect();
+        costs.sort_by(|a, b| {
+            b.total_time()
+                .cmp(&a.total_time())
+                .then_with(|| a.function.cmp(&b.function))
+        });
+        costs
+    }
+
+    /// Write `prusti-report.json` and `prusti-report.html` to `dir`.
+    pub fn write(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let costs = self.sorted_costs();
+        let json = serde_json::to_string_pretty(&costs)?;
+        fs::write(dir.join("prusti-report.json"), json)?;
+        fs::write(dir.join("prusti-report.html"), self.to_html(&costs))
+    }
+
+    fn to_html(&self, costs: &[&FunctionCost]) -> String {
+        let millis = |duration: Duration| format!("{:.1}", duration.as_secs_f64() * 1000.0);
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Prusti verification report</title>\n</head>\n<body>\n\
+             <table>\n<tr><th>Function</th><th>Program</th><th>Total (ms)</th><th>Encoding (ms)</th>\
+             <th>Fold-unfold (ms)</th><th>Viper (ms)</th><th>Branches</th></tr>\n",
+        );
+        for cost in costs {
+            writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(&cost.function),
+                escape_html(&cost.program),
+                millis(cost.total_time()),
+                millis(cost.encoding_time),
+                millis(cost.foldunfold_time),
+                millis(cost.viper_time),
+                cost.branches,
+            )
+            .unwrap();
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> PerformanceReport {
+        let mut report = PerformanceReport::default();
+        for (program, encoding_ms, viper_ms) in [("m_a", 5, 10), ("m_b<T>", 20, 30), ("m_c", 1, 14)]
+        {
+            let cost = report.cost_mut(program);
+            cost.encoding_time = Duration::from_millis(encoding_ms);
+            cost.viper_time = Duration::from_millis(viper_ms);
+        }
+        report
+    }
+
+    #[test]
+    fn test_sorted_costs() {
+        let report = report();
+        let programs: Vec<_> = report
+            .sorted_costs()
+            .iter()
+            .map(|cost| cost.program.as_str())
+            .collect();
+        // The ties between `m_a` and `m_c` are broken by the function name.
+        assert_eq!(programs, ["m_b<T>", "m_a", "m_c"]);
+    }
+
+    #[test]
+    fn test_json() {
+        let report = report();
+        let json = serde_json::to_value(&report.sorted_costs()).unwrap();
+        assert_eq!(json[0]["function"], "m_b<T>");
+        assert_eq!(json[0]["encoding_ms"], 20.0);
+        assert_eq!(json[0]["viper_ms"], 30.0);
+        assert_eq!(json[0]["branches"], 0);
+    }
+
+    #[test]
+    fn test_html() {
+        let report = report();
+        let html = report.to_html(&report.sorted_costs());
+        assert!(html.contains(
+            "<tr><td>m_b&lt;T&gt;</td><td>m_b&lt;T&gt;</td>\
+             <td>50.0</td><td>20.0</td><td>0.0</td><td>30.0</td><td>0</td></tr>"
+        ));
+        assert!(!html.contains("m_b<T>"));
+    }
+}
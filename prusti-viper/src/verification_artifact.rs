@@ -0,0 +1,139 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A signed certificate of a successful verification, written to the file
+//! given by `VERIFICATION_ARTIFACT`. It records the hashes of the source
+//! files, the configuration, the versions of the backend and the outcome of
+//! the verification of each function, so that `prusti-verify-artifact` can
+//! check in CI that the sources were verified without running Prusti again.
+//!
+//! The artifact is signed with the Ed25519 key given by
+//! `ARTIFACT_SIGNING_KEY`. The signature covers the compact JSON encoding
+//! of the `artifact` field with its object keys sorted.
+
+use crate::coverage_report::FunctionCoverage;
+use ring::{
+    digest,
+    signature::{Ed25519KeyPair, KeyPair},
+};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The version of the format of the artifact, checked by
+/// `prusti-verify-artifact`.
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+pub struct VerificationArtifact {
+    pub format_version: u32,
+    #[serde(rename = "crate")]
+    pub krate: String,
+    /// The time of the verification, in seconds since the Unix epoch.
+    pub created_at: u64,
+    pub prusti_version: String,
+    pub backend: BackendVersions,
+    /// The value of each configuration flag.
+    pub configuration: BTreeMap<String, String>,
+    pub sources: Vec<SourceFile>,
+    pub functions: Vec<FunctionCoverage>,
+}
+
+#[derive(Serialize)]
+pub struct BackendVersions {
+    pub viper_backend: String,
+    /// The output of `$Z3_EXE --version`, if Z3 was found.
+    pub z3: Option<String>,
+    /// The SHA-256 hash of each JAR file in `VIPER_HOME`.
+    pub viper_jars: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+pub struct SourceFile {
+    /// The path of the file as given to the compiler.
+    pub path: String,
+    pub sha256: String,
+}
+
+#[derive(Serialize)]
+struct SignedArtifact {
+    artifact: serde_json::Value,
+    /// The hex-encoded Ed25519 public key that checks the signature.
+    public_key: String,
+    signature: String,
+}
+
+impl BackendVersions {
+    /// The versions of the backend that is found through the `Z3_EXE` and
+    /// `VIPER_HOME` environment variables.
+    pub fn current(viper_backend: String) -> io::Result<Self> {
+        let z3 = env::var_os("Z3_EXE").and_then(|z3_exe| {
+            let output = Command::new(z3_exe).arg("--version").output().ok()?;
+            Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        });
+        let mut viper_jars = BTreeMap::new();
+        if let Some(viper_home) = env::var_os("VIPER_HOME") {
+            for entry in fs::read_dir(viper_home)? {
+                let path = entry?.path();
+                if path
+                    .extension()
+                    .map_or(false, |extension| extension == "jar")
+                {
+                    let name = path.file_name().unwrap().to_string_lossy().to_string();
+                    viper_jars.insert(name, sha256_file(&path)?);
+                }
+            }
+        }
+        Ok(BackendVersions {
+            viper_backend,
+            z3,
+            viper_jars,
+        })
+    }
+}
+
+impl SourceFile {
+    pub fn new(path: PathBuf) -> io::Result<Self> {
+        Ok(SourceFile {
+            sha256: sha256_file(&path)?,
+            path: path.to_string_lossy().to_string(),
+        })
+    }
+}
+
+impl VerificationArtifact {
+    /// Sign the artifact with the Ed25519 private key stored in PKCS#8
+    /// format at `key_path` and write it to `path`.
+    pub fn sign_and_write(&self, key_path: &Path, path: &Path) -> io::Result<()> {
+        let key = fs::read(key_path)?;
+        let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&key).map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid Ed25519 key {}: {}", key_path.display(), error),
+            )
+        })?;
+        let artifact = serde_json::to_value(self)?;
+        let signature = key_pair.sign(&serde_json::to_vec(&artifact)?);
+        let signed_artifact = SignedArtifact {
+            artifact,
+            public_key: hex::encode(key_pair.public_key()),
+            signature: hex::encode(signature),
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&signed_artifact)?)
+    }
+}
+
+fn sha256_file(path: &Path) -> io::Result<String> {
+    let content = fs::read(path)?;
+    Ok(hex::encode(digest::digest(&digest::SHA256, &content)))
+}
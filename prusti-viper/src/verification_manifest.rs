@@ -0,0 +1,202 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A manifest recording which modules were verified, by whom and when, kept
+//! in the file given by `VERIFICATION_MANIFEST`. It documents the modules
+//! that are listed in `ASSUMED_VERIFIED_MODULES` and thus not verified again.
+//! The hash of the source of each module is recorded too, so that a module
+//! that changed since its verification is not taken as verified.
+
+use ring::digest;
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    #[serde(rename = "crate")]
+    pub krate: String,
+    /// The path of the module relative to the crate root, such as
+    /// `parser::lexer`, or `crate` for the crate root.
+    pub module: String,
+    pub verified_by: String,
+    /// The time of the verification, in seconds since the Unix epoch.
+    pub verified_at: u64,
+    /// The number of functions of the module that were verified.
+    pub functions: usize,
+    /// The SHA-256 hash of the source of the module when it was verified.
+    /// Entries written before the hash was recorded have an empty hash.
+    #[serde(default)]
+    pub source_hash: String,
+}
+
+/// Whether a module is recorded as verified in its current version.
+#[derive(Debug)]
+pub enum ManifestStatus<'a> {
+    Verified(&'a ManifestEntry),
+    /// The module was verified, but its source changed since.
+    Changed(&'a ManifestEntry),
+    Missing,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct VerificationManifest {
+    modules: Vec<ManifestEntry>,
+}
+
+impl VerificationManifest {
+    /// Read the manifest at `path`, which may not exist yet.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn find(&self, krate: &str, module: &str) -> Option<&ManifestEntry> {
+        self.modules
+            .iter()
+            .find(|entry| entry.krate == krate && entry.module == module)
+    }
+
+    /// Whether the module with the source hash `source_hash` is recorded.
+    pub fn status(&self, krate: &str, module: &str, source_hash: &str) -> ManifestStatus<'_> {
+        match self.find(krate, module) {
+            Some(entry) if entry.source_hash == source_hash => ManifestStatus::Verified(entry),
+            Some(entry) => ManifestStatus::Changed(entry),
+            None => ManifestStatus::Missing,
+        }
+    }
+
+    /// Add `entry`, replacing the previous entry of the same module.
+    pub fn record(&mut self, entry: ManifestEntry) {
+        self.modules
+            .retain(|other| other.krate != entry.krate || other.module != entry.module);
+        self.modules.push(entry);
+        self.modules
+            .sort_by(|a, b| (&a.krate, &a.module).cmp(&(&b.krate, &b.module)));
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)
+    }
+}
+
+/// The hex-encoded SHA-256 hash of the source of a module.
+pub fn source_hash(source: &str) -> String {
+    hex::encode(digest::digest(&digest::SHA256, source.as_bytes()))
+}
+
+/// Whether the module at `module_path` is one of `modules` or is nested in
+/// one of them. The paths are relative to the crate root and may start with
+/// `crate::`.
+pub fn is_in_modules(module_path: &str, modules: &[String]) -> bool {
+    modules.iter().any(|module| {
+        let module = module.strip_prefix("crate::").unwrap_or(module);
+        module == "crate"
+            || module_path == module
+            || module_path
+                .strip_prefix(module)
+                .map_or(false, |rest| rest.starts_with("::"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(module: &str, source: &str) -> ManifestEntry {
+        ManifestEntry {
+            krate: "app".to_string(),
+            module: module.to_string(),
+            verified_by: "alice".to_string(),
+            verified_at: 0,
+            functions: 1,
+            source_hash: source_hash(source),
+        }
+    }
+
+    #[test]
+    fn test_is_in_modules() {
+        let modules = vec!["parser".to_string(), "crate::net::tcp".to_string()];
+        assert!(is_in_modules("parser", &modules));
+        assert!(is_in_modules("parser::lexer", &modules));
+        assert!(is_in_modules("net::tcp", &modules));
+        assert!(!is_in_modules("parser_utils", &modules));
+        assert!(!is_in_modules("net", &modules));
+        assert!(!is_in_modules("crate", &modules));
+        assert!(is_in_modules("net", &["crate".to_string()]));
+    }
+
+    #[test]
+    fn test_status() {
+        let mut manifest = VerificationManifest::default();
+        manifest.record(entry("parser", "fn parse() {}"));
+        assert!(matches!(
+            manifest.status("app", "parser", &source_hash("fn parse() {}")),
+            ManifestStatus::Verified(_)
+        ));
+        assert!(matches!(
+            manifest.status("app", "parser", &source_hash("fn parse() { loop {} }")),
+            ManifestStatus::Changed(_)
+        ));
+        assert!(matches!(
+            manifest.status("app", "lexer", &source_hash("fn parse() {}")),
+            ManifestStatus::Missing
+        ));
+        assert!(matches!(
+            manifest.status("lib", "parser", &source_hash("fn parse() {}")),
+            ManifestStatus::Missing
+        ));
+        // A new verification replaces the previous entry of the module.
+        manifest.record(entry("parser", "fn parse() { loop {} }"));
+        assert!(matches!(
+            manifest.status("app", "parser", &source_hash("fn parse() { loop {} }")),
+            ManifestStatus::Verified(_)
+        ));
+        assert_eq!(manifest.modules.len(), 1);
+    }
+
+    #[test]
+    fn test_read_write() {
+        let path = std::env::temp_dir()
+            .join(format!("prusti-manifest-{}", std::process::id()))
+            .join("manifest.json");
+        // A missing manifest is empty.
+        let mut manifest = VerificationManifest::read(&path).unwrap();
+        assert!(manifest.modules.is_empty());
+        manifest.record(entry("net::tcp", "fn connect() {}"));
+        manifest.record(entry("crate", "mod net;"));
+        manifest.write(&path).unwrap();
+        let manifest = VerificationManifest::read(&path).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        let modules: Vec<_> = manifest
+            .modules
+            .iter()
+            .map(|entry| entry.module.as_str())
+            .collect();
+        assert_eq!(modules, ["crate", "net::tcp"]);
+        assert!(matches!(
+            manifest.status("app", "net::tcp", &source_hash("fn connect() {}")),
+            ManifestStatus::Verified(_)
+        ));
+    }
+
+    #[test]
+    fn test_entry_without_source_hash() {
+        let json = r#"{"modules": [{"crate": "app", "module": "parser", "verified_by": "alice",
+            "verified_at": 0, "functions": 1}]}"#;
+        let manifest: VerificationManifest = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            manifest.status("app", "parser", &source_hash("fn parse() {}")),
+            ManifestStatus::Changed(_)
+        ));
+    }
+}
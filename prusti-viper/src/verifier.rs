@@ -4,16 +4,23 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use prusti_common::vir::{optimizations::optimize_program};
+use prusti_common::vir::{optimizations::optimize_program, viper_text::program_to_viper_text};
 use prusti_common::{
-    config, report::log, Stopwatch, vir::program::Program,
+    config, report::{log, user}, Stopwatch, vir::program::Program,
 };
 use crate::encoder::Encoder;
 use crate::encoder::counterexample_translation;
+use crate::encoder::FailureKind;
+use crate::encoder::SpecificationsInterface;
+use crate::coverage_report::{CoverageReport, CoverageStatus, FunctionCoverage};
+use crate::performance_report::PerformanceReport;
+use crate::verification_artifact::{self, BackendVersions, SourceFile, VerificationArtifact};
+use crate::verification_manifest::{self, ManifestEntry, VerificationManifest};
 // use prusti_filter::validators::Validator;
 use prusti_interface::data::VerificationResult;
-use prusti_interface::data::VerificationTask;
+use prusti_interface::data::{ProcedureDefId, VerificationTask};
 use prusti_interface::environment::Environment;
+use prusti_interface::error_codes::ErrorCode;
 use prusti_interface::PrustiError;
 // use prusti_interface::specifications::TypedSpecificationMap;
 
@@ -22,10 +29,16 @@ use viper::{self, PersistentCache, Viper};
 
 
 use prusti_interface::specs::typed;
+use prusti_interface::utils::read_prusti_attr;
 use ::log::{info, debug, error};
-use prusti_server::{VerificationRequest, PrustiClient, process_verification_request, spawn_server_thread};
-use rustc_span::DUMMY_SP;
+use prusti_server::{VerificationRequest, ViperBackendConfig, PrustiClient, ViperServerClient, process_verification_request, spawn_server_thread};
+use rustc_hash::{FxHashMap, FxHashSet};
+use rustc_hir::def_id::LocalDefId;
+use rustc_middle::{mir, ty};
+use rustc_span::{FileName, Span, DUMMY_SP};
 use prusti_server::tokio::runtime::Builder;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use std::{env, io, path::Path};
 
 // /// A verifier builder is an object that lives entire program's
 // /// lifetime, has no mutable state, and is responsible for constructing
@@ -141,6 +154,9 @@ where
 {
     env: &'v Environment<'tcx>,
     encoder: Encoder<'v, 'tcx>,
+    /// The procedures that call a `#[bounded(..)]` procedure, directly or
+    /// through other procedures, mapped to that procedure.
+    bounded_callers: FxHashMap<ProcedureDefId, ProcedureDefId>,
 }
 
 impl<'v, 'tcx> Verifier<'v, 'tcx> {
@@ -151,6 +167,7 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
         Verifier {
             env,
             encoder: Encoder::new(env, def_spec),
+            bounded_callers: FxHashMap::default(),
         }
     }
 
@@ -159,6 +176,7 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
             "Received {} functions to be verified:",
             task.procedures.len()
         );
+        self.bounded_callers = self.compute_bounded_callers(&task.procedures);
 
         let mut stopwatch = Stopwatch::start("prusti-viper", "encoding to Viper");
 
@@ -254,7 +272,11 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
             stopwatch.start_next("optimizing Viper program");
             let source_file_name = self.encoder.env().source_file_name();
             polymorphic_programs.into_iter().map(
-                |program| Program::Legacy(optimize_program(program, &source_file_name).into())
+                |program| Program::Legacy(optimize_program(
+                    program,
+                    &source_file_name,
+                    |function_name| self.encoder.is_never_inlined_spec(function_name),
+                ).into())
             ).collect()
         } else {
             polymorphic_programs.into_iter().map(
@@ -263,12 +285,28 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
         };
         programs.extend(self.encoder.get_core_proof_programs());
 
+        if config::print_viper_program() {
+            print_viper_programs(&programs);
+        }
+
         stopwatch.start_next("verifying Viper program");
-        let verification_results = verify_programs(self.env, programs);
+        let verification_results = verify_programs(
+            self.env,
+            programs,
+            |program_name| self.encoder.uses_nonlinear_arithmetic(program_name),
+            &mut self.encoder.performance_report(),
+        );
         stopwatch.finish();
 
+        if config::performance_report() {
+            if let Err(error) = self.encoder.performance_report().write(&config::log_dir()) {
+                error!("Failed to write the performance report: {}", error);
+            }
+        }
+
         // Group verification results
         let mut verification_errors : Vec<_> = vec![];
+        let mut reachable_checks = FxHashSet::default();
         let mut consistency_errors : Vec<_> = vec![];
         let mut java_exceptions : Vec<_> = vec![];
         for (method_name, result) in verification_results.into_iter() {
@@ -281,7 +319,11 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
                 }
                 viper::VerificationResult::Failure(errors) => {
                     for error in errors.into_iter() {
-                        verification_errors.push((method_name.clone(), error));
+                        if self.encoder.error_manager().is_reachability_check(&error) {
+                            reachable_checks.extend(error.pos_id.and_then(|id| id.parse::<u64>().ok()));
+                        } else {
+                            verification_errors.push((method_name.clone(), error));
+                        }
                     }
                 }
                 viper::VerificationResult::JavaException(exception) => {
@@ -293,6 +335,7 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
         // Convert verification results to Prusti errors
         let error_manager = self.encoder.error_manager();
         let mut result = VerificationResult::Success;
+        let has_internal_errors = !consistency_errors.is_empty() || !java_exceptions.is_empty();
 
         for (method, error) in consistency_errors.into_iter() {
             PrustiError::internal(
@@ -311,8 +354,16 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
 
         // Report verification errors
         let mut prusti_errors: Vec<_> = vec![];
+        let mut failed_procedures = FxHashSet::default();
+        let mut panicking_procedures = FxHashSet::default();
         for (method, verification_error) in verification_errors.into_iter() {
             debug!("Verification error in {}: {:?}", method, verification_error);
+            if let Some(def_id) = error_manager.get_def_id(&verification_error) {
+                failed_procedures.insert(def_id);
+                if error_manager.is_panic_error(&verification_error) {
+                    panicking_procedures.insert(def_id);
+                }
+            }
             let mut prusti_error = error_manager.translate_verification_error(&verification_error);
 
             // annotate with counterexample, if requested
@@ -337,11 +388,42 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
                 }
             }
 
-            prusti_errors.push(prusti_error);
+            // rank the error by how confident the verifier is that it is real
+            let opt_failure_kind = if config::rank_verification_errors() && prusti_error.is_error() {
+                let failure_kind = FailureKind::of(&verification_error);
+                prusti_error = failure_kind.annotate_error(prusti_error, &verification_error);
+                Some(failure_kind)
+            } else {
+                None
+            };
+
+            prusti_errors.push((opt_failure_kind, prusti_error));
         }
         prusti_errors.sort();
+        drop(error_manager);
 
-        for prusti_error in prusti_errors {
+        let opt_ranking_summary = if config::rank_verification_errors() {
+            let count = |kind| prusti_errors.iter()
+                .filter(|(opt_kind, error)| *opt_kind == Some(kind) && !error.is_disabled())
+                .count();
+            let (violated, unknown, resource_limit) = (
+                count(FailureKind::Violated),
+                count(FailureKind::Unknown),
+                count(FailureKind::ResourceLimit),
+            );
+            if violated + unknown + resource_limit > 0 {
+                Some(format!(
+                    "Verification errors: {} definitely violated, {} unknown, {} resource limit",
+                    violated, unknown, resource_limit,
+                ))
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        for (_, prusti_error) in prusti_errors {
             debug!("Prusti error: {:?}", prusti_error);
             if prusti_error.is_disabled() {
                 prusti_error.cancel();
@@ -351,18 +433,549 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
             result = VerificationResult::Failure;
         }
 
+        if let Some(ranking_summary) = opt_ranking_summary {
+            user::message(ranking_summary);
+        }
+
         if encoding_errors_count != 0 {
             result = VerificationResult::Failure;
         }
 
+        self.report_bounded_procedures(&task.procedures, &failed_procedures);
+
+        if config::unsupported_features_summary() {
+            let unsupported_features = self.encoder.unsupported_features();
+            if !unsupported_features.is_empty() {
+                user::message(unsupported_features.summary(self.env.codemap()));
+            }
+        }
+
+        if config::coverage_report() {
+            let coverage_report = self.coverage_report(&task.procedures, &failed_procedures);
+            user::message(coverage_report.summary());
+            if let Err(error) = coverage_report.write(&config::log_dir()) {
+                error!("Failed to write the coverage report: {}", error);
+            }
+        }
+
+        if config::report_dead_specs() {
+            self.report_dead_specs(&task.procedures);
+        }
+
+        if config::report_dead_code() {
+            self.report_dead_code(&task.procedures, &failed_procedures, &reachable_checks);
+        }
+
+        if config::law_report() {
+            if let Some(law_report) = self.law_report(&task.procedures, &failed_procedures) {
+                user::message(law_report);
+            }
+        }
+
+        if config::strict_panic_freedom() {
+            user::message(self.panic_freedom_summary(
+                &task.procedures,
+                &failed_procedures,
+                &panicking_procedures,
+            ));
+        }
+
+        if let Some(manifest_path) = config::verification_manifest() {
+            if encoding_errors_count == 0 && !has_internal_errors {
+                let manifest_path = Path::new(&manifest_path);
+                let update = self.update_manifest(manifest_path, &task.procedures, &failed_procedures);
+                if let Err(error) = update {
+                    error!("Failed to update the verification manifest: {}", error);
+                }
+            }
+        }
+
         result
     }
+
+    /// Record in the verification manifest the modules whose procedures were
+    /// all verified successfully.
+    fn update_manifest(
+        &self,
+        manifest_path: &Path,
+        procedures: &[ProcedureDefId],
+        failed_procedures: &FxHashSet<ProcedureDefId>,
+    ) -> io::Result<()> {
+        let mut modules: FxHashMap<LocalDefId, Option<usize>> = FxHashMap::default();
+        for &proc_id in procedures {
+            let module = self.env.get_parent_module(proc_id.expect_local());
+            let functions = modules.entry(module).or_insert(Some(0));
+            match self.coverage_status(proc_id, failed_procedures) {
+                CoverageStatus::Failed | CoverageStatus::Unsupported | CoverageStatus::Bounded => {
+                    *functions = None
+                }
+                _ => *functions = functions.map(|count| count + 1),
+            }
+        }
+        let verified_by = config::verified_by()
+            .or_else(|| env::var("USER").ok())
+            .or_else(|| env::var("USERNAME").ok())
+            .unwrap_or_else(|| "unknown".to_string());
+        let verified_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        let mut manifest = VerificationManifest::read(manifest_path)?;
+        for (module, functions) in modules {
+            // A module whose source is not available cannot be checked later.
+            let source = self.env.get_module_source(module);
+            if let (Some(functions), Some(source)) = (functions, source) {
+                manifest.record(ManifestEntry {
+                    krate: self.env.crate_name(),
+                    module: self.env.get_module_path(module),
+                    verified_by: verified_by.clone(),
+                    verified_at,
+                    functions,
+                    source_hash: verification_manifest::source_hash(&source),
+                });
+            }
+        }
+        manifest.write(manifest_path)
+    }
+
+    /// A summary of the procedures of the crate that were proven not to panic.
+    /// A procedure with other verification errors is not proven panic-free,
+    /// because e.g. the preconditions of its callees might not hold.
+    fn panic_freedom_summary(
+        &self,
+        procedures: &[ProcedureDefId],
+        failed_procedures: &FxHashSet<ProcedureDefId>,
+        panicking_procedures: &FxHashSet<ProcedureDefId>,
+    ) -> String {
+        let mut panic_free_count = 0;
+        let mut other_procedures = vec![];
+        for &proc_id in procedures {
+            let status = if panicking_procedures.contains(&proc_id) {
+                "might panic"
+            } else {
+                match self.coverage_status(proc_id, failed_procedures) {
+                    CoverageStatus::Verified | CoverageStatus::NoSpecs => {
+                        panic_free_count += 1;
+                        continue;
+                    }
+                    CoverageStatus::Failed => "unproven",
+                    CoverageStatus::Bounded => "bounded",
+                    CoverageStatus::Trusted | CoverageStatus::Unsupported => "not verified",
+                }
+            };
+            other_procedures.push((status, self.env.get_absolute_item_name(proc_id)));
+        }
+        other_procedures.sort();
+        let mut summary = format!(
+            "Panic freedom of crate {}: {} of {} functions are panic-free\n",
+            self.env.crate_name(),
+            panic_free_count,
+            procedures.len(),
+        );
+        for (status, proc_name) in other_procedures {
+            summary.push_str(&format!("  [{}] {}\n", status, proc_name));
+        }
+        summary
+    }
+
+    /// A summary of whether the laws of the traits implemented in the crate hold
+    /// for each implementation. A law holds for an implementation if the
+    /// implementation of the law method is verified against the contract of
+    /// the law or, if the implementation does not override it, if the default
+    /// body of the law method is verified. Returns `None` if no implemented
+    /// trait declares laws.
+    fn law_report(
+        &self,
+        procedures: &[ProcedureDefId],
+        failed_procedures: &FxHashSet<ProcedureDefId>,
+    ) -> Option<String> {
+        let tcx = self.env.tcx();
+        let mut law_count = 0;
+        let mut holding_count = 0;
+        let mut lines = vec![];
+        for (&trait_id, impl_ids) in tcx.all_local_trait_impls(()) {
+            let laws: Vec<_> = tcx.associated_items(trait_id)
+                .in_definition_order()
+                .filter(|item| self.env.has_prusti_attribute(item.def_id, "law"))
+                .collect();
+            for &impl_id in impl_ids {
+                let impl_ty = tcx.type_of(impl_id.to_def_id());
+                for law in &laws {
+                    let (proof_id, by_default) = match self.env.get_assoc_item(impl_id.to_def_id(), law.name) {
+                        Some(item) => (item.def_id, false),
+                        None => (law.def_id, true),
+                    };
+                    let status = if !procedures.contains(&proof_id) {
+                        "not verified"
+                    } else {
+                        match self.coverage_status(proof_id, failed_procedures) {
+                            CoverageStatus::Verified | CoverageStatus::NoSpecs if by_default => {
+                                "holds by default"
+                            }
+                            CoverageStatus::Verified | CoverageStatus::NoSpecs => "holds",
+                            CoverageStatus::Failed => "violated",
+                            CoverageStatus::Bounded => "bounded",
+                            CoverageStatus::Trusted => "assumed",
+                            CoverageStatus::Unsupported => "not verified",
+                        }
+                    };
+                    law_count += 1;
+                    if status.starts_with("holds") {
+                        holding_count += 1;
+                    }
+                    lines.push((
+                        format!("<{} as {}>::{}", impl_ty, tcx.def_path_str(trait_id), law.name),
+                        status,
+                    ));
+                }
+            }
+        }
+        if law_count == 0 {
+            return None;
+        }
+        lines.sort();
+        let mut summary = format!(
+            "Law compliance of crate {}: {} of {} laws hold for their implementations\n",
+            self.env.crate_name(),
+            holding_count,
+            law_count,
+        );
+        for (law, status) in lines {
+            summary.push_str(&format!("  [{}] {}\n", status, law));
+        }
+        Some(summary)
+    }
+
+    /// Warn about the contracts of trusted procedures that are never assumed
+    /// at a call site, thus neither checked nor used by any proof.
+    fn report_dead_specs(&self, procedures: &[ProcedureDefId]) {
+        let mut dead_specs: Vec<_> = procedures
+            .iter()
+            .copied()
+            .filter(|&proc_id| {
+                self.encoder.is_trusted(proc_id) &&
+                    self.has_contract(proc_id) &&
+                    !self.encoder.is_contract_used(proc_id)
+            })
+            .collect();
+        dead_specs.sort_by_key(|&proc_id| self.env.get_def_span(proc_id));
+        for proc_id in dead_specs {
+            PrustiError::warning(
+                format!(
+                    "the specification of trusted function {:?} is never used",
+                    self.env.get_item_name(proc_id)
+                ),
+                self.env.get_def_span(proc_id).into(),
+            )
+            .set_help(
+                "the function is not verified and is not called from verified code, so its \
+                specification is neither checked nor assumed",
+            )
+            .emit(self.env);
+        }
+    }
+
+    /// Warn that the `#[bounded(..)]` procedures that were verified without
+    /// errors, and the procedures that rely on their contracts, are not proven
+    /// correct for all executions.
+    fn report_bounded_procedures(
+        &self,
+        procedures: &[ProcedureDefId],
+        failed_procedures: &FxHashSet<ProcedureDefId>,
+    ) {
+        let mut bounded_procedures: Vec<_> = procedures
+            .iter()
+            .copied()
+            .filter(|&proc_id| {
+                self.coverage_status(proc_id, failed_procedures) == CoverageStatus::Bounded
+            })
+            .collect();
+        bounded_procedures.sort_by_key(|&proc_id| self.env.get_def_span(proc_id));
+        for proc_id in bounded_procedures {
+            let warning = if let Some(bound) = self.loop_unrolling(proc_id) {
+                PrustiError::warning(
+                    format!(
+                        "{:?} was only verified for the executions that iterate each loop at \
+                        most {} times",
+                        self.env.get_item_name(proc_id),
+                        bound,
+                    ),
+                    self.env.get_def_span(proc_id).into(),
+                )
+                .set_help(
+                    "to verify all executions, remove `#[bounded(..)]` and add loop invariants \
+                    with `body_invariant!(..)`",
+                )
+            } else {
+                let bounded_proc_id = self.bounded_callers[&proc_id];
+                PrustiError::warning(
+                    format!(
+                        "{:?} was verified assuming the contract of {:?}, which was only \
+                        verified for the executions that iterate each loop at most {} times",
+                        self.env.get_item_name(proc_id),
+                        self.env.get_item_name(bounded_proc_id),
+                        self.loop_unrolling(bounded_proc_id).unwrap(),
+                    ),
+                    self.env.get_def_span(proc_id).into(),
+                )
+            };
+            warning.emit(self.env);
+        }
+    }
+
+    /// The procedures that call a `#[bounded(..)]` procedure, directly or
+    /// through other procedures, and whose verification thus relies on a
+    /// contract that is only proven up to the loop bound. Each is mapped to
+    /// such a `#[bounded(..)]` procedure.
+    fn compute_bounded_callers(
+        &self,
+        procedures: &[ProcedureDefId],
+    ) -> FxHashMap<ProcedureDefId, ProcedureDefId> {
+        let tcx = self.env.tcx();
+        let mut callers: FxHashMap<ProcedureDefId, Vec<ProcedureDefId>> = FxHashMap::default();
+        for &caller in procedures {
+            let mir = self.env.local_mir(caller.expect_local(), self.env.identity_substs(caller));
+            for block in mir.basic_blocks() {
+                if let mir::TerminatorKind::Call { func, .. } = &block.terminator().kind {
+                    if let ty::TyKind::FnDef(called_def_id, call_substs) = func.ty(&*mir, tcx).kind() {
+                        let (callee, _) =
+                            self.env.resolve_method_call(caller, *called_def_id, call_substs);
+                        callers.entry(callee).or_default().push(caller);
+                    }
+                }
+            }
+        }
+        let mut bounded_callers = FxHashMap::default();
+        let mut worklist: Vec<_> = procedures
+            .iter()
+            .copied()
+            .filter(|&proc_id| self.loop_unrolling(proc_id).is_some())
+            .map(|proc_id| (proc_id, proc_id))
+            .collect();
+        while let Some((callee, bounded_proc_id)) = worklist.pop() {
+            for &caller in callers.get(&callee).into_iter().flatten() {
+                if self.loop_unrolling(caller).is_none()
+                    && !bounded_callers.contains_key(&caller)
+                {
+                    bounded_callers.insert(caller, bounded_proc_id);
+                    worklist.push((caller, bounded_proc_id));
+                }
+            }
+        }
+        bounded_callers
+    }
+
+    /// Warn about the branches of the verified procedures that were proven
+    /// unreachable, e.g. because of the precondition. The reachability checks
+    /// of a procedure with verification errors are ignored, because the
+    /// verifier does not explore the paths after a failed assertion.
+    fn report_dead_code(
+        &self,
+        procedures: &[ProcedureDefId],
+        failed_procedures: &FxHashSet<ProcedureDefId>,
+        reachable_checks: &FxHashSet<u64>,
+    ) {
+        // A branch can be checked more than once, e.g. in a loop.
+        let mut branches: FxHashMap<(ProcedureDefId, Span), bool> = FxHashMap::default();
+        for (pos_id, proc_id, span) in self.encoder.error_manager().reachability_checks() {
+            if !procedures.contains(&proc_id) {
+                continue;
+            }
+            if !matches!(
+                self.coverage_status(proc_id, failed_procedures),
+                CoverageStatus::Verified | CoverageStatus::NoSpecs
+            ) {
+                continue;
+            }
+            if let Some(span) = span.primary_span() {
+                *branches.entry((proc_id, span)).or_insert(false) |= reachable_checks.contains(&pos_id);
+            }
+        }
+        let mut dead_branches: Vec<_> = branches
+            .into_iter()
+            .filter(|(_, is_reachable)| !is_reachable)
+            .map(|((proc_id, span), _)| (span, proc_id))
+            .collect();
+        dead_branches.sort();
+        for (span, proc_id) in dead_branches {
+            PrustiError::warning("this code is unreachable", span.into())
+                .set_code(ErrorCode::P0034)
+                .add_note(
+                    format!(
+                        "the verifier proved that no execution of {:?} that satisfies its \
+                        precondition reaches this code",
+                        self.env.get_item_name(proc_id),
+                    ),
+                    None,
+                )
+                .set_help(
+                    "remove the code, or replace it with `unreachable!()` to document that \
+                    it cannot be reached",
+                )
+                .emit(self.env);
+        }
+    }
+
+    /// Write the signed verification artifact of a successful verification of
+    /// `task` to the file given by `VERIFICATION_ARTIFACT`, if any.
+    pub fn export_artifact(&self, task: &VerificationTask, prusti_version: &str) {
+        let artifact_path = match config::verification_artifact() {
+            Some(path) => path,
+            None => return,
+        };
+        let result = match config::artifact_signing_key() {
+            Some(key_path) => self.verification_artifact(task, prusti_version).and_then(|artifact| {
+                artifact.sign_and_write(Path::new(&key_path), Path::new(&artifact_path))
+            }),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "the configuration flag ARTIFACT_SIGNING_KEY is not set",
+            )),
+        };
+        match result {
+            Ok(()) => user::message(format!("Wrote the verification artifact to {}", artifact_path)),
+            Err(error) => PrustiError::warning(
+                format!("failed to write the verification artifact: {}", error),
+                DUMMY_SP.into(),
+            ).emit(self.env),
+        }
+    }
+
+    fn verification_artifact(
+        &self,
+        task: &VerificationTask,
+        prusti_version: &str,
+    ) -> io::Result<VerificationArtifact> {
+        let mut sources = vec![];
+        for file in self.env.codemap().files().iter() {
+            if file.is_imported() {
+                continue;
+            }
+            if let FileName::Real(name) = &file.name {
+                if let Some(path) = name.local_path() {
+                    sources.push(SourceFile::new(path.to_path_buf())?);
+                }
+            }
+        }
+        sources.sort_by(|a, b| a.path.cmp(&b.path));
+        let no_failures = FxHashSet::default();
+        let mut functions: Vec<_> = task.procedures
+            .iter()
+            .map(|&proc_id| FunctionCoverage {
+                function: self.env.get_absolute_item_name(proc_id),
+                status: self.coverage_status(proc_id, &no_failures),
+            })
+            .collect();
+        functions.sort_by(|a, b| a.function.cmp(&b.function));
+        Ok(VerificationArtifact {
+            format_version: verification_artifact::FORMAT_VERSION,
+            krate: self.env.crate_name(),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+            prusti_version: prusti_version.to_string(),
+            backend: BackendVersions::current(config::viper_backend())?,
+            configuration: config::all_settings(),
+            sources,
+            functions,
+        })
+    }
+
+    /// Classify the verified procedures by the outcome of their verification.
+    fn coverage_report(
+        &self,
+        procedures: &[ProcedureDefId],
+        failed_procedures: &FxHashSet<ProcedureDefId>,
+    ) -> CoverageReport {
+        let mut report = CoverageReport::default();
+        for &proc_id in procedures {
+            let status = self.coverage_status(proc_id, failed_procedures);
+            let module = self.env.tcx().parent_module_from_def_id(proc_id.expect_local());
+            report.record(
+                self.env.get_item_def_path(module.to_def_id()),
+                self.env.get_absolute_item_name(proc_id),
+                status,
+            );
+        }
+        report
+    }
+
+    fn coverage_status(
+        &self,
+        proc_id: ProcedureDefId,
+        failed_procedures: &FxHashSet<ProcedureDefId>,
+    ) -> CoverageStatus {
+        if self.encoder.is_trusted(proc_id) {
+            CoverageStatus::Trusted
+        } else if let Some(is_unsupported) = self.encoder.procedure_encoding_failure(proc_id) {
+            if is_unsupported {
+                CoverageStatus::Unsupported
+            } else {
+                CoverageStatus::Failed
+            }
+        } else if failed_procedures.contains(&proc_id) {
+            CoverageStatus::Failed
+        } else if self.loop_unrolling(proc_id).is_some()
+            || self.bounded_callers.contains_key(&proc_id)
+        {
+            CoverageStatus::Bounded
+        } else if self.has_specs(proc_id) {
+            CoverageStatus::Verified
+        } else {
+            CoverageStatus::NoSpecs
+        }
+    }
+
+    /// How many times the loops of the procedure are unrolled, if it is
+    /// `#[bounded(..)]`.
+    fn loop_unrolling(&self, proc_id: ProcedureDefId) -> Option<String> {
+        read_prusti_attr("bounded", self.env.tcx().get_attrs(proc_id))
+    }
+
+    /// Whether the procedure is pure or has a precondition, postcondition or
+    /// pledge.
+    fn has_specs(&self, proc_id: ProcedureDefId) -> bool {
+        self.encoder.is_pure(proc_id) || self.has_contract(proc_id)
+    }
+
+    /// Whether the procedure has a precondition, postcondition or pledge.
+    fn has_contract(&self, proc_id: ProcedureDefId) -> bool {
+        self.encoder.get_procedure_specs(proc_id).map_or(false, |specs| {
+            let is_non_empty = |item: &typed::SpecificationItem<Vec<_>>| {
+                item.extract_with_selective_replacement().map_or(false, |specs| !specs.is_empty())
+            };
+            is_non_empty(&specs.pres) || is_non_empty(&specs.posts) ||
+                specs.pledges.extract_with_selective_replacement().map_or(false, |pledges| !pledges.is_empty())
+        })
+    }
+}
+
+/// Print the Viper text of the programs to `stdout`, sorted by name.
+fn print_viper_programs(programs: &[Program]) {
+    let mut programs: Vec<_> = programs.iter().collect();
+    programs.sort_by_key(|program| program.get_name());
+    for program in programs {
+        println!("// Viper program: {}", program.get_name());
+        match program {
+            Program::Legacy(program) => match program_to_viper_text(program) {
+                Ok(viper_text) => println!("{}", viper_text.text),
+                Err(error) => println!("// {}", error),
+            },
+            Program::Low(_) => println!("// the printing of low programs is not supported"),
+        }
+    }
 }
 
 /// Verify a list of programs.
 /// Returns a list of (program_name, verification_result) tuples.
-fn verify_programs(env: &Environment, programs: Vec<Program>)
-    -> Vec<(String, viper::VerificationResult)>
+/// The programs for which `uses_nonlinear_arithmetic` holds are verified with
+/// the nonlinear arithmetic reasoning of the SMT solver enabled.
+/// The time spent verifying each program is recorded in `performance_report`.
+fn verify_programs(
+    env: &Environment,
+    programs: Vec<Program>,
+    uses_nonlinear_arithmetic: impl Fn(&str) -> bool,
+    performance_report: &mut PerformanceReport,
+) -> Vec<(String, viper::VerificationResult)>
 {
     let source_path = env.source_path();
     let rust_program_name = source_path
@@ -375,13 +988,44 @@ fn verify_programs(env: &Environment, programs: Vec<Program>)
         let program_name = program.get_name().to_string();
         // Prepend the Rust file name to the program.
         program.set_name(format!("{}_{}", rust_program_name, program_name));
+        let mut backend_config = ViperBackendConfig::default();
+        if uses_nonlinear_arithmetic(&program_name) {
+            backend_config.enable_nonlinear_arithmetic();
+        }
         let request = VerificationRequest {
             program,
-            backend_config: Default::default(),
+            backend_config,
         };
         (program_name, request)
     });
-    if let Some(server_address) = config::server_address() {
+    if let Some(viper_server_url) = config::viper_server_url() {
+        info!("Connecting to ViperServer at {}", viper_server_url);
+        let client = ViperServerClient::new(&viper_server_url).unwrap_or_else(|error| {
+            panic!(
+                "Could not parse ViperServer URL ({}) due to {:?}",
+                viper_server_url, error
+            )
+        });
+        let mut runtime = Builder::new()
+            .basic_scheduler()
+            .thread_name("prusti-viper")
+            .enable_all()
+            .build()
+            .expect("failed to construct Tokio runtime");
+        verification_requests.map(|(program_name, request)| {
+            let verification_start = Instant::now();
+            let remote_result = runtime.block_on(client.verify(request));
+            let result = remote_result.unwrap_or_else(|error| {
+                panic!(
+                    "Verification request of program {} failed: {:?}",
+                    program_name,
+                    error
+                )
+            });
+            performance_report.cost_mut(&program_name).viper_time = verification_start.elapsed();
+            (program_name, result)
+        }).collect()
+    } else if let Some(server_address) = config::server_address() {
         let server_address = if server_address == "MOCK" {
             spawn_server_thread().to_string()
         } else {
@@ -404,6 +1048,7 @@ fn verify_programs(env: &Environment, programs: Vec<Program>)
             .build()
             .expect("failed to construct Tokio runtime");
         verification_requests.map(|(program_name, request)| {
+            let verification_start = Instant::now();
             let remote_result = runtime.block_on(client.verify(request));
             let result = remote_result.unwrap_or_else(|error| {
                 panic!(
@@ -412,6 +1057,7 @@ fn verify_programs(env: &Environment, programs: Vec<Program>)
                     error
                 )
             });
+            performance_report.cost_mut(&program_name).viper_time = verification_start.elapsed();
             (program_name, result)
         }).collect()
     } else {
@@ -422,7 +1068,9 @@ fn verify_programs(env: &Environment, programs: Vec<Program>)
         stopwatch.finish();
         let mut cache = PersistentCache::load_cache(config::cache_path());
         verification_requests.map(|(program_name, request)| {
+            let verification_start = Instant::now();
             let result = process_verification_request(&viper_thread, request, &mut cache);
+            performance_report.cost_mut(&program_name).viper_time = verification_start.elapsed();
             (program_name, result)
         }).collect()
     }
@@ -79,6 +79,12 @@ impl rustc_driver::Callbacks for PrustiCompilerCalls {
             tcx.hir().walk_toplevel_module(&mut spec_collector);
             tcx.hir().walk_attributes(&mut spec_collector);
             let def_spec = spec_collector.build_def_specs();
+            spec_checker.check_spec_calls(tcx, &def_spec);
+            spec_checker.check_trusted_specs(tcx, &def_spec);
+            spec_checker.check_quantifier_triggers(tcx);
+            spec_checker.check_laws(tcx);
+            spec_checker.report_errors(&env);
+            compiler.session().abort_if_errors();
             if config::print_typeckd_specs() {
                 let mut values: Vec<_> = def_spec
                     .specs
@@ -39,6 +39,7 @@ use callbacks::PrustiCompilerCalls;
 use lazy_static::lazy_static;
 use log::{info, warn};
 use prusti_common::{config, report::user, Stopwatch};
+use prusti_interface::error_codes::ErrorCode;
 use rustc_interface::interface::try_print_query_stack;
 use std::{borrow::Cow, env, panic};
 
@@ -130,6 +131,27 @@ fn main() {
     // have been filtered out.
     let original_rustc_args = config::get_filtered_args();
 
+    // `--explain` with a Prusti error code; rustc explains its own codes.
+    if let Some(code) = arg_value(&original_rustc_args, "--explain", |val| {
+        val.starts_with('P')
+    }) {
+        match ErrorCode::from_code(code) {
+            Some(error_code) => {
+                println!("{}", error_code.explanation());
+                std::process::exit(0);
+            }
+            None => {
+                eprintln!("error: {} is not a valid Prusti error code", code);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Err(message) = config::check_settings() {
+        eprintln!("error: {}", message);
+        std::process::exit(1);
+    }
+
     // If the environment asks us to actually be rustc, or if lints have been disabled (which
     // indicates that an upstream dependency is being compiled), then run `rustc` instead of Prusti.
     let prusti_be_rustc = config::be_rustc();
@@ -3,11 +3,16 @@
 use log::{debug, trace, warn};
 use prusti_common::{config, report::user};
 use prusti_interface::{
-    data::{VerificationResult, VerificationTask},
+    data::{ProcedureDefId, VerificationResult, VerificationTask},
     environment::Environment,
     specs::typed,
+    PrustiError,
 };
-use prusti_viper::verifier::Verifier;
+use prusti_viper::{
+    verification_manifest::{self, is_in_modules, ManifestStatus, VerificationManifest},
+    verifier::Verifier,
+};
+use std::{collections::BTreeMap, path::Path};
 
 pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
     trace!("[verify] enter");
@@ -16,7 +21,23 @@ pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
         warn!("The compiler reported an error, so the program will not be verified.");
     } else {
         debug!("Prepare verification task...");
-        let annotated_procedures = env.get_annotated_procedures();
+        let mut annotated_procedures = env.get_annotated_procedures();
+        if !config::verify_entry_points() {
+            if let Some(entry_fn) = env.get_entry_fn() {
+                debug!("Skip the entry point {:?}", entry_fn);
+                annotated_procedures.retain(|&proc_id| proc_id != entry_fn);
+            }
+        }
+        let assumed_modules = config::assumed_verified_modules();
+        if !assumed_modules.is_empty() {
+            let (assumed_procedures, verified_procedures): (Vec<_>, Vec<_>) =
+                annotated_procedures.into_iter().partition(|&proc_id| {
+                    let module = env.get_parent_module(proc_id.expect_local());
+                    is_in_modules(&env.get_module_path(module), &assumed_modules)
+                });
+            annotated_procedures = verified_procedures;
+            check_assumed_procedures(&env, &assumed_procedures);
+        }
         let verification_task = VerificationTask {
             procedures: annotated_procedures,
         };
@@ -41,13 +62,13 @@ pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
             }
         }
 
+        let mut verifier = Verifier::new(&env, def_spec);
         let verification_result = if verification_task.procedures.is_empty() {
             VerificationResult::Success
         } else {
             debug!("Dump borrow checker info...");
             env.dump_borrowck_info(&verification_task.procedures);
 
-            let mut verifier = Verifier::new(&env, def_spec);
             let verification_result = verifier.verify(&verification_task);
             debug!("Verifier returned {:?}", verification_result);
 
@@ -66,6 +87,7 @@ pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
                         "Successful verification of {} items",
                         verification_task.procedures.len()
                     ));
+                    verifier.export_artifact(&verification_task, &crate::get_prusti_version_info());
                 }
             }
             VerificationResult::Failure => {
@@ -77,3 +99,72 @@ pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
 
     trace!("[verify] exit");
 }
+
+/// Report the procedures of assumed verified modules, which are not verified,
+/// and warn about the modules that are missing from the verification manifest
+/// or changed since their verification.
+fn check_assumed_procedures(env: &Environment<'_>, assumed_procedures: &[ProcedureDefId]) {
+    if assumed_procedures.is_empty() {
+        return;
+    }
+    user::message(format!(
+        "Assuming {} items of assumed verified modules",
+        assumed_procedures.len()
+    ));
+    let manifest_path = match config::verification_manifest() {
+        Some(path) => path,
+        None => return,
+    };
+    let manifest = match VerificationManifest::read(Path::new(&manifest_path)) {
+        Ok(manifest) => manifest,
+        Err(error) => {
+            PrustiError::warning(
+                format!(
+                    "failed to read the verification manifest {}: {}",
+                    manifest_path, error
+                ),
+                env.get_def_span(assumed_procedures[0]).into(),
+            )
+            .emit(env);
+            return;
+        }
+    };
+    let mut modules = BTreeMap::new();
+    for &proc_id in assumed_procedures {
+        let module = env.get_parent_module(proc_id.expect_local());
+        modules.insert(env.get_module_path(module), module);
+    }
+    let krate = env.crate_name();
+    for (module_path, module) in modules {
+        let source_hash = env
+            .get_module_source(module)
+            .map(|source| verification_manifest::source_hash(&source))
+            .unwrap_or_default();
+        match manifest.status(&krate, &module_path, &source_hash) {
+            ManifestStatus::Verified(entry) => debug!(
+                "Module {} was verified by {} at {}",
+                module_path, entry.verified_by, entry.verified_at
+            ),
+            ManifestStatus::Changed(entry) => PrustiError::warning(
+                format!(
+                    "module `{}` is assumed to be verified, but it changed since its \
+                    verification by {} recorded in the verification manifest",
+                    module_path, entry.verified_by
+                ),
+                env.get_def_span(module.to_def_id()).into(),
+            )
+            .set_help("verify the module without `ASSUMED_VERIFIED_MODULES` to record it")
+            .emit(env),
+            ManifestStatus::Missing => PrustiError::warning(
+                format!(
+                    "module `{}` is assumed to be verified, but the verification manifest \
+                    does not record its verification",
+                    module_path
+                ),
+                env.get_def_span(module.to_def_id()).into(),
+            )
+            .set_help("verify the module without `ASSUMED_VERIFIED_MODULES` to record it")
+            .emit(env),
+        }
+    }
+}
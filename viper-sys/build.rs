@@ -157,6 +157,9 @@ fn main() {
                 object_getter!(),
                 method!("pretty", "(Lviper/silver/ast/Node;)Ljava/lang/String;")
             ]),
+            java_class!("viper.silver.ast.AbstractSourcePosition", vec![
+                method!("start"),
+            ]),
             java_class!("viper.silver.ast.AbstractAssign$", vec![
                 object_getter!(),
                 method!("apply"),
@@ -400,6 +403,10 @@ fn main() {
             java_class!("viper.silver.ast.GtOp$", vec![
                 object_getter!(),
             ]),
+            java_class!("viper.silver.ast.HasLineColumn", vec![
+                method!("line"),
+                method!("column"),
+            ]),
             java_class!("viper.silver.ast.HasIdentifier", vec![
                 method!("id"),
             ]),
@@ -668,6 +675,21 @@ fn main() {
             java_class!("viper.silver.ast.WildcardPerm", vec![
                 constructor!(),
             ]),
+            java_class!("viper.silver.parser.FastParser", vec![
+                constructor!(),
+                method!("parse", "(Ljava/lang/String;Ljava/nio/file/Path;Lscala/Option;)Lviper/silver/parser/PProgram;"),
+            ]),
+            java_class!("viper.silver.parser.PProgram", vec![
+                method!("errors"),
+            ]),
+            java_class!("viper.silver.parser.Resolver", vec![
+                constructor!(),
+                method!("run"),
+            ]),
+            java_class!("viper.silver.parser.Translator", vec![
+                constructor!(),
+                method!("translate"),
+            ]),
             java_class!("viper.silver.verifier.AbortedExceptionally", vec![
                 constructor!(),
                 method!("cause"),
@@ -48,6 +48,63 @@ impl<'a> AstUtils<'a> {
         )
     }
 
+    /// Parse, resolve and translate a program given as Viper source text. This
+    /// needs only a handful of JNI calls, unlike building the program with the
+    /// `AstFactory` node by node. Returns `None` if the text is not a valid
+    /// Viper program.
+    pub fn parse_program(&self, text: &str, file_name: &str) -> Option<Program<'a>> {
+        let path = self.jni.unwrap_result(
+            java::nio::file::Paths::with(self.env)
+                .call_get(self.jni.new_string(file_name), self.jni.new_object_array(0)),
+        );
+        let fast_parser_wrapper = silver::parser::FastParser::with(self.env);
+        let parse_program = self.jni.unwrap_result(fast_parser_wrapper.call_parse(
+            self.jni.unwrap_result(fast_parser_wrapper.new()),
+            self.jni.new_string(text),
+            path,
+            self.jni.new_option(None),
+        ));
+        let parse_errors =
+            self.jni.seq_to_vec(self.jni.unwrap_result(
+                silver::parser::PProgram::with(self.env).call_errors(parse_program),
+            ));
+        if !parse_errors.is_empty() {
+            for parse_error in parse_errors {
+                debug!(
+                    "Failed to parse the Viper program: {}",
+                    self.jni.to_string(parse_error)
+                );
+            }
+            return None;
+        }
+
+        let resolver_wrapper = silver::parser::Resolver::with(self.env);
+        let resolved_program = self.get_option(self.jni.unwrap_result(
+            resolver_wrapper.call_run(self.jni.unwrap_result(resolver_wrapper.new(parse_program))),
+        ))?;
+        let translator_wrapper = silver::parser::Translator::with(self.env);
+        let program = self.get_option(
+            self.jni.unwrap_result(
+                translator_wrapper.call_translate(
+                    self.jni
+                        .unwrap_result(translator_wrapper.new(resolved_program)),
+                ),
+            ),
+        )?;
+        Some(Program::new(program))
+    }
+
+    fn get_option(&self, option: JObject<'a>) -> Option<JObject<'a>> {
+        if self.jni.is_instance_of(option, "scala/None$") {
+            None
+        } else {
+            Some(
+                self.jni
+                    .unwrap_result(scala::Some::with(self.env).call_get(option)),
+            )
+        }
+    }
+
     pub fn to_string(&self, program: Program<'a>) -> String {
         self.jni.to_string(program.to_jobject())
     }
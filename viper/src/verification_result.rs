@@ -34,6 +34,9 @@ pub struct VerificationError {
     pub reason_pos_id: Option<String>,
     pub message: String,
     pub counterexample: Option<SiliconCounterexample>,
+    /// Why the SMT solver could not decide the failing check (e.g. `timeout`
+    /// or `incomplete quantifiers`), if the verifier reported it.
+    pub reason_unknown: Option<String>,
 }
 
 impl VerificationError {
@@ -43,6 +46,7 @@ impl VerificationError {
         reason_pos_id: Option<String>,
         message: String,
         counterexample: Option<SiliconCounterexample>,
+        reason_unknown: Option<String>,
     ) -> Self {
         VerificationError {
             full_id,
@@ -50,6 +54,7 @@ impl VerificationError {
             reason_pos_id,
             message,
             counterexample,
+            reason_unknown,
         }
     }
 }
@@ -193,6 +193,10 @@ impl<'a> Verifier<'a> {
                     .jni
                     .unwrap_result(verification_error_wrapper.call_failureContexts(viper_error)));
 
+                    let reason_unknown = failure_contexts.last().and_then(|&failure_context| {
+                        parse_reason_unknown(&self.jni.to_string(failure_context))
+                    });
+
                     let counterexample: Option<SiliconCounterexample> = {
                         if let Some(failure_context) = failure_contexts.pop() {
                             let option_original_counterexample = self
@@ -244,6 +248,8 @@ impl<'a> Verifier<'a> {
                                     .unwrap_result(has_identifier_wrapper.call_id(reason_pos)),
                             ),
                         )
+                    } else if let Some(reason_pos_id) = self.source_position_id(reason_pos) {
+                        Some(reason_pos_id)
                     } else {
                         debug!(
                             "The verifier returned an error whose offending node position has no identifier: {:?}",
@@ -274,6 +280,8 @@ impl<'a> Verifier<'a> {
                             Some(self.jni.get_string(
                                 self.jni.unwrap_result(has_identifier_wrapper.call_id(pos)),
                             ))
+                        } else if let Some(pos_id) = self.source_position_id(pos) {
+                            Some(pos_id)
                         } else {
                             debug!(
                                 "The verifier returned an error whose position has no identifier: {:?}",
@@ -288,6 +296,7 @@ impl<'a> Verifier<'a> {
                         reason_pos_id,
                         message,
                         counterexample,
+                        reason_unknown,
                     ))
                 }
 
@@ -297,6 +306,43 @@ impl<'a> Verifier<'a> {
             }
         })
     }
+
+    /// The `line:column` of a position in a program that was parsed from Viper
+    /// source text, which has no identifier.
+    fn source_position_id(&self, pos: JObject) -> Option<String> {
+        if !self
+            .jni
+            .is_instance_of(pos, "viper/silver/ast/AbstractSourcePosition")
+        {
+            return None;
+        }
+        let start = self
+            .jni
+            .unwrap_result(silver::ast::AbstractSourcePosition::with(self.env).call_start(pos));
+        let has_line_column_wrapper = silver::ast::HasLineColumn::with(self.env);
+        let line = self
+            .jni
+            .unwrap_result(has_line_column_wrapper.call_line(start));
+        let column = self
+            .jni
+            .unwrap_result(has_line_column_wrapper.call_column(start));
+        Some(format!("{}:{}", line, column))
+    }
+}
+
+/// Extract the reason why the SMT solver returned `unknown` from the textual
+/// representation of a Silicon failure context, which contains a line such as
+/// `Reason unknown: timeout` when the solver could not decide a check.
+fn parse_reason_unknown(failure_context: &str) -> Option<String> {
+    failure_context.lines().find_map(|line| {
+        let (label, reason) = line.split_once(':')?;
+        let label = label.trim().to_lowercase();
+        if label.ends_with("reason unknown") || label.ends_with("potential cause") {
+            Some(reason.trim().to_string()).filter(|reason| !reason.is_empty())
+        } else {
+            None
+        }
+    })
 }
 
 impl<'a> Drop for Verifier<'a> {
@@ -0,0 +1,79 @@
+extern crate env_logger;
+extern crate error_chain;
+#[macro_use]
+extern crate lazy_static;
+extern crate viper;
+
+use std::sync::Once;
+use viper::*;
+
+static INIT: Once = Once::new();
+
+lazy_static! {
+    static ref VIPER: Viper = Viper::new();
+}
+
+/// Setup function that is only run once, even if called multiple times.
+fn setup() {
+    INIT.call_once(|| {
+        env_logger::init();
+    });
+}
+
+fn verify_text(text: &str) -> Option<VerificationResult> {
+    let verification_context: VerificationContext = VIPER.attach_current_thread();
+    let ast_utils = verification_context.new_ast_utils();
+    let program = ast_utils.parse_program(text, "test.vpr")?;
+    let verifier = verification_context.new_verifier(viper::VerificationBackend::Silicon, None);
+    Some(verifier.verify(program))
+}
+
+#[test]
+fn success_with_parsed_program() {
+    setup();
+
+    let verification_result = verify_text(
+        "field f: Int\n\
+         method foo(x: Ref) requires acc(x.f) ensures acc(x.f) && x.f == old(x.f) + 1 {\n\
+         \x20   x.f := x.f + 1\n\
+         }\n",
+    );
+
+    assert!(matches!(
+        verification_result,
+        Some(VerificationResult::Success)
+    ));
+}
+
+#[test]
+fn failure_with_parsed_program() {
+    setup();
+
+    let verification_result = verify_text(
+        "method foo(x: Int) {\n\
+         \x20   assert x > 0\n\
+         }\n",
+    );
+
+    if let Some(VerificationResult::Failure(errors)) = verification_result {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].full_id,
+            "assert.failed:assertion.false".to_string()
+        );
+        // The positions of a parsed program are lines and columns.
+        let pos_id = errors[0].pos_id.as_ref().unwrap();
+        assert!(pos_id.starts_with("2:"), "unexpected position {}", pos_id);
+    } else {
+        unreachable!()
+    }
+}
+
+#[test]
+fn parse_error() {
+    setup();
+
+    let verification_result = verify_text("method foo( {\n}\n");
+
+    assert!(verification_result.is_none());
+}
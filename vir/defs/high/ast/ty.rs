@@ -51,6 +51,8 @@ pub enum Int {
     Char,
     /// Used for ghost and mathematical integers.
     Unbounded,
+    /// Used for ghost natural numbers.
+    Nat,
 }
 
 pub enum Float {
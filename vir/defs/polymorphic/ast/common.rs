@@ -5,7 +5,12 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::{
-    common::identifier::WithIdentifier, converter::type_substitution::Generic, polymorphic::ast::*,
+    common::{
+        fraction::{combine_fractions, Fraction},
+        identifier::WithIdentifier,
+    },
+    converter::type_substitution::Generic,
+    polymorphic::ast::*,
 };
 use rustc_hash::FxHashMap;
 use std::{
@@ -50,16 +55,47 @@ pub enum PermAmount {
     Write,
     /// The permission remaining after ``Read`` was subtracted from ``Write``.
     Remaining,
+    /// A fractional permission strictly between ``none`` and ``write``. Use
+    /// `PermAmount::fraction` to construct it.
+    Fraction(Fraction),
 }
 
 impl PermAmount {
+    /// Create the permission amount ``numerator/denominator``. Returns
+    /// ``Write`` if the fraction is equal to one.
+    pub fn fraction(numerator: u32, denominator: u32) -> Self {
+        Fraction::new(numerator, denominator).map_or(PermAmount::Write, PermAmount::Fraction)
+    }
+
     /// Can this permission amount be used in specifications?
     pub fn is_valid_for_specs(&self) -> bool {
         match self {
-            PermAmount::Read | PermAmount::Write => true,
+            PermAmount::Read | PermAmount::Write | PermAmount::Fraction(_) => true,
             PermAmount::Remaining => false,
         }
     }
+
+    /// The permission amount as a rational number, if it is known statically.
+    /// ``Read`` and ``Remaining`` depend on the symbolic value of ``read$()``
+    /// and thus are not rational.
+    pub fn as_fraction(&self) -> Option<(u32, u32)> {
+        match self {
+            PermAmount::Write => Some((1, 1)),
+            PermAmount::Fraction(fraction) => Some((fraction.numerator(), fraction.denominator())),
+            PermAmount::Read | PermAmount::Remaining => None,
+        }
+    }
+}
+
+/// Combine two fractional permission amounts with `combine_fractions`.
+fn combine_perm_amounts(
+    left: PermAmount,
+    right: PermAmount,
+    op: fn(u64, u64) -> Option<u64>,
+) -> Option<PermAmount> {
+    let (numerator, denominator) =
+        combine_fractions(left.as_fraction()?, right.as_fraction()?, op)?;
+    Some(PermAmount::fraction(numerator, denominator))
 }
 
 impl std::ops::Add for PermAmount {
@@ -68,6 +104,10 @@ impl std::ops::Add for PermAmount {
         match (self, other) {
             (PermAmount::Read, PermAmount::Remaining)
             | (PermAmount::Remaining, PermAmount::Read) => Ok(PermAmount::Write),
+            (PermAmount::Fraction(_), _) | (_, PermAmount::Fraction(_)) => {
+                combine_perm_amounts(self, other, u64::checked_add)
+                    .ok_or(PermAmountError::InvalidAdd(self, other))
+            }
             _ => Err(PermAmountError::InvalidAdd(self, other)),
         }
     }
@@ -79,6 +119,10 @@ impl std::ops::Sub for PermAmount {
         match (self, other) {
             (PermAmount::Write, PermAmount::Read) => Ok(PermAmount::Remaining),
             (PermAmount::Write, PermAmount::Remaining) => Ok(PermAmount::Read),
+            (PermAmount::Fraction(_), _) | (_, PermAmount::Fraction(_)) => {
+                combine_perm_amounts(self, other, u64::checked_sub)
+                    .ok_or(PermAmountError::InvalidSub(self, other))
+            }
             _ => Err(PermAmountError::InvalidSub(self, other)),
         }
     }
@@ -90,6 +134,7 @@ impl fmt::Display for PermAmount {
             PermAmount::Read => write!(f, "read"),
             PermAmount::Write => write!(f, "write"),
             PermAmount::Remaining => write!(f, "write-read"),
+            PermAmount::Fraction(fraction) => write!(f, "{}", fraction),
         }
     }
 }
@@ -97,11 +142,20 @@ impl fmt::Display for PermAmount {
 impl PartialOrd for PermAmount {
     fn partial_cmp(&self, other: &PermAmount) -> Option<Ordering> {
         match (self, other) {
-            (PermAmount::Read, PermAmount::Write) => Some(Ordering::Less),
-            (PermAmount::Read, PermAmount::Read) | (PermAmount::Write, PermAmount::Write) => {
-                Some(Ordering::Equal)
+            (PermAmount::Read, PermAmount::Write) | (PermAmount::Remaining, PermAmount::Write) => {
+                Some(Ordering::Less)
+            }
+            (PermAmount::Read, PermAmount::Read)
+            | (PermAmount::Write, PermAmount::Write)
+            | (PermAmount::Remaining, PermAmount::Remaining) => Some(Ordering::Equal),
+            (PermAmount::Write, PermAmount::Read) | (PermAmount::Write, PermAmount::Remaining) => {
+                Some(Ordering::Greater)
+            }
+            (PermAmount::Fraction(_), _) | (_, PermAmount::Fraction(_)) => {
+                let (n1, d1) = self.as_fraction()?;
+                let (n2, d2) = other.as_fraction()?;
+                Some((n1 as u64 * d2 as u64).cmp(&(n2 as u64 * d1 as u64)))
             }
-            (PermAmount::Write, PermAmount::Read) => Some(Ordering::Greater),
             _ => None,
         }
     }
@@ -679,3 +733,32 @@ impl WithIdentifier for Field {
         self.name.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fractional_perm_amounts() {
+        let half = PermAmount::fraction(2, 4);
+        assert_eq!(half.as_fraction(), Some((1, 2)));
+        assert_eq!(PermAmount::fraction(3, 3), PermAmount::Write);
+        let third = PermAmount::fraction(1, 3);
+        let two_thirds = (third + third).ok().unwrap();
+        assert_eq!(two_thirds.as_fraction(), Some((2, 3)));
+        assert_eq!((two_thirds + third).ok(), Some(PermAmount::Write));
+        assert_eq!((half + half).ok(), Some(PermAmount::Write));
+        assert!((two_thirds + half).is_err());
+        assert_eq!((PermAmount::Write - third).ok(), Some(two_thirds));
+        assert!((half - half).is_err());
+        assert!((half + PermAmount::Read).is_err());
+        assert!(third < half && half < PermAmount::Write);
+        assert_eq!(half.partial_cmp(&PermAmount::Read), None);
+        assert!(PermAmount::Remaining < PermAmount::Write);
+        assert_eq!(
+            PermAmount::Remaining.cmp(&PermAmount::Remaining),
+            Ordering::Equal
+        );
+        assert_eq!(PermAmount::Remaining.partial_cmp(&PermAmount::Read), None);
+    }
+}
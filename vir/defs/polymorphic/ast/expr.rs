@@ -354,6 +354,18 @@ impl Expr {
         DefaultPosReplacer { new_pos: pos }.fold(self)
     }
 
+    /// Replace the position of the outermost expression with `pos` if it is
+    /// `Position::default()`. Used when an expression is rebuilt so that it
+    /// keeps the (more precise) position of the expression it replaces.
+    #[must_use]
+    pub fn set_outer_default_pos(self, pos: Position) -> Self {
+        if self.pos().is_default() {
+            self.set_pos(pos)
+        } else {
+            self
+        }
+    }
+
     pub fn predicate_access_predicate(predicate_type: Type, place: Expr, perm: PermAmount) -> Self {
         let pos = place.pos();
         Expr::PredicateAccessPredicate(PredicateAccessPredicate {
@@ -988,11 +1000,14 @@ impl Expr {
                 */
                 self
             }
-            _ => Expr::LabelledOld(LabelledOld {
-                label: label.to_string(),
-                base: Box::new(self),
-                position: Position::default(),
-            }),
+            _ => {
+                let pos = self.pos();
+                Expr::LabelledOld(LabelledOld {
+                    label: label.to_string(),
+                    base: Box::new(self),
+                    position: pos,
+                })
+            }
         }
     }
 
@@ -1124,6 +1139,26 @@ impl Expr {
         }
     }
 
+    /// Check whether the expression contains an old expression with the given label.
+    pub fn uses_label(&self, label: &str) -> bool {
+        struct LabelFinder<'a> {
+            label: &'a str,
+            found: bool,
+        }
+        impl<'a> ExprWalker for LabelFinder<'a> {
+            fn walk_labelled_old(&mut self, LabelledOld { label, base, .. }: &LabelledOld) {
+                self.found |= label == self.label;
+                self.walk(base);
+            }
+        }
+        let mut walker = LabelFinder {
+            label,
+            found: false,
+        };
+        walker.walk(self);
+        walker.found
+    }
+
     pub fn has_proper_prefix(&self, other: &Expr) -> bool {
         debug_assert!(self.is_place(), "self={} other={}", self, other);
         debug_assert!(other.is_place(), "self={} other={}", self, other);
@@ -1485,7 +1520,7 @@ impl Expr {
             fn fold(&mut self, e: Expr) -> Expr {
                 if e.is_place() && &e == self.target {
                     self.subst = true;
-                    self.replacement.clone()
+                    self.replacement.clone().set_outer_default_pos(e.pos())
                 } else {
                     let default_expr = default_fold_expr(self, e);
                     match default_expr {
@@ -1596,7 +1631,7 @@ impl Expr {
                 if e.is_place() {
                     let substitution = self.replacements.iter().find(|(src, _)| src == &e);
                     if let Some((_src, dst)) = substitution {
-                        return dst.clone();
+                        return dst.clone().set_outer_default_pos(e.pos());
                     }
                 }
 
@@ -1951,12 +1986,14 @@ impl Expr {
             ) -> Expr {
                 assert!(permission.is_valid_for_specs());
                 match permission {
-                    PermAmount::Write => Expr::PredicateAccessPredicate(PredicateAccessPredicate {
-                        predicate_type,
-                        argument,
-                        permission,
-                        position,
-                    }),
+                    PermAmount::Write | PermAmount::Fraction(_) => {
+                        Expr::PredicateAccessPredicate(PredicateAccessPredicate {
+                            predicate_type,
+                            argument,
+                            permission,
+                            position,
+                        })
+                    }
                     PermAmount::Read => true.into(),
                     _ => unreachable!(),
                 }
@@ -1971,11 +2008,13 @@ impl Expr {
             ) -> Expr {
                 assert!(permission.is_valid_for_specs());
                 match permission {
-                    PermAmount::Write => Expr::FieldAccessPredicate(FieldAccessPredicate {
-                        base,
-                        permission,
-                        position,
-                    }),
+                    PermAmount::Write | PermAmount::Fraction(_) => {
+                        Expr::FieldAccessPredicate(FieldAccessPredicate {
+                            base,
+                            permission,
+                            position,
+                        })
+                    }
                     PermAmount::Read => true.into(),
                     _ => unreachable!(),
                 }
@@ -35,6 +35,7 @@ impl Predicate {
         Predicate::Struct(StructPredicate {
             typ: typ.clone(),
             this: Self::construct_this(typ),
+            extra_params: vec![],
             body: None,
         })
     }
@@ -65,6 +66,7 @@ impl Predicate {
         Predicate::Struct(StructPredicate {
             typ,
             this,
+            extra_params: vec![],
             body: Some(body),
         })
     }
@@ -92,6 +94,7 @@ impl Predicate {
         Predicate::Enum(EnumPredicate {
             typ: this.typ.clone(),
             this,
+            extra_params: vec![],
             discriminant_field,
             discriminant_bounds,
             variants,
@@ -105,6 +108,47 @@ impl Predicate {
             Predicate::Bodyless(_, this) => this.clone().into(),
         }
     }
+    /// The formal parameters of the predicate. The first one is the `self`
+    /// parameter, whose place is the one that gets folded and unfolded.
+    pub fn params(&self) -> Vec<LocalVar> {
+        let (this, extra_params) = match self {
+            Predicate::Struct(p) => (&p.this, &p.extra_params[..]),
+            Predicate::Enum(p) => (&p.this, &p.extra_params[..]),
+            Predicate::Bodyless(_, this) => (this, &[][..]),
+        };
+        let mut params = vec![this.clone()];
+        params.extend(extra_params.iter().cloned());
+        params
+    }
+    /// The number of arguments of the predicate.
+    pub fn arity(&self) -> usize {
+        match self {
+            Predicate::Struct(p) => 1 + p.extra_params.len(),
+            Predicate::Enum(p) => 1 + p.extra_params.len(),
+            Predicate::Bodyless(_, _) => 1,
+        }
+    }
+    /// Replace the formal parameters of the predicate in `expr` (e.g. a place
+    /// of the body footprint) with the actual `arguments`. The parameters are
+    /// replaced simultaneously, so an argument may mention the parameters.
+    pub fn instantiate(&self, expr: Expr, arguments: &[Expr]) -> Expr {
+        let params = self.params();
+        assert_eq!(
+            params.len(),
+            arguments.len(),
+            "wrong number of arguments for predicate {}",
+            self.name()
+        );
+        expr.fold_places(|place| {
+            let base = place.get_base();
+            match params.iter().position(|param| param == &base) {
+                Some(index) => {
+                    place.replace_place(&params[index].clone().into(), &arguments[index])
+                }
+                None => place,
+            }
+        })
+    }
     /// The predicate type getter.
     pub fn get_type(&self) -> &Type {
         match self {
@@ -153,6 +197,11 @@ pub struct StructPredicate {
     pub typ: Type,
     /// The self reference.
     pub this: LocalVar,
+    /// The parameters after the self reference, which relate the folded place
+    /// to other places. Predicate access predicates have a single argument,
+    /// so a predicate with extra parameters can only be folded and unfolded
+    /// by statements.
+    pub extra_params: Vec<LocalVar>,
     /// The optional body of the predicate.
     pub body: Option<Expr>,
 }
@@ -165,6 +214,9 @@ impl fmt::Display for StructPredicate {
             self.typ.encode_as_string(),
             self.this
         )?;
+        for param in &self.extra_params {
+            write!(f, ", {}", param)?;
+        }
         match self.body {
             None => writeln!(f, ");"),
             Some(ref body) => {
@@ -192,6 +244,7 @@ impl StructPredicate {
         Self {
             typ,
             this,
+            extra_params: vec![],
             body: Some(body),
         }
     }
@@ -229,6 +282,9 @@ pub struct EnumPredicate {
     pub typ: Type,
     /// The self reference.
     pub this: LocalVar,
+    /// The parameters after the self reference, like the ones of
+    /// `StructPredicate`.
+    pub extra_params: Vec<LocalVar>,
     /// The discriminant field.
     pub discriminant_field: Field,
     /// The restrictions of the discriminant field.
@@ -263,7 +319,11 @@ impl<'a> From<&'a Field> for EnumVariantIndex {
 
 impl fmt::Display for EnumPredicate {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "enum_predicate {}({}){{", self.typ.name(), self.this)?;
+        write!(f, "enum_predicate {}({}", self.typ.name(), self.this)?;
+        for param in &self.extra_params {
+            write!(f, ", {}", param)?;
+        }
+        writeln!(f, "){{")?;
         writeln!(f, "  discriminant_field={}", self.discriminant_field)?;
         for (guard, name, variant) in self.variants.iter() {
             writeln!(f, "  {}: {} ==> {}\n", name, guard, variant)?;
@@ -306,3 +366,34 @@ impl WithIdentifier for EnumPredicate {
         self.typ.name()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instantiate_replaces_the_parameters_simultaneously() {
+        let typ = Type::typed_ref("Pair");
+        let this = Predicate::construct_this(typ.clone());
+        let other = LocalVar::new("other", typ.clone());
+        let predicate = Predicate::Struct(StructPredicate {
+            typ,
+            this: this.clone(),
+            extra_params: vec![other.clone()],
+            body: None,
+        });
+        assert_eq!(predicate.arity(), 2);
+        assert_eq!(predicate.params(), vec![this.clone(), other.clone()]);
+
+        let f = Field::new("f", Type::Int);
+        let this_f = Expr::local(this.clone()).field(f.clone());
+        let other_f = Expr::local(other.clone()).field(f);
+        // Swap the two places.
+        let arguments = [Expr::local(other), Expr::local(this)];
+        assert_eq!(
+            predicate.instantiate(this_f.clone(), &arguments),
+            other_f.clone()
+        );
+        assert_eq!(predicate.instantiate(other_f, &arguments), this_f);
+    }
+}
@@ -0,0 +1,336 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A generator of random VIR expressions and statements, used by the
+//! property-based tests of the VIR transformations. It is only compiled for
+//! tests and with the `generator` feature, which the other crates enable in
+//! their development dependencies.
+//!
+//! The generated code is well-typed, uses only the given local variables and
+//! can be run by the `interpreter`. References are only accessed through
+//! their `val_int` field, and only while their predicate is unfolded, so that
+//! the generated statements are also well-formed with respect to the
+//! permissions: the predicates are folded at the start and at the end of
+//! each block, and each `unfold` is eventually followed by a `fold`.
+
+use crate::polymorphic::ast::*;
+
+pub struct Generator {
+    /// The state of the xorshift pseudo-random number generator, so that a
+    /// failing test can be reproduced from its seed.
+    state: u64,
+    locals: Vec<LocalVar>,
+    /// The references whose predicate is currently unfolded.
+    unfolded: Vec<LocalVar>,
+}
+
+impl Generator {
+    /// `locals` are the variables that the generated code may use, of type
+    /// `Int`, `Bool` or a reference.
+    pub fn new(seed: u64, locals: Vec<LocalVar>) -> Self {
+        Generator {
+            state: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1,
+            locals,
+            unfolded: vec![],
+        }
+    }
+
+    /// The field through which the generated code accesses the references.
+    pub fn ref_field() -> Field {
+        Field::new("val_int", Type::Int)
+    }
+
+    /// A pseudo-random number in `0..bound`.
+    pub fn below(&mut self, bound: usize) -> usize {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state % bound as u64) as usize
+    }
+
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    fn choose<T: Clone>(&mut self, items: &[T]) -> Option<T> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(items[self.below(items.len())].clone())
+        }
+    }
+
+    fn small_int(&mut self) -> i64 {
+        self.below(11) as i64 - 5
+    }
+
+    /// The places of type `typ` that can currently be accessed.
+    fn places(&self, typ: &Type) -> Vec<Expr> {
+        let mut places: Vec<Expr> = self
+            .locals
+            .iter()
+            .filter(|var| &var.typ == typ)
+            .cloned()
+            .map(Expr::local)
+            .collect();
+        if typ == &Type::Int {
+            places.extend(
+                self.unfolded
+                    .iter()
+                    .cloned()
+                    .map(|var| Expr::local(var).field(Self::ref_field())),
+            );
+        }
+        places
+    }
+
+    fn references(&self) -> Vec<LocalVar> {
+        self.locals
+            .iter()
+            .filter(|var| var.typ.is_typed_ref_or_type_var())
+            .cloned()
+            .collect()
+    }
+
+    /// A random expression of type `Int` or `Bool` with at most `depth`
+    /// nested operations.
+    pub fn expr(&mut self, typ: &Type, depth: usize) -> Expr {
+        match typ {
+            Type::Int => self.int_expr(depth),
+            Type::Bool => self.bool_expr(depth),
+            _ => unreachable!("cannot generate expressions of type {}", typ),
+        }
+    }
+
+    pub fn int_expr(&mut self, depth: usize) -> Expr {
+        if depth == 0 || self.chance(25) {
+            let places = self.places(&Type::Int);
+            return match self.choose(&places) {
+                Some(place) if self.chance(60) => place,
+                _ => self.small_int().into(),
+            };
+        }
+        let left = self.int_expr(depth - 1);
+        match self.below(9) {
+            0 => Expr::minus(left),
+            1 => {
+                let guard = self.bool_expr(depth - 1);
+                let right = self.int_expr(depth - 1);
+                Expr::ite(guard, left, right)
+            }
+            // Divide mostly by non-zero constants, to not make most of the
+            // expressions fail.
+            2 | 3 if self.chance(50) => {
+                let divisor = match self.small_int() {
+                    0 => 3,
+                    divisor => divisor,
+                };
+                let op_kind = if self.chance(50) {
+                    BinaryOpKind::Div
+                } else {
+                    BinaryOpKind::Mod
+                };
+                Expr::bin_op(op_kind, left, divisor.into())
+            }
+            op => {
+                let op_kind = [
+                    BinaryOpKind::Div,
+                    BinaryOpKind::Mod,
+                    BinaryOpKind::Add,
+                    BinaryOpKind::Sub,
+                    BinaryOpKind::Mul,
+                    BinaryOpKind::Min,
+                    BinaryOpKind::Max,
+                ][op - 2];
+                let right = self.int_expr(depth - 1);
+                Expr::bin_op(op_kind, left, right)
+            }
+        }
+    }
+
+    pub fn bool_expr(&mut self, depth: usize) -> Expr {
+        if depth == 0 || self.chance(25) {
+            let places = self.places(&Type::Bool);
+            return match self.choose(&places) {
+                Some(place) if self.chance(60) => place,
+                _ => self.chance(50).into(),
+            };
+        }
+        match self.below(6) {
+            0 => Expr::not(self.bool_expr(depth - 1)),
+            1 => {
+                let guard = self.bool_expr(depth - 1);
+                let then_expr = self.bool_expr(depth - 1);
+                let else_expr = self.bool_expr(depth - 1);
+                Expr::ite(guard, then_expr, else_expr)
+            }
+            2 | 3 => {
+                let op_kind = [
+                    BinaryOpKind::And,
+                    BinaryOpKind::Or,
+                    BinaryOpKind::Implies,
+                    BinaryOpKind::EqCmp,
+                    BinaryOpKind::NeCmp,
+                ][self.below(5)];
+                let left = self.bool_expr(depth - 1);
+                let right = self.bool_expr(depth - 1);
+                Expr::bin_op(op_kind, left, right)
+            }
+            _ => {
+                let op_kind = [
+                    BinaryOpKind::EqCmp,
+                    BinaryOpKind::NeCmp,
+                    BinaryOpKind::GtCmp,
+                    BinaryOpKind::GeCmp,
+                    BinaryOpKind::LtCmp,
+                    BinaryOpKind::LeCmp,
+                ][self.below(6)];
+                let left = self.int_expr(depth - 1);
+                let right = self.int_expr(depth - 1);
+                Expr::bin_op(op_kind, left, right)
+            }
+        }
+    }
+
+    /// A random block of `length` statements whose expressions have at most
+    /// `depth` nested operations. The block does not change which predicates
+    /// are unfolded.
+    pub fn stmts(&mut self, length: usize, depth: usize) -> Vec<Stmt> {
+        let entry_unfolded = self.unfolded.clone();
+        let mut stmts: Vec<Stmt> = (0..length).map(|_| self.stmt(length, depth)).collect();
+        let refold = self
+            .unfolded
+            .iter()
+            .filter(|var| !entry_unfolded.contains(var))
+            .cloned()
+            .map(Self::fold);
+        let reunfold = entry_unfolded
+            .iter()
+            .filter(|var| !self.unfolded.contains(var))
+            .cloned()
+            .map(Self::unfold);
+        stmts.extend(refold.chain(reunfold).collect::<Vec<_>>());
+        self.unfolded = entry_unfolded;
+        stmts
+    }
+
+    fn stmt(&mut self, length: usize, depth: usize) -> Stmt {
+        match self.below(20) {
+            0..=3 => {
+                let references = self.references();
+                match self.choose(&references) {
+                    Some(var) => {
+                        if let Some(index) = self.unfolded.iter().position(|other| other == &var) {
+                            self.unfolded.remove(index);
+                            Self::fold(var)
+                        } else {
+                            self.unfolded.push(var.clone());
+                            Self::unfold(var)
+                        }
+                    }
+                    None => Stmt::comment("no references"),
+                }
+            }
+            4 => Stmt::Assert(Assert {
+                expr: self.bool_expr(depth),
+                position: Position::default(),
+            }),
+            5 => Stmt::Inhale(Inhale {
+                expr: self.bool_expr(depth),
+            }),
+            6 | 7 if length > 1 => Stmt::If(If {
+                guard: self.bool_expr(depth),
+                then_stmts: self.stmts(length / 2, depth),
+                else_stmts: self.stmts(length / 2, depth),
+            }),
+            _ => {
+                let typ = if self.chance(70) {
+                    Type::Int
+                } else {
+                    Type::Bool
+                };
+                let places = self.places(&typ);
+                match self.choose(&places) {
+                    Some(target) => Stmt::Assign(Assign {
+                        target,
+                        source: self.expr(&typ, depth),
+                        kind: AssignKind::Copy,
+                    }),
+                    None => Stmt::comment(format!("no places of type {}", typ)),
+                }
+            }
+        }
+    }
+
+    fn fold(var: LocalVar) -> Stmt {
+        Stmt::Fold(Fold {
+            predicate: var.typ.clone(),
+            arguments: vec![Expr::local(var)],
+            permission: PermAmount::Write,
+            enum_variant: None,
+            position: Position::default(),
+        })
+    }
+
+    fn unfold(var: LocalVar) -> Stmt {
+        Stmt::Unfold(Unfold {
+            predicate: var.typ.clone(),
+            arguments: vec![Expr::local(var)],
+            permission: PermAmount::Write,
+            enum_variant: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polymorphic::interpreter::{EvalError, Interpreter, Value};
+
+    fn locals() -> Vec<LocalVar> {
+        vec![
+            LocalVar::new("i", Type::Int),
+            LocalVar::new("j", Type::Int),
+            LocalVar::new("b", Type::Bool),
+            LocalVar::new("r", Type::typed_ref("usize")),
+        ]
+    }
+
+    fn inputs(path: &str, typ: &Type) -> Value {
+        match typ {
+            Type::Bool => Value::Bool(path.len() % 2 == 0),
+            _ => Value::Int(path.len() as i128),
+        }
+    }
+
+    fn is_well_typed(result: Result<(), EvalError>) -> bool {
+        !matches!(
+            result,
+            Err(EvalError::TypeMismatch(_)) | Err(EvalError::Unsupported(_))
+        )
+    }
+
+    #[test]
+    fn test_exprs_are_well_typed() {
+        for seed in 0..500 {
+            let mut generator = Generator::new(seed, locals());
+            let typ = if seed % 2 == 0 { Type::Int } else { Type::Bool };
+            let expr = generator.expr(&typ, 4);
+            assert_eq!(expr.get_type(), &typ, "{}", expr);
+            let result = Interpreter::new(inputs).eval(&expr).map(|_| ());
+            assert!(is_well_typed(result), "{}", expr);
+        }
+    }
+
+    #[test]
+    fn test_stmts_are_well_typed() {
+        for seed in 0..500 {
+            let stmts = Generator::new(seed, locals()).stmts(8, 3);
+            let result = Interpreter::new(inputs).execute(&stmts);
+            assert!(is_well_typed(result), "seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Hash-consing of VIR expressions.
+
+use crate::polymorphic::ast::*;
+use rustc_hash::FxHashMap;
+
+/// The identifier of an expression interned by an `ExprInterner`.
+///
+/// Two expressions interned by the same interner have the same identifier if
+/// and only if they are equal, so comparing and hashing them takes constant
+/// time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ExprId(usize);
+
+/// A table of structurally distinct expressions.
+///
+/// Expressions are interned bottom-up: every node is looked up with its
+/// subexpressions replaced by their identifiers. Therefore, interning an
+/// expression takes time linear in its size, and each distinct node is stored
+/// only once. Note that the equality of expressions ignores positions, so
+/// expressions that differ only in their positions have the same identifier.
+#[derive(Default)]
+pub struct ExprInterner {
+    nodes: FxHashMap<Expr, ExprId>,
+}
+
+impl ExprInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, expr: &Expr) -> ExprId {
+        let mut node_interner = NodeInterner {
+            nodes: &mut self.nodes,
+            last_id: None,
+        };
+        node_interner.fold(expr.clone());
+        node_interner.last_id.unwrap()
+    }
+
+    /// The number of distinct nodes of the interned expressions.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+struct NodeInterner<'a> {
+    nodes: &'a mut FxHashMap<Expr, ExprId>,
+    /// The identifier of the last folded expression.
+    last_id: Option<ExprId>,
+}
+
+impl<'a> ExprFolder for NodeInterner<'a> {
+    fn fold(&mut self, expr: Expr) -> Expr {
+        // The subexpressions are folded first, so they are already replaced
+        // by the constants of their identifiers, which do not occur in the
+        // nodes otherwise.
+        let node = default_fold_expr(self, expr);
+        let next_id = ExprId(self.nodes.len());
+        let id = *self.nodes.entry(node).or_insert(next_id);
+        self.last_id = Some(id);
+        Expr::Const(ConstExpr {
+            value: Const::Int(id.0 as i64),
+            position: Position::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equal_expressions_have_the_same_id() {
+        let x = LocalVar::new("x", Type::typed_ref("T"));
+        let f = Field::new("f", Type::Int);
+        let place = Expr::local(x).field(f);
+        let sum = Expr::add(place.clone(), 2.into());
+        let mut interner = ExprInterner::new();
+        let id = interner.intern(&sum);
+        assert_eq!(interner.intern(&sum.clone()), id);
+        assert_eq!(
+            interner.intern(&sum.clone().set_pos(Position::new(1, 2, 3))),
+            id
+        );
+        // The nodes are `x`, `x.f`, `2` and `x.f + 2`.
+        assert_eq!(interner.len(), 4);
+
+        assert_ne!(interner.intern(&Expr::add(place.clone(), 3.into())), id);
+        assert_ne!(interner.intern(&Expr::add(2.into(), place)), id);
+        assert_eq!(interner.len(), 7);
+    }
+
+    #[test]
+    fn test_ids_do_not_clash_with_constants() {
+        let mut interner = ExprInterner::new();
+        let one = interner.intern(&1.into());
+        let zero = interner.intern(&0.into());
+        // The identifier of `1` is the constant `0` and vice versa, so the
+        // nodes of the sums are `0 + 0` and `1 + 1` respectively.
+        assert_eq!((one, zero), (ExprId(0), ExprId(1)));
+        let ones = interner.intern(&Expr::add(1.into(), 1.into()));
+        let zeros = interner.intern(&Expr::add(0.into(), 0.into()));
+        assert_ne!(ones, zeros);
+        assert_ne!(ones, zero);
+        assert_ne!(zeros, one);
+    }
+}
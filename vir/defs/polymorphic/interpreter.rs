@@ -0,0 +1,404 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! An interpreter of straight-line VIR code over a simple heap model, used to
+//! differential-test the optimizations: running a sequence of statements
+//! before and after an optimization on the same inputs must give the same
+//! values to the observable places (e.g. the returned ones).
+//!
+//! Permissions are not modelled: accessibility predicates evaluate to `true`,
+//! while folds, unfolds and the other statements that only move permissions
+//! are no-ops. A location that is read before being written is an input. Its
+//! value is requested from the input function using the path through which
+//! the location is first reached, so that two runs see the same inputs.
+//! References that are inputs are fresh, i.e. they never alias.
+
+use crate::polymorphic::ast::*;
+use rustc_hash::FxHashMap;
+use std::{fmt, mem};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+    Bool(bool),
+    /// A mathematical integer. Overflowing the representation is an error.
+    Int(i128),
+    /// The address of a heap object.
+    Ref(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Ref(address) => write!(f, "ref({})", address),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    /// An assertion or an exhale failed.
+    AssertionFailed(String),
+    /// An inhaled expression is false, so the execution is infeasible.
+    Infeasible(String),
+    DivisionByZero(String),
+    Overflow(String),
+    TypeMismatch(String),
+    /// The statement or expression cannot be interpreted, e.g. a method call.
+    Unsupported(String),
+}
+
+/// The values of the local variables and of the fields of the heap objects.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Heap {
+    pub locals: FxHashMap<String, Value>,
+    /// The fields, indexed by the object address and the field name.
+    pub fields: FxHashMap<(String, String), Value>,
+}
+
+pub struct Interpreter<F> {
+    heap: Heap,
+    /// The heaps at the labels executed so far.
+    labelled_heaps: FxHashMap<String, Heap>,
+    inputs: F,
+}
+
+impl<F: Fn(&str, &Type) -> Value> Interpreter<F> {
+    /// `inputs` gives the value of the input location with the given path
+    /// and type. It is never called for references.
+    pub fn new(inputs: F) -> Self {
+        Interpreter {
+            heap: Heap::default(),
+            labelled_heaps: FxHashMap::default(),
+            inputs,
+        }
+    }
+
+    pub fn heap(&self) -> &Heap {
+        &self.heap
+    }
+
+    pub fn execute(&mut self, stmts: &[Stmt]) -> Result<(), EvalError> {
+        for stmt in stmts {
+            self.execute_stmt(stmt)?;
+        }
+        Ok(())
+    }
+
+    pub fn execute_stmt(&mut self, stmt: &Stmt) -> Result<(), EvalError> {
+        match stmt {
+            Stmt::Comment(_)
+            | Stmt::Fold(_)
+            | Stmt::Unfold(_)
+            | Stmt::Obtain(_)
+            | Stmt::BeginFrame(_)
+            | Stmt::EndFrame(_)
+            | Stmt::TransferPerm(_)
+            | Stmt::Downcast(_) => {}
+            Stmt::Label(Label { label }) => {
+                self.labelled_heaps.insert(label.clone(), self.heap.clone());
+            }
+            Stmt::Inhale(Inhale { expr }) => {
+                if !self.eval_bool(expr)? {
+                    return Err(EvalError::Infeasible(stmt.to_string()));
+                }
+            }
+            Stmt::Exhale(Exhale { expr, .. }) | Stmt::Assert(Assert { expr, .. }) => {
+                if !self.eval_bool(expr)? {
+                    return Err(EvalError::AssertionFailed(stmt.to_string()));
+                }
+            }
+            Stmt::Assign(Assign { target, source, .. }) => {
+                let value = self.eval(source)?;
+                self.assign(target, value)?;
+            }
+            Stmt::If(If {
+                guard,
+                then_stmts,
+                else_stmts,
+            }) => {
+                if self.eval_bool(guard)? {
+                    self.execute(then_stmts)?;
+                } else {
+                    self.execute(else_stmts)?;
+                }
+            }
+            Stmt::MethodCall(_)
+            | Stmt::PackageMagicWand(_)
+            | Stmt::ApplyMagicWand(_)
+            | Stmt::ExpireBorrows(_) => {
+                return Err(EvalError::Unsupported(stmt.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn eval(&mut self, expr: &Expr) -> Result<Value, EvalError> {
+        Ok(match expr {
+            Expr::Local(Local { variable, .. }) => {
+                if let Some(value) = self.heap.locals.get(&variable.name) {
+                    value.clone()
+                } else {
+                    let value = self.input(&variable.name, &variable.typ);
+                    self.heap
+                        .locals
+                        .insert(variable.name.clone(), value.clone());
+                    value
+                }
+            }
+            Expr::Field(FieldExpr { base, field, .. })
+            | Expr::Variant(Variant {
+                base,
+                variant_index: field,
+                ..
+            }) => {
+                let address = self.eval_ref(base)?;
+                let key = (address, field.name.clone());
+                if let Some(value) = self.heap.fields.get(&key) {
+                    value.clone()
+                } else {
+                    let value = self.input(&format!("{}.{}", key.0, key.1), &field.typ);
+                    self.heap.fields.insert(key, value.clone());
+                    value
+                }
+            }
+            Expr::LabelledOld(LabelledOld { label, base, .. }) => {
+                let mut heap = self
+                    .labelled_heaps
+                    .remove(label)
+                    .ok_or_else(|| EvalError::Unsupported(expr.to_string()))?;
+                mem::swap(&mut self.heap, &mut heap);
+                let result = self.eval(base);
+                mem::swap(&mut self.heap, &mut heap);
+                self.labelled_heaps.insert(label.clone(), heap);
+                result?
+            }
+            Expr::Const(ConstExpr { value, .. }) => match value {
+                Const::Bool(value) => Value::Bool(*value),
+                Const::Int(value) => Value::Int((*value).into()),
+                Const::BigInt(value) => Value::Int(
+                    value
+                        .parse()
+                        .map_err(|_| EvalError::Overflow(expr.to_string()))?,
+                ),
+                Const::Float(_) | Const::BitVector(_) | Const::FnPtr => {
+                    return Err(EvalError::Unsupported(expr.to_string()));
+                }
+            },
+            Expr::PredicateAccessPredicate(_) | Expr::FieldAccessPredicate(_) => Value::Bool(true),
+            Expr::UnaryOp(UnaryOp {
+                op_kind, argument, ..
+            }) => match (op_kind, self.eval(argument)?) {
+                (UnaryOpKind::Not, Value::Bool(value)) => Value::Bool(!value),
+                (UnaryOpKind::Minus, Value::Int(value)) => Value::Int(
+                    value
+                        .checked_neg()
+                        .ok_or_else(|| EvalError::Overflow(expr.to_string()))?,
+                ),
+                _ => return Err(EvalError::TypeMismatch(expr.to_string())),
+            },
+            Expr::BinOp(bin_op) => self.eval_bin_op(bin_op)?,
+            Expr::Cond(Cond {
+                guard,
+                then_expr,
+                else_expr,
+                ..
+            }) => {
+                if self.eval_bool(guard)? {
+                    self.eval(then_expr)?
+                } else {
+                    self.eval(else_expr)?
+                }
+            }
+            Expr::LetExpr(LetExpr {
+                variable,
+                def,
+                body,
+                ..
+            }) => {
+                let value = self.eval(def)?;
+                let shadowed = self.heap.locals.insert(variable.name.clone(), value);
+                let result = self.eval(body);
+                if let Some(shadowed) = shadowed {
+                    self.heap.locals.insert(variable.name.clone(), shadowed);
+                } else {
+                    self.heap.locals.remove(&variable.name);
+                }
+                result?
+            }
+            Expr::Unfolding(Unfolding { base, .. }) | Expr::Downcast(DowncastExpr { base, .. }) => {
+                self.eval(base)?
+            }
+            Expr::AddrOf(_)
+            | Expr::MagicWand(_)
+            | Expr::ContainerOp(_)
+            | Expr::Seq(_)
+            | Expr::ForAll(_)
+            | Expr::Exists(_)
+            | Expr::FuncApp(_)
+            | Expr::DomainFuncApp(_)
+            | Expr::InhaleExhale(_)
+            | Expr::SnapApp(_)
+            | Expr::Cast(_) => return Err(EvalError::Unsupported(expr.to_string())),
+        })
+    }
+
+    fn eval_bin_op(&mut self, bin_op: &BinOp) -> Result<Value, EvalError> {
+        let error = || bin_op.to_string();
+        // The boolean connectives are short-circuiting, like in Viper.
+        match bin_op.op_kind {
+            BinaryOpKind::And | BinaryOpKind::Or | BinaryOpKind::Implies => {
+                let left = self.eval_bool(&bin_op.left)?;
+                let short_circuit = match bin_op.op_kind {
+                    BinaryOpKind::And => !left,
+                    BinaryOpKind::Or => left,
+                    _ => !left,
+                };
+                return if short_circuit {
+                    Ok(Value::Bool(bin_op.op_kind != BinaryOpKind::And))
+                } else {
+                    Ok(Value::Bool(self.eval_bool(&bin_op.right)?))
+                };
+            }
+            _ => {}
+        }
+        let left = self.eval(&bin_op.left)?;
+        let right = self.eval(&bin_op.right)?;
+        match bin_op.op_kind {
+            BinaryOpKind::EqCmp => return Ok(Value::Bool(left == right)),
+            BinaryOpKind::NeCmp => return Ok(Value::Bool(left != right)),
+            _ => {}
+        }
+        let (left, right) = match (left, right) {
+            (Value::Int(left), Value::Int(right)) => (left, right),
+            _ => return Err(EvalError::TypeMismatch(error())),
+        };
+        let overflow = || EvalError::Overflow(error());
+        Ok(match bin_op.op_kind {
+            BinaryOpKind::GtCmp => Value::Bool(left > right),
+            BinaryOpKind::GeCmp => Value::Bool(left >= right),
+            BinaryOpKind::LtCmp => Value::Bool(left < right),
+            BinaryOpKind::LeCmp => Value::Bool(left <= right),
+            BinaryOpKind::Add => Value::Int(left.checked_add(right).ok_or_else(overflow)?),
+            BinaryOpKind::Sub => Value::Int(left.checked_sub(right).ok_or_else(overflow)?),
+            BinaryOpKind::Mul => Value::Int(left.checked_mul(right).ok_or_else(overflow)?),
+            // Viper uses the Euclidean division, like SMT-LIB.
+            BinaryOpKind::Div | BinaryOpKind::Mod if right == 0 => {
+                return Err(EvalError::DivisionByZero(error()));
+            }
+            BinaryOpKind::Div => Value::Int(left.checked_div_euclid(right).ok_or_else(overflow)?),
+            BinaryOpKind::Mod => Value::Int(left.checked_rem_euclid(right).ok_or_else(overflow)?),
+            BinaryOpKind::Min => Value::Int(left.min(right)),
+            BinaryOpKind::Max => Value::Int(left.max(right)),
+            _ => return Err(EvalError::Unsupported(error())),
+        })
+    }
+
+    fn eval_bool(&mut self, expr: &Expr) -> Result<bool, EvalError> {
+        match self.eval(expr)? {
+            Value::Bool(value) => Ok(value),
+            _ => Err(EvalError::TypeMismatch(expr.to_string())),
+        }
+    }
+
+    fn eval_ref(&mut self, expr: &Expr) -> Result<String, EvalError> {
+        match self.eval(expr)? {
+            Value::Ref(address) => Ok(address),
+            _ => Err(EvalError::TypeMismatch(expr.to_string())),
+        }
+    }
+
+    fn assign(&mut self, target: &Expr, value: Value) -> Result<(), EvalError> {
+        match target {
+            Expr::Local(Local { variable, .. }) => {
+                self.heap.locals.insert(variable.name.clone(), value);
+            }
+            Expr::Field(FieldExpr { base, field, .. })
+            | Expr::Variant(Variant {
+                base,
+                variant_index: field,
+                ..
+            }) => {
+                let address = self.eval_ref(base)?;
+                self.heap
+                    .fields
+                    .insert((address, field.name.clone()), value);
+            }
+            _ => return Err(EvalError::Unsupported(target.to_string())),
+        }
+        Ok(())
+    }
+
+    fn input(&self, path: &str, typ: &Type) -> Value {
+        if typ.is_typed_ref_or_type_var() {
+            Value::Ref(path.to_string())
+        } else {
+            (self.inputs)(path, typ)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The initial value of each place: the length of its path, e.g. 3 for
+    /// `x.f`, or whether this length is even for booleans.
+    fn inputs(path: &str, typ: &Type) -> Value {
+        match typ {
+            Type::Bool => Value::Bool(path.len() % 2 == 0),
+            _ => Value::Int(path.len() as i128),
+        }
+    }
+
+    #[test]
+    fn test_fields_and_old() {
+        let x = LocalVar::new("x", Type::typed_ref("T"));
+        let f = Field::new("f", Type::Int);
+        let place = Expr::local(x).field(f);
+        let stmts = vec![
+            Stmt::label("l"),
+            Stmt::Assign(Assign {
+                target: place.clone(),
+                source: Expr::add(place.clone(), 2.into()),
+                kind: AssignKind::Copy,
+            }),
+            Stmt::Assert(Assert {
+                expr: Expr::eq_cmp(
+                    place.clone(),
+                    Expr::add(Expr::labelled_old("l", place.clone()), 2.into()),
+                ),
+                position: Position::default(),
+            }),
+        ];
+        let mut interpreter = Interpreter::new(inputs);
+        interpreter.execute(&stmts).unwrap();
+        // `x.f` starts at 3, the length of its path, and is incremented by 2.
+        assert_eq!(interpreter.eval(&place), Ok(Value::Int(5)));
+    }
+
+    #[test]
+    fn test_failures() {
+        let mut interpreter = Interpreter::new(inputs);
+        assert!(matches!(
+            interpreter.execute_stmt(&Stmt::Inhale(Inhale { expr: false.into() })),
+            Err(EvalError::Infeasible(_))
+        ));
+        assert!(matches!(
+            interpreter.eval(&Expr::div(1.into(), 0.into())),
+            Err(EvalError::DivisionByZero(_))
+        ));
+        // The right operand of a short-circuiting connective is not evaluated.
+        assert_eq!(
+            interpreter.eval(&Expr::implies(
+                false.into(),
+                Expr::eq_cmp(Expr::div(1.into(), 0.into()), 0.into())
+            )),
+            Ok(Value::Bool(true))
+        );
+    }
+}
@@ -5,8 +5,8 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 pub use self::{
-    ast::*, borrows::*, cfg::*, conversions::*, gather_labels::*, program::*, to_string::*,
-    utils::*,
+    ast::*, borrows::*, cfg::*, conversions::*, gather_labels::*, interning::*, program::*,
+    to_string::*, utils::*,
 };
 
 pub mod ast;
@@ -14,6 +14,10 @@ pub mod borrows;
 pub mod cfg;
 pub mod conversions;
 pub mod gather_labels;
+#[cfg(any(test, feature = "generator"))]
+pub mod generator;
+pub mod interning;
+pub mod interpreter;
 pub mod program;
 pub mod to_string;
 pub mod utils;
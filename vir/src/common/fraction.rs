@@ -0,0 +1,85 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The rational numbers used by fractional permission amounts.
+
+use std::fmt;
+
+/// A fraction strictly between zero and one, kept in lowest terms.
+///
+/// The fields are private, so that a fraction can only be created through
+/// `PermAmount::fraction` and the arithmetic of permission amounts, which
+/// maintain these invariants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Fraction {
+    numerator: u32,
+    denominator: u32,
+}
+
+impl Fraction {
+    /// Normalize ``numerator/denominator``. Returns `None` if the fraction
+    /// is equal to one.
+    pub(crate) fn new(numerator: u32, denominator: u32) -> Option<Self> {
+        assert!(
+            0 < numerator && numerator <= denominator,
+            "Invalid fractional permission {}/{}",
+            numerator,
+            denominator
+        );
+        let divisor = gcd(numerator as u64, denominator as u64) as u32;
+        let (numerator, denominator) = (numerator / divisor, denominator / divisor);
+        if numerator == denominator {
+            None
+        } else {
+            Some(Fraction {
+                numerator,
+                denominator,
+            })
+        }
+    }
+
+    pub fn numerator(&self) -> u32 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> u32 {
+        self.denominator
+    }
+}
+
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+/// Apply `op` to the numerators of the two fractions brought to a common
+/// denominator and normalize the result. Returns `None` if the result is not
+/// in ``(0, 1]``.
+pub(crate) fn combine_fractions(
+    (n1, d1): (u32, u32),
+    (n2, d2): (u32, u32),
+    op: fn(u64, u64) -> Option<u64>,
+) -> Option<(u32, u32)> {
+    let denominator = d1 as u64 * d2 as u64;
+    let numerator = op(n1 as u64 * d2 as u64, n2 as u64 * d1 as u64)?;
+    if numerator == 0 || numerator > denominator {
+        return None;
+    }
+    let divisor = gcd(numerator, denominator);
+    let numerator = u32::try_from(numerator / divisor).ok()?;
+    let denominator = u32::try_from(denominator / divisor).ok()?;
+    Some((numerator, denominator))
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
@@ -1,6 +1,7 @@
 pub mod constants;
 pub mod display;
 pub mod expression;
+pub mod fraction;
 pub mod graphviz;
 pub mod identifier;
 pub mod position;
@@ -42,6 +42,7 @@ impl From<polymorphic::PermAmount> for legacy::PermAmount {
             polymorphic::PermAmount::Read => legacy::PermAmount::Read,
             polymorphic::PermAmount::Write => legacy::PermAmount::Write,
             polymorphic::PermAmount::Remaining => legacy::PermAmount::Remaining,
+            polymorphic::PermAmount::Fraction(fraction) => legacy::PermAmount::Fraction(fraction),
         }
     }
 }
@@ -504,6 +505,11 @@ impl From<polymorphic::StructPredicate> for legacy::StructPredicate {
         legacy::StructPredicate {
             name: struct_predicate.typ.encode_as_string(),
             this: struct_predicate.this.into(),
+            extra_params: struct_predicate
+                .extra_params
+                .into_iter()
+                .map(|param| param.into())
+                .collect(),
             body: struct_predicate.body.map(|body_expr| body_expr.into()),
         }
     }
@@ -514,6 +520,11 @@ impl From<polymorphic::EnumPredicate> for legacy::EnumPredicate {
         legacy::EnumPredicate {
             name: enum_predicate.typ.name(),
             this: enum_predicate.this.into(),
+            extra_params: enum_predicate
+                .extra_params
+                .into_iter()
+                .map(|param| param.into())
+                .collect(),
             discriminant_field: enum_predicate.discriminant_field.into(),
             discriminant_bounds: enum_predicate.discriminant_bounds.into(),
             variants: enum_predicate
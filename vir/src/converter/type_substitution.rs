@@ -462,6 +462,11 @@ impl Generic for StructPredicate {
     fn substitute(self, map: &FxHashMap<TypeVar, Type>) -> Self {
         let mut struct_predicate = self;
         struct_predicate.this = struct_predicate.this.substitute(map);
+        struct_predicate.extra_params = struct_predicate
+            .extra_params
+            .into_iter()
+            .map(|param| param.substitute(map))
+            .collect();
         struct_predicate.body = struct_predicate.body.map(|expr| expr.substitute(map));
         struct_predicate
     }
@@ -471,6 +476,11 @@ impl Generic for EnumPredicate {
     fn substitute(self, map: &FxHashMap<TypeVar, Type>) -> Self {
         let mut enum_predicate = self;
         enum_predicate.this = enum_predicate.this.substitute(map);
+        enum_predicate.extra_params = enum_predicate
+            .extra_params
+            .into_iter()
+            .map(|param| param.substitute(map))
+            .collect();
         enum_predicate.discriminant_field = enum_predicate.discriminant_field.substitute(map);
         enum_predicate.discriminant_bounds = enum_predicate.discriminant_bounds.substitute(map);
         enum_predicate.variants = enum_predicate
@@ -3184,6 +3194,10 @@ mod tests {
                 name: String::from("_v1"),
                 typ: Type::type_var("T"),
             },
+            extra_params: vec![LocalVar {
+                name: String::from("_v2"),
+                typ: Type::type_var("E"),
+            }],
             body: Some(Expr::Local(Local {
                 variable: LocalVar {
                     name: String::from("_v7"),
@@ -3199,6 +3213,10 @@ mod tests {
                 name: String::from("_v1"),
                 typ: Type::Int,
             },
+            extra_params: vec![LocalVar {
+                name: String::from("_v2"),
+                typ: Type::Bool,
+            }],
             body: Some(Expr::Local(Local {
                 variable: LocalVar {
                     name: String::from("_v7"),
@@ -3222,6 +3240,7 @@ mod tests {
                 name: String::from("_v1"),
                 typ: Type::type_var("T"),
             },
+            extra_params: vec![],
             discriminant_field: Field {
                 name: String::from("f1"),
                 typ: Type::type_var("E"),
@@ -3249,6 +3268,7 @@ mod tests {
                             name: String::from("_v4"),
                             typ: Type::type_var("E"),
                         },
+                        extra_params: vec![],
                         body: Some(Expr::Local(Local {
                             variable: LocalVar {
                                 name: String::from("_v5"),
@@ -3273,6 +3293,7 @@ mod tests {
                             name: String::from("_v7"),
                             typ: Type::type_var("E"),
                         },
+                        extra_params: vec![],
                         body: Some(Expr::Local(Local {
                             variable: LocalVar {
                                 name: String::from("_v8"),
@@ -3291,6 +3312,7 @@ mod tests {
                 name: String::from("_v1"),
                 typ: Type::Int,
             },
+            extra_params: vec![],
             discriminant_field: Field {
                 name: String::from("f1"),
                 typ: Type::Bool,
@@ -3318,6 +3340,7 @@ mod tests {
                             name: String::from("_v4"),
                             typ: Type::Bool,
                         },
+                        extra_params: vec![],
                         body: Some(Expr::Local(Local {
                             variable: LocalVar {
                                 name: String::from("_v5"),
@@ -3342,6 +3365,7 @@ mod tests {
                             name: String::from("_v7"),
                             typ: Type::Bool,
                         },
+                        extra_params: vec![],
                         body: Some(Expr::Local(Local {
                             variable: LocalVar {
                                 name: String::from("_v8"),
@@ -3369,6 +3393,7 @@ mod tests {
                 name: String::from("_v4"),
                 typ: Type::type_var("E"),
             },
+            extra_params: vec![],
             body: Some(Expr::Local(Local {
                 variable: LocalVar {
                     name: String::from("_v5"),
@@ -3383,6 +3408,7 @@ mod tests {
                 name: String::from("_v4"),
                 typ: Type::Bool,
             },
+            extra_params: vec![],
             body: Some(Expr::Local(Local {
                 variable: LocalVar {
                     name: String::from("_v5"),
@@ -3400,6 +3426,7 @@ mod tests {
                 name: String::from("_v1"),
                 typ: Type::type_var("T"),
             },
+            extra_params: vec![],
             discriminant_field: Field {
                 name: String::from("f1"),
                 typ: Type::type_var("E"),
@@ -3427,6 +3454,7 @@ mod tests {
                             name: String::from("_v4"),
                             typ: Type::type_var("E"),
                         },
+                        extra_params: vec![],
                         body: Some(Expr::Local(Local {
                             variable: LocalVar {
                                 name: String::from("_v5"),
@@ -3451,6 +3479,7 @@ mod tests {
                             name: String::from("_v7"),
                             typ: Type::type_var("E"),
                         },
+                        extra_params: vec![],
                         body: Some(Expr::Local(Local {
                             variable: LocalVar {
                                 name: String::from("_v8"),
@@ -3468,6 +3497,7 @@ mod tests {
                 name: String::from("_v1"),
                 typ: Type::Int,
             },
+            extra_params: vec![],
             discriminant_field: Field {
                 name: String::from("f1"),
                 typ: Type::Bool,
@@ -3495,6 +3525,7 @@ mod tests {
                             name: String::from("_v4"),
                             typ: Type::Bool,
                         },
+                        extra_params: vec![],
                         body: Some(Expr::Local(Local {
                             variable: LocalVar {
                                 name: String::from("_v5"),
@@ -3519,6 +3550,7 @@ mod tests {
                             name: String::from("_v7"),
                             typ: Type::Bool,
                         },
+                        extra_params: vec![],
                         body: Some(Expr::Local(Local {
                             variable: LocalVar {
                                 name: String::from("_v8"),
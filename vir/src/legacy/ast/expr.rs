@@ -1845,7 +1845,9 @@ impl Expr {
             ) -> Expr {
                 assert!(perm_amount.is_valid_for_specs());
                 match perm_amount {
-                    PermAmount::Write => Expr::PredicateAccessPredicate(name, arg, perm_amount, p),
+                    PermAmount::Write | PermAmount::Fraction(_) => {
+                        Expr::PredicateAccessPredicate(name, arg, perm_amount, p)
+                    }
                     PermAmount::Read => true.into(),
                     _ => unreachable!(),
                 }
@@ -1858,7 +1860,9 @@ impl Expr {
             ) -> Expr {
                 assert!(perm_amount.is_valid_for_specs());
                 match perm_amount {
-                    PermAmount::Write => Expr::FieldAccessPredicate(reference, perm_amount, p),
+                    PermAmount::Write | PermAmount::Fraction(_) => {
+                        Expr::FieldAccessPredicate(reference, perm_amount, p)
+                    }
                     PermAmount::Read => true.into(),
                     _ => unreachable!(),
                 }
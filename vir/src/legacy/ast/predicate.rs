@@ -35,6 +35,7 @@ impl Predicate {
         Predicate::Struct(StructPredicate {
             name: predicate_name,
             this: Self::construct_this(typ),
+            extra_params: vec![],
             body: None,
         })
     }
@@ -65,6 +66,7 @@ impl Predicate {
         Predicate::Struct(StructPredicate {
             name: predicate_name,
             this,
+            extra_params: vec![],
             body: Some(body),
         })
     }
@@ -93,11 +95,23 @@ impl Predicate {
         Predicate::Enum(EnumPredicate {
             name: predicate_name,
             this,
+            extra_params: vec![],
             discriminant_field,
             discriminant_bounds,
             variants,
         })
     }
+    /// The formal parameters of the predicate, starting with `self`.
+    pub fn params(&self) -> Vec<LocalVar> {
+        let (this, extra_params) = match self {
+            Predicate::Struct(p) => (&p.this, &p.extra_params[..]),
+            Predicate::Enum(p) => (&p.this, &p.extra_params[..]),
+            Predicate::Bodyless(_, this) => (this, &[][..]),
+        };
+        let mut params = vec![this.clone()];
+        params.extend(extra_params.iter().cloned());
+        params
+    }
     /// A `self` place getter.
     pub fn self_place(&self) -> Expr {
         match self {
@@ -140,6 +154,8 @@ pub struct StructPredicate {
     pub name: String,
     /// The self reference.
     pub this: LocalVar,
+    /// The parameters after the self reference.
+    pub extra_params: Vec<LocalVar>,
     /// The optional body of the predicate.
     pub body: Option<Expr>,
 }
@@ -147,6 +163,9 @@ pub struct StructPredicate {
 impl fmt::Display for StructPredicate {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "struct_predicate {}({}", self.name, self.this)?;
+        for param in &self.extra_params {
+            write!(f, ", {}", param)?;
+        }
         match self.body {
             None => writeln!(f, ");"),
             Some(ref body) => {
@@ -176,6 +195,7 @@ impl StructPredicate {
         Self {
             name: predicate_name,
             this,
+            extra_params: vec![],
             body: Some(body),
         }
     }
@@ -207,6 +227,8 @@ pub struct EnumPredicate {
     pub name: String,
     /// The self reference.
     pub this: LocalVar,
+    /// The parameters after the self reference.
+    pub extra_params: Vec<LocalVar>,
     /// The discriminant field.
     pub discriminant_field: Field,
     /// The restrictions of the discriminant field.
@@ -240,7 +262,11 @@ impl<'a> From<&'a Field> for EnumVariantIndex {
 
 impl fmt::Display for EnumPredicate {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "enum_predicate {}({}){{", self.name, self.this)?;
+        write!(f, "enum_predicate {}({}", self.name, self.this)?;
+        for param in &self.extra_params {
+            write!(f, ", {}", param)?;
+        }
+        writeln!(f, "){{")?;
         writeln!(f, "  discriminant_field={}", self.discriminant_field)?;
         for (guard, name, variant) in self.variants.iter() {
             writeln!(f, "  {}: {} ==> {}\n", name, guard, variant)?;
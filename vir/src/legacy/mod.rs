@@ -17,3 +17,4 @@ pub mod gather_labels;
 pub mod program;
 pub mod to_string;
 pub mod utils;
+pub mod viper_text;
@@ -0,0 +1,767 @@
+// © 2022, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Emission of a program as Viper source text, which can be verified without
+//! building the Viper AST through the JVM (e.g. by an external ViperServer).
+//!
+//! The emitted program mirrors the one built by `ToViper`. Because the
+//! verifier reports errors with positions in the text, the emitter records
+//! the text range of every node that has a VIR position, so that the errors
+//! can be mapped back to the position identifiers.
+
+use crate::{
+    common::identifier::WithIdentifier,
+    legacy::{
+        ast::*,
+        borrows::borrow_id,
+        cfg::{CfgMethod, Successor, RETURN_LABEL},
+        Program,
+    },
+};
+
+/// A position in a text, both components are 1-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TextPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+pub struct ViperText {
+    pub text: String,
+    /// The text ranges of the nodes that have a VIR position, with the
+    /// identifier of that position.
+    ranges: Vec<(TextPosition, TextPosition, u64)>,
+}
+
+impl ViperText {
+    /// The identifier of the VIR position of the node that starts at `start`.
+    /// If several nodes start there, the one whose end is closest to `end` is
+    /// chosen.
+    pub fn find_position_id(&self, start: TextPosition, end: Option<TextPosition>) -> Option<u64> {
+        let distance = |position: TextPosition| match end {
+            Some(end) => (
+                (position.line as isize - end.line as isize).abs(),
+                (position.column as isize - end.column as isize).abs(),
+            ),
+            None => (0, 0),
+        };
+        self.ranges
+            .iter()
+            .filter(|(range_start, _, _)| *range_start == start)
+            .min_by_key(|(_, range_end, _)| distance(*range_end))
+            .map(|(_, _, id)| *id)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ViperTextError {
+    #[error("the Viper text emission does not support {0}")]
+    Unsupported(String),
+}
+
+type EmitResult = Result<(), ViperTextError>;
+
+/// Emit `program` as Viper source text.
+pub fn program_to_viper_text(program: &Program) -> Result<ViperText, ViperTextError> {
+    let mut emitter = Emitter {
+        text: String::new(),
+        position: TextPosition { line: 1, column: 1 },
+        indentation: 0,
+        ranges: Vec::new(),
+    };
+    emitter.program(program)?;
+    Ok(ViperText {
+        text: emitter.text,
+        ranges: emitter.ranges,
+    })
+}
+
+struct Emitter {
+    text: String,
+    position: TextPosition,
+    indentation: usize,
+    ranges: Vec<(TextPosition, TextPosition, u64)>,
+}
+
+impl Emitter {
+    fn write(&mut self, text: &str) {
+        for c in text.chars() {
+            if c == '\n' {
+                self.position.line += 1;
+                self.position.column = 1;
+            } else {
+                self.position.column += 1;
+            }
+        }
+        self.text.push_str(text);
+    }
+
+    fn new_line(&mut self) {
+        self.write("\n");
+        self.write(&"  ".repeat(self.indentation));
+    }
+
+    fn record(&mut self, start: TextPosition, position: &Position) {
+        if !position.is_default() {
+            self.ranges.push((start, self.position, position.id()));
+        }
+    }
+
+    fn comment(&mut self, comment: &str) {
+        self.write("// ");
+        self.write(&comment.replace('\n', " "));
+    }
+
+    fn program(&mut self, program: &Program) -> EmitResult {
+        for domain in &program.domains {
+            self.domain(domain)?;
+        }
+        for field in &program.fields {
+            self.write(&format!("field {}: ", field.name));
+            self.typ(&field.typ)?;
+            self.write("\n");
+        }
+        // The function that represents the symbolic read permission amount.
+        self.write("\nfunction read$(): Perm\n  ensures none < result\n  ensures result < write\n");
+        for function in &program.functions {
+            self.function(function)?;
+        }
+        for predicate in &program.viper_predicates {
+            self.predicate(predicate)?;
+        }
+        for method in &program.methods {
+            self.method(method)?;
+        }
+        for method in &program.builtin_methods {
+            self.write(&format!("\nmethod {}(", method.name));
+            self.local_var_decls(&method.formal_args)?;
+            self.write(") returns (");
+            self.local_var_decls(&method.formal_returns)?;
+            self.write(")\n");
+        }
+        Ok(())
+    }
+
+    fn domain(&mut self, domain: &Domain) -> EmitResult {
+        self.write(&format!("\ndomain {}", domain.name));
+        if !domain.type_vars.is_empty() {
+            self.write("[");
+            for (index, type_var) in domain.type_vars.iter().enumerate() {
+                if index > 0 {
+                    self.write(", ");
+                }
+                self.typ(type_var)?;
+            }
+            self.write("]");
+        }
+        self.write(" {");
+        for function in &domain.functions {
+            self.write("\n  ");
+            if function.unique {
+                self.write("unique ");
+            }
+            self.write(&format!("function {}(", function.get_identifier()));
+            self.local_var_decls(&function.formal_args)?;
+            self.write("): ");
+            self.typ(&function.return_type)?;
+        }
+        for axiom in &domain.axioms {
+            self.write(&format!("\n  axiom {} {{", axiom.name));
+            self.indentation = 2;
+            self.new_line();
+            self.expr(&axiom.expr)?;
+            self.indentation = 0;
+            self.write("\n  }");
+        }
+        self.write("\n}\n");
+        Ok(())
+    }
+
+    fn function(&mut self, function: &Function) -> EmitResult {
+        self.write(&format!("\nfunction {}(", function.get_identifier()));
+        self.local_var_decls(&function.formal_args)?;
+        self.write("): ");
+        self.typ(&function.return_type)?;
+        self.indentation = 1;
+        for pre in &function.pres {
+            self.new_line();
+            self.write("requires ");
+            self.expr(pre)?;
+        }
+        for post in &function.posts {
+            self.new_line();
+            self.write("ensures ");
+            self.expr(post)?;
+        }
+        if let Some(body) = &function.body {
+            self.indentation = 0;
+            self.write("\n{");
+            self.indentation = 1;
+            self.new_line();
+            self.expr(body)?;
+            self.write("\n}");
+        }
+        self.indentation = 0;
+        self.write("\n");
+        Ok(())
+    }
+
+    fn predicate(&mut self, predicate: &Predicate) -> EmitResult {
+        let (name, body) = match predicate {
+            Predicate::Struct(p) => (&p.name, p.body.clone()),
+            Predicate::Enum(p) => (&p.name, Some(p.body())),
+            Predicate::Bodyless(name, _) => (name, None),
+        };
+        self.write(&format!("\npredicate {}(", name));
+        self.local_var_decls(&predicate.params())?;
+        self.write(")");
+        if let Some(body) = body {
+            self.write(" {");
+            self.indentation = 1;
+            self.new_line();
+            self.expr(&body)?;
+            self.indentation = 0;
+            self.write("\n}");
+        }
+        self.write("\n");
+        Ok(())
+    }
+
+    fn method(&mut self, method: &CfgMethod) -> EmitResult {
+        self.write(&format!("\nmethod {}() returns (", method.name()));
+        self.local_var_decls(method.get_formal_returns())?;
+        self.write(")\n{");
+        self.indentation = 1;
+        for local_var in &method.local_vars {
+            self.new_line();
+            self.write("var ");
+            self.local_var_decls(std::slice::from_ref(local_var))?;
+        }
+        // Sort blocks by label, except for the first block, like `ToViper`.
+        let labels = method.basic_blocks_labels();
+        let mut blocks: Vec<_> = method.basic_blocks.iter().enumerate().skip(1).collect();
+        blocks.sort_by_key(|(index, _)| &labels[*index]);
+        blocks.insert(0, (0, &method.basic_blocks[0]));
+        for (index, block) in blocks {
+            self.new_line();
+            self.write(&format!("label {}", labels[index]));
+            for stmt in &block.stmts {
+                self.stmt(stmt)?;
+            }
+            self.successor(&labels[index], labels, &block.successor)?;
+        }
+        self.new_line();
+        self.write(&format!("label {}", RETURN_LABEL));
+        self.indentation = 0;
+        self.write("\n}\n");
+        Ok(())
+    }
+
+    fn successor(&mut self, label: &str, labels: &[String], successor: &Successor) -> EmitResult {
+        match successor {
+            Successor::Undefined => {
+                return Err(ViperTextError::Unsupported(format!(
+                    "the undefined successor of block '{}'",
+                    label
+                )));
+            }
+            Successor::Return => {
+                self.new_line();
+                self.write(&format!("goto {}", RETURN_LABEL));
+            }
+            Successor::Goto(target) => {
+                self.new_line();
+                self.write(&format!("goto {}", labels[target.index()]));
+            }
+            Successor::GotoSwitch(guarded_targets, default_target) => {
+                for (guard, target) in guarded_targets {
+                    self.new_line();
+                    self.write("if (");
+                    self.expr(guard)?;
+                    self.write(&format!(") {{ goto {} }}", labels[target.index()]));
+                }
+                self.new_line();
+                self.write(&format!("goto {}", labels[default_target.index()]));
+            }
+        }
+        Ok(())
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt], in_package: bool) -> EmitResult {
+        self.write(" {");
+        self.indentation += 1;
+        for stmt in stmts {
+            if in_package {
+                self.package_stmt(stmt)?;
+            } else {
+                self.stmt(stmt)?;
+            }
+        }
+        self.indentation -= 1;
+        self.new_line();
+        self.write("}");
+        Ok(())
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> EmitResult {
+        self.new_line();
+        let start = self.position;
+        match stmt {
+            Stmt::Comment(comment) => self.comment(comment),
+            Stmt::Label(label) => self.write(&format!("label {}", label)),
+            Stmt::Inhale(expr) => {
+                self.write("inhale ");
+                self.expr(expr)?;
+            }
+            Stmt::Exhale(expr, position) => {
+                self.write("exhale ");
+                self.expr(expr)?;
+                self.record(start, position);
+            }
+            Stmt::Assert(expr, position) => {
+                self.write("assert ");
+                self.expr(expr)?;
+                self.record(start, position);
+            }
+            Stmt::MethodCall(method_name, args, targets) => {
+                if !targets.is_empty() {
+                    let targets: Vec<_> =
+                        targets.iter().map(|target| target.name.as_str()).collect();
+                    self.write(&targets.join(", "));
+                    self.write(" := ");
+                }
+                self.write(method_name);
+                self.args(args)?;
+            }
+            Stmt::Assign(target, source, _) => {
+                self.expr(target)?;
+                self.write(" := ");
+                self.expr(source)?;
+            }
+            Stmt::Fold(predicate_name, args, perm, _, position) => {
+                self.write("fold ");
+                let acc_start = self.position;
+                self.predicate_access_predicate(predicate_name, args, *perm)?;
+                self.record(acc_start, position);
+                self.record(start, position);
+            }
+            Stmt::Unfold(predicate_name, args, perm, _) => {
+                self.write("unfold ");
+                self.predicate_access_predicate(predicate_name, args, *perm)?;
+            }
+            Stmt::Obtain(..)
+            | Stmt::BeginFrame
+            | Stmt::EndFrame
+            | Stmt::TransferPerm(..)
+            | Stmt::ExpireBorrows(_)
+            | Stmt::Downcast(..) => self.comment(&stmt.to_string()),
+            Stmt::PackageMagicWand(wand, package_stmts, _, vars, position) => {
+                self.write("package ");
+                self.expr(wand)?;
+                self.write(" {");
+                self.indentation += 1;
+                for var in vars {
+                    self.new_line();
+                    self.write("var ");
+                    self.local_var_decls(std::slice::from_ref(var))?;
+                }
+                for package_stmt in package_stmts {
+                    self.package_stmt(package_stmt)?;
+                }
+                self.indentation -= 1;
+                self.new_line();
+                self.write("}");
+                self.record(start, position);
+            }
+            Stmt::ApplyMagicWand(wand, position) => {
+                let borrow = match wand {
+                    Expr::MagicWand(_, _, Some(borrow), _) => borrow_id(*borrow),
+                    _ => {
+                        return Err(ViperTextError::Unsupported(format!(
+                            "applying the magic wand {} without a borrow",
+                            wand
+                        )))
+                    }
+                };
+                self.write(&format!("inhale acc(DeadBorrowToken$({}), write)", borrow));
+                self.new_line();
+                let apply_start = self.position;
+                self.write("apply ");
+                self.expr(wand)?;
+                self.record(apply_start, position);
+            }
+            Stmt::If(guard, then_stmts, else_stmts) => {
+                self.write("if (");
+                self.expr(guard)?;
+                self.write(")");
+                self.stmts(then_stmts, false)?;
+                self.write(" else");
+                self.stmts(else_stmts, false)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Like `ToViper`, help Silicon to show that it has access to the paths
+    /// needed by the statements of a package.
+    fn package_stmt(&mut self, stmt: &Stmt) -> EmitResult {
+        let footprint = match stmt {
+            Stmt::Assign(_, expr, _) | Stmt::Exhale(expr, _) => {
+                expr.compute_footprint(PermAmount::Read)
+            }
+            Stmt::Fold(_, args, ..) if args.len() == 1 => {
+                args[0].compute_footprint(PermAmount::Read)
+            }
+            Stmt::If(guard, then_stmts, else_stmts) => {
+                self.new_line();
+                self.write("if (");
+                self.expr(guard)?;
+                self.write(")");
+                self.stmts(then_stmts, true)?;
+                self.write(" else");
+                return self.stmts(else_stmts, true);
+            }
+            _ => vec![],
+        };
+        for access in footprint {
+            self.stmt(&Stmt::Assert(access, Position::default()))?;
+        }
+        self.stmt(stmt)
+    }
+
+    fn predicate_access_predicate(
+        &mut self,
+        predicate_name: &str,
+        args: &[Expr],
+        perm: PermAmount,
+    ) -> EmitResult {
+        self.write(&format!("acc({}", predicate_name));
+        self.args(args)?;
+        self.write(", ");
+        self.perm(perm);
+        self.write(")");
+        Ok(())
+    }
+
+    fn perm(&mut self, perm: PermAmount) {
+        match perm {
+            PermAmount::Write => self.write("write"),
+            PermAmount::Read => self.write("read$()"),
+            PermAmount::Remaining => self.write("(write - read$())"),
+            PermAmount::Fraction(fraction) => self.write(&format!(
+                "({} / {})",
+                fraction.numerator(),
+                fraction.denominator()
+            )),
+        }
+    }
+
+    fn args(&mut self, args: &[Expr]) -> EmitResult {
+        self.write("(");
+        for (index, arg) in args.iter().enumerate() {
+            if index > 0 {
+                self.write(", ");
+            }
+            self.expr(arg)?;
+        }
+        self.write(")");
+        Ok(())
+    }
+
+    fn local_var_decls(&mut self, vars: &[LocalVar]) -> EmitResult {
+        for (index, var) in vars.iter().enumerate() {
+            if index > 0 {
+                self.write(", ");
+            }
+            self.write(&format!("{}: ", var.name));
+            self.typ(&var.typ)?;
+        }
+        Ok(())
+    }
+
+    fn typ(&mut self, typ: &Type) -> EmitResult {
+        match typ {
+            Type::Int => self.write("Int"),
+            Type::Bool => self.write("Bool"),
+            Type::TypedRef(_) => self.write("Ref"),
+            Type::Domain(name) => self.write(name),
+            Type::Snapshot(name) => self.write(&format!("Snap${}", name)),
+            Type::Seq(elem_ty) => {
+                self.write("Seq[");
+                self.typ(elem_ty)?;
+                self.write("]");
+            }
+            Type::Float(_) | Type::BitVector(_) => {
+                return Err(ViperTextError::Unsupported(format!("the type {}", typ)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Emit a compound expression in parentheses. Its range excludes the
+    /// parentheses, like in the positions reported by Viper.
+    fn parenthesized(
+        &mut self,
+        position: &Position,
+        emit: impl FnOnce(&mut Self) -> EmitResult,
+    ) -> EmitResult {
+        self.write("(");
+        let start = self.position;
+        emit(self)?;
+        self.record(start, position);
+        self.write(")");
+        Ok(())
+    }
+
+    fn expr(&mut self, expr: &Expr) -> EmitResult {
+        let start = self.position;
+        let unsupported = || {
+            Err(ViperTextError::Unsupported(format!(
+                "the expression {}",
+                expr
+            )))
+        };
+        match expr {
+            Expr::Local(var, position) => {
+                if var.name == "__result" {
+                    self.write("result");
+                } else {
+                    self.write(&var.name);
+                }
+                self.record(start, position);
+            }
+            Expr::Variant(base, field, position) | Expr::Field(base, field, position) => {
+                self.expr(base)?;
+                self.write(&format!(".{}", field.name));
+                self.record(start, position);
+            }
+            Expr::Const(value, position) => {
+                match value {
+                    Const::Bool(value) => self.write(&value.to_string()),
+                    Const::Int(value) => self.write(&value.to_string()),
+                    Const::BigInt(value) => self.write(value),
+                    Const::FnPtr => self.write("null"),
+                    Const::Float(_) | Const::BitVector(_) => return unsupported(),
+                }
+                self.record(start, position);
+            }
+            Expr::LabelledOld(label, base, position) => {
+                self.write(&format!("old[{}](", label));
+                self.expr(base)?;
+                self.write(")");
+                self.record(start, position);
+            }
+            Expr::MagicWand(lhs, rhs, borrow, position) => {
+                let borrow = borrow
+                    .map(|borrow| borrow_id(borrow) as isize)
+                    .unwrap_or(-1);
+                self.parenthesized(position, |this| {
+                    this.write(&format!("(acc(DeadBorrowToken$({}), write) && ", borrow));
+                    this.expr(lhs)?;
+                    this.write(") --* ");
+                    this.expr(rhs)
+                })?;
+            }
+            Expr::PredicateAccessPredicate(predicate_name, arg, perm, position) => {
+                self.predicate_access_predicate(predicate_name, std::slice::from_ref(arg), *perm)?;
+                self.record(start, position);
+            }
+            Expr::FieldAccessPredicate(place, perm, position) => {
+                self.write("acc(");
+                self.expr(place)?;
+                self.write(", ");
+                self.perm(*perm);
+                self.write(")");
+                self.record(start, position);
+            }
+            Expr::UnaryOp(op, arg, position) => {
+                if matches!(arg.get_type(), Type::Float(_) | Type::BitVector(_)) {
+                    return unsupported();
+                }
+                let op = match op {
+                    UnaryOpKind::Not => "!",
+                    UnaryOpKind::Minus => "- ",
+                    UnaryOpKind::IsNaN => return unsupported(),
+                };
+                self.parenthesized(position, |this| {
+                    this.write(op);
+                    this.expr(arg)
+                })?;
+            }
+            Expr::BinOp(op, left, right, position) => {
+                if matches!(
+                    left.get_maybe_type(),
+                    Some(Type::Float(_) | Type::BitVector(_))
+                ) {
+                    return unsupported();
+                }
+                let op = match op {
+                    BinaryOpKind::EqCmp => "==",
+                    BinaryOpKind::NeCmp => "!=",
+                    BinaryOpKind::GtCmp => ">",
+                    BinaryOpKind::GeCmp => ">=",
+                    BinaryOpKind::LtCmp => "<",
+                    BinaryOpKind::LeCmp => "<=",
+                    BinaryOpKind::Add => "+",
+                    BinaryOpKind::Sub => "-",
+                    BinaryOpKind::Mul => "*",
+                    BinaryOpKind::Div => "\\",
+                    BinaryOpKind::Mod => "%",
+                    BinaryOpKind::And => "&&",
+                    BinaryOpKind::Or => "||",
+                    BinaryOpKind::Implies => "==>",
+                    _ => return unsupported(),
+                };
+                self.parenthesized(position, |this| {
+                    this.expr(left)?;
+                    this.write(&format!(" {} ", op));
+                    this.expr(right)
+                })?;
+            }
+            Expr::ContainerOp(op, left, right, _) => match op {
+                ContainerOpKind::SeqIndex => {
+                    self.expr(left)?;
+                    self.write("[");
+                    self.expr(right)?;
+                    self.write("]");
+                }
+                ContainerOpKind::SeqConcat => {
+                    self.write("(");
+                    self.expr(left)?;
+                    self.write(" ++ ");
+                    self.expr(right)?;
+                    self.write(")");
+                }
+                ContainerOpKind::SeqLen => {
+                    self.write("|");
+                    self.expr(left)?;
+                    self.write("|");
+                }
+            },
+            Expr::Seq(typ, elems, _) => {
+                if elems.is_empty() {
+                    self.typ(typ)?;
+                    self.write("()");
+                } else {
+                    self.write("Seq");
+                    self.args(elems)?;
+                }
+            }
+            Expr::Unfolding(predicate_name, args, base, perm, _, position) => {
+                self.parenthesized(position, |this| {
+                    this.write("unfolding ");
+                    this.predicate_access_predicate(predicate_name, args, *perm)?;
+                    this.write(" in ");
+                    this.expr(base)
+                })?;
+            }
+            Expr::Cond(guard, then_expr, else_expr, position) => {
+                self.parenthesized(position, |this| {
+                    this.expr(guard)?;
+                    this.write(" ? ");
+                    this.expr(then_expr)?;
+                    this.write(" : ");
+                    this.expr(else_expr)
+                })?;
+            }
+            Expr::ForAll(vars, triggers, body, position)
+            | Expr::Exists(vars, triggers, body, position) => {
+                let quantifier = if matches!(expr, Expr::ForAll(..)) {
+                    "forall"
+                } else {
+                    "exists"
+                };
+                self.parenthesized(position, |this| {
+                    this.write(&format!("{} ", quantifier));
+                    this.local_var_decls(vars)?;
+                    this.write(" ::");
+                    for trigger in triggers {
+                        this.write(" {");
+                        for (index, element) in trigger.elements().iter().enumerate() {
+                            if index > 0 {
+                                this.write(", ");
+                            }
+                            this.expr(element)?;
+                        }
+                        this.write("}");
+                    }
+                    this.write(" ");
+                    this.expr(body)
+                })?;
+            }
+            Expr::LetExpr(var, def, body, position) => {
+                self.parenthesized(position, |this| {
+                    this.write(&format!("let {} == (", var.name));
+                    this.expr(def)?;
+                    this.write(") in ");
+                    this.expr(body)
+                })?;
+            }
+            Expr::FuncApp(function_name, args, _, _, position) => {
+                self.write(function_name);
+                self.args(args)?;
+                self.record(start, position);
+            }
+            Expr::DomainFuncApp(function, args, _) => {
+                self.write(&function.get_identifier());
+                self.args(args)?;
+            }
+            Expr::InhaleExhale(inhale_expr, exhale_expr, _) => {
+                self.write("[");
+                self.expr(inhale_expr)?;
+                self.write(", ");
+                self.expr(exhale_expr)?;
+                self.write("]");
+            }
+            Expr::Downcast(base, ..) => self.expr(base)?,
+            Expr::AddrOf(..) | Expr::SnapApp(..) | Expr::Cast(..) => return unsupported(),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positions() {
+        let x = LocalVar::new("x", Type::Int);
+        let position = Position::new(1, 2, 42);
+        let division = Expr::BinOp(
+            BinaryOpKind::Div,
+            box Expr::local(x.clone()),
+            box Expr::local(x.clone()),
+            position,
+        );
+        let function = Function {
+            name: "f".to_string(),
+            formal_args: vec![x],
+            return_type: Type::Int,
+            pres: vec![],
+            posts: vec![],
+            body: Some(division),
+        };
+        let program = Program {
+            name: "test".to_string(),
+            domains: vec![],
+            fields: vec![],
+            builtin_methods: vec![],
+            methods: vec![],
+            functions: vec![function],
+            viper_predicates: vec![],
+        };
+        let viper_text = program_to_viper_text(&program).unwrap();
+        assert!(viper_text
+            .text
+            .ends_with("function f(x: Int): Int\n{\n  (x \\ x)\n}\n"));
+        let line = viper_text.text.lines().count() - 1;
+        let start = TextPosition { line, column: 4 };
+        let end = TextPosition { line, column: 9 };
+        assert_eq!(viper_text.find_position_id(start, Some(end)), Some(42));
+        assert_eq!(viper_text.find_position_id(end, None), None);
+    }
+}